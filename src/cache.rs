@@ -8,7 +8,7 @@ use serde::de::DeserializeOwned;
 use url::Url;
 
 use crate::detail::IntoOpt;
-use crate::loader::DefinitionLoader;
+use crate::loader::{DefinitionLoader, LoadWarning};
 use crate::validation::ValidateDefinition;
 
 /// Cache for resources referred to by workflow definitions, including sub-workflow definitions, etc.
@@ -24,6 +24,7 @@ use crate::validation::ValidateDefinition;
 pub struct DefinitionCache {
     loader: DefinitionLoader,
     cache: HashMap<Url, (Rc<dyn Any>, &'static str)>,
+    warnings: Vec<(Url, LoadWarning)>,
 }
 
 impl DefinitionCache {
@@ -32,6 +33,19 @@ impl DefinitionCache {
         Self::default()
     }
 
+    /// Creates a new empty cache that will use the given [`DefinitionLoader`] to load resources.
+    pub fn with_loader(loader: DefinitionLoader) -> Self {
+        Self { loader, ..Self::default() }
+    }
+
+    /// Returns all warnings emitted while loading resources, along with the URI of the
+    /// resource that caused each warning.
+    ///
+    /// See [`DefinitionLoader::load_with_warnings`].
+    pub fn warnings(&self) -> &[(Url, LoadWarning)] {
+        &self.warnings
+    }
+
     /// Fetches a definition object from the cache, loading it on the first call.
     ///
     /// * If the cache already contains a definition object for the given URI, it is returned.
@@ -65,7 +79,9 @@ impl DefinitionCache {
             });
         }
 
-        let def = self.loader.load(&uri)?;
+        let (def, warnings) = self.loader.load_with_warnings(&uri)?;
+        self.warnings
+            .extend(warnings.into_iter().map(|warning| (uri.clone(), warning)));
         self.cache
             .insert(uri, (Rc::clone(&def) as Rc<dyn Any>, def_type_name));
 
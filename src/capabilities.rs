@@ -0,0 +1,53 @@
+//! Discovery of the optional capabilities available in this build of the crate.
+
+/// Optional capabilities of this crate, as determined by the features enabled at build time.
+///
+/// Use [`capabilities`] to check whether an operation is supported before performing it,
+/// instead of handling [`FeatureDisabled`] errors after the fact.
+///
+/// [`FeatureDisabled`]: crate::Error::FeatureDisabled
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// Whether resources in YAML format can be loaded (requires the `yaml` feature)
+    pub yaml: bool,
+
+    /// Whether workflow definitions can be validated (requires the `validate` feature)
+    ///
+    /// If `false`, [`ValidateDefinition::validate_definition`] always fails and loaded
+    /// resources are not validated.
+    ///
+    /// [`ValidateDefinition::validate_definition`]: crate::validation::ValidateDefinition::validate_definition
+    pub validate: bool,
+
+    /// Whether workflow data input can be validated against the workflow's data input schema
+    /// (requires the `json-schema` feature)
+    pub json_schema: bool,
+
+    /// Whether resources can be loaded from `http://` or `https://` URIs
+    ///
+    /// This is not currently supported; loading such URIs fails with an [`UnsupportedUriScheme`] error.
+    ///
+    /// [`UnsupportedUriScheme`]: crate::Error::UnsupportedUriScheme
+    pub http: bool,
+}
+
+impl Capabilities {
+    /// Returns the names of the crate features that are disabled in this build.
+    pub fn disabled_features(&self) -> impl Iterator<Item = &'static str> {
+        [("json-schema", self.json_schema), ("validate", self.validate), ("yaml", self.yaml)]
+            .into_iter()
+            .filter(|(_, enabled)| !enabled)
+            .map(|(feature, _)| feature)
+    }
+}
+
+/// Returns the optional capabilities available in this build of the crate.
+pub const fn capabilities() -> Capabilities {
+    Capabilities {
+        yaml: cfg!(feature = "yaml"),
+        validate: cfg!(feature = "validate"),
+        json_schema: cfg!(feature = "json-schema"),
+        http: false,
+    }
+}
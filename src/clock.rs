@@ -0,0 +1,124 @@
+//! Sources of the current time.
+//!
+//! Runtimes read the current time and wait through a [`Clock`] instead of using [`std::time`]
+//! directly, for example when they compute deadlines, expiries and schedules (see
+//! [`EventSubscription::for_instance`] or [`InvocationContext::with_timeout_on`]) or execute
+//! [sleep states]. The [`SystemClock`] reads the system time and sleeps for real, while a
+//! [`TestClock`] only moves forward when it is [advanced](TestClock::advance) or slept on, so
+//! that tests of sleep states, timeouts and schedules run instantly and deterministically.
+//!
+//! [`EventSubscription::for_instance`]: crate::workflow::subscription::EventSubscription::for_instance
+//! [`InvocationContext::with_timeout_on`]: crate::workflow::executor::InvocationContext::with_timeout_on
+//! [sleep states]: crate::workflow::runtime::sleep::sleep_state
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+/// Source of the current time.
+pub trait Clock: Debug {
+    /// Returns the current date and time.
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Returns the current instant of a monotonic clock, used to compute deadlines.
+    fn instant(&self) -> Instant;
+
+    /// Blocks the current thread for the given duration.
+    fn sleep(&self, duration: Duration);
+}
+
+/// [`Clock`] reading the system time.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// [`Clock`] whose time only changes when it is [advanced](Self::advance).
+///
+/// [Sleeping](Clock::sleep) on a test clock advances it without blocking.
+///
+/// Clones of a test clock share the same time, so a test can keep a clone to advance the time
+/// seen by the code under test.
+#[derive(Debug, Clone)]
+pub struct TestClock {
+    start: Instant,
+    state: Arc<Mutex<TestClockState>>,
+}
+
+#[derive(Debug)]
+struct TestClockState {
+    now: DateTime<Utc>,
+    elapsed: Duration,
+}
+
+impl TestClock {
+    /// Creates a test clock starting at the given date and time.
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            start: Instant::now(),
+            state: Arc::new(Mutex::new(TestClockState { now, elapsed: Duration::ZERO })),
+        }
+    }
+
+    /// Moves the clock forward by the given duration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resulting date and time is out of range.
+    pub fn advance(&self, duration: Duration) {
+        let mut state = self.state();
+        state.now = TimeDelta::from_std(duration)
+            .ok()
+            .and_then(|delta| state.now.checked_add_signed(delta))
+            .expect("test clock should stay within the range of supported dates");
+        state.elapsed += duration;
+    }
+
+    /// Returns the time elapsed since the clock was created, i.e. the total duration
+    /// it was [advanced](Self::advance) by.
+    pub fn elapsed(&self) -> Duration {
+        self.state().elapsed
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, TestClockState> {
+        // The state is always consistent, even if a thread panicked while holding the lock.
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl Default for TestClock {
+    /// Creates a test clock starting at the Unix epoch.
+    fn default() -> Self {
+        Self::new(DateTime::UNIX_EPOCH)
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.state().now
+    }
+
+    fn instant(&self) -> Instant {
+        self.start + self.state().elapsed
+    }
+
+    /// Returns immediately after [advancing](Self::advance) the clock by the given duration.
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
@@ -0,0 +1,26 @@
+//! Conversion of workflow definitions from and to other workflow languages.
+
+pub mod asl;
+
+use std::fmt;
+
+/// Construct that could not be converted faithfully.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionIssue {
+    /// [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901) to the construct
+    /// in the source document (e.g. `/States/ProcessOrder/Retry`)
+    pub pointer: String,
+
+    /// Message describing how the construct was (or was not) converted
+    pub message: String,
+}
+
+impl fmt::Display for ConversionIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.pointer.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.pointer, self.message)
+        }
+    }
+}
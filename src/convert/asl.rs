@@ -0,0 +1,1581 @@
+//! Conversion from and to the [Amazon States Language] (ASL) used by AWS Step Functions.
+//!
+//! [Amazon States Language]: https://states-language.net/spec.html
+
+use std::collections::HashSet;
+
+use serde_json::{json, Map, Value};
+
+use crate::convert::ConversionIssue;
+use crate::workflow::definition::common::{ExecutionMode, InvocationMode};
+use crate::workflow::definition::functions::{FunctionType, Functions};
+use crate::workflow::definition::intervals::IsoDuration;
+use crate::workflow::definition::retries::Retries;
+use crate::workflow::definition::timeouts::{
+    ActionExecTimeout, StateExecTimeout, Timeouts, WorkflowExecTimeout,
+};
+use crate::workflow::definition::{
+    Action, CompletionType, DataCondition, End, FunctionRef, State, StateDataFilter, SwitchState,
+    Transition, WorkflowDefinition,
+};
+
+/// Result of importing an ASL state machine.
+///
+/// See [`import`].
+#[derive(Debug, Clone)]
+pub struct AslImport {
+    /// Imported workflow definition
+    pub definition: WorkflowDefinition,
+
+    /// Constructs of the state machine that were left out or only approximated
+    pub issues: Vec<ConversionIssue>,
+}
+
+impl AslImport {
+    /// Returns `true` if the whole state machine could be imported.
+    pub fn is_complete(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Imports an ASL state machine as a workflow definition with the given ID.
+///
+/// States are imported as follows:
+///
+/// * `Task` states become operation states. Their `Resource` is imported as a `custom` function
+///   whose operation is the resource's ARN.
+/// * `Choice` states become data-based switch states.
+/// * `Parallel` states become parallel states. Since parallel state branches can only contain
+///   actions, each branch must be a sequence of `Task` states.
+/// * `Map` states become foreach states, with the same restriction as `Parallel` branches.
+///   The current item is available through the `item` iteration parameter.
+/// * `Wait` states become sleep states.
+/// * `Pass`, `Succeed` and `Fail` states become inject states.
+///
+/// JSONPath expressions are converted to `jq` expressions when possible. Constructs that cannot
+/// be converted (for example `Retry` and `Catch`, intrinsic functions or JSONPath filters) are
+/// reported in the returned [`AslImport`]'s [`issues`](AslImport::issues).
+///
+/// Note that by default, ASL replaces the state's input with the result of a task, whereas
+/// action results are merged into the state data in the Serverless Workflow specification.
+///
+/// # Errors
+///
+/// * [`InvalidStateMachine`]: the state machine is malformed (e.g. a state has no `Type`)
+/// * [`JsonConversionFailed`]: the imported workflow definition could not be deserialized
+///
+/// [`InvalidStateMachine`]: crate::Error::InvalidStateMachine
+/// [`JsonConversionFailed`]: crate::Error::JsonConversionFailed
+pub fn import(id: &str, state_machine: &Value) -> crate::Result<AslImport> {
+    Importer::default().import(id, state_machine)
+}
+
+#[derive(Default)]
+struct Importer {
+    functions: Vec<Value>,
+    in_map_iteration: bool,
+    issues: Vec<ConversionIssue>,
+}
+
+impl Importer {
+    fn issue<P, M>(&mut self, pointer: P, message: M)
+    where
+        P: Into<String>,
+        M: Into<String>,
+    {
+        self.issues
+            .push(ConversionIssue { pointer: pointer.into(), message: message.into() });
+    }
+
+    fn import(mut self, id: &str, state_machine: &Value) -> crate::Result<AslImport> {
+        let start_at = required_str(state_machine, "StartAt", "")?;
+        let states = required_object(state_machine, "States", "")?;
+
+        // Put the start state first to make the imported definition easier to read.
+        let mut imported_states = Vec::with_capacity(states.len());
+        let ordered = states
+            .iter()
+            .filter(|(name, _)| *name == start_at)
+            .chain(states.iter().filter(|(name, _)| *name != start_at));
+        for (name, state) in ordered {
+            imported_states.push(self.state(name, state, &format!("/States/{}", token(name)))?);
+        }
+
+        let mut workflow = Map::new();
+        workflow.insert("id".into(), json!(id));
+        workflow.insert("specVersion".into(), json!("0.8"));
+        if let Some(comment) = state_machine.get("Comment").and_then(Value::as_str) {
+            workflow.insert("description".into(), json!(comment));
+        }
+        workflow.insert("start".into(), json!(start_at));
+        if let Some(timeout) = state_machine.get("TimeoutSeconds") {
+            workflow.insert(
+                "timeouts".into(),
+                json!({ "workflowExecTimeout": seconds(timeout, "/TimeoutSeconds")? }),
+            );
+        }
+        if !self.functions.is_empty() {
+            workflow.insert("functions".into(), Value::Array(std::mem::take(&mut self.functions)));
+        }
+        workflow.insert("states".into(), Value::Array(imported_states));
+
+        Ok(AslImport {
+            definition: serde_path_to_error::deserialize(Value::Object(workflow))?,
+            issues: self.issues,
+        })
+    }
+
+    fn state(&mut self, name: &str, state: &Value, pointer: &str) -> crate::Result<Value> {
+        let state_type = required_str(state, "Type", pointer)?;
+
+        let mut imported = Map::new();
+        imported.insert("name".into(), json!(name));
+        let has_transition = match state_type {
+            "Task" => {
+                imported.insert("type".into(), json!("operation"));
+                let action = self.task_action(name, state, pointer, false)?;
+                imported.insert("actions".into(), json!([action]));
+                if let Some(timeout) = state.get("TimeoutSeconds") {
+                    let timeout = seconds(timeout, &format!("{}/TimeoutSeconds", pointer))?;
+                    imported.insert("timeouts".into(), json!({ "actionExecTimeout": timeout }));
+                }
+                true
+            },
+            "Choice" => {
+                self.choice_state(state, pointer, &mut imported)?;
+                false
+            },
+            "Parallel" => {
+                let branches = state
+                    .get("Branches")
+                    .and_then(Value::as_array)
+                    .ok_or_else(|| invalid(format!("{}/Branches", pointer), "expected an array"))?;
+                let mut imported_branches = Vec::with_capacity(branches.len());
+                for (i, branch) in branches.iter().enumerate() {
+                    let actions =
+                        self.sequence_actions(branch, &format!("{}/Branches/{}", pointer, i))?;
+                    imported_branches
+                        .push(json!({ "name": format!("branch{}", i), "actions": actions }));
+                }
+
+                imported.insert("type".into(), json!("parallel"));
+                imported.insert("branches".into(), Value::Array(imported_branches));
+                self.result_issues(state, pointer);
+                true
+            },
+            "Map" => {
+                self.map_state(state, pointer, &mut imported)?;
+                true
+            },
+            "Wait" => {
+                let duration = match state.get("Seconds") {
+                    Some(duration) => seconds(duration, &format!("{}/Seconds", pointer))?,
+                    None if ["Timestamp", "SecondsPath", "TimestampPath"]
+                        .iter()
+                        .any(|key| state.get(key).is_some()) =>
+                    {
+                        self.issue(
+                            pointer,
+                            "only Wait states with a fixed number of Seconds can be imported",
+                        );
+                        "PT0S".into()
+                    },
+                    None => {
+                        return Err(invalid(pointer.into(), "Wait state must specify a duration"))
+                    },
+                };
+
+                imported.insert("type".into(), json!("sleep"));
+                imported.insert("duration".into(), json!(duration));
+                true
+            },
+            "Pass" => {
+                imported.insert("type".into(), json!("inject"));
+                imported.insert("data".into(), self.pass_data(state, pointer));
+                true
+            },
+            "Succeed" => {
+                imported.insert("type".into(), json!("inject"));
+                imported.insert("data".into(), json!({}));
+                imported.insert("end".into(), json!(true));
+                false
+            },
+            "Fail" => {
+                self.issue(
+                    pointer,
+                    "Fail states have no equivalent; imported as an inject state ending the workflow",
+                );
+                imported.insert("type".into(), json!("inject"));
+                imported.insert("data".into(), json!({}));
+                imported.insert("end".into(), json!(true));
+                false
+            },
+            _ => return Err(invalid(format!("{}/Type", pointer), "unknown state type")),
+        };
+
+        if has_transition {
+            match (state.get("Next").and_then(Value::as_str), state.get("End")) {
+                (Some(next), _) => imported.insert("transition".into(), json!(next)),
+                (None, Some(Value::Bool(true))) => imported.insert("end".into(), json!(true)),
+                _ => return Err(invalid(pointer.into(), "state must have either Next or End")),
+            };
+        }
+        if state_type != "Fail" {
+            let mut filter = Map::new();
+            for (key, filter_key) in [("InputPath", "input"), ("OutputPath", "output")] {
+                if let Some(path) = state.get(key) {
+                    if let Some(expression) =
+                        self.path_expression(path, &format!("{}/{}", pointer, key))
+                    {
+                        filter.insert(filter_key.into(), json!(expression));
+                    }
+                }
+            }
+            if !filter.is_empty() {
+                imported.insert("stateDataFilter".into(), Value::Object(filter));
+            }
+        }
+        if state.get("Retry").is_some() {
+            self.issue(
+                format!("{}/Retry", pointer),
+                "retriers must be migrated to retry definitions referenced by actions",
+            );
+        }
+        if state.get("Catch").is_some() {
+            self.issue(
+                format!("{}/Catch", pointer),
+                "catchers must be migrated to error definitions and `onErrors`",
+            );
+        }
+
+        Ok(Value::Object(imported))
+    }
+
+    fn task_action(
+        &mut self,
+        name: &str,
+        state: &Value,
+        pointer: &str,
+        nested: bool,
+    ) -> crate::Result<Value> {
+        let resource = required_str(state, "Resource", pointer)?;
+
+        let mut function_ref = Map::new();
+        function_ref.insert("refName".into(), json!(self.function(name, resource)));
+        if let Some(parameters) = state.get("Parameters") {
+            let parameters_pointer = format!("{}/Parameters", pointer);
+            match self.payload_template(parameters, &parameters_pointer) {
+                arguments @ Value::Object(_) => {
+                    function_ref.insert("arguments".into(), arguments);
+                },
+                _ => self.issue(parameters_pointer, "only object parameters can be imported"),
+            }
+        }
+
+        let mut filter = Map::new();
+        if nested {
+            if let Some(path) = state.get("InputPath") {
+                if let Some(expression) =
+                    self.path_expression(path, &format!("{}/InputPath", pointer))
+                {
+                    filter.insert("fromStateData".into(), json!(expression));
+                }
+            }
+        }
+        match state.get("ResultPath") {
+            Some(Value::Null) => {
+                filter.insert("useResults".into(), json!(false));
+            },
+            Some(Value::String(path)) if path != "$" => {
+                let path_pointer = format!("{}/ResultPath", pointer);
+                if let Some(expression) = self.expression(path, &path_pointer) {
+                    filter.insert("toStateData".into(), json!(expression));
+                }
+            },
+            _ => (),
+        }
+        if state.get("ResultSelector").is_some() {
+            self.issue(
+                format!("{}/ResultSelector", pointer),
+                "result selectors must be migrated to `actionDataFilter.results`",
+            );
+        }
+        for key in ["HeartbeatSeconds", "HeartbeatSecondsPath", "Credentials"] {
+            if state.get(key).is_some() {
+                self.issue(format!("{}/{}", pointer, key), format!("{} is not supported", key));
+            }
+        }
+
+        let mut action = Map::new();
+        action.insert("name".into(), json!(name));
+        action.insert("functionRef".into(), Value::Object(function_ref));
+        if !filter.is_empty() {
+            action.insert("actionDataFilter".into(), Value::Object(filter));
+        }
+
+        Ok(Value::Object(action))
+    }
+
+    fn function(&mut self, name: &str, resource: &str) -> String {
+        let existing = self
+            .functions
+            .iter()
+            .find(|function| function["operation"] == resource)
+            .and_then(|function| function["name"].as_str());
+        if let Some(existing) = existing {
+            return existing.into();
+        }
+
+        let taken: HashSet<_> = self
+            .functions
+            .iter()
+            .filter_map(|function| function["name"].as_str())
+            .collect();
+        let function_name = (1..)
+            .map(|i| match i {
+                1 => name.to_string(),
+                i => format!("{}{}", name, i),
+            })
+            .find(|function_name| !taken.contains(function_name.as_str()))
+            .expect("there should always be an available function name");
+
+        self.functions.push(json!({
+            "name": function_name,
+            "operation": resource,
+            "type": "custom",
+        }));
+        function_name
+    }
+
+    fn sequence_actions(&mut self, machine: &Value, pointer: &str) -> crate::Result<Vec<Value>> {
+        let start_at = required_str(machine, "StartAt", pointer)?;
+        let states = required_object(machine, "States", pointer)?;
+
+        let mut actions = Vec::new();
+        let mut visited = HashSet::new();
+        let mut current = start_at;
+        while visited.insert(current) {
+            let state_pointer = format!("{}/States/{}", pointer, token(current));
+            let state = states
+                .get(current)
+                .ok_or_else(|| invalid(state_pointer.clone(), "state does not exist"))?;
+            if required_str(state, "Type", &state_pointer)? != "Task" {
+                self.issue(
+                    state_pointer,
+                    "only sequences of Task states can be imported as actions",
+                );
+                break;
+            }
+
+            actions.push(self.task_action(current, state, &state_pointer, true)?);
+            for key in ["OutputPath", "TimeoutSeconds", "Retry", "Catch"] {
+                if state.get(key).is_some() {
+                    self.issue(
+                        format!("{}/{}", state_pointer, key),
+                        format!("{} is not supported on actions", key),
+                    );
+                }
+            }
+
+            match (state.get("Next").and_then(Value::as_str), state.get("End")) {
+                (Some(next), _) => current = next,
+                (None, Some(Value::Bool(true))) => break,
+                _ => {
+                    return Err(invalid(state_pointer, "state must have either Next or End"));
+                },
+            }
+        }
+        if visited.len() < states.len() {
+            self.issue(
+                format!("{}/States", pointer),
+                "states that are not part of the main sequence are not imported",
+            );
+        }
+
+        Ok(actions)
+    }
+
+    fn choice_state(
+        &mut self,
+        state: &Value,
+        pointer: &str,
+        imported: &mut Map<String, Value>,
+    ) -> crate::Result<()> {
+        let choices = state
+            .get("Choices")
+            .and_then(Value::as_array)
+            .ok_or_else(|| invalid(format!("{}/Choices", pointer), "expected an array"))?;
+
+        let mut conditions = Vec::with_capacity(choices.len());
+        for (i, choice) in choices.iter().enumerate() {
+            let choice_pointer = format!("{}/Choices/{}", pointer, i);
+            let next = required_str(choice, "Next", &choice_pointer)?;
+            if let Some(condition) = self.choice_rule(choice, &choice_pointer) {
+                conditions.push(json!({
+                    "condition": format!("${{ {} }}", condition),
+                    "transition": next,
+                }));
+            }
+        }
+        let default_condition = match state.get("Default").and_then(Value::as_str) {
+            Some(default) => json!({ "transition": default }),
+            None => {
+                self.issue(
+                    pointer,
+                    "Choice state has no Default; the workflow ends when no choice matches",
+                );
+                json!({ "end": true })
+            },
+        };
+
+        imported.insert("type".into(), json!("switch"));
+        imported.insert("dataConditions".into(), Value::Array(conditions));
+        imported.insert("defaultCondition".into(), default_condition);
+        Ok(())
+    }
+
+    fn choice_rule(&mut self, rule: &Value, pointer: &str) -> Option<String> {
+        for (key, operator) in [("And", " and "), ("Or", " or ")] {
+            if let Some(rules) = rule.get(key).and_then(Value::as_array) {
+                let conditions = rules
+                    .iter()
+                    .enumerate()
+                    .map(|(i, rule)| self.choice_rule(rule, &format!("{}/{}/{}", pointer, key, i)))
+                    .collect::<Option<Vec<_>>>()?;
+                return Some(
+                    conditions
+                        .iter()
+                        .map(|condition| format!("({})", condition))
+                        .collect::<Vec<_>>()
+                        .join(operator),
+                );
+            }
+        }
+        if let Some(rule) = rule.get("Not") {
+            let condition = self.choice_rule(rule, &format!("{}/Not", pointer))?;
+            return Some(format!("({}) | not", condition));
+        }
+
+        let Some(variable) = rule.get("Variable").and_then(Value::as_str) else {
+            self.issue(pointer, "choice rule has no Variable");
+            return None;
+        };
+        let variable = self.jq_path_or_issue(variable, &format!("{}/Variable", pointer))?;
+        let Some((comparison, operand)) = rule.as_object().and_then(|rule| {
+            rule.iter()
+                .find(|(key, _)| !["Variable", "Next", "Comment"].contains(&key.as_str()))
+        }) else {
+            self.issue(pointer, "choice rule has no comparison");
+            return None;
+        };
+        let comparison_pointer = format!("{}/{}", pointer, comparison);
+
+        let type_check = |expected_type: &str| {
+            let operator = if operand == &Value::Bool(true) { "==" } else { "!=" };
+            Some(format!("({} | type) {} \"{}\"", variable, operator, expected_type))
+        };
+        match comparison.as_str() {
+            "IsNull" => return type_check("null"),
+            "IsBoolean" => return type_check("boolean"),
+            "IsNumeric" => return type_check("number"),
+            "IsString" => return type_check("string"),
+            "StringMatches" => {
+                return match operand.as_str() {
+                    Some(pattern) if !pattern.contains('\\') => {
+                        let regex = format!("^{}$", wildcard_to_regex(pattern));
+                        Some(format!("{} | test({})", variable, json!(regex)))
+                    },
+                    _ => {
+                        self.issue(
+                            comparison_pointer,
+                            "only StringMatches patterns without escapes can be imported",
+                        );
+                        None
+                    },
+                };
+            },
+            _ => (),
+        }
+
+        let (comparison, operand) = match comparison.strip_suffix("Path") {
+            Some(comparison) => {
+                let path = operand.as_str().unwrap_or_default();
+                (comparison, self.jq_path_or_issue(path, &comparison_pointer)?)
+            },
+            None => (comparison.as_str(), operand.to_string()),
+        };
+        let operator = ["String", "Numeric", "Boolean"]
+            .iter()
+            .find_map(|prefix| comparison.strip_prefix(prefix))
+            .and_then(|comparison| match comparison {
+                "Equals" => Some("=="),
+                "LessThan" => Some("<"),
+                "GreaterThan" => Some(">"),
+                "LessThanEquals" => Some("<="),
+                "GreaterThanEquals" => Some(">="),
+                _ => None,
+            });
+        match operator {
+            Some(operator) => Some(format!("{} {} {}", variable, operator, operand)),
+            None => {
+                self.issue(
+                    comparison_pointer,
+                    format!("comparison operator '{}' is not supported", comparison),
+                );
+                None
+            },
+        }
+    }
+
+    fn map_state(
+        &mut self,
+        state: &Value,
+        pointer: &str,
+        imported: &mut Map<String, Value>,
+    ) -> crate::Result<()> {
+        let (processor_key, processor) = ["ItemProcessor", "Iterator"]
+            .iter()
+            .find_map(|key| state.get(key).map(|processor| (key, processor)))
+            .ok_or_else(|| invalid(pointer.into(), "Map state must have an ItemProcessor"))?;
+
+        let items_path = state
+            .get("ItemsPath")
+            .and_then(Value::as_str)
+            .unwrap_or("$");
+        let input_collection = self
+            .expression(items_path, &format!("{}/ItemsPath", pointer))
+            .unwrap_or_else(|| "${ . }".into());
+
+        // Within iterations, paths are relative to the current item.
+        self.in_map_iteration = true;
+        let actions = self.sequence_actions(processor, &format!("{}/{}", pointer, processor_key));
+        self.in_map_iteration = false;
+
+        imported.insert("type".into(), json!("foreach"));
+        imported.insert("inputCollection".into(), json!(input_collection));
+        imported.insert("iterationParam".into(), json!("item"));
+        imported.insert("actions".into(), Value::Array(actions?));
+        match state.get("MaxConcurrency").and_then(Value::as_u64) {
+            Some(1) => {
+                imported.insert("mode".into(), json!("sequential"));
+            },
+            Some(max_concurrency) if max_concurrency > 1 => {
+                imported.insert("batchSize".into(), json!(max_concurrency));
+            },
+            _ => (),
+        }
+        if let Some(Value::String(path)) = state.get("ResultPath") {
+            if let Some(expression) = self.expression(path, &format!("{}/ResultPath", pointer)) {
+                imported.insert("outputCollection".into(), json!(expression));
+            }
+        }
+        for key in ["ItemSelector", "Parameters", "ItemReader", "ResultWriter", "ResultSelector"] {
+            if state.get(key).is_some() {
+                self.issue(format!("{}/{}", pointer, key), format!("{} is not supported", key));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn pass_data(&mut self, state: &Value, pointer: &str) -> Value {
+        if state.get("Parameters").is_some() {
+            self.issue(format!("{}/Parameters", pointer), "Parameters is not supported");
+        }
+
+        let Some(result) = state.get("Result") else {
+            return json!({});
+        };
+        let result_path = state
+            .get("ResultPath")
+            .and_then(Value::as_str)
+            .unwrap_or("$");
+        let keys: Vec<_> = match result_path.strip_prefix("$.") {
+            Some(path) => path.split('.').collect(),
+            None if result_path == "$" => Vec::new(),
+            None => vec![""],
+        };
+        if keys.iter().any(|key| !is_identifier(key)) {
+            self.issue(format!("{}/ResultPath", pointer), "only simple result paths are supported");
+            return json!({});
+        }
+
+        let data = keys.iter().rev().fold(result.clone(), |data, key| {
+            Value::Object(Map::from_iter([((*key).to_string(), data)]))
+        });
+        match data {
+            data @ Value::Object(_) => data,
+            _ => {
+                self.issue(format!("{}/Result", pointer), "only object results are supported");
+                json!({})
+            },
+        }
+    }
+
+    fn result_issues(&mut self, state: &Value, pointer: &str) {
+        for key in ["ResultPath", "ResultSelector"] {
+            if state.get(key).is_some() {
+                self.issue(format!("{}/{}", pointer, key), format!("{} is not supported", key));
+            }
+        }
+    }
+
+    fn payload_template(&mut self, template: &Value, pointer: &str) -> Value {
+        match template {
+            Value::Object(fields) => {
+                let mut converted = Map::new();
+                for (key, value) in fields {
+                    let field_pointer = format!("{}/{}", pointer, token(key));
+                    match (key.strip_suffix(".$"), value.as_str()) {
+                        (Some(key), Some(path)) => {
+                            let value = match self.expression(path, &field_pointer) {
+                                Some(expression) => json!(expression),
+                                None => value.clone(),
+                            };
+                            converted.insert(key.into(), value);
+                        },
+                        _ => {
+                            let value = self.payload_template(value, &field_pointer);
+                            converted.insert(key.clone(), value);
+                        },
+                    }
+                }
+                Value::Object(converted)
+            },
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| self.payload_template(item, &format!("{}/{}", pointer, i)))
+                    .collect(),
+            ),
+            value => value.clone(),
+        }
+    }
+
+    fn path_expression(&mut self, path: &Value, pointer: &str) -> Option<String> {
+        match path {
+            Value::Null => Some("${ {} }".into()),
+            Value::String(path) => self.expression(path, pointer),
+            _ => {
+                self.issue(pointer, "expected a JSONPath expression");
+                None
+            },
+        }
+    }
+
+    fn expression(&mut self, path: &str, pointer: &str) -> Option<String> {
+        self.jq_path_or_issue(path, pointer)
+            .map(|path| format!("${{ {} }}", path))
+    }
+
+    fn jq_path_or_issue(&mut self, path: &str, pointer: &str) -> Option<String> {
+        let jq_path = self.jq_path(path);
+        if jq_path.is_none() {
+            let message = if path.starts_with("States.") {
+                format!("intrinsic function '{}' is not supported", path)
+            } else {
+                format!("JSONPath expression '{}' cannot be converted to jq", path)
+            };
+            self.issue(pointer, message);
+        }
+
+        jq_path
+    }
+
+    fn jq_path(&self, path: &str) -> Option<String> {
+        let rest = path.strip_prefix('$')?;
+        let unsupported = ["$", "..", "[*]", "?(", "@", "['", ":"];
+        if unsupported.iter().any(|pattern| rest.contains(pattern))
+            || !(rest.is_empty() || rest.starts_with('.') || rest.starts_with('['))
+        {
+            return None;
+        }
+
+        Some(match (self.in_map_iteration, rest) {
+            (false, "") => ".".into(),
+            (false, rest) => rest.into(),
+            (true, rest) => format!(".item{}", rest),
+        })
+    }
+}
+
+fn required_str<'a>(value: &'a Value, key: &str, pointer: &str) -> crate::Result<&'a str> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid(format!("{}/{}", pointer, key), "expected a string"))
+}
+
+fn required_object<'a>(
+    value: &'a Value,
+    key: &str,
+    pointer: &str,
+) -> crate::Result<&'a Map<String, Value>> {
+    value
+        .get(key)
+        .and_then(Value::as_object)
+        .ok_or_else(|| invalid(format!("{}/{}", pointer, key), "expected an object"))
+}
+
+fn seconds(value: &Value, pointer: &str) -> crate::Result<String> {
+    value
+        .as_u64()
+        .map(|seconds| format!("PT{}S", seconds))
+        .ok_or_else(|| invalid(pointer.into(), "expected a number of seconds"))
+}
+
+fn invalid(pointer: String, reason: &'static str) -> crate::Error {
+    crate::Error::InvalidStateMachine { pointer, reason }
+}
+
+/// Escapes a JSON pointer reference token (see [RFC 6901](https://datatracker.ietf.org/doc/html/rfc6901#section-3)).
+fn token(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+fn is_identifier(key: &str) -> bool {
+    !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn wildcard_to_regex(pattern: &str) -> String {
+    pattern
+        .chars()
+        .map(|c| match c {
+            '*' => ".*".into(),
+            c if ".+?^$()[]{}|".contains(c) => format!("\\{}", c),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Exports a workflow definition as an ASL state machine.
+///
+/// This is the inverse of [`import`]: operation, parallel and foreach states become `Task`,
+/// `Parallel` and `Map` states (one `Task` state per action), data-based switch states become
+/// `Choice` states, sleep states become `Wait` states and inject states become `Pass` states.
+/// Actions must reference `custom` functions whose operation is the resource to invoke (usually
+/// an ARN), and retry definitions referenced by actions are exported as retriers.
+///
+/// Only simple `jq` paths (e.g. `${ .order.items[0] }`) and conditions comparing a path with
+/// a literal value (e.g. `${ .total > 100 }`) can be converted to JSONPath. Descriptive properties
+/// (name, version, metadata, etc.) are not exported.
+///
+/// Note that by default, ASL replaces the state's input with the result of a task, whereas
+/// action results are merged into the state data in the Serverless Workflow specification.
+/// Use `actionDataFilter.toStateData` to store action results in the state data.
+///
+/// # Errors
+///
+/// * [`UnsupportedConversion`]: the workflow definition uses a construct that ASL cannot express,
+///                              like event states, compensation or error handling
+///
+/// [`UnsupportedConversion`]: crate::Error::UnsupportedConversion
+pub fn export(definition: &WorkflowDefinition) -> crate::Result<Value> {
+    Exporter { definition, iteration_param: None }.export()
+}
+
+/// Next state of an exported ASL state.
+enum Next {
+    State(String),
+    End,
+}
+
+struct Exporter<'a> {
+    definition: &'a WorkflowDefinition,
+    iteration_param: Option<&'a str>,
+}
+
+impl<'a> Exporter<'a> {
+    fn export(&mut self) -> crate::Result<Value> {
+        let definition = self.definition;
+        let start = definition
+            .start_state_name()
+            .ok_or_else(|| unsupported("/states", "workflow definition has no states"))?;
+        if definition
+            .start
+            .as_ref()
+            .is_some_and(|start| start.schedule().is_some())
+        {
+            return Err(unsupported("/start/schedule", "scheduled starts cannot be exported"));
+        }
+        if definition.keep_active {
+            return Err(unsupported("/keepActive", "keepActive cannot be exported"));
+        }
+
+        let mut states = Map::new();
+        for (i, state) in definition.states.iter().enumerate() {
+            self.state(state, &format!("/states/{}", i), &mut states)?;
+        }
+
+        let mut state_machine = Map::new();
+        if let Some(description) = &definition.description {
+            state_machine.insert("Comment".into(), json!(description));
+        }
+        state_machine.insert("StartAt".into(), json!(start));
+        state_machine.insert("States".into(), Value::Object(states));
+        match &definition.timeouts {
+            Some(Timeouts::Uri(_)) => return Err(external_definitions("/timeouts")),
+            Some(Timeouts::Complex { workflow_exec_timeout: Some(timeout), .. }) => {
+                let pointer = "/timeouts/workflowExecTimeout";
+                let duration = match timeout {
+                    WorkflowExecTimeout::Simple(duration) => duration,
+                    WorkflowExecTimeout::Complex { duration, .. } => duration,
+                };
+                state_machine
+                    .insert("TimeoutSeconds".into(), json!(duration_seconds(duration, pointer)?));
+            },
+            _ => (),
+        }
+
+        Ok(Value::Object(state_machine))
+    }
+
+    fn state(
+        &mut self,
+        state: &'a State,
+        pointer: &str,
+        states: &mut Map<String, Value>,
+    ) -> crate::Result<()> {
+        let name = state.name();
+        let (chain, state_data_filter, next) = match state {
+            State::Sleep(state) => {
+                check_state(pointer, state.on_errors.is_some(), state.compensated_by.is_some())?;
+                check_state_timeout(
+                    state
+                        .timeouts
+                        .as_ref()
+                        .and_then(|t| t.state_exec_timeout.as_ref()),
+                    pointer,
+                )?;
+                let seconds = duration_seconds(&state.duration, &format!("{}/duration", pointer))?;
+
+                (
+                    vec![(
+                        name.to_string(),
+                        into_object(json!({ "Type": "Wait", "Seconds": seconds })),
+                    )],
+                    state.state_data_filter.as_ref(),
+                    Some(self.next(state.transition.as_ref(), state.end.as_ref(), pointer)?),
+                )
+            },
+            State::Operation(state) => {
+                check_state(pointer, state.on_errors.is_some(), state.compensated_by.is_some())?;
+                let timeouts = state.timeouts.as_ref();
+                check_state_timeout(timeouts.and_then(|t| t.state_exec_timeout.as_ref()), pointer)?;
+                let timeout = timeouts.and_then(|t| t.action_exec_timeout.as_ref());
+                let actions_pointer = format!("{}/actions", pointer);
+
+                let chain = match state.action_mode {
+                    ExecutionMode::Sequential => {
+                        self.task_chain(name, &state.actions, timeout, &actions_pointer)?
+                    },
+                    ExecutionMode::Parallel => {
+                        let mut branches = Vec::with_capacity(state.actions.len());
+                        for (i, action) in state.actions.iter().enumerate() {
+                            let branch_name = action_state_name(name, action, i);
+                            let chain = self.task_chain(
+                                &branch_name,
+                                std::slice::from_ref(action),
+                                timeout,
+                                &actions_pointer,
+                            )?;
+                            branches.push(branch(chain));
+                        }
+                        vec![(
+                            name.to_string(),
+                            into_object(json!({ "Type": "Parallel", "Branches": branches })),
+                        )]
+                    },
+                };
+
+                (
+                    chain,
+                    state.state_data_filter.as_ref(),
+                    Some(self.next(state.transition.as_ref(), state.end.as_ref(), pointer)?),
+                )
+            },
+            State::Parallel(state) => {
+                check_state(pointer, state.on_errors.is_some(), state.compensated_by.is_some())?;
+                check_state_timeout(
+                    state
+                        .timeouts
+                        .as_ref()
+                        .and_then(|t| t.state_exec_timeout.as_ref()),
+                    pointer,
+                )?;
+                if state.completion_type == CompletionType::AtLeast {
+                    return Err(unsupported(
+                        format!("{}/completionType", pointer),
+                        "Parallel states always wait for all branches",
+                    ));
+                }
+
+                let mut branches = Vec::with_capacity(state.branches.len());
+                for (i, parallel_branch) in state.branches.iter().enumerate() {
+                    let branch_pointer = format!("{}/branches/{}", pointer, i);
+                    let timeouts = parallel_branch.timeouts.as_ref();
+                    if timeouts.is_some_and(|t| t.branch_exec_timeout.is_some()) {
+                        return Err(unsupported(
+                            format!("{}/timeouts/branchExecTimeout", branch_pointer),
+                            "Parallel state branches cannot time out",
+                        ));
+                    }
+                    let chain = self.task_chain(
+                        &parallel_branch.name,
+                        &parallel_branch.actions,
+                        timeouts.and_then(|t| t.action_exec_timeout.as_ref()),
+                        &format!("{}/actions", branch_pointer),
+                    )?;
+                    branches.push(branch(chain));
+                }
+
+                (
+                    vec![(
+                        name.to_string(),
+                        into_object(json!({ "Type": "Parallel", "Branches": branches })),
+                    )],
+                    state.state_data_filter.as_ref(),
+                    Some(self.next(state.transition.as_ref(), state.end.as_ref(), pointer)?),
+                )
+            },
+            State::Switch(SwitchState::DataBased(state)) => {
+                check_state(pointer, state.on_errors.is_some(), state.compensated_by.is_some())?;
+                check_state_timeout(
+                    state
+                        .timeouts
+                        .as_ref()
+                        .and_then(|t| t.state_exec_timeout.as_ref()),
+                    pointer,
+                )?;
+
+                let end_state = format!("{} (end)", name);
+                let mut needs_end_state = false;
+                let mut target = |next: Next| match next {
+                    Next::State(next) => next,
+                    Next::End => {
+                        needs_end_state = true;
+                        end_state.clone()
+                    },
+                };
+
+                let mut choices = Vec::with_capacity(state.data_conditions.len());
+                for (i, condition) in state.data_conditions.iter().enumerate() {
+                    let condition_pointer = format!("{}/dataConditions/{}", pointer, i);
+                    let (condition, next) = match condition {
+                        DataCondition::Transition(condition) => (
+                            &condition.condition,
+                            self.next(Some(&condition.transition), None, &condition_pointer)?,
+                        ),
+                        DataCondition::End(condition) => (
+                            &condition.condition,
+                            self.next(None, Some(&condition.end), &condition_pointer)?,
+                        ),
+                    };
+
+                    let mut choice =
+                        self.choice_rule(condition, &format!("{}/condition", condition_pointer))?;
+                    choice.insert("Next".into(), json!(target(next)));
+                    choices.push(Value::Object(choice));
+                }
+                let default = self.next(
+                    state.default_condition.transition.as_ref(),
+                    state.default_condition.end.as_ref(),
+                    &format!("{}/defaultCondition", pointer),
+                )?;
+                let choice =
+                    json!({ "Type": "Choice", "Choices": choices, "Default": target(default) });
+
+                if needs_end_state {
+                    states.insert(end_state, json!({ "Type": "Succeed" }));
+                }
+
+                (
+                    vec![(name.to_string(), into_object(choice))],
+                    state.state_data_filter.as_ref(),
+                    None,
+                )
+            },
+            State::Inject(state) => {
+                check_state(pointer, false, state.compensated_by.is_some())?;
+                check_state_timeout(
+                    state
+                        .timeouts
+                        .as_ref()
+                        .and_then(|t| t.state_exec_timeout.as_ref()),
+                    pointer,
+                )?;
+
+                // Injected data is merged into the state data, so each property is set separately.
+                let mut keys: Vec<_> = state.data.meta.keys().collect();
+                keys.sort();
+                let mut chain = Vec::with_capacity(keys.len().max(1));
+                for (i, key) in keys.into_iter().enumerate() {
+                    if !is_identifier(key) {
+                        return Err(unsupported(
+                            format!("{}/data/{}", pointer, token(key)),
+                            "only identifiers can be used as injected property names",
+                        ));
+                    }
+                    let pass_name = match i {
+                        0 => name.to_string(),
+                        _ => format!("{} ({})", name, key),
+                    };
+                    chain.push((
+                        pass_name,
+                        into_object(json!({
+                            "Type": "Pass",
+                            "Result": state.data.meta[key],
+                            "ResultPath": format!("$.{}", key),
+                        })),
+                    ));
+                }
+                if chain.is_empty() {
+                    chain.push((name.to_string(), into_object(json!({ "Type": "Pass" }))));
+                }
+
+                (
+                    chain,
+                    state.state_data_filter.as_ref(),
+                    Some(self.next(state.transition.as_ref(), state.end.as_ref(), pointer)?),
+                )
+            },
+            State::ForEach(state) => {
+                check_state(pointer, state.on_errors.is_some(), state.compensated_by.is_some())?;
+                let timeouts = state.timeouts.as_ref();
+                check_state_timeout(timeouts.and_then(|t| t.state_exec_timeout.as_ref()), pointer)?;
+                let Some(iteration_param) = &state.iteration_param else {
+                    return Err(unsupported(
+                        pointer,
+                        "foreach states must specify an iterationParam to be exported",
+                    ));
+                };
+
+                let mut map = Map::new();
+                map.insert("Type".into(), json!("Map"));
+                map.insert(
+                    "ItemsPath".into(),
+                    json!(self.json_path(
+                        &state.input_collection,
+                        &format!("{}/inputCollection", pointer)
+                    )?),
+                );
+
+                self.iteration_param = Some(iteration_param);
+                let chain = self.task_chain(
+                    &format!("{} (item)", name),
+                    &state.actions,
+                    timeouts.and_then(|t| t.action_exec_timeout.as_ref()),
+                    &format!("{}/actions", pointer),
+                );
+                self.iteration_param = None;
+                map.insert("ItemProcessor".into(), branch(chain?));
+
+                if let Some(output_collection) = &state.output_collection {
+                    let path = self
+                        .json_path(output_collection, &format!("{}/outputCollection", pointer))?;
+                    map.insert("ResultPath".into(), json!(path));
+                }
+                match (state.mode, &state.batch_size) {
+                    (ExecutionMode::Sequential, _) => {
+                        map.insert("MaxConcurrency".into(), json!(1));
+                    },
+                    (ExecutionMode::Parallel, Some(batch_size)) => {
+                        let batch_size =
+                            batch_size.value().ok().filter(|&n| n > 0).ok_or_else(|| {
+                                unsupported(format!("{}/batchSize", pointer), "invalid batch size")
+                            })?;
+                        map.insert("MaxConcurrency".into(), json!(batch_size));
+                    },
+                    (ExecutionMode::Parallel, None) => (),
+                }
+
+                (
+                    vec![(name.to_string(), map)],
+                    state.state_data_filter.as_ref(),
+                    Some(self.next(state.transition.as_ref(), state.end.as_ref(), pointer)?),
+                )
+            },
+            State::Event(_) | State::Callback(_) | State::Switch(SwitchState::EventBased(_)) => {
+                return Err(unsupported(pointer, "states consuming events cannot be exported"));
+            },
+            State::Custom(_) => {
+                return Err(unsupported(pointer, "custom states cannot be exported"));
+            },
+        };
+
+        self.insert_chain(chain, state_data_filter, next, pointer, states)
+    }
+
+    fn insert_chain(
+        &self,
+        mut chain: Vec<(String, Map<String, Value>)>,
+        state_data_filter: Option<&StateDataFilter>,
+        next: Option<Next>,
+        pointer: &str,
+        states: &mut Map<String, Value>,
+    ) -> crate::Result<()> {
+        if let Some(filter) = state_data_filter {
+            let filter_pointer = format!("{}/stateDataFilter", pointer);
+            if let Some(input) = &filter.input {
+                let path = self.json_path(input, &format!("{}/input", filter_pointer))?;
+                if let Some((_, first)) = chain.first_mut() {
+                    first.insert("InputPath".into(), json!(path));
+                }
+            }
+            if let Some(output) = &filter.output {
+                let path = self.json_path(output, &format!("{}/output", filter_pointer))?;
+                if let Some((_, last)) = chain.last_mut() {
+                    last.insert("OutputPath".into(), json!(path));
+                }
+            }
+        }
+
+        link(chain, next, states);
+        Ok(())
+    }
+
+    fn next(
+        &self,
+        transition: Option<&Transition>,
+        end: Option<&End>,
+        pointer: &str,
+    ) -> crate::Result<Next> {
+        match (transition, end) {
+            (Some(Transition::ByName(next_state)), _) => Ok(Next::State(next_state.clone())),
+            (Some(Transition::Complex { next_state, produce_events, compensate }), _) => {
+                let pointer = format!("{}/transition", pointer);
+                check_flow(&pointer, produce_events.is_some(), *compensate, false)?;
+                Ok(Next::State(next_state.clone()))
+            },
+            (None, Some(End::Simple(_))) => Ok(Next::End),
+            (None, Some(End::Complex { produce_events, compensate, continue_as, .. })) => {
+                let pointer = format!("{}/end", pointer);
+                check_flow(&pointer, produce_events.is_some(), *compensate, continue_as.is_some())?;
+                Ok(Next::End)
+            },
+            (None, None) => {
+                Err(unsupported(pointer, "state has no transition and does not end the workflow"))
+            },
+        }
+    }
+
+    fn task_chain(
+        &self,
+        name: &str,
+        actions: &[Action],
+        timeout: Option<&ActionExecTimeout>,
+        pointer: &str,
+    ) -> crate::Result<Vec<(String, Map<String, Value>)>> {
+        if actions.is_empty() {
+            return Err(unsupported(pointer, "at least one action is required"));
+        }
+
+        // Action timeouts are defined by the state (or branch) owning the actions.
+        let timeout_seconds = timeout
+            .map(|timeout| {
+                let owner_pointer = pointer.strip_suffix("/actions").unwrap_or(pointer);
+                let timeout_pointer = format!("{}/timeouts/actionExecTimeout", owner_pointer);
+                duration_seconds(&timeout.0, &timeout_pointer)
+            })
+            .transpose()?;
+
+        actions
+            .iter()
+            .enumerate()
+            .map(|(i, action)| {
+                let mut task = self.task(action, &format!("{}/{}", pointer, i))?;
+                if let Some(seconds) = timeout_seconds {
+                    task.insert("TimeoutSeconds".into(), json!(seconds));
+                }
+
+                let state_name = match i {
+                    0 => name.to_string(),
+                    i => action_state_name(name, action, i),
+                };
+                Ok((state_name, task))
+            })
+            .collect()
+    }
+
+    fn task(&self, action: &Action, pointer: &str) -> crate::Result<Map<String, Value>> {
+        for (is_set, key, reason) in [
+            (action.event_ref.is_some(), "eventRef", "event references cannot be exported"),
+            (action.sub_flow_ref.is_some(), "subFlowRef", "sub-workflows cannot be exported"),
+            (action.sleep.is_some(), "sleep", "action sleeps cannot be exported"),
+            (action.condition.is_some(), "condition", "conditional actions cannot be exported"),
+            (
+                action.retryable_errors.is_some(),
+                "retryableErrors",
+                "retries are exported for all errors",
+            ),
+            (
+                action.non_retryable_errors.is_some(),
+                "nonRetryableErrors",
+                "retries are exported for all errors",
+            ),
+        ] {
+            if is_set {
+                return Err(unsupported(format!("{}/{}", pointer, key), reason));
+            }
+        }
+
+        let function_pointer = format!("{}/functionRef", pointer);
+        let function_ref = action
+            .function_ref
+            .as_ref()
+            .ok_or_else(|| unsupported(pointer, "action must reference a function"))?;
+        let function = match &self.definition.functions {
+            Some(Functions::Inline(functions)) => functions
+                .iter()
+                .find(|function| function.name == function_ref.ref_name()),
+            Some(Functions::Uri(_)) => return Err(external_definitions("/functions")),
+            None => None,
+        }
+        .ok_or_else(|| unsupported(&function_pointer, "referenced function is not defined"))?;
+        if !matches!(function.function_type, FunctionType::Custom) {
+            return Err(unsupported(
+                function_pointer,
+                "only custom functions whose operation is a resource can be exported",
+            ));
+        }
+
+        let mut task = Map::new();
+        task.insert("Type".into(), json!("Task"));
+        task.insert("Resource".into(), json!(function.operation));
+        if let FunctionRef::Complex { arguments, selection_set, invoke, .. } = function_ref {
+            if *invoke == InvocationMode::Async {
+                return Err(unsupported(
+                    format!("{}/invoke", function_pointer),
+                    "functions cannot be invoked asynchronously",
+                ));
+            }
+            if selection_set.is_some() {
+                return Err(unsupported(
+                    format!("{}/selectionSet", function_pointer),
+                    "GraphQL selection sets cannot be exported",
+                ));
+            }
+            if let Some(arguments) = arguments {
+                let arguments = Value::Object(arguments.arguments.clone().into_iter().collect());
+                let parameters =
+                    self.parameters(&arguments, &format!("{}/arguments", function_pointer))?;
+                task.insert("Parameters".into(), parameters);
+            }
+        }
+
+        if let Some(filter) = &action.action_data_filter {
+            let filter_pointer = format!("{}/actionDataFilter", pointer);
+            if let Some(from_state_data) = &filter.from_state_data {
+                let path =
+                    self.json_path(from_state_data, &format!("{}/fromStateData", filter_pointer))?;
+                task.insert("InputPath".into(), json!(path));
+            }
+            if filter.results.is_some() {
+                return Err(unsupported(
+                    format!("{}/results", filter_pointer),
+                    "action result filters cannot be exported",
+                ));
+            }
+            if !filter.use_results {
+                task.insert("ResultPath".into(), Value::Null);
+            } else if let Some(to_state_data) = &filter.to_state_data {
+                let path =
+                    self.json_path(to_state_data, &format!("{}/toStateData", filter_pointer))?;
+                task.insert("ResultPath".into(), json!(path));
+            }
+        }
+
+        if let Some(retry_ref) = &action.retry_ref {
+            let retrier = self.retrier(retry_ref, &format!("{}/retryRef", pointer))?;
+            task.insert("Retry".into(), json!([retrier]));
+        }
+
+        Ok(task)
+    }
+
+    fn retrier(&self, retry_ref: &str, pointer: &str) -> crate::Result<Value> {
+        let (i, retry) = match &self.definition.retries {
+            Some(Retries::Inline(retries)) => retries
+                .iter()
+                .enumerate()
+                .find(|(_, retry)| retry.name == retry_ref),
+            Some(Retries::Uri(_)) => return Err(external_definitions("/retries")),
+            None => None,
+        }
+        .ok_or_else(|| unsupported(pointer, "referenced retry definition is not defined"))?;
+        let retry_pointer = format!("/retries/{}", i);
+        if retry.increment.is_some() {
+            return Err(unsupported(
+                format!("{}/increment", retry_pointer),
+                "retry delay increments cannot be exported",
+            ));
+        }
+        if retry.jitter.is_some() {
+            return Err(unsupported(
+                format!("{}/jitter", retry_pointer),
+                "retry jitter cannot be exported",
+            ));
+        }
+
+        let mut retrier = Map::new();
+        retrier.insert("ErrorEquals".into(), json!(["States.ALL"]));
+        let max_attempts = retry.max_attempts.value().map_err(|_| {
+            unsupported(format!("{}/maxAttempts", retry_pointer), "invalid number of attempts")
+        })?;
+        retrier.insert("MaxAttempts".into(), json!(max_attempts));
+        if let Some(delay) = &retry.delay {
+            let seconds = duration_seconds(delay, &format!("{}/delay", retry_pointer))?;
+            retrier.insert("IntervalSeconds".into(), json!(seconds));
+        }
+        if let Some(max_delay) = &retry.max_delay {
+            let seconds = duration_seconds(max_delay, &format!("{}/maxDelay", retry_pointer))?;
+            retrier.insert("MaxDelaySeconds".into(), json!(seconds));
+        }
+        if let Some(multiplier) = &retry.multiplier {
+            let multiplier = multiplier.value().map_err(|_| {
+                unsupported(format!("{}/multiplier", retry_pointer), "invalid multiplier")
+            })?;
+            retrier.insert("BackoffRate".into(), json!(multiplier));
+        }
+
+        Ok(Value::Object(retrier))
+    }
+
+    fn choice_rule(&self, condition: &str, pointer: &str) -> crate::Result<Map<String, Value>> {
+        let unsupported_condition = || {
+            unsupported(
+                pointer,
+                "only conditions comparing a path with a literal value can be exported",
+            )
+        };
+
+        let expression = strip_expression(condition);
+        let (left, operator, right) = ["==", "!=", "<=", ">=", "<", ">"]
+            .iter()
+            .find_map(|operator| {
+                expression
+                    .split_once(&format!(" {} ", operator))
+                    .map(|(left, right)| (left.trim(), *operator, right.trim()))
+            })
+            .ok_or_else(unsupported_condition)?;
+        let variable = self.json_path(left, pointer)?;
+        let literal: Value = serde_json::from_str(right).map_err(|_| unsupported_condition())?;
+
+        let comparison = match operator {
+            "==" | "!=" => "Equals",
+            "<" => "LessThan",
+            ">" => "GreaterThan",
+            "<=" => "LessThanEquals",
+            _ => "GreaterThanEquals",
+        };
+        let (key, value) = match &literal {
+            Value::Null if comparison == "Equals" => ("IsNull".to_string(), json!(true)),
+            Value::String(_) => (format!("String{}", comparison), literal),
+            Value::Number(_) => (format!("Numeric{}", comparison), literal),
+            Value::Bool(_) if comparison == "Equals" => ("BooleanEquals".to_string(), literal),
+            _ => return Err(unsupported_condition()),
+        };
+
+        let mut rule = Map::new();
+        rule.insert("Variable".into(), json!(variable));
+        rule.insert(key, value);
+        if operator == "!=" {
+            rule = Map::from_iter([("Not".to_string(), Value::Object(rule))]);
+        }
+
+        Ok(rule)
+    }
+
+    fn parameters(&self, template: &Value, pointer: &str) -> crate::Result<Value> {
+        match template {
+            Value::Object(fields) => {
+                let mut parameters = Map::new();
+                for (key, value) in fields {
+                    let field_pointer = format!("{}/{}", pointer, token(key));
+                    match value {
+                        Value::String(expression) if is_expression(expression) => {
+                            let path = self.json_path(expression, &field_pointer)?;
+                            parameters.insert(format!("{}.$", key), json!(path));
+                        },
+                        value => {
+                            parameters.insert(key.clone(), self.parameters(value, &field_pointer)?);
+                        },
+                    }
+                }
+                Ok(Value::Object(parameters))
+            },
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let item_pointer = format!("{}/{}", pointer, i);
+                    match item {
+                        Value::String(expression) if is_expression(expression) => {
+                            Err(unsupported(item_pointer, "expressions cannot be used in arrays"))
+                        },
+                        item => self.parameters(item, &item_pointer),
+                    }
+                })
+                .collect(),
+            value => Ok(value.clone()),
+        }
+    }
+
+    fn json_path(&self, expression: &str, pointer: &str) -> crate::Result<String> {
+        let path = strip_expression(expression);
+        let is_simple_path = path.starts_with('.')
+            && !path.contains("..")
+            && path
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "_.[]".contains(c));
+        if !is_simple_path {
+            return Err(unsupported(pointer, "only simple paths can be converted to JSONPath"));
+        }
+
+        match self.iteration_param {
+            Some(param) => path
+                .strip_prefix('.')
+                .and_then(|path| path.strip_prefix(param))
+                .filter(|rest| rest.is_empty() || rest.starts_with(['.', '[']))
+                .map(|rest| format!("${}", rest))
+                .ok_or_else(|| {
+                    unsupported(
+                        pointer,
+                        "only paths relative to the iteration parameter can be used in foreach actions",
+                    )
+                }),
+            None if path == "." => Ok("$".into()),
+            None => Ok(format!("${}", path)),
+        }
+    }
+}
+
+fn link(
+    chain: Vec<(String, Map<String, Value>)>,
+    next: Option<Next>,
+    states: &mut Map<String, Value>,
+) {
+    let names: Vec<_> = chain.iter().map(|(name, _)| name.clone()).collect();
+    for (i, (name, mut state)) in chain.into_iter().enumerate() {
+        match (names.get(i + 1), &next) {
+            (Some(next_name), _) | (None, Some(Next::State(next_name))) => {
+                state.insert("Next".into(), json!(next_name));
+            },
+            (None, Some(Next::End)) => {
+                state.insert("End".into(), json!(true));
+            },
+            (None, None) => (),
+        }
+        states.insert(name, Value::Object(state));
+    }
+}
+
+fn branch(chain: Vec<(String, Map<String, Value>)>) -> Value {
+    let start_at = chain
+        .first()
+        .map(|(name, _)| name.clone())
+        .unwrap_or_default();
+    let mut states = Map::new();
+    link(chain, Some(Next::End), &mut states);
+
+    json!({ "StartAt": start_at, "States": states })
+}
+
+fn action_state_name(prefix: &str, action: &Action, index: usize) -> String {
+    match &action.name {
+        Some(name) => format!("{} ({})", prefix, name),
+        None => format!("{} ({})", prefix, index),
+    }
+}
+
+fn check_state(pointer: &str, has_error_handling: bool, is_compensated: bool) -> crate::Result<()> {
+    if has_error_handling {
+        return Err(unsupported(
+            format!("{}/onErrors", pointer),
+            "error handling cannot be exported",
+        ));
+    }
+    if is_compensated {
+        return Err(unsupported(pointer, "compensation cannot be exported"));
+    }
+
+    Ok(())
+}
+
+fn check_state_timeout(timeout: Option<&StateExecTimeout>, pointer: &str) -> crate::Result<()> {
+    match timeout {
+        Some(_) => Err(unsupported(
+            format!("{}/timeouts/stateExecTimeout", pointer),
+            "state execution timeouts cannot be exported",
+        )),
+        None => Ok(()),
+    }
+}
+
+fn check_flow(
+    pointer: &str,
+    produces_events: bool,
+    compensates: bool,
+    continues: bool,
+) -> crate::Result<()> {
+    for (is_set, key, reason) in [
+        (produces_events, "produceEvents", "producing events cannot be exported"),
+        (compensates, "compensate", "compensation cannot be exported"),
+        (continues, "continueAs", "continueAs cannot be exported"),
+    ] {
+        if is_set {
+            return Err(unsupported(format!("{}/{}", pointer, key), reason));
+        }
+    }
+
+    Ok(())
+}
+
+fn duration_seconds(duration: &str, pointer: &str) -> crate::Result<i64> {
+    duration
+        .parse::<IsoDuration>()
+        .ok()
+        .and_then(|duration| duration.to_time_delta())
+        .filter(|delta| delta.subsec_nanos() == 0)
+        .map(|delta| delta.num_seconds())
+        .ok_or_else(|| {
+            unsupported(pointer, "only durations of a whole number of seconds can be exported")
+        })
+}
+
+fn strip_expression(expression: &str) -> &str {
+    let expression = expression.trim();
+    expression
+        .strip_prefix("${")
+        .and_then(|expression| expression.strip_suffix('}'))
+        .unwrap_or(expression)
+        .trim()
+}
+
+fn is_expression(value: &str) -> bool {
+    value.trim_start().starts_with("${")
+}
+
+fn into_object(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(object) => object,
+        _ => Map::new(),
+    }
+}
+
+fn unsupported<P>(pointer: P, reason: &'static str) -> crate::Error
+where
+    P: Into<String>,
+{
+    crate::Error::UnsupportedConversion { pointer: pointer.into(), reason }
+}
+
+fn external_definitions(pointer: &str) -> crate::Error {
+    unsupported(
+        pointer,
+        "external definitions must be resolved first (see WorkflowDefinition::resolve_external_refs)",
+    )
+}
@@ -1,6 +1,12 @@
+pub mod expression;
 #[cfg(feature = "validate")]
 pub mod garde;
 pub mod newtype;
+pub mod rng;
+
+use std::io;
+
+use serde::Serialize;
 
 use crate::workflow::definition::auth::Scheme;
 use crate::workflow::definition::common::{ExecutionMode, InvocationMode};
@@ -33,6 +39,10 @@ pub fn false_value() -> bool {
     false
 }
 
+pub fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
 pub fn jq() -> String {
     "jq".to_string()
 }
@@ -69,6 +79,29 @@ pub fn rest() -> FunctionType {
     FunctionType::Rest
 }
 
+// Returns the size of a value serialized as compact JSON, in bytes, without allocating it.
+pub fn json_size<T>(value: &T) -> usize
+where
+    T: Serialize + ?Sized,
+{
+    struct Counter(usize);
+
+    impl io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    serde_json::to_writer(&mut counter, value).expect("JSON values should be serializable");
+    counter.0
+}
+
 // A trait that is essentially a stub for `garde::Validate` (with `Context = ()`).
 // If the `validate` feature is disabled, it's an empty trait.
 // It's implemented for all types (that also implement `garde::Validate`, if needed).
@@ -0,0 +1,249 @@
+//! Lightweight static analysis of jq workflow expressions.
+//!
+//! These helpers do not fully parse jq; they only look at the top-level structure of
+//! expressions, skipping over string literals and nested groups.
+
+/// Possible result type of a jq expression, as determined by [`result_kind`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ResultKind {
+    /// Expression always yields a boolean
+    Boolean,
+
+    /// Expression yields a value of the given (non-boolean) type
+    NonBoolean(&'static str),
+
+    /// Expression's result type cannot be determined statically
+    Unknown,
+}
+
+/// Boolean-returning jq builtins, when they are the last filter of an expression.
+const BOOLEAN_BUILTINS: &[&str] = &[
+    "all",
+    "any",
+    "contains",
+    "endswith",
+    "has",
+    "in",
+    "inside",
+    "isempty",
+    "isinfinite",
+    "isnan",
+    "isnormal",
+    "isvalid",
+    "not",
+    "startswith",
+    "test",
+];
+
+/// Returns the expression without its `${ }` wrapper, if any.
+pub fn strip_expression(expression: &str) -> &str {
+    let expression = expression.trim();
+    match expression
+        .strip_prefix("${")
+        .and_then(|expression| expression.strip_suffix('}'))
+    {
+        Some(expression) => expression.trim(),
+        None => expression,
+    }
+}
+
+/// Returns `true` if the value is a workflow expression (i.e., it is wrapped in `${ }`).
+pub fn is_expression(value: &str) -> bool {
+    let value = value.trim();
+    value.starts_with("${") && value.ends_with('}')
+}
+
+/// Returns the names of the properties selected by a simple jq path (e.g. `${ .order.items }`
+/// or `${ .order["line items"] }`), or `None` if the expression is not a simple path.
+///
+/// The path `.` selects the whole data and yields no property names.
+pub fn simple_path(expression: &str) -> Option<Vec<&str>> {
+    let mut rest = strip_expression(expression);
+    if rest == "." {
+        return Some(Vec::new());
+    }
+
+    let mut path = Vec::new();
+    while !rest.is_empty() {
+        let (name, remaining) = if let Some(quoted) = rest.strip_prefix(".\"") {
+            quoted.split_once('"')?
+        } else if let Some(indexed) = rest
+            .strip_prefix(".[\"")
+            .or_else(|| rest.strip_prefix("[\"").filter(|_| !path.is_empty()))
+        {
+            let (name, remaining) = indexed.split_once('"')?;
+            (name, remaining.strip_prefix(']')?)
+        } else {
+            let name = rest.strip_prefix('.')?;
+            let end = name
+                .find(|c: char| !is_identifier_char(c))
+                .unwrap_or(name.len());
+            name.split_at(end)
+        };
+        if name.is_empty() || name.contains('\\') {
+            return None;
+        }
+
+        path.push(name);
+        rest = remaining;
+    }
+    Some(path)
+}
+
+/// Attempts to determine the type of value yielded by a jq expression.
+pub fn result_kind(expression: &str) -> ResultKind {
+    let expression = strip_expression(expression);
+    let last = top_level_segments(expression, '|')
+        .last()
+        .copied()
+        .unwrap_or_default()
+        .trim();
+
+    if has_top_level_boolean_operator(last) {
+        return ResultKind::Boolean;
+    }
+
+    if let Some(inner) = strip_parentheses(last) {
+        return result_kind(inner);
+    }
+
+    let function_name = last
+        .split(|c: char| !is_identifier_char(c))
+        .next()
+        .unwrap_or_default();
+    match last.chars().next() {
+        _ if last == "true" || last == "false" => ResultKind::Boolean,
+        _ if BOOLEAN_BUILTINS.contains(&function_name) && !function_name.is_empty() => {
+            ResultKind::Boolean
+        },
+        _ if last == "null" => ResultKind::NonBoolean("null"),
+        Some('{') => ResultKind::NonBoolean("object"),
+        Some('[') => ResultKind::NonBoolean("array"),
+        Some('"') => ResultKind::NonBoolean("string"),
+        Some(c) if c.is_ascii_digit() => ResultKind::NonBoolean("number"),
+        Some('-') if last[1..].starts_with(|c: char| c.is_ascii_digit()) => {
+            ResultKind::NonBoolean("number")
+        },
+        _ => ResultKind::Unknown,
+    }
+}
+
+/// Returns the names of the workflow constants referenced by an expression through `$CONST`.
+///
+/// Both `$CONST.name` (or `$CONST."name"`) and `$CONST["name"]` are recognized. Only the
+/// top-level constant name is returned (e.g. `a` for `$CONST.a.b`), and references found
+/// in string literals (including string interpolations) are ignored.
+pub fn constant_references(expression: &str) -> Vec<&str> {
+    let mut references = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in expression.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => (),
+            }
+            continue;
+        }
+
+        let rest = &expression[i..];
+        if c == '"' {
+            in_string = true;
+        } else if let Some(rest) = rest.strip_prefix("$CONST") {
+            let name = if let Some(quoted) = rest
+                .strip_prefix(".\"")
+                .or_else(|| rest.strip_prefix("[\""))
+            {
+                quoted.split('"').next()
+            } else if let Some(name) = rest.strip_prefix('.') {
+                name.split(|c: char| !is_identifier_char(c)).next()
+            } else {
+                None
+            };
+
+            references.extend(name.filter(|name| !name.is_empty()));
+        }
+    }
+    references
+}
+
+/// Splits an expression on the given separator, ignoring separators found in strings or groups.
+fn top_level_segments(expression: &str, separator: char) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    scan_top_level(expression, |i, c, rest| {
+        if c == separator && !rest.starts_with('=') {
+            segments.push(&expression[start..i]);
+            start = i + c.len_utf8();
+        }
+    });
+    segments.push(&expression[start..]);
+    segments
+}
+
+/// Returns the expression without its wrapping parentheses, if it is wrapped in parentheses.
+fn strip_parentheses(expression: &str) -> Option<&str> {
+    let inner = expression.strip_prefix('(')?.strip_suffix(')')?;
+
+    let mut has_top_level_chars = false;
+    scan_top_level(expression, |_, _, _| has_top_level_chars = true);
+    (!has_top_level_chars).then_some(inner)
+}
+
+/// Returns `true` if the expression contains a comparison or a boolean `and` / `or` at the
+/// top level. `and` and `or` are only considered if they are whole keywords, and not field
+/// accesses (e.g. `.flags.or`) or variables (e.g. `$and`).
+fn has_top_level_boolean_operator(expression: &str) -> bool {
+    let mut found = false;
+    let mut previous = ' ';
+    scan_top_level(expression, |_, c, rest| {
+        found |= match c {
+            '=' | '!' => rest.starts_with('='),
+            '<' | '>' => true,
+            'a' | 'o' if !is_identifier_char(previous) && previous != '.' && previous != '$' => {
+                let word = rest.split(|c: char| !is_identifier_char(c)).next();
+                matches!((c, word), ('a', Some("nd")) | ('o', Some("r")))
+            },
+            _ => false,
+        };
+        previous = c;
+    });
+    found
+}
+
+/// Calls `f` for every character found at the top level of the expression (e.g. not in a string
+/// literal nor inside parentheses, brackets or braces). `f` receives the character's index, the
+/// character itself and the rest of the expression following it.
+fn scan_top_level<F>(expression: &str, mut f: F)
+where
+    F: FnMut(usize, char, &str),
+{
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in expression.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => (),
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' if depth > 0 => depth -= 1,
+            _ if depth == 0 => f(i, c, &expression[i + c.len_utf8()..]),
+            _ => (),
+        }
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
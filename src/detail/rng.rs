@@ -0,0 +1,31 @@
+use chrono::TimeDelta;
+
+// SplitMix64 pseudo-random number generator; good enough for simulations and generated
+// examples, and deterministic for a given seed.
+pub struct Rng(pub u64);
+
+impl Rng {
+    pub fn next_f64(&mut self) -> f64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn chance(&mut self, probability: f64) -> bool {
+        self.next_f64() < probability
+    }
+
+    // Returns a number in `0..len`, or `0` if `len` is `0`.
+    pub fn index(&mut self, len: usize) -> usize {
+        ((self.next_f64() * len as f64) as usize).min(len.saturating_sub(1))
+    }
+
+    pub fn exponential(&mut self, mean: TimeDelta) -> TimeDelta {
+        let factor = -(1.0 - self.next_f64()).ln();
+        TimeDelta::milliseconds((mean.num_milliseconds() as f64 * factor) as i64)
+    }
+}
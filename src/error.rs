@@ -1,6 +1,7 @@
 //! Error type for this crate.
 
 use std::convert::Infallible;
+use std::fmt;
 use std::io;
 use std::num::{ParseFloatError, ParseIntError};
 
@@ -20,6 +21,14 @@ pub enum Error {
     #[error("workflow identifier not specified")]
     MissingIdentifier,
 
+    /// Workflow definition does not specify a version of the specification, or specifies
+    /// a version that is not supported.
+    #[error("unsupported spec version: {}", .spec_version.as_deref().unwrap_or("(none)"))]
+    UnsupportedSpecVersion {
+        /// The unsupported spec version, if one was specified.
+        spec_version: Option<String>,
+    },
+
     /// A string was supposed to contain an integer number but there was a parsing error.
     #[error("invalid number: {}", .0)]
     InvalidInt(#[from] ParseIntError),
@@ -28,6 +37,48 @@ pub enum Error {
     #[error("invalid floating-point number: {}", .0)]
     InvalidFloat(#[from] ParseFloatError),
 
+    /// A cron expression could not be parsed.
+    #[error("invalid cron expression: {}", .0)]
+    InvalidCronExpression(#[from] cron::error::Error),
+
+    /// A string was supposed to contain an ISO 8601 date and time but there was a parsing error.
+    #[error("invalid date and time: {}", .0)]
+    InvalidDateTime(#[from] chrono::ParseError),
+
+    /// A timezone name is not a valid [IANA time zone](https://www.iana.org/time-zones) name.
+    #[error("invalid timezone: {}", .timezone)]
+    InvalidTimezone {
+        /// The invalid timezone name.
+        timezone: String,
+    },
+
+    /// A string was supposed to contain an ISO 8601 duration but there was a parsing error.
+    #[error("invalid ISO 8601 duration: {}", .duration)]
+    InvalidDuration {
+        /// The invalid duration.
+        duration: String,
+    },
+
+    /// An ISO 8601 duration was supposed to have a fixed length, but it is
+    /// [calendar-dependent] or too large.
+    ///
+    /// [calendar-dependent]: crate::workflow::definition::intervals::IsoDuration::is_calendar_dependent
+    #[error("ISO 8601 duration '{}' does not have a fixed length", .duration)]
+    UnsupportedDuration {
+        /// The duration.
+        duration: String,
+    },
+
+    /// A string was supposed to contain an ISO 8601 repeating interval but there was a parsing error.
+    #[error("invalid ISO 8601 repeating interval '{}': {}", .interval, .reason)]
+    InvalidRecurringInterval {
+        /// The invalid interval.
+        interval: String,
+
+        /// Reason why the interval is invalid.
+        reason: &'static str,
+    },
+
     /// One or more validation errors occurred.
     ///
     /// ### Note
@@ -38,9 +89,34 @@ pub enum Error {
         #[cfg(feature = "validate")]
         #[from]
         garde::Report,
-        #[cfg(not(feature = "yaml"))] crate::impossible::Impossible,
+        #[cfg(not(feature = "validate"))] crate::impossible::Impossible,
     ),
 
+    /// A workflow definition contains a [custom state] of a type for which
+    /// no handler is registered.
+    ///
+    /// [custom state]: crate::workflow::definition::State::Custom
+    #[error("state '{}' has unknown type '{}'", .state, .state_type)]
+    UnknownStateType {
+        /// Name of the custom state.
+        state: String,
+
+        /// Type of the custom state.
+        state_type: String,
+    },
+
+    /// A [custom state] was rejected by the handler registered for its type.
+    ///
+    /// [custom state]: crate::workflow::definition::State::Custom
+    #[error("invalid custom state '{}': {}", .state, .reason)]
+    InvalidCustomState {
+        /// Name of the custom state.
+        state: String,
+
+        /// Reason why the state is invalid, as returned by the handler.
+        reason: String,
+    },
+
     // --- Errors related to loading/saving workflow definitions ---
     /// Error while parsing a URL/URI.
     #[error("invalid URL: {}", .0)]
@@ -67,27 +143,65 @@ pub enum Error {
         file_ext: String,
     },
 
+    /// An external resource referred to by a workflow definition refers back to itself,
+    /// directly or indirectly.
+    #[error("circular reference to external resource '{}'", .uri)]
+    CircularExternalReference {
+        /// URI of the external resource.
+        uri: Url,
+    },
+
     /// Conversion to/from JSON failed.
-    #[error("JSON conversion failed: {}", .0)]
-    JsonConversionFailed(#[from] serde_json::Error),
+    #[error("JSON conversion failed{}: {}", at_path(.path), .source)]
+    JsonConversionFailed {
+        /// Path to the element that could not be converted (e.g. `states[0].end`), if known.
+        path: Option<String>,
+
+        /// The underlying JSON error.
+        source: serde_json::Error,
+
+        /// Location of the element that could not be converted in the source document, if it
+        /// was determined while loading it (see [`source_location`](Error::source_location)).
+        location: Option<SourceLocation>,
+    },
 
     /// Conversion to/from YAML failed.
     ///
     /// ### Note
     ///
     /// This variant can only occur if the `yaml` feature is enabled.
-    #[error("YAML conversion failed: {}", .0)]
-    YamlConversionFailed(
+    #[error("YAML conversion failed{}: {}", at_path(.path), .source)]
+    YamlConversionFailed {
+        /// Path to the element that could not be converted (e.g. `states[0].end`), if known.
+        path: Option<String>,
+
+        /// The underlying YAML error.
         #[cfg(feature = "yaml")]
-        #[from]
-        serde_yaml::Error,
-        #[cfg(not(feature = "yaml"))] crate::impossible::Impossible,
-    ),
+        source: serde_yaml::Error,
+
+        /// The underlying YAML error.
+        #[cfg(not(feature = "yaml"))]
+        source: crate::impossible::Impossible,
+
+        /// Location of the element that could not be converted in the source document, if it
+        /// was determined while loading it (see [`source_location`](Error::source_location)).
+        location: Option<SourceLocation>,
+    },
 
     /// A file I/O error occurred.
     #[error("file I/O error: {}", .0)]
     FileIo(#[from] io::Error),
 
+    /// A definition object was altered by a round trip through this crate
+    /// (see [`DefinitionLoader::assert_round_trip`]).
+    ///
+    /// [`DefinitionLoader::assert_round_trip`]: crate::loader::DefinitionLoader::assert_round_trip
+    #[error("definition altered by round trip: {} difference(s)", .differences.len())]
+    RoundTripFailed {
+        /// Differences between the original document and the document after the round trip.
+        differences: Vec<crate::loader::RoundTripDifference>,
+    },
+
     // --- Errors related to caching of workflow definition objects ---
     /// A definition object was found in cache for a URI but is of the wrong type.
     #[error("error: cached object was expected to be of type '{}', actual type is '{}'", .expected_type, .actual_type)]
@@ -99,6 +213,387 @@ pub enum Error {
         actual_type: &'static str,
     },
 
+    // --- Errors related to workflow registries ---
+    /// A workflow definition was registered in a [`WorkflowRegistry`], but a different
+    /// definition is already registered with the same ID and version.
+    ///
+    /// [`WorkflowRegistry`]: crate::registry::WorkflowRegistry
+    #[error("a different workflow definition is already registered for '{}'", .key)]
+    DuplicateWorkflowDefinition {
+        /// ID and version of the workflow definition.
+        key: crate::registry::WorkflowKey,
+
+        /// URI of the workflow definition already registered, if known.
+        existing_uri: Option<String>,
+
+        /// URI of the workflow definition that could not be registered, if known.
+        new_uri: Option<String>,
+    },
+
+    /// A workflow definition in a [`RegistrySnapshot`] could not be restored.
+    ///
+    /// [`RegistrySnapshot`]: crate::registry::RegistrySnapshot
+    #[error("workflow definition '{}' could not be restored: {}", .key, .reason)]
+    RegistryRestoreFailed {
+        /// ID and version of the workflow definition.
+        key: crate::registry::WorkflowKey,
+
+        /// URI of the workflow definition, if known.
+        uri: Option<String>,
+
+        /// Reason why the workflow definition could not be restored.
+        reason: &'static str,
+    },
+
+    /// A workflow definition depends on a workflow that is not registered in a [`WorkflowRegistry`].
+    ///
+    /// [`WorkflowRegistry`]: crate::registry::WorkflowRegistry
+    #[error("workflow '{}' is referenced but not registered", .dependency)]
+    MissingWorkflowDependency {
+        /// ID and version of the referenced workflow. If the reference does not specify
+        /// a version, no version of the workflow is registered.
+        dependency: crate::registry::WorkflowKey,
+
+        /// ID and version of the workflow definition containing the reference, if known.
+        referenced_by: Option<crate::registry::WorkflowKey>,
+    },
+
+    /// A reference to a workflow does not specify a version, but multiple versions of the
+    /// workflow are registered in a [`WorkflowRegistry`].
+    ///
+    /// [`WorkflowRegistry`]: crate::registry::WorkflowRegistry
+    #[error("reference to workflow '{}' is ambiguous: versions {:?} are registered", .id, .versions)]
+    AmbiguousWorkflowReference {
+        /// ID of the referenced workflow.
+        id: String,
+
+        /// Registered versions of the workflow.
+        versions: Vec<String>,
+    },
+
+    // --- Errors related to workflow instances ---
+    /// A workflow instance was supposed to point to a state that does not exist
+    /// in its workflow definition.
+    #[error("workflow definition has no state named '{}'", .state)]
+    UnknownState {
+        /// Name of the state.
+        state: String,
+    },
+
+    /// A workflow instance references an event that is not defined in its workflow definition.
+    #[error("workflow definition has no event named '{}'", .event)]
+    UnknownEvent {
+        /// Name of the event.
+        event: String,
+    },
+
+    /// A CloudEvent could not be built for an event produced by a workflow.
+    #[error("CloudEvent for event '{}' could not be built: {}", .event, .reason)]
+    InvalidCloudEvent {
+        /// Name of the event.
+        event: String,
+
+        /// Reason why the CloudEvent could not be built.
+        reason: String,
+    },
+
+    /// A workflow instance cannot be resumed at a state that is [used for compensation].
+    ///
+    /// [used for compensation]: crate::workflow::definition::State::used_for_compensation
+    #[error("cannot resume at state '{}' because it is used for compensation", .state)]
+    CompensationState {
+        /// Name of the state.
+        state: String,
+    },
+
+    /// The data of a workflow instance exceeds the configured size limit.
+    #[error("data of workflow instance '{}' is too large ({} bytes, limit is {} bytes)", .instance, .size, .limit)]
+    InstanceDataTooLarge {
+        /// ID of the workflow instance.
+        instance: String,
+
+        /// Size of the instance data, in bytes of serialized JSON.
+        size: usize,
+
+        /// Maximum size of the instance data, in bytes of serialized JSON.
+        limit: usize,
+    },
+
+    // --- Errors related to workflow execution ---
+    /// A workflow timeout expired (see [`Deadlines::check`]).
+    ///
+    /// [`Deadlines::check`]: crate::workflow::runtime::deadlines::Deadlines::check
+    #[error("{} expired{}", .timeout, in_state(.state))]
+    TimedOut {
+        /// Kind of timeout that expired.
+        timeout: crate::workflow::runtime::deadlines::TimeoutKind,
+
+        /// Name of the state being executed when the timeout expired, if any.
+        state: Option<String>,
+    },
+
+    /// A workflow expression could not be evaluated.
+    #[error("expression '{}' could not be evaluated: {}", .expression, .reason)]
+    ExpressionFailed {
+        /// The expression that could not be evaluated.
+        expression: String,
+
+        /// Reason why the expression could not be evaluated.
+        reason: String,
+    },
+
+    /// A workflow expression evaluated to a value of an unexpected type.
+    #[error("expression '{}' must evaluate to {}", .expression, .expected)]
+    InvalidExpressionResult {
+        /// The expression that was evaluated.
+        expression: String,
+
+        /// Description of the expected value (e.g. `a boolean`).
+        expected: &'static str,
+    },
+
+    /// Event data or action results could not be merged into the state data.
+    #[error("cannot merge data into state data element '{}': {}", .element, .reason)]
+    StateDataMergeFailed {
+        /// Expression selecting the state data element.
+        element: String,
+
+        /// Reason why the data could not be merged.
+        reason: &'static str,
+    },
+
+    /// An action cannot be performed by the workflow runtime.
+    #[error("action '{}' is not supported: {}", .action, .reason)]
+    UnsupportedAction {
+        /// Name of the action, or its index in its state if it has no name.
+        action: String,
+
+        /// Reason why the action is not supported.
+        reason: &'static str,
+    },
+
+    /// A [parallel state] requires more branches to complete than it has, or does not specify
+    /// how many.
+    ///
+    /// [parallel state]: crate::workflow::definition::ParallelState
+    #[error("invalid completion count for parallel state '{}': {}", .state, .reason)]
+    InvalidCompletionCount {
+        /// Name of the parallel state.
+        state: String,
+
+        /// Reason why the completion count is invalid.
+        reason: String,
+    },
+
+    /// A branch of a [parallel state] did not complete before its [branch execution timeout].
+    ///
+    /// [parallel state]: crate::workflow::definition::ParallelState
+    /// [branch execution timeout]: crate::workflow::definition::timeouts::BranchExecTimeout
+    #[error("branch '{}' of parallel state '{}' timed out", .branch, .state)]
+    BranchTimedOut {
+        /// Name of the parallel state.
+        state: String,
+
+        /// Name of the branch that timed out.
+        branch: String,
+    },
+
+    /// A state is compensated by a state that is not [used for compensation].
+    ///
+    /// [used for compensation]: crate::workflow::definition::State::used_for_compensation
+    #[error("state '{}' cannot be compensated by state '{}', which is not used for compensation", .state, .compensated_by)]
+    InvalidCompensation {
+        /// Name of the compensated state.
+        state: String,
+
+        /// Name of the state responsible for compensating it.
+        compensated_by: String,
+    },
+
+    /// A workflow instance cannot [continue as] a new execution or invoke a [sub-workflow],
+    /// because the new instance would exceed the maximum [depth] (see
+    /// [`ContinueAsExecution::with_max_depth`] and [`SubflowInvocation::with_max_depth`]).
+    ///
+    /// [continue as]: crate::workflow::definition::End::continue_as
+    /// [sub-workflow]: crate::workflow::definition::Action::sub_flow_ref
+    /// [depth]: crate::workflow::instance::WorkflowInstance::depth
+    /// [`ContinueAsExecution::with_max_depth`]: crate::workflow::runtime::continue_as::ContinueAsExecution::with_max_depth
+    /// [`SubflowInvocation::with_max_depth`]: crate::workflow::runtime::subflow::SubflowInvocation::with_max_depth
+    #[error("workflow instance '{}' cannot run workflow '{}': maximum depth of {} exceeded", .instance, .workflow, .limit)]
+    RecursionLimitExceeded {
+        /// ID of the workflow instance.
+        instance: String,
+
+        /// Identifier of the workflow to continue execution as or to invoke.
+        workflow: String,
+
+        /// Maximum depth of workflow instances.
+        limit: u32,
+    },
+
+    // --- Errors related to action execution ---
+    /// An action references a function that is not defined in its workflow definition.
+    #[error("workflow definition has no function named '{}'", .function)]
+    UnknownFunction {
+        /// Name of the function.
+        function: String,
+    },
+
+    /// A function references an auth definition that is not defined in its workflow definition.
+    #[error("workflow definition has no auth definition named '{}'", .auth)]
+    UnknownAuth {
+        /// Name of the auth definition.
+        auth: String,
+    },
+
+    /// An action references a retry definition that is not defined in its workflow definition.
+    #[error("workflow definition has no retry definition named '{}'", .retry)]
+    UnknownRetry {
+        /// Name of the retry definition.
+        retry: String,
+    },
+
+    /// A [`HandlerActionExecutor`] was asked to call a function for which no handler
+    /// is registered.
+    ///
+    /// [`HandlerActionExecutor`]: crate::workflow::executor::HandlerActionExecutor
+    #[error("no handler registered for function '{}'", .function)]
+    MissingFunctionHandler {
+        /// Name of the function.
+        function: String,
+    },
+
+    /// A function's [operation] could not be resolved.
+    ///
+    /// [operation]: crate::workflow::definition::functions::Function::operation
+    #[error("invalid operation '{}' for function '{}': {}", .operation, .function, .reason)]
+    InvalidFunctionOperation {
+        /// Name of the function.
+        function: String,
+
+        /// The function's operation.
+        operation: String,
+
+        /// Reason why the operation could not be resolved.
+        reason: &'static str,
+    },
+
+    /// A function call is missing an argument required by the function's operation.
+    #[error("missing argument '{}' for function '{}'", .argument, .function)]
+    MissingFunctionArgument {
+        /// Name of the function.
+        function: String,
+
+        /// Name of the missing argument.
+        argument: String,
+    },
+
+    /// An executor's [`EgressPolicy`] does not allow sending the request calling a function.
+    ///
+    /// [`EgressPolicy`]: crate::workflow::executor::http::EgressPolicy
+    #[error("egress policy does not allow function '{}' to send requests to '{}'", .function, .url)]
+    EgressDenied {
+        /// Name of the function.
+        function: String,
+
+        /// URL of the denied request.
+        url: Url,
+    },
+
+    /// A [`UrlPattern`] could not be parsed.
+    ///
+    /// [`UrlPattern`]: crate::workflow::executor::http::UrlPattern
+    #[error("invalid URL pattern '{}': {}", .pattern, .reason)]
+    InvalidUrlPattern {
+        /// The invalid pattern.
+        pattern: String,
+
+        /// Reason why the pattern is invalid.
+        reason: &'static str,
+    },
+
+    /// A function's auth definition is not supported by an [`ActionExecutor`].
+    ///
+    /// [`ActionExecutor`]: crate::workflow::executor::ActionExecutor
+    #[error("unsupported auth definition '{}': {}", .auth, .reason)]
+    UnsupportedAuth {
+        /// Name of the auth definition.
+        auth: String,
+
+        /// Reason why the auth definition is not supported.
+        reason: &'static str,
+    },
+
+    /// The result of a function call exceeds the configured size limit.
+    #[error("result of function '{}' is too large ({} bytes, limit is {} bytes)", .function, .size, .limit)]
+    ActionResultTooLarge {
+        /// Name of the function.
+        function: String,
+
+        /// Size of the result, in bytes of serialized JSON.
+        size: usize,
+
+        /// Maximum size of the result, in bytes of serialized JSON.
+        limit: usize,
+    },
+
+    /// An [`ActionExecutor`] failed to call a function.
+    ///
+    /// [`ActionExecutor`]: crate::workflow::executor::ActionExecutor
+    #[error("call to function '{}' failed with error '{}': {}", .function, .error, .reason)]
+    ActionFailed {
+        /// Name of the function.
+        function: String,
+
+        /// Name of the workflow error that occurred, used to find the state's [error handler].
+        ///
+        /// [error handler]: crate::workflow::definition::State::error_handler
+        error: String,
+
+        /// Description of the failure.
+        reason: String,
+    },
+
+    // --- Errors related to conversions from/to other workflow languages ---
+    /// An [Amazon States Language] state machine could not be imported.
+    ///
+    /// [Amazon States Language]: https://states-language.net/spec.html
+    #[error("invalid state machine at '{}': {}", .pointer, .reason)]
+    InvalidStateMachine {
+        /// JSON pointer to the invalid construct in the state machine.
+        pointer: String,
+
+        /// Reason why the construct is invalid.
+        reason: &'static str,
+    },
+
+    /// A workflow definition construct cannot be converted to another workflow language.
+    #[error("construct at '{}' cannot be converted: {}", .pointer, .reason)]
+    UnsupportedConversion {
+        /// JSON pointer to the construct in the workflow definition.
+        pointer: String,
+
+        /// Reason why the construct cannot be converted.
+        reason: &'static str,
+    },
+
+    // --- Errors related to workflow data input ---
+    /// A JSON Schema could not be compiled.
+    #[error("invalid JSON Schema: {}", .message)]
+    InvalidJsonSchema {
+        /// Message describing the problem with the JSON Schema.
+        message: String,
+    },
+
+    /// Workflow data input failed validation against the workflow's [data input schema].
+    ///
+    /// [data input schema]: crate::workflow::definition::WorkflowDefinition::data_input_schema
+    #[error("workflow data input failed validation: {} error(s)", .errors.len())]
+    InputValidationFailed {
+        /// The validation errors found in the workflow data input.
+        errors: Vec<crate::validation::input::InputValidationError>,
+    },
+
     // --- Utility errors ---
     /// Operation is unsupported because a feature is disabled.
     #[error("unsupported operation, requires feature '{}'", .required_feature)]
@@ -108,6 +603,99 @@ pub enum Error {
     },
 }
 
+impl Error {
+    /// Returns the name of the workflow error that occurred, if this error can be handled by
+    /// the workflow.
+    ///
+    /// Only [`ActionFailed`] errors carry a workflow error; the name can be resolved to one of
+    /// the workflow's error definitions with [`WorkflowDefinition::error_def`].
+    ///
+    /// [`ActionFailed`]: Error::ActionFailed
+    /// [`WorkflowDefinition::error_def`]: crate::workflow::definition::WorkflowDefinition::error_def
+    pub fn workflow_error(&self) -> Option<&str> {
+        match self {
+            Self::ActionFailed { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+
+    /// Returns the location in the source document where this error occurred, if known.
+    ///
+    /// Locations are only available for [`JsonConversionFailed`] and [`YamlConversionFailed`]
+    /// errors that occurred while parsing a document. When a [`DefinitionLoader`] fails to convert
+    /// an element of a well-formed document (for example, an action inside a state), the location
+    /// of that element is looked up in the document; otherwise, the location is the one reported
+    /// by the parser, if it was able to pinpoint the problem.
+    ///
+    /// [`JsonConversionFailed`]: Error::JsonConversionFailed
+    /// [`YamlConversionFailed`]: Error::YamlConversionFailed
+    /// [`DefinitionLoader`]: crate::loader::DefinitionLoader
+    pub fn source_location(&self) -> Option<SourceLocation> {
+        match self {
+            Self::JsonConversionFailed { location: Some(location), .. }
+            | Self::YamlConversionFailed { location: Some(location), .. } => Some(*location),
+            Self::JsonConversionFailed { source, .. } if source.line() > 0 => {
+                Some(SourceLocation { line: source.line(), column: source.column() })
+            },
+            #[cfg(feature = "yaml")]
+            Self::YamlConversionFailed { source, .. } => source.location().map(|location| {
+                SourceLocation { line: location.line(), column: location.column() }
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Location in a source document.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SourceLocation {
+    /// Line number, starting at 1.
+    pub line: usize,
+
+    /// Column number, starting at 1.
+    pub column: usize,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Self::JsonConversionFailed { path: None, source: value, location: None }
+    }
+}
+
+impl From<serde_path_to_error::Error<serde_json::Error>> for Error {
+    fn from(value: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        Self::JsonConversionFailed {
+            path: path_to_string(value.path()),
+            source: value.into_inner(),
+            location: None,
+        }
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl From<serde_yaml::Error> for Error {
+    fn from(value: serde_yaml::Error) -> Self {
+        Self::YamlConversionFailed { path: None, source: value, location: None }
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl From<serde_path_to_error::Error<serde_yaml::Error>> for Error {
+    fn from(value: serde_path_to_error::Error<serde_yaml::Error>) -> Self {
+        Self::YamlConversionFailed {
+            path: path_to_string(value.path()),
+            source: value.into_inner(),
+            location: None,
+        }
+    }
+}
+
 impl<E> OptFrom<E> for Error
 where
     E: Into<Error>,
@@ -122,3 +710,21 @@ impl OptFrom<Infallible> for Error {
         None
     }
 }
+
+fn path_to_string(path: &serde_path_to_error::Path) -> Option<String> {
+    // An empty path is displayed as `.`, which is not very helpful.
+    path.iter().next().map(|_| path.to_string())
+}
+
+fn at_path(path: &Option<String>) -> String {
+    path.as_ref()
+        .map(|path| format!(" at '{}'", path))
+        .unwrap_or_default()
+}
+
+fn in_state(state: &Option<String>) -> String {
+    state
+        .as_ref()
+        .map(|state| format!(" in state '{}'", state))
+        .unwrap_or_default()
+}
@@ -1,5 +1,7 @@
 //! Definition of a type that cannot be created.
 
+use std::fmt;
+
 /// A type that cannot be created.
 ///
 /// Because the enum has no variant, a value of this type cannot exist. It is used to denote
@@ -13,3 +15,11 @@
 /// is still unstable. When the type is stabilized, it could be used instead.
 #[derive(Debug)]
 pub enum Impossible {}
+
+impl fmt::Display for Impossible {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl std::error::Error for Impossible {}
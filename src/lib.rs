@@ -1,6 +1,7 @@
 //! A Rust library implementing the [Serverless workflow] specification. In progress.
 //!
-//! Implements [v0.8] of the specification.
+//! Implements [v0.8] of the specification. Workflow definitions written with version 1.0 of the
+//! DSL can also be [loaded](workflow::versioned::VersionedWorkflowDefinition).
 //!
 //! [Serverless workflow]: https://serverlessworkflow.io/
 //! [v0.8]: https://github.com/serverlessworkflow/specification/blob/v0.8/specification.md
@@ -13,12 +14,18 @@
 #![cfg_attr(any(nightly_rustc, docsrs), feature(doc_cfg))]
 
 pub mod cache;
+pub mod capabilities;
+pub mod clock;
+pub mod convert;
 pub(crate) mod detail;
 pub mod error;
 pub mod impossible;
 pub mod loader;
+pub mod openapi;
+pub mod registry;
 pub mod validation;
 pub mod workflow;
 
+pub use capabilities::capabilities;
 pub use error::Error;
 pub use error::Result;
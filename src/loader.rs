@@ -1,22 +1,50 @@
 //! Loader of workflow definition resources.
 
+mod locate;
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::sync::Arc;
 
 use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
 use url::Url;
 
+use crate::validation::lint::rules::MisplacedWildcardErrorHandlers;
+use crate::validation::lint::LintRule;
 use crate::validation::ValidateDefinition;
+use crate::workflow::definition::{CustomState, State, WorkflowDefinition, WorkflowHeader};
+use crate::workflow::versioned::{SpecVersion, VersionedWorkflowDefinition};
 
 /// Loader used through this crate to load workflow definition resources.
 ///
-/// Can load resources from both JSON and YAML[^1] files. Can load resources from file
-/// or HTTP(S) URIs.
+/// Can load resources from both JSON and YAML[^1] files. Can currently only load resources
+/// from file URIs.
+///
+/// By default, the loader is strict: resources containing properties not defined in the
+/// specification are rejected. In [lenient mode], such properties are instead ignored and
+/// reported as [`LoadWarning`]s. The loader can also [warn about deprecated constructs] found in
+/// workflow definitions. Wildcard error handlers defined before handlers for specific errors are
+/// always reported as [`LoadWarning::MisplacedWildcardErrorHandler`]s. Workflow definitions
+/// containing [custom states] are rejected, unless a [handler](Self::custom_state) is registered
+/// for their type.
 ///
 /// [^1]: requires the `yaml` feature (enabled by default).
-#[derive(Debug, Default)]
-pub struct DefinitionLoader {}
+///
+/// [lenient mode]: Self::lenient
+/// [warn about deprecated constructs]: Self::warn_deprecated
+/// [custom states]: State::Custom
+#[derive(Debug, Default, Clone)]
+pub struct DefinitionLoader {
+    lenient: bool,
+    warn_deprecated: bool,
+    custom_states: HashMap<String, Arc<dyn CustomStateHandler>>,
+}
 
 impl DefinitionLoader {
     /// Creates a new default loader.
@@ -24,6 +52,57 @@ impl DefinitionLoader {
         Self::default()
     }
 
+    /// Returns a copy of this loader with lenient mode enabled or disabled.
+    ///
+    /// In lenient mode, properties not defined in the specification (for example, properties
+    /// introduced in a newer spec revision or vendor extensions) are ignored instead of causing
+    /// a [`JsonConversionFailed`] or [`YamlConversionFailed`] error. Ignored properties are
+    /// returned as [`LoadWarning`]s by [`load_with_warnings`].
+    ///
+    /// [`JsonConversionFailed`]: crate::Error::JsonConversionFailed
+    /// [`YamlConversionFailed`]: crate::Error::YamlConversionFailed
+    /// [`load_with_warnings`]: Self::load_with_warnings
+    pub fn lenient(self, lenient: bool) -> Self {
+        Self { lenient, ..self }
+    }
+
+    /// Returns whether this loader is in [lenient mode](Self::lenient).
+    pub fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Returns a copy of this loader that warns about deprecated constructs or not.
+    ///
+    /// When enabled, loading a v0.8 workflow definition returns a [`LoadWarning::Deprecated`]
+    /// for every construct that is dropped or changed in version 1.0 of the DSL (see
+    /// [`WorkflowDefinition::migrate_to_v1`]). Warnings are returned by [`load_with_warnings`].
+    ///
+    /// [`load_with_warnings`]: Self::load_with_warnings
+    pub fn warn_deprecated(self, warn_deprecated: bool) -> Self {
+        Self { warn_deprecated, ..self }
+    }
+
+    /// Returns whether this loader [warns about deprecated constructs](Self::warn_deprecated).
+    pub fn warns_deprecated(&self) -> bool {
+        self.warn_deprecated
+    }
+
+    /// Returns a copy of this loader that accepts [custom states] of the given type.
+    ///
+    /// When a workflow definition is loaded, its custom states of that type are validated
+    /// using `handler`.
+    ///
+    /// [custom states]: State::Custom
+    pub fn custom_state<S, H>(mut self, state_type: S, handler: H) -> Self
+    where
+        S: Into<String>,
+        H: CustomStateHandler + 'static,
+    {
+        self.custom_states
+            .insert(state_type.into(), Arc::new(handler));
+        self
+    }
+
     /// Loads a definition object located at the given URI and returns it.
     ///
     /// If the `validate` feature is enabled, the resource is validated before being returned.
@@ -35,11 +114,14 @@ impl DefinitionLoader {
     /// * [`FeatureDisabled`]: operation cannot be performed because a disabled feature
     /// * [`InvalidFileUri`]: `uri` is a `file://` URI but the URI format is invalid
     /// * [`FileIo`]: I/O error while loading file content
-    /// * [`JsonConversionFailed`]: error while deserializing JSON data
-    /// * [`YamlConversionFailed`]: error while deserializing YAML data[^3]
+    /// * [`JsonConversionFailed`]: error while deserializing JSON data; includes the path to the offending element
+    /// * [`YamlConversionFailed`]: error while deserializing YAML data[^3]; includes the path to the offending element
     /// * [`ValidationFailed`]: definition successfully loaded but determined to be invalid[^4]
+    /// * [`UnknownStateType`]: workflow definition contains a [custom state](State::Custom)
+    ///   with no [registered handler](Self::custom_state)
+    /// * [`InvalidCustomState`]: a custom state was rejected by the handler of its type
     ///
-    /// [^1]: currently, only `file://` or `http(s)://` URIs are supported.
+    /// [^1]: currently, only `file://` URIs are supported.
     ///
     /// [^2]: currently, only JSON and YAML files are supported. YAML files require
     ///       the `yaml` feature (enabled by default).
@@ -56,13 +138,273 @@ impl DefinitionLoader {
     /// [`JsonConversionFailed`]: crate::Error::JsonConversionFailed
     /// [`YamlConversionFailed`]: crate::Error::YamlConversionFailed
     /// [`ValidationFailed`]: crate::Error::ValidationFailed
+    /// [`UnknownStateType`]: crate::Error::UnknownStateType
+    /// [`InvalidCustomState`]: crate::Error::InvalidCustomState
     pub fn load<T>(&self, uri: &Url) -> crate::Result<Rc<T>>
     where
-        T: ValidateDefinition + DeserializeOwned,
+        T: ValidateDefinition + DeserializeOwned + Any,
+    {
+        self.load_with_warnings(uri).map(|(def, _)| def)
+    }
+
+    /// Loads a definition object located at the given URI and returns it, along with any
+    /// warning emitted while loading.
+    ///
+    /// Warnings are emitted in [lenient mode](Self::lenient), when [warning about deprecated
+    /// constructs](Self::warn_deprecated) and for [wildcard error handlers] defined before
+    /// handlers for specific errors.
+    ///
+    /// [wildcard error handlers]: LoadWarning::MisplacedWildcardErrorHandler
+    ///
+    /// # Errors
+    ///
+    /// See [`load`](Self::load).
+    pub fn load_with_warnings<T>(&self, uri: &Url) -> crate::Result<(Rc<T>, Vec<LoadWarning>)>
+    where
+        T: ValidateDefinition + DeserializeOwned + Any,
     {
+        let (bytes, file_ext) = self.load_bytes(uri)?;
+        let (def, warnings) = self.load_from_bytes(&bytes, &file_ext)?;
+
+        Ok((Rc::new(def), warnings))
+    }
+
+    /// Loads the workflow definitions stored in the given directory, whatever version of the
+    /// specification they conform to.
+    ///
+    /// Each JSON or YAML file in the directory (not including subdirectories) is classified
+    /// according to the [version of the specification](SpecVersion::detect) it declares. Files
+    /// declaring a supported version are loaded as [`VersionedWorkflowDefinition`]s, like with
+    /// [`load_with_warnings`]; other files are reported as unsupported. Files with other
+    /// extensions are ignored.
+    ///
+    /// Files that cannot be loaded do not prevent the others from being loaded: the outcome for
+    /// each file is returned in a [`CatalogReport`].
+    ///
+    /// # Errors
+    ///
+    /// * [`FileIo`]: I/O error while listing the directory's content
+    ///
+    /// [`load_with_warnings`]: Self::load_with_warnings
+    /// [`FileIo`]: crate::Error::FileIo
+    pub fn load_catalog<P>(&self, dir: P) -> crate::Result<CatalogReport>
+    where
+        P: AsRef<Path>,
+    {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let file_ext = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("")
+                .to_ascii_lowercase();
+            if matches!(file_ext.as_str(), "json" | "yaml" | "yml") && path.is_file() {
+                files.push((path, file_ext));
+            }
+        }
+        files.sort();
+
+        let entries = files
+            .into_iter()
+            .map(|(path, file_ext)| {
+                let outcome = self
+                    .load_catalog_entry(&path, &file_ext)
+                    .unwrap_or_else(CatalogOutcome::Failed);
+                CatalogEntry { path, outcome }
+            })
+            .collect();
+        Ok(CatalogReport { entries })
+    }
+
+    fn load_catalog_entry(&self, path: &Path, file_ext: &str) -> crate::Result<CatalogOutcome> {
+        let bytes = fs::read(path)?;
+        let document: Value = match file_ext {
+            "json" => self.load_from_json(&bytes)?,
+            _ => self.load_from_yaml(&bytes)?,
+        };
+        let spec_version = match SpecVersion::detect(&document) {
+            Ok(spec_version) => spec_version,
+            Err(crate::Error::UnsupportedSpecVersion { spec_version }) => {
+                return Ok(CatalogOutcome::Unsupported { spec_version });
+            },
+            Err(err) => return Err(err),
+        };
+
+        // Definitions are loaded through their concrete type so that lenient mode
+        // can find the unknown fields.
+        let (definition, warnings) = match spec_version {
+            SpecVersion::V0_8 => {
+                let (definition, warnings) = self.load_from_bytes(&bytes, file_ext)?;
+                (VersionedWorkflowDefinition::V0_8(definition), warnings)
+            },
+            SpecVersion::V1_0 => {
+                let (definition, warnings) = self.load_from_bytes(&bytes, file_ext)?;
+                (VersionedWorkflowDefinition::V1_0(definition), warnings)
+            },
+        };
+        Ok(CatalogOutcome::Loaded { definition: Rc::new(definition), warnings })
+    }
+
+    // Loads a definition object from the content of a resource with the given file extension.
+    fn load_from_bytes<T>(
+        &self,
+        bytes: &[u8],
+        file_ext: &str,
+    ) -> crate::Result<(T, Vec<LoadWarning>)>
+    where
+        T: ValidateDefinition + DeserializeOwned + Any,
+    {
+        let deserialize = || -> crate::Result<(T, Vec<LoadWarning>)> {
+            Ok(match (file_ext, self.lenient) {
+                ("json", false) => (self.load_from_json::<T>(bytes)?, Vec::new()),
+                ("yaml" | "yml", false) => (self.load_from_yaml::<T>(bytes)?, Vec::new()),
+                ("json", true) => deserialize_lenient(self.load_from_json::<Value>(bytes)?)?,
+                ("yaml" | "yml", true) => {
+                    deserialize_lenient(self.load_from_yaml::<Value>(bytes)?)?
+                },
+                (ext, _) => {
+                    return Err(crate::Error::UnsupportedFileFormat { file_ext: ext.into() })
+                },
+            })
+        };
+        let (def, mut warnings) =
+            deserialize().map_err(|err| locate::locate_conversion_error(err, bytes, file_ext))?;
+
+        #[cfg(feature = "validate")]
+        {
+            def.validate_definition()?;
+        }
+        if let Some(definition) = workflow_definition(&def) {
+            self.validate_custom_states(definition)?;
+            warnings.extend(misplaced_wildcard_warnings(definition));
+        }
+
+        if self.warn_deprecated {
+            warnings.extend(deprecation_warnings(&def));
+        }
+
+        Ok((def, warnings))
+    }
+
+    /// Checks that a definition object survives a round trip through this crate, and returns the
+    /// differences found.
+    ///
+    /// The content of a resource with the given file extension is loaded like with
+    /// [`load_with_warnings`], then serialized back to JSON and compared structurally with the
+    /// original document. Each property that was lost, added or changed is returned as a
+    /// [`RoundTripDifference`]; an empty list means that the definition object can safely be used
+    /// in read-modify-write pipelines.
+    ///
+    /// The [`DefaultsPolicy`] determines whether properties set to their default value are
+    /// reported when they were only added or removed during the round trip (for example, a
+    /// state's `usedForCompensation` property, which is always serialized).
+    ///
+    /// # Errors
+    ///
+    /// * [`UnsupportedFileFormat`]: the file extension is not supported
+    /// * [`FeatureDisabled`]: operation cannot be performed because a disabled feature
+    /// * [`JsonConversionFailed`]: error while deserializing JSON data
+    /// * [`YamlConversionFailed`]: error while deserializing YAML data
+    ///
+    /// See [`load`](Self::load) for other errors that can occur while loading.
+    ///
+    /// [`load_with_warnings`]: Self::load_with_warnings
+    /// [`UnsupportedFileFormat`]: crate::Error::UnsupportedFileFormat
+    /// [`FeatureDisabled`]: crate::Error::FeatureDisabled
+    /// [`JsonConversionFailed`]: crate::Error::JsonConversionFailed
+    /// [`YamlConversionFailed`]: crate::Error::YamlConversionFailed
+    pub fn round_trip<T>(
+        &self,
+        bytes: &[u8],
+        file_ext: &str,
+        defaults: DefaultsPolicy,
+    ) -> crate::Result<Vec<RoundTripDifference>>
+    where
+        T: ValidateDefinition + DeserializeOwned + Serialize + PartialEq + Any,
+    {
+        let original: Value = match file_ext {
+            "json" => self.load_from_json(bytes)?,
+            "yaml" | "yml" => self.load_from_yaml(bytes)?,
+            ext => return Err(crate::Error::UnsupportedFileFormat { file_ext: ext.into() }),
+        };
+        let (def, _) = self.load_from_bytes::<T>(bytes, file_ext)?;
+        let serialized = serde_json::to_value(&def)?;
+
+        let mut differences = Vec::new();
+        diff_values(&original, &serialized, String::new(), &mut differences);
+        if defaults == DefaultsPolicy::Ignore {
+            differences.retain(|difference| !is_default(&def, &serialized, difference));
+        }
+
+        Ok(differences)
+    }
+
+    /// Asserts that a definition object survives a round trip through this crate, ignoring
+    /// properties set to their default value.
+    ///
+    /// See [`round_trip`](Self::round_trip) for details.
+    ///
+    /// # Errors
+    ///
+    /// * [`RoundTripFailed`]: the definition object was altered by the round trip; the error
+    ///                        lists the differences found
+    ///
+    /// See [`round_trip`](Self::round_trip) for other errors.
+    ///
+    /// [`RoundTripFailed`]: crate::Error::RoundTripFailed
+    pub fn assert_round_trip<T>(&self, bytes: &[u8], file_ext: &str) -> crate::Result<()>
+    where
+        T: ValidateDefinition + DeserializeOwned + Serialize + PartialEq + Any,
+    {
+        let differences = self.round_trip::<T>(bytes, file_ext, DefaultsPolicy::Ignore)?;
+        match differences.is_empty() {
+            true => Ok(()),
+            false => Err(crate::Error::RoundTripFailed { differences }),
+        }
+    }
+
+    /// Loads the [header](WorkflowHeader) of the workflow definition located at the given URI.
+    ///
+    /// Only the properties identifying the workflow definition are deserialized; its states and
+    /// other parts are skipped, and the definition is not validated. This is much faster than
+    /// [loading](Self::load) the entire workflow definition, which is useful to index a large
+    /// number of definitions.
+    ///
+    /// # Errors
+    ///
+    /// * [`UnsupportedUriScheme`]: `uri`'s scheme is not supported
+    /// * [`UnsupportedFileFormat`]: `uri`'s file extension is not supported
+    /// * [`FeatureDisabled`]: operation cannot be performed because a disabled feature
+    /// * [`InvalidFileUri`]: `uri` is a `file://` URI but the URI format is invalid
+    /// * [`FileIo`]: I/O error while loading file content
+    /// * [`JsonConversionFailed`]: error while deserializing JSON data
+    /// * [`YamlConversionFailed`]: error while deserializing YAML data
+    ///
+    /// See [`load`](Self::load) for details.
+    ///
+    /// [`UnsupportedUriScheme`]: crate::Error::UnsupportedUriScheme
+    /// [`UnsupportedFileFormat`]: crate::Error::UnsupportedFileFormat
+    /// [`FeatureDisabled`]: crate::Error::FeatureDisabled
+    /// [`InvalidFileUri`]: crate::Error::InvalidPathInFileUri
+    /// [`FileIo`]: crate::Error::FileIo
+    /// [`JsonConversionFailed`]: crate::Error::JsonConversionFailed
+    /// [`YamlConversionFailed`]: crate::Error::YamlConversionFailed
+    pub fn load_header(&self, uri: &Url) -> crate::Result<WorkflowHeader> {
+        let (bytes, file_ext) = self.load_bytes(uri)?;
+        match file_ext.as_str() {
+            "json" => self.load_from_json(&bytes),
+            "yaml" | "yml" => self.load_from_yaml(&bytes),
+            ext => Err(crate::Error::UnsupportedFileFormat { file_ext: ext.into() }),
+        }
+    }
+
+    // Returns the content of the resource at `uri`, along with its (lowercase) file extension.
+    fn load_bytes(&self, uri: &Url) -> crate::Result<(Vec<u8>, String)> {
+        // Loading resources from HTTP(S) URIs is not currently supported;
+        // see `Capabilities::http`.
         let bytes = match uri.scheme() {
             "file" => self.load_from_file(uri),
-            "http" | "https" => self.load_from_http(uri),
             scheme => Err(crate::Error::UnsupportedUriScheme { scheme: scheme.into() }),
         }?;
 
@@ -70,23 +412,35 @@ impl DefinitionLoader {
             .path_segments()
             .and_then(|mut p| p.next_back())
             .and_then(|p| Path::new(p).extension())
-            .map(|ext| ext.to_ascii_lowercase());
-        let file_ext = file_ext
-            .as_deref()
             .and_then(|ext| ext.to_str())
-            .unwrap_or("");
-        let def = Rc::new(match file_ext {
-            "json" => self.load_from_json::<T>(&bytes),
-            "yaml" | "yml" => self.load_from_yaml::<T>(&bytes),
-            ext => Err(crate::Error::UnsupportedFileFormat { file_ext: ext.into() }),
-        }?);
+            .unwrap_or("")
+            .to_ascii_lowercase();
 
-        #[cfg(feature = "validate")]
-        {
-            def.validate_definition()?;
+        Ok((bytes, file_ext))
+    }
+
+    fn validate_custom_states(&self, definition: &WorkflowDefinition) -> crate::Result<()> {
+        let custom_states = definition.states.iter().filter_map(|state| match state {
+            State::Custom(state) => Some(state),
+            _ => None,
+        });
+
+        for state in custom_states {
+            let handler = self.custom_states.get(&state.state_type).ok_or_else(|| {
+                crate::Error::UnknownStateType {
+                    state: state.name.clone(),
+                    state_type: state.state_type.clone(),
+                }
+            })?;
+            handler
+                .validate(state)
+                .map_err(|reason| crate::Error::InvalidCustomState {
+                    state: state.name.clone(),
+                    reason,
+                })?;
         }
 
-        Ok(def)
+        Ok(())
     }
 
     fn load_from_file(&self, uri: &Url) -> crate::Result<Vec<u8>> {
@@ -97,15 +451,15 @@ impl DefinitionLoader {
         Ok(fs::read(path)?)
     }
 
-    fn load_from_http(&self, _uri: &Url) -> crate::Result<Vec<u8>> {
-        unimplemented!("loading resources from HTTP URIs is not currently supported");
-    }
-
     fn load_from_json<T>(&self, bytes: &[u8]) -> crate::Result<T>
     where
         T: DeserializeOwned,
     {
-        Ok(serde_json::from_slice(bytes)?)
+        let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+        let value = serde_path_to_error::deserialize(&mut deserializer)?;
+        deserializer.end()?;
+
+        Ok(value)
     }
 
     fn load_from_yaml<T>(&self, #[allow(unused)] bytes: &[u8]) -> crate::Result<T>
@@ -114,7 +468,7 @@ impl DefinitionLoader {
     {
         #[cfg(feature = "yaml")]
         {
-            Ok(serde_yaml::from_slice(bytes)?)
+            Ok(serde_path_to_error::deserialize(serde_yaml::Deserializer::from_slice(bytes))?)
         }
 
         #[cfg(not(feature = "yaml"))]
@@ -123,3 +477,456 @@ impl DefinitionLoader {
         }
     }
 }
+
+/// Handler for [custom states](State::Custom) of a given type.
+///
+/// Handlers are registered with [`DefinitionLoader::custom_state`].
+pub trait CustomStateHandler: fmt::Debug + Send + Sync {
+    /// Validates a custom state of the handler's type.
+    ///
+    /// # Errors
+    ///
+    /// A message describing why the state is invalid.
+    fn validate(&self, state: &CustomState) -> Result<(), String>;
+}
+
+/// Warning emitted while loading a definition object.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum LoadWarning {
+    /// A property not defined in the specification was found and ignored (see [lenient mode]).
+    ///
+    /// [lenient mode]: DefinitionLoader::lenient
+    UnknownField {
+        /// JSON pointer to the ignored property (e.g. `/states/0/actions/0/foo`).
+        pointer: String,
+
+        /// Value of the ignored property.
+        value: Value,
+    },
+
+    /// A construct that is dropped or changed in version 1.0 of the DSL was found
+    /// (see [`warn_deprecated`]).
+    ///
+    /// [`warn_deprecated`]: DefinitionLoader::warn_deprecated
+    Deprecated {
+        /// JSON pointer to the deprecated construct (e.g. `/states/2/onErrors`).
+        pointer: String,
+
+        /// Message describing how the construct changes in version 1.0 of the DSL.
+        message: String,
+    },
+
+    /// A [wildcard] error handler is defined before handlers for specific errors, which would
+    /// never be used by runtimes picking the first matching handler (see
+    /// [`MisplacedWildcardErrorHandlers`]).
+    ///
+    /// [wildcard]: crate::workflow::definition::WILDCARD_ERROR
+    MisplacedWildcardErrorHandler {
+        /// JSON pointer to the wildcard error handler (e.g. `/states/0/onErrors/1`).
+        pointer: String,
+
+        /// Message listing the errors of the handlers defined after the wildcard handler.
+        message: String,
+    },
+}
+
+impl fmt::Display for LoadWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownField { pointer, .. } => write!(f, "unknown field '{}' ignored", pointer),
+            Self::Deprecated { pointer, message } => {
+                write!(f, "deprecated construct at '{}': {}", pointer, message)
+            },
+            Self::MisplacedWildcardErrorHandler { pointer, message } => {
+                write!(f, "misplaced error handler at '{}': {}", pointer, message)
+            },
+        }
+    }
+}
+
+/// Policy used to compare properties set to their default value in a
+/// [round trip](DefinitionLoader::round_trip).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DefaultsPolicy {
+    /// Properties added or removed during the round trip are not reported if their presence
+    /// does not change the definition object (i.e., if they are set to their default value).
+    Ignore,
+
+    /// All properties added or removed during the round trip are reported.
+    Strict,
+}
+
+/// Difference between a document and the same document after a
+/// [round trip](DefinitionLoader::round_trip) through this crate.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RoundTripDifference {
+    /// A property of the original document was lost.
+    Lost {
+        /// JSON pointer to the property (e.g. `/states/0/foo`).
+        pointer: String,
+
+        /// Value of the property in the original document.
+        original: Value,
+    },
+
+    /// A property not found in the original document was added.
+    Added {
+        /// JSON pointer to the property (e.g. `/states/0/usedForCompensation`).
+        pointer: String,
+
+        /// Value of the added property.
+        value: Value,
+    },
+
+    /// The value of a property was changed.
+    Changed {
+        /// JSON pointer to the property (e.g. `/retries/0/multiplier`).
+        pointer: String,
+
+        /// Value of the property in the original document.
+        original: Value,
+
+        /// Value of the property after the round trip.
+        value: Value,
+    },
+}
+
+impl RoundTripDifference {
+    /// Returns a JSON pointer to the property that differs.
+    pub fn pointer(&self) -> &str {
+        match self {
+            Self::Lost { pointer, .. }
+            | Self::Added { pointer, .. }
+            | Self::Changed { pointer, .. } => pointer,
+        }
+    }
+}
+
+impl fmt::Display for RoundTripDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lost { pointer, original } => write!(f, "'{}' lost (was {})", pointer, original),
+            Self::Added { pointer, value } => write!(f, "'{}' added ({})", pointer, value),
+            Self::Changed { pointer, original, value } => {
+                write!(f, "'{}' changed from {} to {}", pointer, original, value)
+            },
+        }
+    }
+}
+
+/// Result of [loading a catalog](DefinitionLoader::load_catalog) of workflow definitions.
+#[derive(Debug, Default)]
+pub struct CatalogReport {
+    /// Outcome of loading each file of the catalog, sorted by path
+    pub entries: Vec<CatalogEntry>,
+}
+
+impl CatalogReport {
+    /// Returns the entries of the workflow definitions that were loaded.
+    pub fn loaded(&self) -> impl Iterator<Item = &CatalogEntry> + '_ {
+        self.entries
+            .iter()
+            .filter(|entry| matches!(entry.outcome, CatalogOutcome::Loaded { .. }))
+    }
+
+    /// Returns the entries of the files declaring an unsupported version of the specification.
+    pub fn unsupported(&self) -> impl Iterator<Item = &CatalogEntry> + '_ {
+        self.entries
+            .iter()
+            .filter(|entry| matches!(entry.outcome, CatalogOutcome::Unsupported { .. }))
+    }
+
+    /// Returns the entries of the files that could not be loaded.
+    pub fn failed(&self) -> impl Iterator<Item = &CatalogEntry> + '_ {
+        self.entries
+            .iter()
+            .filter(|entry| matches!(entry.outcome, CatalogOutcome::Failed(_)))
+    }
+
+    /// Returns `true` if all files of the catalog were loaded.
+    pub fn is_complete(&self) -> bool {
+        self.loaded().count() == self.entries.len()
+    }
+}
+
+/// File of a [`CatalogReport`].
+#[derive(Debug)]
+pub struct CatalogEntry {
+    /// Path of the file
+    pub path: PathBuf,
+
+    /// Outcome of loading the file
+    pub outcome: CatalogOutcome,
+}
+
+impl CatalogEntry {
+    /// Returns the version of the specification of the loaded workflow definition, if any.
+    pub fn spec_version(&self) -> Option<SpecVersion> {
+        match &self.outcome {
+            CatalogOutcome::Loaded { definition, .. } => Some(definition.spec_version()),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome of loading a file of a catalog.
+#[derive(Debug)]
+pub enum CatalogOutcome {
+    /// Workflow definition was loaded.
+    Loaded {
+        /// The loaded workflow definition.
+        definition: Rc<VersionedWorkflowDefinition>,
+
+        /// Warnings emitted while loading (see [`DefinitionLoader::load_with_warnings`]).
+        warnings: Vec<LoadWarning>,
+    },
+
+    /// File does not declare a version of the specification, or declares a version that is
+    /// not supported (e.g. `0.7`).
+    Unsupported {
+        /// The unsupported spec version, if one was specified.
+        spec_version: Option<String>,
+    },
+
+    /// File declares a supported version of the specification but could not be loaded.
+    Failed(crate::Error),
+}
+
+/// Returns warnings for the constructs of a v0.8 workflow definition that are dropped or changed
+/// in version 1.0 of the DSL. Other definition objects produce no warning.
+fn deprecation_warnings(def: &dyn Any) -> Vec<LoadWarning> {
+    workflow_definition(def)
+        .map(|definition| {
+            definition
+                .migrate_to_v1()
+                .issues
+                .into_iter()
+                .map(|issue| LoadWarning::Deprecated {
+                    pointer: issue.pointer,
+                    message: issue.message,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns warnings for the wildcard error handlers of a workflow definition that are defined
+/// before handlers for specific errors.
+fn misplaced_wildcard_warnings(definition: &WorkflowDefinition) -> Vec<LoadWarning> {
+    MisplacedWildcardErrorHandlers
+        .check(definition)
+        .into_iter()
+        .map(|issue| LoadWarning::MisplacedWildcardErrorHandler {
+            pointer: issue.pointer,
+            message: issue.message,
+        })
+        .collect()
+}
+
+/// Returns the v0.8 workflow definition contained in a definition object, if any.
+fn workflow_definition(def: &dyn Any) -> Option<&WorkflowDefinition> {
+    match def.downcast_ref::<VersionedWorkflowDefinition>() {
+        Some(VersionedWorkflowDefinition::V0_8(definition)) => Some(definition),
+        Some(_) => None,
+        None => def.downcast_ref::<WorkflowDefinition>(),
+    }
+}
+
+/// Deserializes a definition object, removing unknown fields until deserialization succeeds.
+fn deserialize_lenient<T>(mut value: Value) -> crate::Result<(T, Vec<LoadWarning>)>
+where
+    T: DeserializeOwned,
+{
+    let mut warnings = Vec::new();
+    loop {
+        let err = match serde_path_to_error::deserialize::<_, T>(&value) {
+            Ok(def) => return Ok((def, warnings)),
+            Err(err) => err,
+        };
+
+        let Some(field) = unknown_field_name(&err.inner().to_string()) else {
+            return Err(err.into());
+        };
+        let candidates = subtree(&value, err.path())
+            .map(|(pointer, subtree)| find_field(subtree, &field, pointer))
+            .unwrap_or_default();
+
+        // The path reported by serde might stop short of the object actually containing the
+        // unknown field (for example, when deserializing internally-tagged enums), so there
+        // might be multiple candidates. We pick the first one that, once removed, allows us
+        // to make progress: either deserialization succeeds, or it fails because of another
+        // unknown field. If none is found, we also accept a candidate that fails with the same
+        // error, which can happen if the same unknown field is present multiple times.
+        let mut fallback = None;
+        let mut accepted = None;
+        for pointer in candidates {
+            let mut attempt = value.clone();
+            let Some(removed) = remove_at(&mut attempt, &pointer) else {
+                continue;
+            };
+
+            match serde_path_to_error::deserialize::<_, T>(&attempt) {
+                Ok(_) => {
+                    accepted = Some((pointer, removed, attempt));
+                    break;
+                },
+                Err(next_err) => match unknown_field_name(&next_err.inner().to_string()) {
+                    Some(next_field)
+                        if next_field != field
+                            || next_err.path().to_string() != err.path().to_string() =>
+                    {
+                        accepted = Some((pointer, removed, attempt));
+                        break;
+                    },
+                    Some(_) if fallback.is_none() => fallback = Some((pointer, removed, attempt)),
+                    _ => (),
+                },
+            }
+        }
+
+        match accepted.or(fallback) {
+            Some((pointer, removed, attempt)) => {
+                warnings.push(LoadWarning::UnknownField { pointer, value: removed });
+                value = attempt;
+            },
+            None => return Err(err.into()),
+        }
+    }
+}
+
+/// Extracts the name of the unknown field from a serde "unknown field" error message.
+fn unknown_field_name(message: &str) -> Option<String> {
+    let rest = message.strip_prefix("unknown field `")?;
+    rest.find('`').map(|end| rest[..end].to_string())
+}
+
+/// Returns the value found at the given path, along with its JSON pointer.
+fn subtree<'v>(value: &'v Value, path: &serde_path_to_error::Path) -> Option<(String, &'v Value)> {
+    let mut pointer = String::new();
+    let mut current = value;
+    for segment in path.iter() {
+        current = match segment {
+            serde_path_to_error::Segment::Seq { index } => {
+                pointer.push_str(&format!("/{}", index));
+                current.get(*index)?
+            },
+            serde_path_to_error::Segment::Map { key } => {
+                pointer.push_str(&format!("/{}", escape_pointer_token(key)));
+                current.get(key)?
+            },
+            _ => break,
+        };
+    }
+
+    Some((pointer, current))
+}
+
+/// Returns JSON pointers to all occurrences of the given field in the given value, recursively.
+fn find_field(value: &Value, field: &str, pointer: String) -> Vec<String> {
+    match value {
+        Value::Object(map) => map
+            .iter()
+            .flat_map(|(key, value)| {
+                let pointer = format!("{}/{}", pointer, escape_pointer_token(key));
+                let found = (key == field).then(|| pointer.clone());
+                found.into_iter().chain(find_field(value, field, pointer))
+            })
+            .collect(),
+        Value::Array(values) => values
+            .iter()
+            .enumerate()
+            .flat_map(|(i, value)| find_field(value, field, format!("{}/{}", pointer, i)))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Compares two values structurally, adding the differences found to `differences`.
+fn diff_values(
+    original: &Value,
+    value: &Value,
+    pointer: String,
+    differences: &mut Vec<RoundTripDifference>,
+) {
+    match (original, value) {
+        (Value::Object(original), Value::Object(value)) => {
+            for (key, original) in original {
+                let pointer = format!("{}/{}", pointer, escape_pointer_token(key));
+                match value.get(key) {
+                    Some(value) => diff_values(original, value, pointer, differences),
+                    None => differences
+                        .push(RoundTripDifference::Lost { pointer, original: original.clone() }),
+                }
+            }
+            for (key, value) in value.iter().filter(|(key, _)| !original.contains_key(*key)) {
+                let pointer = format!("{}/{}", pointer, escape_pointer_token(key));
+                differences.push(RoundTripDifference::Added { pointer, value: value.clone() });
+            }
+        },
+        (Value::Array(original), Value::Array(value)) => {
+            for (i, original) in original.iter().enumerate() {
+                let pointer = format!("{}/{}", pointer, i);
+                match value.get(i) {
+                    Some(value) => diff_values(original, value, pointer, differences),
+                    None => differences
+                        .push(RoundTripDifference::Lost { pointer, original: original.clone() }),
+                }
+            }
+            for (i, value) in value.iter().enumerate().skip(original.len()) {
+                let pointer = format!("{}/{}", pointer, i);
+                differences.push(RoundTripDifference::Added { pointer, value: value.clone() });
+            }
+        },
+        (original, value) if original != value => {
+            differences.push(RoundTripDifference::Changed {
+                pointer,
+                original: original.clone(),
+                value: value.clone(),
+            });
+        },
+        _ => {},
+    }
+}
+
+/// Returns `true` if the property lost or added during a round trip is set to its default value,
+/// i.e. if adding it to (or removing it from) the serialized definition object does not change it.
+fn is_default<T>(def: &T, serialized: &Value, difference: &RoundTripDifference) -> bool
+where
+    T: DeserializeOwned + PartialEq,
+{
+    let mut document = serialized.clone();
+    let changed = match difference {
+        RoundTripDifference::Lost { pointer, original } => {
+            insert_at(&mut document, pointer, original.clone())
+        },
+        RoundTripDifference::Added { pointer, .. } => remove_at(&mut document, pointer).is_some(),
+        RoundTripDifference::Changed { .. } => false,
+    };
+
+    changed && serde_json::from_value::<T>(document).is_ok_and(|other| other == *def)
+}
+
+/// Inserts a value in an object at the given JSON pointer. Returns `false` if the parent of the
+/// pointer is not an object.
+fn insert_at(value: &mut Value, pointer: &str, new_value: Value) -> bool {
+    let Some((parent, key)) = pointer.rsplit_once('/') else {
+        return false;
+    };
+    let key = key.replace("~1", "/").replace("~0", "~");
+    match value.pointer_mut(parent).and_then(Value::as_object_mut) {
+        Some(parent) => parent.insert(key, new_value).is_none(),
+        None => false,
+    }
+}
+
+/// Removes the value at the given JSON pointer and returns it.
+fn remove_at(value: &mut Value, pointer: &str) -> Option<Value> {
+    let (parent, key) = pointer.rsplit_once('/')?;
+    let key = key.replace("~1", "/").replace("~0", "~");
+    value.pointer_mut(parent)?.as_object_mut()?.remove(&key)
+}
+
+fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
@@ -0,0 +1,298 @@
+//! Lookup of the location of elements in source documents.
+
+use std::fmt;
+
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde_json::Value;
+
+use crate::error::SourceLocation;
+use crate::loader::{find_field, unknown_field_name};
+use crate::validation::report::pointer_from_path;
+
+// Message of the error used to stop deserialization once the element is found; the parser
+// attaches the current location to it.
+const FOUND: &str = "element found";
+
+/// Sets the source location of a conversion error that occurred at a known path, if the
+/// element found at that path can be located in the source document.
+///
+/// Parsers cannot always pinpoint such errors: for example, the content of states is buffered
+/// before being converted (to determine their type), so errors inside states are reported at
+/// the end of the state or with no location at all.
+pub(super) fn locate_conversion_error(
+    mut err: crate::Error,
+    bytes: &[u8],
+    file_ext: &str,
+) -> crate::Error {
+    let location = match &err {
+        crate::Error::JsonConversionFailed { path: Some(path), source, location: None } => {
+            locate_path(bytes, file_ext, path, &source.to_string())
+        },
+        #[cfg(feature = "yaml")]
+        crate::Error::YamlConversionFailed { path: Some(path), source, location: None } => {
+            locate_path(bytes, file_ext, path, &source.to_string())
+        },
+        _ => None,
+    };
+
+    match &mut err {
+        crate::Error::JsonConversionFailed { location: slot, .. }
+        | crate::Error::YamlConversionFailed { location: slot, .. }
+            if location.is_some() =>
+        {
+            *slot = location;
+        },
+        _ => (),
+    }
+    err
+}
+
+/// Returns the location of the element at the given deserialization path (e.g. `states[0]`).
+///
+/// If the error `message` is about an unknown field, the location of the first occurrence of
+/// that field under the path is returned instead, since serde paths can stop short of the
+/// object containing the field (for example, for internally-tagged enums).
+fn locate_path(bytes: &[u8], file_ext: &str, path: &str, message: &str) -> Option<SourceLocation> {
+    // Documents that cannot be parsed have syntax errors, which the parser locates itself.
+    let document: Value = match file_ext {
+        "json" => serde_json::from_slice(bytes).ok()?,
+        #[cfg(feature = "yaml")]
+        "yaml" | "yml" => serde_yaml::from_slice(bytes).ok()?,
+        _ => return None,
+    };
+
+    let mut pointer = pointer_from_path(path, Some(&document));
+    // YAML error messages are prefixed with the path of the error.
+    let unknown_field = message
+        .find("unknown field")
+        .and_then(|start| unknown_field_name(&message[start..]));
+    if let Some(field) = unknown_field {
+        if let Some(field_pointer) = document.pointer(&pointer).and_then(|subtree| {
+            find_field(subtree, &field, pointer.clone())
+                .into_iter()
+                .next()
+        }) {
+            pointer = field_pointer;
+        }
+    }
+
+    locate(bytes, file_ext, &pointer)
+}
+
+/// Returns the location of the element at the given JSON pointer in a source document.
+///
+/// For object properties, the location of the property's key is returned.
+fn locate(bytes: &[u8], file_ext: &str, pointer: &str) -> Option<SourceLocation> {
+    let tokens: Vec<_> = pointer
+        .split('/')
+        .skip(1)
+        .map(|token| token.replace("~1", "/").replace("~0", "~"))
+        .collect();
+    let seed = Locate { tokens: &tokens };
+
+    match file_ext {
+        "json" => {
+            let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+            match seed.deserialize(&mut deserializer) {
+                Err(err) if err.to_string().starts_with(FOUND) => {
+                    Some(json_token_start(bytes, err.line(), err.column()))
+                },
+                _ => None,
+            }
+        },
+        #[cfg(feature = "yaml")]
+        "yaml" | "yml" => match seed.deserialize(serde_yaml::Deserializer::from_slice(bytes)) {
+            Err(err) if err.to_string().contains(FOUND) => err.location().map(|location| {
+                SourceLocation { line: location.line(), column: location.column() }
+            }),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the location of the start of the JSON token ending at the given location.
+///
+/// The JSON parser reports the location of the last character it read, which is the closing
+/// quote for strings (including object keys).
+fn json_token_start(bytes: &[u8], line: usize, column: usize) -> SourceLocation {
+    let text = bytes
+        .split(|&b| b == b'\n')
+        .nth(line.saturating_sub(1))
+        .unwrap_or_default();
+    let start = match text.get(..column) {
+        Some([before @ .., b'"']) => before
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|&(i, &b)| b == b'"' && (i == 0 || before[i - 1] != b'\\'))
+            .map(|(i, _)| i + 1),
+        _ => None,
+    };
+
+    SourceLocation { line, column: start.unwrap_or(column) }
+}
+
+/// Deserializes a document until the element at the given pointer tokens is found, at which
+/// point a [`FOUND`] error is returned.
+struct Locate<'a> {
+    tokens: &'a [String],
+}
+
+impl Locate<'_> {
+    fn scalar<E>(self) -> Result<(), E>
+    where
+        E: de::Error,
+    {
+        match self.tokens {
+            [] => Err(E::custom(FOUND)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for Locate<'_> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Locate<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any value")
+    }
+
+    fn visit_bool<E: de::Error>(self, _v: bool) -> Result<Self::Value, E> {
+        self.scalar()
+    }
+
+    fn visit_i64<E: de::Error>(self, _v: i64) -> Result<Self::Value, E> {
+        self.scalar()
+    }
+
+    fn visit_u64<E: de::Error>(self, _v: u64) -> Result<Self::Value, E> {
+        self.scalar()
+    }
+
+    fn visit_f64<E: de::Error>(self, _v: f64) -> Result<Self::Value, E> {
+        self.scalar()
+    }
+
+    fn visit_str<E: de::Error>(self, _v: &str) -> Result<Self::Value, E> {
+        self.scalar()
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.scalar()
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.scalar()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let Some((token, tokens)) = self.tokens.split_first() else {
+            return Err(de::Error::custom(FOUND));
+        };
+
+        let Ok(index) = token.parse::<usize>() else {
+            return Ok(());
+        };
+        for _ in 0..index {
+            if seq.next_element::<IgnoredAny>()?.is_none() {
+                return Ok(());
+            }
+        }
+        seq.next_element_seed(Locate { tokens })?;
+        Ok(())
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let Some((token, tokens)) = self.tokens.split_first() else {
+            return Err(de::Error::custom(FOUND));
+        };
+
+        while let Some(matches) =
+            map.next_key_seed(Key { name: token, target: tokens.is_empty() })?
+        {
+            if matches {
+                return map.next_value_seed(Locate { tokens });
+            }
+            map.next_value::<IgnoredAny>()?;
+        }
+        Ok(())
+    }
+}
+
+/// Deserializes a map key, returning whether it has the given name. If the key is the `target`
+/// element, a [`FOUND`] error is returned instead.
+struct Key<'a> {
+    name: &'a str,
+    target: bool,
+}
+
+impl<'de> DeserializeSeed<'de> for Key<'_> {
+    type Value = bool;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Key<'_> {
+    type Value = bool;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map key")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        match v == self.name {
+            true if self.target => Err(E::custom(FOUND)),
+            matches => Ok(matches),
+        }
+    }
+
+    fn visit_bool<E: de::Error>(self, _v: bool) -> Result<Self::Value, E> {
+        Ok(false)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_f64<E: de::Error>(self, _v: f64) -> Result<Self::Value, E> {
+        Ok(false)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(false)
+    }
+}
@@ -0,0 +1,293 @@
+//! Model of [OpenAPI] documents, used to resolve the operations called by [REST functions].
+//!
+//! Only the parts of an OpenAPI document needed to call its operations are deserialized;
+//! everything else is ignored.
+//!
+//! [OpenAPI]: https://spec.openapis.org/oas/v3.1.0
+//! [REST functions]: crate::workflow::definition::functions::FunctionType::Rest
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use url::Url;
+
+use crate::workflow::definition::functions::{Function, FunctionType};
+
+/// OpenAPI document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+pub struct OpenApiDocument {
+    /// Servers hosting the API
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub servers: Vec<Server>,
+
+    /// Paths of the API's operations, keyed by path template (e.g. `/pets/{petId}`)
+    #[serde(default)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub paths: BTreeMap<String, PathItem>,
+
+    /// Reusable objects referenced elsewhere in the document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub components: Option<Components>,
+}
+
+impl OpenApiDocument {
+    /// Returns the operation with the given [ID](Operation::operation_id), if any.
+    pub fn operation(&self, operation_id: &str) -> Option<OperationRef<'_>> {
+        self.paths.iter().find_map(|(path, path_item)| {
+            path_item
+                .operations()
+                .find(|(_, operation)| operation.operation_id.as_deref() == Some(operation_id))
+                .map(|(method, operation)| OperationRef {
+                    document: self,
+                    method,
+                    path,
+                    path_item,
+                    operation,
+                })
+        })
+    }
+
+    /// Returns the definition of a parameter, following [references] to
+    /// [component parameters](Components::parameters).
+    ///
+    /// Returns `None` if the parameter references a parameter that is not defined in this
+    /// document's components, or that is defined in another document.
+    ///
+    /// [references]: Parameter::Reference
+    pub fn resolve_parameter<'a>(&'a self, parameter: &'a Parameter) -> Option<&'a ParameterDef> {
+        match parameter {
+            Parameter::Definition(definition) => Some(definition),
+            Parameter::Reference { reference } => {
+                let name = reference.strip_prefix(COMPONENT_PARAMETERS_PREFIX)?;
+                self.components.as_ref()?.parameters.get(name)
+            },
+        }
+    }
+}
+
+/// Returns the URI of the OpenAPI document and the operation ID of a [REST function].
+///
+/// # Errors
+///
+/// * [`InvalidFunctionOperation`]: the function is not a REST function, or its
+///                                 [operation](Function::operation) does not have the
+///                                 `<openapi_uri>#<operationId>` format
+/// * [`InvalidUrl`]: the OpenAPI document URI is invalid
+///
+/// [REST function]: FunctionType::Rest
+/// [`InvalidFunctionOperation`]: crate::Error::InvalidFunctionOperation
+/// [`InvalidUrl`]: crate::Error::InvalidUrl
+pub fn operation_location(function: &Function) -> crate::Result<(Url, &str)> {
+    let invalid = |reason| crate::Error::InvalidFunctionOperation {
+        function: function.name.clone(),
+        operation: function.operation.clone(),
+        reason,
+    };
+
+    if function.function_type != FunctionType::Rest {
+        return Err(invalid("only REST functions are supported"));
+    }
+    let (uri, operation_id) = function
+        .operation
+        .rsplit_once('#')
+        .ok_or_else(|| invalid("expected '<openapi_uri>#<operationId>'"))?;
+
+    Ok((Url::parse(uri)?, operation_id))
+}
+
+const COMPONENT_PARAMETERS_PREFIX: &str = "#/components/parameters/";
+
+/// Server hosting an API
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Server {
+    /// URL of the server, which can be relative to the OpenAPI document's location
+    pub url: String,
+}
+
+/// Reusable objects of an [`OpenApiDocument`]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Components {
+    /// Parameters that can be referenced by operations (e.g. `#/components/parameters/petId`),
+    /// keyed by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: BTreeMap<String, ParameterDef>,
+}
+
+/// Operations available on a single path
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathItem {
+    /// Parameters shared by all operations on this path
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<Parameter>,
+
+    /// `GET` operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub get: Option<Operation>,
+
+    /// `PUT` operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub put: Option<Operation>,
+
+    /// `POST` operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post: Option<Operation>,
+
+    /// `DELETE` operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delete: Option<Operation>,
+
+    /// `OPTIONS` operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<Operation>,
+
+    /// `HEAD` operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head: Option<Operation>,
+
+    /// `PATCH` operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch: Option<Operation>,
+
+    /// `TRACE` operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<Operation>,
+}
+
+impl PathItem {
+    /// Returns the operations defined on this path, along with their HTTP method.
+    pub fn operations(&self) -> impl Iterator<Item = (&'static str, &Operation)> + '_ {
+        [
+            ("GET", &self.get),
+            ("PUT", &self.put),
+            ("POST", &self.post),
+            ("DELETE", &self.delete),
+            ("OPTIONS", &self.options),
+            ("HEAD", &self.head),
+            ("PATCH", &self.patch),
+            ("TRACE", &self.trace),
+        ]
+        .into_iter()
+        .filter_map(|(method, operation)| operation.as_ref().map(|operation| (method, operation)))
+    }
+}
+
+/// API operation
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Operation {
+    /// Unique identifier of the operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+
+    /// Operation parameters. Override [path parameters](PathItem::parameters) with the same
+    /// name and location.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<Parameter>,
+
+    /// Definition of the operation's request body, if it accepts one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<Value>,
+}
+
+/// Operation parameter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Parameter {
+    /// Reference to a parameter defined elsewhere in the document
+    Reference {
+        /// JSON reference to the parameter (e.g. `#/components/parameters/petId`)
+        #[serde(rename = "$ref")]
+        reference: String,
+    },
+
+    /// Parameter definition
+    Definition(ParameterDef),
+}
+
+/// Operation parameter definition
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParameterDef {
+    /// Name of the parameter
+    pub name: String,
+
+    /// Location of the parameter
+    #[serde(rename = "in")]
+    pub location: ParameterLocation,
+
+    /// Whether the parameter is mandatory. Path parameters are always required.
+    #[serde(default)]
+    pub required: bool,
+
+    /// JSON schema of the parameter's value, if specified
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Value>,
+}
+
+/// Location of an operation [parameter](ParameterDef)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParameterLocation {
+    /// Parameter substituted in the path template
+    Path,
+
+    /// Query string parameter
+    Query,
+
+    /// HTTP header
+    Header,
+
+    /// Cookie value
+    Cookie,
+}
+
+/// Operation found in an [`OpenApiDocument`].
+///
+/// See [`OpenApiDocument::operation`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct OperationRef<'a> {
+    /// Document containing the operation
+    pub document: &'a OpenApiDocument,
+
+    /// HTTP method of the operation (e.g. `GET`)
+    pub method: &'static str,
+
+    /// Path template of the operation (e.g. `/pets/{petId}`)
+    pub path: &'a str,
+
+    /// Path item containing the operation
+    pub path_item: &'a PathItem,
+
+    /// Operation definition
+    pub operation: &'a Operation,
+}
+
+impl<'a> OperationRef<'a> {
+    /// Returns the definitions of the operation's parameters, including parameters shared
+    /// by all operations on its path.
+    ///
+    /// [References](Parameter::Reference) are [resolved](OpenApiDocument::resolve_parameter);
+    /// references that cannot be resolved are not returned.
+    pub fn parameters(&self) -> Vec<&'a ParameterDef> {
+        let operation_parameters = self.definitions(&self.operation.parameters);
+        self.definitions(&self.path_item.parameters)
+            .into_iter()
+            .filter(|shared| {
+                !operation_parameters.iter().any(|parameter| {
+                    parameter.name == shared.name && parameter.location == shared.location
+                })
+            })
+            .chain(operation_parameters.iter().copied())
+            .collect()
+    }
+
+    fn definitions(&self, parameters: &'a [Parameter]) -> Vec<&'a ParameterDef> {
+        parameters
+            .iter()
+            .filter_map(|parameter| self.document.resolve_parameter(parameter))
+            .collect()
+    }
+}
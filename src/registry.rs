@@ -0,0 +1,543 @@
+//! Registry of workflow definitions, keyed by workflow ID and version.
+
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::cache::DefinitionCache;
+use crate::detail::IntoOpt;
+use crate::workflow::definition::{End, State, WorkflowDefinition};
+
+/// Key identifying a workflow definition in a [`WorkflowRegistry`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct WorkflowKey {
+    /// Workflow [identifier](crate::workflow::definition::Identifier::id)
+    pub id: String,
+
+    /// Workflow version, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl WorkflowKey {
+    /// Returns the key of the given workflow definition.
+    ///
+    /// # Errors
+    ///
+    /// * [`MissingIdentifier`]: the workflow definition has no identifier
+    ///
+    /// [`MissingIdentifier`]: crate::Error::MissingIdentifier
+    pub fn for_definition(definition: &WorkflowDefinition) -> crate::Result<Self> {
+        Ok(Self { id: definition.identifier.id()?.into(), version: definition.version.clone() })
+    }
+
+    // Returns `true` if a reference to the given workflow ID and version targets this key.
+    // References that do not specify a version target all versions of the workflow.
+    pub(crate) fn matches(&self, id: &str, version: Option<&str>) -> bool {
+        self.id == id && (version.is_none() || self.version.as_deref() == version)
+    }
+}
+
+impl fmt::Display for WorkflowKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.version {
+            Some(version) => write!(f, "{}@{}", self.id, version),
+            None => write!(f, "{}", self.id),
+        }
+    }
+}
+
+/// Workflow definition registered in a [`WorkflowRegistry`].
+#[derive(Debug, Clone)]
+pub struct RegisteredWorkflow {
+    definition: Rc<WorkflowDefinition>,
+    uri: Option<Url>,
+    content_hash: u64,
+}
+
+impl RegisteredWorkflow {
+    /// Returns the workflow definition.
+    pub fn definition(&self) -> &Rc<WorkflowDefinition> {
+        &self.definition
+    }
+
+    /// Returns the URI the workflow definition was loaded from, if known.
+    pub fn uri(&self) -> Option<&Url> {
+        self.uri.as_ref()
+    }
+
+    /// Returns a hash of the workflow definition's content.
+    ///
+    /// This is the workflow definition's [fingerprint](WorkflowDefinition::fingerprint): two
+    /// workflow definitions with the same content have the same hash.
+    pub fn content_hash(&self) -> u64 {
+        self.content_hash
+    }
+}
+
+/// Serializable snapshot of the workflow definitions registered in a [`WorkflowRegistry`].
+///
+/// See [`WorkflowRegistry::snapshot`] and [`WorkflowRegistry::restore`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegistrySnapshot {
+    /// Registered workflow definitions, ordered by ID and version
+    pub workflows: Vec<WorkflowSnapshot>,
+}
+
+/// Workflow definition registration stored in a [`RegistrySnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowSnapshot {
+    /// ID and version of the workflow definition
+    #[serde(flatten)]
+    pub key: WorkflowKey,
+
+    /// URI the workflow definition was loaded from, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<Url>,
+
+    /// [Hash](RegisteredWorkflow::content_hash) of the workflow definition's content
+    pub content_hash: u64,
+}
+
+/// Graph of the dependencies between workflow definitions registered in a [`WorkflowRegistry`].
+///
+/// A workflow depends on the workflows it invokes as [sub-workflows] or [continues as].
+/// See [`WorkflowRegistry::dependency_graph`].
+///
+/// [sub-workflows]: crate::workflow::definition::Action::sub_flow_ref
+/// [continues as]: crate::workflow::definition::End::continue_as
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DependencyGraph {
+    dependencies: BTreeMap<WorkflowKey, BTreeSet<WorkflowKey>>,
+}
+
+impl DependencyGraph {
+    /// Returns the keys of the workflows the given workflow directly depends on.
+    pub fn dependencies(&self, key: &WorkflowKey) -> Vec<&WorkflowKey> {
+        self.dependencies
+            .get(key)
+            .map(|dependencies| dependencies.iter().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the shortest dependency cycle going through the given workflow, if any.
+    ///
+    /// The cycle starts and ends with the given workflow's key; for example, a workflow
+    /// that invokes itself as a sub-workflow has the cycle `[key, key]`.
+    pub fn cycle(&self, key: &WorkflowKey) -> Option<Vec<&WorkflowKey>> {
+        let (start, _) = self.dependencies.get_key_value(key)?;
+
+        let mut parents = BTreeMap::new();
+        let mut pending = VecDeque::from([start]);
+        while let Some(current) = pending.pop_front() {
+            for dependency in self.dependencies(current) {
+                if dependency == start {
+                    let mut cycle = vec![dependency, current];
+                    let mut key = current;
+                    while key != start {
+                        key = parents[key];
+                        cycle.push(key);
+                    }
+                    cycle.reverse();
+                    return Some(cycle);
+                }
+                if let Entry::Vacant(entry) = parents.entry(dependency) {
+                    entry.insert(current);
+                    pending.push_back(dependency);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Registry of workflow definitions, keyed by [workflow ID and version](WorkflowKey).
+///
+/// Registering the same workflow definition multiple times is allowed, but registering
+/// two definitions with the same ID and version but different content is reported as an error
+/// instead of letting one definition silently replace the other.
+///
+/// # Thread-safety
+///
+/// Like [`DefinitionCache`], **this class is not thread-safe**, since definitions are stored in [`Rc`]s.
+#[derive(Debug, Default)]
+pub struct WorkflowRegistry {
+    workflows: BTreeMap<WorkflowKey, RegisteredWorkflow>,
+}
+
+impl WorkflowRegistry {
+    /// Creates a new empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a workflow definition.
+    ///
+    /// If a workflow definition with the same content is already registered with the same
+    /// ID and version, the existing registration is returned.
+    ///
+    /// # Errors
+    ///
+    /// * [`MissingIdentifier`]: the workflow definition has no identifier
+    /// * [`DuplicateWorkflowDefinition`]: a different workflow definition is already registered
+    ///                                    with the same ID and version
+    /// * [`JsonConversionFailed`]: failed to compute the hash of the workflow definition's content
+    ///
+    /// [`MissingIdentifier`]: crate::Error::MissingIdentifier
+    /// [`DuplicateWorkflowDefinition`]: crate::Error::DuplicateWorkflowDefinition
+    /// [`JsonConversionFailed`]: crate::Error::JsonConversionFailed
+    pub fn register<D>(&mut self, definition: D) -> crate::Result<&RegisteredWorkflow>
+    where
+        D: Into<Rc<WorkflowDefinition>>,
+    {
+        self.insert(definition.into(), None)
+    }
+
+    /// Loads a workflow definition through the given [`DefinitionCache`] and registers it.
+    ///
+    /// The workflow definition's URI is stored along with the registration and is reported
+    /// in case of conflict.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by [`DefinitionCache::get_or_insert`] or [`register`](Self::register).
+    pub fn register_uri<U>(
+        &mut self,
+        uri: U,
+        cache: &mut DefinitionCache,
+    ) -> crate::Result<&RegisteredWorkflow>
+    where
+        U: TryInto<Url>,
+        <U as TryInto<Url>>::Error: IntoOpt<crate::Error>,
+    {
+        let uri = uri.try_into().map_err(|err| {
+            err.into_opt()
+                .expect("if try_info fails, an error should be returned")
+        })?;
+
+        let definition = cache.get_or_insert::<WorkflowDefinition, _>(uri.clone())?;
+        self.insert(definition, Some(uri))
+    }
+
+    /// Restores a registry from a [snapshot](Self::snapshot).
+    ///
+    /// Every workflow definition in the snapshot is loaded again from its URI through the given
+    /// [`DefinitionCache`], and its external references are [resolved] to make sure they still
+    /// exist. The registry is only returned if all workflow definitions could be restored
+    /// with the same content as when the snapshot was taken.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by [`DefinitionCache::get_or_insert`] or
+    /// [`WorkflowDefinition::resolve_external_refs`], in addition to:
+    ///
+    /// * [`RegistryRestoreFailed`]: a workflow definition in the snapshot has no URI, or its URI
+    ///                              now refers to a different workflow definition
+    /// * [`JsonConversionFailed`]: failed to compute the hash of a workflow definition's content
+    ///
+    /// [resolved]: WorkflowDefinition::resolve_external_refs
+    /// [`RegistryRestoreFailed`]: crate::Error::RegistryRestoreFailed
+    /// [`JsonConversionFailed`]: crate::Error::JsonConversionFailed
+    pub fn restore(
+        snapshot: &RegistrySnapshot,
+        cache: &mut DefinitionCache,
+    ) -> crate::Result<Self> {
+        let mut registry = Self::new();
+        for workflow in &snapshot.workflows {
+            let restore_failed = |reason| crate::Error::RegistryRestoreFailed {
+                key: workflow.key.clone(),
+                uri: workflow.uri.as_ref().map(Url::to_string),
+                reason,
+            };
+
+            let uri = workflow.uri.as_ref().ok_or_else(|| {
+                restore_failed("workflow definition was not registered from a URI")
+            })?;
+            let definition = cache.get_or_insert::<WorkflowDefinition, _>(uri.clone())?;
+            definition.resolve_external_refs(cache)?;
+
+            if WorkflowKey::for_definition(&definition)? != workflow.key {
+                return Err(restore_failed("URI now refers to a different workflow definition"));
+            }
+            if definition.fingerprint()? != workflow.content_hash {
+                return Err(restore_failed("workflow definition content has changed"));
+            }
+
+            registry.insert(definition, Some(uri.clone()))?;
+        }
+
+        Ok(registry)
+    }
+
+    /// Returns a snapshot of the registered workflow definitions.
+    ///
+    /// The snapshot can be serialized and later passed to [`restore`](Self::restore), for example
+    /// to restore the registry when a service restarts.
+    pub fn snapshot(&self) -> RegistrySnapshot {
+        let workflows = self
+            .workflows
+            .iter()
+            .map(|(key, workflow)| WorkflowSnapshot {
+                key: key.clone(),
+                uri: workflow.uri.clone(),
+                content_hash: workflow.content_hash,
+            })
+            .collect();
+
+        RegistrySnapshot { workflows }
+    }
+
+    /// Returns the workflow definition registered with the given ID and version, if any.
+    pub fn get(&self, id: &str, version: Option<&str>) -> Option<&RegisteredWorkflow> {
+        self.workflows
+            .get(&WorkflowKey { id: id.into(), version: version.map(Into::into) })
+    }
+
+    /// Returns the registered workflow definition targeted by a reference found in the given
+    /// workflow definition (e.g. a [sub-workflow] or [continue as] reference).
+    ///
+    /// References that specify a version target that version only. References that do not
+    /// specify a version target the workflow registered without a version, or else its only
+    /// registered version.
+    ///
+    /// # Errors
+    ///
+    /// * [`MissingWorkflowDependency`]: no registered workflow matches the reference
+    /// * [`AmbiguousWorkflowReference`]: the reference does not specify a version and multiple
+    ///                                   versions of the workflow are registered
+    ///
+    /// [sub-workflow]: crate::workflow::definition::Action::sub_flow_ref
+    /// [continue as]: crate::workflow::definition::End::continue_as
+    /// [`MissingWorkflowDependency`]: crate::Error::MissingWorkflowDependency
+    /// [`AmbiguousWorkflowReference`]: crate::Error::AmbiguousWorkflowReference
+    pub fn resolve_reference(
+        &self,
+        definition: &WorkflowDefinition,
+        id: &str,
+        version: Option<&str>,
+    ) -> crate::Result<&RegisteredWorkflow> {
+        if let Some(workflow) = self.get(id, version) {
+            return Ok(workflow);
+        }
+
+        match self.resolve(id, version).as_slice() {
+            [] => Err(missing_dependency(definition, id, version)),
+            [key] => Ok(&self.workflows[*key]),
+            keys => Err(crate::Error::AmbiguousWorkflowReference {
+                id: id.into(),
+                versions: keys.iter().filter_map(|key| key.version.clone()).collect(),
+            }),
+        }
+    }
+
+    /// Returns all registered workflow definitions, ordered by ID and version.
+    pub fn iter(&self) -> impl Iterator<Item = (&WorkflowKey, &RegisteredWorkflow)> + '_ {
+        self.workflows.iter()
+    }
+
+    /// Returns the number of registered workflow definitions.
+    pub fn len(&self) -> usize {
+        self.workflows.len()
+    }
+
+    /// Returns `true` if no workflow definition is registered.
+    pub fn is_empty(&self) -> bool {
+        self.workflows.is_empty()
+    }
+
+    /// Returns the keys of registered workflow definitions that can reach themselves.
+    ///
+    /// A workflow definition can reach itself if it invokes itself as a [sub-workflow] or
+    /// [continues as] itself, either directly or through other registered workflow definitions.
+    /// Executing such a workflow could recurse indefinitely, unless the recursion is bounded
+    /// by the workflow data; at runtime, continuations and sub-workflow invocations are also
+    /// bounded by a [maximum depth](crate::workflow::runtime::DEFAULT_MAX_DEPTH). References that do not specify a version are assumed to possibly
+    /// target any registered version of the workflow; references to workflows that are not
+    /// registered are ignored.
+    ///
+    /// [sub-workflow]: crate::workflow::definition::Action::sub_flow_ref
+    /// [continues as]: crate::workflow::definition::End::continue_as
+    pub fn recursive_workflows(&self) -> Vec<&WorkflowKey> {
+        self.workflows
+            .keys()
+            .filter(|key| self.can_reach(key, key))
+            .collect()
+    }
+
+    /// Returns the keys of all registered workflow definitions the given workflow definition
+    /// depends on.
+    ///
+    /// Dependencies include workflows invoked as [sub-workflows] or [continued as], along with
+    /// their own dependencies, transitively. References that do not specify a version depend
+    /// on all registered versions of the workflow. This can be used to make sure all
+    /// sub-workflows are registered before a workflow is executed.
+    ///
+    /// # Errors
+    ///
+    /// * [`MissingWorkflowDependency`]: a workflow referenced by the given workflow definition,
+    ///                                  or by one of its dependencies, is not registered
+    ///
+    /// [sub-workflows]: crate::workflow::definition::Action::sub_flow_ref
+    /// [continued as]: crate::workflow::definition::End::continue_as
+    /// [`MissingWorkflowDependency`]: crate::Error::MissingWorkflowDependency
+    pub fn dependencies(
+        &self,
+        definition: &WorkflowDefinition,
+    ) -> crate::Result<BTreeSet<&WorkflowKey>> {
+        let mut dependencies = BTreeSet::new();
+        let mut pending = vec![definition];
+        while let Some(definition) = pending.pop() {
+            for (id, version) in references(definition) {
+                let resolved = self.resolve(id, version);
+                if resolved.is_empty() {
+                    return Err(missing_dependency(definition, id, version));
+                }
+
+                for key in resolved {
+                    if dependencies.insert(key) {
+                        pending.push(&self.workflows[key].definition);
+                    }
+                }
+            }
+        }
+
+        Ok(dependencies)
+    }
+
+    /// Validates that all workflows referenced by registered workflow definitions are registered.
+    ///
+    /// This checks that every workflow invoked as a [sub-workflow] or [continued as] by
+    /// a registered workflow definition can be found in the registry, so that missing
+    /// workflows can be detected once all workflow definitions are registered instead of
+    /// failing at runtime. References that do not specify a version are valid if any version
+    /// of the workflow is registered.
+    ///
+    /// # Errors
+    ///
+    /// * [`MissingWorkflowDependency`]: a registered workflow definition references a workflow
+    ///                                  that is not registered
+    ///
+    /// [sub-workflow]: crate::workflow::definition::Action::sub_flow_ref
+    /// [continued as]: crate::workflow::definition::End::continue_as
+    /// [`MissingWorkflowDependency`]: crate::Error::MissingWorkflowDependency
+    pub fn validate_references(&self) -> crate::Result<()> {
+        for workflow in self.workflows.values() {
+            let definition = &workflow.definition;
+            if let Some((id, version)) =
+                references(definition).find(|(id, version)| self.resolve(id, *version).is_empty())
+            {
+                return Err(missing_dependency(definition, id, version));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the graph of dependencies between the registered workflow definitions.
+    ///
+    /// Like in [`recursive_workflows`](Self::recursive_workflows), references that do not specify
+    /// a version depend on all registered versions of the workflow, and references to workflows
+    /// that are not registered are ignored.
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        let dependencies = self
+            .workflows
+            .keys()
+            .map(|key| (key.clone(), self.referenced_keys(key).into_iter().cloned().collect()))
+            .collect();
+
+        DependencyGraph { dependencies }
+    }
+
+    fn can_reach(&self, from: &WorkflowKey, to: &WorkflowKey) -> bool {
+        let mut visited = BTreeSet::new();
+        let mut pending = self.referenced_keys(from);
+        while let Some(key) = pending.pop() {
+            if key == to {
+                return true;
+            }
+            if visited.insert(key) {
+                pending.extend(self.referenced_keys(key));
+            }
+        }
+
+        false
+    }
+
+    // Returns the keys of the registered workflow definitions referenced by the given one.
+    fn referenced_keys(&self, key: &WorkflowKey) -> Vec<&WorkflowKey> {
+        let Some(workflow) = self.workflows.get(key) else {
+            return Vec::new();
+        };
+
+        references(&workflow.definition)
+            .flat_map(|(id, version)| self.resolve(id, version))
+            .collect()
+    }
+
+    // Returns the keys of the registered workflow definitions matching a reference.
+    fn resolve(&self, id: &str, version: Option<&str>) -> Vec<&WorkflowKey> {
+        self.workflows
+            .keys()
+            .filter(|key| key.matches(id, version))
+            .collect()
+    }
+
+    fn insert(
+        &mut self,
+        definition: Rc<WorkflowDefinition>,
+        uri: Option<Url>,
+    ) -> crate::Result<&RegisteredWorkflow> {
+        let key = WorkflowKey::for_definition(&definition)?;
+        let content_hash = definition.fingerprint()?;
+
+        match self.workflows.entry(key) {
+            Entry::Occupied(entry) if entry.get().content_hash == content_hash => {
+                Ok(entry.into_mut())
+            },
+            Entry::Occupied(entry) => Err(crate::Error::DuplicateWorkflowDefinition {
+                key: entry.key().clone(),
+                existing_uri: entry.get().uri.as_ref().map(Url::to_string),
+                new_uri: uri.map(String::from),
+            }),
+            Entry::Vacant(entry) => {
+                Ok(entry.insert(RegisteredWorkflow { definition, uri, content_hash }))
+            },
+        }
+    }
+}
+
+// Returns the IDs and versions of the workflows referenced by a workflow definition,
+// either as sub-workflows or through `continueAs`.
+fn references(definition: &WorkflowDefinition) -> impl Iterator<Item = (&str, Option<&str>)> {
+    let sub_flows = definition
+        .states
+        .iter()
+        .flat_map(State::actions)
+        .filter_map(|action| action.sub_flow_ref.as_ref())
+        .map(|sub_flow_ref| (sub_flow_ref.workflow_id(), sub_flow_ref.version()));
+    let continue_as = definition
+        .states
+        .iter()
+        .flat_map(State::ends)
+        .filter_map(End::continue_as)
+        .map(|continue_as| (continue_as.workflow_id(), continue_as.version()));
+
+    sub_flows.chain(continue_as)
+}
+
+fn missing_dependency(
+    definition: &WorkflowDefinition,
+    id: &str,
+    version: Option<&str>,
+) -> crate::Error {
+    crate::Error::MissingWorkflowDependency {
+        dependency: WorkflowKey { id: id.into(), version: version.map(Into::into) },
+        referenced_by: WorkflowKey::for_definition(definition).ok(),
+    }
+}
@@ -1,5 +1,11 @@
 //! Types and traits pertaining to workflow definition validation.
 
+pub mod incremental;
+pub mod input;
+pub mod lint;
+pub mod report;
+pub mod states;
+
 use crate::detail::GardeValidate;
 
 /// Trait used for workflow definition validation.
@@ -0,0 +1,192 @@
+//! Incremental validation of workflow definitions.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::validation::lint::Linter;
+use crate::validation::report::{ValidationFinding, ValidationReport};
+use crate::workflow::definition::{State, WorkflowDefinition};
+
+/// Validator that only re-validates the parts of a workflow definition that changed since
+/// the last validation.
+///
+/// This is meant to be used by interactive tooling (editors, language servers, etc.) that need
+/// to validate the same workflow definition over and over as it is modified.
+///
+/// * [States] are validated individually; a state is only re-validated if its content changed.
+/// * Top-level workflow properties are only re-validated if any of them changed, or if states
+///   were added, removed or renamed.
+/// * Lint rules (which usually need to look at the whole workflow) are always run.
+///
+/// Validating a workflow definition always returns the complete [`ValidationReport`]: findings
+/// for parts that did not change are returned from cache.
+///
+/// Definition validation errors are only reported if the `validate` feature is enabled.
+///
+/// [States]: WorkflowDefinition::states
+#[derive(Debug)]
+pub struct IncrementalValidator {
+    linter: Linter,
+    definition_fingerprint: Option<Value>,
+    definition_findings: Vec<ValidationFinding>,
+    states: HashMap<String, CachedState>,
+    revalidated_states: Vec<String>,
+}
+
+#[derive(Debug)]
+struct CachedState {
+    fingerprint: Value,
+    findings: Vec<ValidationFinding>,
+}
+
+impl IncrementalValidator {
+    /// Creates a new incremental validator that will use the given [`Linter`].
+    pub fn new(linter: Linter) -> Self {
+        Self {
+            linter,
+            definition_fingerprint: None,
+            definition_findings: Vec::new(),
+            states: HashMap::new(),
+            revalidated_states: Vec::new(),
+        }
+    }
+
+    /// Validates the given workflow definition, re-validating only the parts that changed
+    /// since the previous call.
+    ///
+    /// # Errors
+    ///
+    /// * [`JsonConversionFailed`]: failed to compute the fingerprint of the workflow definition
+    ///   or to extract its top-level properties
+    ///
+    /// [`JsonConversionFailed`]: crate::Error::JsonConversionFailed
+    pub fn validate(&mut self, definition: &WorkflowDefinition) -> crate::Result<ValidationReport> {
+        let mut definition_fingerprint = serde_json::to_value(definition)?;
+        // Top-level findings can depend on the states (e.g. there must be at least one), so the
+        // top-level fingerprint keeps the state names; the states themselves are fingerprinted
+        // separately.
+        let state_fingerprints = match definition_fingerprint
+            .as_object_mut()
+            .and_then(|fields| fields.insert("states".into(), state_names(definition)))
+        {
+            Some(Value::Array(states)) => states,
+            _ => Vec::new(),
+        };
+
+        let mut report = ValidationReport::default();
+
+        if self.definition_fingerprint.as_ref() != Some(&definition_fingerprint) {
+            self.definition_findings = validate_top_level(definition, &definition_fingerprint)?;
+            self.definition_fingerprint = Some(definition_fingerprint);
+        }
+        report
+            .findings
+            .extend(self.definition_findings.iter().cloned());
+
+        self.revalidated_states.clear();
+        let mut states = HashMap::with_capacity(definition.states.len());
+        for (i, (state, fingerprint)) in
+            definition.states.iter().zip(state_fingerprints).enumerate()
+        {
+            let cached = match self.states.remove(state.name()) {
+                Some(cached) if cached.fingerprint == fingerprint => cached,
+                _ => {
+                    self.revalidated_states.push(state.name().into());
+                    let findings = validate_state(state, &fingerprint);
+                    CachedState { fingerprint, findings }
+                },
+            };
+
+            report
+                .findings
+                .extend(cached.findings.iter().map(|finding| ValidationFinding {
+                    pointer: format!("/states/{}{}", i, finding.pointer),
+                    ..finding.clone()
+                }));
+            states.insert(state.name().into(), cached);
+        }
+        self.states = states;
+
+        report.extend(self.linter.lint(definition).into());
+        Ok(report)
+    }
+
+    /// Returns the names of the states that were re-validated during the last call to [`validate`].
+    ///
+    /// [`validate`]: Self::validate
+    pub fn revalidated_states(&self) -> &[String] {
+        &self.revalidated_states
+    }
+}
+
+impl Default for IncrementalValidator {
+    fn default() -> Self {
+        Self::new(Linter::default())
+    }
+}
+
+fn state_names(definition: &WorkflowDefinition) -> Value {
+    definition
+        .states
+        .iter()
+        .map(|state| Value::String(state.name().into()))
+        .collect()
+}
+
+/// Validates the top-level properties of the given workflow definition, without validating
+/// its states.
+///
+/// `fingerprint` is the top-level fingerprint of the definition, i.e. its JSON representation
+/// with `states` replaced by the state names.
+fn validate_top_level(
+    #[allow(unused)] definition: &WorkflowDefinition,
+    #[allow(unused)] fingerprint: &Value,
+) -> crate::Result<Vec<ValidationFinding>> {
+    #[cfg(feature = "validate")]
+    {
+        use garde::Validate;
+
+        let mut document = fingerprint.clone();
+        document["states"] = Value::Array(Vec::new());
+        let top_level: WorkflowDefinition = serde_json::from_value(document.clone())?;
+
+        // Since the states were stripped, findings about the `states` property itself are
+        // only relevant if the actual definition doesn't have any.
+        Ok(match top_level.validate(&()) {
+            Ok(()) => Vec::new(),
+            Err(report) => ValidationReport::from_garde_report(&report, Some(&document))
+                .findings
+                .into_iter()
+                .filter(|finding| {
+                    !finding.pointer.starts_with("/states") || definition.states.is_empty()
+                })
+                .collect(),
+        })
+    }
+
+    #[cfg(not(feature = "validate"))]
+    {
+        Ok(Vec::new())
+    }
+}
+
+fn validate_state(
+    #[allow(unused)] state: &State,
+    #[allow(unused)] document: &Value,
+) -> Vec<ValidationFinding> {
+    #[cfg(feature = "validate")]
+    {
+        use garde::Validate;
+
+        match state.validate(&()) {
+            Ok(()) => Vec::new(),
+            Err(report) => ValidationReport::from_garde_report(&report, Some(document)).findings,
+        }
+    }
+
+    #[cfg(not(feature = "validate"))]
+    {
+        Vec::new()
+    }
+}
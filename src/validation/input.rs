@@ -0,0 +1,242 @@
+//! Validation of workflow data input against a workflow's [data input schema].
+//!
+//! Requires the `json-schema` feature (enabled by default). Generating [example input]
+//! does not.
+//!
+//! [example input]: example_input
+//! [data input schema]: WorkflowDefinition::data_input_schema
+
+mod example;
+
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::cache::DefinitionCache;
+use crate::validation::input::example::Generator;
+use crate::workflow::definition::WorkflowDefinition;
+
+/// A JSON Schema document.
+///
+/// Used to load JSON Schemas referred to by workflow definitions through a [`DefinitionCache`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[serde(transparent)]
+pub struct JsonSchema(#[cfg_attr(feature = "validate", garde(skip))] pub Value);
+
+impl JsonSchema {
+    /// Returns an example of a value conforming to this schema.
+    ///
+    /// The schema's own `examples`, `default`, `const` or `enum` values are used when available.
+    /// Otherwise, a skeleton value is built from the schema's type: objects include all their
+    /// properties, arrays contain as few items as allowed (but at least one), strings and numbers
+    /// satisfy length and range constraints, etc. Only local `$ref`s (e.g. `#/definitions/Pet`)
+    /// are followed.
+    ///
+    /// Constraints such as `pattern`, `multipleOf`, `uniqueItems` or `not` are ignored, so the
+    /// example is not guaranteed to be valid for every schema.
+    pub fn example(&self) -> Value {
+        Generator::example(&self.0).generate()
+    }
+
+    /// Returns a random value conforming to this schema.
+    ///
+    /// Values are picked at random within the schema's constraints; optional object properties
+    /// are included or not at random. The same seed always returns the same value. Like for
+    /// [`example`](Self::example), some constraints are ignored.
+    pub fn random_instance(&self, seed: u64) -> Value {
+        Generator::random(&self.0, seed).generate()
+    }
+}
+
+/// Validator for workflow data input.
+///
+/// Validates workflow data input against a JSON Schema. Validation errors are reported according
+/// to the [`fail_on_validation_errors`] setting of the workflow's [data input schema].
+///
+/// [`fail_on_validation_errors`]: crate::workflow::definition::DataInputSchema::fail_on_validation_errors
+/// [data input schema]: WorkflowDefinition::data_input_schema
+#[derive(Debug)]
+pub struct InputValidator {
+    #[cfg(feature = "json-schema")]
+    validator: jsonschema::Validator,
+    fail_on_validation_errors: bool,
+}
+
+impl InputValidator {
+    #[cfg_attr(
+        feature = "json-schema",
+        doc = r"
+            Creates a validator using the given JSON Schema.
+
+            # Errors
+
+            * [`InvalidJsonSchema`](crate::Error::InvalidJsonSchema): `schema` is not a valid JSON Schema
+        "
+    )]
+    #[cfg_attr(
+        not(feature = "json-schema"),
+        doc = r"
+            Creates a validator using the given JSON Schema.
+
+            Always returns [`FeatureDisabled`] because the `json-schema` feature is disabled.
+
+            [`FeatureDisabled`]: crate::Error::FeatureDisabled
+        "
+    )]
+    pub fn new(
+        #[allow(unused)] schema: &JsonSchema,
+        #[allow(unused)] fail_on_validation_errors: bool,
+    ) -> crate::Result<Self> {
+        #[cfg(feature = "json-schema")]
+        {
+            let validator = jsonschema::validator_for(&schema.0)
+                .map_err(|err| crate::Error::InvalidJsonSchema { message: err.to_string() })?;
+
+            Ok(Self { validator, fail_on_validation_errors })
+        }
+
+        #[cfg(not(feature = "json-schema"))]
+        {
+            Err(crate::Error::FeatureDisabled { required_feature: "json-schema" })
+        }
+    }
+
+    /// Creates a validator for the data input of the given workflow definition.
+    ///
+    /// The JSON Schema referred to by the workflow's [data input schema] is fetched through
+    /// the given [`DefinitionCache`]. If the workflow does not have a data input schema,
+    /// `Ok(None)` is returned.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by [`DefinitionCache::get_or_insert`] or [`InputValidator::new`].
+    ///
+    /// [data input schema]: WorkflowDefinition::data_input_schema
+    pub fn for_definition(
+        definition: &WorkflowDefinition,
+        cache: &mut DefinitionCache,
+    ) -> crate::Result<Option<Self>> {
+        definition
+            .data_input_schema
+            .as_ref()
+            .map(|data_input_schema| {
+                let schema = cache.get_or_insert::<JsonSchema, _>(data_input_schema.schema())?;
+                Self::new(&schema, data_input_schema.fail_on_validation_errors())
+            })
+            .transpose()
+    }
+
+    /// Returns whether workflow execution should fail if there are validation errors.
+    pub fn fail_on_validation_errors(&self) -> bool {
+        self.fail_on_validation_errors
+    }
+
+    /// Validates workflow data input.
+    ///
+    /// If validation errors are found and [`fail_on_validation_errors`] is `true`, an error is
+    /// returned. Otherwise, the validation errors found (if any) are returned, so that they can
+    /// be reported as warnings.
+    ///
+    /// # Errors
+    ///
+    /// * [`InputValidationFailed`]: `input` does not conform to the JSON Schema and
+    ///   [`fail_on_validation_errors`] is `true`
+    ///
+    /// [`fail_on_validation_errors`]: Self::fail_on_validation_errors
+    /// [`InputValidationFailed`]: crate::Error::InputValidationFailed
+    pub fn validate(&self, input: &Value) -> crate::Result<Vec<InputValidationError>> {
+        let errors = self.validation_errors(input);
+
+        if self.fail_on_validation_errors && !errors.is_empty() {
+            Err(crate::Error::InputValidationFailed { errors })
+        } else {
+            Ok(errors)
+        }
+    }
+
+    #[cfg(feature = "json-schema")]
+    fn validation_errors(&self, input: &Value) -> Vec<InputValidationError> {
+        self.validator
+            .iter_errors(input)
+            .map(|err| InputValidationError {
+                instance_path: err.instance_path.to_string(),
+                message: err.to_string(),
+            })
+            .collect()
+    }
+
+    #[cfg(not(feature = "json-schema"))]
+    fn validation_errors(&self, _input: &Value) -> Vec<InputValidationError> {
+        Vec::new()
+    }
+}
+
+/// Validates workflow data input against a workflow's [data input schema].
+///
+/// Shortcut for [`InputValidator::for_definition`] followed by [`InputValidator::validate`].
+/// If the workflow does not have a data input schema, input is considered valid.
+///
+/// # Errors
+///
+/// Any error returned by [`InputValidator::for_definition`] or [`InputValidator::validate`].
+///
+/// [data input schema]: WorkflowDefinition::data_input_schema
+pub fn validate_input(
+    definition: &WorkflowDefinition,
+    input: &Value,
+    cache: &mut DefinitionCache,
+) -> crate::Result<Vec<InputValidationError>> {
+    match InputValidator::for_definition(definition, cache)? {
+        Some(validator) => validator.validate(input),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Returns an [example](JsonSchema::example) of data input for a workflow, generated from its
+/// [data input schema].
+///
+/// The JSON Schema referred to by the workflow's data input schema is fetched through the given
+/// [`DefinitionCache`]. If the workflow does not have a data input schema, `Ok(None)` is returned.
+///
+/// # Errors
+///
+/// Any error returned by [`DefinitionCache::get_or_insert`].
+///
+/// [data input schema]: WorkflowDefinition::data_input_schema
+pub fn example_input(
+    definition: &WorkflowDefinition,
+    cache: &mut DefinitionCache,
+) -> crate::Result<Option<Value>> {
+    definition
+        .data_input_schema
+        .as_ref()
+        .map(|data_input_schema| {
+            let schema = cache.get_or_insert::<JsonSchema, _>(data_input_schema.schema())?;
+            Ok(schema.example())
+        })
+        .transpose()
+}
+
+/// A validation error found in workflow data input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputValidationError {
+    /// JSON pointer to the part of the input that failed validation.
+    ///
+    /// Empty if the error concerns the input as a whole.
+    pub instance_path: String,
+
+    /// Message describing the validation error.
+    pub message: String,
+}
+
+impl Display for InputValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.instance_path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.instance_path, self.message)
+        }
+    }
+}
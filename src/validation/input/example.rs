@@ -0,0 +1,284 @@
+use serde_json::{Map, Number, Value};
+
+use crate::detail::rng::Rng;
+
+// Maximum number of `$ref`s followed when generating a value, to stop on recursive schemas.
+const MAX_REF_DEPTH: usize = 8;
+
+// Number of extra elements or characters that random arrays or strings can have
+// when the schema does not specify a maximum.
+const RANDOM_EXTRA_LEN: u64 = 4;
+
+// Range of random numbers when the schema does not specify a minimum nor a maximum.
+const RANDOM_NUMBER_RANGE: f64 = 100.0;
+
+// Generates values from a JSON Schema. Without an RNG, values are deterministic examples
+// (using the schema's own examples and defaults when available); with an RNG, values are
+// picked at random within the schema's constraints.
+pub struct Generator<'a> {
+    root: &'a Value,
+    rng: Option<Rng>,
+}
+
+impl<'a> Generator<'a> {
+    pub fn example(root: &'a Value) -> Self {
+        Self { root, rng: None }
+    }
+
+    pub fn random(root: &'a Value, seed: u64) -> Self {
+        Self { root, rng: Some(Rng(seed)) }
+    }
+
+    pub fn generate(&mut self) -> Value {
+        self.value(self.root, 0)
+    }
+
+    fn value(&mut self, schema: &Value, depth: usize) -> Value {
+        let Value::Object(schema) = schema else {
+            return Value::Null;
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            return match self.resolve(reference) {
+                Some(target) if depth < MAX_REF_DEPTH => self.value(target, depth + 1),
+                _ => Value::Null,
+            };
+        }
+        if let Some(value) = schema.get("const") {
+            return value.clone();
+        }
+        if let Some(Value::Array(values)) = schema.get("enum") {
+            return self.pick(values).cloned().unwrap_or_default();
+        }
+        if self.rng.is_none() {
+            let example = schema
+                .get("examples")
+                .and_then(Value::as_array)
+                .and_then(|examples| examples.first())
+                .or_else(|| schema.get("default"));
+            if let Some(example) = example {
+                return example.clone();
+            }
+        }
+
+        let mut value = self.typed(schema, depth);
+        for subschema in schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let subvalue = self.value(subschema, depth);
+            merge(&mut value, subvalue);
+        }
+        for keyword in ["oneOf", "anyOf"] {
+            if let Some(Value::Array(subschemas)) = schema.get(keyword) {
+                if let Some(subschema) = self.pick(subschemas) {
+                    let subvalue = self.value(subschema, depth);
+                    merge(&mut value, subvalue);
+                }
+            }
+        }
+
+        value
+    }
+
+    fn typed(&mut self, schema: &Map<String, Value>, depth: usize) -> Value {
+        let schema_type = match schema.get("type") {
+            Some(Value::String(schema_type)) => Some(schema_type.as_str()),
+            Some(Value::Array(types)) => {
+                let types: Vec<_> = types
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .filter(|schema_type| *schema_type != "null")
+                    .collect();
+                self.pick(&types).copied()
+            },
+            _ if schema.contains_key("properties") => Some("object"),
+            _ if schema.contains_key("items") => Some("array"),
+            _ => None,
+        };
+
+        match schema_type {
+            Some("object") => Value::Object(self.object(schema, depth)),
+            Some("array") => Value::Array(self.array(schema, depth)),
+            Some("string") => Value::String(self.string(schema)),
+            Some("integer") => self.number(schema, true),
+            Some("number") => self.number(schema, false),
+            Some("boolean") => Value::Bool(self.rng.as_mut().is_some_and(|rng| rng.chance(0.5))),
+            _ => Value::Null,
+        }
+    }
+
+    fn object(&mut self, schema: &Map<String, Value>, depth: usize) -> Map<String, Value> {
+        let required: Vec<_> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect();
+
+        let mut object = Map::new();
+        if let Some(Value::Object(properties)) = schema.get("properties") {
+            for (name, property) in properties {
+                let included = match self.rng.as_mut() {
+                    Some(rng) => required.contains(&name.as_str()) || rng.chance(0.5),
+                    None => true,
+                };
+                if included {
+                    object.insert(name.clone(), self.value(property, depth));
+                }
+            }
+        }
+        for name in required {
+            object.entry(name).or_insert(Value::Null);
+        }
+
+        object
+    }
+
+    fn array(&mut self, schema: &Map<String, Value>, depth: usize) -> Vec<Value> {
+        let min = schema.get("minItems").and_then(Value::as_u64).unwrap_or(0);
+        let max = schema.get("maxItems").and_then(Value::as_u64);
+        let len = match self.rng.as_mut() {
+            Some(rng) => {
+                min + rng
+                    .index((max.unwrap_or(min + RANDOM_EXTRA_LEN).saturating_sub(min) + 1) as usize)
+                    as u64
+            },
+            None => max.map_or(min.max(1), |max| min.max(1).min(max)),
+        };
+
+        (0..len as usize)
+            .map(|i| match schema.get("items") {
+                Some(Value::Array(items)) => items
+                    .get(i)
+                    .map(|item| self.value(item, depth))
+                    .unwrap_or_default(),
+                Some(items) => self.value(items, depth),
+                None => Value::Null,
+            })
+            .collect()
+    }
+
+    fn string(&mut self, schema: &Map<String, Value>) -> String {
+        let format_example = match schema.get("format").and_then(Value::as_str) {
+            Some("date-time") => Some("1970-01-01T00:00:00Z"),
+            Some("date") => Some("1970-01-01"),
+            Some("time") => Some("00:00:00Z"),
+            Some("duration") => Some("PT1S"),
+            Some("email") => Some("user@example.com"),
+            Some("hostname") => Some("example.com"),
+            Some("ipv4") => Some("127.0.0.1"),
+            Some("ipv6") => Some("::1"),
+            Some("uri" | "iri" | "uri-reference" | "iri-reference") => Some("https://example.com"),
+            Some("uuid") => Some("00000000-0000-0000-0000-000000000000"),
+            _ => None,
+        };
+        if let Some(format_example) = format_example {
+            return format_example.into();
+        }
+
+        let min = schema.get("minLength").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let max = schema
+            .get("maxLength")
+            .and_then(Value::as_u64)
+            .map(|max| max as usize);
+        match self.rng.as_mut() {
+            Some(rng) => {
+                const CHARACTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+                let len = min
+                    + rng.index(
+                        max.unwrap_or(min + RANDOM_EXTRA_LEN as usize)
+                            .saturating_sub(min)
+                            + 1,
+                    );
+                (0..len)
+                    .map(|_| CHARACTERS[rng.index(CHARACTERS.len())] as char)
+                    .collect()
+            },
+            None => {
+                let mut string: String = "string".chars().take(max.unwrap_or(usize::MAX)).collect();
+                while string.len() < min {
+                    string.push('x');
+                }
+                string
+            },
+        }
+    }
+
+    fn number(&mut self, schema: &Map<String, Value>, integer: bool) -> Value {
+        let bound = |keyword, exclusive_keyword, offset: f64| {
+            let inclusive = schema.get(keyword).and_then(Value::as_f64);
+            // Draft 4 uses booleans for exclusive bounds; later drafts use numbers.
+            match schema.get(exclusive_keyword) {
+                Some(Value::Bool(true)) => inclusive.map(|bound| bound + offset),
+                Some(Value::Number(bound)) => bound.as_f64().map(|bound| bound + offset),
+                _ => inclusive,
+            }
+        };
+        let step = if integer { 1.0 } else { 1e-6 };
+        let min = bound("minimum", "exclusiveMinimum", step);
+        let max = bound("maximum", "exclusiveMaximum", -step);
+
+        let number = match self.rng.as_mut() {
+            Some(rng) => {
+                let (min, max) = match (min, max) {
+                    (Some(min), Some(max)) => (min, max),
+                    (Some(min), None) => (min, min + RANDOM_NUMBER_RANGE),
+                    (None, Some(max)) => (max - RANDOM_NUMBER_RANGE, max),
+                    (None, None) => (0.0, RANDOM_NUMBER_RANGE),
+                };
+                min + rng.next_f64() * (max - min)
+            },
+            None => min
+                .map_or(0.0, |min| min.max(0.0))
+                .min(max.unwrap_or(f64::MAX)),
+        };
+
+        if integer {
+            let mut number = number.round();
+            if let Some(min) = min {
+                number = number.max(min.ceil());
+            }
+            if let Some(max) = max {
+                number = number.min(max.floor());
+            }
+            Value::Number((number as i64).into())
+        } else {
+            Number::from_f64(number).map_or(Value::Null, Value::Number)
+        }
+    }
+
+    fn resolve(&self, reference: &str) -> Option<&'a Value> {
+        let pointer = reference.strip_prefix('#')?;
+        self.root.pointer(pointer)
+    }
+
+    fn pick<'v, T>(&mut self, values: &'v [T]) -> Option<&'v T> {
+        match self.rng.as_mut() {
+            Some(rng) => values.get(rng.index(values.len())),
+            None => values.first(),
+        }
+    }
+}
+
+// Merges a value generated from a subschema into the value generated from the schema itself.
+fn merge(value: &mut Value, subvalue: Value) {
+    match (value, subvalue) {
+        (Value::Object(object), Value::Object(subobject)) => {
+            for (name, subvalue) in subobject {
+                match object.get_mut(&name) {
+                    Some(value) => merge(value, subvalue),
+                    None => {
+                        object.insert(name, subvalue);
+                    },
+                }
+            }
+        },
+        (value @ Value::Null, subvalue) => *value = subvalue,
+        _ => (),
+    }
+}
@@ -0,0 +1,289 @@
+//! Configurable lint rules for workflow definitions.
+//!
+//! Contrary to [definition validation], which checks that a workflow definition conforms to the
+//! specification, lint rules report potential problems in otherwise-valid workflow definitions
+//! (for example, states that can never be reached). Each rule has a unique ID and a default
+//! [`Severity`], which can be changed (or the rule disabled entirely) through a [`LintConfig`].
+//!
+//! [definition validation]: crate::validation::ValidateDefinition
+
+pub mod rules;
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::validation::lint::rules::{
+    DeadEndStates, MisplacedWildcardErrorHandlers, MissingDescription, NonBooleanConditions,
+    OverlappingConditions, UndefinedConstants, UnreachableStates, UnusedFunctions,
+};
+use crate::workflow::definition::WorkflowDefinition;
+
+/// Severity of a [lint finding](LintFinding).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Informational finding
+    Info,
+
+    /// Potential problem in the workflow definition
+    Warning,
+
+    /// Problem in the workflow definition that needs to be fixed
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Info => write!(f, "info"),
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Level at which a lint rule is configured in a [`LintConfig`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    /// Rule is disabled
+    Off,
+
+    /// Rule reports findings as [`Severity::Info`]
+    Info,
+
+    /// Rule reports findings as [`Severity::Warning`]
+    Warning,
+
+    /// Rule reports findings as [`Severity::Error`]
+    Error,
+}
+
+impl LintLevel {
+    /// Returns the severity corresponding to this level, or `None` if the level is [`Off`](Self::Off).
+    pub fn severity(&self) -> Option<Severity> {
+        match self {
+            Self::Off => None,
+            Self::Info => Some(Severity::Info),
+            Self::Warning => Some(Severity::Warning),
+            Self::Error => Some(Severity::Error),
+        }
+    }
+}
+
+impl From<Severity> for LintLevel {
+    fn from(value: Severity) -> Self {
+        match value {
+            Severity::Info => Self::Info,
+            Severity::Warning => Self::Warning,
+            Severity::Error => Self::Error,
+        }
+    }
+}
+
+/// Configuration of lint rules.
+///
+/// Rules not present in the configuration are run at their [default severity].
+///
+/// [default severity]: LintRule::default_severity
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintConfig {
+    /// Level of lint rules, keyed by [rule ID](LintRule::id)
+    #[serde(default)]
+    pub rules: HashMap<String, LintLevel>,
+}
+
+impl LintConfig {
+    /// Returns a copy of this configuration where the given rule is configured at the given level.
+    pub fn with_level<I, L>(mut self, rule_id: I, level: L) -> Self
+    where
+        I: Into<String>,
+        L: Into<LintLevel>,
+    {
+        self.rules.insert(rule_id.into(), level.into());
+        self
+    }
+
+    /// Returns the severity at which the given rule should report findings, or `None` if the
+    /// rule is disabled.
+    pub fn severity_for(&self, rule: &dyn LintRule) -> Option<Severity> {
+        match self.rules.get(rule.id()) {
+            Some(level) => level.severity(),
+            None => Some(rule.default_severity()),
+        }
+    }
+}
+
+/// Problem reported by a [`LintRule`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    /// JSON pointer to the offending element in the workflow definition (e.g. `/states/2`)
+    pub pointer: String,
+
+    /// Message describing the problem
+    pub message: String,
+}
+
+impl LintIssue {
+    /// Creates a new lint issue.
+    pub fn new<P, M>(pointer: P, message: M) -> Self
+    where
+        P: Into<String>,
+        M: Into<String>,
+    {
+        Self { pointer: pointer.into(), message: message.into() }
+    }
+}
+
+/// A lint rule that can be run on a workflow definition.
+pub trait LintRule: Send + Sync {
+    /// Unique ID of the rule, in kebab-case (e.g. `unreachable-states`).
+    fn id(&self) -> &'static str;
+
+    /// Severity of the rule's findings if not configured otherwise in a [`LintConfig`].
+    fn default_severity(&self) -> Severity;
+
+    /// Checks the given workflow definition and returns any issue found.
+    fn check(&self, definition: &WorkflowDefinition) -> Vec<LintIssue>;
+}
+
+/// Finding reported in a [`LintReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintFinding {
+    /// ID of the rule that reported this finding
+    pub rule_id: String,
+
+    /// Severity of the finding
+    pub severity: Severity,
+
+    /// JSON pointer to the offending element in the workflow definition
+    pub pointer: String,
+
+    /// Message describing the finding
+    pub message: String,
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}] {}: {}", self.severity, self.rule_id, self.pointer, self.message)
+    }
+}
+
+/// Result of linting a workflow definition.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintReport {
+    /// Findings reported by lint rules, in the order rules were run
+    pub findings: Vec<LintFinding>,
+}
+
+impl LintReport {
+    /// Returns `true` if no rule reported any finding.
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Returns `true` if at least one finding has [`Severity::Error`].
+    pub fn has_errors(&self) -> bool {
+        self.findings
+            .iter()
+            .any(|finding| finding.severity == Severity::Error)
+    }
+
+    /// Returns the findings with the given severity.
+    pub fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &LintFinding> + '_ {
+        self.findings
+            .iter()
+            .filter(move |finding| finding.severity == severity)
+    }
+}
+
+/// Runs [lint rules](LintRule) on workflow definitions.
+pub struct Linter {
+    rules: Vec<Box<dyn LintRule>>,
+    config: LintConfig,
+}
+
+impl Linter {
+    /// Creates a linter running all built-in [rules] with the given configuration.
+    pub fn new(config: LintConfig) -> Self {
+        Self::without_rules(config)
+            .with_rule(UnreachableStates)
+            .with_rule(DeadEndStates)
+            .with_rule(UnusedFunctions)
+            .with_rule(NonBooleanConditions)
+            .with_rule(OverlappingConditions)
+            .with_rule(UndefinedConstants::default())
+            .with_rule(MisplacedWildcardErrorHandlers)
+            .with_rule(MissingDescription)
+    }
+
+    /// Creates a linter with no rules. Use [`with_rule`](Self::with_rule) to register rules.
+    pub fn without_rules(config: LintConfig) -> Self {
+        Self { rules: Vec::new(), config }
+    }
+
+    /// Registers an additional rule with the linter.
+    pub fn with_rule<R>(mut self, rule: R) -> Self
+    where
+        R: LintRule + 'static,
+    {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// Returns the IDs of the rules registered with the linter.
+    pub fn rule_ids(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.rules.iter().map(|rule| rule.id())
+    }
+
+    /// Runs all enabled rules on the given workflow definition.
+    pub fn lint(&self, definition: &WorkflowDefinition) -> LintReport {
+        let findings = self
+            .rules
+            .iter()
+            .filter_map(|rule| {
+                self.config
+                    .severity_for(rule.as_ref())
+                    .map(|severity| (rule, severity))
+            })
+            .flat_map(|(rule, severity)| {
+                rule.check(definition)
+                    .into_iter()
+                    .map(move |issue| LintFinding {
+                        rule_id: rule.id().into(),
+                        severity,
+                        pointer: issue.pointer,
+                        message: issue.message,
+                    })
+            })
+            .collect();
+
+        LintReport { findings }
+    }
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new(LintConfig::default())
+    }
+}
+
+impl fmt::Debug for Linter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Linter")
+            .field("rules", &self.rule_ids().collect::<Vec<_>>())
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+/// Lints the given workflow definition using all built-in [rules] and the given configuration.
+///
+/// Shorthand for `Linter::new(config.clone()).lint(definition)`.
+pub fn lint(definition: &WorkflowDefinition, config: &LintConfig) -> LintReport {
+    Linter::new(config.clone()).lint(definition)
+}
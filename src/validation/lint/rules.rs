@@ -0,0 +1,647 @@
+//! Built-in lint rules.
+
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+use crate::cache::DefinitionCache;
+use crate::detail::expression::{constant_references, result_kind, strip_expression, ResultKind};
+use crate::loader::DefinitionLoader;
+use crate::openapi::{operation_location, OpenApiDocument};
+use crate::registry::{DependencyGraph, WorkflowKey, WorkflowRegistry};
+use crate::validation::lint::{LintIssue, LintRule, Severity};
+use crate::validation::states::{find_dead_end_states, find_unreachable_states};
+use crate::workflow::definition::functions::{FunctionType, Functions};
+use crate::workflow::definition::{
+    Action, Constants, End, Error, State, SwitchState, WorkflowDefinition,
+};
+
+/// Reports states that cannot be reached from the starting state.
+///
+/// See [`find_unreachable_states`].
+#[derive(Debug, Default, Copy, Clone)]
+pub struct UnreachableStates;
+
+impl LintRule for UnreachableStates {
+    fn id(&self) -> &'static str {
+        "unreachable-states"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, definition: &WorkflowDefinition) -> Vec<LintIssue> {
+        find_unreachable_states(definition)
+            .into_iter()
+            .map(|state| {
+                let message = if state.referenced_by.is_empty() {
+                    format!("state '{}' is never transitioned to", state.name)
+                } else {
+                    format!(
+                        "state '{}' is only transitioned to by unreachable state(s): {}",
+                        state.name,
+                        state.referenced_by.join(", ")
+                    )
+                };
+
+                LintIssue::new(state_pointer(definition, &state.name), message)
+            })
+            .collect()
+    }
+}
+
+/// Reports states that have no way to transition to another state or end the workflow.
+///
+/// See [`find_dead_end_states`].
+#[derive(Debug, Default, Copy, Clone)]
+pub struct DeadEndStates;
+
+impl LintRule for DeadEndStates {
+    fn id(&self) -> &'static str {
+        "dead-end-states"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, definition: &WorkflowDefinition) -> Vec<LintIssue> {
+        find_dead_end_states(definition)
+            .into_iter()
+            .map(|name| {
+                LintIssue::new(
+                    state_pointer(definition, name),
+                    format!("state '{}' has no transition and does not end the workflow", name),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Reports inline function definitions that are not referenced by any action.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct UnusedFunctions;
+
+impl LintRule for UnusedFunctions {
+    fn id(&self) -> &'static str {
+        "unused-functions"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, definition: &WorkflowDefinition) -> Vec<LintIssue> {
+        let Some(Functions::Inline(functions)) = &definition.functions else {
+            return Vec::new();
+        };
+
+        let used: HashSet<_> = definition
+            .states
+            .iter()
+            .flat_map(State::actions)
+            .filter_map(|action| action.function_ref.as_ref())
+            .map(|function_ref| function_ref.ref_name())
+            .collect();
+
+        functions
+            .iter()
+            .enumerate()
+            .filter(|(_, function)| !used.contains(function.name.as_str()))
+            .map(|(i, function)| {
+                LintIssue::new(
+                    format!("/functions/{}", i),
+                    format!("function '{}' is never used", function.name),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Reports [wildcard] error handlers defined before handlers for specific errors.
+///
+/// Handlers for specific errors [take precedence] over wildcard handlers regardless of their
+/// order, but runtimes that pick the first matching handler would never use handlers defined
+/// after a wildcard one. Defining wildcard handlers last avoids the ambiguity.
+///
+/// The [`DefinitionLoader`] also reports these issues as
+/// [load warnings](crate::loader::LoadWarning::MisplacedWildcardErrorHandler), so they are
+/// surfaced even when lint rules are not run. They are not validation errors, since the
+/// specification allows handlers to be defined in any order.
+///
+/// [wildcard]: crate::workflow::definition::WILDCARD_ERROR
+/// [take precedence]: State::error_handler
+#[derive(Debug, Default, Copy, Clone)]
+pub struct MisplacedWildcardErrorHandlers;
+
+impl LintRule for MisplacedWildcardErrorHandlers {
+    fn id(&self) -> &'static str {
+        "misplaced-wildcard-error-handlers"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, definition: &WorkflowDefinition) -> Vec<LintIssue> {
+        let mut issues = Vec::new();
+        for (i, state) in definition.states.iter().enumerate() {
+            let on_errors = state.on_errors();
+            for (j, error) in on_errors.iter().enumerate() {
+                if !error.is_wildcard() {
+                    continue;
+                }
+
+                let shadowed: Vec<_> = on_errors[j + 1..]
+                    .iter()
+                    .filter(|error| !error.is_wildcard())
+                    .flat_map(Error::error_names)
+                    .collect();
+                if !shadowed.is_empty() {
+                    issues.push(LintIssue::new(
+                        format!("/states/{}/onErrors/{}", i, j),
+                        format!(
+                            "wildcard error handler is defined before handler(s) for specific errors: {}",
+                            shadowed.join(", ")
+                        ),
+                    ));
+                }
+            }
+        }
+        issues
+    }
+}
+
+/// Reports workflow definitions without a description.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct MissingDescription;
+
+impl LintRule for MissingDescription {
+    fn id(&self) -> &'static str {
+        "missing-description"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn check(&self, definition: &WorkflowDefinition) -> Vec<LintIssue> {
+        match &definition.description {
+            Some(description) if !description.trim().is_empty() => Vec::new(),
+            _ => vec![LintIssue::new("", "workflow definition has no description")],
+        }
+    }
+}
+
+/// Reports `jq` conditions that do not yield a boolean value.
+///
+/// With `jq`, any value other than `false` and `null` is considered true, so a condition
+/// returning an object or a string (for example) is always true. This rule checks the conditions
+/// of data-based switch states and of actions. Conditions whose result type cannot be determined
+/// statically are not reported.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct NonBooleanConditions;
+
+impl LintRule for NonBooleanConditions {
+    fn id(&self) -> &'static str {
+        "non-boolean-conditions"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, definition: &WorkflowDefinition) -> Vec<LintIssue> {
+        if definition.expression_lang != "jq" {
+            return Vec::new();
+        }
+
+        let data_conditions = definition
+            .states
+            .iter()
+            .enumerate()
+            .filter_map(|(i, state)| match state {
+                State::Switch(SwitchState::DataBased(state)) => Some((i, state)),
+                _ => None,
+            })
+            .flat_map(|(i, state)| {
+                state
+                    .data_conditions
+                    .iter()
+                    .enumerate()
+                    .map(move |(j, condition)| {
+                        (
+                            format!("/states/{}/dataConditions/{}/condition", i, j),
+                            condition.condition(),
+                        )
+                    })
+            });
+        let action_conditions = definition
+            .states
+            .iter()
+            .enumerate()
+            .flat_map(|(i, state)| actions_with_pointers(state, &format!("/states/{}", i)))
+            .filter_map(|(pointer, action)| {
+                action
+                    .condition
+                    .as_deref()
+                    .map(|condition| (format!("{}/condition", pointer), condition))
+            });
+
+        data_conditions
+            .chain(action_conditions)
+            .filter_map(|(pointer, condition)| match result_kind(condition) {
+                ResultKind::NonBoolean(kind) => Some(LintIssue::new(
+                    pointer,
+                    format!(
+                        "condition '{}' yields a value of type '{}' instead of a boolean",
+                        condition, kind
+                    ),
+                )),
+                ResultKind::Boolean | ResultKind::Unknown => None,
+            })
+            .collect()
+    }
+}
+
+/// Reports switch state conditions that can never be selected.
+///
+/// Switch states select the first condition that matches (see [`DataBasedSwitchState::select`]
+/// and [`EventBasedSwitchState::select`]), so a condition is never selected if it comes after a
+/// data condition that is always `true`, if it has the same expression as an earlier data
+/// condition, or if it references the same event as an earlier event condition. Data conditions
+/// that are always `false` are also reported. Constant conditions are only detected in jq.
+///
+/// [`DataBasedSwitchState::select`]: crate::workflow::definition::DataBasedSwitchState::select
+/// [`EventBasedSwitchState::select`]: crate::workflow::definition::EventBasedSwitchState::select
+#[derive(Debug, Default, Copy, Clone)]
+pub struct OverlappingConditions;
+
+impl LintRule for OverlappingConditions {
+    fn id(&self) -> &'static str {
+        "overlapping-conditions"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, definition: &WorkflowDefinition) -> Vec<LintIssue> {
+        let jq = definition.expression_lang == "jq";
+
+        let mut issues = Vec::new();
+        for (i, state) in definition.states.iter().enumerate() {
+            match state {
+                State::Switch(SwitchState::DataBased(state)) => {
+                    let conditions: Vec<_> = state
+                        .data_conditions
+                        .iter()
+                        .map(|condition| strip_expression(condition.condition()))
+                        .collect();
+                    for (j, condition) in conditions.iter().enumerate() {
+                        let earlier = &conditions[..j];
+                        let message = if let Some(k) =
+                            earlier.iter().position(|earlier| jq && *earlier == "true")
+                        {
+                            format!("condition is never selected: condition {} always matches", k)
+                        } else if let Some(k) =
+                            earlier.iter().position(|earlier| earlier == condition)
+                        {
+                            format!(
+                                "condition is never selected: condition {} has the same expression",
+                                k
+                            )
+                        } else if jq && *condition == "false" {
+                            "condition never matches".into()
+                        } else {
+                            continue;
+                        };
+                        issues.push(LintIssue::new(
+                            format!("/states/{}/dataConditions/{}", i, j),
+                            message,
+                        ));
+                    }
+                },
+                State::Switch(SwitchState::EventBased(state)) => {
+                    for (j, condition) in state.event_conditions.iter().enumerate() {
+                        if let Some(k) = state.event_conditions[..j]
+                            .iter()
+                            .position(|earlier| earlier.event_ref() == condition.event_ref())
+                        {
+                            issues.push(LintIssue::new(
+                                format!("/states/{}/eventConditions/{}", i, j),
+                                format!(
+                                    "condition is never selected: condition {} references the same event '{}'",
+                                    k,
+                                    condition.event_ref()
+                                ),
+                            ));
+                        }
+                    }
+                },
+                _ => (),
+            }
+        }
+        issues
+    }
+}
+
+/// Reports references to workflow constants (e.g. `$CONST.name`) that are not defined.
+///
+/// If the workflow definition's [constants] are stored in an external resource, the resource
+/// is loaded using the rule's [`DefinitionLoader`]; failure to load the constants is reported
+/// instead. Only the top-level constant name of each reference is checked.
+///
+/// [constants]: WorkflowDefinition::constants
+#[derive(Debug, Default, Clone)]
+pub struct UndefinedConstants {
+    loader: DefinitionLoader,
+}
+
+impl UndefinedConstants {
+    /// Creates a rule that loads external constants using the given loader.
+    pub fn with_loader(loader: DefinitionLoader) -> Self {
+        Self { loader }
+    }
+
+    fn constant_names(&self, constants: Option<&Constants>) -> crate::Result<HashSet<String>> {
+        let mut constants = match constants {
+            Some(constants) => constants.clone(),
+            None => return Ok(HashSet::new()),
+        };
+
+        let mut visited = HashSet::new();
+        loop {
+            match constants {
+                Constants::Multiple { constants } => return Ok(constants.into_keys().collect()),
+                Constants::One(uri) => {
+                    if !visited.insert(uri.clone()) {
+                        return Err(crate::Error::CircularExternalReference { uri });
+                    }
+                    let resource = self.loader.load::<Constants>(&uri)?;
+                    constants = Constants::clone(&resource);
+                },
+            }
+        }
+    }
+}
+
+impl LintRule for UndefinedConstants {
+    fn id(&self) -> &'static str {
+        "undefined-constants"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, definition: &WorkflowDefinition) -> Vec<LintIssue> {
+        let names = match self.constant_names(definition.constants.as_ref()) {
+            Ok(names) => names,
+            Err(err) => {
+                return vec![LintIssue::new(
+                    "/constants",
+                    format!("failed to load workflow constants: {}", err),
+                )];
+            },
+        };
+
+        // Expressions can appear in many places, so look at every string in the definition.
+        let mut issues = Vec::new();
+        if let Ok(Value::Object(fields)) = serde_json::to_value(definition) {
+            for (key, value) in fields.iter().filter(|(key, _)| *key != "constants") {
+                visit_strings(value, &format!("/{}", pointer_token(key)), &mut |pointer, value| {
+                    for name in constant_references(value) {
+                        if !names.contains(name) {
+                            issues.push(LintIssue::new(
+                                pointer,
+                                format!("constant '{}' is not defined", name),
+                            ));
+                        }
+                    }
+                });
+            }
+        }
+        issues
+    }
+}
+
+/// Reports [REST functions] whose operation cannot be found in their OpenAPI document.
+///
+/// OpenAPI documents are loaded using the rule's [`DefinitionLoader`]; failure to load a
+/// document is reported instead. Only inline function definitions are checked.
+///
+/// This rule is not run by [`Linter::new`] since loading OpenAPI documents usually requires
+/// network access; add it with [`Linter::with_rule`].
+///
+/// [REST functions]: FunctionType::Rest
+/// [`Linter::new`]: crate::validation::lint::Linter::new
+/// [`Linter::with_rule`]: crate::validation::lint::Linter::with_rule
+#[derive(Debug, Default, Clone)]
+pub struct UndefinedOperations {
+    loader: DefinitionLoader,
+}
+
+impl UndefinedOperations {
+    /// Creates a rule that loads OpenAPI documents using the given loader.
+    pub fn with_loader(loader: DefinitionLoader) -> Self {
+        Self { loader }
+    }
+}
+
+impl LintRule for UndefinedOperations {
+    fn id(&self) -> &'static str {
+        "undefined-operations"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, definition: &WorkflowDefinition) -> Vec<LintIssue> {
+        let Some(Functions::Inline(functions)) = &definition.functions else {
+            return Vec::new();
+        };
+
+        // Functions often share OpenAPI documents, so only load each one once.
+        let mut cache = DefinitionCache::with_loader(self.loader.clone());
+        functions
+            .iter()
+            .enumerate()
+            .filter(|(_, function)| function.function_type == FunctionType::Rest)
+            .filter_map(|(i, function)| {
+                let message = match operation_location(function).and_then(|(uri, operation_id)| {
+                    let document = cache.get_or_insert::<OpenApiDocument, _>(uri)?;
+                    Ok(document.operation(operation_id).is_some())
+                }) {
+                    Ok(true) => return None,
+                    Ok(false) => format!(
+                        "operation '{}' of function '{}' is not defined",
+                        function.operation, function.name
+                    ),
+                    Err(err) => {
+                        format!(
+                            "failed to resolve operation of function '{}': {}",
+                            function.name, err
+                        )
+                    },
+                };
+                Some(LintIssue::new(format!("/functions/{}/operation", i), message))
+            })
+            .collect()
+    }
+}
+
+/// Reports workflows that can invoke themselves as a sub-workflow or continue as themselves,
+/// either directly or through other workflows.
+///
+/// Dependencies between workflows are looked up in the rule's [`DependencyGraph`], usually
+/// built from a [`WorkflowRegistry`]; workflows that are not part of the graph are not reported.
+/// Since recursion can be bounded by the workflow data, findings are warnings by default;
+/// configure the rule at [`LintLevel::Error`] to reject recursive workflows.
+///
+/// This rule is not run by [`Linter::new`] since it needs a registry; add it with
+/// [`Linter::with_rule`].
+///
+/// [`LintLevel::Error`]: crate::validation::lint::LintLevel::Error
+/// [`Linter::new`]: crate::validation::lint::Linter::new
+/// [`Linter::with_rule`]: crate::validation::lint::Linter::with_rule
+#[derive(Debug, Default, Clone)]
+pub struct RecursiveWorkflows {
+    graph: DependencyGraph,
+}
+
+impl RecursiveWorkflows {
+    /// Creates a rule that looks up dependencies between the workflows registered
+    /// in the given registry.
+    pub fn new(registry: &WorkflowRegistry) -> Self {
+        Self::with_graph(registry.dependency_graph())
+    }
+
+    /// Creates a rule that looks up dependencies between workflows in the given graph.
+    pub fn with_graph(graph: DependencyGraph) -> Self {
+        Self { graph }
+    }
+}
+
+impl LintRule for RecursiveWorkflows {
+    fn id(&self) -> &'static str {
+        "recursive-workflows"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, definition: &WorkflowDefinition) -> Vec<LintIssue> {
+        let Some(cycle) = WorkflowKey::for_definition(definition)
+            .ok()
+            .and_then(|key| self.graph.cycle(&key))
+        else {
+            return Vec::new();
+        };
+
+        // Point to the first state referencing the next workflow in the cycle.
+        let next = cycle[1];
+        let pointer = definition
+            .states
+            .iter()
+            .position(|state| references_workflow(state, next))
+            .map(|i| format!("/states/{}", i))
+            .unwrap_or_default();
+        let cycle: Vec<_> = cycle.iter().map(ToString::to_string).collect();
+
+        vec![LintIssue::new(
+            pointer,
+            format!("workflow '{}' can invoke itself: {}", cycle[0], cycle.join(" -> ")),
+        )]
+    }
+}
+
+fn references_workflow(state: &State, key: &WorkflowKey) -> bool {
+    let sub_flows = state
+        .actions()
+        .into_iter()
+        .filter_map(|action| action.sub_flow_ref.as_ref())
+        .any(|sub_flow_ref| key.matches(sub_flow_ref.workflow_id(), sub_flow_ref.version()));
+    let continue_as = state
+        .ends()
+        .into_iter()
+        .filter_map(End::continue_as)
+        .any(|continue_as| key.matches(continue_as.workflow_id(), continue_as.version()));
+
+    sub_flows || continue_as
+}
+
+fn visit_strings<F>(value: &Value, pointer: &str, f: &mut F)
+where
+    F: FnMut(&str, &str),
+{
+    match value {
+        Value::String(value) => f(pointer, value),
+        Value::Array(values) => {
+            for (i, value) in values.iter().enumerate() {
+                visit_strings(value, &format!("{}/{}", pointer, i), f);
+            }
+        },
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                visit_strings(value, &format!("{}/{}", pointer, pointer_token(key)), f);
+            }
+        },
+        _ => (),
+    }
+}
+
+fn pointer_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn actions_with_pointers<'a>(state: &'a State, state_pointer: &str) -> Vec<(String, &'a Action)> {
+    let indexed = |pointer: String, actions: &'a [Action]| {
+        actions
+            .iter()
+            .enumerate()
+            .map(move |(i, action)| (format!("{}/{}", pointer, i), action))
+    };
+
+    match state {
+        State::Event(state) => state
+            .on_events
+            .iter()
+            .enumerate()
+            .flat_map(|(i, on_events)| {
+                indexed(
+                    format!("{}/onEvents/{}/actions", state_pointer, i),
+                    on_events.actions.as_deref().unwrap_or_default(),
+                )
+            })
+            .collect(),
+        State::Operation(state) => {
+            indexed(format!("{}/actions", state_pointer), &state.actions).collect()
+        },
+        State::Parallel(state) => state
+            .branches
+            .iter()
+            .enumerate()
+            .flat_map(|(i, branch)| {
+                indexed(format!("{}/branches/{}/actions", state_pointer, i), &branch.actions)
+            })
+            .collect(),
+        State::ForEach(state) => {
+            indexed(format!("{}/actions", state_pointer), &state.actions).collect()
+        },
+        State::Callback(state) => vec![(format!("{}/action", state_pointer), &state.action)],
+        State::Sleep(_) | State::Switch(_) | State::Inject(_) | State::Custom(_) => Vec::new(),
+    }
+}
+
+fn state_pointer(definition: &WorkflowDefinition, name: &str) -> String {
+    definition
+        .states
+        .iter()
+        .position(|state| state.name() == name)
+        .map(|i| format!("/states/{}", i))
+        .unwrap_or_default()
+}
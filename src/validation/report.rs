@@ -0,0 +1,201 @@
+//! Structured, serializable validation reports.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::validation::lint::{LintReport, Linter, Severity};
+use crate::workflow::definition::WorkflowDefinition;
+
+/// Code used for findings produced by [definition validation].
+///
+/// [definition validation]: crate::validation::ValidateDefinition
+pub const DEFINITION_VALIDATION_CODE: &str = "definition";
+
+/// Single finding in a [`ValidationReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationFinding {
+    /// JSON pointer to the offending element in the workflow definition (e.g. `/states/2/end`)
+    pub pointer: String,
+
+    /// Code of the rule that produced the finding
+    ///
+    /// For lint findings, this is the [lint rule ID](crate::validation::lint::LintRule::id).
+    /// For definition validation errors, this is [`DEFINITION_VALIDATION_CODE`].
+    pub code: String,
+
+    /// Message describing the finding
+    pub message: String,
+
+    /// Severity of the finding
+    pub severity: Severity,
+}
+
+impl fmt::Display for ValidationFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}] {}: {}", self.severity, self.code, self.pointer, self.message)
+    }
+}
+
+/// Structured report of validation findings for a workflow definition.
+///
+/// Contrary to [`Error::ValidationFailed`], this report is easy to consume programmatically
+/// and can be serialized (to JSON, for example) for use by CI tooling.
+///
+/// [`Error::ValidationFailed`]: crate::Error::ValidationFailed
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationReport {
+    /// Findings in the report
+    pub findings: Vec<ValidationFinding>,
+}
+
+impl ValidationReport {
+    /// Validates the given workflow definition and lints it with the given [`Linter`], returning
+    /// all findings in a single report.
+    ///
+    /// Definition validation errors are only reported if the `validate` feature is enabled.
+    pub fn for_definition(definition: &WorkflowDefinition, linter: &Linter) -> Self {
+        #[allow(unused_mut)]
+        let mut report = Self::default();
+
+        #[cfg(feature = "validate")]
+        {
+            use garde::Validate;
+
+            if let Err(validation_report) = definition.validate(&()) {
+                let document = serde_json::to_value(definition).ok();
+                report.extend(Self::from_garde_report(&validation_report, document.as_ref()));
+            }
+        }
+
+        report.extend(Self::from(linter.lint(definition)));
+        report
+    }
+
+    /// Returns `true` if the report contains no finding.
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Returns `true` if at least one finding has [`Severity::Error`].
+    pub fn has_errors(&self) -> bool {
+        self.findings
+            .iter()
+            .any(|finding| finding.severity == Severity::Error)
+    }
+
+    /// Appends all findings of another report to this report.
+    pub fn extend(&mut self, other: ValidationReport) {
+        self.findings.extend(other.findings);
+    }
+}
+
+impl From<LintReport> for ValidationReport {
+    fn from(value: LintReport) -> Self {
+        let findings = value
+            .findings
+            .into_iter()
+            .map(|finding| ValidationFinding {
+                pointer: finding.pointer,
+                code: finding.rule_id,
+                message: finding.message,
+                severity: finding.severity,
+            })
+            .collect();
+
+        Self { findings }
+    }
+}
+
+#[cfg(feature = "validate")]
+impl ValidationReport {
+    /// Converts validation errors to findings. If the validated `document` is provided,
+    /// it is used to make sure finding pointers can be resolved in the document.
+    ///
+    /// See [`pointer_from_path`].
+    pub(crate) fn from_garde_report(report: &garde::Report, document: Option<&Value>) -> Self {
+        let findings = report
+            .iter()
+            .map(|(path, error)| ValidationFinding {
+                pointer: pointer_from_path(&path.to_string(), document),
+                code: DEFINITION_VALIDATION_CODE.into(),
+                message: error.message().into(),
+                severity: Severity::Error,
+            })
+            .collect();
+
+        Self { findings }
+    }
+}
+
+#[cfg(feature = "validate")]
+impl From<&garde::Report> for ValidationReport {
+    fn from(value: &garde::Report) -> Self {
+        Self::from_garde_report(value, None)
+    }
+}
+
+/// Converts a validation path (e.g. `states[0].action_mode`) to a JSON pointer
+/// (e.g. `/states/0/actionMode`).
+///
+/// Validation paths use the names of Rust fields, which are converted to the property names
+/// used in workflow definition documents. If the validated `document` is provided, path
+/// components that have no equivalent in the document (like the fields of enum variants or
+/// flattened fields) are skipped, so that the pointer can be resolved in the document.
+pub(crate) fn pointer_from_path(path: &str, document: Option<&Value>) -> String {
+    let mut pointer = String::new();
+    let mut current = document;
+    for component in path
+        .split(['.', '['])
+        .map(|component| component.trim_end_matches(']'))
+        .filter(|component| !component.is_empty())
+    {
+        let mut key = property_name(component);
+        if let Some(value) = current {
+            let child = match value {
+                // Map keys are not converted, so look for the original component first.
+                Value::Object(properties) => match properties.get(component) {
+                    Some(child) => {
+                        key = component.into();
+                        Some(child)
+                    },
+                    None => properties.get(&key),
+                },
+                Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+                _ => None,
+            };
+
+            match child {
+                Some(child) => current = Some(child),
+                None => continue,
+            }
+        }
+
+        pointer.push('/');
+        pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+    }
+    pointer
+}
+
+/// Returns the name of the property corresponding to a Rust field in workflow definition documents.
+fn property_name(field: &str) -> String {
+    match field {
+        "function_type" | "event_type" | "state_type" => "type".into(),
+        field => {
+            let mut name = String::with_capacity(field.len());
+            let mut capitalize = false;
+            for c in field.chars() {
+                match c {
+                    '_' if !name.is_empty() => capitalize = true,
+                    c if capitalize => {
+                        name.extend(c.to_uppercase());
+                        capitalize = false;
+                    },
+                    c => name.push(c),
+                }
+            }
+            name
+        },
+    }
+}
@@ -0,0 +1,102 @@
+//! Validation rules pertaining to the states of a workflow definition and their transitions.
+
+use crate::workflow::definition::{State, SwitchState, WorkflowDefinition};
+use crate::workflow::graph::StateGraph;
+
+/// A workflow [`State`] that cannot be reached from the starting state.
+///
+/// [`State`]: crate::workflow::definition::State
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnreachableState {
+    /// Name of the unreachable state
+    pub name: String,
+
+    /// Names of the states that have a transition to the unreachable state
+    ///
+    /// If this is empty, no state refers to the unreachable state. Otherwise, all states listed
+    /// here are themselves unreachable.
+    pub referenced_by: Vec<String>,
+}
+
+/// Returns all states of the given workflow definition that cannot be reached from its starting state.
+///
+/// States are considered reachable if they can be reached from the [starting state] by following
+/// any transition: state transitions, switch state conditions, error handlers or compensation.
+/// Unreachable states are returned in the order they appear in the workflow definition.
+///
+/// [starting state]: WorkflowDefinition::start_state_name
+pub fn find_unreachable_states(definition: &WorkflowDefinition) -> Vec<UnreachableState> {
+    let graph = StateGraph::new(definition);
+    let reachable = graph.reachable_states();
+
+    graph
+        .states()
+        .map(|state| state.name())
+        .filter(|name| !reachable.contains(name))
+        .map(|name| {
+            let mut referenced_by: Vec<_> = graph
+                .edges_to(name)
+                .map(|edge| edge.from.to_string())
+                .collect();
+            referenced_by.dedup();
+
+            UnreachableState { name: name.into(), referenced_by }
+        })
+        .collect()
+}
+
+/// Returns the names of all dead-end states of the given workflow definition.
+///
+/// A dead-end state is a state that has no `transition`, no `end`, no terminal condition and is
+/// not `usedForCompensation`. A workflow instance reaching such a state would never complete.
+/// Dead-end states are returned in the order they appear in the workflow definition.
+///
+/// When the `validate` feature is enabled, [validating] a workflow definition also reports
+/// dead-end states as errors.
+///
+/// [validating]: crate::validation::ValidateDefinition::validate_definition
+pub fn find_dead_end_states(definition: &WorkflowDefinition) -> Vec<&str> {
+    definition
+        .states
+        .iter()
+        .filter(|state| is_dead_end(state))
+        .map(State::name)
+        .collect()
+}
+
+fn is_dead_end(state: &State) -> bool {
+    match state {
+        State::Sleep(state) => {
+            !state.used_for_compensation && state.transition.is_none() && state.end.is_none()
+        },
+        State::Event(state) => state.transition.is_none() && state.end.is_none(),
+        State::Operation(state) => {
+            !state.used_for_compensation && state.transition.is_none() && state.end.is_none()
+        },
+        State::Parallel(state) => {
+            !state.used_for_compensation && state.transition.is_none() && state.end.is_none()
+        },
+        State::Switch(SwitchState::DataBased(state)) => {
+            !state.used_for_compensation
+                && state.default_condition.transition.is_none()
+                && state.default_condition.end.is_none()
+                && state.data_conditions.is_empty()
+        },
+        State::Switch(SwitchState::EventBased(state)) => {
+            !state.used_for_compensation
+                && state.default_condition.transition.is_none()
+                && state.default_condition.end.is_none()
+                && state.event_conditions.is_empty()
+        },
+        State::Inject(state) => {
+            !state.used_for_compensation && state.transition.is_none() && state.end.is_none()
+        },
+        State::ForEach(state) => {
+            !state.used_for_compensation && state.transition.is_none() && state.end.is_none()
+        },
+        State::Callback(state) => {
+            !state.used_for_compensation && state.transition.is_none() && state.end.is_none()
+        },
+        State::Custom(state) => state.transition.is_none() && state.end.is_none(),
+    }
+}
@@ -1,4 +1,17 @@
 //! Workflow types
 
+#[cfg(feature = "cloudevents")]
+pub mod cloudevents;
+pub mod compatibility;
 pub mod definition;
+pub mod executor;
+pub mod format;
+pub mod graph;
 pub mod instance;
+pub mod migration;
+pub mod preflight;
+pub mod runtime;
+pub mod simulation;
+pub mod subscription;
+pub mod v1;
+pub mod versioned;
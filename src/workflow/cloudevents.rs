@@ -0,0 +1,157 @@
+//! Integration with the [CloudEvents SDK].
+//!
+//! Converts between [`Event`]s of the CloudEvents SDK and the event model of workflow
+//! definitions: consumed CloudEvents can be matched against [event definitions](EventDef) and
+//! [subscriptions](EventSubscription), and CloudEvents can be built for the events
+//! [produced](ProduceEventDef) by workflow actions.
+//!
+//! [CloudEvents SDK]: https://docs.rs/cloudevents-sdk
+
+use ::cloudevents::{AttributesReader, Event, EventBuilder, EventBuilderV10};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::workflow::definition::events::{EventDef, Events};
+use crate::workflow::definition::{Data, ProduceEventDef, WorkflowDefinition};
+use crate::workflow::subscription::EventSubscription;
+
+/// Content type of the data of CloudEvents built by [`ProduceEventDef::to_cloud_event`].
+pub const DATA_CONTENT_TYPE: &str = "application/json";
+
+impl EventDef {
+    /// Returns `true` if the given CloudEvent matches this event definition.
+    ///
+    /// The CloudEvent must have the same type, and the same source if the event definition
+    /// specifies one. It must also have all context attributes used in the definition's
+    /// [correlation rules], with the specified values if any.
+    ///
+    /// [correlation rules]: Self::correlation
+    pub fn matches_cloud_event(&self, event: &Event) -> bool {
+        event.ty() == self.event_type
+            && match &self.source {
+                Some(source) => event.source() == source,
+                None => true,
+            }
+            && self.correlation.iter().flatten().all(|correlation| {
+                match (
+                    context_attribute(event, &correlation.context_attribute_name),
+                    &correlation.context_attribute_value,
+                ) {
+                    (Some(value), Some(expected)) => value == *expected,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                }
+            })
+    }
+
+    /// Returns the part of a consumed CloudEvent that should be accessible to workflow states.
+    ///
+    /// See [`consumed_event_data`](Self::consumed_event_data); the CloudEvent is converted to
+    /// its JSON format first. Binary event data is only available when [`data_only`] is `false`,
+    /// in the `data_base64` attribute.
+    ///
+    /// # Errors
+    ///
+    /// * [`JsonConversionFailed`]: the CloudEvent could not be converted to JSON
+    ///
+    /// [`data_only`]: Self::data_only
+    /// [`JsonConversionFailed`]: crate::Error::JsonConversionFailed
+    pub fn consumed_cloud_event_data(&self, event: &Event) -> crate::Result<Value> {
+        Ok(self.consumed_event_data(&serde_json::to_value(event)?))
+    }
+}
+
+impl ProduceEventDef {
+    /// Builds the CloudEvent produced by this definition.
+    ///
+    /// The CloudEvent gets a new, randomly-generated ID and the type and source of the
+    /// [referenced event definition]; if the event definition has no source, the workflow's
+    /// [identifier](crate::workflow::definition::Identifier::id) is used. Its data is `data` if
+    /// provided (e.g. the result of the [data expression]), or else the definition's data object,
+    /// if any. The definition's [context attributes] are added as extension attributes.
+    ///
+    /// # Errors
+    ///
+    /// * [`UnknownEvent`]: the referenced event is not defined in the workflow definition
+    /// * [`MissingIdentifier`]: the event definition has no source and the workflow definition
+    ///                          has no identifier
+    /// * [`InvalidCloudEvent`]: the CloudEvent could not be built
+    ///
+    /// [referenced event definition]: Self::event_ref
+    /// [data expression]: Data::Expression
+    /// [context attributes]: Self::context_attributes
+    /// [`UnknownEvent`]: crate::Error::UnknownEvent
+    /// [`MissingIdentifier`]: crate::Error::MissingIdentifier
+    /// [`InvalidCloudEvent`]: crate::Error::InvalidCloudEvent
+    pub fn to_cloud_event(
+        &self,
+        definition: &WorkflowDefinition,
+        data: Option<Value>,
+    ) -> crate::Result<Event> {
+        let event_def = match &definition.events {
+            Some(Events::Inline(events)) => {
+                events.iter().find(|event| event.name == self.event_ref)
+            },
+            _ => None,
+        }
+        .ok_or_else(|| crate::Error::UnknownEvent { event: self.event_ref.clone() })?;
+        let source = match &event_def.source {
+            Some(source) => source.as_str(),
+            None => definition.identifier.id()?,
+        };
+
+        let mut builder = EventBuilderV10::new()
+            .id(Uuid::new_v4().to_string())
+            .source(source)
+            .ty(event_def.event_type.as_str());
+        let data = data.or_else(|| match &self.data {
+            Some(Data::Object { fields }) => {
+                Some(Value::Object(fields.clone().into_iter().collect()))
+            },
+            _ => None,
+        });
+        if let Some(data) = data {
+            builder = builder.data(DATA_CONTENT_TYPE, data);
+        }
+        for (name, value) in self
+            .context_attributes
+            .iter()
+            .flat_map(|attributes| &attributes.attributes)
+        {
+            builder = builder.extension(name, value.as_str());
+        }
+
+        builder
+            .build()
+            .map_err(|err| crate::Error::InvalidCloudEvent {
+                event: self.event_ref.clone(),
+                reason: err.to_string(),
+            })
+    }
+}
+
+impl EventSubscription {
+    /// Returns `true` if the given CloudEvent matches this subscription.
+    ///
+    /// See [`matches`](Self::matches); the CloudEvent's extension attributes are used as
+    /// context attributes.
+    pub fn matches_cloud_event(&self, event: &Event) -> bool {
+        let attributes: Map<_, _> = event
+            .iter_extensions()
+            .map(|(name, value)| (name.to_string(), Value::String(value.to_string())))
+            .collect();
+
+        self.matches(event.ty(), Some(event.source().as_str()), &attributes)
+    }
+}
+
+// Returns the value of a CloudEvent context attribute, including extension attributes.
+fn context_attribute(event: &Event, name: &str) -> Option<String> {
+    match name {
+        "id" => Some(event.id().into()),
+        "source" => Some(event.source().to_string()),
+        "type" => Some(event.ty().into()),
+        "subject" => event.subject().map(Into::into),
+        _ => event.extension(name).map(ToString::to_string),
+    }
+}
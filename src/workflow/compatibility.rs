@@ -0,0 +1,260 @@
+//! Compatibility of workflow definitions with this crate.
+//!
+//! This crate does not implement every feature of the specification yet. A
+//! [`CompatibilityReport`], returned by [`WorkflowDefinition::compatibility_report`], lists
+//! the [features](Feature) used by a workflow definition that are only partially implemented,
+//! so users can decide up front whether the crate can run the workflow.
+//!
+//! Each feature has a stable [identifier](Feature::id), which is referenced by the issues
+//! tracking its implementation.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::workflow::definition::{
+    Action, DataCondition, End, EventCondition, State, SwitchState, Transition, WorkflowDefinition,
+};
+
+/// URL of the repository's issue tracker.
+const ISSUES_URL: &str = "https://github.com/clechasseur/travailleur/issues";
+
+/// Feature of the specification that this crate only partially implements.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Feature {
+    /// [Operation states](crate::workflow::definition::OperationState) have no execution
+    /// building block; runtimes must perform their actions themselves.
+    OperationStates,
+
+    /// [ForEach states](crate::workflow::definition::ForEachState) have no execution
+    /// building block.
+    ForEachStates,
+
+    /// [Callback states](crate::workflow::definition::CallbackState) have no execution
+    /// building block.
+    CallbackStates,
+
+    /// Actions calling [sub-workflows](Action::sub_flow_ref) are not performed.
+    SubflowActions,
+
+    /// Actions producing [events](Action::event_ref) are not performed.
+    EventActions,
+
+    /// Actions are not [retried](Action::retry_ref) when they fail.
+    ActionRetries,
+
+    /// Events [produced](crate::workflow::definition::ProduceEventDef) by transitions and ends
+    /// are not produced.
+    ProducedEvents,
+}
+
+impl Feature {
+    /// Returns the feature's stable identifier (e.g. `subflow-actions`).
+    ///
+    /// Identifiers never change, so they can be used to track features across versions of
+    /// this crate.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::OperationStates => "operation-states",
+            Self::ForEachStates => "foreach-states",
+            Self::CallbackStates => "callback-states",
+            Self::SubflowActions => "subflow-actions",
+            Self::EventActions => "event-actions",
+            Self::ActionRetries => "action-retries",
+            Self::ProducedEvents => "produced-events",
+        }
+    }
+
+    /// Returns a description of the feature's limitations in this crate.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::OperationStates => "operation states must be executed by the runtime",
+            Self::ForEachStates => "foreach states must be executed by the runtime",
+            Self::CallbackStates => "callback states must be executed by the runtime",
+            Self::SubflowActions => "actions calling sub-workflows are not supported",
+            Self::EventActions => "actions producing events are not supported",
+            Self::ActionRetries => "action retries must be handled by the runtime",
+            Self::ProducedEvents => "events must be produced by the runtime",
+        }
+    }
+
+    /// Returns the URL of the issues tracking the feature's implementation.
+    ///
+    /// The URL searches the issue tracker for the feature's [identifier](Self::id).
+    pub fn tracking_url(&self) -> String {
+        format!("{}?q=is%3Aissue+%22{}%22", ISSUES_URL, self.id())
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// Use of a partially-implemented [`Feature`] in a workflow definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompatibilityIssue {
+    /// Partially-implemented feature
+    pub feature: Feature,
+
+    /// JSON pointer to the element using the feature in the workflow definition
+    /// (e.g. `/states/2`)
+    pub pointer: String,
+
+    /// Message describing the limitation
+    pub message: String,
+}
+
+impl fmt::Display for CompatibilityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.pointer, self.message)
+    }
+}
+
+/// Partially-implemented features used by a workflow definition.
+///
+/// See [`WorkflowDefinition::compatibility_report`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CompatibilityReport {
+    /// Uses of partially-implemented features, in the order the elements using them are defined
+    pub issues: Vec<CompatibilityIssue>,
+}
+
+impl CompatibilityReport {
+    /// Returns `true` if the workflow definition uses no partially-implemented feature.
+    pub fn is_fully_supported(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Returns the distinct partially-implemented features used by the workflow definition.
+    pub fn features(&self) -> BTreeSet<Feature> {
+        self.issues.iter().map(|issue| issue.feature).collect()
+    }
+
+    /// Returns the uses of the given feature.
+    pub fn with_feature(&self, feature: Feature) -> impl Iterator<Item = &CompatibilityIssue> + '_ {
+        self.issues
+            .iter()
+            .filter(move |issue| issue.feature == feature)
+    }
+}
+
+pub(crate) fn compatibility_report(definition: &WorkflowDefinition) -> CompatibilityReport {
+    let mut report = CompatibilityReport::default();
+    let mut issue = |feature: Feature, pointer: String, subject: &str| {
+        report.issues.push(CompatibilityIssue {
+            feature,
+            pointer,
+            message: format!("{}: {}", subject, feature.description()),
+        });
+    };
+
+    for (i, state) in definition.states.iter().enumerate() {
+        let pointer = format!("/states/{}", i);
+        let subject = format!("state '{}'", state.name());
+        let mut features = BTreeSet::new();
+
+        match state {
+            State::Operation(_) => features.insert(Feature::OperationStates),
+            State::ForEach(_) => features.insert(Feature::ForEachStates),
+            State::Callback(_) => features.insert(Feature::CallbackStates),
+            _ => false,
+        };
+        for action in state.actions() {
+            features.extend(action_features(definition, action));
+        }
+        for transition in transitions(state) {
+            if let Transition::Complex { produce_events, .. } = transition {
+                if produce_events
+                    .as_ref()
+                    .is_some_and(|events| !events.is_empty())
+                {
+                    features.insert(Feature::ProducedEvents);
+                }
+            }
+        }
+        for end in state.ends() {
+            if let End::Complex { produce_events, .. } = end {
+                if produce_events
+                    .as_ref()
+                    .is_some_and(|events| !events.is_empty())
+                {
+                    features.insert(Feature::ProducedEvents);
+                }
+            }
+        }
+
+        for feature in features {
+            issue(feature, pointer.clone(), &subject);
+        }
+    }
+
+    report
+}
+
+fn action_features(definition: &WorkflowDefinition, action: &Action) -> Vec<Feature> {
+    let mut features = Vec::new();
+    if action.sub_flow_ref.is_some() {
+        features.push(Feature::SubflowActions);
+    }
+    if action.event_ref.is_some() {
+        features.push(Feature::EventActions);
+    }
+    let retries = action.retry_ref.is_some()
+        || action
+            .retryable_errors
+            .as_ref()
+            .is_some_and(|errors| !errors.is_empty());
+    if retries || (definition.auto_retries && action.function_ref.is_some()) {
+        features.push(Feature::ActionRetries);
+    }
+    features
+}
+
+// Returns all transitions of a state, including transitions of switch conditions
+// and error handlers.
+fn transitions(state: &State) -> Vec<&Transition> {
+    let transition = match state {
+        State::Sleep(state) => &state.transition,
+        State::Event(state) => &state.transition,
+        State::Operation(state) => &state.transition,
+        State::Parallel(state) => &state.transition,
+        State::Switch(SwitchState::DataBased(state)) => &state.default_condition.transition,
+        State::Switch(SwitchState::EventBased(state)) => &state.default_condition.transition,
+        State::Inject(state) => &state.transition,
+        State::ForEach(state) => &state.transition,
+        State::Callback(state) => &state.transition,
+        State::Custom(state) => &state.transition,
+    };
+    let conditions: Vec<&Transition> = match state {
+        State::Switch(SwitchState::DataBased(state)) => state
+            .data_conditions
+            .iter()
+            .filter_map(|condition| match condition {
+                DataCondition::Transition(condition) => Some(&condition.transition),
+                DataCondition::End(_) => None,
+            })
+            .collect(),
+        State::Switch(SwitchState::EventBased(state)) => state
+            .event_conditions
+            .iter()
+            .filter_map(|condition| match condition {
+                EventCondition::Transition(condition) => Some(&condition.transition),
+                EventCondition::End(_) => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    transition
+        .iter()
+        .chain(conditions)
+        .chain(
+            state
+                .on_errors()
+                .iter()
+                .filter_map(|error| error.transition.as_ref()),
+        )
+        .collect()
+}
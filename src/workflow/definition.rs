@@ -1,6 +1,17 @@
 //! Serverless Workflow specification - workflow schema
 //!
 //! Corresponding JSON schema: [workflow.json](https://github.com/serverlessworkflow/specification/blob/v0.8/schema/workflow.json).
+//!
+//! When the `schemars` feature is enabled, definition types implement [`JsonSchema`], so a JSON
+//! schema can be generated from this model (for example via [`schema_for!`]).
+//!
+//! When the `arbitrary` feature is enabled, definition types implement [`Arbitrary`], so
+//! random workflow definitions can be generated for fuzzing or property-based testing. Generated
+//! definitions can be serialized and deserialized, but are usually not valid.
+//!
+//! [`JsonSchema`]: https://docs.rs/schemars/0.8/schemars/trait.JsonSchema.html
+//! [`schema_for!`]: https://docs.rs/schemars/0.8/schemars/macro.schema_for.html
+//! [`Arbitrary`]: https://docs.rs/arbitrary/1/arbitrary/trait.Arbitrary.html
 
 pub mod auth;
 pub mod common;
@@ -8,40 +19,65 @@ pub(crate) mod detail;
 pub mod errors;
 pub mod events;
 pub mod functions;
+pub mod intervals;
 pub mod retries;
 pub mod secrets;
 pub mod timeouts;
 
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::str::FromStr;
+use std::time::Duration;
 
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use chrono::{DateTime, FixedOffset, TimeDelta, TimeZone};
+use chrono_tz::Tz;
+use indexmap::IndexMap;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
 use url::Url;
 
+use crate::cache::DefinitionCache;
 #[cfg(feature = "validate")]
 use crate::detail::garde::{
     must_be, one_of_three_must_be_set, one_of_two_must_be_set, unique_values,
 };
 use crate::detail::{all_of, false_value, jq, parallel, sequential, sync, terminate, true_value};
+use crate::workflow::compatibility::{compatibility_report, CompatibilityReport};
 use crate::workflow::definition::auth::Auth;
 use crate::workflow::definition::common::{
-    ExecutionMode, InvocationMode, Metadata, NonNegativeNumber,
+    ExecutionMode, Extensions, InvocationMode, Metadata, NonNegativeNumber,
+};
+#[cfg(feature = "arbitrary")]
+use crate::workflow::definition::detail::arbitrary::{
+    arbitrary_custom_state_properties, arbitrary_custom_state_type, arbitrary_url, arbitrary_values,
 };
+use crate::workflow::definition::detail::external::resolve_optional_external_ref;
+use crate::workflow::definition::detail::fingerprint::fingerprint;
 #[cfg(feature = "validate")]
-use crate::workflow::definition::detail::garde::if_not_used_for_compensation_then_must_have_transition_or_end;
-use crate::workflow::definition::errors::Errors;
+use crate::workflow::definition::detail::garde::{
+    if_not_used_for_compensation_then_must_have_transition_or_end, must_be_valid_cron_expression,
+    must_be_valid_optional_date_time, must_be_valid_optional_recurring_interval,
+    must_be_valid_optional_timezone, must_be_valid_recurring_interval,
+};
+use crate::workflow::definition::detail::minify::minify;
+use crate::workflow::definition::detail::normalize::Normalize;
+use crate::workflow::definition::errors::{ErrorDef, Errors};
 use crate::workflow::definition::events::Events;
 use crate::workflow::definition::functions::Functions;
-use crate::workflow::definition::retries::Retries;
+use crate::workflow::definition::intervals::RecurringInterval;
+use crate::workflow::definition::retries::{Retries, RetryDef};
 use crate::workflow::definition::secrets::Secrets;
 use crate::workflow::definition::timeouts::{
-    ActionExecTimeout, BranchExecTimeout, EventTimeout, StateExecTimeout, Timeouts,
+    parse_duration, ActionExecTimeout, BranchExecTimeout, EventTimeout, StateExecTimeout, Timeouts,
     WorkflowExecTimeout,
 };
+use crate::workflow::migration::{migrate_to_v1, V1Migration};
 
 /// Workflow definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkflowDefinition {
     /// Workflow unique identifier
@@ -49,6 +85,11 @@ pub struct WorkflowDefinition {
     #[cfg_attr(feature = "validate", garde(dive))]
     pub identifier: Identifier,
 
+    /// Workflow name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(length(min = 1)))]
+    pub name: Option<String>,
+
     /// Workflow description
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "validate", garde(skip))]
@@ -142,6 +183,11 @@ pub struct WorkflowDefinition {
     /// State definitions
     #[cfg_attr(feature = "validate", garde(dive, length(min = 1)))]
     pub states: Vec<State>,
+
+    /// Extension properties
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub extensions: Extensions,
 }
 
 impl WorkflowDefinition {
@@ -169,11 +215,240 @@ impl WorkflowDefinition {
             .map(StartDef::state_name)
             .or_else(|| self.states.first().map(State::name))
     }
+
+    /// Returns a copy of this workflow definition where all external references have been resolved.
+    ///
+    /// Top-level sections of a workflow definition ([`functions`], [`events`], [`errors`],
+    /// [`retries`], [`auth`], [`secrets`], [`timeouts`] and [`constants`]) can either be specified
+    /// inline or as a URI pointing to an external resource. This method fetches all such resources
+    /// (via the given [`DefinitionCache`]) and returns a workflow definition where all sections are
+    /// specified inline.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by [`DefinitionCache::get_or_insert`], in addition to:
+    ///
+    /// * [`CircularExternalReference`]: an external resource refers back to itself, directly or indirectly
+    ///
+    /// [`functions`]: Self::functions
+    /// [`events`]: Self::events
+    /// [`errors`]: Self::errors
+    /// [`retries`]: Self::retries
+    /// [`auth`]: Self::auth
+    /// [`secrets`]: Self::secrets
+    /// [`timeouts`]: Self::timeouts
+    /// [`constants`]: Self::constants
+    /// [`CircularExternalReference`]: crate::Error::CircularExternalReference
+    pub fn resolve_external_refs(&self, cache: &mut DefinitionCache) -> crate::Result<Self> {
+        Ok(Self {
+            functions: resolve_optional_external_ref(&self.functions, cache)?,
+            events: resolve_optional_external_ref(&self.events, cache)?,
+            errors: resolve_optional_external_ref(&self.errors, cache)?,
+            retries: resolve_optional_external_ref(&self.retries, cache)?,
+            auth: resolve_optional_external_ref(&self.auth, cache)?,
+            secrets: resolve_optional_external_ref(&self.secrets, cache)?,
+            timeouts: resolve_optional_external_ref(&self.timeouts, cache)?,
+            constants: resolve_optional_external_ref(&self.constants, cache)?,
+            ..self.clone()
+        })
+    }
+
+    /// Converts the workflow definition to version 1.0 of the DSL.
+    ///
+    /// The conversion is best-effort: constructs that have no equivalent in the DSL 1.0 (or that
+    /// can only be approximated) are reported in the returned [`V1Migration`]'s [`issues`].
+    /// Definitions referenced by URI are not loaded; call [`resolve_external_refs`] first
+    /// to migrate them as well.
+    ///
+    /// Note that the data flow of the DSL 1.0 differs from v0.8's: task output replaces
+    /// the data passed to the next task instead of being merged into the state data.
+    ///
+    /// [`issues`]: V1Migration::issues
+    /// [`resolve_external_refs`]: Self::resolve_external_refs
+    pub fn migrate_to_v1(&self) -> V1Migration {
+        migrate_to_v1(self)
+    }
+
+    /// Returns the spec features used by the workflow definition that this crate only
+    /// partially implements.
+    ///
+    /// Each [feature](crate::workflow::compatibility::Feature) has a stable identifier and
+    /// a [URL](crate::workflow::compatibility::Feature::tracking_url) listing the issues tracking
+    /// its implementation, so users can decide up front whether the crate can run the workflow.
+    pub fn compatibility_report(&self) -> CompatibilityReport {
+        compatibility_report(self)
+    }
+
+    /// Returns a copy of the workflow definition where all shorthand forms are expanded.
+    ///
+    /// For example, [`Transition::ByName`] is replaced by an equivalent [`Transition::Complex`],
+    /// [`End::Simple`] by an [`End::Complex`], [`FunctionRef::ByName`] by a [`FunctionRef::Complex`],
+    /// etc., with the spec defaults filled in explicitly. The start state is also set explicitly
+    /// when it is omitted. This way, consumers of the normalized definition only need to handle
+    /// one representation of each construct.
+    ///
+    /// Optional collections that are empty (e.g. `"onErrors": []`) are equivalent to omitted ones
+    /// and are normalized to `None`. Note that when the `validate` feature is enabled, empty
+    /// collections are rejected during validation if the specification requires at least one
+    /// element (e.g. [`annotations`](Self::annotations)).
+    ///
+    /// Definitions referenced by URI are left as-is; call [`resolve_external_refs`] first
+    /// to inline them.
+    ///
+    /// [`resolve_external_refs`]: Self::resolve_external_refs
+    pub fn normalize(&self) -> Self {
+        let mut definition = self.clone();
+        Normalize::normalize(&mut definition);
+        definition
+    }
+
+    /// Serializes the workflow definition in its most compact form.
+    ///
+    /// This is the inverse of [`normalize`]: complex forms are collapsed to their shorthand
+    /// equivalent whenever possible (e.g. a [`Transition::Complex`] with only a `nextState`
+    /// is written as a [`Transition::ByName`]) and values equal to the spec defaults are omitted.
+    /// The resulting document is equivalent to the workflow definition, but smaller to store
+    /// or transport.
+    ///
+    /// # Errors
+    ///
+    /// [`JsonConversionFailed`] if the workflow definition cannot be serialized.
+    ///
+    /// [`normalize`]: Self::normalize
+    /// [`JsonConversionFailed`]: crate::Error::JsonConversionFailed
+    pub fn minify(&self) -> crate::Result<Value> {
+        minify(self)
+    }
+
+    /// Returns a stable hash of the workflow definition's content.
+    ///
+    /// The hash is computed on the [normalized] definition, so two definitions that only differ
+    /// in the use of shorthand forms or in the order of their properties have the same
+    /// fingerprint. The hashing algorithm does not depend on the platform or Rust version,
+    /// so fingerprints can be persisted, for example to use them as cache keys, to detect
+    /// changes or to record which revision of a definition a workflow instance ran against.
+    ///
+    /// Definitions referenced by URI are not loaded; call [`resolve_external_refs`] first
+    /// to include their content in the fingerprint.
+    ///
+    /// # Errors
+    ///
+    /// [`JsonConversionFailed`] if the workflow definition cannot be serialized.
+    ///
+    /// [normalized]: Self::normalize
+    /// [`resolve_external_refs`]: Self::resolve_external_refs
+    /// [`JsonConversionFailed`]: crate::Error::JsonConversionFailed
+    pub fn fingerprint(&self) -> crate::Result<u64> {
+        fingerprint(self)
+    }
+
+    /// Returns the error definition referred to by the given workflow error name, if any.
+    ///
+    /// Errors are matched by name or by [code](ErrorDef::code); see [`ErrorDef::matches`].
+    /// Error definitions specified as a URI are not loaded; use [`resolve_external_refs`]
+    /// first to resolve them.
+    ///
+    /// [`resolve_external_refs`]: Self::resolve_external_refs
+    pub fn error_def(&self, error: &str) -> Option<&ErrorDef> {
+        match &self.errors {
+            Some(Errors::Inlined(errors)) => errors.iter().find(|def| def.matches(error)),
+            _ => None,
+        }
+    }
+
+    /// Returns the retry definition with the given name, if any.
+    ///
+    /// Retry definitions specified as a URI are not loaded; use [`resolve_external_refs`]
+    /// first to resolve them.
+    ///
+    /// [`resolve_external_refs`]: Self::resolve_external_refs
+    pub fn retry_def(&self, name: &str) -> Option<&RetryDef> {
+        match &self.retries {
+            Some(Retries::Inline(retries)) => retries.iter().find(|retry| retry.name == name),
+            _ => None,
+        }
+    }
+}
+
+/// Header of a workflow definition
+///
+/// Contains the properties identifying a workflow definition, without its states or any other
+/// part needed to execute it. Headers can be loaded from workflow definition files quickly via
+/// [`DefinitionLoader::load_header`], for example to index a large catalog of definitions.
+///
+/// [`DefinitionLoader::load_header`]: crate::loader::DefinitionLoader::load_header
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase", from = "HeaderFields")]
+pub struct WorkflowHeader {
+    /// Workflow unique identifier
+    #[serde(flatten)]
+    pub identifier: Identifier,
+
+    /// Workflow name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Workflow version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// Serverless Workflow schema version
+    pub spec_version: String,
+
+    /// List of helpful terms describing the workflows intended purpose, subject areas, or other important qualities
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Vec<String>>,
+}
+
+impl From<&WorkflowDefinition> for WorkflowHeader {
+    fn from(value: &WorkflowDefinition) -> Self {
+        Self {
+            identifier: value.identifier.clone(),
+            name: value.name.clone(),
+            version: value.version.clone(),
+            spec_version: value.spec_version.clone(),
+            annotations: value.annotations.clone(),
+        }
+    }
+}
+
+// Flat mirror of `WorkflowHeader` used to deserialize it. Deserializing a flattened field would
+// buffer every other property of the workflow definition (including its states).
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HeaderFields {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    key: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    spec_version: String,
+    #[serde(default)]
+    annotations: Option<Vec<String>>,
+}
+
+impl From<HeaderFields> for WorkflowHeader {
+    fn from(value: HeaderFields) -> Self {
+        Self {
+            identifier: Identifier { id: value.id, key: value.key },
+            name: value.name,
+            version: value.version,
+            spec_version: value.spec_version,
+            annotations: value.annotations,
+        }
+    }
 }
 
 /// Workflow identifier
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Identifier {
     /// Workflow unique identifier
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -210,8 +485,10 @@ impl Identifier {
 }
 
 /// JSON Schema used to validate the workflow data input
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged, deny_unknown_fields)]
 pub enum DataInputSchema {
     /// URI of the JSON Schema used to validate the workflow data input
@@ -231,25 +508,54 @@ pub enum DataInputSchema {
     },
 }
 
+impl DataInputSchema {
+    /// Returns the URI of the JSON Schema used to validate the workflow data input.
+    pub fn schema(&self) -> &str {
+        match self {
+            Self::UriOnly(schema) => schema.as_str(),
+            Self::Full { schema, .. } => schema.as_str(),
+        }
+    }
+
+    /// Returns whether workflow execution should fail if there are validation errors.
+    ///
+    /// If `self` is [`UriOnly`](Self::UriOnly), this returns the default value (`true`).
+    pub fn fail_on_validation_errors(&self) -> bool {
+        match self {
+            Self::UriOnly(_) => true,
+            Self::Full { fail_on_validation_errors, .. } => *fail_on_validation_errors,
+        }
+    }
+}
+
 /// Workflow constants
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum Constants {
     /// URI to a resource containing constants data (json or yaml)
-    One(#[cfg_attr(feature = "validate", garde(skip))] Url),
+    One(
+        #[cfg_attr(feature = "validate", garde(skip))]
+        #[cfg_attr(feature = "arbitrary", arbitrary(with = arbitrary_url))]
+        Url,
+    ),
 
     /// Workflow constants data (object type)
     Multiple {
         #[serde(flatten)]
         #[cfg_attr(feature = "validate", garde(skip))]
-        constants: HashMap<String, Value>,
+        #[cfg_attr(feature = "arbitrary", arbitrary(with = arbitrary_values))]
+        constants: IndexMap<String, Value>,
     },
 }
 
 /// Sleep time definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Sleep {
     /// Amount of time (ISO 8601 duration format) to sleep before function/subflow invocation. Does not apply if 'eventRef' is defined.
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -262,31 +568,269 @@ pub struct Sleep {
     after: Option<String>,
 }
 
+impl Sleep {
+    /// Returns the amount of time (ISO 8601 duration format) to sleep before function/subflow invocation.
+    pub fn before(&self) -> Option<&str> {
+        self.before.as_deref()
+    }
+
+    /// Returns the amount of time (ISO 8601 duration format) to sleep after function/subflow invocation.
+    pub fn after(&self) -> Option<&str> {
+        self.after.as_deref()
+    }
+
+    /// Returns the amount of time to sleep before function/subflow invocation, if specified.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidDuration`]: the duration is not a valid ISO 8601 duration
+    /// * [`UnsupportedDuration`]: the duration is calendar-dependent or too large
+    ///
+    /// [`InvalidDuration`]: crate::Error::InvalidDuration
+    /// [`UnsupportedDuration`]: crate::Error::UnsupportedDuration
+    pub fn before_as_duration(&self) -> crate::Result<Option<Duration>> {
+        self.before().map(parse_duration).transpose()
+    }
+
+    /// Returns the amount of time to sleep after function/subflow invocation, if specified.
+    ///
+    /// # Errors
+    ///
+    /// See [`before_as_duration`](Self::before_as_duration).
+    pub fn after_as_duration(&self) -> crate::Result<Option<Duration>> {
+        self.after().map(parse_duration).transpose()
+    }
+}
+
 /// Cron definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged, deny_unknown_fields)]
 pub enum CronDef {
     /// Cron expression defining when workflow instances should be created (automatically)
-    Expr(#[cfg_attr(feature = "validate", garde(length(min = 1)))] String),
+    Expr(
+        #[cfg_attr(
+            feature = "validate",
+            garde(length(min = 1), custom(must_be_valid_cron_expression))
+        )]
+        String,
+    ),
 
     /// Repeating cron definition
     #[serde(rename_all = "camelCase")]
     Repeat {
         /// Repeating interval (cron expression) describing when the workflow instance should be created
-        #[cfg_attr(feature = "validate", garde(length(min = 1)))]
+        #[cfg_attr(
+            feature = "validate",
+            garde(length(min = 1), custom(must_be_valid_cron_expression))
+        )]
         expression: String,
 
         /// Specific date and time (ISO 8601 format) when the cron expression invocation is no longer valid
         #[serde(default, skip_serializing_if = "Option::is_none")]
-        #[cfg_attr(feature = "validate", garde(skip))]
+        #[cfg_attr(feature = "validate", garde(custom(must_be_valid_optional_date_time)))]
         valid_until: Option<String>,
     },
 }
 
+impl CronDef {
+    /// Returns the cron expression.
+    pub fn expression(&self) -> &str {
+        match self {
+            Self::Expr(expression) => expression.as_str(),
+            Self::Repeat { expression, .. } => expression.as_str(),
+        }
+    }
+
+    /// Returns the date and time when the cron expression invocation is no longer valid.
+    ///
+    /// Will return `Ok(None)` if the cron definition does not have an end of validity.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidDateTime`]: [`Repeat::valid_until`] is not a valid date and time
+    ///
+    /// [`InvalidDateTime`]: crate::Error::InvalidDateTime
+    /// [`Repeat::valid_until`]: Self::Repeat::valid_until
+    pub fn valid_until(&self) -> crate::Result<Option<DateTime<FixedOffset>>> {
+        match self {
+            Self::Expr(_) => Ok(None),
+            Self::Repeat { valid_until, .. } => valid_until
+                .as_deref()
+                .map(|valid_until| Ok(DateTime::parse_from_rfc3339(valid_until)?))
+                .transpose(),
+        }
+    }
+
+    /// Returns the amount of time remaining before the cron definition's [end of validity], as of `now`.
+    ///
+    /// * If the cron definition does not have an end of validity, returns `Ok(None)`.
+    /// * If the end of validity has been reached, returns a zero [`TimeDelta`].
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidDateTime`]: the cron definition's end of validity is not a valid date and time
+    ///
+    /// [end of validity]: Self::valid_until
+    /// [`InvalidDateTime`]: crate::Error::InvalidDateTime
+    pub fn remaining_validity<Z>(&self, now: &DateTime<Z>) -> crate::Result<Option<TimeDelta>>
+    where
+        Z: TimeZone,
+    {
+        Ok(self.valid_until()?.map(|valid_until| {
+            valid_until
+                .signed_duration_since(now)
+                .max(TimeDelta::zero())
+        }))
+    }
+
+    /// Returns whether the cron definition's [end of validity] has been reached, as of `now`.
+    ///
+    /// Once a cron definition has expired, no more workflow instances should be created for it.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidDateTime`]: the cron definition's end of validity is not a valid date and time
+    ///
+    /// [end of validity]: Self::valid_until
+    /// [`InvalidDateTime`]: crate::Error::InvalidDateTime
+    pub fn has_expired<Z>(&self, now: &DateTime<Z>) -> crate::Result<bool>
+    where
+        Z: TimeZone,
+    {
+        Ok(self
+            .remaining_validity(now)?
+            .is_some_and(|remaining| remaining.is_zero()))
+    }
+
+    /// Parses the cron expression and returns the corresponding [`cron::Schedule`].
+    ///
+    /// Cron expressions can include 6 or 7 fields (seconds, minutes, hours, day of month,
+    /// month, day of week and optional year); in such expressions, days of the week are numbered
+    /// from `1` (Sunday) to `7` (Saturday). Standard 5-field cron expressions (without seconds)
+    /// are also accepted; in such a case, the seconds are assumed to be `0` and days of the week
+    /// use the standard numbering, from `0` (Sunday) to `6` (Saturday), `7` also meaning Sunday.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidCronExpression`]: the cron expression could not be parsed
+    ///
+    /// [`InvalidCronExpression`]: crate::Error::InvalidCronExpression
+    pub fn schedule(&self) -> crate::Result<cron::Schedule> {
+        parse_cron_expression(self.expression())
+    }
+
+    /// Returns the next (at most) `n` occurrences of the cron expression after the given date and time.
+    ///
+    /// Occurrences at or after the cron definition's [end of validity] are not returned, so the
+    /// returned [`Vec`] can contain less than `n` items.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidCronExpression`]: the cron expression could not be parsed
+    /// * [`InvalidDateTime`]: the cron definition's [end of validity] is not a valid date and time
+    ///
+    /// [end of validity]: Self::valid_until
+    /// [`InvalidCronExpression`]: crate::Error::InvalidCronExpression
+    /// [`InvalidDateTime`]: crate::Error::InvalidDateTime
+    pub fn next_occurrences<Z>(
+        &self,
+        after: &DateTime<Z>,
+        n: usize,
+    ) -> crate::Result<Vec<DateTime<Z>>>
+    where
+        Z: TimeZone,
+    {
+        let schedule = self.schedule()?;
+        let valid_until = self.valid_until()?;
+
+        Ok(schedule
+            .after(after)
+            .take_while(|occurrence| match valid_until {
+                Some(valid_until) => *occurrence < valid_until,
+                None => true,
+            })
+            .take(n)
+            .collect())
+    }
+}
+
+pub(crate) fn parse_cron_expression(expression: &str) -> crate::Result<cron::Schedule> {
+    // The `cron` crate requires a seconds field, which standard cron expressions do not have.
+    // It also numbers days of the week from 1 (Sunday) to 7 (Saturday), whereas standard cron
+    // numbers them from 0 (Sunday) to 6 (Saturday), with 7 also meaning Sunday.
+    let fields: Vec<_> = expression.split_whitespace().collect();
+    let expression = match fields.as_slice() {
+        [minutes, hours, days_of_month, months, days_of_week] => Cow::Owned(format!(
+            "0 {} {} {} {} {}",
+            minutes,
+            hours,
+            days_of_month,
+            months,
+            standard_days_of_week(days_of_week)
+        )),
+        _ => Cow::Borrowed(expression),
+    };
+
+    Ok(cron::Schedule::from_str(&expression)?)
+}
+
+// Converts the day of week field of a standard cron expression to the numbering used by the
+// `cron` crate. Names (e.g. `MON-FRI`) and values that cannot be converted are left untouched,
+// so that the `cron` crate can report them.
+fn standard_days_of_week(field: &str) -> Cow<'_, str> {
+    let convert = |item: &str| -> Option<Vec<u32>> {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, step.parse().ok().filter(|step| *step > 0)?),
+            None => (item, 1),
+        };
+        let (first, last) = match range.split_once('-') {
+            _ if range == "*" => (0, 6),
+            Some((first, last)) => (first.parse().ok()?, last.parse().ok()?),
+            None if item.contains('/') => (range.parse().ok()?, 6),
+            None => (range.parse().ok()?, range.parse().ok()?),
+        };
+        if first > last || last > 7 {
+            return None;
+        }
+
+        Some(
+            (first..=last)
+                .step_by(step)
+                .map(|day| day % 7 + 1)
+                .collect(),
+        )
+    };
+
+    let items: Vec<_> = field.split(',').map(|item| (item, convert(item))).collect();
+    if items.iter().all(|(_, days)| days.is_none()) {
+        return Cow::Borrowed(field);
+    }
+
+    let mut days = std::collections::BTreeSet::new();
+    let mut others = Vec::new();
+    for (item, converted) in items {
+        match converted {
+            Some(converted) => days.extend(converted),
+            None => others.push(item.to_string()),
+        }
+    }
+    Cow::Owned(
+        days.into_iter()
+            .map(|day| day.to_string())
+            .chain(others)
+            .collect::<Vec<_>>()
+            .join(","),
+    )
+}
+
 /// "Continue as" definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum ContinueAsDef {
     Simple(#[cfg_attr(feature = "validate", garde(length(min = 1)))] String),
@@ -317,11 +861,31 @@ pub enum ContinueAsDef {
     },
 }
 
+impl ContinueAsDef {
+    /// Returns the ID of the workflow to continue execution as.
+    pub fn workflow_id(&self) -> &str {
+        match self {
+            Self::Simple(workflow_id) => workflow_id,
+            Self::WithData { workflow_id, .. } => workflow_id,
+        }
+    }
+
+    /// Returns the version of the workflow to continue execution as, if specified.
+    pub fn version(&self) -> Option<&str> {
+        match self {
+            Self::Simple(_) => None,
+            Self::WithData { version, .. } => version.as_deref(),
+        }
+    }
+}
+
 /// Data configuration
 ///
 /// Determines how to pass data to an event or workflow.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum Data {
     /// An expression which selects parts of the state's data to pass to the event or workflow.
@@ -331,13 +895,16 @@ pub enum Data {
     Object {
         #[serde(flatten)]
         #[cfg_attr(feature = "validate", garde(skip))]
-        fields: HashMap<String, Value>,
+        #[cfg_attr(feature = "arbitrary", arbitrary(with = arbitrary_values))]
+        fields: IndexMap<String, Value>,
     },
 }
 
 /// Transition definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged, deny_unknown_fields)]
 pub enum Transition {
     /// Name of state to transition to
@@ -362,9 +929,29 @@ pub enum Transition {
     },
 }
 
+impl Transition {
+    /// Returns the name of the state to transition to.
+    pub fn next_state(&self) -> &str {
+        match self {
+            Self::ByName(next_state) => next_state,
+            Self::Complex { next_state, .. } => next_state,
+        }
+    }
+
+    /// Returns `true` if workflow compensation must be performed before the transition is taken.
+    pub fn compensate(&self) -> bool {
+        match self {
+            Self::ByName(_) => false,
+            Self::Complex { compensate, .. } => *compensate,
+        }
+    }
+}
+
 /// Error definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct Error {
     /// Reference to a unique workflow error definition. Used of errorRefs is not used
@@ -388,9 +975,33 @@ pub struct Error {
     pub end: Option<End>,
 }
 
+impl Error {
+    /// Returns the names of the errors handled by this error handler.
+    pub fn error_names(&self) -> Vec<&str> {
+        self.error_ref
+            .iter()
+            .chain(self.error_refs.iter().flatten())
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Returns `true` if this error handler handles [all errors](WILDCARD_ERROR).
+    pub fn is_wildcard(&self) -> bool {
+        self.error_names().contains(&WILDCARD_ERROR)
+    }
+}
+
+/// Name of the error matching all errors.
+///
+/// An [error handler](Error) referencing this error is only used if no other handler
+/// references the error that occurred; see [`State::error_handler`].
+pub const WILDCARD_ERROR: &str = "*";
+
 /// OnEvents definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct OnEvents {
     /// References one or more unique event names in the defined workflow events
@@ -414,9 +1025,11 @@ pub struct OnEvents {
 }
 
 /// Workflow action definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
 pub struct Action {
     /// Unique action identifier
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -484,11 +1097,18 @@ pub struct Action {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "validate", garde(length(min = 1)))]
     pub condition: Option<String>,
+
+    /// Extension properties
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub extensions: Extensions,
 }
 
 /// Function reference definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged, deny_unknown_fields)]
 pub enum FunctionRef {
     /// Name of the referenced function
@@ -518,18 +1138,53 @@ pub enum FunctionRef {
     },
 }
 
+impl FunctionRef {
+    /// Returns the name of the referenced function.
+    pub fn ref_name(&self) -> &str {
+        match self {
+            Self::ByName(ref_name) => ref_name,
+            Self::Complex { ref_name, .. } => ref_name,
+        }
+    }
+
+    /// Returns the arguments passed to the function, if any.
+    pub fn arguments(&self) -> Option<&FunctionArguments> {
+        match self {
+            Self::ByName(_) => None,
+            Self::Complex { arguments, .. } => arguments.as_ref(),
+        }
+    }
+
+    /// Returns the GraphQL selection set to request, if any.
+    ///
+    /// Only used if the referenced function is a [GraphQL function].
+    ///
+    /// [GraphQL function]: crate::workflow::definition::functions::FunctionType::GraphQL
+    pub fn selection_set(&self) -> Option<&str> {
+        match self {
+            Self::ByName(_) => None,
+            Self::Complex { selection_set, .. } => selection_set.as_deref(),
+        }
+    }
+}
+
 /// Arguments passed to a function
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FunctionArguments {
     #[serde(flatten)]
     #[cfg_attr(feature = "validate", garde(skip))]
-    pub arguments: HashMap<String, Value>,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arbitrary_values))]
+    pub arguments: IndexMap<String, Value>,
 }
 
 /// Event References
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct EventRef {
     /// Reference to the unique name of a 'produced' event definition
@@ -574,18 +1229,22 @@ pub struct EventRef {
 }
 
 /// Event context attributes
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ContextAttributes {
     /// Context attributes
     #[serde(flatten)]
     #[cfg_attr(feature = "validate", garde(skip))]
-    pub attributes: HashMap<String, String>,
+    pub attributes: IndexMap<String, String>,
 }
 
 /// Sub-workflow reference definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum SubflowRef {
     /// Unique id of the sub-workflow to be invoked
@@ -619,8 +1278,28 @@ pub enum SubflowRef {
     },
 }
 
+impl SubflowRef {
+    /// Returns the ID of the sub-workflow to invoke.
+    pub fn workflow_id(&self) -> &str {
+        match self {
+            Self::ById(workflow_id) => workflow_id,
+            Self::Complex { workflow_id, .. } => workflow_id,
+        }
+    }
+
+    /// Returns the version of the sub-workflow to invoke, if specified.
+    pub fn version(&self) -> Option<&str> {
+        match self {
+            Self::ById(_) => None,
+            Self::Complex { version, .. } => version.as_deref(),
+        }
+    }
+}
+
 /// "On complete" sub-workflow behavior
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 pub enum OnComplete {
     /// Sub-workflow should complete when parent workflow completes
@@ -631,8 +1310,10 @@ pub enum OnComplete {
 }
 
 /// Branch Definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub struct Branch {
     /// Branch name
@@ -650,8 +1331,10 @@ pub struct Branch {
 }
 
 /// [`Branch`]-specific timeouts
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct BranchTimeouts {
     /// Action exec timeout
@@ -666,8 +1349,10 @@ pub struct BranchTimeouts {
 }
 
 /// Possible workflow states
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(tag = "type", rename_all = "lowercase")]
 #[allow(clippy::large_enum_variant)] // All variants are similarly huge, so no big difference
 pub enum State {
@@ -694,6 +1379,88 @@ pub enum State {
 
     /// Callback state
     Callback(#[cfg_attr(feature = "validate", garde(dive))] CallbackState),
+
+    /// State of a type not defined in the specification
+    ///
+    /// Any state with an unknown `type` is deserialized as a custom state. However,
+    /// [`DefinitionLoader`] rejects custom states unless a [`CustomStateHandler`] has been
+    /// [registered](DefinitionLoader::custom_state) for their type.
+    ///
+    /// [`DefinitionLoader`]: crate::loader::DefinitionLoader
+    /// [`CustomStateHandler`]: crate::loader::CustomStateHandler
+    #[serde(untagged)]
+    Custom(#[cfg_attr(feature = "validate", garde(dive))] CustomState),
+}
+
+// Types of the states defined in the specification; states of any other type are `CustomState`s.
+const BUILT_IN_STATE_TYPES: [&str; 8] =
+    ["sleep", "event", "operation", "parallel", "switch", "inject", "foreach", "callback"];
+
+// Schema of a custom state's `type`, which cannot be one of the built-in types.
+#[cfg(feature = "schemars")]
+fn custom_state_type_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+    use schemars::schema::{InstanceType, SchemaObject, SubschemaValidation};
+
+    let built_in_types = SchemaObject {
+        enum_values: Some(BUILT_IN_STATE_TYPES.map(Value::from).into()),
+        ..Default::default()
+    };
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        subschemas: Some(Box::new(SubschemaValidation {
+            not: Some(Box::new(built_in_types.into())),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+// Workflow states defined in the specification. Used to deserialize `State`s.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+#[allow(clippy::large_enum_variant)] // Only used transiently
+enum BuiltInState {
+    Sleep(SleepState),
+    Event(EventState),
+    Operation(OperationState),
+    Parallel(ParallelState),
+    Switch(SwitchState),
+    Inject(InjectState),
+    ForEach(ForEachState),
+    Callback(CallbackState),
+}
+
+impl From<BuiltInState> for State {
+    fn from(value: BuiltInState) -> Self {
+        match value {
+            BuiltInState::Sleep(state) => Self::Sleep(state),
+            BuiltInState::Event(state) => Self::Event(state),
+            BuiltInState::Operation(state) => Self::Operation(state),
+            BuiltInState::Parallel(state) => Self::Parallel(state),
+            BuiltInState::Switch(state) => Self::Switch(state),
+            BuiltInState::Inject(state) => Self::Inject(state),
+            BuiltInState::ForEach(state) => Self::ForEach(state),
+            BuiltInState::Callback(state) => Self::Callback(state),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for State {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        let state = match value.get("type").and_then(Value::as_str) {
+            Some(state_type) if !BUILT_IN_STATE_TYPES.contains(&state_type) => {
+                CustomState::deserialize(value).map(Self::Custom)
+            },
+            _ => BuiltInState::deserialize(value).map(Into::into),
+        };
+
+        state.map_err(D::Error::custom)
+    }
 }
 
 impl State {
@@ -711,14 +1478,246 @@ impl State {
             Self::Inject(state) => state.name.as_str(),
             Self::ForEach(state) => state.name.as_str(),
             Self::Callback(state) => state.name.as_str(),
+            Self::Custom(state) => state.name.as_str(),
+        }
+    }
+
+    /// Returns `true` if the state is used to compensate another state.
+    ///
+    /// [Event states](Self::Event) and [custom states](Self::Custom) are never used
+    /// for compensation.
+    pub fn used_for_compensation(&self) -> bool {
+        match self {
+            Self::Sleep(state) => state.used_for_compensation,
+            Self::Operation(state) => state.used_for_compensation,
+            Self::Parallel(state) => state.used_for_compensation,
+            Self::Switch(SwitchState::DataBased(state)) => state.used_for_compensation,
+            Self::Switch(SwitchState::EventBased(state)) => state.used_for_compensation,
+            Self::Inject(state) => state.used_for_compensation,
+            Self::ForEach(state) => state.used_for_compensation,
+            Self::Callback(state) => state.used_for_compensation,
+            Self::Event(_) | Self::Custom(_) => false,
+        }
+    }
+
+    /// Returns the name of the state responsible for compensating this state, if any.
+    ///
+    /// [Custom states](Self::Custom) cannot be compensated.
+    pub fn compensated_by(&self) -> Option<&str> {
+        match self {
+            Self::Sleep(state) => state.compensated_by.as_deref(),
+            Self::Event(state) => state.compensated_by.as_deref(),
+            Self::Operation(state) => state.compensated_by.as_deref(),
+            Self::Parallel(state) => state.compensated_by.as_deref(),
+            Self::Switch(SwitchState::DataBased(state)) => state.compensated_by.as_deref(),
+            Self::Switch(SwitchState::EventBased(state)) => state.compensated_by.as_deref(),
+            Self::Inject(state) => state.compensated_by.as_deref(),
+            Self::ForEach(state) => state.compensated_by.as_deref(),
+            Self::Callback(state) => state.compensated_by.as_deref(),
+            Self::Custom(_) => None,
+        }
+    }
+
+    /// Returns the state's metadata, if any.
+    ///
+    /// [Custom states](Self::Custom) have no metadata.
+    pub fn metadata(&self) -> Option<&Metadata> {
+        match self {
+            Self::Sleep(state) => state.metadata.as_ref(),
+            Self::Event(state) => state.metadata.as_ref(),
+            Self::Operation(state) => state.metadata.as_ref(),
+            Self::Parallel(state) => state.metadata.as_ref(),
+            Self::Switch(SwitchState::DataBased(state)) => state.metadata.as_ref(),
+            Self::Switch(SwitchState::EventBased(state)) => state.metadata.as_ref(),
+            Self::Inject(state) => state.metadata.as_ref(),
+            Self::ForEach(state) => state.metadata.as_ref(),
+            Self::Callback(state) => state.metadata.as_ref(),
+            Self::Custom(_) => None,
+        }
+    }
+
+    /// Returns the state's data filter, if any.
+    ///
+    /// [Custom states](Self::Custom) have no data filter.
+    pub fn state_data_filter(&self) -> Option<&StateDataFilter> {
+        match self {
+            Self::Sleep(state) => state.state_data_filter.as_ref(),
+            Self::Event(state) => state.state_data_filter.as_ref(),
+            Self::Operation(state) => state.state_data_filter.as_ref(),
+            Self::Parallel(state) => state.state_data_filter.as_ref(),
+            Self::Switch(SwitchState::DataBased(state)) => state.state_data_filter.as_ref(),
+            Self::Switch(SwitchState::EventBased(state)) => state.state_data_filter.as_ref(),
+            Self::Inject(state) => state.state_data_filter.as_ref(),
+            Self::ForEach(state) => state.state_data_filter.as_ref(),
+            Self::Callback(state) => state.state_data_filter.as_ref(),
+            Self::Custom(_) => None,
+        }
+    }
+
+    /// Returns all actions defined in the state, including actions of [parallel branches]
+    /// and [event handlers].
+    ///
+    /// [parallel branches]: ParallelState::branches
+    /// [event handlers]: EventState::on_events
+    pub fn actions(&self) -> Vec<&Action> {
+        match self {
+            Self::Event(state) => state
+                .on_events
+                .iter()
+                .flat_map(|on_events| on_events.actions.iter().flatten())
+                .collect(),
+            Self::Operation(state) => state.actions.iter().collect(),
+            Self::Parallel(state) => state
+                .branches
+                .iter()
+                .flat_map(|branch| branch.actions.iter())
+                .collect(),
+            Self::ForEach(state) => state.actions.iter().collect(),
+            Self::Callback(state) => vec![&state.action],
+            Self::Sleep(_) | Self::Switch(_) | Self::Inject(_) | Self::Custom(_) => Vec::new(),
+        }
+    }
+
+    /// Returns all end definitions of the state, including ends of [switch conditions]
+    /// and [error handlers].
+    ///
+    /// [switch conditions]: DataBasedSwitchState::data_conditions
+    /// [error handlers]: OperationState::on_errors
+    pub fn ends(&self) -> Vec<&End> {
+        let end = match self {
+            Self::Sleep(state) => &state.end,
+            Self::Event(state) => &state.end,
+            Self::Operation(state) => &state.end,
+            Self::Parallel(state) => &state.end,
+            Self::Switch(SwitchState::DataBased(state)) => &state.default_condition.end,
+            Self::Switch(SwitchState::EventBased(state)) => &state.default_condition.end,
+            Self::Inject(state) => &state.end,
+            Self::ForEach(state) => &state.end,
+            Self::Callback(state) => &state.end,
+            Self::Custom(state) => &state.end,
+        };
+        let conditions: Vec<&End> = match self {
+            Self::Switch(SwitchState::DataBased(state)) => state
+                .data_conditions
+                .iter()
+                .filter_map(|condition| match condition {
+                    DataCondition::End(condition) => Some(&condition.end),
+                    DataCondition::Transition(_) => None,
+                })
+                .collect(),
+            Self::Switch(SwitchState::EventBased(state)) => state
+                .event_conditions
+                .iter()
+                .filter_map(|condition| match condition {
+                    EventCondition::End(condition) => Some(&condition.end),
+                    EventCondition::Transition(_) => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        end.iter()
+            .chain(conditions)
+            .chain(
+                self.on_errors()
+                    .iter()
+                    .filter_map(|error| error.end.as_ref()),
+            )
+            .collect()
+    }
+
+    /// Returns the state's error handlers, in the order they are defined.
+    ///
+    /// [Inject states](Self::Inject) and [custom states](Self::Custom) have no error handlers.
+    pub fn on_errors(&self) -> &[Error] {
+        let on_errors = match self {
+            Self::Sleep(state) => &state.on_errors,
+            Self::Event(state) => &state.on_errors,
+            Self::Operation(state) => &state.on_errors,
+            Self::Parallel(state) => &state.on_errors,
+            Self::Switch(SwitchState::DataBased(state)) => &state.on_errors,
+            Self::Switch(SwitchState::EventBased(state)) => &state.on_errors,
+            Self::ForEach(state) => &state.on_errors,
+            Self::Callback(state) => &state.on_errors,
+            Self::Inject(_) | Self::Custom(_) => &None,
+        };
+
+        on_errors.as_deref().unwrap_or_default()
+    }
+
+    /// Returns the error handler to use when the given error occurs in the state, if any.
+    ///
+    /// Handlers referencing the error by name take precedence over [wildcard] handlers;
+    /// if multiple handlers match, the first one defined wins.
+    ///
+    /// [wildcard]: WILDCARD_ERROR
+    pub fn error_handler(&self, error_name: &str) -> Option<&Error> {
+        let on_errors = self.on_errors();
+
+        on_errors
+            .iter()
+            .find(|error| error.error_names().contains(&error_name))
+            .or_else(|| on_errors.iter().find(|error| error.is_wildcard()))
+    }
+
+    /// Returns the state's execution timeout, if any.
+    ///
+    /// [Custom states](Self::Custom) have no timeouts.
+    pub fn state_exec_timeout(&self) -> Option<&StateExecTimeout> {
+        match self {
+            Self::Sleep(state) => state.timeouts.as_ref()?.state_exec_timeout.as_ref(),
+            Self::Event(state) => state.timeouts.as_ref()?.state_exec_timeout.as_ref(),
+            Self::Operation(state) => state.timeouts.as_ref()?.state_exec_timeout.as_ref(),
+            Self::Parallel(state) => state.timeouts.as_ref()?.state_exec_timeout.as_ref(),
+            Self::Switch(SwitchState::DataBased(state)) => {
+                state.timeouts.as_ref()?.state_exec_timeout.as_ref()
+            },
+            Self::Switch(SwitchState::EventBased(state)) => {
+                state.timeouts.as_ref()?.state_exec_timeout.as_ref()
+            },
+            Self::Inject(state) => state.timeouts.as_ref()?.state_exec_timeout.as_ref(),
+            Self::ForEach(state) => state.timeouts.as_ref()?.state_exec_timeout.as_ref(),
+            Self::Callback(state) => state.timeouts.as_ref()?.state_exec_timeout.as_ref(),
+            Self::Custom(_) => None,
+        }
+    }
+
+    /// Returns the execution timeout of the state's actions, if any.
+    ///
+    /// Only event, operation, foreach and callback states define an action execution timeout;
+    /// actions of [parallel branches](ParallelState::branches) use the
+    /// [timeouts of their branch](Branch::timeouts).
+    pub fn action_exec_timeout(&self) -> Option<&ActionExecTimeout> {
+        match self {
+            Self::Event(state) => state.timeouts.as_ref()?.action_exec_timeout.as_ref(),
+            Self::Operation(state) => state.timeouts.as_ref()?.action_exec_timeout.as_ref(),
+            Self::ForEach(state) => state.timeouts.as_ref()?.action_exec_timeout.as_ref(),
+            Self::Callback(state) => state.timeouts.as_ref()?.action_exec_timeout.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the state's event timeout, if any.
+    ///
+    /// Only event, event-based switch and callback states wait for events.
+    pub fn event_timeout(&self) -> Option<&EventTimeout> {
+        match self {
+            Self::Event(state) => state.timeouts.as_ref()?.event_timeout.as_ref(),
+            Self::Switch(SwitchState::EventBased(state)) => {
+                state.timeouts.as_ref()?.event_timeout.as_ref()
+            },
+            Self::Callback(state) => state.timeouts.as_ref()?.event_timeout.as_ref(),
+            _ => None,
         }
     }
 }
 
 /// Causes the workflow execution to sleep for a specified duration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
 pub struct SleepState {
     /// Unique State id
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -774,11 +1773,33 @@ pub struct SleepState {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "validate", garde(dive))]
     pub metadata: Option<Metadata>,
+
+    /// Extension properties
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub extensions: Extensions,
+}
+
+impl SleepState {
+    /// Returns the duration to sleep.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidDuration`]: the duration is not a valid ISO 8601 duration
+    /// * [`UnsupportedDuration`]: the duration is calendar-dependent or too large
+    ///
+    /// [`InvalidDuration`]: crate::Error::InvalidDuration
+    /// [`UnsupportedDuration`]: crate::Error::UnsupportedDuration
+    pub fn sleep_duration(&self) -> crate::Result<Duration> {
+        parse_duration(&self.duration)
+    }
 }
 
 /// [`SleepState`]-specific timeouts
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct SleepStateTimeouts {
     /// State exec timeout
@@ -788,9 +1809,11 @@ pub struct SleepStateTimeouts {
 }
 
 /// This state is used to wait for events from event sources, then consumes them and invoke one or more actions to run in sequence or parallel
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
 pub struct EventState {
     /// Unique State id
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -847,11 +1870,18 @@ pub struct EventState {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "validate", garde(dive))]
     pub metadata: Option<Metadata>,
+
+    /// Extension properties
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub extensions: Extensions,
 }
 
 /// [`EventState`]-specific timeouts
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct EventStateTimeouts {
     /// State exec timeout
@@ -871,9 +1901,11 @@ pub struct EventStateTimeouts {
 }
 
 /// Defines actions be performed. Does not wait for incoming events
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
 pub struct OperationState {
     /// Unique State id
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -934,11 +1966,18 @@ pub struct OperationState {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "validate", garde(dive))]
     pub metadata: Option<Metadata>,
+
+    /// Extension properties
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub extensions: Extensions,
 }
 
 /// [`OperationState`]-specific timeouts
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct OperationStateTimeouts {
     /// State exec timeout
@@ -953,9 +1992,11 @@ pub struct OperationStateTimeouts {
 }
 
 /// Consists of a number of states that are executed in parallel
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
 pub struct ParallelState {
     /// Unique State id
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -1024,11 +2065,18 @@ pub struct ParallelState {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "validate", garde(dive))]
     pub metadata: Option<Metadata>,
+
+    /// Extension properties
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub extensions: Extensions,
 }
 
 /// [`ParallelState`]-specific timeouts
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct ParallelStateTimeouts {
     /// State exec timeout
@@ -1044,6 +2092,8 @@ pub struct ParallelStateTimeouts {
 
 /// Completion type values
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub enum CompletionType {
     /// All branches must be completed
@@ -1054,8 +2104,10 @@ pub enum CompletionType {
 }
 
 /// Permits transitions to other states based on events or data conditions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum SwitchState {
     /// Transition based on events
@@ -1066,9 +2118,11 @@ pub enum SwitchState {
 }
 
 /// Permits transitions to other states based on events
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
 pub struct EventBasedSwitchState {
     /// Unique State id
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -1120,11 +2174,32 @@ pub struct EventBasedSwitchState {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "validate", garde(dive))]
     pub metadata: Option<Metadata>,
+
+    /// Extension properties
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub extensions: Extensions,
+}
+
+impl EventBasedSwitchState {
+    /// Selects the condition taken by the switch state when the event with the given name
+    /// is consumed.
+    ///
+    /// If multiple conditions reference the event, the first one defined wins. Returns `None`
+    /// if no condition references the event.
+    pub fn select(&self, event_ref: &str) -> Option<SwitchSelection> {
+        self.event_conditions
+            .iter()
+            .position(|condition| condition.event_ref() == event_ref)
+            .map(SwitchSelection::Condition)
+    }
 }
 
 /// [`EventBasedSwitchState`]-specific timeouts
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct EventBasedSwitchStateTimeouts {
     /// State exec timeout
@@ -1139,9 +2214,11 @@ pub struct EventBasedSwitchStateTimeouts {
 }
 
 /// Permits transitions to other states based on data conditions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
 pub struct DataBasedSwitchState {
     /// Unique State id
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -1193,11 +2270,44 @@ pub struct DataBasedSwitchState {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "validate", garde(dive))]
     pub metadata: Option<Metadata>,
+
+    /// Extension properties
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub extensions: Extensions,
+}
+
+impl DataBasedSwitchState {
+    /// Selects the condition taken by the switch state.
+    ///
+    /// Conditions are evaluated in the order they are defined, by calling `matches` with their
+    /// [expression](DataCondition::condition); the first one that matches wins, and the following
+    /// conditions are not evaluated. If no condition matches, the [default condition] is selected.
+    ///
+    /// # Errors
+    ///
+    /// Errors returned by `matches` are propagated.
+    ///
+    /// [default condition]: Self::default_condition
+    pub fn select<F>(&self, mut matches: F) -> crate::Result<SwitchSelection>
+    where
+        F: FnMut(&str) -> crate::Result<bool>,
+    {
+        for (i, condition) in self.data_conditions.iter().enumerate() {
+            if matches(condition.condition())? {
+                return Ok(SwitchSelection::Condition(i));
+            }
+        }
+
+        Ok(SwitchSelection::Default)
+    }
 }
 
 /// [`DataBasedSwitchState`]-specific timeouts
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct DataBasedSwitchStateTimeouts {
     /// State exec timeout
@@ -1210,8 +2320,10 @@ pub struct DataBasedSwitchStateTimeouts {
 ///
 /// [`transition`]: Self::transition
 /// [`end`]: Self::end
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub struct DefaultConditionDef {
     /// Transition definition
@@ -1226,8 +2338,10 @@ pub struct DefaultConditionDef {
 }
 
 /// Switch state data event condition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum EventCondition {
     /// Transition condition
@@ -1237,9 +2351,54 @@ pub enum EventCondition {
     End(#[cfg_attr(feature = "validate", garde(dive))] EndEventCondition),
 }
 
+/// Condition selected by a [`SwitchState`].
+///
+/// See [`DataBasedSwitchState::select`] and [`EventBasedSwitchState::select`]. Runtimes can record
+/// selections in the history of workflow instances to show which condition won.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SwitchSelection {
+    /// Condition at the given index in the state's [data conditions] or [event conditions]
+    ///
+    /// [data conditions]: DataBasedSwitchState::data_conditions
+    /// [event conditions]: EventBasedSwitchState::event_conditions
+    Condition(usize),
+
+    /// The state's default condition
+    Default,
+}
+
+impl EventCondition {
+    /// Returns the name of the condition, if any.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::Transition(condition) => condition.name.as_deref(),
+            Self::End(condition) => condition.name.as_deref(),
+        }
+    }
+
+    /// Returns the name of the event referenced by the condition.
+    pub fn event_ref(&self) -> &str {
+        match self {
+            Self::Transition(condition) => &condition.event_ref,
+            Self::End(condition) => &condition.event_ref,
+        }
+    }
+
+    /// Returns the condition's event data filter, if any.
+    pub fn event_data_filter(&self) -> Option<&EventDataFilter> {
+        match self {
+            Self::Transition(condition) => condition.event_data_filter.as_ref(),
+            Self::End(condition) => condition.event_data_filter.as_ref(),
+        }
+    }
+}
+
 /// Switch state data event condition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct TransitionEventCondition {
     /// Event condition name
@@ -1267,8 +2426,10 @@ pub struct TransitionEventCondition {
 }
 
 /// Switch state data event condition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct EndEventCondition {
     /// Event condition name
@@ -1296,8 +2457,10 @@ pub struct EndEventCondition {
 }
 
 /// Switch state data based condition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum DataCondition {
     /// Transition condition
@@ -1307,9 +2470,29 @@ pub enum DataCondition {
     End(#[cfg_attr(feature = "validate", garde(dive))] EndDataCondition),
 }
 
+impl DataCondition {
+    /// Returns the name of the condition, if any.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::Transition(condition) => condition.name.as_deref(),
+            Self::End(condition) => condition.name.as_deref(),
+        }
+    }
+
+    /// Returns the workflow expression evaluated against state data.
+    pub fn condition(&self) -> &str {
+        match self {
+            Self::Transition(condition) => &condition.condition,
+            Self::End(condition) => &condition.condition,
+        }
+    }
+}
+
 /// Switch state data based condition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct TransitionDataCondition {
     /// Data condition name
@@ -1332,8 +2515,10 @@ pub struct TransitionDataCondition {
 }
 
 /// Switch state data based condition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct EndDataCondition {
     /// Data condition name
@@ -1356,9 +2541,11 @@ pub struct EndDataCondition {
 }
 
 /// Inject static data into state data. Does not perform any actions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
 pub struct InjectState {
     /// Unique State id
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -1409,23 +2596,33 @@ pub struct InjectState {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "validate", garde(dive))]
     pub metadata: Option<Metadata>,
+
+    /// Extension properties
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub extensions: Extensions,
 }
 
 /// Data to be injected by an [`InjectState`] (see [`data`]).
 ///
 /// [`data`]: InjectState::data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct InjectData {
     /// Data fields
     #[serde(flatten)]
     #[cfg_attr(feature = "validate", garde(skip))]
-    pub meta: HashMap<String, Value>,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arbitrary_values))]
+    pub meta: IndexMap<String, Value>,
 }
 
 /// [`InjectState`]-specific timeouts
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct InjectStateTimeouts {
     /// State exec timeout
@@ -1435,9 +2632,11 @@ pub struct InjectStateTimeouts {
 }
 
 /// Execute a set of defined actions or workflows for each element of a data array
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
 pub struct ForEachState {
     /// Unique State id
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -1528,11 +2727,18 @@ pub struct ForEachState {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "validate", garde(dive))]
     pub metadata: Option<Metadata>,
+
+    /// Extension properties
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub extensions: Extensions,
 }
 
 /// [`ForEachState`]-specific timeouts
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct ForEachStateTimeouts {
     /// State exec timeout
@@ -1547,9 +2753,11 @@ pub struct ForEachStateTimeouts {
 }
 
 /// This state performs an action, then waits for the callback event that denotes completion of the action
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
 pub struct CallbackState {
     /// Unique State id
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -1614,11 +2822,18 @@ pub struct CallbackState {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "validate", garde(dive))]
     pub metadata: Option<Metadata>,
+
+    /// Extension properties
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub extensions: Extensions,
 }
 
 /// [`CallbackState`]-specific timeouts
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct CallbackStateTimeouts {
     /// State exec timeout
@@ -1637,9 +2852,46 @@ pub struct CallbackStateTimeouts {
     pub event_timeout: Option<EventTimeout>,
 }
 
+/// State of a type not defined in the specification, such as a vendor-specific state type
+/// provided by a runtime extension
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct CustomState {
+    /// State type
+    #[serde(rename = "type")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "custom_state_type_schema"))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arbitrary_custom_state_type))]
+    pub state_type: String,
+
+    /// State name
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub name: String,
+
+    /// Next transition of the workflow after the state
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(dive))]
+    pub transition: Option<Transition>,
+
+    /// State end definition
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(dive))]
+    pub end: Option<End>,
+
+    /// Other properties of the state
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arbitrary_custom_state_properties))]
+    pub properties: Map<String, Value>,
+}
+
 /// Workflow start definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged, deny_unknown_fields)]
 pub enum StartDef {
     ByName(#[cfg_attr(feature = "validate", garde(length(min = 1)))] String),
@@ -1677,14 +2929,22 @@ impl StartDef {
 }
 
 /// Schedule definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged, deny_unknown_fields)]
 pub enum Schedule {
     /// Time interval (must be repeating interval) described with ISO 8601 format.
     ///
     /// Declares when workflow instances will be automatically created.  (UTC timezone is assumed)
-    TimeInterval(#[cfg_attr(feature = "validate", garde(length(min = 1)))] String),
+    TimeInterval(
+        #[cfg_attr(
+            feature = "validate",
+            garde(length(min = 1), custom(must_be_valid_recurring_interval))
+        )]
+        String,
+    ),
 
     /// Start state schedule definition
     Complex {
@@ -1692,7 +2952,10 @@ pub enum Schedule {
         ///
         /// Declares when workflow instances will be automatically created.
         #[serde(default, skip_serializing_if = "Option::is_none")]
-        #[cfg_attr(feature = "validate", garde(length(min = 1)))]
+        #[cfg_attr(
+            feature = "validate",
+            garde(length(min = 1), custom(must_be_valid_optional_recurring_interval))
+        )]
         interval: Option<String>,
 
         /// Cron definition
@@ -1702,7 +2965,7 @@ pub enum Schedule {
 
         /// Timezone name used to evaluate the interval & cron-expression. (default: UTC)
         #[serde(default, skip_serializing_if = "Option::is_none")]
-        #[cfg_attr(feature = "validate", garde(skip))]
+        #[cfg_attr(feature = "validate", garde(custom(must_be_valid_optional_timezone)))]
         timezone: Option<String>,
     },
 }
@@ -1722,12 +2985,52 @@ impl Schedule {
             Self::Complex { interval, .. } => interval.as_ref(),
         }
     }
+
+    /// Parses the schedule's interval and returns the corresponding [`RecurringInterval`].
+    ///
+    /// Will return `Ok(None)` if the schedule does not have an interval.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidRecurringInterval`]: the interval is not a valid ISO 8601 repeating interval
+    ///
+    /// [`InvalidRecurringInterval`]: crate::Error::InvalidRecurringInterval
+    pub fn recurring_interval(&self) -> crate::Result<Option<RecurringInterval>> {
+        self.interval().map(|interval| interval.parse()).transpose()
+    }
+
+    /// Returns the timezone used to evaluate the schedule's interval and cron expression.
+    ///
+    /// The timezone name must be a valid [IANA time zone] name. If the schedule does not
+    /// specify a timezone, UTC is assumed.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidTimezone`]: the timezone is not a known IANA time zone name
+    ///
+    /// [IANA time zone]: https://www.iana.org/time-zones
+    /// [`InvalidTimezone`]: crate::Error::InvalidTimezone
+    pub fn timezone(&self) -> crate::Result<Tz> {
+        match self {
+            Self::Complex { timezone: Some(timezone), .. } => parse_timezone(timezone),
+            _ => Ok(Tz::UTC),
+        }
+    }
+}
+
+pub(crate) fn parse_timezone(timezone: &str) -> crate::Result<Tz> {
+    timezone
+        .parse()
+        .map_err(|_| crate::Error::InvalidTimezone { timezone: timezone.into() })
 }
 
 /// State end definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged, deny_unknown_fields)]
+#[allow(clippy::large_enum_variant)] // Ends are stored inline in states, which are already large
 pub enum End {
     /// Simple end definition.
     ///
@@ -1759,9 +3062,29 @@ pub enum End {
     },
 }
 
+impl End {
+    /// Returns the workflow to continue execution as when the workflow ends, if any.
+    pub fn continue_as(&self) -> Option<&ContinueAsDef> {
+        match self {
+            Self::Simple(_) => None,
+            Self::Complex { continue_as, .. } => continue_as.as_ref(),
+        }
+    }
+
+    /// Returns `true` if workflow compensation must be performed when the workflow ends.
+    pub fn compensate(&self) -> bool {
+        match self {
+            Self::Simple(_) => false,
+            Self::Complex { compensate, .. } => *compensate,
+        }
+    }
+}
+
 /// Produce an event and set its data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct ProduceEventDef {
     /// References a name of a defined event
@@ -1783,8 +3106,10 @@ pub struct ProduceEventDef {
 }
 
 /// State data filter
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub struct StateDataFilter {
     /// Workflow expression to filter the state data input
@@ -1799,8 +3124,10 @@ pub struct StateDataFilter {
 }
 
 /// Event data filter
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct EventDataFilter {
     /// If set to `false`, event payload is not added/merged to state data.
@@ -1826,8 +3153,10 @@ pub struct EventDataFilter {
 }
 
 /// Action data filter
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct ActionDataFilter {
     /// Workflow expression that selects state data that the state action can use
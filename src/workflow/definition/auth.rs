@@ -7,22 +7,46 @@ use url::Url;
 
 use crate::detail::basic;
 use crate::workflow::definition::common::Metadata;
+#[cfg(feature = "arbitrary")]
+use crate::workflow::definition::detail::arbitrary::arbitrary_url;
 
 /// Auth definitions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum Auth {
     /// URI to a resource containing auth definitions (json or yaml)
-    Uri(#[cfg_attr(feature = "validate", garde(skip))] Url),
+    Uri(
+        #[cfg_attr(feature = "validate", garde(skip))]
+        #[cfg_attr(feature = "arbitrary", arbitrary(with = arbitrary_url))]
+        Url,
+    ),
 
     /// Workflow auth definitions
     Definitions(#[cfg_attr(feature = "validate", garde(dive, length(min = 1)))] Vec<AuthDef>),
 }
 
+/// Resource containing workflow auth definitions.
+///
+/// Referred to by [`Auth::Uri`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(deny_unknown_fields)]
+pub struct AuthResource {
+    /// Workflow auth definitions
+    #[cfg_attr(feature = "validate", garde(dive))]
+    pub auth: Auth,
+}
+
 /// Auth definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct AuthDef {
     /// Unique auth definition name
     #[cfg_attr(feature = "validate", garde(length(min = 1)))]
@@ -39,8 +63,10 @@ pub struct AuthDef {
 }
 
 /// Auth definition properties
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum AuthDefProperties {
     /// Expression referencing a workflow secret that contains all needed auth info
@@ -58,6 +84,8 @@ pub enum AuthDefProperties {
 
 /// Auth scheme
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 pub enum Scheme {
     /// Basic authentication
@@ -71,8 +99,10 @@ pub enum Scheme {
 }
 
 /// Basic auth properties definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged, deny_unknown_fields)]
 pub enum BasicPropsDef {
     /// Expression referencing a workflow secret that contains all needed basic auth info
@@ -83,26 +113,31 @@ pub enum BasicPropsDef {
 }
 
 /// Basic auth properties definition auth info
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(deny_unknown_fields)]
 pub struct BasicPropsDefAuthInfo {
     /// String or a workflow expression. Contains the user name
     #[cfg_attr(feature = "validate", garde(length(min = 1)))]
-    username: String,
+    pub username: String,
 
     /// String or a workflow expression. Contains the user password
     #[cfg_attr(feature = "validate", garde(length(min = 1)))]
-    password: String,
+    pub password: String,
 
     /// Auth metadata
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "validate", garde(dive))]
-    metadata: Option<Metadata>,
+    pub metadata: Option<Metadata>,
 }
 
 /// Bearer auth properties definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged, deny_unknown_fields)]
 pub enum BearerPropsDef {
     /// Expression referencing a workflow secret that contains all needed bearer auth info
@@ -113,22 +148,27 @@ pub enum BearerPropsDef {
 }
 
 /// Bearer auth properties definition auth info
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(deny_unknown_fields)]
 pub struct BearerPropsDefAuthInfo {
     /// String or a workflow expression. Contains the token
     #[cfg_attr(feature = "validate", garde(length(min = 1)))]
-    token: String,
+    pub token: String,
 
     /// Auth metadata
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "validate", garde(dive))]
-    metadata: Option<Metadata>,
+    pub metadata: Option<Metadata>,
 }
 
 /// OAuth2 auth properties definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum OAuth2PropsDef {
     /// Expression referencing a workflow secret that contains all needed OAuth2 auth info
@@ -139,9 +179,11 @@ pub enum OAuth2PropsDef {
 }
 
 /// OAuth2 auth properties definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
-#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct OAuth2PropsDefAuthInfo {
     /// String or a workflow expression. Contains the authority information
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -164,7 +206,7 @@ pub struct OAuth2PropsDefAuthInfo {
     /// Array containing strings or workflow expressions. Contains the OAuth2 scopes
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "validate", garde(length(min = 1)))]
-    scopes: Option<Vec<String>>,
+    pub(crate) scopes: Option<Vec<String>>,
 
     /// String or a workflow expression. Contains the user name. Used only if grantType is 'resourceOwner'
     ///
@@ -183,7 +225,7 @@ pub struct OAuth2PropsDefAuthInfo {
     /// Array containing strings or workflow expressions. Contains the OAuth2 audiences
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "validate", garde(length(min = 1)))]
-    audiences: Option<Vec<String>>,
+    pub(crate) audiences: Option<Vec<String>>,
 
     /// String or a workflow expression. Contains the subject token
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -203,6 +245,8 @@ pub struct OAuth2PropsDefAuthInfo {
 
 /// OAuth2 grant type
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub enum GrantType {
     /// Password grant
@@ -2,30 +2,183 @@
 //!
 //! Corresponding JSON schema: [common.json](https://github.com/serverlessworkflow/specification/blob/v0.8/schema/common.json).
 
-use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
 
+use indexmap::IndexMap;
 use num::Zero;
-use serde::{Deserialize, Serialize};
+use serde::de::{MapAccess, Visitor};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use url::Url;
 
 #[cfg(feature = "validate")]
 use crate::detail::garde::{must_be_a_number, must_be_zero_or_greater};
+#[cfg(feature = "arbitrary")]
+use crate::workflow::definition::detail::arbitrary::{
+    arbitrary_non_negative_number, arbitrary_values,
+};
 
 /// Metadata information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Metadata {
     /// Attached metadata, comprised of custom properties.
     #[serde(flatten)]
     #[cfg_attr(feature = "validate", garde(skip))]
-    pub meta: HashMap<String, String>,
+    pub meta: IndexMap<String, String>,
+}
+
+impl Metadata {
+    /// Metadata property containing a human-readable name to display instead of the element's name.
+    pub const DISPLAY_NAME: &'static str = "displayName";
+
+    /// Metadata property containing the name or URI of an icon representing the element.
+    pub const ICON: &'static str = "icon";
+
+    /// Metadata property containing the name of a group the element belongs to.
+    pub const GROUP: &'static str = "group";
+
+    /// Metadata property containing the URL of documentation about the element.
+    pub const DOCUMENTATION_URL: &'static str = "documentationUrl";
+
+    /// Returns the [display name](Self::DISPLAY_NAME), if specified.
+    pub fn display_name(&self) -> Option<&str> {
+        self.meta.get(Self::DISPLAY_NAME).map(String::as_str)
+    }
+
+    /// Returns the [icon](Self::ICON), if specified.
+    pub fn icon(&self) -> Option<&str> {
+        self.meta.get(Self::ICON).map(String::as_str)
+    }
+
+    /// Returns the [group](Self::GROUP), if specified.
+    pub fn group(&self) -> Option<&str> {
+        self.meta.get(Self::GROUP).map(String::as_str)
+    }
+
+    /// Returns the [documentation URL](Self::DOCUMENTATION_URL), if specified.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidUrl`]: the documentation URL is not a valid URL
+    ///
+    /// [`InvalidUrl`]: crate::Error::InvalidUrl
+    pub fn documentation_url(&self) -> crate::Result<Option<Url>> {
+        self.meta
+            .get(Self::DOCUMENTATION_URL)
+            .map(|url| Url::parse(url).map_err(Into::into))
+            .transpose()
+    }
+}
+
+/// Extension properties, e.g. properties whose name starts with `x-`.
+///
+/// Extension properties are not part of the specification, but can be used to attach custom
+/// (often vendor-specific) information to workflow definition objects. They are preserved
+/// when a workflow definition is loaded and saved again.
+///
+/// When deserializing, properties whose name does not start with `x-` are rejected.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct Extensions(pub Map<String, Value>);
+
+impl Extensions {
+    /// Prefix of extension property names.
+    pub const PREFIX: &'static str = "x-";
+}
+
+impl Deref for Extensions {
+    type Target = Map<String, Value>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Extensions {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for Extensions {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ExtensionsVisitor;
+
+        impl<'de> Visitor<'de> for ExtensionsVisitor {
+            type Value = Extensions;
+
+            fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+                write!(formatter, "extension properties starting with `{}`", Extensions::PREFIX)
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut extensions = Map::new();
+                while let Some(key) = map.next_key::<String>()? {
+                    if !key.starts_with(Extensions::PREFIX) {
+                        return Err(de::Error::unknown_field(&key, &[]));
+                    }
+                    extensions.insert(key, map.next_value()?);
+                }
+
+                Ok(Extensions(extensions))
+            }
+        }
+
+        deserializer.deserialize_map(ExtensionsVisitor)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Extensions {
+    fn schema_name() -> String {
+        "Extensions".into()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{InstanceType, ObjectValidation, Schema, SchemaObject};
+
+        SchemaObject {
+            instance_type: Some(InstanceType::Object.into()),
+            object: Some(Box::new(ObjectValidation {
+                pattern_properties: [(format!("^{}", Self::PREFIX), Schema::Bool(true))]
+                    .into_iter()
+                    .collect(),
+                additional_properties: Some(Box::new(Schema::Bool(false))),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Extensions {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self(
+            arbitrary_values(u)?
+                .into_iter()
+                .map(|(name, value)| (format!("{}{}", Self::PREFIX, name), value))
+                .collect(),
+        ))
+    }
 }
 
 /// A non-negative number, represented either as a number or as a string (that must contain a number).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum NonNegativeNumber<T>
 where
@@ -41,6 +194,23 @@ where
     ),
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for NonNegativeNumber<i64> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let number = u.int_in_range(0..=i64::MAX)?;
+        arbitrary_non_negative_number(u, number)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for NonNegativeNumber<f64> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // Generate multiples of 0.01 to avoid non-finite numbers, which cannot be represented in JSON.
+        let number = f64::from(u32::arbitrary(u)?) / 100.0;
+        arbitrary_non_negative_number(u, number)
+    }
+}
+
 impl<T> NonNegativeNumber<T>
 where
     T: PartialOrd + Zero + Display + FromStr + Copy,
@@ -150,6 +320,8 @@ where
 
 /// Possible execution modes for actions or workflows: either sequentially or in parallel.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 pub enum ExecutionMode {
     /// Sequential execution
@@ -161,6 +333,8 @@ pub enum ExecutionMode {
 
 /// Possible invocation modes for actions or functions: either synchronously or asynchronously.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 pub enum InvocationMode {
     /// Synchronous invocation
@@ -0,0 +1,112 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use indexmap::IndexMap;
+use num::Zero;
+use serde_json::{Map, Number, Value};
+use url::Url;
+
+use crate::workflow::definition::common::NonNegativeNumber;
+use crate::workflow::definition::BUILT_IN_STATE_TYPES;
+
+// Maximum nesting depth of generated JSON values.
+const MAX_VALUE_DEPTH: usize = 3;
+
+// Maximum number of elements in generated JSON arrays and objects.
+const MAX_VALUE_LEN: usize = 4;
+
+// Properties of a custom state that are deserialized into its fields.
+const CUSTOM_STATE_FIELDS: [&str; 4] = ["type", "name", "transition", "end"];
+
+pub fn arbitrary_values(u: &mut Unstructured<'_>) -> Result<IndexMap<String, Value>> {
+    object(u, MAX_VALUE_DEPTH, |_| true)
+}
+
+pub fn arbitrary_url(u: &mut Unstructured<'_>) -> Result<Url> {
+    let name: String = String::arbitrary(u)?
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect();
+
+    Url::parse(&format!("file:///{}.json", name)).map_err(|_| arbitrary::Error::IncorrectFormat)
+}
+
+// Returns a number between 0 and 1, with at most two decimals.
+pub fn arbitrary_ratio(u: &mut Unstructured<'_>) -> Result<f64> {
+    Ok(f64::from(u.int_in_range(0..=100u8)?) / 100.0)
+}
+
+pub fn arbitrary_non_negative_number<T>(
+    u: &mut Unstructured<'_>,
+    number: T,
+) -> Result<NonNegativeNumber<T>>
+where
+    T: PartialOrd + Zero + Display + FromStr,
+{
+    Ok(if bool::arbitrary(u)? {
+        NonNegativeNumber::Number(number)
+    } else {
+        NonNegativeNumber::String(number.to_string())
+    })
+}
+
+pub fn arbitrary_custom_state_type(u: &mut Unstructured<'_>) -> Result<String> {
+    let state_type = String::arbitrary(u)?;
+
+    Ok(if BUILT_IN_STATE_TYPES.contains(&state_type.as_str()) {
+        format!("x-{}", state_type)
+    } else {
+        state_type
+    })
+}
+
+pub fn arbitrary_custom_state_properties(u: &mut Unstructured<'_>) -> Result<Map<String, Value>> {
+    object(u, MAX_VALUE_DEPTH, |name| !CUSTOM_STATE_FIELDS.contains(&name))
+}
+
+fn value(u: &mut Unstructured<'_>, depth: usize) -> Result<Value> {
+    let max_kind = if depth == 0 { 3 } else { 5 };
+
+    Ok(match u.int_in_range(0..=max_kind)? {
+        0 => Value::Null,
+        1 => Value::Bool(bool::arbitrary(u)?),
+        2 => number(u)?,
+        3 => Value::String(String::arbitrary(u)?),
+        4 => {
+            let len = u.int_in_range(0..=MAX_VALUE_LEN)?;
+            Value::Array(
+                (0..len)
+                    .map(|_| value(u, depth - 1))
+                    .collect::<Result<_>>()?,
+            )
+        },
+        _ => Value::Object(object(u, depth - 1, |_| true)?),
+    })
+}
+
+fn number(u: &mut Unstructured<'_>) -> Result<Value> {
+    Ok(match u.int_in_range(0..=2u8)? {
+        0 => i64::arbitrary(u)?.into(),
+        1 => u64::arbitrary(u)?.into(),
+        // Non-finite numbers cannot be represented in JSON.
+        _ => Number::from_f64(f64::arbitrary(u)?).map_or(Value::Null, Value::Number),
+    })
+}
+
+fn object<T, F>(u: &mut Unstructured<'_>, depth: usize, allowed: F) -> Result<T>
+where
+    T: FromIterator<(String, Value)>,
+    F: Fn(&str) -> bool,
+{
+    let len = u.int_in_range(0..=MAX_VALUE_LEN)?;
+    let mut fields = Vec::with_capacity(len);
+    for _ in 0..len {
+        let name = String::arbitrary(u)?;
+        if allowed(&name) {
+            fields.push((name, value(u, depth)?));
+        }
+    }
+
+    Ok(fields.into_iter().collect())
+}
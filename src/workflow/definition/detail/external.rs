@@ -0,0 +1,99 @@
+use std::any::Any;
+use std::collections::HashSet;
+
+use serde::de::DeserializeOwned;
+use url::Url;
+
+use crate::cache::DefinitionCache;
+use crate::validation::ValidateDefinition;
+use crate::workflow::definition::auth::{Auth, AuthResource};
+use crate::workflow::definition::errors::{Errors, ErrorsResource};
+use crate::workflow::definition::events::{Events, EventsResource};
+use crate::workflow::definition::functions::{Functions, FunctionsResource};
+use crate::workflow::definition::retries::{Retries, RetriesResource};
+use crate::workflow::definition::secrets::{Secrets, SecretsResource};
+use crate::workflow::definition::timeouts::{Timeouts, TimeoutsResource};
+use crate::workflow::definition::Constants;
+
+// A workflow definition section that can either be inlined or refer to an external resource via a URI.
+pub trait ExternalRef: Clone {
+    type Resource: ValidateDefinition + DeserializeOwned + Any;
+
+    fn uri(&self) -> Option<&Url>;
+    fn from_resource(resource: &Self::Resource) -> Self;
+}
+
+pub fn resolve_external_ref<T>(value: &T, cache: &mut DefinitionCache) -> crate::Result<T>
+where
+    T: ExternalRef,
+{
+    let mut value = value.clone();
+    let mut visited = HashSet::new();
+
+    while let Some(uri) = value.uri().cloned() {
+        if !visited.insert(uri.clone()) {
+            return Err(crate::Error::CircularExternalReference { uri });
+        }
+
+        let resource = cache.get_or_insert::<T::Resource, _>(uri)?;
+        value = T::from_resource(&resource);
+    }
+
+    Ok(value)
+}
+
+pub fn resolve_optional_external_ref<T>(
+    value: &Option<T>,
+    cache: &mut DefinitionCache,
+) -> crate::Result<Option<T>>
+where
+    T: ExternalRef,
+{
+    value
+        .as_ref()
+        .map(|value| resolve_external_ref(value, cache))
+        .transpose()
+}
+
+macro_rules! impl_external_ref {
+    ($typ:ident, $uri_variant:ident, $resource:ident, $field:ident) => {
+        impl ExternalRef for $typ {
+            type Resource = $resource;
+
+            fn uri(&self) -> Option<&Url> {
+                match self {
+                    Self::$uri_variant(uri) => Some(uri),
+                    _ => None,
+                }
+            }
+
+            fn from_resource(resource: &Self::Resource) -> Self {
+                resource.$field.clone()
+            }
+        }
+    };
+}
+
+impl_external_ref!(Functions, Uri, FunctionsResource, functions);
+impl_external_ref!(Events, Uri, EventsResource, events);
+impl_external_ref!(Errors, Uri, ErrorsResource, errors);
+impl_external_ref!(Retries, Uri, RetriesResource, retries);
+impl_external_ref!(Auth, Uri, AuthResource, auth);
+impl_external_ref!(Secrets, Uri, SecretsResource, secrets);
+impl_external_ref!(Timeouts, Uri, TimeoutsResource, timeouts);
+
+impl ExternalRef for Constants {
+    // Constants resources contain the constants data directly.
+    type Resource = Constants;
+
+    fn uri(&self) -> Option<&Url> {
+        match self {
+            Self::One(uri) => Some(uri),
+            Self::Multiple { .. } => None,
+        }
+    }
+
+    fn from_resource(resource: &Self::Resource) -> Self {
+        resource.clone()
+    }
+}
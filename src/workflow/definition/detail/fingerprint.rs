@@ -0,0 +1,37 @@
+use serde_json::Value;
+
+use crate::workflow::definition::WorkflowDefinition;
+
+// Parameters of the 64-bit FNV-1a hash function.
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+pub fn fingerprint(definition: &WorkflowDefinition) -> crate::Result<u64> {
+    // Sorting object keys makes the result independent of the order in which properties
+    // appear in the source document.
+    let content = sorted(serde_json::to_value(definition.normalize())?).to_string();
+
+    // Unlike the standard library's hasher, FNV-1a is specified, so the result
+    // does not change between builds.
+    Ok(content
+        .bytes()
+        .fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)))
+}
+
+// Sorts the keys of all objects in a JSON value, recursively.
+fn sorted(value: Value) -> Value {
+    match value {
+        Value::Array(values) => Value::Array(values.into_iter().map(sorted).collect()),
+        Value::Object(values) => {
+            let mut values: Vec<_> = values.into_iter().collect();
+            values.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                values
+                    .into_iter()
+                    .map(|(name, value)| (name, sorted(value)))
+                    .collect(),
+            )
+        },
+        value => value,
+    }
+}
@@ -1,4 +1,8 @@
+use chrono::DateTime;
+
 use crate::workflow::definition::events::EventKind;
+use crate::workflow::definition::intervals::RecurringInterval;
+use crate::workflow::definition::{parse_cron_expression, parse_timezone};
 
 pub fn if_not_used_for_compensation_then_must_have_transition_or_end<'t, 'u, T, U, C>(
     transition: &'t Option<T>,
@@ -11,7 +15,9 @@ where
 {
     |used_for_compensation, _ctx| {
         if !used_for_compensation && transition.is_none() && end.is_none() {
-            Err(garde::Error::new(""))
+            Err(garde::Error::new(
+                "state must have a `transition` or an `end` unless it is used for compensation",
+            ))
         } else {
             Ok(())
         }
@@ -32,3 +38,71 @@ where
         }
     }
 }
+
+pub fn must_be_valid_cron_expression<S, C>(expression: S, _ctx: &C) -> garde::Result
+where
+    S: AsRef<str>,
+    C: ?Sized,
+{
+    let expression = expression.as_ref();
+
+    parse_cron_expression(expression)
+        .map(|_| ())
+        .map_err(|err| {
+            garde::Error::new(format!("invalid cron expression '{}': {}", expression, err))
+        })
+}
+
+pub fn must_be_valid_optional_date_time<C>(value: &Option<String>, _ctx: &C) -> garde::Result
+where
+    C: ?Sized,
+{
+    match value {
+        Some(value) => DateTime::parse_from_rfc3339(value)
+            .map(|_| ())
+            .map_err(|err| {
+                garde::Error::new(format!(
+                    "expected ISO 8601 date and time, got '{}': {}",
+                    value, err
+                ))
+            }),
+        None => Ok(()),
+    }
+}
+
+pub fn must_be_valid_recurring_interval<S, C>(interval: S, _ctx: &C) -> garde::Result
+where
+    S: AsRef<str>,
+    C: ?Sized,
+{
+    interval
+        .as_ref()
+        .parse::<RecurringInterval>()
+        .map(|_| ())
+        .map_err(|err| garde::Error::new(err.to_string()))
+}
+
+pub fn must_be_valid_optional_recurring_interval<C>(
+    interval: &Option<String>,
+    ctx: &C,
+) -> garde::Result
+where
+    C: ?Sized,
+{
+    match interval {
+        Some(interval) => must_be_valid_recurring_interval(interval, ctx),
+        None => Ok(()),
+    }
+}
+
+pub fn must_be_valid_optional_timezone<C>(timezone: &Option<String>, _ctx: &C) -> garde::Result
+where
+    C: ?Sized,
+{
+    match timezone {
+        Some(timezone) => parse_timezone(timezone).map(|_| ()).map_err(|_| {
+            garde::Error::new(format!("expected IANA time zone name, got '{}'", timezone))
+        }),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,201 @@
+use serde_json::{json, Value};
+
+use crate::workflow::definition::WorkflowDefinition;
+
+pub fn minify(definition: &WorkflowDefinition) -> crate::Result<Value> {
+    let mut document = serde_json::to_value(definition)?;
+
+    strip_defaults(
+        &mut document,
+        &[
+            ("expressionLang", json!("jq")),
+            ("keepActive", json!(false)),
+            ("autoRetries", json!(false)),
+        ],
+    );
+    if document.get("start") == first_state_name(definition).as_ref() {
+        remove(&mut document, "start");
+    }
+    if let Some(schema) = document.get_mut("dataInputSchema") {
+        strip_defaults(schema, &[("failOnValidationErrors", json!(true))]);
+        collapse(schema, "schema");
+    }
+    if let Some(Value::Object(start)) = document.get_mut("start") {
+        if let Some(schedule) = start.get_mut("schedule") {
+            minify_schedule(schedule);
+        }
+    }
+    if let Some(timeouts) = document.get_mut("timeouts") {
+        minify_timeouts(timeouts);
+    }
+    for_each(document.get_mut("functions"), |function| {
+        strip_defaults(function, &[("type", json!("rest"))]);
+    });
+    for_each(document.get_mut("events"), |event| {
+        strip_defaults(event, &[("kind", json!("consumed")), ("dataOnly", json!(true))]);
+    });
+    for_each(document.get_mut("auth"), |auth| {
+        strip_defaults(auth, &[("scheme", json!("basic"))]);
+    });
+    for_each(document.get_mut("states"), minify_state);
+
+    Ok(document)
+}
+
+fn first_state_name(definition: &WorkflowDefinition) -> Option<Value> {
+    definition.states.first().map(|state| state.name().into())
+}
+
+fn minify_schedule(schedule: &mut Value) {
+    if let Some(cron) = schedule.get_mut("cron") {
+        collapse(cron, "expression");
+    }
+    collapse(schedule, "interval");
+}
+
+fn minify_timeouts(timeouts: &mut Value) {
+    if let Some(workflow_exec_timeout) = timeouts.get_mut("workflowExecTimeout") {
+        minify_workflow_exec_timeout(workflow_exec_timeout);
+    }
+    if let Some(state_exec_timeout) = timeouts.get_mut("stateExecTimeout") {
+        collapse(state_exec_timeout, "total");
+    }
+}
+
+fn minify_workflow_exec_timeout(workflow_exec_timeout: &mut Value) {
+    strip_defaults(workflow_exec_timeout, &[("interrupt", json!(true))]);
+    collapse(workflow_exec_timeout, "duration");
+}
+
+fn minify_state(state: &mut Value) {
+    strip_defaults(state, &[("usedForCompensation", json!(false))]);
+    match state.get("type").and_then(Value::as_str) {
+        Some("event") => {
+            strip_defaults(state, &[("exclusive", json!(true))]);
+            for_each(state.get_mut("onEvents"), |on_events| {
+                strip_defaults(on_events, &[("actionMode", json!("sequential"))]);
+                minify_event_data_filter(on_events);
+                for_each(on_events.get_mut("actions"), minify_action);
+            });
+        },
+        Some("operation") => {
+            strip_defaults(state, &[("actionMode", json!("sequential"))]);
+            for_each(state.get_mut("actions"), minify_action);
+        },
+        Some("parallel") => {
+            strip_defaults(state, &[("completionType", json!("allOf"))]);
+            for_each(state.get_mut("branches"), |branch| {
+                for_each(branch.get_mut("actions"), minify_action);
+            });
+        },
+        Some("switch") => {
+            for_each(state.get_mut("dataConditions"), minify_outcome);
+            for_each(state.get_mut("eventConditions"), |condition| {
+                minify_event_data_filter(condition);
+                minify_outcome(condition);
+            });
+            if let Some(default_condition) = state.get_mut("defaultCondition") {
+                minify_outcome(default_condition);
+            }
+        },
+        Some("foreach") => {
+            strip_defaults(state, &[("mode", json!("parallel"))]);
+            for_each(state.get_mut("actions"), minify_action);
+        },
+        Some("callback") => {
+            minify_event_data_filter(state);
+            if let Some(action) = state.get_mut("action") {
+                minify_action(action);
+            }
+        },
+        _ => {},
+    }
+    if let Some(timeouts) = state.get_mut("timeouts") {
+        minify_timeouts(timeouts);
+    }
+    for_each(state.get_mut("onErrors"), minify_outcome);
+    minify_outcome(state);
+}
+
+// Minifies the `transition` and `end` of a state, condition or error definition.
+fn minify_outcome(owner: &mut Value) {
+    if let Some(transition) = owner.get_mut("transition") {
+        strip_defaults(transition, &[("compensate", json!(false))]);
+        collapse(transition, "nextState");
+    }
+    if let Some(end) = owner.get_mut("end") {
+        strip_defaults(end, &[("terminate", json!(false)), ("compensate", json!(false))]);
+        if let Some(continue_as) = end.get_mut("continueAs") {
+            if let Some(workflow_exec_timeout) = continue_as.get_mut("workflowExecTimeout") {
+                minify_workflow_exec_timeout(workflow_exec_timeout);
+            }
+            collapse(continue_as, "workflowId");
+        }
+        if end.as_object().is_some_and(|end| end.is_empty()) {
+            *end = Value::Bool(true);
+        }
+    }
+}
+
+fn minify_action(action: &mut Value) {
+    if let Some(function_ref) = action.get_mut("functionRef") {
+        strip_defaults(function_ref, &[("invoke", json!("sync"))]);
+        collapse(function_ref, "refName");
+    }
+    if let Some(event_ref) = action.get_mut("eventRef") {
+        strip_defaults(event_ref, &[("invoke", json!("sync"))]);
+    }
+    if let Some(sub_flow_ref) = action.get_mut("subFlowRef") {
+        strip_defaults(
+            sub_flow_ref,
+            &[("invoke", json!("sync")), ("onParentComplete", json!("terminate"))],
+        );
+        collapse(sub_flow_ref, "workflowId");
+    }
+    if let Some(action_data_filter) = action.get_mut("actionDataFilter") {
+        strip_defaults(action_data_filter, &[("useResults", json!(true))]);
+    }
+}
+
+fn minify_event_data_filter(owner: &mut Value) {
+    if let Some(event_data_filter) = owner.get_mut("eventDataFilter") {
+        strip_defaults(event_data_filter, &[("useData", json!(true))]);
+    }
+}
+
+// Removes the object's fields that are set to their default value.
+fn strip_defaults(value: &mut Value, defaults: &[(&str, Value)]) {
+    if let Value::Object(fields) = value {
+        for (name, default) in defaults {
+            if fields.get(*name) == Some(default) {
+                fields.remove(*name);
+            }
+        }
+    }
+}
+
+// Replaces an object by the value of its only field, if that field is `name`.
+fn collapse(value: &mut Value, name: &str) {
+    if let Value::Object(fields) = value {
+        if fields.len() == 1 {
+            if let Some(field) = fields.remove(name) {
+                *value = field;
+            }
+        }
+    }
+}
+
+fn remove(value: &mut Value, name: &str) {
+    if let Value::Object(fields) = value {
+        fields.remove(name);
+    }
+}
+
+fn for_each<F>(value: Option<&mut Value>, f: F)
+where
+    F: FnMut(&mut Value),
+{
+    if let Some(Value::Array(values)) = value {
+        values.iter_mut().for_each(f);
+    }
+}
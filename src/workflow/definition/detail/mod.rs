@@ -1,2 +1,8 @@
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+pub mod external;
+pub mod fingerprint;
 #[cfg(feature = "validate")]
 pub mod garde;
+pub mod minify;
+pub mod normalize;
@@ -0,0 +1,418 @@
+use crate::detail::{sync, terminate};
+use crate::workflow::definition::auth::{
+    Auth, AuthDef, AuthDefProperties, OAuth2PropsDef, OAuth2PropsDefAuthInfo,
+};
+use crate::workflow::definition::errors::Errors;
+use crate::workflow::definition::events::{EventDef, Events};
+use crate::workflow::definition::functions::Functions;
+use crate::workflow::definition::retries::Retries;
+use crate::workflow::definition::timeouts::{StateExecTimeout, Timeouts, WorkflowExecTimeout};
+use crate::workflow::definition::{
+    Action, Branch, ContinueAsDef, CronDef, DataCondition, DataInputSchema, DefaultConditionDef,
+    End, Error, EventCondition, FunctionRef, OnEvents, Schedule, StartDef, State, SubflowRef,
+    SwitchState, Transition, WorkflowDefinition,
+};
+
+// A workflow definition element that can be expanded from its shorthand form(s).
+pub trait Normalize {
+    fn normalize(&mut self);
+}
+
+impl<T> Normalize for Option<T>
+where
+    T: Normalize,
+{
+    fn normalize(&mut self) {
+        if let Some(value) = self {
+            value.normalize();
+        }
+    }
+}
+
+impl<T> Normalize for Vec<T>
+where
+    T: Normalize,
+{
+    fn normalize(&mut self) {
+        self.iter_mut().for_each(Normalize::normalize);
+    }
+}
+
+impl Normalize for WorkflowDefinition {
+    fn normalize(&mut self) {
+        if self.start.is_none() {
+            self.start = self
+                .states
+                .first()
+                .map(|state| StartDef::ByName(state.name().into()));
+        }
+
+        drop_empty(&mut self.annotations);
+        if matches!(&self.errors, Some(Errors::Inlined(errors)) if errors.is_empty()) {
+            self.errors = None;
+        }
+        if matches!(&self.events, Some(Events::Inline(events)) if events.is_empty()) {
+            self.events = None;
+        }
+        if matches!(&self.functions, Some(Functions::Inline(functions)) if functions.is_empty()) {
+            self.functions = None;
+        }
+        if matches!(&self.retries, Some(Retries::Inline(retries)) if retries.is_empty()) {
+            self.retries = None;
+        }
+        if matches!(&self.auth, Some(Auth::Definitions(auth)) if auth.is_empty()) {
+            self.auth = None;
+        }
+
+        self.data_input_schema.normalize();
+        self.start.normalize();
+        self.timeouts.normalize();
+        self.events.normalize();
+        self.auth.normalize();
+        self.states.normalize();
+    }
+}
+
+impl Normalize for Events {
+    fn normalize(&mut self) {
+        if let Self::Inline(events) = self {
+            events.normalize();
+        }
+    }
+}
+
+impl Normalize for EventDef {
+    fn normalize(&mut self) {
+        drop_empty(&mut self.correlation);
+    }
+}
+
+impl Normalize for Auth {
+    fn normalize(&mut self) {
+        if let Self::Definitions(auth) = self {
+            auth.normalize();
+        }
+    }
+}
+
+impl Normalize for AuthDef {
+    fn normalize(&mut self) {
+        if let AuthDefProperties::OAuth2Auth(OAuth2PropsDef::AuthInfo(info)) = &mut self.properties
+        {
+            info.normalize();
+        }
+    }
+}
+
+impl Normalize for OAuth2PropsDefAuthInfo {
+    fn normalize(&mut self) {
+        drop_empty(&mut self.scopes);
+        drop_empty(&mut self.audiences);
+    }
+}
+
+impl Normalize for DataInputSchema {
+    fn normalize(&mut self) {
+        if let Self::UriOnly(schema) = self {
+            *self = Self::Full { schema: std::mem::take(schema), fail_on_validation_errors: true };
+        }
+    }
+}
+
+impl Normalize for StartDef {
+    fn normalize(&mut self) {
+        if let Self::Complex { schedule, .. } = self {
+            schedule.normalize();
+        }
+    }
+}
+
+impl Normalize for Schedule {
+    fn normalize(&mut self) {
+        match self {
+            Self::TimeInterval(interval) => {
+                *self = Self::Complex {
+                    interval: Some(std::mem::take(interval)),
+                    cron: None,
+                    timezone: None,
+                };
+            },
+            Self::Complex { cron, .. } => cron.normalize(),
+        }
+    }
+}
+
+impl Normalize for CronDef {
+    fn normalize(&mut self) {
+        if let Self::Expr(expression) = self {
+            *self = Self::Repeat { expression: std::mem::take(expression), valid_until: None };
+        }
+    }
+}
+
+impl Normalize for Timeouts {
+    fn normalize(&mut self) {
+        if let Self::Complex { workflow_exec_timeout, state_exec_timeout, .. } = self {
+            workflow_exec_timeout.normalize();
+            state_exec_timeout.normalize();
+        }
+    }
+}
+
+impl Normalize for WorkflowExecTimeout {
+    fn normalize(&mut self) {
+        if let Self::Simple(duration) = self {
+            *self = Self::Complex {
+                duration: std::mem::take(duration),
+                interrupt: true,
+                run_before: None,
+            };
+        }
+    }
+}
+
+impl Normalize for StateExecTimeout {
+    fn normalize(&mut self) {
+        if let Self::Simple(total) = self {
+            *self = Self::Complex { single: None, total: std::mem::take(total) };
+        }
+    }
+}
+
+impl Normalize for Transition {
+    fn normalize(&mut self) {
+        match self {
+            Self::ByName(next_state) => {
+                *self = Self::Complex {
+                    next_state: std::mem::take(next_state),
+                    produce_events: None,
+                    compensate: false,
+                };
+            },
+            Self::Complex { produce_events, .. } => drop_empty(produce_events),
+        }
+    }
+}
+
+impl Normalize for End {
+    fn normalize(&mut self) {
+        match self {
+            Self::Simple(_) => {
+                *self = Self::Complex {
+                    terminate: false,
+                    produce_events: None,
+                    compensate: false,
+                    continue_as: None,
+                };
+            },
+            Self::Complex { produce_events, continue_as, .. } => {
+                drop_empty(produce_events);
+                continue_as.normalize();
+            },
+        }
+    }
+}
+
+impl Normalize for ContinueAsDef {
+    fn normalize(&mut self) {
+        match self {
+            Self::Simple(workflow_id) => {
+                *self = Self::WithData {
+                    workflow_id: std::mem::take(workflow_id),
+                    version: None,
+                    data: None,
+                    workflow_exec_timeout: None,
+                };
+            },
+            Self::WithData { workflow_exec_timeout, .. } => workflow_exec_timeout.normalize(),
+        }
+    }
+}
+
+impl Normalize for Error {
+    fn normalize(&mut self) {
+        drop_empty(&mut self.error_refs);
+        self.transition.normalize();
+        self.end.normalize();
+    }
+}
+
+impl Normalize for Action {
+    fn normalize(&mut self) {
+        self.function_ref.normalize();
+        self.sub_flow_ref.normalize();
+        drop_empty(&mut self.non_retryable_errors);
+        drop_empty(&mut self.retryable_errors);
+    }
+}
+
+impl Normalize for FunctionRef {
+    fn normalize(&mut self) {
+        if let Self::ByName(ref_name) = self {
+            *self = Self::Complex {
+                ref_name: std::mem::take(ref_name),
+                arguments: None,
+                selection_set: None,
+                invoke: sync(),
+            };
+        }
+    }
+}
+
+impl Normalize for SubflowRef {
+    fn normalize(&mut self) {
+        if let Self::ById(workflow_id) = self {
+            *self = Self::Complex {
+                workflow_id: std::mem::take(workflow_id),
+                version: None,
+                on_parent_complete: terminate(),
+                invoke: sync(),
+            };
+        }
+    }
+}
+
+impl Normalize for OnEvents {
+    fn normalize(&mut self) {
+        normalize_collection(&mut self.actions);
+    }
+}
+
+impl Normalize for Branch {
+    fn normalize(&mut self) {
+        self.actions.normalize();
+    }
+}
+
+impl Normalize for DefaultConditionDef {
+    fn normalize(&mut self) {
+        self.transition.normalize();
+        self.end.normalize();
+    }
+}
+
+impl Normalize for EventCondition {
+    fn normalize(&mut self) {
+        match self {
+            Self::Transition(condition) => condition.transition.normalize(),
+            Self::End(condition) => condition.end.normalize(),
+        }
+    }
+}
+
+impl Normalize for DataCondition {
+    fn normalize(&mut self) {
+        match self {
+            Self::Transition(condition) => condition.transition.normalize(),
+            Self::End(condition) => condition.end.normalize(),
+        }
+    }
+}
+
+impl Normalize for State {
+    fn normalize(&mut self) {
+        match self {
+            Self::Sleep(state) => {
+                state.transition.normalize();
+                state.end.normalize();
+                normalize_collection(&mut state.on_errors);
+                normalize_state_exec_timeout(
+                    state.timeouts.as_mut().map(|t| &mut t.state_exec_timeout),
+                );
+            },
+            Self::Event(state) => {
+                state.on_events.normalize();
+                state.transition.normalize();
+                state.end.normalize();
+                normalize_collection(&mut state.on_errors);
+                normalize_state_exec_timeout(
+                    state.timeouts.as_mut().map(|t| &mut t.state_exec_timeout),
+                );
+            },
+            Self::Operation(state) => {
+                state.actions.normalize();
+                state.transition.normalize();
+                state.end.normalize();
+                normalize_collection(&mut state.on_errors);
+                normalize_state_exec_timeout(
+                    state.timeouts.as_mut().map(|t| &mut t.state_exec_timeout),
+                );
+            },
+            Self::Parallel(state) => {
+                state.branches.normalize();
+                state.transition.normalize();
+                state.end.normalize();
+                normalize_collection(&mut state.on_errors);
+                normalize_state_exec_timeout(
+                    state.timeouts.as_mut().map(|t| &mut t.state_exec_timeout),
+                );
+            },
+            Self::Switch(SwitchState::EventBased(state)) => {
+                state.event_conditions.normalize();
+                state.default_condition.normalize();
+                normalize_collection(&mut state.on_errors);
+                normalize_state_exec_timeout(
+                    state.timeouts.as_mut().map(|t| &mut t.state_exec_timeout),
+                );
+            },
+            Self::Switch(SwitchState::DataBased(state)) => {
+                state.data_conditions.normalize();
+                state.default_condition.normalize();
+                normalize_collection(&mut state.on_errors);
+                normalize_state_exec_timeout(
+                    state.timeouts.as_mut().map(|t| &mut t.state_exec_timeout),
+                );
+            },
+            Self::Inject(state) => {
+                state.transition.normalize();
+                state.end.normalize();
+                normalize_state_exec_timeout(
+                    state.timeouts.as_mut().map(|t| &mut t.state_exec_timeout),
+                );
+            },
+            Self::ForEach(state) => {
+                state.actions.normalize();
+                state.transition.normalize();
+                state.end.normalize();
+                normalize_collection(&mut state.on_errors);
+                normalize_state_exec_timeout(
+                    state.timeouts.as_mut().map(|t| &mut t.state_exec_timeout),
+                );
+            },
+            Self::Callback(state) => {
+                state.action.normalize();
+                state.transition.normalize();
+                state.end.normalize();
+                normalize_collection(&mut state.on_errors);
+                normalize_state_exec_timeout(
+                    state.timeouts.as_mut().map(|t| &mut t.state_exec_timeout),
+                );
+            },
+            Self::Custom(state) => {
+                state.transition.normalize();
+                state.end.normalize();
+            },
+        }
+    }
+}
+
+fn normalize_state_exec_timeout(state_exec_timeout: Option<&mut Option<StateExecTimeout>>) {
+    if let Some(state_exec_timeout) = state_exec_timeout {
+        state_exec_timeout.normalize();
+    }
+}
+
+// Empty optional collections are equivalent to omitted ones, so they are normalized to `None`.
+fn drop_empty<T>(collection: &mut Option<Vec<T>>) {
+    if collection.as_ref().is_some_and(Vec::is_empty) {
+        *collection = None;
+    }
+}
+
+fn normalize_collection<T>(collection: &mut Option<Vec<T>>)
+where
+    T: Normalize,
+{
+    drop_empty(collection);
+    collection.normalize();
+}
@@ -5,13 +5,22 @@
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+#[cfg(feature = "arbitrary")]
+use crate::workflow::definition::detail::arbitrary::arbitrary_url;
+
 /// Workflow Error definitions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum Errors {
     /// URI to a resource containing error definitions (json or yaml)
-    Uri(#[cfg_attr(feature = "validate", garde(skip))] Url),
+    Uri(
+        #[cfg_attr(feature = "validate", garde(skip))]
+        #[cfg_attr(feature = "arbitrary", arbitrary(with = arbitrary_url))]
+        Url,
+    ),
 
     /// Workflow Error definitions.
     ///
@@ -19,11 +28,27 @@ pub enum Errors {
     Inlined(#[cfg_attr(feature = "validate", garde(dive, length(min = 1)))] Vec<ErrorDef>),
 }
 
+/// Resource containing workflow error definitions.
+///
+/// Referred to by [`Errors::Uri`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(deny_unknown_fields)]
+pub struct ErrorsResource {
+    /// Workflow error definitions
+    #[cfg_attr(feature = "validate", garde(dive))]
+    pub errors: Errors,
+}
+
 /// Workflow Error definition.
 ///
 /// Defines a checked error that can be explicitly handled during workflow execution
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 #[cfg_attr(feature = "validate", garde(allow_unvalidated))]
 pub struct ErrorDef {
@@ -43,3 +68,11 @@ pub struct ErrorDef {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 }
+
+impl ErrorDef {
+    /// Returns `true` if the given workflow error name refers to this error definition,
+    /// either by [name](Self::name) or by [code](Self::code).
+    pub fn matches(&self, error: &str) -> bool {
+        self.name == error || self.code.as_deref() == Some(error)
+    }
+}
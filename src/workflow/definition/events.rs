@@ -3,29 +3,54 @@
 //! Corresponding JSON schema: [events.json](https://github.com/serverlessworkflow/specification/blob/v0.8/schema/events.json).
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use url::Url;
 
 use crate::detail::{consumed, true_value};
-use crate::workflow::definition::common::Metadata;
+use crate::workflow::definition::common::{Extensions, Metadata};
+#[cfg(feature = "arbitrary")]
+use crate::workflow::definition::detail::arbitrary::arbitrary_url;
 #[cfg(feature = "validate")]
 use crate::workflow::definition::detail::garde::mandatory_for_consumed_events;
 
 /// Workflow CloudEvent definitions. Defines CloudEvents that can be consumed or produced
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum Events {
     /// URI to a resource containing event definitions (json or yaml)
-    Uri(#[cfg_attr(feature = "validate", garde(skip))] Url),
+    Uri(
+        #[cfg_attr(feature = "validate", garde(skip))]
+        #[cfg_attr(feature = "arbitrary", arbitrary(with = arbitrary_url))]
+        Url,
+    ),
 
     /// Inline event definitions
     Inline(#[cfg_attr(feature = "validate", garde(length(min = 1)))] Vec<EventDef>),
 }
 
+/// Resource containing workflow event definitions.
+///
+/// Referred to by [`Events::Uri`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(deny_unknown_fields)]
+pub struct EventsResource {
+    /// Workflow event definitions
+    #[cfg_attr(feature = "validate", garde(dive))]
+    pub events: Events,
+}
+
 /// Event definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
 pub struct EventDef {
     /// Unique event name
     #[cfg_attr(feature = "validate", garde(length(min = 1)))]
@@ -64,10 +89,36 @@ pub struct EventDef {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "validate", garde(dive))]
     pub metadata: Option<Metadata>,
+
+    /// Extension properties
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub extensions: Extensions,
+}
+
+impl EventDef {
+    /// Returns the part of a consumed CloudEvent that should be accessible to workflow states.
+    ///
+    /// `event` is the CloudEvent in [JSON format]. Depending on the value of [`data_only`]:
+    ///
+    /// * `true`: only the event payload (the `data` attribute) is returned, or `null` if the event has no payload.
+    /// * `false`: the entire event is returned, including both the payload and context attributes.
+    ///
+    /// [JSON format]: https://github.com/cloudevents/spec/blob/v1.0.2/cloudevents/formats/json-format.md
+    /// [`data_only`]: Self::data_only
+    pub fn consumed_event_data(&self, event: &Value) -> Value {
+        if self.data_only {
+            event.get("data").cloned().unwrap_or(Value::Null)
+        } else {
+            event.clone()
+        }
+    }
 }
 
 /// CloudEvent kind
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 pub enum EventKind {
     /// CloudEvent is consumed
@@ -78,8 +129,10 @@ pub enum EventKind {
 }
 
 /// CloudEvent correlation definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct CorrelationDef {
     /// CloudEvent Extension Context Attribute name
@@ -6,24 +6,48 @@ use serde::{Deserialize, Serialize};
 use url::Url;
 
 use crate::detail::rest;
-use crate::workflow::definition::common::Metadata;
+use crate::workflow::definition::common::{Extensions, Metadata};
+#[cfg(feature = "arbitrary")]
+use crate::workflow::definition::detail::arbitrary::arbitrary_url;
 
 /// Workflow function definitions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum Functions {
     /// URI to a resource containing function definitions (json or yaml)
-    Uri(#[cfg_attr(feature = "validate", garde(skip))] Url),
+    Uri(
+        #[cfg_attr(feature = "validate", garde(skip))]
+        #[cfg_attr(feature = "arbitrary", arbitrary(with = arbitrary_url))]
+        Url,
+    ),
 
     /// Inline function definitions
     Inline(#[cfg_attr(feature = "validate", garde(length(min = 1)))] Vec<Function>),
 }
 
+/// Resource containing workflow function definitions.
+///
+/// Referred to by [`Functions::Uri`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(deny_unknown_fields)]
+pub struct FunctionsResource {
+    /// Workflow function definitions
+    #[cfg_attr(feature = "validate", garde(dive))]
+    pub functions: Functions,
+}
+
 /// Function definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
-#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
 pub struct Function {
     /// Unique function name
     #[cfg_attr(feature = "validate", garde(length(min = 1)))]
@@ -68,10 +92,17 @@ pub struct Function {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "validate", garde(dive))]
     pub metadata: Option<Metadata>,
+
+    /// Extension properties
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub extensions: Extensions,
 }
 
 /// Function type
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 pub enum FunctionType {
     /// REST endpoint
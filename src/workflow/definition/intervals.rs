@@ -0,0 +1,389 @@
+//! ISO 8601 durations and repeating intervals, as used in [schedules].
+//!
+//! [schedules]: crate::workflow::definition::Schedule
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, FixedOffset, Months, TimeDelta, TimeZone};
+
+/// ISO 8601 duration (e.g. `PT30M` or `P1DT12H`).
+///
+/// Fractions are only supported for seconds.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct IsoDuration {
+    /// Number of years
+    pub years: u32,
+
+    /// Number of months
+    pub months: u32,
+
+    /// Number of weeks
+    pub weeks: u32,
+
+    /// Number of days
+    pub days: u32,
+
+    /// Number of hours
+    pub hours: u32,
+
+    /// Number of minutes
+    pub minutes: u32,
+
+    /// Number of whole seconds
+    pub seconds: u32,
+
+    /// Fractional part of the seconds, in nanoseconds
+    pub nanoseconds: u32,
+}
+
+impl IsoDuration {
+    /// Returns `true` if the duration is zero.
+    pub fn is_zero(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns `true` if the exact length of the duration depends on the date and time
+    /// it is applied to (e.g. if the duration includes years or months).
+    pub fn is_calendar_dependent(&self) -> bool {
+        self.years > 0 || self.months > 0
+    }
+
+    /// Converts the duration to a [`TimeDelta`].
+    ///
+    /// Days are assumed to last 24 hours. Returns `None` if the duration is
+    /// [calendar-dependent](Self::is_calendar_dependent) or too large.
+    pub fn to_time_delta(&self) -> Option<TimeDelta> {
+        if self.is_calendar_dependent() {
+            return None;
+        }
+
+        let days = i64::from(self.weeks) * 7 + i64::from(self.days);
+        let seconds =
+            i64::from(self.hours) * 3600 + i64::from(self.minutes) * 60 + i64::from(self.seconds);
+
+        TimeDelta::try_days(days)?
+            .checked_add(&TimeDelta::try_seconds(seconds)?)?
+            .checked_add(&TimeDelta::nanoseconds(self.nanoseconds.into()))
+    }
+
+    /// Converts the duration to a [`std::time::Duration`].
+    ///
+    /// Days are assumed to last 24 hours. Returns `None` if the duration is
+    /// [calendar-dependent](Self::is_calendar_dependent) or too large.
+    pub fn to_std_duration(&self) -> Option<std::time::Duration> {
+        self.to_time_delta()?.to_std().ok()
+    }
+
+    /// Adds the duration to the given date and time.
+    ///
+    /// Returns `None` if the resulting date and time would be out of range.
+    pub fn add_to<Z>(&self, date_time: &DateTime<Z>) -> Option<DateTime<Z>>
+    where
+        Z: TimeZone,
+    {
+        self.add_times_to(date_time, 1)
+    }
+
+    fn add_times_to<Z>(&self, date_time: &DateTime<Z>, times: u32) -> Option<DateTime<Z>>
+    where
+        Z: TimeZone,
+    {
+        let months = self
+            .years
+            .checked_mul(12)?
+            .checked_add(self.months)?
+            .checked_mul(times)?;
+        let delta = Self { years: 0, months: 0, ..*self }
+            .to_time_delta()?
+            .checked_mul(i32::try_from(times).ok()?)?;
+
+        date_time
+            .clone()
+            .checked_add_months(Months::new(months))?
+            .checked_add_signed(delta)
+    }
+
+    fn from_time_delta(delta: TimeDelta) -> Option<Self> {
+        if delta <= TimeDelta::zero() {
+            return None;
+        }
+
+        Some(Self {
+            seconds: u32::try_from(delta.num_seconds()).ok()?,
+            nanoseconds: u32::try_from(delta.subsec_nanos()).ok()?,
+            ..Self::default()
+        })
+    }
+}
+
+impl From<std::time::Duration> for IsoDuration {
+    /// Converts a [`std::time::Duration`] to an ISO 8601 duration expressed in hours, minutes
+    /// and seconds (e.g. 90 minutes become `PT1H30M`).
+    ///
+    /// Durations longer than [`u32::MAX`] hours are truncated.
+    fn from(duration: std::time::Duration) -> Self {
+        let seconds = duration.as_secs();
+
+        Self {
+            hours: u32::try_from(seconds / 3600).unwrap_or(u32::MAX),
+            minutes: (seconds / 60 % 60) as u32,
+            seconds: (seconds % 60) as u32,
+            nanoseconds: duration.subsec_nanos(),
+            ..Self::default()
+        }
+    }
+}
+
+impl FromStr for IsoDuration {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_duration(s).ok_or_else(|| crate::Error::InvalidDuration { duration: s.into() })
+    }
+}
+
+impl fmt::Display for IsoDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "PT0S");
+        }
+
+        write!(f, "P")?;
+        for (value, designator) in
+            [(self.years, 'Y'), (self.months, 'M'), (self.weeks, 'W'), (self.days, 'D')]
+        {
+            if value > 0 {
+                write!(f, "{}{}", value, designator)?;
+            }
+        }
+
+        if self.hours > 0 || self.minutes > 0 || self.seconds > 0 || self.nanoseconds > 0 {
+            write!(f, "T")?;
+            for (value, designator) in [(self.hours, 'H'), (self.minutes, 'M')] {
+                if value > 0 {
+                    write!(f, "{}{}", value, designator)?;
+                }
+            }
+            match (self.seconds, self.nanoseconds) {
+                (0, 0) => (),
+                (seconds, 0) => write!(f, "{}S", seconds)?,
+                (seconds, nanoseconds) => {
+                    let fraction = format!("{:09}", nanoseconds);
+                    write!(f, "{}.{}S", seconds, fraction.trim_end_matches('0'))?;
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_duration(s: &str) -> Option<IsoDuration> {
+    let rest = s.strip_prefix('P')?;
+    let (date, time) = match rest.split_once('T') {
+        Some((_, "")) => return None,
+        Some((date, time)) => (date, time),
+        None => (rest, ""),
+    };
+    if date.is_empty() && time.is_empty() {
+        return None;
+    }
+
+    let mut duration = IsoDuration::default();
+    for (index, value) in parse_components(date, "YMWD")? {
+        let field = match index {
+            0 => &mut duration.years,
+            1 => &mut duration.months,
+            2 => &mut duration.weeks,
+            _ => &mut duration.days,
+        };
+        *field = parse_digits(value)?;
+    }
+    for (index, value) in parse_components(time, "HMS")? {
+        match index {
+            0 => duration.hours = parse_digits(value)?,
+            1 => duration.minutes = parse_digits(value)?,
+            _ => {
+                let (seconds, fraction) = match value.split_once(['.', ',']) {
+                    Some((seconds, fraction)) => (seconds, Some(fraction)),
+                    None => (value, None),
+                };
+                duration.seconds = parse_digits(seconds)?;
+                if let Some(fraction) = fraction {
+                    if fraction.is_empty() {
+                        return None;
+                    }
+                    let fraction = format!("{:0<9.9}", fraction);
+                    duration.nanoseconds = parse_digits(&fraction)?;
+                }
+            },
+        }
+    }
+
+    Some(duration)
+}
+
+/// Splits a part of an ISO 8601 duration into `(designator index, value)` components,
+/// making sure designators appear in the right order and at most once.
+fn parse_components<'a>(part: &'a str, designators: &str) -> Option<Vec<(usize, &'a str)>> {
+    let mut components = Vec::new();
+    let mut rest = part;
+    let mut next_index = 0;
+    while !rest.is_empty() {
+        let end = rest.find(|c: char| !c.is_ascii_digit() && c != '.' && c != ',')?;
+        let designator = rest[end..].chars().next()?;
+        let index = next_index + designators[next_index..].find(designator)?;
+        if end == 0 {
+            return None;
+        }
+
+        components.push((index, &rest[..end]));
+        next_index = index + 1;
+        rest = &rest[end + designator.len_utf8()..];
+    }
+
+    Some(components)
+}
+
+fn parse_digits(value: &str) -> Option<u32> {
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    value.parse().ok()
+}
+
+/// ISO 8601 repeating interval (e.g. `R/PT30M` or `R5/2024-01-01T00:00:00Z/P1D`).
+///
+/// The following forms are supported (where the number of repetitions `n` is optional):
+///
+/// * `Rn/<duration>`
+/// * `Rn/<start>/<duration>`
+/// * `Rn/<start>/<end>`
+///
+/// As the specification demands, the interval must be repeating, e.g. it must start with `R`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurringInterval {
+    /// Number of repetitions, or `None` if the interval repeats indefinitely
+    pub repetitions: Option<u32>,
+
+    /// Start of the first repetition, if specified
+    pub start: Option<DateTime<FixedOffset>>,
+
+    /// Duration of each repetition
+    pub duration: IsoDuration,
+}
+
+impl RecurringInterval {
+    /// Returns the next (at most) `n` occurrences of the interval after the given date and time.
+    ///
+    /// Occurrences correspond to the start of each repetition, e.g. `start`, `start + duration`,
+    /// `start + 2 * duration`, etc., up to the number of [repetitions]. If the interval does not
+    /// specify a [start], `after` is used, so the first occurrence is `after + duration`.
+    ///
+    /// If the number of repetitions is bounded, the returned [`Vec`] can contain less than `n` items.
+    ///
+    /// [repetitions]: Self::repetitions
+    /// [start]: Self::start
+    pub fn next_occurrences<Z>(&self, after: &DateTime<Z>, n: usize) -> Vec<DateTime<Z>>
+    where
+        Z: TimeZone,
+    {
+        let start = match self.start {
+            Some(start) => start.with_timezone(&after.timezone()),
+            None => after.clone(),
+        };
+
+        // If the duration has a fixed length, skip directly to the first repetition after `after`.
+        let first_repetition = match self.duration.to_time_delta() {
+            Some(delta) if start < *after => {
+                let elapsed = after.clone().signed_duration_since(start.clone());
+                u64::try_from(total_nanoseconds(elapsed) / total_nanoseconds(delta) + 1)
+                    .unwrap_or(u64::MAX)
+            },
+            _ => 0,
+        };
+
+        (first_repetition..)
+            .take_while(|&repetition| match self.repetitions {
+                Some(repetitions) => repetition < u64::from(repetitions),
+                None => true,
+            })
+            .map_while(|repetition| {
+                self.duration
+                    .add_times_to(&start, u32::try_from(repetition).ok()?)
+            })
+            .filter(|occurrence| occurrence > after)
+            .take(n)
+            .collect()
+    }
+}
+
+impl FromStr for RecurringInterval {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_recurring_interval(s)
+            .map_err(|reason| crate::Error::InvalidRecurringInterval { interval: s.into(), reason })
+    }
+}
+
+impl fmt::Display for RecurringInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "R")?;
+        if let Some(repetitions) = self.repetitions {
+            write!(f, "{}", repetitions)?;
+        }
+        if let Some(start) = self.start {
+            write!(f, "/{}", start.to_rfc3339())?;
+        }
+        write!(f, "/{}", self.duration)
+    }
+}
+
+fn parse_recurring_interval(s: &str) -> Result<RecurringInterval, &'static str> {
+    let mut parts = s.split('/');
+    let repetitions = parts
+        .next()
+        .and_then(|repetitions| repetitions.strip_prefix('R'))
+        .ok_or("interval must be repeating")?;
+    let repetitions = match repetitions {
+        "" => None,
+        repetitions => Some(parse_digits(repetitions).ok_or("invalid number of repetitions")?),
+    };
+    if repetitions == Some(0) {
+        return Err("interval must repeat at least once");
+    }
+
+    let parse_duration = |duration: &str| parse_duration(duration).ok_or("invalid duration");
+    let parse_date_time = |date_time: &str| {
+        DateTime::parse_from_rfc3339(date_time).map_err(|_| "invalid date and time")
+    };
+    let (start, duration) = match parts.collect::<Vec<_>>().as_slice() {
+        [duration] => (None, parse_duration(duration)?),
+        [start, _] if start.starts_with('P') => {
+            return Err("intervals with a duration and an end are not supported");
+        },
+        [start, duration] if duration.starts_with('P') => {
+            (Some(parse_date_time(start)?), parse_duration(duration)?)
+        },
+        [start, end] => {
+            let start = parse_date_time(start)?;
+            let end = parse_date_time(end)?;
+            let duration = IsoDuration::from_time_delta(end.signed_duration_since(start))
+                .ok_or("interval must end after it starts")?;
+            (Some(start), duration)
+        },
+        _ => return Err("expected a duration, a start and duration or a start and end"),
+    };
+    if duration.is_zero() {
+        return Err("interval duration must not be zero");
+    }
+
+    Ok(RecurringInterval { repetitions, start, duration })
+}
+
+fn total_nanoseconds(delta: TimeDelta) -> i128 {
+    i128::from(delta.num_seconds()) * 1_000_000_000 + i128::from(delta.subsec_nanos())
+}
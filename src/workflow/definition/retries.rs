@@ -2,29 +2,56 @@
 //!
 //! Corresponding JSON schema: [retries.json](https://github.com/serverlessworkflow/specification/blob/v0.8/schema/retries.json).
 
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 use url::Url;
 
 #[cfg(feature = "validate")]
 use crate::detail::garde::must_be_optional_multiple_of;
 use crate::workflow::definition::common::NonNegativeNumber;
+#[cfg(feature = "arbitrary")]
+use crate::workflow::definition::detail::arbitrary::{arbitrary_ratio, arbitrary_url};
+use crate::workflow::definition::intervals::IsoDuration;
 
 /// Workflow Retry definitions.
 ///
 /// Define retry strategies that can be referenced in states onError definitions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum Retries {
     /// URI to a resource containing retry definitions (json or yaml)
-    Uri(#[cfg_attr(feature = "validate", garde(skip))] Url),
+    Uri(
+        #[cfg_attr(feature = "validate", garde(skip))]
+        #[cfg_attr(feature = "arbitrary", arbitrary(with = arbitrary_url))]
+        Url,
+    ),
 
     /// Inline retry definitions
     Inline(#[cfg_attr(feature = "validate", garde(dive, length(min = 1)))] Vec<RetryDef>),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Resource containing workflow retry definitions.
+///
+/// Referred to by [`Retries::Uri`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(deny_unknown_fields)]
+pub struct RetriesResource {
+    /// Workflow retry definitions
+    #[cfg_attr(feature = "validate", garde(dive))]
+    pub retries: Retries,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct RetryDef {
     /// Unique retry strategy name
@@ -60,14 +87,90 @@ pub struct RetryDef {
     pub jitter: Option<Jitter>,
 }
 
+impl RetryDef {
+    /// Multiplier used by [exponential](Self::exponential) retry strategies.
+    pub const EXPONENTIAL_MULTIPLIER: f64 = 2.0;
+
+    /// Creates a retry strategy waiting for the same delay between each attempt.
+    pub fn fixed<N>(name: N, delay: Duration, max_attempts: u32) -> Self
+    where
+        N: Into<String>,
+    {
+        Self {
+            name: name.into(),
+            delay: Some(iso_duration(delay)),
+            max_delay: None,
+            increment: None,
+            multiplier: None,
+            max_attempts: NonNegativeNumber::Number(max_attempts.into()),
+            jitter: None,
+        }
+    }
+
+    /// Creates a retry strategy where the delay between attempts increases by `increment`
+    /// after each attempt.
+    pub fn linear<N>(name: N, delay: Duration, increment: Duration, max_attempts: u32) -> Self
+    where
+        N: Into<String>,
+    {
+        Self { increment: Some(iso_duration(increment)), ..Self::fixed(name, delay, max_attempts) }
+    }
+
+    /// Creates a retry strategy where the delay between attempts starts at `base_delay` and
+    /// doubles after each attempt.
+    ///
+    /// Use [`with_multiplier`](Self::with_multiplier) to grow the delay at a different rate, and
+    /// [`with_max_delay`](Self::with_max_delay) to cap it.
+    pub fn exponential<N>(name: N, base_delay: Duration, max_attempts: u32) -> Self
+    where
+        N: Into<String>,
+    {
+        Self::fixed(name, base_delay, max_attempts).with_multiplier(Self::EXPONENTIAL_MULTIPLIER)
+    }
+
+    /// Returns a copy of this retry strategy where the delay is multiplied by `multiplier`
+    /// after each attempt.
+    ///
+    /// Since the specification requires multipliers to be multiples of `0.01`, the multiplier
+    /// is rounded to two decimals; negative multipliers are replaced by `0`.
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        let multiplier = (multiplier.max(0.0) * 100.0).round() / 100.0;
+        self.multiplier = Some(NonNegativeNumber::Number(multiplier));
+        self
+    }
+
+    /// Returns a copy of this retry strategy where the delay between attempts never
+    /// exceeds `max_delay`.
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = Some(iso_duration(max_delay));
+        self
+    }
+
+    /// Returns a copy of this retry strategy with the given jitter.
+    pub fn with_jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = Some(jitter);
+        self
+    }
+}
+
 /// Retry definition jitter value
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum Jitter {
     /// Maximum amount of random time added or subtracted from the delay between each retry relative to total delay (between 0 and 1)
-    Float(#[cfg_attr(feature = "validate", garde(range(min = 0.0, max = 1.0)))] f64),
+    Float(
+        #[cfg_attr(feature = "validate", garde(range(min = 0.0, max = 1.0)))]
+        #[cfg_attr(feature = "arbitrary", arbitrary(with = arbitrary_ratio))]
+        f64,
+    ),
 
     /// Absolute maximum amount of random time added or subtracted from the delay between each retry (ISO 8601 duration format)
     Duration(#[cfg_attr(feature = "validate", garde(skip))] String),
 }
+
+fn iso_duration(duration: Duration) -> String {
+    IsoDuration::from(duration).to_string()
+}
@@ -5,14 +5,37 @@
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+#[cfg(feature = "arbitrary")]
+use crate::workflow::definition::detail::arbitrary::arbitrary_url;
+
 /// Workflow secrets definitions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum Secrets {
     /// URI to a resource containing secrets definitions (json or yaml)
-    Uri(#[cfg_attr(feature = "validate", garde(skip))] Url),
+    Uri(
+        #[cfg_attr(feature = "validate", garde(skip))]
+        #[cfg_attr(feature = "arbitrary", arbitrary(with = arbitrary_url))]
+        Url,
+    ),
 
     /// Workflow Secrets definitions
     Inline(#[cfg_attr(feature = "validate", garde(length(min = 1)))] Vec<String>),
 }
+
+/// Resource containing workflow secrets definitions.
+///
+/// Referred to by [`Secrets::Uri`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(deny_unknown_fields)]
+pub struct SecretsResource {
+    /// Workflow secrets definitions
+    #[cfg_attr(feature = "validate", garde(dive))]
+    pub secrets: Secrets,
+}
@@ -2,18 +2,29 @@
 //!
 //! Corresponding JSON schema: [timeouts.json](https://github.com/serverlessworkflow/specification/blob/v0.8/schema/timeouts.json).
 
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 use url::Url;
 
 use crate::detail::true_value;
+#[cfg(feature = "arbitrary")]
+use crate::workflow::definition::detail::arbitrary::arbitrary_url;
+use crate::workflow::definition::intervals::IsoDuration;
 
 /// Workflow default timeouts definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged, deny_unknown_fields)]
 pub enum Timeouts {
     /// URI to a resource containing timeouts definitions (json or yaml)
-    Uri(#[cfg_attr(feature = "validate", garde(skip))] Url),
+    Uri(
+        #[cfg_attr(feature = "validate", garde(skip))]
+        #[cfg_attr(feature = "arbitrary", arbitrary(with = arbitrary_url))]
+        Url,
+    ),
 
     /// Workflow default timeouts
     #[serde(rename_all = "camelCase")]
@@ -45,9 +56,25 @@ pub enum Timeouts {
     },
 }
 
+/// Resource containing workflow default timeouts definitions.
+///
+/// Referred to by [`Timeouts::Uri`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(deny_unknown_fields)]
+pub struct TimeoutsResource {
+    /// Workflow default timeouts definitions
+    #[cfg_attr(feature = "validate", garde(dive))]
+    pub timeouts: Timeouts,
+}
+
 /// Workflow execution timeouts
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged, deny_unknown_fields)]
 pub enum WorkflowExecTimeout {
     /// Workflow execution timeout duration (ISO 8601 duration format).
@@ -76,9 +103,30 @@ pub enum WorkflowExecTimeout {
     },
 }
 
+impl WorkflowExecTimeout {
+    /// Returns the workflow execution timeout duration.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidDuration`]: the duration is not a valid ISO 8601 duration
+    /// * [`UnsupportedDuration`]: the duration is [calendar-dependent] or too large
+    ///
+    /// [`InvalidDuration`]: crate::Error::InvalidDuration
+    /// [`UnsupportedDuration`]: crate::Error::UnsupportedDuration
+    /// [calendar-dependent]: IsoDuration::is_calendar_dependent
+    pub fn as_duration(&self) -> crate::Result<Duration> {
+        match self {
+            Self::Simple(duration) => parse_duration(duration),
+            Self::Complex { duration, .. } => parse_duration(duration),
+        }
+    }
+}
+
 /// State execution timeouts
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged, deny_unknown_fields)]
 pub enum StateExecTimeout {
     /// Total state execution timeout (including retries) (ISO 8601 duration format)
@@ -97,20 +145,117 @@ pub enum StateExecTimeout {
     },
 }
 
+impl StateExecTimeout {
+    /// Returns the total state execution timeout duration, including retries.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidDuration`]: the duration is not a valid ISO 8601 duration
+    /// * [`UnsupportedDuration`]: the duration is [calendar-dependent] or too large
+    ///
+    /// [`InvalidDuration`]: crate::Error::InvalidDuration
+    /// [`UnsupportedDuration`]: crate::Error::UnsupportedDuration
+    /// [calendar-dependent]: IsoDuration::is_calendar_dependent
+    pub fn as_duration(&self) -> crate::Result<Duration> {
+        match self {
+            Self::Simple(total) => parse_duration(total),
+            Self::Complex { total, .. } => parse_duration(total),
+        }
+    }
+
+    /// Returns the single state execution timeout duration, not including retries, if specified.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidDuration`]: the duration is not a valid ISO 8601 duration
+    /// * [`UnsupportedDuration`]: the duration is [calendar-dependent] or too large
+    ///
+    /// [`InvalidDuration`]: crate::Error::InvalidDuration
+    /// [`UnsupportedDuration`]: crate::Error::UnsupportedDuration
+    /// [calendar-dependent]: IsoDuration::is_calendar_dependent
+    pub fn single_as_duration(&self) -> crate::Result<Option<Duration>> {
+        match self {
+            Self::Simple(_) => Ok(None),
+            Self::Complex { single, .. } => single.as_deref().map(parse_duration).transpose(),
+        }
+    }
+}
+
 /// Single actions definition execution timeout duration (ISO 8601 duration format)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct ActionExecTimeout(#[cfg_attr(feature = "validate", garde(length(min = 1)))] pub String);
 
+impl ActionExecTimeout {
+    /// Returns the single actions definition execution timeout duration.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidDuration`]: the duration is not a valid ISO 8601 duration
+    /// * [`UnsupportedDuration`]: the duration is [calendar-dependent] or too large
+    ///
+    /// [`InvalidDuration`]: crate::Error::InvalidDuration
+    /// [`UnsupportedDuration`]: crate::Error::UnsupportedDuration
+    /// [calendar-dependent]: IsoDuration::is_calendar_dependent
+    pub fn as_duration(&self) -> crate::Result<Duration> {
+        parse_duration(&self.0)
+    }
+}
+
 /// Single branch execution timeout duration (ISO 8601 duration format)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct BranchExecTimeout(#[cfg_attr(feature = "validate", garde(length(min = 1)))] pub String);
 
+impl BranchExecTimeout {
+    /// Returns the single branch execution timeout duration.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidDuration`]: the duration is not a valid ISO 8601 duration
+    /// * [`UnsupportedDuration`]: the duration is [calendar-dependent] or too large
+    ///
+    /// [`InvalidDuration`]: crate::Error::InvalidDuration
+    /// [`UnsupportedDuration`]: crate::Error::UnsupportedDuration
+    /// [calendar-dependent]: IsoDuration::is_calendar_dependent
+    pub fn as_duration(&self) -> crate::Result<Duration> {
+        parse_duration(&self.0)
+    }
+}
+
 /// Timeout duration to wait for consuming defined events (ISO 8601 duration format)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(transparent)]
 pub struct EventTimeout(#[cfg_attr(feature = "validate", garde(length(min = 1)))] pub String);
+
+impl EventTimeout {
+    /// Returns the duration to wait for consuming defined events.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidDuration`]: the duration is not a valid ISO 8601 duration
+    /// * [`UnsupportedDuration`]: the duration is [calendar-dependent] or too large
+    ///
+    /// [`InvalidDuration`]: crate::Error::InvalidDuration
+    /// [`UnsupportedDuration`]: crate::Error::UnsupportedDuration
+    /// [calendar-dependent]: IsoDuration::is_calendar_dependent
+    pub fn as_duration(&self) -> crate::Result<Duration> {
+        parse_duration(&self.0)
+    }
+}
+
+pub(crate) fn parse_duration(duration: &str) -> crate::Result<Duration> {
+    duration
+        .parse::<IsoDuration>()?
+        .to_std_duration()
+        .ok_or_else(|| crate::Error::UnsupportedDuration { duration: duration.into() })
+}
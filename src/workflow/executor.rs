@@ -0,0 +1,718 @@
+//! Integration point for executing workflow actions.
+//!
+//! Runtimes call an [`ActionExecutor`] for each action that references a function. Applications
+//! embedding this crate implement the trait to invoke the services described by
+//! [function definitions], or use one of the provided executors:
+//!
+//! * [`RestActionExecutor`](rest::RestActionExecutor) calls [REST functions] described by
+//!   OpenAPI documents.
+//! * [`GraphQlActionExecutor`](graphql::GraphQlActionExecutor) calls [GraphQL functions].
+//! * [`WasmActionExecutor`](wasm::WasmActionExecutor) calls [custom functions] implemented as
+//!   sandboxed WebAssembly modules.
+//! * [`ScriptActionExecutor`](script::ScriptActionExecutor) calls [custom functions]
+//!   implemented as Rhai scripts.
+//! * [`HandlerActionExecutor`] calls Rust closures registered by function name, to run
+//!   in-process code without any network hop.
+//! * [`NoopActionExecutor`] and [`MockActionExecutor`] can be used when no real service should
+//!   be called, e.g. in tests.
+//!
+//! [`SizeLimitActionExecutor`] can wrap any executor to prevent large function results from
+//! bloating workflow data. [`PooledActionExecutor`] can wrap any executor to limit how many
+//! functions are called concurrently, per pool of workflows sharing an annotation.
+//!
+//! Executors calling remote services can implement [`AsyncActionExecutor`] instead, so that
+//! function calls do not block executor threads. [`AsyncAdapter`] and [`BlockingAdapter`]
+//! convert between the two kinds of executors.
+//!
+//! [function definitions]: Function
+//! [REST functions]: crate::workflow::definition::functions::FunctionType::Rest
+//! [GraphQL functions]: crate::workflow::definition::functions::FunctionType::GraphQL
+//! [custom functions]: crate::workflow::definition::functions::FunctionType::Custom
+
+pub mod graphql;
+pub mod http;
+pub mod rest;
+pub mod script;
+pub mod wasm;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::num::NonZeroUsize;
+use std::pin::{pin, Pin};
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+use serde_json::{Map, Value};
+
+use crate::clock::{Clock, SystemClock};
+use crate::detail::json_size;
+use crate::workflow::definition::auth::{Auth, AuthDef};
+use crate::workflow::definition::functions::{Function, Functions};
+use crate::workflow::definition::{FunctionRef, WorkflowDefinition};
+use crate::workflow::instance::WorkflowInstance;
+
+/// Call to a function, passed to an [`ActionExecutor`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionCall<'a> {
+    /// Definition of the function to call
+    pub function: &'a Function,
+
+    /// Function arguments, with all expressions already evaluated
+    pub arguments: Map<String, Value>,
+
+    /// Auth definition referenced by the function, if any
+    pub auth: Option<&'a AuthDef>,
+
+    /// GraphQL selection set specified by the [function reference](FunctionRef::selection_set),
+    /// if any
+    pub selection_set: Option<String>,
+
+    /// Context in which the function is called
+    pub context: InvocationContext,
+}
+
+impl<'a> FunctionCall<'a> {
+    /// Creates a call to the function referenced by `function_ref`, resolving the function
+    /// and its auth definition in the given workflow definition.
+    ///
+    /// The call's [context](Self::context) only identifies the workflow; use
+    /// [`with_context`](Self::with_context) to provide more information.
+    ///
+    /// Function and auth definitions specified as a URI are not loaded; use
+    /// [`WorkflowDefinition::resolve_external_refs`] first to resolve them.
+    ///
+    /// # Errors
+    ///
+    /// * [`UnknownFunction`]: the referenced function is not defined in the workflow definition
+    /// * [`UnknownAuth`]: the function's auth definition is not defined in the workflow definition
+    ///
+    /// [`UnknownFunction`]: crate::Error::UnknownFunction
+    /// [`UnknownAuth`]: crate::Error::UnknownAuth
+    pub fn new(
+        definition: &'a WorkflowDefinition,
+        function_ref: &FunctionRef,
+        arguments: Map<String, Value>,
+    ) -> crate::Result<Self> {
+        let function = match &definition.functions {
+            Some(Functions::Inline(functions)) => functions
+                .iter()
+                .find(|function| function.name == function_ref.ref_name()),
+            _ => None,
+        }
+        .ok_or_else(|| crate::Error::UnknownFunction {
+            function: function_ref.ref_name().into(),
+        })?;
+
+        let auth = function
+            .auth_ref
+            .as_ref()
+            .map(|auth_ref| {
+                match &definition.auth {
+                    Some(Auth::Definitions(auth)) => {
+                        auth.iter().find(|auth| &auth.name == auth_ref)
+                    },
+                    _ => None,
+                }
+                .ok_or_else(|| crate::Error::UnknownAuth { auth: auth_ref.clone() })
+            })
+            .transpose()?;
+
+        let selection_set = function_ref.selection_set().map(Into::into);
+
+        let context = InvocationContext::new(definition);
+
+        Ok(Self { function, arguments, auth, selection_set, context })
+    }
+
+    /// Returns a copy of this call with the given context.
+    pub fn with_context(mut self, context: InvocationContext) -> Self {
+        self.context = context;
+        self
+    }
+}
+
+/// Context of a [function call](FunctionCall).
+///
+/// Executors can use the context to propagate tracing information to the services they call,
+/// enforce deadlines, log consistently, etc.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvocationContext {
+    /// ID of the workflow calling the function (see [`Identifier::id`])
+    ///
+    /// [`Identifier::id`]: crate::workflow::definition::Identifier::id
+    pub workflow_id: Option<String>,
+
+    /// Version of the workflow calling the function
+    pub workflow_version: Option<String>,
+
+    /// [Annotations](WorkflowDefinition::annotations) of the workflow calling the function
+    pub annotations: Vec<String>,
+
+    /// ID of the workflow instance calling the function
+    pub instance_id: Option<String>,
+
+    /// Name of the state calling the function
+    pub state: Option<String>,
+
+    /// Name of the action calling the function
+    pub action: Option<String>,
+
+    /// Attempt number, starting at 1. Incremented each time the action is retried.
+    pub attempt: u32,
+
+    /// Instant at which the action times out, if any
+    pub deadline: Option<Instant>,
+
+    /// Key identifying the call across attempts, so that services can detect duplicate calls
+    pub idempotency_key: Option<String>,
+}
+
+impl InvocationContext {
+    /// Creates the context of a first attempt at calling a function from the given workflow.
+    pub fn new(definition: &WorkflowDefinition) -> Self {
+        Self {
+            workflow_id: definition.identifier.id().ok().map(Into::into),
+            workflow_version: definition.version.clone(),
+            annotations: definition.annotations.clone().unwrap_or_default(),
+            instance_id: None,
+            state: None,
+            action: None,
+            attempt: 1,
+            deadline: None,
+            idempotency_key: None,
+        }
+    }
+
+    /// Returns a copy of this context for a call made by the given workflow instance,
+    /// from its current state.
+    pub fn with_instance(mut self, instance: &WorkflowInstance) -> Self {
+        self.instance_id = Some(instance.id.clone());
+        self.state = instance.state.clone();
+        self
+    }
+
+    /// Returns a copy of this context for a call made by the action with the given name.
+    pub fn with_action<S>(mut self, action: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.action = Some(action.into());
+        self
+    }
+
+    /// Returns a copy of this context with the given attempt number.
+    pub fn with_attempt(mut self, attempt: u32) -> Self {
+        self.attempt = attempt;
+        self
+    }
+
+    /// Returns a copy of this context where the action times out at the given instant.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Returns a copy of this context where the action times out after the given duration
+    /// (e.g. an [action execution timeout]).
+    ///
+    /// [action execution timeout]: crate::workflow::definition::timeouts::ActionExecTimeout::as_duration
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_timeout_on(&SystemClock, timeout)
+    }
+
+    /// Returns a copy of this context where the action times out after the given duration,
+    /// as measured by the given [`Clock`].
+    pub fn with_timeout_on<C>(self, clock: &C, timeout: Duration) -> Self
+    where
+        C: Clock + ?Sized,
+    {
+        self.with_deadline(clock.instant() + timeout)
+    }
+
+    /// Returns a copy of this context with the given idempotency key.
+    pub fn with_idempotency_key<S>(mut self, idempotency_key: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.idempotency_key = Some(idempotency_key.into());
+        self
+    }
+
+    /// Returns the time left before the action times out, or `None` if it has no deadline.
+    ///
+    /// If the deadline has passed, returns [`Duration::ZERO`].
+    pub fn remaining(&self) -> Option<Duration> {
+        self.remaining_on(&SystemClock)
+    }
+
+    /// Returns the time left before the action times out, as measured by the given [`Clock`],
+    /// or `None` if it has no deadline.
+    ///
+    /// If the deadline has passed, returns [`Duration::ZERO`].
+    pub fn remaining_on<C>(&self, clock: &C) -> Option<Duration>
+    where
+        C: Clock + ?Sized,
+    {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(clock.instant()))
+    }
+}
+
+/// Trait implemented by applications to execute the functions called by workflow actions.
+pub trait ActionExecutor {
+    /// Calls a function and returns its result.
+    ///
+    /// # Errors
+    ///
+    /// * [`ActionFailed`]: the function call failed. The error name is matched against the
+    ///                     state's [error handlers].
+    ///
+    /// Implementations may also return other errors, which are not handled by the workflow.
+    ///
+    /// [`ActionFailed`]: crate::Error::ActionFailed
+    /// [error handlers]: crate::workflow::definition::State::error_handler
+    fn execute(&self, call: &FunctionCall<'_>) -> crate::Result<Value>;
+
+    /// Checks that this executor can call a function, without calling it.
+    ///
+    /// This is used by [preflight checks] before any workflow instance is started, so the
+    /// call's [arguments](FunctionCall::arguments) are empty. Executors should check what they
+    /// can without side effects (e.g. that the function's endpoint can be resolved).
+    ///
+    /// The default implementation accepts all calls.
+    ///
+    /// [preflight checks]: crate::workflow::preflight
+    fn check(&self, #[allow(unused)] call: &FunctionCall<'_>) -> crate::Result<()> {
+        Ok(())
+    }
+}
+
+/// Future returned by [`AsyncActionExecutor::execute`].
+pub type ExecuteFuture<'a> = Pin<Box<dyn Future<Output = crate::Result<Value>> + Send + 'a>>;
+
+/// Asynchronous version of [`ActionExecutor`].
+///
+/// Implement this trait for executors that call remote services, so that waiting for the
+/// services' responses does not block executor threads. The trait does not depend on a specific
+/// async runtime.
+pub trait AsyncActionExecutor: Send + Sync {
+    /// Calls a function and returns a future resolving to its result.
+    ///
+    /// See [`ActionExecutor::execute`] for the errors the future can resolve to.
+    fn execute<'a>(&'a self, call: &'a FunctionCall<'_>) -> ExecuteFuture<'a>;
+}
+
+/// Adapter implementing [`AsyncActionExecutor`] for an [`ActionExecutor`].
+///
+/// Functions are called synchronously when the returned future is polled.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct AsyncAdapter<E>(pub E);
+
+impl<E> AsyncActionExecutor for AsyncAdapter<E>
+where
+    E: ActionExecutor + Send + Sync,
+{
+    fn execute<'a>(&'a self, call: &'a FunctionCall<'_>) -> ExecuteFuture<'a> {
+        Box::pin(async move { self.0.execute(call) })
+    }
+}
+
+/// Adapter implementing [`ActionExecutor`] for an [`AsyncActionExecutor`].
+///
+/// Each call blocks the current thread until the executor's future completes. Futures that
+/// need a specific async runtime (for example to perform I/O) must be run in that runtime
+/// instead.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct BlockingAdapter<E>(pub E);
+
+impl<E> ActionExecutor for BlockingAdapter<E>
+where
+    E: AsyncActionExecutor,
+{
+    fn execute(&self, call: &FunctionCall<'_>) -> crate::Result<Value> {
+        block_on(self.0.execute(call))
+    }
+}
+
+/// [`ActionExecutor`] that does not call any function and always returns `null`.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct NoopActionExecutor;
+
+impl ActionExecutor for NoopActionExecutor {
+    fn execute(&self, _call: &FunctionCall<'_>) -> crate::Result<Value> {
+        Ok(Value::Null)
+    }
+}
+
+/// [`ActionExecutor`] returning predefined results for each function.
+///
+/// Functions with no configured result or failure return `null`.
+#[derive(Debug, Default, Clone)]
+pub struct MockActionExecutor {
+    results: HashMap<String, Value>,
+    failures: HashMap<String, String>,
+}
+
+impl MockActionExecutor {
+    /// Creates a new executor with no configured results.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Configures the result returned when the given function is called.
+    pub fn with_result<S>(mut self, function: S, result: Value) -> Self
+    where
+        S: Into<String>,
+    {
+        let function = function.into();
+        self.failures.remove(&function);
+        self.results.insert(function, result);
+        self
+    }
+
+    /// Configures the given function to fail with the given workflow error.
+    pub fn with_failure<S, E>(mut self, function: S, error: E) -> Self
+    where
+        S: Into<String>,
+        E: Into<String>,
+    {
+        let function = function.into();
+        self.results.remove(&function);
+        self.failures.insert(function, error.into());
+        self
+    }
+}
+
+impl ActionExecutor for MockActionExecutor {
+    fn execute(&self, call: &FunctionCall<'_>) -> crate::Result<Value> {
+        let name = &call.function.name;
+        if let Some(error) = self.failures.get(name) {
+            return Err(crate::Error::ActionFailed {
+                function: name.clone(),
+                error: error.clone(),
+                reason: "mock failure".into(),
+            });
+        }
+
+        Ok(self.results.get(name).cloned().unwrap_or_default())
+    }
+}
+
+/// Action to take when a function result exceeds the limit of a [`SizeLimitActionExecutor`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum OversizedResult {
+    /// Return an [`ActionResultTooLarge`] error, which is not handled by the workflow.
+    ///
+    /// [`ActionResultTooLarge`]: crate::Error::ActionResultTooLarge
+    Reject,
+
+    /// Return an [`ActionFailed`] error named [`RESULT_TOO_LARGE_ERROR`], so that
+    /// the state's [error handlers] can handle it.
+    ///
+    /// [`ActionFailed`]: crate::Error::ActionFailed
+    /// [error handlers]: crate::workflow::definition::State::error_handler
+    Fail,
+
+    /// Replace the result with `null`.
+    Discard,
+}
+
+/// Name of the workflow error returned when a function result exceeds the limit of a
+/// [`SizeLimitActionExecutor`] configured to [fail](OversizedResult::Fail).
+pub const RESULT_TOO_LARGE_ERROR: &str = "ResultTooLarge";
+
+/// [`ActionExecutor`] limiting the size of the results returned by another executor.
+///
+/// Result sizes are measured in bytes of serialized JSON. What happens when a result exceeds
+/// the limit is determined by the executor's [`OversizedResult`] policy (by default,
+/// [`Reject`](OversizedResult::Reject)).
+#[derive(Debug, Clone)]
+pub struct SizeLimitActionExecutor<E> {
+    executor: E,
+    limit: usize,
+    policy: OversizedResult,
+}
+
+impl<E> SizeLimitActionExecutor<E> {
+    /// Creates an executor rejecting results of the given executor larger than `limit` bytes.
+    pub fn new(executor: E, limit: usize) -> Self {
+        Self { executor, limit, policy: OversizedResult::Reject }
+    }
+
+    /// Returns a copy of this executor that handles oversized results with the given policy.
+    pub fn with_policy(mut self, policy: OversizedResult) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+impl<E> ActionExecutor for SizeLimitActionExecutor<E>
+where
+    E: ActionExecutor,
+{
+    fn execute(&self, call: &FunctionCall<'_>) -> crate::Result<Value> {
+        let result = self.executor.execute(call)?;
+        let size = json_size(&result);
+        if size <= self.limit {
+            return Ok(result);
+        }
+
+        let function = call.function.name.clone();
+        match self.policy {
+            OversizedResult::Reject => {
+                Err(crate::Error::ActionResultTooLarge { function, size, limit: self.limit })
+            },
+            OversizedResult::Fail => Err(crate::Error::ActionFailed {
+                function,
+                error: RESULT_TOO_LARGE_ERROR.into(),
+                reason: format!("result is {} bytes, limit is {} bytes", size, self.limit),
+            }),
+            OversizedResult::Discard => Ok(Value::Null),
+        }
+    }
+
+    fn check(&self, call: &FunctionCall<'_>) -> crate::Result<()> {
+        self.executor.check(call)
+    }
+}
+
+/// [`ActionExecutor`] routing function calls to named worker pools, based on the
+/// [annotations] of the calling workflows.
+///
+/// Each pool is named after the annotation it serves (e.g. `"gpu"` or `"io-heavy"`) and limits
+/// how many of its calls are performed concurrently; calls beyond the limit block until another
+/// call of the same pool completes. This way, runtimes executing workflow instances of
+/// heterogeneous workloads on shared threads can keep one workload from starving the others.
+///
+/// A call is routed to the first pool (in the order they were [added](Self::with_pool)) whose
+/// name is one of the calling workflow's annotations. Calls from workflows matching no pool
+/// belong to the default pool, which is unbounded unless [configured](Self::with_default_concurrency)
+/// otherwise. All calls are performed by the wrapped executor, which can inspect the
+/// [call context](InvocationContext::annotations) if pools need to be handled differently.
+///
+/// [annotations]: WorkflowDefinition::annotations
+#[derive(Debug)]
+pub struct PooledActionExecutor<E> {
+    executor: E,
+    pools: Vec<(String, Semaphore)>,
+    default_pool: Option<Semaphore>,
+}
+
+impl<E> PooledActionExecutor<E> {
+    /// Creates an executor with no worker pools, calling functions with the given executor.
+    pub fn new(executor: E) -> Self {
+        Self { executor, pools: Vec::new(), default_pool: None }
+    }
+
+    /// Adds a pool serving the workflows with the given annotation, performing at most
+    /// `concurrency` calls at the same time.
+    ///
+    /// If a pool with the same name was already added, its concurrency is replaced.
+    pub fn with_pool<S>(mut self, name: S, concurrency: NonZeroUsize) -> Self
+    where
+        S: Into<String>,
+    {
+        let name = name.into();
+        let semaphore = Semaphore::new(concurrency);
+        match self.pools.iter_mut().find(|(pool, _)| *pool == name) {
+            Some((_, pool)) => *pool = semaphore,
+            None => self.pools.push((name, semaphore)),
+        }
+        self
+    }
+
+    /// Limits the number of calls performed at the same time for workflows matching no pool.
+    pub fn with_default_concurrency(mut self, concurrency: NonZeroUsize) -> Self {
+        self.default_pool = Some(Semaphore::new(concurrency));
+        self
+    }
+
+    /// Returns the name of the pool the given call is routed to, or `None` if it is routed
+    /// to the default pool.
+    pub fn pool(&self, call: &FunctionCall<'_>) -> Option<&str> {
+        self.find_pool(call).map(|(name, _)| name.as_str())
+    }
+
+    fn find_pool(&self, call: &FunctionCall<'_>) -> Option<&(String, Semaphore)> {
+        self.pools
+            .iter()
+            .find(|(name, _)| call.context.annotations.contains(name))
+    }
+}
+
+impl<E> ActionExecutor for PooledActionExecutor<E>
+where
+    E: ActionExecutor,
+{
+    /// Calls a function with the wrapped executor once a slot is available in the call's pool.
+    fn execute(&self, call: &FunctionCall<'_>) -> crate::Result<Value> {
+        let _permit = self
+            .find_pool(call)
+            .map(|(_, pool)| pool)
+            .or(self.default_pool.as_ref())
+            .map(Semaphore::acquire);
+
+        self.executor.execute(call)
+    }
+
+    fn check(&self, call: &FunctionCall<'_>) -> crate::Result<()> {
+        self.executor.check(call)
+    }
+}
+
+// Counting semaphore limiting the number of concurrent calls in a worker pool.
+#[derive(Debug)]
+struct Semaphore {
+    limit: usize,
+    active: Mutex<usize>,
+    released: Condvar,
+}
+
+impl Semaphore {
+    fn new(limit: NonZeroUsize) -> Self {
+        Self { limit: limit.get(), active: Mutex::new(0), released: Condvar::new() }
+    }
+
+    // Blocks until a slot is available, then returns a permit releasing the slot when dropped.
+    fn acquire(&self) -> SemaphorePermit<'_> {
+        let mut active = self.active.lock().unwrap_or_else(PoisonError::into_inner);
+        while *active >= self.limit {
+            active = self
+                .released
+                .wait(active)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        *active += 1;
+
+        SemaphorePermit(self)
+    }
+}
+
+struct SemaphorePermit<'a>(&'a Semaphore);
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        *self.0.active.lock().unwrap_or_else(PoisonError::into_inner) -= 1;
+        self.0.released.notify_one();
+    }
+}
+
+type FunctionHandler = dyn Fn(&FunctionCall<'_>) -> crate::Result<Value>;
+
+/// [`ActionExecutor`] calling Rust closures registered by function name.
+///
+/// Handlers can be registered for functions of any [type], but this is especially useful for
+/// [custom functions] implemented by the application itself. Calls to functions with no
+/// registered handler are passed to the [fallback executor], if any.
+///
+/// [type]: Function::function_type
+/// [custom functions]: crate::workflow::definition::functions::FunctionType::Custom
+/// [fallback executor]: Self::with_fallback
+#[derive(Default)]
+pub struct HandlerActionExecutor {
+    handlers: HashMap<String, Box<FunctionHandler>>,
+    fallback: Option<Box<dyn ActionExecutor>>,
+}
+
+impl HandlerActionExecutor {
+    /// Creates a new executor with no registered handlers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a handler called for the function with the given name.
+    ///
+    /// If a handler was already registered for the function, it is replaced.
+    pub fn with_handler<S, F>(mut self, function: S, handler: F) -> Self
+    where
+        S: Into<String>,
+        F: Fn(&FunctionCall<'_>) -> crate::Result<Value> + 'static,
+    {
+        self.handlers.insert(function.into(), Box::new(handler));
+        self
+    }
+
+    /// Sets the executor used to call functions with no registered handler.
+    pub fn with_fallback<E>(mut self, fallback: E) -> Self
+    where
+        E: ActionExecutor + 'static,
+    {
+        self.fallback = Some(Box::new(fallback));
+        self
+    }
+}
+
+impl ActionExecutor for HandlerActionExecutor {
+    /// Calls the handler registered for the function, or the fallback executor if the function
+    /// has no registered handler.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by the handler or fallback executor, in addition to:
+    ///
+    /// * [`MissingFunctionHandler`]: no handler is registered for the function and
+    ///                               there is no fallback executor
+    ///
+    /// [`MissingFunctionHandler`]: crate::Error::MissingFunctionHandler
+    fn execute(&self, call: &FunctionCall<'_>) -> crate::Result<Value> {
+        match (self.handlers.get(&call.function.name), &self.fallback) {
+            (Some(handler), _) => handler(call),
+            (None, Some(fallback)) => fallback.execute(call),
+            (None, None) => {
+                Err(crate::Error::MissingFunctionHandler { function: call.function.name.clone() })
+            },
+        }
+    }
+
+    /// Checks that a handler is registered for the function, or that the fallback executor
+    /// can call it.
+    fn check(&self, call: &FunctionCall<'_>) -> crate::Result<()> {
+        match (self.handlers.contains_key(&call.function.name), &self.fallback) {
+            (true, _) => Ok(()),
+            (false, Some(fallback)) => fallback.check(call),
+            (false, None) => {
+                Err(crate::Error::MissingFunctionHandler { function: call.function.name.clone() })
+            },
+        }
+    }
+}
+
+impl fmt::Debug for HandlerActionExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut functions: Vec<_> = self.handlers.keys().collect();
+        functions.sort();
+
+        f.debug_struct("HandlerActionExecutor")
+            .field("handlers", &functions)
+            .field("fallback", &self.fallback.is_some())
+            .finish()
+    }
+}
+
+// Runs a future to completion on the current thread, parking the thread while the future
+// is pending.
+fn block_on<F>(future: F) -> F::Output
+where
+    F: Future,
+{
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Executor calling [GraphQL functions].
+//!
+//! [GraphQL functions]: crate::workflow::definition::functions::FunctionType::GraphQL
+
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::workflow::definition::functions::FunctionType;
+#[cfg(feature = "graphql")]
+use crate::workflow::executor::http::send;
+use crate::workflow::executor::http::{authorization, EgressPolicy, HttpRequest};
+use crate::workflow::executor::{ActionExecutor, FunctionCall};
+
+/// [`ActionExecutor`] calling [GraphQL functions] over HTTP.
+///
+/// The [operation] of GraphQL functions has the format `<endpoint>#<query|mutation>#<name>`.
+/// The executor sends a single query or mutation calling the field `name`, with the function
+/// arguments passed as field arguments and the call's [selection set] requested on the result.
+/// Arguments are written as GraphQL literals, so enum values cannot be passed.
+///
+/// The result of the function is the value of the field in the response's `data`. If the
+/// response contains errors, an [`ActionFailed`] error is returned; its error name is the
+/// `code` extension of the first error, if any, or [`GRAPHQL_ERROR`] otherwise. HTTP errors
+/// are reported like for [REST functions].
+///
+/// [GraphQL functions]: FunctionType::GraphQL
+/// [operation]: crate::workflow::definition::functions::Function::operation
+/// [selection set]: FunctionCall::selection_set
+/// [`ActionFailed`]: crate::Error::ActionFailed
+/// [`GRAPHQL_ERROR`]: Self::GRAPHQL_ERROR
+/// [REST functions]: crate::workflow::executor::rest::RestActionExecutor
+#[derive(Debug, Clone)]
+pub struct GraphQlActionExecutor {
+    egress: EgressPolicy,
+    #[cfg(feature = "graphql")]
+    agent: ureq::Agent,
+}
+
+impl GraphQlActionExecutor {
+    /// Error name of [`ActionFailed`] errors returned when the GraphQL response contains errors
+    /// without a `code` extension.
+    ///
+    /// [`ActionFailed`]: crate::Error::ActionFailed
+    pub const GRAPHQL_ERROR: &'static str = "graphql";
+
+    /// Creates a new executor.
+    pub fn new() -> Self {
+        Self {
+            egress: EgressPolicy::default(),
+            #[cfg(feature = "graphql")]
+            agent: ureq::Agent::new(),
+        }
+    }
+
+    /// Returns a copy of this executor that only sends requests allowed by the given
+    /// [`EgressPolicy`]. By default, requests can be sent to any URL.
+    pub fn with_egress_policy(mut self, egress: EgressPolicy) -> Self {
+        self.egress = egress;
+        self
+    }
+
+    /// Returns the HTTP request to send to call a function.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidFunctionOperation`]: the function is not a GraphQL function, or its operation
+    ///                                 does not have the expected format
+    /// * [`InvalidUrl`]: the GraphQL endpoint URL is invalid
+    /// * [`UnsupportedAuth`]: the function's auth definition is not supported
+    /// * [`EgressDenied`]: the executor's [egress policy] does not allow the request
+    ///
+    /// [`InvalidFunctionOperation`]: crate::Error::InvalidFunctionOperation
+    /// [`InvalidUrl`]: crate::Error::InvalidUrl
+    /// [`UnsupportedAuth`]: crate::Error::UnsupportedAuth
+    /// [`EgressDenied`]: crate::Error::EgressDenied
+    /// [egress policy]: Self::with_egress_policy
+    pub fn request(&self, call: &FunctionCall<'_>) -> crate::Result<HttpRequest> {
+        let function = call.function;
+        let invalid = |reason| crate::Error::InvalidFunctionOperation {
+            function: function.name.clone(),
+            operation: function.operation.clone(),
+            reason,
+        };
+
+        if function.function_type != FunctionType::GraphQL {
+            return Err(invalid("only GraphQL functions are supported"));
+        }
+        let mut parts = function.operation.rsplitn(3, '#');
+        let (Some(name), Some(kind), Some(endpoint)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid("expected '<endpoint>#<query|mutation>#<name>'"));
+        };
+        if kind != "query" && kind != "mutation" {
+            return Err(invalid("expected 'query' or 'mutation'"));
+        }
+        let url = Url::parse(endpoint)?;
+
+        let mut document = format!("{} {{ {}", kind, name);
+        if !call.arguments.is_empty() {
+            let arguments: Vec<_> = call
+                .arguments
+                .iter()
+                .map(|(name, value)| format!("{}: {}", name, literal(value)))
+                .collect();
+            document.push_str(&format!("({})", arguments.join(", ")));
+        }
+        match call.selection_set.as_deref().map(str::trim) {
+            Some(selection_set) if selection_set.starts_with('{') => {
+                document.push_str(&format!(" {}", selection_set));
+            },
+            Some(selection_set) if !selection_set.is_empty() => {
+                document.push_str(&format!(" {{ {} }}", selection_set));
+            },
+            _ => (),
+        }
+        document.push_str(" }");
+
+        let mut headers = Vec::new();
+        if let Some(auth) = call.auth {
+            headers.push(("Authorization".into(), authorization(auth)?));
+        }
+
+        let body = Some(json!({ "query": document }));
+        let request = HttpRequest { method: "POST", url, headers, body, timeout: None }
+            .with_context(&call.context);
+        self.egress.check(&function.name, &request)?;
+        Ok(request)
+    }
+
+    /// Returns the result of a function from a GraphQL response.
+    ///
+    /// # Errors
+    ///
+    /// * [`ActionFailed`]: the response contains errors
+    ///
+    /// [`ActionFailed`]: crate::Error::ActionFailed
+    pub fn result(&self, call: &FunctionCall<'_>, response: Value) -> crate::Result<Value> {
+        let mut response = match response {
+            Value::Object(response) => response,
+            _ => Default::default(),
+        };
+
+        match response.remove("errors") {
+            Some(Value::Array(errors)) if !errors.is_empty() => {
+                let error = errors[0]
+                    .pointer("/extensions/code")
+                    .and_then(Value::as_str)
+                    .unwrap_or(Self::GRAPHQL_ERROR);
+                let messages: Vec<_> = errors
+                    .iter()
+                    .filter_map(|error| error.get("message").and_then(Value::as_str))
+                    .collect();
+
+                Err(crate::Error::ActionFailed {
+                    function: call.function.name.clone(),
+                    error: error.into(),
+                    reason: messages.join("; "),
+                })
+            },
+            _ => {
+                let name = call
+                    .function
+                    .operation
+                    .rsplit('#')
+                    .next()
+                    .unwrap_or_default();
+                Ok(response
+                    .get_mut("data")
+                    .and_then(|data| data.get_mut(name))
+                    .map(Value::take)
+                    .unwrap_or_default())
+            },
+        }
+    }
+}
+
+impl ActionExecutor for GraphQlActionExecutor {
+    #[cfg_attr(
+        feature = "graphql",
+        doc = r"
+            Sends the [HTTP request](Self::request) calling the function and returns the
+            [result](Self::result) found in the response.
+
+            # Errors
+
+            Any error returned by [`request`](Self::request) or [`result`](Self::result),
+            in addition to:
+
+            * [`ActionFailed`](crate::Error::ActionFailed): the server responded with an error
+              status, or the request could not be sent
+        "
+    )]
+    #[cfg_attr(
+        not(feature = "graphql"),
+        doc = r"
+            Calls a GraphQL function.
+
+            Always returns [`FeatureDisabled`] because the `graphql` feature is disabled.
+
+            [`FeatureDisabled`]: crate::Error::FeatureDisabled
+        "
+    )]
+    fn execute(&self, #[allow(unused)] call: &FunctionCall<'_>) -> crate::Result<Value> {
+        #[cfg(feature = "graphql")]
+        {
+            let response = send(&self.agent, &call.function.name, &self.request(call)?)?;
+            self.result(call, response)
+        }
+
+        #[cfg(not(feature = "graphql"))]
+        {
+            Err(crate::Error::FeatureDisabled { required_feature: "graphql" })
+        }
+    }
+
+    /// Checks that the function's operation is valid, that its auth definition is supported
+    /// and that the executor's [egress policy](GraphQlActionExecutor::with_egress_policy)
+    /// allows calling its endpoint.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by [`request`](Self::request).
+    fn check(&self, call: &FunctionCall<'_>) -> crate::Result<()> {
+        if cfg!(not(feature = "graphql")) {
+            return Err(crate::Error::FeatureDisabled { required_feature: "graphql" });
+        }
+
+        self.request(call).map(|_| ())
+    }
+}
+
+impl Default for GraphQlActionExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// JSON strings, numbers, booleans and `null` are also valid GraphQL literals, but object keys
+// must not be quoted.
+fn literal(value: &Value) -> String {
+    match value {
+        Value::Array(values) => {
+            let values: Vec<_> = values.iter().map(literal).collect();
+            format!("[{}]", values.join(", "))
+        },
+        Value::Object(fields) => {
+            let fields: Vec<_> = fields
+                .iter()
+                .map(|(name, value)| format!("{}: {}", name, literal(value)))
+                .collect();
+            format!("{{{}}}", fields.join(", "))
+        },
+        value => value.to_string(),
+    }
+}
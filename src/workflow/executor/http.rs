@@ -0,0 +1,318 @@
+//! HTTP requests sent by executors calling remote services.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde_json::Value;
+use url::Url;
+
+use crate::workflow::definition::auth::{
+    AuthDef, AuthDefProperties, BasicPropsDef, BearerPropsDef,
+};
+use crate::workflow::executor::InvocationContext;
+
+/// Error name of [`ActionFailed`] errors returned when an HTTP request could not be sent
+/// or its response could not be read.
+///
+/// [`ActionFailed`]: crate::Error::ActionFailed
+pub const TRANSPORT_ERROR: &str = "transport";
+
+/// Error name of [`ActionFailed`] errors returned when an HTTP request is not sent because
+/// the action's [deadline](InvocationContext::deadline) has passed.
+///
+/// [`ActionFailed`]: crate::Error::ActionFailed
+pub const TIMEOUT_ERROR: &str = "timeout";
+
+/// Name of the HTTP header used to send the call's
+/// [idempotency key](InvocationContext::idempotency_key).
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// HTTP request sent by an executor to call a function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    /// HTTP method (e.g. `GET`)
+    pub method: &'static str,
+
+    /// Request URL, including query parameters
+    pub url: Url,
+
+    /// HTTP headers
+    pub headers: Vec<(String, String)>,
+
+    /// JSON request body, if any
+    pub body: Option<Value>,
+
+    /// Time left to complete the request, from the call's [deadline](InvocationContext::deadline)
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    // Applies the parts of a call's context that can be propagated over HTTP.
+    pub(crate) fn with_context(mut self, context: &InvocationContext) -> Self {
+        if let Some(idempotency_key) = &context.idempotency_key {
+            self.headers
+                .push((IDEMPOTENCY_KEY_HEADER.into(), idempotency_key.clone()));
+        }
+        self.timeout = context.remaining();
+        self
+    }
+}
+
+/// Policy restricting the URLs that executors may send [HTTP requests](HttpRequest) to.
+///
+/// A policy is made of rules [allowing](Self::allow) or [denying](Self::deny) URLs matching
+/// [patterns](UrlPattern). Deny rules take precedence over allow rules; URLs matching no rule
+/// are allowed by [`allow_by_default`](Self::allow_by_default) policies and denied by
+/// [`deny_by_default`](Self::deny_by_default) policies.
+///
+/// Deployments executing untrusted workflow definitions can use deny-by-default policies
+/// listing the services that functions are allowed to call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EgressPolicy {
+    allow_by_default: bool,
+    allowed: Vec<UrlPattern>,
+    denied: Vec<UrlPattern>,
+}
+
+impl EgressPolicy {
+    /// Creates a policy allowing all URLs, except those [denied](Self::deny) explicitly.
+    pub fn allow_by_default() -> Self {
+        Self { allow_by_default: true, allowed: Vec::new(), denied: Vec::new() }
+    }
+
+    /// Creates a policy denying all URLs, except those [allowed](Self::allow) explicitly.
+    pub fn deny_by_default() -> Self {
+        Self { allow_by_default: false, ..Self::allow_by_default() }
+    }
+
+    /// Returns a copy of this policy allowing URLs matching the given pattern, unless
+    /// they are [denied](Self::deny).
+    pub fn allow(mut self, pattern: UrlPattern) -> Self {
+        self.allowed.push(pattern);
+        self
+    }
+
+    /// Returns a copy of this policy denying URLs matching the given pattern.
+    pub fn deny(mut self, pattern: UrlPattern) -> Self {
+        self.denied.push(pattern);
+        self
+    }
+
+    /// Returns `true` if requests can be sent to the given URL.
+    pub fn allows(&self, url: &Url) -> bool {
+        if self.denied.iter().any(|pattern| pattern.matches(url)) {
+            return false;
+        }
+        self.allow_by_default || self.allowed.iter().any(|pattern| pattern.matches(url))
+    }
+
+    // Checks that the policy allows sending the request calling the given function.
+    pub(crate) fn check(&self, function: &str, request: &HttpRequest) -> crate::Result<()> {
+        if self.allows(&request.url) {
+            Ok(())
+        } else {
+            Err(crate::Error::EgressDenied { function: function.into(), url: request.url.clone() })
+        }
+    }
+}
+
+impl Default for EgressPolicy {
+    /// Creates a policy allowing all URLs.
+    fn default() -> Self {
+        Self::allow_by_default()
+    }
+}
+
+/// Pattern matching URLs, used in an [`EgressPolicy`].
+///
+/// Patterns have the format `[<scheme>://]<host>[:<port>][/<path>]`:
+///
+/// * `scheme`: if specified, URLs must use this scheme (e.g. `https`).
+/// * `host`: URLs must have this host. `*.example.com` matches all subdomains of `example.com`
+///   (but not `example.com` itself), and `*` matches all hosts.
+/// * `port`: if specified, URLs must use this port (or use this port by default for their
+///   scheme, e.g. `443` for `https`).
+/// * `path`: if specified, the path of URLs must start with this path (e.g. `/v1/`).
+///
+/// Hosts and schemes are case-insensitive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlPattern {
+    scheme: Option<String>,
+    host: String,
+    port: Option<u16>,
+    path: Option<String>,
+}
+
+impl UrlPattern {
+    /// Returns `true` if the given URL matches this pattern.
+    pub fn matches(&self, url: &Url) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let host = host.to_ascii_lowercase();
+        let host_matches = match self.host.strip_prefix("*.") {
+            _ if self.host == "*" => true,
+            Some(domain) => host
+                .strip_suffix(domain)
+                .is_some_and(|subdomain| subdomain.ends_with('.')),
+            None => host == self.host,
+        };
+
+        let scheme_matches = match &self.scheme {
+            Some(scheme) => url.scheme() == scheme,
+            None => true,
+        };
+        let port_matches = match self.port {
+            Some(port) => url.port_or_known_default() == Some(port),
+            None => true,
+        };
+        let path_matches = match &self.path {
+            Some(path) => url.path().starts_with(path.as_str()),
+            None => true,
+        };
+
+        host_matches && scheme_matches && port_matches && path_matches
+    }
+}
+
+impl FromStr for UrlPattern {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason| crate::Error::InvalidUrlPattern { pattern: s.into(), reason };
+
+        let (scheme, rest) = match s.split_once("://") {
+            Some((scheme, rest)) => (Some(scheme.to_ascii_lowercase()), rest),
+            None => (None, s),
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], Some(rest[i..].to_string())),
+            None => (rest, None),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            // Bracketed IPv6 addresses contain colons.
+            Some((host, port)) if !port.contains(']') => {
+                (host, Some(port.parse().map_err(|_| invalid("invalid port"))?))
+            },
+            _ => (authority, None),
+        };
+
+        if scheme.as_deref().is_some_and(str::is_empty) {
+            return Err(invalid("empty scheme"));
+        }
+        if host.is_empty() {
+            return Err(invalid("empty host"));
+        }
+        let valid_wildcard = match host.strip_prefix("*.") {
+            _ if host == "*" => true,
+            Some(domain) => !domain.is_empty() && !domain.contains('*'),
+            None => !host.contains('*'),
+        };
+        if !valid_wildcard {
+            return Err(invalid("wildcards are only supported as '*' or at the start of the host"));
+        }
+
+        Ok(Self { scheme, host: host.to_ascii_lowercase(), port, path })
+    }
+}
+
+impl fmt::Display for UrlPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(scheme) = &self.scheme {
+            write!(f, "{}://", scheme)?;
+        }
+        write!(f, "{}", self.host)?;
+        if let Some(port) = self.port {
+            write!(f, ":{}", port)?;
+        }
+        if let Some(path) = &self.path {
+            write!(f, "{}", path)?;
+        }
+        Ok(())
+    }
+}
+
+// Returns the value of the `Authorization` header to send for the given auth definition.
+pub(crate) fn authorization(auth: &AuthDef) -> crate::Result<String> {
+    match &auth.properties {
+        AuthDefProperties::BasicAuth(BasicPropsDef::AuthInfo(info)) => {
+            let credentials = format!("{}:{}", info.username, info.password);
+            Ok(format!("Basic {}", base64(credentials.as_bytes())))
+        },
+        AuthDefProperties::BearerAuth(BearerPropsDef::AuthInfo(info)) => {
+            Ok(format!("Bearer {}", info.token))
+        },
+        _ => Err(crate::Error::UnsupportedAuth {
+            auth: auth.name.clone(),
+            reason: "only basic and bearer auth with inline credentials are supported",
+        }),
+    }
+}
+
+// Sends a request and returns the response body, parsed as JSON if possible.
+#[cfg(any(feature = "graphql", feature = "rest"))]
+pub(crate) fn send(
+    agent: &ureq::Agent,
+    function: &str,
+    request: &HttpRequest,
+) -> crate::Result<Value> {
+    let failed = |error: String, reason: String| crate::Error::ActionFailed {
+        function: function.into(),
+        error,
+        reason,
+    };
+
+    let mut http_request =
+        request.headers.iter().fold(
+            agent.request_url(request.method, &request.url),
+            |http_request, (name, value)| http_request.set(name, value),
+        );
+    if let Some(timeout) = request.timeout {
+        if timeout.is_zero() {
+            return Err(failed(TIMEOUT_ERROR.into(), "deadline has passed".into()));
+        }
+        http_request = http_request.timeout(timeout);
+    }
+    let response = match &request.body {
+        Some(body) => http_request.send_json(body),
+        None => http_request.call(),
+    };
+
+    match response {
+        Ok(response) => {
+            let body = response
+                .into_string()
+                .map_err(|err| failed(TRANSPORT_ERROR.into(), err.to_string()))?;
+            if body.is_empty() {
+                Ok(Value::Null)
+            } else {
+                Ok(serde_json::from_str(&body).unwrap_or(Value::String(body)))
+            }
+        },
+        Err(ureq::Error::Status(status, response)) => {
+            Err(failed(status.to_string(), response.into_string().unwrap_or_default()))
+        },
+        Err(err) => Err(failed(TRANSPORT_ERROR.into(), err.to_string())),
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let bits = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |bits, (i, byte)| bits | u32::from(*byte) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(char::from(ALPHABET[(bits >> (18 - 6 * i) & 0x3f) as usize]));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
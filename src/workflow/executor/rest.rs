@@ -0,0 +1,309 @@
+//! Executor calling [REST functions] described by [OpenAPI] documents.
+//!
+//! [REST functions]: crate::workflow::definition::functions::FunctionType::Rest
+//! [OpenAPI]: crate::openapi
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use serde_json::Value;
+use url::Url;
+
+use crate::cache::DefinitionCache;
+use crate::openapi::{operation_location, OpenApiDocument, ParameterLocation};
+use crate::workflow::definition::functions::Function;
+#[cfg(feature = "rest")]
+use crate::workflow::executor::http::send;
+use crate::workflow::executor::http::{authorization, EgressPolicy, HttpRequest};
+use crate::workflow::executor::{ActionExecutor, FunctionCall};
+
+/// [`ActionExecutor`] calling [REST functions] over HTTP.
+///
+/// The [operation] of REST functions has the format `<openapi_uri>#<operationId>`. The OpenAPI
+/// document is loaded through the executor's [`DefinitionCache`], then function arguments are
+/// mapped to the operation's parameters by name. If the operation accepts a request body, the
+/// remaining arguments are sent as a JSON object; otherwise, they are ignored.
+///
+/// Requests are sent to the [server] configured on the executor, or to the first server of the
+/// OpenAPI document. Only [basic and bearer auth] with inline credentials are supported;
+/// credentials are used as-is, without evaluating expressions.
+///
+/// If the server responds with an error status, an [`ActionFailed`] error is returned with the
+/// status code (e.g. `404`) as error name. Network errors are reported with the
+/// [`TRANSPORT_ERROR`] error name.
+///
+/// # Thread-safety
+///
+/// Like [`DefinitionCache`], **this class is not thread-safe**.
+///
+/// [REST functions]: crate::workflow::definition::functions::FunctionType::Rest
+/// [operation]: crate::workflow::definition::functions::Function::operation
+/// [server]: Self::with_server
+/// [basic and bearer auth]: crate::workflow::definition::auth::Scheme
+/// [`ActionFailed`]: crate::Error::ActionFailed
+/// [`TRANSPORT_ERROR`]: crate::workflow::executor::http::TRANSPORT_ERROR
+pub struct RestActionExecutor {
+    cache: RefCell<DefinitionCache>,
+    server: Option<Url>,
+    egress: EgressPolicy,
+    #[cfg(feature = "rest")]
+    agent: ureq::Agent,
+}
+
+impl RestActionExecutor {
+    /// Creates an executor loading OpenAPI documents through a new [`DefinitionCache`].
+    pub fn new() -> Self {
+        Self::with_cache(DefinitionCache::new())
+    }
+
+    /// Creates an executor loading OpenAPI documents through the given [`DefinitionCache`].
+    pub fn with_cache(cache: DefinitionCache) -> Self {
+        Self {
+            cache: RefCell::new(cache),
+            server: None,
+            egress: EgressPolicy::default(),
+            #[cfg(feature = "rest")]
+            agent: ureq::Agent::new(),
+        }
+    }
+
+    /// Returns a copy of this executor that sends requests to the given server instead of
+    /// the servers listed in OpenAPI documents.
+    pub fn with_server(mut self, server: Url) -> Self {
+        self.server = Some(server);
+        self
+    }
+
+    /// Returns a copy of this executor that only sends requests allowed by the given
+    /// [`EgressPolicy`]. By default, requests can be sent to any URL.
+    pub fn with_egress_policy(mut self, egress: EgressPolicy) -> Self {
+        self.egress = egress;
+        self
+    }
+
+    /// Returns the HTTP request to send to call a function.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by [`DefinitionCache::get_or_insert`], in addition to:
+    ///
+    /// * [`InvalidFunctionOperation`]: the function is not a REST function, its operation does not
+    ///                                 have the expected format, the OpenAPI document does not
+    ///                                 contain the operation or does not list any server
+    /// * [`InvalidUrl`]: the OpenAPI document URI or server URL is invalid
+    /// * [`MissingFunctionArgument`]: an argument for a required parameter is missing
+    /// * [`UnsupportedAuth`]: the function's auth definition is not supported
+    /// * [`EgressDenied`]: the executor's [egress policy] does not allow the request
+    ///
+    /// [`InvalidFunctionOperation`]: crate::Error::InvalidFunctionOperation
+    /// [`InvalidUrl`]: crate::Error::InvalidUrl
+    /// [`MissingFunctionArgument`]: crate::Error::MissingFunctionArgument
+    /// [`UnsupportedAuth`]: crate::Error::UnsupportedAuth
+    /// [`EgressDenied`]: crate::Error::EgressDenied
+    /// [egress policy]: Self::with_egress_policy
+    pub fn request(&self, call: &FunctionCall<'_>) -> crate::Result<HttpRequest> {
+        let function = call.function;
+        let invalid = |reason| crate::Error::InvalidFunctionOperation {
+            function: function.name.clone(),
+            operation: function.operation.clone(),
+            reason,
+        };
+
+        let (document, operation_id, mut url) = self.resolve(function)?;
+        let operation = document
+            .operation(operation_id)
+            .expect("operation should have been resolved");
+
+        let mut arguments = call.arguments.clone();
+        let mut path_arguments = HashMap::new();
+        let mut query = Vec::new();
+        let mut headers = Vec::new();
+        let mut cookies = Vec::new();
+        for parameter in operation.parameters() {
+            let value = match arguments.remove(&parameter.name) {
+                Some(value) => argument_string(value),
+                None if parameter.required || parameter.location == ParameterLocation::Path => {
+                    return Err(crate::Error::MissingFunctionArgument {
+                        function: function.name.clone(),
+                        argument: parameter.name.clone(),
+                    });
+                },
+                None => continue,
+            };
+
+            match parameter.location {
+                ParameterLocation::Path => {
+                    path_arguments.insert(format!("{{{}}}", parameter.name), value);
+                },
+                ParameterLocation::Query => query.push((parameter.name.as_str(), value)),
+                ParameterLocation::Header => headers.push((parameter.name.clone(), value)),
+                ParameterLocation::Cookie => cookies.push(format!("{}={}", parameter.name, value)),
+            }
+        }
+
+        append_path(&mut url, operation.path, &path_arguments)
+            .map_err(|_| invalid("server URL cannot have a path"))?;
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        if !cookies.is_empty() {
+            headers.push(("Cookie".into(), cookies.join("; ")));
+        }
+        if let Some(auth) = call.auth {
+            headers.push(("Authorization".into(), authorization(auth)?));
+        }
+
+        let body = operation
+            .operation
+            .request_body
+            .as_ref()
+            .map(|_| Value::Object(arguments));
+
+        let request = HttpRequest { method: operation.method, url, headers, body, timeout: None }
+            .with_context(&call.context);
+        self.egress.check(&function.name, &request)?;
+        Ok(request)
+    }
+
+    // Loads the OpenAPI document describing a function's operation. Returns the document,
+    // the operation's ID and the URL of the server to send requests to.
+    fn resolve<'f>(
+        &self,
+        function: &'f Function,
+    ) -> crate::Result<(Rc<OpenApiDocument>, &'f str, Url)> {
+        let invalid = |reason| crate::Error::InvalidFunctionOperation {
+            function: function.name.clone(),
+            operation: function.operation.clone(),
+            reason,
+        };
+
+        let (uri, operation_id) = operation_location(function)?;
+
+        let document = self
+            .cache
+            .borrow_mut()
+            .get_or_insert::<OpenApiDocument, _>(uri.clone())?;
+        if document.operation(operation_id).is_none() {
+            return Err(invalid("operation not found in OpenAPI document"));
+        }
+        let url = match (&self.server, document.servers.first()) {
+            (Some(server), _) => server.clone(),
+            (None, Some(server)) => uri.join(&server.url)?,
+            (None, None) => return Err(invalid("OpenAPI document does not list any server")),
+        };
+
+        Ok((document, operation_id, url))
+    }
+}
+
+impl ActionExecutor for RestActionExecutor {
+    #[cfg_attr(
+        feature = "rest",
+        doc = r"
+            Sends the [HTTP request](Self::request) calling the function and returns the
+            response body, parsed as JSON if possible.
+
+            # Errors
+
+            Any error returned by [`request`](Self::request), in addition to:
+
+            * [`ActionFailed`](crate::Error::ActionFailed): the server responded with an error
+              status, or the request could not be sent
+        "
+    )]
+    #[cfg_attr(
+        not(feature = "rest"),
+        doc = r"
+            Calls a REST function.
+
+            Always returns [`FeatureDisabled`] because the `rest` feature is disabled.
+
+            [`FeatureDisabled`]: crate::Error::FeatureDisabled
+        "
+    )]
+    fn execute(&self, #[allow(unused)] call: &FunctionCall<'_>) -> crate::Result<Value> {
+        #[cfg(feature = "rest")]
+        {
+            send(&self.agent, &call.function.name, &self.request(call)?)
+        }
+
+        #[cfg(not(feature = "rest"))]
+        {
+            Err(crate::Error::FeatureDisabled { required_feature: "rest" })
+        }
+    }
+
+    /// Checks that the function's OpenAPI document can be loaded and describes its operation,
+    /// that a server is available, that the function's auth definition is supported and that
+    /// the executor's [egress policy](RestActionExecutor::with_egress_policy) allows calling
+    /// the operation.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by [`request`](Self::request), except for missing arguments.
+    fn check(&self, call: &FunctionCall<'_>) -> crate::Result<()> {
+        if cfg!(not(feature = "rest")) {
+            return Err(crate::Error::FeatureDisabled { required_feature: "rest" });
+        }
+
+        let (document, operation_id, mut url) = self.resolve(call.function)?;
+        let operation = document
+            .operation(operation_id)
+            .expect("operation should have been resolved");
+        call.auth.map(authorization).transpose()?;
+
+        // Path parameters are not substituted, since arguments are not known in advance.
+        if append_path(&mut url, operation.path, &HashMap::new()).is_ok()
+            && !self.egress.allows(&url)
+        {
+            return Err(crate::Error::EgressDenied { function: call.function.name.clone(), url });
+        }
+        Ok(())
+    }
+}
+
+impl Default for RestActionExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for RestActionExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RestActionExecutor")
+            .field("cache", &self.cache)
+            .field("server", &self.server)
+            .field("egress", &self.egress)
+            .finish()
+    }
+}
+
+// Arguments are sent as-is if they are strings; other values are sent as JSON.
+fn argument_string(value: Value) -> String {
+    match value {
+        Value::String(value) => value,
+        value => value.to_string(),
+    }
+}
+
+// Appends an operation's path to a server URL, substituting path parameters with arguments.
+fn append_path(
+    url: &mut Url,
+    path: &str,
+    path_arguments: &HashMap<String, String>,
+) -> Result<(), ()> {
+    url.path_segments_mut()?.pop_if_empty().extend(
+        path.split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| {
+                path_arguments
+                    .iter()
+                    .fold(segment.to_string(), |segment, (name, value)| {
+                        segment.replace(name, value)
+                    })
+            }),
+    );
+    Ok(())
+}
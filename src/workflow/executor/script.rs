@@ -0,0 +1,274 @@
+//! Executor calling [custom functions] implemented as [Rhai] scripts.
+//!
+//! [custom functions]: crate::workflow::definition::functions::FunctionType::Custom
+//! [Rhai]: https://rhai.rs
+
+#[cfg(feature = "rhai")]
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use crate::workflow::definition::functions::{Function, FunctionType};
+use crate::workflow::executor::{ActionExecutor, FunctionCall};
+
+/// Prefix of the [operation] of custom functions implemented as scripts.
+///
+/// [operation]: Function::operation
+pub const SCRIPT_SCHEME: &str = "script:";
+
+/// Source of a script called by a function.
+///
+/// See [`ScriptActionExecutor::operation`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ScriptSource<'a> {
+    /// Script embedded in the function's operation (`script:<source>`)
+    Embedded(&'a str),
+
+    /// Name of a script [registered] on the executor or stored in its [script directory]
+    /// (`script://<name>`)
+    ///
+    /// [registered]: ScriptActionExecutor::with_script
+    /// [script directory]: ScriptActionExecutor::with_script_dir
+    Referenced(&'a str),
+}
+
+/// [`ActionExecutor`] calling [custom functions] implemented as [Rhai] scripts.
+///
+/// The [operation] of such functions is either `script:<source>`, to embed a short script in the
+/// workflow definition, or `script://<name>`, to reference a script [registered](Self::with_script)
+/// on the executor or stored in its [script directory](Self::with_script_dir). Scripts are
+/// compiled the first time they are called.
+///
+/// The function arguments are available to the script as a map named `input`; the value of the
+/// script's last expression is the function result. For example, a function with operation
+/// `script:input.price * input.quantity` returns the total price of an order line.
+///
+/// If a script throws a string (e.g. `throw "OutOfStock"`), an [`ActionFailed`] error is
+/// returned with that string as error name, so that it can be handled by the state's
+/// [error handlers]. Other script errors are reported with the [`SCRIPT_ERROR`] error name.
+///
+/// # Thread-safety
+///
+/// **This class is not thread-safe**, since compiled scripts are cached.
+///
+/// [custom functions]: FunctionType::Custom
+/// [Rhai]: https://rhai.rs
+/// [operation]: Function::operation
+/// [`ActionFailed`]: crate::Error::ActionFailed
+/// [error handlers]: crate::workflow::definition::State::error_handler
+/// [`SCRIPT_ERROR`]: Self::SCRIPT_ERROR
+#[derive(Default)]
+pub struct ScriptActionExecutor {
+    scripts: HashMap<String, String>,
+    script_dir: Option<PathBuf>,
+    #[cfg(feature = "rhai")]
+    engine: rhai::Engine,
+    #[cfg(feature = "rhai")]
+    compiled: RefCell<HashMap<String, rhai::AST>>,
+}
+
+impl ScriptActionExecutor {
+    /// Error name of [`ActionFailed`] errors returned when a script fails without throwing
+    /// an error name.
+    ///
+    /// [`ActionFailed`]: crate::Error::ActionFailed
+    pub const SCRIPT_ERROR: &'static str = "script";
+
+    /// Creates an executor with no registered script.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a copy of this executor where the given script source is registered under
+    /// the given name.
+    pub fn with_script<N, S>(mut self, name: N, source: S) -> Self
+    where
+        N: Into<String>,
+        S: Into<String>,
+    {
+        self.scripts.insert(name.into(), source.into());
+        self
+    }
+
+    /// Returns a copy of this executor that loads referenced scripts that are not
+    /// [registered](Self::with_script) from files in the given directory.
+    ///
+    /// The script name is used as a path relative to the directory; it cannot contain
+    /// `..` components.
+    pub fn with_script_dir<P>(mut self, script_dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.script_dir = Some(script_dir.into());
+        self
+    }
+
+    /// Returns the source of the script called by a function.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidFunctionOperation`]: the function is not a custom function, or its operation
+    ///                                 is not a script operation
+    ///
+    /// [`InvalidFunctionOperation`]: crate::Error::InvalidFunctionOperation
+    pub fn operation(function: &Function) -> crate::Result<ScriptSource<'_>> {
+        let invalid = |reason| crate::Error::InvalidFunctionOperation {
+            function: function.name.clone(),
+            operation: function.operation.clone(),
+            reason,
+        };
+
+        if function.function_type != FunctionType::Custom {
+            return Err(invalid("only custom functions are supported"));
+        }
+        match function.operation.strip_prefix(SCRIPT_SCHEME) {
+            Some(name) if name.starts_with("//") => Ok(ScriptSource::Referenced(&name[2..])),
+            Some(source) => Ok(ScriptSource::Embedded(source)),
+            None => Err(invalid("expected 'script:<source>' or 'script://<name>'")),
+        }
+    }
+
+    /// Returns the source of a referenced script.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidFunctionOperation`]: the script is not registered and there is no script
+    ///                                 file with this name
+    /// * [`FileIo`]: the script file could not be read
+    ///
+    /// [`InvalidFunctionOperation`]: crate::Error::InvalidFunctionOperation
+    /// [`FileIo`]: crate::Error::FileIo
+    pub fn script(&self, function: &Function, name: &str) -> crate::Result<String> {
+        if let Some(source) = self.scripts.get(name) {
+            return Ok(source.clone());
+        }
+
+        let path = self
+            .script_dir
+            .as_ref()
+            .filter(|_| !name.split(['/', '\\']).any(|component| component == ".."))
+            .map(|script_dir| script_dir.join(name))
+            .filter(|path| path.is_file())
+            .ok_or_else(|| crate::Error::InvalidFunctionOperation {
+                function: function.name.clone(),
+                operation: function.operation.clone(),
+                reason: "script is not registered",
+            })?;
+        Ok(std::fs::read_to_string(path)?)
+    }
+
+    #[cfg(feature = "rhai")]
+    fn call(&self, call: &FunctionCall<'_>) -> crate::Result<Value> {
+        use rhai::{Dynamic, EvalAltResult, Scope};
+
+        let function = call.function;
+        let failed = |error: String, reason: String| crate::Error::ActionFailed {
+            function: function.name.clone(),
+            error,
+            reason,
+        };
+
+        let (key, source) = match Self::operation(function)? {
+            ScriptSource::Embedded(source) => (function.operation.clone(), source.to_string()),
+            ScriptSource::Referenced(name) => {
+                (function.operation.clone(), self.script(function, name)?)
+            },
+        };
+        let cached = self.compiled.borrow().get(&key).cloned();
+        let ast = match cached {
+            Some(ast) => ast,
+            None => {
+                let ast = self
+                    .engine
+                    .compile(source)
+                    .map_err(|err| failed(Self::SCRIPT_ERROR.into(), err.to_string()))?;
+                self.compiled.borrow_mut().insert(key, ast.clone());
+                ast
+            },
+        };
+
+        let input = rhai::serde::to_dynamic(&call.arguments)
+            .map_err(|err| failed(Self::SCRIPT_ERROR.into(), err.to_string()))?;
+        let mut scope = Scope::new();
+        scope.push_dynamic("input", input);
+        let result = self
+            .engine
+            .eval_ast_with_scope::<Dynamic>(&mut scope, &ast)
+            .map_err(|err| match *err {
+                EvalAltResult::ErrorRuntime(value, _) if value.is_string() => {
+                    failed(value.to_string(), "error thrown by script".into())
+                },
+                err => failed(Self::SCRIPT_ERROR.into(), err.to_string()),
+            })?;
+
+        rhai::serde::from_dynamic(&result)
+            .map_err(|err| failed(Self::SCRIPT_ERROR.into(), err.to_string()))
+    }
+}
+
+impl ActionExecutor for ScriptActionExecutor {
+    #[cfg_attr(
+        feature = "rhai",
+        doc = r"
+            Evaluates the function's script with the function arguments as `input`.
+
+            # Errors
+
+            Any error returned by [`operation`](Self::operation) or [`script`](Self::script),
+            in addition to:
+
+            * [`ActionFailed`](crate::Error::ActionFailed): the script could not be compiled
+              or failed
+        "
+    )]
+    #[cfg_attr(
+        not(feature = "rhai"),
+        doc = r"
+            Calls a custom function implemented as a script.
+
+            Always returns [`FeatureDisabled`] because the `rhai` feature is disabled.
+
+            [`FeatureDisabled`]: crate::Error::FeatureDisabled
+        "
+    )]
+    fn execute(&self, #[allow(unused)] call: &FunctionCall<'_>) -> crate::Result<Value> {
+        #[cfg(feature = "rhai")]
+        {
+            self.call(call)
+        }
+
+        #[cfg(not(feature = "rhai"))]
+        {
+            Err(crate::Error::FeatureDisabled { required_feature: "rhai" })
+        }
+    }
+
+    /// Checks that the function's operation is valid and that the script it references,
+    /// if any, can be found. Scripts are not compiled.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by [`operation`](Self::operation) or [`script`](Self::script).
+    fn check(&self, call: &FunctionCall<'_>) -> crate::Result<()> {
+        if cfg!(not(feature = "rhai")) {
+            return Err(crate::Error::FeatureDisabled { required_feature: "rhai" });
+        }
+
+        match Self::operation(call.function)? {
+            ScriptSource::Embedded(_) => Ok(()),
+            ScriptSource::Referenced(name) => self.script(call.function, name).map(|_| ()),
+        }
+    }
+}
+
+impl fmt::Debug for ScriptActionExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScriptActionExecutor")
+            .field("scripts", &self.scripts)
+            .field("script_dir", &self.script_dir)
+            .finish()
+    }
+}
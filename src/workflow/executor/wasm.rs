@@ -0,0 +1,237 @@
+//! Executor calling [custom functions] implemented as WebAssembly modules.
+//!
+//! [custom functions]: crate::workflow::definition::functions::FunctionType::Custom
+
+#[cfg(feature = "wasm")]
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use crate::workflow::definition::functions::{Function, FunctionType};
+use crate::workflow::executor::{ActionExecutor, FunctionCall};
+
+/// Scheme of the [operation] of custom functions implemented as WebAssembly modules.
+///
+/// [operation]: Function::operation
+pub const WASM_SCHEME: &str = "wasm://";
+
+/// [`ActionExecutor`] calling [custom functions] implemented as WebAssembly modules.
+///
+/// The [operation] of such functions has the format `wasm://<module>#<export>`, where `module`
+/// is the name of a module [registered](Self::with_module) on the executor. Each call runs in a
+/// new instance of the module, without any import, so modules cannot access the host.
+///
+/// Modules must export their linear memory as `memory`, along with the following functions:
+///
+/// * `alloc(len: i32) -> i32`: allocates `len` bytes and returns their address
+/// * `<export>(ptr: i32, len: i32) -> i64`: called with the address and length of the function
+///   arguments, serialized as a UTF-8 JSON object. Returns the address of the function result,
+///   serialized as UTF-8 JSON, in its high 32 bits and its length in its low 32 bits.
+///
+/// If the module cannot be instantiated, traps or does not follow this convention, an
+/// [`ActionFailed`] error is returned with the [`WASM_ERROR`] error name.
+///
+/// [custom functions]: FunctionType::Custom
+/// [operation]: Function::operation
+/// [`ActionFailed`]: crate::Error::ActionFailed
+/// [`WASM_ERROR`]: Self::WASM_ERROR
+#[derive(Default)]
+pub struct WasmActionExecutor {
+    modules: HashMap<String, PathBuf>,
+    #[cfg(feature = "wasm")]
+    engine: wasmtime::Engine,
+    #[cfg(feature = "wasm")]
+    compiled: RefCell<HashMap<String, wasmtime::Module>>,
+}
+
+impl WasmActionExecutor {
+    /// Error name of [`ActionFailed`] errors returned when a WebAssembly module fails.
+    ///
+    /// [`ActionFailed`]: crate::Error::ActionFailed
+    pub const WASM_ERROR: &'static str = "wasm";
+
+    /// Creates an executor with no registered module.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a copy of this executor where the WebAssembly module stored at `path`
+    /// (in binary or text format) is registered under the given name.
+    ///
+    /// Modules are compiled the first time they are called.
+    pub fn with_module<S, P>(mut self, name: S, path: P) -> Self
+    where
+        S: Into<String>,
+        P: Into<PathBuf>,
+    {
+        self.modules.insert(name.into(), path.into());
+        self
+    }
+
+    /// Returns the name of the module and export called by a function.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidFunctionOperation`]: the function is not a custom function, or its operation
+    ///                                 does not have the `wasm://<module>#<export>` format
+    ///
+    /// [`InvalidFunctionOperation`]: crate::Error::InvalidFunctionOperation
+    pub fn operation(function: &Function) -> crate::Result<(&str, &str)> {
+        let invalid = |reason| crate::Error::InvalidFunctionOperation {
+            function: function.name.clone(),
+            operation: function.operation.clone(),
+            reason,
+        };
+
+        if function.function_type != FunctionType::Custom {
+            return Err(invalid("only custom functions are supported"));
+        }
+        function
+            .operation
+            .strip_prefix(WASM_SCHEME)
+            .and_then(|operation| operation.split_once('#'))
+            .filter(|(module, export)| !module.is_empty() && !export.is_empty())
+            .ok_or_else(|| invalid("expected 'wasm://<module>#<export>'"))
+    }
+
+    #[cfg(feature = "wasm")]
+    fn call(&self, call: &FunctionCall<'_>, module: &str, export: &str) -> crate::Result<Value> {
+        use wasmtime::{Instance, Module, Store};
+
+        let function = &call.function.name;
+        let failed = |reason: String| crate::Error::ActionFailed {
+            function: function.clone(),
+            error: Self::WASM_ERROR.into(),
+            reason,
+        };
+
+        let path =
+            self.modules
+                .get(module)
+                .ok_or_else(|| crate::Error::InvalidFunctionOperation {
+                    function: function.clone(),
+                    operation: call.function.operation.clone(),
+                    reason: "WebAssembly module is not registered",
+                })?;
+        let cached = self.compiled.borrow().get(module).cloned();
+        let compiled = match cached {
+            Some(compiled) => compiled,
+            None => {
+                let compiled =
+                    Module::from_file(&self.engine, path).map_err(|err| failed(err.to_string()))?;
+                self.compiled
+                    .borrow_mut()
+                    .insert(module.into(), compiled.clone());
+                compiled
+            },
+        };
+
+        let mut store = Store::new(&self.engine, ());
+        let instance =
+            Instance::new(&mut store, &compiled, &[]).map_err(|err| failed(err.to_string()))?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| failed("module does not export its memory".into()))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut store, "alloc")
+            .map_err(|err| failed(err.to_string()))?;
+        let export = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, export)
+            .map_err(|err| failed(err.to_string()))?;
+
+        let input = serde_json::to_vec(&call.arguments)?;
+        let len = i32::try_from(input.len()).map_err(|err| failed(err.to_string()))?;
+        let ptr = alloc
+            .call(&mut store, len)
+            .map_err(|err| failed(err.to_string()))?;
+        memory
+            .write(&mut store, ptr as u32 as usize, &input)
+            .map_err(|err| failed(err.to_string()))?;
+
+        let result = export
+            .call(&mut store, (ptr, len))
+            .map_err(|err| failed(err.to_string()))?;
+        let mut output = vec![0; result as u32 as usize];
+        memory
+            .read(&store, (result >> 32) as u32 as usize, &mut output)
+            .map_err(|err| failed(err.to_string()))?;
+        serde_json::from_slice(&output).map_err(|err| failed(err.to_string()))
+    }
+}
+
+impl ActionExecutor for WasmActionExecutor {
+    #[cfg_attr(
+        feature = "wasm",
+        doc = r"
+            Instantiates the function's WebAssembly module and calls its export with the
+            function arguments.
+
+            # Errors
+
+            Any error returned by [`operation`](Self::operation), in addition to:
+
+            * [`InvalidFunctionOperation`](crate::Error::InvalidFunctionOperation): the module
+              is not registered
+            * [`ActionFailed`](crate::Error::ActionFailed): the module could not be compiled or
+              instantiated, or the call failed
+        "
+    )]
+    #[cfg_attr(
+        not(feature = "wasm"),
+        doc = r"
+            Calls a custom function implemented as a WebAssembly module.
+
+            Always returns [`FeatureDisabled`] because the `wasm` feature is disabled.
+
+            [`FeatureDisabled`]: crate::Error::FeatureDisabled
+        "
+    )]
+    fn execute(&self, #[allow(unused)] call: &FunctionCall<'_>) -> crate::Result<Value> {
+        #[cfg(feature = "wasm")]
+        {
+            let (module, export) = Self::operation(call.function)?;
+            self.call(call, module, export)
+        }
+
+        #[cfg(not(feature = "wasm"))]
+        {
+            Err(crate::Error::FeatureDisabled { required_feature: "wasm" })
+        }
+    }
+
+    /// Checks that the function's operation is valid and that its module is registered.
+    /// Modules are not compiled.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by [`operation`](Self::operation), in addition to:
+    ///
+    /// * [`InvalidFunctionOperation`](crate::Error::InvalidFunctionOperation): the module
+    ///   is not registered
+    fn check(&self, call: &FunctionCall<'_>) -> crate::Result<()> {
+        if cfg!(not(feature = "wasm")) {
+            return Err(crate::Error::FeatureDisabled { required_feature: "wasm" });
+        }
+
+        let (module, _) = Self::operation(call.function)?;
+        if !self.modules.contains_key(module) {
+            return Err(crate::Error::InvalidFunctionOperation {
+                function: call.function.name.clone(),
+                operation: call.function.operation.clone(),
+                reason: "WebAssembly module is not registered",
+            });
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for WasmActionExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WasmActionExecutor")
+            .field("modules", &self.modules)
+            .finish()
+    }
+}
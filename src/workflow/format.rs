@@ -0,0 +1,283 @@
+//! Formats of the IDs and timestamps of serialized workflow instances and subscriptions.
+//!
+//! By default, [workflow instances](crate::workflow::instance::WorkflowInstance) and [event subscriptions](crate::workflow::subscription::EventSubscription)
+//! serialize instance IDs as UUID strings and timestamps as RFC 3339 strings. Persistence layers
+//! storing them in existing databases or analytics schemas can use a [`SerializationFormat`]
+//! to serialize IDs as binary UUIDs and/or timestamps as milliseconds since the Unix epoch:
+//!
+//! ```
+//! # use serde::de::DeserializeSeed;
+//! # use travailleur::workflow::definition::Identifier;
+//! # use travailleur::workflow::format::{IdFormat, SerializationFormat, TimestampFormat};
+//! # use travailleur::workflow::instance::WorkflowInstance;
+//! let identifier = Identifier { id: Some("orders".into()), key: None };
+//! let instance = WorkflowInstance::for_workflow_identifier(identifier, None, None);
+//!
+//! let format = SerializationFormat::new()
+//!     .with_ids(IdFormat::Binary)
+//!     .with_timestamps(TimestampFormat::EpochMillis);
+//! let value = serde_json::to_value(format.apply(&instance)).unwrap();
+//! assert_eq!(16, value["id"].as_array().unwrap().len());
+//!
+//! let deserialized: WorkflowInstance = format.seed().deserialize(value).unwrap();
+//! assert_eq!(instance.id, deserialized.id);
+//! ```
+//!
+//! Values serialized in the default formats can be deserialized directly; values serialized
+//! in other formats must be deserialized through a [seed](SerializationFormat::seed) using
+//! the same formats.
+
+use std::cell::Cell;
+use std::fmt;
+use std::marker::PhantomData;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::de::{self, DeserializeSeed, SeqAccess, Visitor};
+use serde::{ser, Deserialize, Deserializer, Serialize, Serializer};
+use uuid::Uuid;
+
+/// Format of serialized workflow instance IDs.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum IdFormat {
+    /// IDs are serialized as strings (e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`).
+    #[default]
+    String,
+
+    /// IDs are serialized as the 16 bytes of their UUID.
+    ///
+    /// Only IDs that are UUIDs (like those [generated](crate::workflow::instance::WorkflowInstance::for_definition) for new
+    /// instances) can be serialized in this format. Self-describing formats without a byte
+    /// type, like JSON, serialize the bytes as an array of numbers.
+    Binary,
+}
+
+/// Format of serialized timestamps.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TimestampFormat {
+    /// Timestamps are serialized as RFC 3339 strings (e.g. `2024-05-01T12:00:00Z`).
+    #[default]
+    Rfc3339,
+
+    /// Timestamps are serialized as the number of milliseconds since the Unix epoch.
+    ///
+    /// Sub-millisecond precision is lost.
+    EpochMillis,
+}
+
+/// Formats used to serialize the IDs and timestamps of [workflow instances](crate::workflow::instance::WorkflowInstance),
+/// [event subscriptions](crate::workflow::subscription::EventSubscription) and [subscription snapshots](crate::workflow::subscription::SubscriptionSnapshot).
+///
+/// See the [module documentation](self) for an example.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SerializationFormat {
+    /// Format of workflow instance IDs
+    pub ids: IdFormat,
+
+    /// Format of timestamps
+    pub timestamps: TimestampFormat,
+}
+
+impl SerializationFormat {
+    /// Creates a serialization format using the default formats (UUID strings and RFC 3339
+    /// timestamps), which are the formats used when serializing values directly.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a copy of this format serializing instance IDs in the given format.
+    pub fn with_ids(mut self, ids: IdFormat) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Returns a copy of this format serializing timestamps in the given format.
+    pub fn with_timestamps(mut self, timestamps: TimestampFormat) -> Self {
+        self.timestamps = timestamps;
+        self
+    }
+
+    /// Returns a wrapper serializing the given value in this format.
+    pub fn apply<T>(self, value: &T) -> Formatted<'_, T> {
+        Formatted { value, format: self }
+    }
+
+    /// Returns a seed deserializing a value serialized in this format.
+    ///
+    /// Values serialized in the default formats can be deserialized directly.
+    pub fn seed<T>(self) -> FormattedSeed<T> {
+        FormattedSeed { format: self, _value: PhantomData }
+    }
+}
+
+/// Value serialized in a given [`SerializationFormat`].
+///
+/// Returned by [`SerializationFormat::apply`]. Only the IDs and timestamps of
+/// [workflow instances](crate::workflow::instance::WorkflowInstance) and [event subscriptions](crate::workflow::subscription::EventSubscription) found in
+/// the value are affected by the format.
+#[derive(Debug, Clone, Copy)]
+pub struct Formatted<'a, T> {
+    value: &'a T,
+    format: SerializationFormat,
+}
+
+impl<T> Serialize for Formatted<'_, T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        with_format(self.format, || self.value.serialize(serializer))
+    }
+}
+
+/// Seed deserializing a value serialized in a given [`SerializationFormat`].
+///
+/// Returned by [`SerializationFormat::seed`]; see [`Formatted`].
+#[derive(Debug, Clone, Copy)]
+pub struct FormattedSeed<T> {
+    format: SerializationFormat,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<'de, T> DeserializeSeed<'de> for FormattedSeed<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        with_format(self.format, || T::deserialize(deserializer))
+    }
+}
+
+thread_local! {
+    // Format of the value currently being serialized or deserialized on this thread.
+    static CURRENT_FORMAT: Cell<SerializationFormat> = Cell::new(SerializationFormat::new());
+}
+
+// Calls `f` with the current format set to `format`, restoring the previous one afterwards.
+fn with_format<F, R>(format: SerializationFormat, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    struct Restore(SerializationFormat);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT_FORMAT.with(|current| current.set(self.0));
+        }
+    }
+
+    let _restore = Restore(CURRENT_FORMAT.with(|current| current.replace(format)));
+    f()
+}
+
+fn current_format() -> SerializationFormat {
+    CURRENT_FORMAT.with(Cell::get)
+}
+
+// (De)serializes an instance ID in the current `IdFormat`.
+pub(crate) mod id {
+    use super::*;
+
+    pub fn serialize<S>(id: &str, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match current_format().ids {
+            IdFormat::String => serializer.serialize_str(id),
+            IdFormat::Binary => {
+                let uuid = Uuid::parse_str(id).map_err(|_| {
+                    ser::Error::custom(format!(
+                        "instance ID '{}' is not a UUID and cannot be serialized as binary",
+                        id
+                    ))
+                })?;
+                serializer.serialize_bytes(uuid.as_bytes())
+            },
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct BinaryIdVisitor;
+
+        impl<'de> Visitor<'de> for BinaryIdVisitor {
+            type Value = String;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("the 16 bytes of a UUID")
+            }
+
+            fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Uuid::from_slice(value)
+                    .map(|uuid| uuid.to_string())
+                    .map_err(|_| E::invalid_length(value.len(), &self))
+            }
+
+            // Self-describing formats without a byte type (like JSON) serialize bytes as sequences.
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut bytes = Vec::with_capacity(16);
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    bytes.push(byte);
+                }
+                self.visit_bytes(&bytes)
+            }
+        }
+
+        match current_format().ids {
+            IdFormat::String => String::deserialize(deserializer),
+            IdFormat::Binary => deserializer.deserialize_bytes(BinaryIdVisitor),
+        }
+    }
+}
+
+// (De)serializes an optional timestamp in the current `TimestampFormat`.
+pub(crate) mod optional_timestamp {
+    use super::*;
+
+    pub fn serialize<S>(timestamp: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match (timestamp, current_format().timestamps) {
+            (None, _) => serializer.serialize_none(),
+            (Some(timestamp), TimestampFormat::Rfc3339) => {
+                serializer.serialize_some(&timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            },
+            (Some(timestamp), TimestampFormat::EpochMillis) => {
+                serializer.serialize_some(&timestamp.timestamp_millis())
+            },
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match current_format().timestamps {
+            TimestampFormat::Rfc3339 => Option::<DateTime<Utc>>::deserialize(deserializer),
+            TimestampFormat::EpochMillis => match Option::<i64>::deserialize(deserializer)? {
+                Some(millis) => DateTime::from_timestamp_millis(millis)
+                    .map(Some)
+                    .ok_or_else(|| {
+                        de::Error::custom(format!("timestamp {} is out of range", millis))
+                    }),
+                None => Ok(None),
+            },
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Graph of the states of a workflow definition and the transitions between them.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::workflow::definition::{
+    DataCondition, EventCondition, State, SwitchState, Transition, WorkflowDefinition,
+};
+
+/// Kind of [edge](StateEdge) in a [`StateGraph`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// State's `transition`
+    Transition,
+
+    /// Transition of one of a switch state's data or event conditions
+    Condition,
+
+    /// Transition of a switch state's default condition
+    DefaultCondition,
+
+    /// Transition of one of a state's error handlers (`onErrors`)
+    Error,
+
+    /// State used to compensate a state (`compensatedBy`)
+    Compensation,
+}
+
+/// Edge between two states in a [`StateGraph`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct StateEdge<'a> {
+    /// Name of the source state
+    pub from: &'a str,
+
+    /// Name of the target state
+    ///
+    /// This might refer to a state that does not exist in the workflow definition.
+    pub to: &'a str,
+
+    /// Kind of edge
+    pub kind: EdgeKind,
+}
+
+/// Graph of the states of a [`WorkflowDefinition`].
+///
+/// Each node in the graph is a [`State`], while edges represent the possible ways to go from
+/// one state to the next (see [`EdgeKind`]).
+#[derive(Debug, Clone)]
+pub struct StateGraph<'a> {
+    start: Option<&'a str>,
+    states: Vec<&'a State>,
+    edges: HashMap<&'a str, Vec<StateEdge<'a>>>,
+}
+
+impl<'a> StateGraph<'a> {
+    /// Builds the state graph of the given workflow definition.
+    pub fn new(definition: &'a WorkflowDefinition) -> Self {
+        let states: Vec<_> = definition.states.iter().collect();
+        let edges = states
+            .iter()
+            .map(|state| (state.name(), state_edges(state)))
+            .collect();
+
+        Self { start: definition.start_state_name(), states, edges }
+    }
+
+    /// Returns the name of the starting state, if any.
+    ///
+    /// See [`WorkflowDefinition::start_state_name`].
+    pub fn start(&self) -> Option<&'a str> {
+        self.start
+    }
+
+    /// Returns the graph's states, in the order they appear in the workflow definition.
+    pub fn states(&self) -> impl Iterator<Item = &'a State> + '_ {
+        self.states.iter().copied()
+    }
+
+    /// Returns the state with the given name, if it exists.
+    pub fn state(&self, name: &str) -> Option<&'a State> {
+        self.states
+            .iter()
+            .copied()
+            .find(|state| state.name() == name)
+    }
+
+    /// Returns the edges going out of the state with the given name.
+    pub fn edges_from(&self, name: &str) -> impl Iterator<Item = &StateEdge<'a>> + '_ {
+        self.edges.get(name).into_iter().flatten()
+    }
+
+    /// Returns the edges going into the state with the given name.
+    pub fn edges_to<'s>(&'s self, name: &'s str) -> impl Iterator<Item = &'s StateEdge<'a>> + 's {
+        self.states
+            .iter()
+            .flat_map(|state| self.edges_from(state.name()))
+            .filter(move |edge| edge.to == name)
+    }
+
+    /// Returns the shortest path from the [starting state](Self::start) to the state with the given name.
+    ///
+    /// The returned path includes both the starting state and the target state. If the target state
+    /// cannot be reached from the starting state, returns `None`.
+    pub fn path_from_start(&self, name: &str) -> Option<Vec<&'a str>> {
+        let start = self.start.filter(|start| self.state(start).is_some())?;
+
+        let mut previous: HashMap<&'a str, &'a str> = HashMap::new();
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(current) = queue.pop_front() {
+            if current == name {
+                let mut path = vec![current];
+                while let Some(&prev) = previous.get(path.last().unwrap()) {
+                    path.push(prev);
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for edge in self.edges_from(current) {
+                if self.state(edge.to).is_some() && visited.insert(edge.to) {
+                    previous.insert(edge.to, current);
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns the names of all states that can be reached from the [starting state](Self::start).
+    ///
+    /// The starting state itself is included.
+    pub fn reachable_states(&self) -> HashSet<&'a str> {
+        let mut reachable = HashSet::new();
+        let mut queue: VecDeque<_> = self
+            .start
+            .filter(|start| self.state(start).is_some())
+            .into_iter()
+            .collect();
+        while let Some(current) = queue.pop_front() {
+            if reachable.insert(current) {
+                queue.extend(
+                    self.edges_from(current)
+                        .map(|edge| edge.to)
+                        .filter(|to| self.state(to).is_some()),
+                );
+            }
+        }
+
+        reachable
+    }
+}
+
+fn state_edges(state: &State) -> Vec<StateEdge<'_>> {
+    let (transition, on_errors, compensated_by) = match state {
+        State::Sleep(state) => (&state.transition, &state.on_errors, &state.compensated_by),
+        State::Event(state) => (&state.transition, &state.on_errors, &state.compensated_by),
+        State::Operation(state) => (&state.transition, &state.on_errors, &state.compensated_by),
+        State::Parallel(state) => (&state.transition, &state.on_errors, &state.compensated_by),
+        State::Switch(SwitchState::DataBased(state)) => {
+            (&None, &state.on_errors, &state.compensated_by)
+        },
+        State::Switch(SwitchState::EventBased(state)) => {
+            (&None, &state.on_errors, &state.compensated_by)
+        },
+        State::Inject(state) => (&state.transition, &None, &state.compensated_by),
+        State::ForEach(state) => (&state.transition, &state.on_errors, &state.compensated_by),
+        State::Callback(state) => (&state.transition, &state.on_errors, &state.compensated_by),
+        State::Custom(state) => (&state.transition, &None, &None),
+    };
+
+    let (conditions, default_condition): (Vec<&Transition>, _) = match state {
+        State::Switch(SwitchState::DataBased(state)) => (
+            state
+                .data_conditions
+                .iter()
+                .filter_map(|condition| match condition {
+                    DataCondition::Transition(condition) => Some(&condition.transition),
+                    DataCondition::End(_) => None,
+                })
+                .collect(),
+            state.default_condition.transition.as_ref(),
+        ),
+        State::Switch(SwitchState::EventBased(state)) => (
+            state
+                .event_conditions
+                .iter()
+                .filter_map(|condition| match condition {
+                    EventCondition::Transition(condition) => Some(&condition.transition),
+                    EventCondition::End(_) => None,
+                })
+                .collect(),
+            state.default_condition.transition.as_ref(),
+        ),
+        _ => (Vec::new(), None),
+    };
+
+    let from = state.name();
+    let transitions = transition
+        .iter()
+        .map(|transition| (transition, EdgeKind::Transition))
+        .chain(
+            conditions
+                .into_iter()
+                .map(|transition| (transition, EdgeKind::Condition)),
+        )
+        .chain(
+            default_condition
+                .into_iter()
+                .map(|transition| (transition, EdgeKind::DefaultCondition)),
+        )
+        .chain(
+            on_errors
+                .iter()
+                .flatten()
+                .filter_map(|error| error.transition.as_ref())
+                .map(|transition| (transition, EdgeKind::Error)),
+        )
+        .map(|(transition, kind)| StateEdge { from, to: transition.next_state(), kind });
+    let compensation = compensated_by.iter().map(|compensated_by| StateEdge {
+        from,
+        to: compensated_by,
+        kind: EdgeKind::Compensation,
+    });
+
+    transitions.chain(compensation).collect()
+}
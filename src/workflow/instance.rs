@@ -1,10 +1,16 @@
 //! Workflow instance type
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use uuid::Uuid;
 
+use crate::detail::{is_zero, json_size};
+use crate::workflow::definition::timeouts::{Timeouts, WorkflowExecTimeout};
 use crate::workflow::definition::{Identifier, WorkflowDefinition};
+use crate::workflow::format;
+use crate::workflow::runtime::compensation::CompensationLog;
 
 /// Workflow instance container.
 ///
@@ -12,12 +18,16 @@ use crate::workflow::definition::{Identifier, WorkflowDefinition};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowInstance {
     /// Workflow instance ID. Unique among all workflow instances.
+    ///
+    /// Serialized as a string by default; see [`format`](mod@format) for other formats.
+    #[serde(with = "format::id")]
     pub id: String,
 
     /// Workflow identifier.
     pub workflow_identifier: Identifier,
 
-    /// Name of current workflow state, or `None` if workflow has completed.
+    /// Name of current workflow state, or `None` if workflow has completed or is
+    /// [kept active](Self::kept_active).
     pub state: Option<String>,
 
     /// Workflow data (a JSON object), passed between states.
@@ -27,6 +37,42 @@ pub struct WorkflowInstance {
 
     /// Whether workflow has terminated prematurely.
     pub terminated: bool,
+
+    /// Whether the instance is kept active after all its execution paths have ended, because
+    /// its workflow definition [keeps instances active](WorkflowDefinition::keep_active).
+    ///
+    /// Such instances have no current [`state`](Self::state), but are not finished: they await
+    /// events until they are [terminated](Self::terminate). See
+    /// [`EndExecution`](crate::workflow::runtime::end::EndExecution).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub kept_active: bool,
+
+    /// Labels attached to the instance for operational grouping (e.g. deployment, tenant or
+    /// trigger source), by key. See [`LabelSelector`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+
+    /// States of the instance eligible for compensation. See
+    /// [`compensation`](crate::workflow::runtime::compensation).
+    #[serde(default, skip_serializing_if = "CompensationLog::is_empty")]
+    pub compensation: CompensationLog,
+
+    /// Workflow execution timeout overriding the one of the workflow definition, if any.
+    ///
+    /// Set for instances [continuing](crate::workflow::runtime::continue_as) the execution
+    /// of another instance. See [`exec_timeout`](Self::exec_timeout).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow_exec_timeout: Option<WorkflowExecTimeout>,
+
+    /// Number of instances this instance descends from, through
+    /// [`continueAs`](crate::workflow::runtime::continue_as) ends or
+    /// [sub-workflow invocations](crate::workflow::runtime::subflow); `0` if the instance was
+    /// started on its own.
+    ///
+    /// Used to [bound](crate::workflow::runtime::DEFAULT_MAX_DEPTH) workflows that continue as
+    /// or invoke themselves indefinitely.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub depth: u32,
 }
 
 impl WorkflowInstance {
@@ -42,13 +88,11 @@ impl WorkflowInstance {
         definition: &WorkflowDefinition,
         input: Option<Map<String, Value>>,
     ) -> Self {
-        Self {
-            id: Self::generate_id(),
-            workflow_identifier: definition.identifier.clone(),
-            state: definition.start_state_name().map(|name| name.into()),
-            data: input.unwrap_or_default(),
-            terminated: false,
-        }
+        Self::for_workflow_identifier(
+            definition.identifier.clone(),
+            definition.start_state_name().map(|name| name.into()),
+            input,
+        )
     }
 
     /// Generates a new workflow instance for a workflow identified via its [`Identifier`].
@@ -71,10 +115,218 @@ impl WorkflowInstance {
             state,
             data: data.unwrap_or_default(),
             terminated: false,
+            kept_active: false,
+            labels: BTreeMap::new(),
+            compensation: CompensationLog::new(),
+            workflow_exec_timeout: None,
+            depth: 0,
         }
     }
 
+    /// Generates a new workflow instance that resumes execution of a [`WorkflowDefinition`]
+    /// at the given state.
+    ///
+    /// This can be used to restart a failed workflow instance from a chosen point (for example,
+    /// after external systems have been fixed). The instance will have a new, randomly-generated
+    /// [`id`], will point to the state named `state_name` and contain the given `data`.
+    ///
+    /// # Errors
+    ///
+    /// * [`UnknownState`]: the workflow definition has no state named `state_name`
+    /// * [`CompensationState`]: the state is [used for compensation], so it can only be reached
+    ///                          when compensating another state
+    ///
+    /// [`id`]: Self::id
+    /// [`UnknownState`]: crate::Error::UnknownState
+    /// [`CompensationState`]: crate::Error::CompensationState
+    /// [used for compensation]: crate::workflow::definition::State::used_for_compensation
+    pub fn resume_at<S>(
+        definition: &WorkflowDefinition,
+        state_name: S,
+        data: Map<String, Value>,
+    ) -> crate::Result<Self>
+    where
+        S: Into<String>,
+    {
+        let state_name = state_name.into();
+        let state = definition
+            .states
+            .iter()
+            .find(|state| state.name() == state_name)
+            .ok_or_else(|| crate::Error::UnknownState { state: state_name.clone() })?;
+        if state.used_for_compensation() {
+            return Err(crate::Error::CompensationState { state: state_name });
+        }
+
+        Ok(Self::for_workflow_identifier(
+            definition.identifier.clone(),
+            Some(state_name),
+            Some(data),
+        ))
+    }
+
+    /// Returns `true` if the instance is finished, i.e. it has no current [`state`](Self::state)
+    /// and is not [kept active](Self::kept_active).
+    pub fn is_finished(&self) -> bool {
+        self.state.is_none() && !self.kept_active
+    }
+
+    /// Terminates the instance, ending all its execution paths.
+    ///
+    /// Runtimes call this when an [end](crate::workflow::definition::End) with `terminate`
+    /// set is reached (see [`EndExecution`](crate::workflow::runtime::end::EndExecution)),
+    /// or when the instance reaches its [workflow execution timeout](Self::exec_timeout).
+    /// This is the only way to finish instances that are [kept active](Self::kept_active).
+    pub fn terminate(&mut self) {
+        self.state = None;
+        self.terminated = true;
+        self.kept_active = false;
+    }
+
+    /// Returns a copy of this instance with the given label attached.
+    ///
+    /// If the instance already had a label with the same key, its value is replaced.
+    pub fn with_label<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns the value of the instance's label with the given key, if any.
+    pub fn label(&self, key: &str) -> Option<&str> {
+        self.labels.get(key).map(String::as_str)
+    }
+
+    /// Returns the workflow execution timeout of the instance, if any.
+    ///
+    /// This is the instance's [`workflow_exec_timeout`](Self::workflow_exec_timeout) if set,
+    /// or else the workflow execution timeout of the given workflow definition.
+    pub fn exec_timeout<'a>(
+        &'a self,
+        definition: &'a WorkflowDefinition,
+    ) -> Option<&'a WorkflowExecTimeout> {
+        self.workflow_exec_timeout
+            .as_ref()
+            .or(match &definition.timeouts {
+                Some(Timeouts::Complex { workflow_exec_timeout, .. }) => {
+                    workflow_exec_timeout.as_ref()
+                },
+                _ => None,
+            })
+    }
+
+    /// Returns the size of the instance's [data](Self::data), in bytes of serialized JSON.
+    pub fn data_size(&self) -> usize {
+        json_size(&self.data)
+    }
+
+    /// Checks that the instance's [data](Self::data) does not exceed the given size limit,
+    /// in bytes of serialized JSON.
+    ///
+    /// Runtimes can call this after merging state or action results into the instance data,
+    /// before persisting the instance.
+    ///
+    /// # Errors
+    ///
+    /// * [`InstanceDataTooLarge`]: the instance data exceeds the limit
+    ///
+    /// [`InstanceDataTooLarge`]: crate::Error::InstanceDataTooLarge
+    pub fn check_data_size(&self, limit: usize) -> crate::Result<()> {
+        let size = self.data_size();
+        if size > limit {
+            return Err(crate::Error::InstanceDataTooLarge {
+                instance: self.id.clone(),
+                size,
+                limit,
+            });
+        }
+        Ok(())
+    }
+
     fn generate_id() -> String {
         Uuid::new_v4().into()
     }
 }
+
+/// Selects [workflow instances](WorkflowInstance) according to their [labels].
+///
+/// An instance matches a selector if it has all of the selector's labels with the same values,
+/// as well as labels with all of the selector's keys (regardless of their values). An empty
+/// selector matches all instances.
+///
+/// [labels]: WorkflowInstance::labels
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LabelSelector {
+    labels: BTreeMap<String, String>,
+    keys: Vec<String>,
+}
+
+impl LabelSelector {
+    /// Creates a selector matching all instances.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a copy of this selector that only matches instances having the given label.
+    pub fn with_label<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns a copy of this selector that only matches instances having a label with
+    /// the given key, regardless of its value.
+    pub fn with_key<K>(mut self, key: K) -> Self
+    where
+        K: Into<String>,
+    {
+        self.keys.push(key.into());
+        self
+    }
+
+    /// Returns `true` if the given instance matches this selector.
+    pub fn matches(&self, instance: &WorkflowInstance) -> bool {
+        self.labels
+            .iter()
+            .all(|(key, value)| instance.label(key) == Some(value.as_str()))
+            && self
+                .keys
+                .iter()
+                .all(|key| instance.labels.contains_key(key))
+    }
+
+    /// Returns the instances matching this selector.
+    pub fn filter<'a, I>(&'a self, instances: I) -> impl Iterator<Item = &'a WorkflowInstance> + 'a
+    where
+        I: IntoIterator<Item = &'a WorkflowInstance>,
+        I::IntoIter: 'a,
+    {
+        instances
+            .into_iter()
+            .filter(move |instance| self.matches(instance))
+    }
+
+    /// Counts the instances matching this selector by value of the label with the given key
+    /// (e.g. to report metrics per tenant).
+    ///
+    /// Instances that do not have a label with this key are counted under `None`.
+    pub fn count_by<'a, I>(&self, instances: I, key: &str) -> BTreeMap<Option<&'a str>, usize>
+    where
+        I: IntoIterator<Item = &'a WorkflowInstance>,
+    {
+        let mut counts = BTreeMap::new();
+        for instance in instances
+            .into_iter()
+            .filter(|instance| self.matches(instance))
+        {
+            *counts.entry(instance.label(key)).or_default() += 1;
+        }
+        counts
+    }
+}
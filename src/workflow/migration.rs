@@ -0,0 +1,1337 @@
+//! Migration of v0.8 workflow definitions to version 1.0 of the DSL.
+//!
+//! Migration is best-effort: the DSL 1.0 has no direct equivalent for some v0.8 constructs
+//! (for example, compensation or workflow-level error definitions). These constructs are left out
+//! of the migrated definition and reported as [`MigrationIssue`]s, so that they can be migrated
+//! by hand.
+
+use std::fmt;
+
+use serde_json::{json, Map, Value};
+
+use crate::workflow::definition::common::{ExecutionMode, InvocationMode, Metadata};
+use crate::workflow::definition::events::{EventDef, Events};
+use crate::workflow::definition::functions::{Function, FunctionType, Functions};
+use crate::workflow::definition::secrets::Secrets;
+use crate::workflow::definition::timeouts::{StateExecTimeout, Timeouts, WorkflowExecTimeout};
+use crate::workflow::definition::{
+    Action, CompletionType, ContextAttributes, CronDef, Data, DataCondition, DataInputSchema, End,
+    EventCondition, FunctionRef, OnComplete, Schedule, State, StateDataFilter, SubflowRef,
+    SwitchState, Transition, WorkflowDefinition,
+};
+use crate::workflow::v1;
+
+/// Version of the DSL used by migrated workflow definitions.
+pub const DSL_VERSION: &str = "1.0.0";
+
+/// Namespace used by migrated workflow definitions, since v0.8 has no notion of namespace.
+pub const DEFAULT_NAMESPACE: &str = "default";
+
+/// Version used by migrated workflow definitions that do not specify a version.
+pub const DEFAULT_VERSION: &str = "0.0.1";
+
+/// Construct of a v0.8 workflow definition that could not be migrated faithfully.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationIssue {
+    /// [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901) to the construct
+    /// in the v0.8 workflow definition (e.g. `/states/2/onErrors`)
+    pub pointer: String,
+
+    /// Message describing how the construct was (or was not) migrated
+    pub message: String,
+}
+
+impl fmt::Display for MigrationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.pointer.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.pointer, self.message)
+        }
+    }
+}
+
+/// Result of migrating a v0.8 workflow definition to version 1.0 of the DSL.
+///
+/// See [`WorkflowDefinition::migrate_to_v1`].
+#[derive(Debug, Clone)]
+pub struct V1Migration {
+    /// Migrated workflow definition
+    pub definition: v1::WorkflowDefinition,
+
+    /// Constructs that were left out or only approximated in the migrated workflow definition
+    pub issues: Vec<MigrationIssue>,
+}
+
+impl V1Migration {
+    /// Returns `true` if the whole workflow definition could be migrated.
+    pub fn is_complete(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+pub(crate) fn migrate_to_v1(definition: &WorkflowDefinition) -> V1Migration {
+    let mut migrator = Migrator { definition, issues: Vec::new() };
+    let definition = migrator.workflow();
+
+    V1Migration { definition, issues: migrator.issues }
+}
+
+/// Pieces of a state that are common to most state types.
+#[derive(Default)]
+struct StateCommon<'a> {
+    state_data_filter: Option<&'a StateDataFilter>,
+    state_exec_timeout: Option<&'a StateExecTimeout>,
+    has_error_handling: bool,
+    transition: Option<&'a Transition>,
+    end: Option<&'a End>,
+    is_compensated: bool,
+    metadata: Option<&'a Metadata>,
+    has_extensions: bool,
+}
+
+struct Migrator<'a> {
+    definition: &'a WorkflowDefinition,
+    issues: Vec<MigrationIssue>,
+}
+
+impl<'a> Migrator<'a> {
+    fn issue<P, M>(&mut self, pointer: P, message: M)
+    where
+        P: Into<String>,
+        M: Into<String>,
+    {
+        self.issues
+            .push(MigrationIssue { pointer: pointer.into(), message: message.into() });
+    }
+
+    fn workflow(&mut self) -> v1::WorkflowDefinition {
+        let definition = self.definition;
+
+        let name = match definition.identifier.id() {
+            Ok(id) => id.to_string(),
+            Err(_) => {
+                self.issue("", "workflow definition has no identifier");
+                String::new()
+            },
+        };
+        let version = match &definition.version {
+            Some(version) => version.clone(),
+            None => {
+                self.issue(
+                    "",
+                    format!("workflow definition has no version; using '{}'", DEFAULT_VERSION),
+                );
+                DEFAULT_VERSION.into()
+            },
+        };
+        let mut metadata = metadata_map(definition.metadata.as_ref()).unwrap_or_default();
+        if let Some(annotations) = &definition.annotations {
+            metadata.insert("annotations".into(), json!(annotations));
+        }
+        let document = v1::Document {
+            dsl: DSL_VERSION.into(),
+            namespace: DEFAULT_NAMESPACE.into(),
+            name,
+            version,
+            title: definition.name.clone(),
+            summary: definition.description.clone(),
+            tags: None,
+            metadata: (!metadata.is_empty()).then_some(metadata),
+        };
+
+        let input = definition.data_input_schema.as_ref().map(|schema| {
+            if let DataInputSchema::Full { fail_on_validation_errors: false, .. } = schema {
+                self.issue(
+                    "/dataInputSchema/failOnValidationErrors",
+                    "invalid workflow input is always rejected in DSL 1.0",
+                );
+            }
+
+            v1::Input {
+                schema: Some(
+                    json!({ "format": "json", "resource": { "endpoint": schema.schema() } }),
+                ),
+                from: None,
+            }
+        });
+
+        if definition.expression_lang != "jq" {
+            self.issue(
+                "/expressionLang",
+                format!(
+                    "expression language '{}' is not supported in DSL 1.0; expressions are copied as-is",
+                    definition.expression_lang
+                ),
+            );
+        }
+        if definition.constants.is_some() {
+            self.issue(
+                "/constants",
+                "constants have no equivalent in DSL 1.0; they can be passed as workflow input instead",
+            );
+        }
+        if definition.errors.is_some() {
+            self.issue(
+                "/errors",
+                "error definitions must be migrated to the `catch` clause of `try` tasks",
+            );
+        }
+        if definition.retries.is_some() {
+            self.issue(
+                "/retries",
+                "retry definitions must be migrated to the `retry` policies of `try` tasks",
+            );
+        }
+        if definition.auth.is_some() {
+            self.issue("/auth", "auth definitions must be migrated to `use.authentications`");
+        }
+        if definition.keep_active {
+            self.issue("/keepActive", "keepActive has no equivalent in DSL 1.0");
+        }
+        if definition.auto_retries {
+            self.issue("/autoRetries", "autoRetries has no equivalent in DSL 1.0");
+        }
+        if let Some(Events::Uri(_)) = &definition.events {
+            self.unresolved_uri("/events");
+        }
+        if let Some(Functions::Uri(_)) = &definition.functions {
+            self.unresolved_uri("/functions");
+        }
+        if !definition.extensions.is_empty() {
+            self.issue("", "extension properties are not migrated");
+        }
+
+        let uses = self.uses();
+        let timeout = self.workflow_timeout();
+        let schedule = self.schedule();
+        let tasks = self.states();
+
+        v1::WorkflowDefinition { document, input, uses, tasks, timeout, output: None, schedule }
+    }
+
+    fn unresolved_uri(&mut self, pointer: &str) {
+        self.issue(
+            pointer,
+            "definitions referenced by URI must be resolved before migrating \
+                (see `WorkflowDefinition::resolve_external_refs`)",
+        );
+    }
+
+    fn uses(&mut self) -> Option<v1::Use> {
+        match &self.definition.secrets {
+            Some(Secrets::Inline(secrets)) => {
+                Some(v1::Use { secrets: Some(secrets.clone()), ..v1::Use::default() })
+            },
+            Some(Secrets::Uri(_)) => {
+                self.unresolved_uri("/secrets");
+                None
+            },
+            None => None,
+        }
+    }
+
+    fn workflow_timeout(&mut self) -> Option<Value> {
+        let timeouts = match &self.definition.timeouts {
+            Some(Timeouts::Uri(_)) => {
+                self.unresolved_uri("/timeouts");
+                return None;
+            },
+            Some(timeouts) => timeouts,
+            None => return None,
+        };
+        let Timeouts::Complex {
+            workflow_exec_timeout,
+            state_exec_timeout,
+            action_exec_timeout,
+            branch_exec_timeout,
+            event_timeout,
+        } = timeouts
+        else {
+            return None;
+        };
+
+        if state_exec_timeout.is_some()
+            || action_exec_timeout.is_some()
+            || branch_exec_timeout.is_some()
+            || event_timeout.is_some()
+        {
+            self.issue(
+                "/timeouts",
+                "default timeouts have no equivalent in DSL 1.0; they must be set on each task",
+            );
+        }
+
+        workflow_exec_timeout.as_ref().map(|timeout| {
+            let duration = match timeout {
+                WorkflowExecTimeout::Simple(duration) => duration,
+                WorkflowExecTimeout::Complex { duration, interrupt, run_before } => {
+                    if !interrupt || run_before.is_some() {
+                        self.issue(
+                            "/timeouts/workflowExecTimeout",
+                            "only the duration of the workflow execution timeout is migrated",
+                        );
+                    }
+                    duration
+                },
+            };
+            json!({ "after": duration })
+        })
+    }
+
+    fn schedule(&mut self) -> Option<v1::Schedule> {
+        let schedule = self.definition.start.as_ref()?.schedule()?;
+
+        let every = schedule.interval().map(|interval| {
+            match schedule.recurring_interval() {
+                Ok(Some(recurring)) if recurring.repetitions.is_none() && recurring.start.is_none() => (),
+                _ => self.issue(
+                    "/start/schedule",
+                    format!(
+                        "only the duration of interval '{}' is migrated; repetitions and start are not supported in DSL 1.0",
+                        interval
+                    ),
+                ),
+            }
+            let duration = interval.rsplit('/').next().unwrap_or(interval);
+            json!(duration)
+        });
+        let cron = match schedule {
+            Schedule::Complex { cron: Some(cron), .. } => {
+                if let CronDef::Repeat { valid_until: Some(_), .. } = cron {
+                    self.issue(
+                        "/start/schedule/cron/validUntil",
+                        "cron expiration has no equivalent in DSL 1.0",
+                    );
+                }
+                Some(cron.expression().to_string())
+            },
+            _ => None,
+        };
+        if let Schedule::Complex { timezone: Some(timezone), .. } = schedule {
+            if timezone != "UTC" {
+                self.issue(
+                    "/start/schedule/timezone",
+                    "schedules are always evaluated in UTC in DSL 1.0",
+                );
+            }
+        }
+
+        Some(v1::Schedule { every, cron, ..v1::Schedule::default() })
+    }
+
+    fn states(&mut self) -> Vec<v1::TaskItem> {
+        // Tasks are executed in order in DSL 1.0, so the start state must come first.
+        let mut states: Vec<_> = self.definition.states.iter().enumerate().collect();
+        if let Some(start) = self.definition.start_state_name() {
+            if let Some(position) = states.iter().position(|(_, state)| state.name() == start) {
+                let start = states.remove(position);
+                states.insert(0, start);
+            }
+        }
+
+        states
+            .into_iter()
+            .map(|(i, state)| self.state(state, &format!("/states/{}", i)))
+            .collect()
+    }
+
+    fn state(&mut self, state: &'a State, pointer: &str) -> v1::TaskItem {
+        let (common, task) = match state {
+            State::Sleep(state) => (
+                StateCommon {
+                    state_data_filter: state.state_data_filter.as_ref(),
+                    state_exec_timeout: state
+                        .timeouts
+                        .as_ref()
+                        .and_then(|timeouts| timeouts.state_exec_timeout.as_ref()),
+                    has_error_handling: state.on_errors.is_some(),
+                    transition: state.transition.as_ref(),
+                    end: state.end.as_ref(),
+                    is_compensated: state.compensated_by.is_some() || state.used_for_compensation,
+                    metadata: state.metadata.as_ref(),
+                    has_extensions: !state.extensions.is_empty(),
+                },
+                v1::Task::Wait(v1::WaitTask {
+                    wait: json!(state.duration),
+                    base: v1::TaskBase::default(),
+                }),
+            ),
+            State::Event(state) => {
+                let timeouts = state.timeouts.as_ref();
+                if timeouts.is_some_and(|timeouts| timeouts.action_exec_timeout.is_some()) {
+                    self.action_timeout_issue(pointer);
+                }
+
+                (
+                    StateCommon {
+                        state_data_filter: state.state_data_filter.as_ref(),
+                        state_exec_timeout: timeouts
+                            .and_then(|timeouts| timeouts.state_exec_timeout.as_ref()),
+                        has_error_handling: state.on_errors.is_some(),
+                        transition: state.transition.as_ref(),
+                        end: state.end.as_ref(),
+                        is_compensated: state.compensated_by.is_some(),
+                        metadata: state.metadata.as_ref(),
+                        has_extensions: !state.extensions.is_empty(),
+                    },
+                    self.event_state(
+                        state.exclusive,
+                        &state.on_events,
+                        timeouts.and_then(|timeouts| timeouts.event_timeout.as_ref()),
+                        pointer,
+                    ),
+                )
+            },
+            State::Operation(state) => {
+                let timeouts = state.timeouts.as_ref();
+                if timeouts.is_some_and(|timeouts| timeouts.action_exec_timeout.is_some()) {
+                    self.action_timeout_issue(pointer);
+                }
+
+                (
+                    StateCommon {
+                        state_data_filter: state.state_data_filter.as_ref(),
+                        state_exec_timeout: timeouts
+                            .and_then(|timeouts| timeouts.state_exec_timeout.as_ref()),
+                        has_error_handling: state.on_errors.is_some(),
+                        transition: state.transition.as_ref(),
+                        end: state.end.as_ref(),
+                        is_compensated: state.compensated_by.is_some()
+                            || state.used_for_compensation,
+                        metadata: state.metadata.as_ref(),
+                        has_extensions: !state.extensions.is_empty(),
+                    },
+                    self.actions_task(
+                        &state.actions,
+                        state.action_mode,
+                        &format!("{}/actions", pointer),
+                    ),
+                )
+            },
+            State::Parallel(state) => {
+                let timeouts = state.timeouts.as_ref();
+                if timeouts.is_some_and(|timeouts| timeouts.branch_exec_timeout.is_some()) {
+                    self.issue(
+                        format!("{}/timeouts/branchExecTimeout", pointer),
+                        "default branch timeouts must be set on each branch",
+                    );
+                }
+
+                (
+                    StateCommon {
+                        state_data_filter: state.state_data_filter.as_ref(),
+                        state_exec_timeout: timeouts
+                            .and_then(|timeouts| timeouts.state_exec_timeout.as_ref()),
+                        has_error_handling: state.on_errors.is_some(),
+                        transition: state.transition.as_ref(),
+                        end: state.end.as_ref(),
+                        is_compensated: state.compensated_by.is_some()
+                            || state.used_for_compensation,
+                        metadata: state.metadata.as_ref(),
+                        has_extensions: !state.extensions.is_empty(),
+                    },
+                    self.parallel_state(state, pointer),
+                )
+            },
+            State::Switch(SwitchState::DataBased(state)) => (
+                StateCommon {
+                    state_data_filter: state.state_data_filter.as_ref(),
+                    state_exec_timeout: state
+                        .timeouts
+                        .as_ref()
+                        .and_then(|timeouts| timeouts.state_exec_timeout.as_ref()),
+                    has_error_handling: state.on_errors.is_some(),
+                    is_compensated: state.compensated_by.is_some() || state.used_for_compensation,
+                    metadata: state.metadata.as_ref(),
+                    has_extensions: !state.extensions.is_empty(),
+                    ..StateCommon::default()
+                },
+                self.data_based_switch_state(state, pointer),
+            ),
+            State::Switch(SwitchState::EventBased(state)) => (
+                StateCommon {
+                    state_data_filter: state.state_data_filter.as_ref(),
+                    state_exec_timeout: state
+                        .timeouts
+                        .as_ref()
+                        .and_then(|timeouts| timeouts.state_exec_timeout.as_ref()),
+                    has_error_handling: state.on_errors.is_some(),
+                    is_compensated: state.compensated_by.is_some() || state.used_for_compensation,
+                    metadata: state.metadata.as_ref(),
+                    has_extensions: !state.extensions.is_empty(),
+                    ..StateCommon::default()
+                },
+                self.event_based_switch_state(state, pointer),
+            ),
+            State::Inject(state) => (
+                StateCommon {
+                    state_data_filter: state.state_data_filter.as_ref(),
+                    state_exec_timeout: state
+                        .timeouts
+                        .as_ref()
+                        .and_then(|timeouts| timeouts.state_exec_timeout.as_ref()),
+                    has_error_handling: false,
+                    transition: state.transition.as_ref(),
+                    end: state.end.as_ref(),
+                    is_compensated: state.compensated_by.is_some() || state.used_for_compensation,
+                    metadata: state.metadata.as_ref(),
+                    has_extensions: !state.extensions.is_empty(),
+                },
+                v1::Task::Set(v1::SetTask {
+                    set: Value::Object(state.data.meta.clone().into_iter().collect()),
+                    base: v1::TaskBase::default(),
+                }),
+            ),
+            State::ForEach(state) => {
+                let timeouts = state.timeouts.as_ref();
+                if timeouts.is_some_and(|timeouts| timeouts.action_exec_timeout.is_some()) {
+                    self.action_timeout_issue(pointer);
+                }
+
+                (
+                    StateCommon {
+                        state_data_filter: state.state_data_filter.as_ref(),
+                        state_exec_timeout: timeouts
+                            .and_then(|timeouts| timeouts.state_exec_timeout.as_ref()),
+                        has_error_handling: state.on_errors.is_some(),
+                        transition: state.transition.as_ref(),
+                        end: state.end.as_ref(),
+                        is_compensated: state.compensated_by.is_some()
+                            || state.used_for_compensation,
+                        metadata: state.metadata.as_ref(),
+                        has_extensions: !state.extensions.is_empty(),
+                    },
+                    self.for_each_state(state, pointer),
+                )
+            },
+            State::Callback(state) => {
+                let timeouts = state.timeouts.as_ref();
+                if timeouts.is_some_and(|timeouts| timeouts.action_exec_timeout.is_some()) {
+                    self.action_timeout_issue(pointer);
+                }
+                if state.event_data_filter.is_some() {
+                    self.event_data_filter_issue(&format!("{}/eventDataFilter", pointer));
+                }
+
+                let action = self.action(&state.action, 0, &format!("{}/action", pointer));
+                let mut listen =
+                    self.listen_one(&state.event_ref, &format!("{}/eventRef", pointer));
+                if let Some(timeout) = timeouts.and_then(|timeouts| timeouts.event_timeout.as_ref())
+                {
+                    listen.base.timeout = Some(json!({ "after": timeout.0 }));
+                }
+
+                (
+                    StateCommon {
+                        state_data_filter: state.state_data_filter.as_ref(),
+                        state_exec_timeout: timeouts
+                            .and_then(|timeouts| timeouts.state_exec_timeout.as_ref()),
+                        has_error_handling: state.on_errors.is_some(),
+                        transition: state.transition.as_ref(),
+                        end: state.end.as_ref(),
+                        is_compensated: state.compensated_by.is_some()
+                            || state.used_for_compensation,
+                        metadata: state.metadata.as_ref(),
+                        has_extensions: !state.extensions.is_empty(),
+                    },
+                    v1::Task::Do(v1::DoTask {
+                        tasks: vec![
+                            action,
+                            v1::TaskItem {
+                                name: "callback".into(),
+                                task: v1::Task::Listen(listen),
+                            },
+                        ],
+                        base: v1::TaskBase::default(),
+                    }),
+                )
+            },
+            State::Custom(state) => {
+                self.issue(
+                    pointer,
+                    format!(
+                        "custom state type '{}' has no equivalent in DSL 1.0",
+                        state.state_type
+                    ),
+                );
+
+                (
+                    StateCommon {
+                        transition: state.transition.as_ref(),
+                        end: state.end.as_ref(),
+                        ..StateCommon::default()
+                    },
+                    v1::Task::Set(v1::SetTask {
+                        set: Value::Object(Map::new()),
+                        base: v1::TaskBase::default(),
+                    }),
+                )
+            },
+        };
+
+        // Some tasks set their own timeout (e.g. event timeouts) or transition (e.g. the default
+        // condition of event-based switch states), which must be preserved.
+        let mut task = task;
+        let mut base = self.state_base(common, pointer);
+        let task_base = task.base_mut();
+        if let Some(timeout) = task_base.timeout.take() {
+            if base.timeout.is_some() {
+                self.issue(
+                    format!("{}/timeouts", pointer),
+                    "only the state execution timeout is migrated",
+                );
+            } else {
+                base.timeout = Some(timeout);
+            }
+        }
+        base.then = base.then.or(task_base.then.take());
+        *task.base_mut() = base;
+
+        v1::TaskItem { name: state.name().into(), task }
+    }
+
+    fn state_base(&mut self, common: StateCommon<'_>, pointer: &str) -> v1::TaskBase {
+        if common.has_error_handling {
+            self.issue(
+                format!("{}/onErrors", pointer),
+                "error handling must be migrated to a `try` task",
+            );
+        }
+        if common.is_compensated {
+            self.issue(pointer, "compensation has no equivalent in DSL 1.0");
+        }
+        if common.has_extensions {
+            self.issue(pointer, "extension properties are not migrated");
+        }
+
+        let then = match (common.transition, common.end) {
+            (Some(transition), _) => {
+                Some(self.transition(transition, &format!("{}/transition", pointer)))
+            },
+            (None, Some(end)) => Some(self.end(end, &format!("{}/end", pointer))),
+            (None, None) => None,
+        };
+        let timeout = common.state_exec_timeout.map(|timeout| {
+            let duration = match timeout {
+                StateExecTimeout::Simple(duration) => duration,
+                StateExecTimeout::Complex { single, total } => {
+                    if single.is_some() {
+                        self.issue(
+                            format!("{}/timeouts/stateExecTimeout/single", pointer),
+                            "single state execution timeouts have no equivalent in DSL 1.0",
+                        );
+                    }
+                    total
+                },
+            };
+            json!({ "after": duration })
+        });
+
+        v1::TaskBase {
+            input: common
+                .state_data_filter
+                .and_then(|filter| filter.input.as_ref())
+                .map(|from| v1::Input { schema: None, from: Some(json!(from)) }),
+            output: common
+                .state_data_filter
+                .and_then(|filter| filter.output.as_ref())
+                .map(|transform| v1::Output { schema: None, transform: Some(json!(transform)) }),
+            timeout,
+            then,
+            metadata: metadata_map(common.metadata),
+            ..v1::TaskBase::default()
+        }
+    }
+
+    fn transition(&mut self, transition: &Transition, pointer: &str) -> String {
+        match transition {
+            Transition::ByName(next_state) => next_state.clone(),
+            Transition::Complex { next_state, produce_events, compensate } => {
+                if produce_events.is_some() {
+                    self.produce_events_issue(&format!("{}/produceEvents", pointer));
+                }
+                if *compensate {
+                    self.issue(
+                        format!("{}/compensate", pointer),
+                        "compensation has no equivalent in DSL 1.0",
+                    );
+                }
+                next_state.clone()
+            },
+        }
+    }
+
+    fn end(&mut self, end: &End, pointer: &str) -> String {
+        if let End::Complex { produce_events, compensate, continue_as, .. } = end {
+            if produce_events.is_some() {
+                self.produce_events_issue(&format!("{}/produceEvents", pointer));
+            }
+            if *compensate {
+                self.issue(
+                    format!("{}/compensate", pointer),
+                    "compensation has no equivalent in DSL 1.0",
+                );
+            }
+            if continue_as.is_some() {
+                self.issue(
+                    format!("{}/continueAs", pointer),
+                    "continueAs has no equivalent in DSL 1.0; a `run` task can be used instead",
+                );
+            }
+        }
+
+        "end".into()
+    }
+
+    fn produce_events_issue(&mut self, pointer: &str) {
+        self.issue(pointer, "produced events must be migrated to `emit` tasks");
+    }
+
+    fn action_timeout_issue(&mut self, pointer: &str) {
+        self.issue(
+            format!("{}/timeouts/actionExecTimeout", pointer),
+            "default action timeouts must be set on each task",
+        );
+    }
+
+    fn event_data_filter_issue(&mut self, pointer: &str) {
+        self.issue(
+            pointer,
+            "event data filters must be migrated to the `output` or `export` of `listen` tasks",
+        );
+    }
+
+    fn event_state(
+        &mut self,
+        exclusive: bool,
+        on_events: &'a [crate::workflow::definition::OnEvents],
+        event_timeout: Option<&crate::workflow::definition::timeouts::EventTimeout>,
+        pointer: &str,
+    ) -> v1::Task {
+        let filters: Vec<_> = on_events
+            .iter()
+            .enumerate()
+            .flat_map(|(i, on_events)| {
+                on_events
+                    .event_refs
+                    .iter()
+                    .enumerate()
+                    .map(move |(j, event_ref)| {
+                        (event_ref, format!("{}/onEvents/{}/eventRefs/{}", pointer, i, j))
+                    })
+            })
+            .map(|(event_ref, pointer)| self.event_filter(event_ref, &pointer))
+            .collect();
+        let to = if exclusive {
+            v1::EventConsumptionStrategy { any: Some(filters), ..Default::default() }
+        } else {
+            v1::EventConsumptionStrategy { all: Some(filters), ..Default::default() }
+        };
+        let listen = v1::ListenTask {
+            listen: v1::Listen { to },
+            base: v1::TaskBase {
+                timeout: event_timeout.map(|timeout| json!({ "after": timeout.0 })),
+                ..v1::TaskBase::default()
+            },
+        };
+
+        let with_actions = on_events
+            .iter()
+            .filter(|on_events| on_events.actions.as_ref().is_some_and(|a| !a.is_empty()))
+            .count();
+        if exclusive && with_actions > 1 {
+            self.issue(
+                format!("{}/onEvents", pointer),
+                "actions are performed regardless of which event was received; \
+                    a `switch` task must be added to select them",
+            );
+        }
+
+        let mut tasks =
+            vec![v1::TaskItem { name: "listen".into(), task: v1::Task::Listen(listen) }];
+        for (i, on_events) in on_events.iter().enumerate() {
+            let on_events_pointer = format!("{}/onEvents/{}", pointer, i);
+            if on_events.event_data_filter.is_some() {
+                self.event_data_filter_issue(&format!("{}/eventDataFilter", on_events_pointer));
+            }
+            if let Some(actions) = on_events.actions.as_deref().filter(|a| !a.is_empty()) {
+                tasks.push(v1::TaskItem {
+                    name: format!("onEvents{}", i),
+                    task: self.actions_task(
+                        actions,
+                        on_events.action_mode,
+                        &format!("{}/actions", on_events_pointer),
+                    ),
+                });
+            }
+        }
+
+        if tasks.len() == 1 {
+            tasks.remove(0).task
+        } else {
+            v1::Task::Do(v1::DoTask { tasks, base: v1::TaskBase::default() })
+        }
+    }
+
+    fn parallel_state(
+        &mut self,
+        state: &'a crate::workflow::definition::ParallelState,
+        pointer: &str,
+    ) -> v1::Task {
+        let compete = match state.completion_type {
+            CompletionType::AllOf => false,
+            CompletionType::AtLeast => {
+                let num_completed = state.num_completed.as_ref().and_then(|n| n.value().ok());
+                if num_completed != Some(1) {
+                    self.issue(
+                        format!("{}/numCompleted", pointer),
+                        "only one or all branches can be awaited in DSL 1.0; all branches are awaited",
+                    );
+                }
+                num_completed == Some(1)
+            },
+        };
+
+        let branches = state
+            .branches
+            .iter()
+            .enumerate()
+            .map(|(i, branch)| {
+                let branch_pointer = format!("{}/branches/{}", pointer, i);
+                let timeouts = branch.timeouts.as_ref();
+                if timeouts.is_some_and(|timeouts| timeouts.action_exec_timeout.is_some()) {
+                    self.action_timeout_issue(&branch_pointer);
+                }
+
+                let mut task = self.actions_task(
+                    &branch.actions,
+                    ExecutionMode::Sequential,
+                    &format!("{}/actions", branch_pointer),
+                );
+                task.base_mut().timeout = timeouts
+                    .and_then(|timeouts| timeouts.branch_exec_timeout.as_ref())
+                    .map(|timeout| json!({ "after": timeout.0 }));
+
+                v1::TaskItem { name: branch.name.clone(), task }
+            })
+            .collect();
+
+        v1::Task::Fork(v1::ForkTask {
+            fork: v1::Fork { branches, compete },
+            base: v1::TaskBase::default(),
+        })
+    }
+
+    fn data_based_switch_state(
+        &mut self,
+        state: &'a crate::workflow::definition::DataBasedSwitchState,
+        pointer: &str,
+    ) -> v1::Task {
+        let mut cases: Vec<_> = state
+            .data_conditions
+            .iter()
+            .enumerate()
+            .map(|(i, condition)| {
+                let condition_pointer = format!("{}/dataConditions/{}", pointer, i);
+                let (name, when, then) = match condition {
+                    DataCondition::Transition(condition) => (
+                        &condition.name,
+                        &condition.condition,
+                        self.transition(
+                            &condition.transition,
+                            &format!("{}/transition", condition_pointer),
+                        ),
+                    ),
+                    DataCondition::End(condition) => (
+                        &condition.name,
+                        &condition.condition,
+                        self.end(&condition.end, &format!("{}/end", condition_pointer)),
+                    ),
+                };
+
+                v1::SwitchCaseItem {
+                    name: name.clone().unwrap_or_else(|| format!("case{}", i)),
+                    case: v1::SwitchCase { when: Some(when.clone()), then },
+                }
+            })
+            .collect();
+        cases.extend(self.default_case(&state.default_condition, pointer));
+
+        v1::Task::Switch(v1::SwitchTask { switch: cases, base: v1::TaskBase::default() })
+    }
+
+    fn event_based_switch_state(
+        &mut self,
+        state: &'a crate::workflow::definition::EventBasedSwitchState,
+        pointer: &str,
+    ) -> v1::Task {
+        self.issue(
+            format!("{}/eventConditions", pointer),
+            "event-based switch states have no equivalent in DSL 1.0; they are migrated to a \
+                `listen` task, but the transition to take must be selected by hand",
+        );
+
+        let filters = state
+            .event_conditions
+            .iter()
+            .enumerate()
+            .map(|(i, condition)| {
+                let condition_pointer = format!("{}/eventConditions/{}", pointer, i);
+                let (event_ref, event_data_filter) = match condition {
+                    EventCondition::Transition(condition) => {
+                        (&condition.event_ref, &condition.event_data_filter)
+                    },
+                    EventCondition::End(condition) => {
+                        (&condition.event_ref, &condition.event_data_filter)
+                    },
+                };
+                if event_data_filter.is_some() {
+                    self.event_data_filter_issue(&format!("{}/eventDataFilter", condition_pointer));
+                }
+
+                self.event_filter(event_ref, &format!("{}/eventRef", condition_pointer))
+            })
+            .collect();
+
+        let then = self
+            .default_case(&state.default_condition, pointer)
+            .map(|case| case.case.then);
+        v1::Task::Listen(v1::ListenTask {
+            listen: v1::Listen {
+                to: v1::EventConsumptionStrategy { any: Some(filters), ..Default::default() },
+            },
+            base: v1::TaskBase {
+                timeout: state
+                    .timeouts
+                    .as_ref()
+                    .and_then(|timeouts| timeouts.event_timeout.as_ref())
+                    .map(|timeout| json!({ "after": timeout.0 })),
+                then,
+                ..v1::TaskBase::default()
+            },
+        })
+    }
+
+    fn default_case(
+        &mut self,
+        default_condition: &crate::workflow::definition::DefaultConditionDef,
+        pointer: &str,
+    ) -> Option<v1::SwitchCaseItem> {
+        let pointer = format!("{}/defaultCondition", pointer);
+        let then = match (&default_condition.transition, &default_condition.end) {
+            (Some(transition), _) => {
+                self.transition(transition, &format!("{}/transition", pointer))
+            },
+            (None, Some(end)) => self.end(end, &format!("{}/end", pointer)),
+            (None, None) => return None,
+        };
+
+        Some(v1::SwitchCaseItem {
+            name: "default".into(),
+            case: v1::SwitchCase { when: None, then },
+        })
+    }
+
+    fn for_each_state(
+        &mut self,
+        state: &'a crate::workflow::definition::ForEachState,
+        pointer: &str,
+    ) -> v1::Task {
+        if state.output_collection.is_some() {
+            self.issue(
+                format!("{}/outputCollection", pointer),
+                "iteration results must be collected with the `export` of the iterated tasks",
+            );
+        }
+        if state.mode == ExecutionMode::Parallel || state.batch_size.is_some() {
+            self.issue(
+                format!("{}/mode", pointer),
+                "iterations are performed sequentially in DSL 1.0",
+            );
+        }
+
+        let actions = self.actions(&state.actions, &format!("{}/actions", pointer));
+        v1::Task::For(v1::ForTask {
+            iteration: v1::ForLoop {
+                each: state.iteration_param.clone(),
+                collection: state.input_collection.clone(),
+                at: None,
+            },
+            while_condition: None,
+            tasks: actions,
+            base: v1::TaskBase::default(),
+        })
+    }
+
+    fn actions_task(
+        &mut self,
+        actions: &'a [Action],
+        mode: ExecutionMode,
+        pointer: &str,
+    ) -> v1::Task {
+        let tasks = self.actions(actions, pointer);
+        match mode {
+            ExecutionMode::Sequential => {
+                v1::Task::Do(v1::DoTask { tasks, base: v1::TaskBase::default() })
+            },
+            ExecutionMode::Parallel => v1::Task::Fork(v1::ForkTask {
+                fork: v1::Fork { branches: tasks, compete: false },
+                base: v1::TaskBase::default(),
+            }),
+        }
+    }
+
+    fn actions(&mut self, actions: &'a [Action], pointer: &str) -> Vec<v1::TaskItem> {
+        actions
+            .iter()
+            .enumerate()
+            .map(|(i, action)| self.action(action, i, &format!("{}/{}", pointer, i)))
+            .collect()
+    }
+
+    fn action(&mut self, action: &'a Action, index: usize, pointer: &str) -> v1::TaskItem {
+        let name = action
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("action{}", index));
+
+        let mut tasks = Vec::new();
+        if let Some(before) = action.sleep.as_ref().and_then(|sleep| sleep.before()) {
+            tasks.push(wait_task("sleepBefore", before));
+        }
+        if let Some(function_ref) = &action.function_ref {
+            let task = self.function_call(function_ref, &format!("{}/functionRef", pointer));
+            tasks.push(v1::TaskItem { name: name.clone(), task });
+        } else if let Some(event_ref) = &action.event_ref {
+            tasks.extend(self.event_ref(event_ref, &format!("{}/eventRef", pointer)));
+        } else if let Some(sub_flow_ref) = &action.sub_flow_ref {
+            let task = self.sub_flow_run(sub_flow_ref, &format!("{}/subFlowRef", pointer));
+            tasks.push(v1::TaskItem { name: name.clone(), task });
+        } else {
+            self.issue(pointer, "action does not reference a function, event or sub-workflow");
+        }
+        if let Some(after) = action.sleep.as_ref().and_then(|sleep| sleep.after()) {
+            tasks.push(wait_task("sleepAfter", after));
+        }
+
+        if action.retry_ref.is_some()
+            || action.retryable_errors.is_some()
+            || action.non_retryable_errors.is_some()
+        {
+            self.issue(pointer, "retries must be migrated to a `try` task");
+        }
+        if !action.extensions.is_empty() {
+            self.issue(pointer, "extension properties are not migrated");
+        }
+
+        let mut base =
+            v1::TaskBase { condition: action.condition.clone(), ..v1::TaskBase::default() };
+        if let Some(filter) = &action.action_data_filter {
+            let filter_pointer = format!("{}/actionDataFilter", pointer);
+            base.input = filter
+                .from_state_data
+                .as_ref()
+                .map(|from| v1::Input { schema: None, from: Some(json!(from)) });
+            if filter.use_results {
+                base.output = filter
+                    .results
+                    .as_ref()
+                    .map(|results| v1::Output { schema: None, transform: Some(json!(results)) });
+            } else {
+                self.issue(
+                    format!("{}/useResults", filter_pointer),
+                    "task results are always used in DSL 1.0",
+                );
+            }
+            if filter.to_state_data.is_some() {
+                self.issue(
+                    format!("{}/toStateData", filter_pointer),
+                    "merging results into the state data must be done with `export`",
+                );
+            }
+        }
+
+        let mut task = if tasks.len() == 1 {
+            tasks.remove(0).task
+        } else {
+            v1::Task::Do(v1::DoTask { tasks, base: v1::TaskBase::default() })
+        };
+        let task_base = task.base_mut();
+        base.timeout = task_base.timeout.take();
+        *task_base = base;
+
+        v1::TaskItem { name, task }
+    }
+
+    fn function_call(&mut self, function_ref: &FunctionRef, pointer: &str) -> v1::Task {
+        let (arguments, selection_set, invoke) = match function_ref {
+            FunctionRef::ByName(_) => (None, None, InvocationMode::Sync),
+            FunctionRef::Complex { arguments, selection_set, invoke, .. } => {
+                (arguments.as_ref(), selection_set.as_ref(), *invoke)
+            },
+        };
+        let arguments: Option<Map<String, Value>> =
+            arguments.map(|arguments| arguments.arguments.clone().into_iter().collect());
+        if selection_set.is_some() {
+            self.issue(
+                format!("{}/selectionSet", pointer),
+                "GraphQL selection sets have no equivalent in DSL 1.0",
+            );
+        }
+        if invoke == InvocationMode::Async {
+            self.issue(
+                format!("{}/invoke", pointer),
+                "functions are always invoked synchronously in DSL 1.0",
+            );
+        }
+
+        let ref_name = function_ref.ref_name();
+        let Some(function) = self.function(ref_name) else {
+            self.issue(
+                format!("{}/refName", pointer),
+                format!(
+                    "function '{}' is not defined inline; it is called as a custom function",
+                    ref_name
+                ),
+            );
+            return call_task(ref_name, arguments);
+        };
+        if function.auth_ref.is_some() {
+            self.issue(
+                format!("{}/refName", pointer),
+                format!(
+                    "authentication of function '{}' must be migrated to `use.authentications`",
+                    ref_name
+                ),
+            );
+        }
+
+        let (document, operation) = match function.operation.split_once('#') {
+            Some((document, operation)) => (document, operation),
+            None => ("", function.operation.as_str()),
+        };
+        match function.function_type {
+            FunctionType::Rest => {
+                let mut with = Map::new();
+                with.insert("document".into(), json!({ "endpoint": document }));
+                with.insert("operationId".into(), json!(operation));
+                if let Some(arguments) = arguments {
+                    with.insert("parameters".into(), Value::Object(arguments));
+                }
+                call_task("openapi", Some(with))
+            },
+            FunctionType::AsyncApi => {
+                let mut with = Map::new();
+                with.insert("document".into(), json!({ "endpoint": document }));
+                with.insert("operationRef".into(), json!(operation));
+                if let Some(arguments) = arguments {
+                    with.insert("message".into(), json!({ "payload": arguments }));
+                }
+                call_task("asyncapi", Some(with))
+            },
+            FunctionType::GRpc => {
+                let (service, method) = operation.split_once('#').unwrap_or((operation, ""));
+                self.issue(
+                    format!("{}/refName", pointer),
+                    format!(
+                        "the host of the gRPC service of function '{}' must be specified",
+                        ref_name
+                    ),
+                );
+
+                let mut with = Map::new();
+                with.insert("proto".into(), json!({ "endpoint": document }));
+                with.insert("service".into(), json!({ "name": service }));
+                with.insert("method".into(), json!(method));
+                if let Some(arguments) = arguments {
+                    with.insert("arguments".into(), Value::Object(arguments));
+                }
+                call_task("grpc", Some(with))
+            },
+            FunctionType::Expression => v1::Task::Set(v1::SetTask {
+                set: json!(format!("${{ {} }}", function.operation)),
+                base: v1::TaskBase::default(),
+            }),
+            function_type
+            @ (FunctionType::GraphQL | FunctionType::OData | FunctionType::Custom) => {
+                self.issue(
+                    format!("{}/refName", pointer),
+                    format!(
+                        "functions of type '{:?}' have no equivalent in DSL 1.0; \
+                            function '{}' is called as a custom function",
+                        function_type, ref_name
+                    ),
+                );
+                call_task(ref_name, arguments)
+            },
+        }
+    }
+
+    fn function(&self, name: &str) -> Option<&'a Function> {
+        match &self.definition.functions {
+            Some(Functions::Inline(functions)) => {
+                functions.iter().find(|function| function.name == name)
+            },
+            _ => None,
+        }
+    }
+
+    fn event(&self, name: &str) -> Option<&'a EventDef> {
+        match &self.definition.events {
+            Some(Events::Inline(events)) => events.iter().find(|event| event.name == name),
+            _ => None,
+        }
+    }
+
+    fn event_attributes(&mut self, event_ref: &str, pointer: &str) -> Map<String, Value> {
+        let mut attributes = Map::new();
+        match self.event(event_ref) {
+            Some(event) => {
+                attributes.insert("type".into(), json!(event.event_type));
+                if let Some(source) = &event.source {
+                    attributes.insert("source".into(), json!(source));
+                }
+                if event.correlation.is_some() {
+                    self.issue(
+                        pointer,
+                        format!(
+                            "correlation of event '{}' must be migrated to `correlate`",
+                            event_ref
+                        ),
+                    );
+                }
+            },
+            None => {
+                self.issue(
+                    pointer,
+                    format!(
+                        "event '{}' is not defined inline; its name is used as its type",
+                        event_ref
+                    ),
+                );
+                attributes.insert("type".into(), json!(event_ref));
+            },
+        }
+
+        attributes
+    }
+
+    fn event_filter(&mut self, event_ref: &str, pointer: &str) -> v1::EventFilter {
+        v1::EventFilter { with: self.event_attributes(event_ref, pointer), correlate: None }
+    }
+
+    fn listen_one(&mut self, event_ref: &str, pointer: &str) -> v1::ListenTask {
+        v1::ListenTask {
+            listen: v1::Listen {
+                to: v1::EventConsumptionStrategy {
+                    one: Some(self.event_filter(event_ref, pointer)),
+                    ..Default::default()
+                },
+            },
+            base: v1::TaskBase::default(),
+        }
+    }
+
+    fn event_ref(
+        &mut self,
+        event_ref: &crate::workflow::definition::EventRef,
+        pointer: &str,
+    ) -> Vec<v1::TaskItem> {
+        let mut with = self.event_attributes(
+            &event_ref.trigger_event_ref,
+            &format!("{}/triggerEventRef", pointer),
+        );
+        match &event_ref.data {
+            Some(Data::Expression(expression)) => {
+                with.insert("data".into(), json!(expression));
+            },
+            Some(Data::Object { fields }) => {
+                with.insert("data".into(), json!(fields));
+            },
+            None => (),
+        }
+        with.extend(context_attributes(event_ref.context_attributes.as_ref()));
+
+        let mut tasks = vec![v1::TaskItem {
+            name: "emit".into(),
+            task: v1::Task::Emit(v1::EmitTask {
+                emit: v1::Emit { event: v1::EventDefinition { with } },
+                base: v1::TaskBase::default(),
+            }),
+        }];
+        if event_ref.invoke == InvocationMode::Sync {
+            let mut listen = self
+                .listen_one(&event_ref.result_event_ref, &format!("{}/resultEventRef", pointer));
+            listen.base.timeout = event_ref
+                .result_event_timeout
+                .as_ref()
+                .map(|timeout| json!({ "after": timeout }));
+            tasks.push(v1::TaskItem { name: "listen".into(), task: v1::Task::Listen(listen) });
+        }
+
+        tasks
+    }
+
+    fn sub_flow_run(&mut self, sub_flow_ref: &SubflowRef, pointer: &str) -> v1::Task {
+        let (name, version, invoke) = match sub_flow_ref {
+            SubflowRef::ById(workflow_id) => (workflow_id, None, InvocationMode::Sync),
+            SubflowRef::Complex { workflow_id, version, on_parent_complete, invoke } => {
+                if *invoke == InvocationMode::Async && *on_parent_complete == OnComplete::Continue {
+                    self.issue(
+                        format!("{}/onParentComplete", pointer),
+                        "sub-workflows cannot outlive their parent in DSL 1.0",
+                    );
+                }
+                (workflow_id, version.clone(), *invoke)
+            },
+        };
+
+        v1::Task::Run(v1::RunTask {
+            run: v1::Run {
+                workflow: Some(v1::RunWorkflow {
+                    namespace: DEFAULT_NAMESPACE.into(),
+                    name: name.clone(),
+                    version,
+                    input: None,
+                }),
+                wait_for_completion: (invoke == InvocationMode::Async).then_some(false),
+                ..v1::Run::default()
+            },
+            base: v1::TaskBase::default(),
+        })
+    }
+}
+
+fn call_task(call: &str, with: Option<Map<String, Value>>) -> v1::Task {
+    v1::Task::Call(v1::CallTask { call: call.into(), with, base: v1::TaskBase::default() })
+}
+
+fn wait_task(name: &str, duration: &str) -> v1::TaskItem {
+    v1::TaskItem {
+        name: name.into(),
+        task: v1::Task::Wait(v1::WaitTask { wait: json!(duration), base: v1::TaskBase::default() }),
+    }
+}
+
+fn metadata_map(metadata: Option<&Metadata>) -> Option<Map<String, Value>> {
+    metadata.map(|metadata| {
+        metadata
+            .meta
+            .iter()
+            .map(|(key, value)| (key.clone(), json!(value)))
+            .collect()
+    })
+}
+
+fn context_attributes(attributes: Option<&ContextAttributes>) -> Map<String, Value> {
+    attributes
+        .map(|attributes| {
+            attributes
+                .attributes
+                .iter()
+                .map(|(key, value)| (key.clone(), json!(value)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,225 @@
+//! Startup self-checks of workflow definitions.
+//!
+//! A [`Preflight`] checks that a runtime has everything it needs to execute a workflow
+//! definition before any [instance](crate::workflow::instance::WorkflowInstance) is started:
+//! executors for the functions referenced by its actions, resolvable function endpoints and
+//! auth definitions, and event infrastructure for states waiting for events. Problems are
+//! reported in a [`PreflightReport`] instead of surfacing in the middle of an execution.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde_json::Map;
+
+use crate::workflow::definition::functions::{FunctionType, Functions};
+use crate::workflow::definition::{FunctionRef, State, SwitchState, WorkflowDefinition};
+use crate::workflow::executor::{ActionExecutor, FunctionCall};
+
+/// Startup self-check of a [`WorkflowDefinition`].
+///
+/// The following checks are performed:
+///
+/// * Each function referenced by an action is defined in the workflow definition.
+/// * An executor is [registered](Self::executor) for the [type] of each referenced function.
+/// * The auth definition of each referenced function, if any, is defined in the workflow
+///   definition.
+/// * The executor can call each referenced function, according to its
+///   [`check`](ActionExecutor::check) method (e.g. the function's endpoint can be resolved).
+/// * If no [event infrastructure](Self::events) is available, no state consumes or
+///   produces events.
+///
+/// Functions and auth definitions specified as a URI are not loaded; use
+/// [`WorkflowDefinition::resolve_external_refs`] first to resolve them.
+///
+/// [type]: crate::workflow::definition::functions::Function::function_type
+pub struct Preflight<'a> {
+    definition: &'a WorkflowDefinition,
+    executors: HashMap<FunctionType, &'a dyn ActionExecutor>,
+    events: bool,
+}
+
+impl<'a> Preflight<'a> {
+    /// Creates a preflight check of the given workflow definition.
+    ///
+    /// By default, no executor is registered and no event infrastructure is available.
+    pub fn new(definition: &'a WorkflowDefinition) -> Self {
+        Self { definition, executors: HashMap::new(), events: false }
+    }
+
+    /// Registers the executor used to call functions of the given type.
+    ///
+    /// If an executor was already registered for this type, it is replaced.
+    pub fn executor(
+        mut self,
+        function_type: FunctionType,
+        executor: &'a dyn ActionExecutor,
+    ) -> Self {
+        self.executors.insert(function_type, executor);
+        self
+    }
+
+    /// Sets whether the runtime can consume and produce events.
+    pub fn events(mut self, available: bool) -> Self {
+        self.events = available;
+        self
+    }
+
+    /// Runs the checks and returns the problems found.
+    pub fn run(&self) -> PreflightReport {
+        let mut report = PreflightReport::default();
+        let mut checked = HashSet::new();
+        for (i, state) in self.definition.states.iter().enumerate() {
+            for function_ref in state
+                .actions()
+                .into_iter()
+                .filter_map(|action| action.function_ref.as_ref())
+            {
+                if checked.insert(function_ref.ref_name()) {
+                    self.check_function(i, function_ref, &mut report);
+                }
+            }
+
+            if !self.events && uses_events(state) {
+                report.issues.push(PreflightIssue {
+                    check: PreflightCheck::Events,
+                    pointer: format!("/states/{}", i),
+                    message: format!(
+                        "state '{}' uses events, but no event infrastructure is available",
+                        state.name()
+                    ),
+                });
+            }
+        }
+
+        report
+    }
+
+    fn check_function(
+        &self,
+        state_index: usize,
+        function_ref: &FunctionRef,
+        report: &mut PreflightReport,
+    ) {
+        let function_index = match &self.definition.functions {
+            Some(Functions::Inline(functions)) => functions
+                .iter()
+                .position(|function| function.name == function_ref.ref_name()),
+            _ => None,
+        };
+        let mut issue = |check, message| {
+            let pointer = match function_index {
+                Some(j) => format!("/functions/{}", j),
+                None => format!("/states/{}", state_index),
+            };
+            report
+                .issues
+                .push(PreflightIssue { check, pointer, message });
+        };
+
+        let call = match FunctionCall::new(self.definition, function_ref, Map::new()) {
+            Ok(call) => call,
+            Err(err @ crate::Error::UnknownAuth { .. }) => {
+                return issue(PreflightCheck::Auth, err.to_string())
+            },
+            Err(err) => return issue(PreflightCheck::Function, err.to_string()),
+        };
+        let Some(executor) = self.executors.get(&call.function.function_type) else {
+            return issue(
+                PreflightCheck::Executor,
+                format!(
+                    "no executor is registered for the type of function '{}'",
+                    call.function.name
+                ),
+            );
+        };
+        match executor.check(&call) {
+            Ok(()) => (),
+            Err(err @ crate::Error::UnsupportedAuth { .. }) => {
+                issue(PreflightCheck::Auth, err.to_string())
+            },
+            Err(err @ crate::Error::FeatureDisabled { .. }) => {
+                issue(PreflightCheck::Executor, err.to_string())
+            },
+            Err(err) => issue(PreflightCheck::Endpoint, err.to_string()),
+        }
+    }
+}
+
+impl fmt::Debug for Preflight<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Preflight")
+            .field("definition", &self.definition.identifier)
+            .field("executors", &self.executors.keys().collect::<Vec<_>>())
+            .field("events", &self.events)
+            .finish()
+    }
+}
+
+// Whether the state waits for events or has actions producing events.
+fn uses_events(state: &State) -> bool {
+    matches!(
+        state,
+        State::Event(_) | State::Callback(_) | State::Switch(SwitchState::EventBased(_))
+    ) || state
+        .actions()
+        .iter()
+        .any(|action| action.event_ref.is_some())
+}
+
+/// Check that failed in a [`PreflightIssue`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PreflightCheck {
+    /// A referenced function is not defined in the workflow definition
+    Function,
+
+    /// No executor can call a referenced function, either because none is registered for its
+    /// type or because the executor's feature is disabled
+    Executor,
+
+    /// A referenced function's auth definition is not defined or not supported
+    Auth,
+
+    /// The executor cannot call a referenced function (e.g. its endpoint cannot be resolved)
+    Endpoint,
+
+    /// A state uses events, but no event infrastructure is available
+    Events,
+}
+
+/// Problem found by a [`Preflight`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreflightIssue {
+    /// Check that failed
+    pub check: PreflightCheck,
+
+    /// JSON pointer to the offending element in the workflow definition (e.g. `/functions/1`)
+    pub pointer: String,
+
+    /// Message describing the problem
+    pub message: String,
+}
+
+impl fmt::Display for PreflightIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.pointer, self.message)
+    }
+}
+
+/// Results of a [`Preflight`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PreflightReport {
+    /// Problems found, in the order the states referencing them are defined
+    pub issues: Vec<PreflightIssue>,
+}
+
+impl PreflightReport {
+    /// Returns `true` if all checks passed.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Returns the problems found by the given check.
+    pub fn with_check(&self, check: PreflightCheck) -> impl Iterator<Item = &PreflightIssue> + '_ {
+        self.issues.iter().filter(move |issue| issue.check == check)
+    }
+}
@@ -0,0 +1,215 @@
+//! Building blocks for executing workflow states.
+//!
+//! This crate does not include a complete workflow engine; instead, this module provides the
+//! pieces runtimes need to execute the states of a workflow definition, such as
+//! [`EndExecution`](end::EndExecution),
+//! [`EventStateExecution`](event::EventStateExecution),
+//! [`EventSwitchExecution`](switch::EventSwitchExecution),
+//! [`InjectStateExecution`](inject::InjectStateExecution) and
+//! [`ParallelStateExecution`](parallel::ParallelStateExecution). See also the [`retry`] module
+//! for retries of failed actions, the [`errors`] module for routing errors to the states' error
+//! handlers, the [`deadlines`] module for enforcing timeouts, the [`sleep`] module for sleep
+//! states and action sleeps, and the [`scheduler`] module for scheduled workflow instances.
+//!
+//! State executions do not apply [state data filters]: runtimes filter the state data input with
+//! [`filter_state_input`](filters::filter_state_input) when entering a state, and its output
+//! with [`filter_state_output`](filters::filter_state_output) before transitioning. See the
+//! [`filters`] module for the data filters applied by state executions, the [`compensation`]
+//! module for workflow compensation, the [`continue_as`] module for workflows continuing as
+//! new executions and the [`subflow`] module for sub-workflow invocations.
+//!
+//! The crate does not evaluate workflow expressions itself: runtimes provide an
+//! [`ExpressionEvaluator`], for example backed by a jq implementation. Functions called by
+//! actions are invoked through an [`ActionExecutor`].
+//!
+//! [state data filters]: crate::workflow::definition::StateDataFilter
+
+pub mod compensation;
+pub mod continue_as;
+pub mod deadlines;
+pub mod end;
+pub mod errors;
+pub mod event;
+pub mod filters;
+pub mod inject;
+pub mod parallel;
+pub mod retry;
+pub mod scheduler;
+pub mod sleep;
+pub mod subflow;
+pub mod switch;
+
+use serde_json::{Map, Value};
+
+use crate::detail::expression::{is_expression, strip_expression};
+use crate::workflow::definition::common::ExecutionMode;
+use crate::workflow::definition::{Action, WorkflowDefinition};
+use crate::workflow::executor::{ActionExecutor, FunctionCall, InvocationContext};
+use crate::workflow::runtime::filters::{
+    filter_action_input, filter_action_results, merge_action_results,
+};
+
+/// Default maximum [depth](crate::workflow::instance::WorkflowInstance::depth) of workflow
+/// instances created by [`continueAs`](continue_as::ContinueAsExecution::with_max_depth) ends
+/// or [sub-workflow invocations](subflow::SubflowInvocation::with_max_depth).
+pub const DEFAULT_MAX_DEPTH: u32 = 100;
+
+/// Trait implemented by applications to evaluate workflow expressions.
+///
+/// The trait is implemented for closures with the same signature as
+/// [`evaluate`](Self::evaluate).
+pub trait ExpressionEvaluator {
+    /// Evaluates a workflow expression against the given data and returns its result.
+    ///
+    /// The expression is passed without its `${ }` wrapper.
+    ///
+    /// # Errors
+    ///
+    /// * [`ExpressionFailed`]: the expression could not be evaluated
+    ///
+    /// [`ExpressionFailed`]: crate::Error::ExpressionFailed
+    fn evaluate(&self, expression: &str, data: &Value) -> crate::Result<Value>;
+}
+
+impl<F> ExpressionEvaluator for F
+where
+    F: Fn(&str, &Value) -> crate::Result<Value>,
+{
+    fn evaluate(&self, expression: &str, data: &Value) -> crate::Result<Value> {
+        self(expression, data)
+    }
+}
+
+// Evaluates an expression, which may or may not be wrapped in `${ }`.
+pub(crate) fn evaluate<X>(evaluator: &X, expression: &str, data: &Value) -> crate::Result<Value>
+where
+    X: ExpressionEvaluator + ?Sized,
+{
+    evaluator.evaluate(strip_expression(expression), data)
+}
+
+// Evaluates the expressions found in the string values of a JSON value, recursively.
+pub(crate) fn evaluate_value<X>(evaluator: &X, value: &Value, data: &Value) -> crate::Result<Value>
+where
+    X: ExpressionEvaluator + ?Sized,
+{
+    Ok(match value {
+        Value::String(value) if is_expression(value) => evaluate(evaluator, value, data)?,
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| evaluate_value(evaluator, value, data))
+                .collect::<crate::Result<_>>()?,
+        ),
+        Value::Object(values) => Value::Object(
+            values
+                .iter()
+                .map(|(name, value)| Ok((name.clone(), evaluate_value(evaluator, value, data)?)))
+                .collect::<crate::Result<_>>()?,
+        ),
+        value => value.clone(),
+    })
+}
+
+// Performs actions in the given mode, merging their results into the state data.
+//
+// Since action executors are blocking, parallel actions are called one after the other; however,
+// they all receive the state data as it was before any of them was performed, and their results
+// are only merged once all of them have completed.
+pub(crate) fn perform_actions<E, X>(
+    definition: &WorkflowDefinition,
+    actions: &[Action],
+    mode: ExecutionMode,
+    data: &mut Map<String, Value>,
+    executor: &E,
+    evaluator: &X,
+) -> crate::Result<()>
+where
+    E: ActionExecutor + ?Sized,
+    X: ExpressionEvaluator + ?Sized,
+{
+    let context = InvocationContext::new(definition);
+    match mode {
+        ExecutionMode::Sequential => {
+            for (index, action) in actions.iter().enumerate() {
+                if let Some(results) =
+                    perform_action(definition, action, index, data, &context, executor, evaluator)?
+                {
+                    merge_action_results(action.action_data_filter.as_ref(), data, results)?;
+                }
+            }
+        },
+        ExecutionMode::Parallel => {
+            let results = actions
+                .iter()
+                .enumerate()
+                .map(|(index, action)| {
+                    perform_action(definition, action, index, data, &context, executor, evaluator)
+                })
+                .collect::<crate::Result<Vec<_>>>()?;
+            for (action, results) in actions.iter().zip(results) {
+                if let Some(results) = results {
+                    merge_action_results(action.action_data_filter.as_ref(), data, results)?;
+                }
+            }
+        },
+    }
+    Ok(())
+}
+
+// Performs an action in the given invocation context and returns its filtered results, or `None`
+// if the action was skipped or its results should not be merged into the state data.
+pub(crate) fn perform_action<E, X>(
+    definition: &WorkflowDefinition,
+    action: &Action,
+    index: usize,
+    data: &Map<String, Value>,
+    context: &InvocationContext,
+    executor: &E,
+    evaluator: &X,
+) -> crate::Result<Option<Value>>
+where
+    E: ActionExecutor + ?Sized,
+    X: ExpressionEvaluator + ?Sized,
+{
+    let state_data = Value::Object(data.clone());
+    if let Some(condition) = &action.condition {
+        match evaluate(evaluator, condition, &state_data)? {
+            Value::Bool(true) => (),
+            Value::Bool(false) => return Ok(None),
+            _ => {
+                return Err(crate::Error::InvalidExpressionResult {
+                    expression: condition.clone(),
+                    expected: "a boolean",
+                })
+            },
+        }
+    }
+
+    let function_ref =
+        action
+            .function_ref
+            .as_ref()
+            .ok_or_else(|| crate::Error::UnsupportedAction {
+                action: action.name.clone().unwrap_or_else(|| format!("#{}", index)),
+                reason: "only actions calling functions are supported",
+            })?;
+
+    let filter = action.action_data_filter.as_ref();
+    let input = filter_action_input(filter, data, evaluator)?;
+    let arguments = function_ref
+        .arguments()
+        .into_iter()
+        .flat_map(|arguments| &arguments.arguments)
+        .map(|(name, value)| Ok((name.clone(), evaluate_value(evaluator, value, &input)?)))
+        .collect::<crate::Result<_>>()?;
+
+    let mut context = context.clone();
+    if let Some(name) = &action.name {
+        context = context.with_action(name.as_str());
+    }
+    let call = FunctionCall::new(definition, function_ref, arguments)?.with_context(context);
+    let results = executor.execute(&call)?;
+
+    filter_action_results(filter, results, evaluator)
+}
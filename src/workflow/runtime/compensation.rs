@@ -0,0 +1,231 @@
+//! Workflow compensation.
+//!
+//! States can define a [state compensating them](State::compensated_by), used to undo their
+//! work when workflow compensation is triggered by a [transition] or an [end]. Runtimes track
+//! the states eligible for compensation in the [`CompensationLog`] of each [workflow instance]:
+//!
+//! 1. When a state completes, runtimes [record](CompensationLog::record) it in the log.
+//! 2. When a transition or end triggers compensation, runtimes call
+//!    [`CompensationLog::compensate`] to get the [steps](CompensationStep) to perform, in the
+//!    reverse order of the states' executions.
+//! 3. For each step, runtimes execute the [compensation state](CompensationStep::compensated_by)
+//!    like any other state, then follow its transitions (which can only lead to other states
+//!    [used for compensation](State::used_for_compensation)) until it ends.
+//! 4. Once all steps are performed, runtimes take the transition (or end the workflow).
+//!
+//! Steps 2 and 3 can be performed by [`CompensationLog::run`], which executes the compensation
+//! states through a [`StateRunner`] provided by the runtime.
+//!
+//! [transition]: crate::workflow::definition::Transition::compensate
+//! [end]: crate::workflow::definition::End::compensate
+//! [workflow instance]: crate::workflow::instance::WorkflowInstance::compensation
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::workflow::definition::{State, WorkflowDefinition};
+
+/// States of a workflow instance eligible for compensation, in the order they were executed.
+///
+/// See the [module documentation](self) for details.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CompensationLog {
+    states: Vec<String>,
+}
+
+impl CompensationLog {
+    /// Creates an empty compensation log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the names of the states eligible for compensation, in the order they were
+    /// executed.
+    ///
+    /// States executed multiple times (e.g. in loops) appear once per execution.
+    pub fn states(&self) -> &[String] {
+        &self.states
+    }
+
+    /// Returns `true` if no state is eligible for compensation.
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Records the completed execution of the state with the given name.
+    ///
+    /// Only states that [define a compensation state](State::compensated_by) are eligible for
+    /// compensation; others are not recorded. Runtimes should only record states that completed
+    /// successfully, since the work of failed states does not need to be undone.
+    ///
+    /// Returns `true` if the state was recorded.
+    ///
+    /// # Errors
+    ///
+    /// * [`UnknownState`]: the workflow definition has no state named `state`
+    ///
+    /// [`UnknownState`]: crate::Error::UnknownState
+    pub fn record(&mut self, definition: &WorkflowDefinition, state: &str) -> crate::Result<bool> {
+        let recorded = find_state(definition, state)?.compensated_by().is_some();
+        if recorded {
+            self.states.push(state.into());
+        }
+        Ok(recorded)
+    }
+
+    /// Returns the compensation steps to perform, in the reverse order of the executions of
+    /// the compensated states, and clears the log.
+    ///
+    /// If an error occurs, the log is left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// * [`UnknownState`]: a recorded state or its compensation state is not defined in
+    ///                     the workflow definition
+    /// * [`InvalidCompensation`]: a compensation state is not [used for compensation]
+    ///
+    /// [`UnknownState`]: crate::Error::UnknownState
+    /// [`InvalidCompensation`]: crate::Error::InvalidCompensation
+    /// [used for compensation]: State::used_for_compensation
+    pub fn compensate<'a>(
+        &mut self,
+        definition: &'a WorkflowDefinition,
+    ) -> crate::Result<Vec<CompensationStep<'a>>> {
+        let steps = self
+            .states
+            .iter()
+            .rev()
+            .map(|name| {
+                let state = find_state(definition, name)?;
+                let Some(compensated_by) = state.compensated_by() else {
+                    // Only states with a compensation state are recorded, unless the
+                    // workflow definition changed since.
+                    return Ok(None);
+                };
+
+                let compensated_by = find_state(definition, compensated_by)?;
+                if !compensated_by.used_for_compensation() {
+                    return Err(crate::Error::InvalidCompensation {
+                        state: name.clone(),
+                        compensated_by: compensated_by.name().into(),
+                    });
+                }
+                Ok(Some(CompensationStep { state, compensated_by }))
+            })
+            .filter_map(crate::Result::transpose)
+            .collect::<crate::Result<_>>()?;
+
+        self.states.clear();
+        Ok(steps)
+    }
+
+    /// Performs the compensation steps, in the reverse order of the executions of the
+    /// compensated states, and clears the log.
+    ///
+    /// For each [step](Self::compensate), the compensation state is executed through the given
+    /// `runner`, then the states it transitions to are executed in turn until one of them ends.
+    /// The state data is passed from one state to the next, starting with `data`; the output of
+    /// the last state executed is returned.
+    ///
+    /// The log is cleared before the steps are performed, so that they are not performed again
+    /// if one of them fails.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by the runner, in which case the remaining steps are not performed.
+    /// In addition:
+    ///
+    /// * [`UnknownState`]: a recorded state, its compensation state or a state transitioned to
+    ///                     during compensation is not defined in the workflow definition
+    /// * [`InvalidCompensation`]: a compensation state, or a state transitioned to during
+    ///                            compensation, is not [used for compensation]
+    ///
+    /// [`UnknownState`]: crate::Error::UnknownState
+    /// [`InvalidCompensation`]: crate::Error::InvalidCompensation
+    /// [used for compensation]: State::used_for_compensation
+    pub fn run<R>(
+        &mut self,
+        definition: &WorkflowDefinition,
+        mut data: Map<String, Value>,
+        runner: &R,
+    ) -> crate::Result<Map<String, Value>>
+    where
+        R: StateRunner + ?Sized,
+    {
+        for step in self.compensate(definition)? {
+            let mut state = step.compensated_by;
+            loop {
+                let output = runner.run_state(state, data)?;
+                data = output.data;
+                let Some(next_state) = output.next_state else {
+                    break;
+                };
+
+                state = find_state(definition, &next_state)?;
+                if !state.used_for_compensation() {
+                    return Err(crate::Error::InvalidCompensation {
+                        state: step.state.name().into(),
+                        compensated_by: next_state,
+                    });
+                }
+            }
+        }
+        Ok(data)
+    }
+}
+
+/// Trait implemented by runtimes to execute the states used for compensation.
+///
+/// The trait is implemented for closures with the same signature as
+/// [`run_state`](Self::run_state).
+pub trait StateRunner {
+    /// Executes the given state with the given state data input, and returns its output and
+    /// the state it transitions to.
+    ///
+    /// The state is executed like any other state, including its [state data filters].
+    ///
+    /// # Errors
+    ///
+    /// Any error that occurred while executing the state.
+    ///
+    /// [state data filters]: crate::workflow::definition::StateDataFilter
+    fn run_state(&self, state: &State, data: Map<String, Value>) -> crate::Result<StateOutput>;
+}
+
+impl<F> StateRunner for F
+where
+    F: Fn(&State, Map<String, Value>) -> crate::Result<StateOutput>,
+{
+    fn run_state(&self, state: &State, data: Map<String, Value>) -> crate::Result<StateOutput> {
+        self(state, data)
+    }
+}
+
+/// Output of a state executed by a [`StateRunner`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateOutput {
+    /// State data output
+    pub data: Map<String, Value>,
+
+    /// Name of the state to transition to, or `None` if the state ends
+    pub next_state: Option<String>,
+}
+
+/// Compensation of a state, returned by [`CompensationLog::compensate`].
+#[derive(Debug, Copy, Clone)]
+pub struct CompensationStep<'a> {
+    /// State to compensate
+    pub state: &'a State,
+
+    /// State responsible for compensating [`state`](Self::state)
+    pub compensated_by: &'a State,
+}
+
+fn find_state<'a>(definition: &'a WorkflowDefinition, name: &str) -> crate::Result<&'a State> {
+    definition
+        .states
+        .iter()
+        .find(|state| state.name() == name)
+        .ok_or_else(|| crate::Error::UnknownState { state: name.into() })
+}
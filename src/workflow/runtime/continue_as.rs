@@ -0,0 +1,185 @@
+//! Execution of [`continueAs`](ContinueAsDef) ends.
+
+use std::rc::Rc;
+
+use serde_json::{Map, Value};
+
+use crate::registry::WorkflowRegistry;
+use crate::workflow::definition::{ContinueAsDef, Data, WorkflowDefinition};
+use crate::workflow::instance::WorkflowInstance;
+use crate::workflow::runtime::{evaluate, evaluate_value, ExpressionEvaluator, DEFAULT_MAX_DEPTH};
+
+/// Execution of a [`ContinueAsDef`], found in an [end](crate::workflow::definition::End::continue_as).
+///
+/// When a state ends the workflow and continues execution as another workflow (or as a new
+/// execution of the same workflow), runtimes [run](Self::run) this execution to complete the
+/// current instance and create the instance continuing it.
+///
+/// Workflows continuing as themselves (directly or through other workflows, see
+/// [`WorkflowRegistry::recursive_workflows`]) could run indefinitely; the number of successive
+/// continuations is therefore limited to a [maximum depth](Self::with_max_depth). Continuations
+/// and [sub-workflow invocations](crate::workflow::runtime::subflow) share the same
+/// [depth](WorkflowInstance::depth).
+#[derive(Debug, Clone)]
+pub struct ContinueAsExecution<'a> {
+    continue_as: &'a ContinueAsDef,
+    max_depth: u32,
+}
+
+/// Workflow instance continuing the execution of another one, returned by
+/// [`ContinueAsExecution::run`].
+#[derive(Debug, Clone)]
+pub struct ContinuedWorkflow {
+    /// Definition of the workflow to continue execution as
+    pub definition: Rc<WorkflowDefinition>,
+
+    /// New workflow instance, starting at the workflow's start state
+    pub instance: WorkflowInstance,
+}
+
+impl<'a> ContinueAsExecution<'a> {
+    /// Starts the execution of the given "continue as" definition.
+    ///
+    /// The number of successive continuations is limited to [`DEFAULT_MAX_DEPTH`].
+    ///
+    /// [`DEFAULT_MAX_DEPTH`]: crate::workflow::runtime::DEFAULT_MAX_DEPTH
+    pub fn new(continue_as: &'a ContinueAsDef) -> Self {
+        Self { continue_as, max_depth: DEFAULT_MAX_DEPTH }
+    }
+
+    /// Returns a copy of this execution limiting the number of successive continuations to
+    /// `max_depth`.
+    ///
+    /// Each continued instance's [depth] is one more than the instance it continues;
+    /// [`run`](Self::run) fails if it would exceed `max_depth`.
+    ///
+    /// [depth]: WorkflowInstance::depth
+    pub fn with_max_depth(self, max_depth: u32) -> Self {
+        Self { max_depth, ..self }
+    }
+
+    /// Returns the executed "continue as" definition.
+    pub fn continue_as(&self) -> &'a ContinueAsDef {
+        self.continue_as
+    }
+
+    /// Returns the maximum number of successive continuations.
+    pub fn max_depth(&self) -> u32 {
+        self.max_depth
+    }
+
+    /// Selects the workflow data input of the continued execution from the state data output.
+    ///
+    /// * If the "continue as" definition specifies an expression, it is evaluated against the
+    ///   state data and must evaluate to an object.
+    /// * If it specifies an object, its values that are workflow expressions are evaluated
+    ///   against the state data.
+    /// * Otherwise, the whole state data is passed to the continued execution.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by the evaluator, in addition to:
+    ///
+    /// * [`InvalidExpressionResult`]: the data expression did not evaluate to an object
+    ///
+    /// [`InvalidExpressionResult`]: crate::Error::InvalidExpressionResult
+    pub fn input<X>(
+        &self,
+        data: &Map<String, Value>,
+        evaluator: &X,
+    ) -> crate::Result<Map<String, Value>>
+    where
+        X: ExpressionEvaluator + ?Sized,
+    {
+        let ContinueAsDef::WithData { data: Some(input), .. } = self.continue_as else {
+            return Ok(data.clone());
+        };
+
+        let state_data = Value::Object(data.clone());
+        match input {
+            Data::Expression(expression) => match evaluate(evaluator, expression, &state_data)? {
+                Value::Object(input) => Ok(input),
+                _ => Err(crate::Error::InvalidExpressionResult {
+                    expression: expression.clone(),
+                    expected: "an object",
+                }),
+            },
+            Data::Object { fields } => fields
+                .iter()
+                .map(|(name, value)| {
+                    Ok((name.clone(), evaluate_value(evaluator, value, &state_data)?))
+                })
+                .collect(),
+        }
+    }
+
+    /// Completes the given instance and creates a new instance continuing its execution.
+    ///
+    /// The workflow to continue execution as is [resolved](WorkflowRegistry::resolve_reference)
+    /// in the given registry. The new instance starts at the workflow's start state, with
+    /// the data [selected](Self::input) from `instance`'s data, which must be the data output of
+    /// the state that ended. It keeps the instance's labels, and uses the workflow execution
+    /// timeout of the "continue as" definition, if any, instead of the workflow's own. Its
+    /// [depth](WorkflowInstance::depth) is one more than `instance`'s.
+    ///
+    /// `instance` is then completed: its [`state`](WorkflowInstance::state) is cleared, leaving
+    /// its data as the final output of the workflow. If an error occurs, `instance` is left
+    /// unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by the evaluator, in addition to:
+    ///
+    /// * [`RecursionLimitExceeded`]: the new instance would exceed the
+    ///                               [maximum depth](Self::with_max_depth)
+    /// * [`InvalidExpressionResult`]: the data expression did not evaluate to an object
+    /// * [`MissingWorkflowDependency`]: the workflow to continue execution as is not registered
+    /// * [`AmbiguousWorkflowReference`]: the "continue as" definition does not specify a version
+    ///                                   and multiple versions of the workflow are registered
+    ///
+    /// [`RecursionLimitExceeded`]: crate::Error::RecursionLimitExceeded
+    /// [`InvalidExpressionResult`]: crate::Error::InvalidExpressionResult
+    /// [`MissingWorkflowDependency`]: crate::Error::MissingWorkflowDependency
+    /// [`AmbiguousWorkflowReference`]: crate::Error::AmbiguousWorkflowReference
+    pub fn run<X>(
+        &self,
+        definition: &WorkflowDefinition,
+        instance: &mut WorkflowInstance,
+        registry: &WorkflowRegistry,
+        evaluator: &X,
+    ) -> crate::Result<ContinuedWorkflow>
+    where
+        X: ExpressionEvaluator + ?Sized,
+    {
+        let depth = instance.depth.saturating_add(1);
+        if depth > self.max_depth {
+            return Err(crate::Error::RecursionLimitExceeded {
+                instance: instance.id.clone(),
+                workflow: self.continue_as.workflow_id().into(),
+                limit: self.max_depth,
+            });
+        }
+
+        let continued = registry
+            .resolve_reference(
+                definition,
+                self.continue_as.workflow_id(),
+                self.continue_as.version(),
+            )?
+            .definition()
+            .clone();
+        let input = self.input(&instance.data, evaluator)?;
+
+        let mut continued_instance = WorkflowInstance::for_definition(&continued, Some(input));
+        continued_instance.labels = instance.labels.clone();
+        continued_instance.depth = depth;
+        if let ContinueAsDef::WithData { workflow_exec_timeout: Some(timeout), .. } =
+            self.continue_as
+        {
+            continued_instance.workflow_exec_timeout = Some(timeout.clone());
+        }
+
+        instance.state = None;
+        Ok(ContinuedWorkflow { definition: continued, instance: continued_instance })
+    }
+}
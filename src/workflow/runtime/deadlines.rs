@@ -0,0 +1,406 @@
+//! Deadlines derived from workflow timeouts.
+//!
+//! The specification defines timeouts at several levels: the workflow execution timeout, state
+//! execution timeouts (for a single attempt and in total, including retries), branch and action
+//! execution timeouts, and event timeouts. Except for the workflow execution timeout, they can
+//! be defined by states (or parallel branches), or else by the workflow's
+//! [default timeouts](WorkflowDefinition::timeouts).
+//!
+//! [`Deadlines`] tracks the instants at which these timeouts expire along an execution path.
+//! Runtimes update it as the execution progresses (entering states, performing actions, waiting
+//! for events), [check](Deadlines::check) it to enforce the timeouts and [apply](Deadlines::apply)
+//! it to the [context](InvocationContext) of function calls, so that executors can honor them.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::workflow::definition::timeouts::{
+    ActionExecTimeout, BranchExecTimeout, EventTimeout, StateExecTimeout, Timeouts,
+    WorkflowExecTimeout,
+};
+use crate::workflow::definition::{Branch, ParallelState, State, WorkflowDefinition};
+use crate::workflow::executor::InvocationContext;
+
+/// Kind of workflow timeout.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TimeoutKind {
+    /// [Workflow execution timeout](WorkflowExecTimeout)
+    WorkflowExec,
+
+    /// Total [state execution timeout](StateExecTimeout), including retries
+    StateExec,
+
+    /// Single [state execution timeout](StateExecTimeout::single_as_duration), not including
+    /// retries
+    SingleStateExec,
+
+    /// [Branch execution timeout](BranchExecTimeout)
+    BranchExec,
+
+    /// [Action execution timeout](ActionExecTimeout)
+    ActionExec,
+
+    /// [Event timeout](EventTimeout)
+    Event,
+}
+
+impl fmt::Display for TimeoutKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::WorkflowExec => "workflowExecTimeout",
+            Self::StateExec => "stateExecTimeout",
+            Self::SingleStateExec => "stateExecTimeout.single",
+            Self::BranchExec => "branchExecTimeout",
+            Self::ActionExec => "actionExecTimeout",
+            Self::Event => "eventTimeout",
+        })
+    }
+}
+
+/// Instant at which a timeout expires.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Deadline {
+    /// Kind of timeout
+    pub timeout: TimeoutKind,
+
+    /// Instant at which the timeout expires
+    pub at: Instant,
+
+    /// Whether the execution is interrupted when the timeout expires.
+    ///
+    /// Only workflow execution timeouts can be non-interrupting; see
+    /// [`Deadlines::workflow_expired`].
+    pub interrupt: bool,
+}
+
+/// Deadlines of an execution path of a workflow instance.
+///
+/// Deadlines are computed from the timeouts defined in the workflow definition when the
+/// execution path reaches the corresponding step. Timeouts too large to be represented never
+/// expire. Default timeouts defined in an [external resource](Timeouts::Uri) must be resolved
+/// beforehand; they are otherwise ignored. See the [module documentation](self) for details.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Deadlines {
+    state: Option<String>,
+    deadlines: Vec<Deadline>,
+}
+
+impl Deadlines {
+    /// Creates the deadlines of a workflow instance started at the given instant.
+    ///
+    /// The instance times out according to the workflow's execution timeout, if any. If its
+    /// [`interrupt`] property is `false`, the execution is not interrupted when the timeout
+    /// expires; see [`workflow_expired`](Self::workflow_expired).
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidDuration`]: the timeout is not a valid ISO 8601 duration
+    /// * [`UnsupportedDuration`]: the timeout is calendar-dependent or too large
+    ///
+    /// [`interrupt`]: WorkflowExecTimeout::Complex::interrupt
+    /// [`InvalidDuration`]: crate::Error::InvalidDuration
+    /// [`UnsupportedDuration`]: crate::Error::UnsupportedDuration
+    pub fn for_workflow(
+        definition: &WorkflowDefinition,
+        started_at: Instant,
+    ) -> crate::Result<Self> {
+        let mut deadlines = Self::default();
+        let timeout = match &definition.timeouts {
+            Some(Timeouts::Complex { workflow_exec_timeout, .. }) => workflow_exec_timeout.as_ref(),
+            _ => None,
+        };
+        if let Some(timeout) = timeout {
+            let interrupt = match timeout {
+                WorkflowExecTimeout::Simple(_) => true,
+                WorkflowExecTimeout::Complex { interrupt, .. } => *interrupt,
+            };
+            deadlines.set(TimeoutKind::WorkflowExec, Some(timeout.as_duration()?), started_at);
+            deadlines.interrupt(TimeoutKind::WorkflowExec, interrupt);
+        }
+
+        Ok(deadlines)
+    }
+
+    /// Returns the name of the state the execution path is in, if any.
+    pub fn state(&self) -> Option<&str> {
+        self.state.as_deref()
+    }
+
+    /// Starts the execution of the given state at the given instant.
+    ///
+    /// The state's total and single execution deadlines are computed from the state's execution
+    /// timeout, or else the workflow's default one. Deadlines of the previous state's branches,
+    /// actions and events are removed.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidDuration`]: the timeout is not a valid ISO 8601 duration
+    /// * [`UnsupportedDuration`]: the timeout is calendar-dependent or too large
+    ///
+    /// [`InvalidDuration`]: crate::Error::InvalidDuration
+    /// [`UnsupportedDuration`]: crate::Error::UnsupportedDuration
+    pub fn enter_state(
+        &mut self,
+        definition: &WorkflowDefinition,
+        state: &State,
+        now: Instant,
+    ) -> crate::Result<()> {
+        let timeout = state_exec_timeout(definition, state);
+        let total = timeout.map(StateExecTimeout::as_duration).transpose()?;
+        let single = timeout
+            .map(StateExecTimeout::single_as_duration)
+            .transpose()?
+            .flatten();
+
+        self.state = Some(state.name().into());
+        self.set(TimeoutKind::StateExec, total, now);
+        self.set(TimeoutKind::SingleStateExec, single, now);
+        for timeout in [TimeoutKind::BranchExec, TimeoutKind::ActionExec, TimeoutKind::Event] {
+            self.set(timeout, None, now);
+        }
+        Ok(())
+    }
+
+    /// Starts a new attempt at executing the current state at the given instant.
+    ///
+    /// Only the single state execution deadline is reset; the total state execution deadline
+    /// includes retries.
+    ///
+    /// # Errors
+    ///
+    /// See [`enter_state`](Self::enter_state).
+    pub fn retry_state(
+        &mut self,
+        definition: &WorkflowDefinition,
+        state: &State,
+        now: Instant,
+    ) -> crate::Result<()> {
+        let single = state_exec_timeout(definition, state)
+            .map(StateExecTimeout::single_as_duration)
+            .transpose()?
+            .flatten();
+
+        self.set(TimeoutKind::SingleStateExec, single, now);
+        Ok(())
+    }
+
+    /// Starts the execution of a branch of a parallel state at the given instant.
+    ///
+    /// Each branch has its own execution path, so this is usually called on a clone of the
+    /// parallel state's deadlines. The branch execution deadline is computed from the branch's
+    /// execution timeout, or else the state's, or else the workflow's default one.
+    ///
+    /// # Errors
+    ///
+    /// See [`enter_state`](Self::enter_state).
+    pub fn enter_branch(
+        &mut self,
+        definition: &WorkflowDefinition,
+        state: &ParallelState,
+        branch: &Branch,
+        now: Instant,
+    ) -> crate::Result<()> {
+        let timeout = branch_exec_timeout(definition, state, branch)
+            .map(BranchExecTimeout::as_duration)
+            .transpose()?;
+
+        self.set(TimeoutKind::BranchExec, timeout, now);
+        Ok(())
+    }
+
+    /// Starts performing an action of the given state (or of the given branch of a parallel
+    /// state) at the given instant.
+    ///
+    /// The action execution deadline is computed from the branch's or state's action execution
+    /// timeout, or else the workflow's default one. Call
+    /// [`finish_action`](Self::finish_action) once the action has been performed.
+    ///
+    /// # Errors
+    ///
+    /// See [`enter_state`](Self::enter_state).
+    pub fn start_action(
+        &mut self,
+        definition: &WorkflowDefinition,
+        state: &State,
+        branch: Option<&Branch>,
+        now: Instant,
+    ) -> crate::Result<()> {
+        let timeout = match branch {
+            Some(branch) => branch_action_exec_timeout(definition, branch),
+            None => state
+                .action_exec_timeout()
+                .or_else(|| default_action_exec_timeout(definition)),
+        }
+        .map(ActionExecTimeout::as_duration)
+        .transpose()?;
+
+        self.set(TimeoutKind::ActionExec, timeout, now);
+        Ok(())
+    }
+
+    /// Removes the action execution deadline, once an action has been performed.
+    pub fn finish_action(&mut self) {
+        self.remove(TimeoutKind::ActionExec);
+    }
+
+    /// Starts waiting for the events of the given state at the given instant.
+    ///
+    /// The event deadline is computed from the state's event timeout, or else the workflow's
+    /// default one. How the state proceeds when the deadline passes depends on its type (for
+    /// example, event-based switch states select their default condition). Call
+    /// [`events_received`](Self::events_received) once the events have been consumed.
+    ///
+    /// # Errors
+    ///
+    /// See [`enter_state`](Self::enter_state).
+    pub fn wait_for_events(
+        &mut self,
+        definition: &WorkflowDefinition,
+        state: &State,
+        now: Instant,
+    ) -> crate::Result<()> {
+        let timeout = state
+            .event_timeout()
+            .or(match &definition.timeouts {
+                Some(Timeouts::Complex { event_timeout, .. }) => event_timeout.as_ref(),
+                _ => None,
+            })
+            .map(EventTimeout::as_duration)
+            .transpose()?;
+
+        self.set(TimeoutKind::Event, timeout, now);
+        Ok(())
+    }
+
+    /// Removes the event deadline, once the events the state waited for have been consumed.
+    pub fn events_received(&mut self) {
+        self.remove(TimeoutKind::Event);
+    }
+
+    /// Returns the deadline of the given kind of timeout, if any.
+    pub fn get(&self, timeout: TimeoutKind) -> Option<&Deadline> {
+        self.deadlines
+            .iter()
+            .find(|deadline| deadline.timeout == timeout)
+    }
+
+    /// Returns the earliest deadline interrupting the execution, if any.
+    ///
+    /// Runtimes waiting for something (e.g. for events) should not wait past this deadline.
+    pub fn next(&self) -> Option<&Deadline> {
+        self.deadlines
+            .iter()
+            .filter(|deadline| deadline.interrupt)
+            .min_by_key(|deadline| deadline.at)
+    }
+
+    /// Checks that no deadline interrupting the execution has passed at the given instant.
+    ///
+    /// # Errors
+    ///
+    /// * [`TimedOut`]: a deadline has passed. If multiple deadlines have passed, the
+    ///                 earliest one is reported.
+    ///
+    /// [`TimedOut`]: crate::Error::TimedOut
+    pub fn check(&self, now: Instant) -> crate::Result<()> {
+        match self.next() {
+            Some(deadline) if deadline.at <= now => {
+                Err(crate::Error::TimedOut { timeout: deadline.timeout, state: self.state.clone() })
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns `true` if the workflow execution timeout has expired at the given instant.
+    ///
+    /// If the timeout is [interrupting](Deadline::interrupt), [`check`](Self::check) also fails
+    /// once it expires. Otherwise, the current execution is allowed to finish: runtimes should
+    /// check this method before each transition and, once the timeout has expired, run the
+    /// timeout's [`run_before`] state, if any, then terminate the workflow instance.
+    ///
+    /// [`run_before`]: WorkflowExecTimeout::Complex::run_before
+    pub fn workflow_expired(&self, now: Instant) -> bool {
+        self.get(TimeoutKind::WorkflowExec)
+            .is_some_and(|deadline| deadline.at <= now)
+    }
+
+    /// Returns a copy of the given invocation context whose [deadline] is the
+    /// [earliest deadline](Self::next) interrupting the execution, if any.
+    ///
+    /// [deadline]: InvocationContext::deadline
+    pub fn apply(&self, context: InvocationContext) -> InvocationContext {
+        match self.next() {
+            Some(deadline) => context.with_deadline(deadline.at),
+            None => context,
+        }
+    }
+
+    fn set(&mut self, timeout: TimeoutKind, duration: Option<Duration>, now: Instant) {
+        self.remove(timeout);
+        if let Some(at) = duration.and_then(|duration| now.checked_add(duration)) {
+            self.deadlines
+                .push(Deadline { timeout, at, interrupt: true });
+        }
+    }
+
+    fn interrupt(&mut self, timeout: TimeoutKind, interrupt: bool) {
+        for deadline in &mut self.deadlines {
+            if deadline.timeout == timeout {
+                deadline.interrupt = interrupt;
+            }
+        }
+    }
+
+    fn remove(&mut self, timeout: TimeoutKind) {
+        self.deadlines
+            .retain(|deadline| deadline.timeout != timeout);
+    }
+}
+
+// Returns the state's execution timeout, or else the workflow's default one.
+fn state_exec_timeout<'a>(
+    definition: &'a WorkflowDefinition,
+    state: &'a State,
+) -> Option<&'a StateExecTimeout> {
+    state.state_exec_timeout().or(match &definition.timeouts {
+        Some(Timeouts::Complex { state_exec_timeout, .. }) => state_exec_timeout.as_ref(),
+        _ => None,
+    })
+}
+
+// Returns the branch's execution timeout, or else the parallel state's, or else the workflow's
+// default one.
+pub(crate) fn branch_exec_timeout<'a>(
+    definition: &'a WorkflowDefinition,
+    state: &'a ParallelState,
+    branch: &'a Branch,
+) -> Option<&'a BranchExecTimeout> {
+    branch
+        .timeouts
+        .as_ref()
+        .and_then(|timeouts| timeouts.branch_exec_timeout.as_ref())
+        .or_else(|| state.timeouts.as_ref()?.branch_exec_timeout.as_ref())
+        .or(match &definition.timeouts {
+            Some(Timeouts::Complex { branch_exec_timeout, .. }) => branch_exec_timeout.as_ref(),
+            _ => None,
+        })
+}
+
+// Returns the action execution timeout of a branch, or else the workflow's default one.
+pub(crate) fn branch_action_exec_timeout<'a>(
+    definition: &'a WorkflowDefinition,
+    branch: &'a Branch,
+) -> Option<&'a ActionExecTimeout> {
+    branch
+        .timeouts
+        .as_ref()
+        .and_then(|timeouts| timeouts.action_exec_timeout.as_ref())
+        .or_else(|| default_action_exec_timeout(definition))
+}
+
+fn default_action_exec_timeout(definition: &WorkflowDefinition) -> Option<&ActionExecTimeout> {
+    match &definition.timeouts {
+        Some(Timeouts::Complex { action_exec_timeout, .. }) => action_exec_timeout.as_ref(),
+        _ => None,
+    }
+}
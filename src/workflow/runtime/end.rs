@@ -0,0 +1,76 @@
+//! Execution of [ends](End).
+
+use crate::workflow::definition::{End, WorkflowDefinition};
+use crate::workflow::instance::WorkflowInstance;
+
+/// Outcome of an [`EndExecution`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EndOutcome {
+    /// The instance completed.
+    Completed,
+
+    /// The instance is [kept active](WorkflowInstance::kept_active), awaiting events, because
+    /// its workflow definition [keeps instances active](WorkflowDefinition::keep_active).
+    KeptActive,
+
+    /// The instance was [terminated](WorkflowInstance::terminate) by an end with
+    /// `terminate` set.
+    Terminated,
+}
+
+/// Execution of an [`End`], reached when an execution path of a workflow instance ends.
+///
+/// Ends that continue execution as a new instance are handled by
+/// [`ContinueAsExecution`](crate::workflow::runtime::continue_as::ContinueAsExecution) instead;
+/// ends that trigger compensation must be [compensated](crate::workflow::runtime::compensation)
+/// first.
+#[derive(Debug, Clone)]
+pub struct EndExecution<'a> {
+    end: &'a End,
+}
+
+impl<'a> EndExecution<'a> {
+    /// Starts the execution of the given end.
+    pub fn new(end: &'a End) -> Self {
+        Self { end }
+    }
+
+    /// Returns the executed end.
+    pub fn end(&self) -> &'a End {
+        self.end
+    }
+
+    /// Ends the current execution path of the given instance, and returns what happened to it.
+    ///
+    /// * If the end has `terminate` set, the instance is [terminated](WorkflowInstance::terminate),
+    ///   even if it is kept active.
+    /// * Otherwise, if the workflow definition [keeps instances active], the instance is
+    ///   [kept active](WorkflowInstance::kept_active): it awaits events (see
+    ///   [`EventSubscription::for_instance`]) until it is terminated by another end or reaches
+    ///   its [workflow execution timeout](WorkflowInstance::exec_timeout).
+    /// * Otherwise, the instance completes.
+    ///
+    /// In all cases, the instance's [`state`](WorkflowInstance::state) is cleared, leaving its
+    /// data as the workflow output.
+    ///
+    /// [keeps instances active]: WorkflowDefinition::keep_active
+    /// [`EventSubscription::for_instance`]: crate::workflow::subscription::EventSubscription::for_instance
+    pub fn run(
+        &self,
+        definition: &WorkflowDefinition,
+        instance: &mut WorkflowInstance,
+    ) -> EndOutcome {
+        if let End::Complex { terminate: true, .. } = self.end {
+            instance.terminate();
+            return EndOutcome::Terminated;
+        }
+
+        instance.state = None;
+        instance.kept_active = definition.keep_active;
+        if instance.kept_active {
+            EndOutcome::KeptActive
+        } else {
+            EndOutcome::Completed
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Handling of errors occurring while executing workflow states.
+//!
+//! When a state fails, for example because one of its actions failed and was not
+//! [retried](super::retry), runtimes look up how the workflow handles the error with
+//! [`ErrorRoute::resolve`]. The [workflow error] is resolved to one of the workflow's error
+//! definitions by name or code (see [`WorkflowDefinition::error_def`]), then matched against
+//! the state's [error handlers]: handlers referencing the error, through their `errorRef` or
+//! `errorRefs`, take precedence over [wildcard] handlers.
+//!
+//! [workflow error]: crate::Error::workflow_error
+//! [error handlers]: State::on_errors
+//! [wildcard]: crate::workflow::definition::WILDCARD_ERROR
+
+use crate::workflow::definition::{End, Error, State, Transition, WorkflowDefinition};
+
+/// Way a workflow handles an error that occurred in one of its states.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ErrorRoute<'a> {
+    /// The error is handled by transitioning to another state.
+    Transition(&'a Transition),
+
+    /// The error is handled by ending the workflow execution.
+    End(&'a End),
+
+    /// The state has no handler for the error, so the workflow execution fails.
+    Unhandled,
+}
+
+impl<'a> ErrorRoute<'a> {
+    /// Returns how the given state handles the given error.
+    ///
+    /// Errors that do not carry a [workflow error](crate::Error::workflow_error) cannot be
+    /// handled by the workflow.
+    pub fn resolve(
+        definition: &WorkflowDefinition,
+        state: &'a State,
+        error: &crate::Error,
+    ) -> Self {
+        match error.workflow_error() {
+            Some(error) => Self::for_workflow_error(definition, state, error),
+            None => Self::Unhandled,
+        }
+    }
+
+    /// Returns how the given state handles the workflow error with the given name or code.
+    pub fn for_workflow_error(
+        definition: &WorkflowDefinition,
+        state: &'a State,
+        error: &str,
+    ) -> Self {
+        match error_handler(definition, state, error) {
+            Some(Error { transition: Some(transition), .. }) => Self::Transition(transition),
+            Some(Error { end: Some(end), .. }) => Self::End(end),
+            _ => Self::Unhandled,
+        }
+    }
+
+    /// Returns `true` if the error is handled by the workflow.
+    pub fn is_handled(&self) -> bool {
+        !matches!(self, Self::Unhandled)
+    }
+}
+
+/// Returns the handler of the given state for the workflow error with the given name or
+/// code, if any.
+///
+/// This is like [`State::error_handler`], but the error is first resolved to the workflow's
+/// error definitions, so that errors reported by code are handled by handlers referencing
+/// their definition.
+pub fn error_handler<'a>(
+    definition: &WorkflowDefinition,
+    state: &'a State,
+    error: &str,
+) -> Option<&'a Error> {
+    let name = definition
+        .error_def(error)
+        .map_or(error, |def| def.name.as_str());
+
+    state.error_handler(name)
+}
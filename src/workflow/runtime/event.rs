@@ -0,0 +1,219 @@
+//! Execution of [event states](EventState).
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::{Map, Value};
+
+use crate::workflow::definition::{EventState, OnEvents, WorkflowDefinition};
+use crate::workflow::executor::ActionExecutor;
+use crate::workflow::runtime::filters::merge_event_data;
+use crate::workflow::runtime::{perform_actions, ExpressionEvaluator};
+
+/// Execution of an [`EventState`].
+///
+/// Runtimes [consume](Self::consume) the events the state waits for as they arrive. Once the
+/// execution is [complete](Self::is_complete), its actions can be [run](Self::run):
+///
+/// * If the state is [exclusive], the first consumed event completes the execution, and only
+///   the [`on_events`] entries referencing that event are triggered.
+/// * Otherwise, all events referenced by the state's [`on_events`] entries must be consumed
+///   before the execution completes, and all entries are triggered.
+///
+/// [exclusive]: EventState::exclusive
+/// [`on_events`]: EventState::on_events
+#[derive(Debug, Clone)]
+pub struct EventStateExecution<'a> {
+    state: &'a EventState,
+    events: BTreeMap<&'a str, Value>,
+}
+
+impl<'a> EventStateExecution<'a> {
+    /// Starts the execution of the given event state, with no event consumed.
+    pub fn new(state: &'a EventState) -> Self {
+        Self { state, events: BTreeMap::new() }
+    }
+
+    /// Returns the executed event state.
+    pub fn state(&self) -> &'a EventState {
+        self.state
+    }
+
+    /// Consumes an event, identified by the name of its event definition.
+    ///
+    /// `data` is the part of the event accessible to the workflow (see
+    /// [`EventDef::consumed_event_data`]). If an event with the same name was already consumed,
+    /// its data is replaced.
+    ///
+    /// Returns `true` if the event was consumed, or `false` if the state does not wait for it or
+    /// the execution is already [complete](Self::is_complete).
+    ///
+    /// [`EventDef::consumed_event_data`]: crate::workflow::definition::events::EventDef::consumed_event_data
+    pub fn consume(&mut self, event: &str, data: Value) -> bool {
+        if self.is_complete() {
+            return false;
+        }
+
+        match self.event_refs().find(|event_ref| *event_ref == event) {
+            Some(event_ref) => {
+                self.events.insert(event_ref, data);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Returns `true` if the [`on_events`](EventState::on_events) entry at the given index
+    /// has been satisfied, i.e. one of its events (if the state is [exclusive]) or all of them
+    /// (otherwise) have been consumed.
+    ///
+    /// [exclusive]: EventState::exclusive
+    pub fn is_satisfied(&self, index: usize) -> bool {
+        self.state
+            .on_events
+            .get(index)
+            .is_some_and(|on_events| self.satisfies(on_events))
+    }
+
+    /// Returns `true` if enough events have been consumed for the state's actions to run.
+    pub fn is_complete(&self) -> bool {
+        if self.state.exclusive {
+            !self.events.is_empty()
+        } else {
+            self.state
+                .on_events
+                .iter()
+                .all(|on_events| self.satisfies(on_events))
+        }
+    }
+
+    /// Returns the names of the events the execution still waits for, in the order they are
+    /// referenced by the state.
+    ///
+    /// Returns no event if the execution is [complete](Self::is_complete).
+    pub fn pending_events(&self) -> Vec<&'a str> {
+        if self.is_complete() {
+            return Vec::new();
+        }
+
+        let mut seen = BTreeSet::new();
+        self.event_refs()
+            .filter(|event_ref| !self.events.contains_key(event_ref) && seen.insert(*event_ref))
+            .collect()
+    }
+
+    /// Returns the [`on_events`](EventState::on_events) entries whose actions must run,
+    /// in the order they are defined.
+    ///
+    /// Returns no entry if the execution is not [complete](Self::is_complete).
+    pub fn triggered(&self) -> Vec<&'a OnEvents> {
+        if !self.is_complete() {
+            return Vec::new();
+        }
+
+        self.state
+            .on_events
+            .iter()
+            .filter(|on_events| self.satisfies(on_events))
+            .collect()
+    }
+
+    /// Runs the [triggered](Self::triggered) `on_events` entries and returns the resulting
+    /// state data.
+    ///
+    /// For each triggered entry, in order, the data of its consumed events is filtered and merged
+    /// into the state data according to the entry's [event data filter], then its actions are
+    /// performed in the entry's [action mode]:
+    ///
+    /// * Actions whose [condition] does not evaluate to `true` are skipped.
+    /// * Function arguments that are workflow expressions are evaluated against the action's
+    ///   input, selected by its [action data filter].
+    /// * Function results are filtered and merged into the state data according to the action
+    ///   data filter.
+    /// * Parallel actions are called one after the other, since [`ActionExecutor`]s are blocking;
+    ///   they all receive the same state data, and their results are merged in the order the
+    ///   actions are defined once all of them have completed.
+    ///
+    /// Data is merged into the [selected state data element] as follows: objects are merged
+    /// recursively, arrays keep the unique elements of both arrays and other values replace the
+    /// existing element.
+    ///
+    /// Actions' sleeps, retries and timeouts are not handled; runtimes can wrap the executor to
+    /// implement them. If the execution is not complete, `data` is returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by the executor or evaluator, in addition to:
+    ///
+    /// * [`InvalidExpressionResult`]: an action condition did not evaluate to a boolean
+    /// * [`StateDataMergeFailed`]: data could not be merged into the selected state data element
+    /// * [`UnsupportedAction`]: an action produces an event or calls a sub-workflow instead of
+    ///                          calling a function
+    /// * [`UnknownFunction`]: an action references a function that is not defined in the
+    ///                        workflow definition
+    /// * [`UnknownAuth`]: a function's auth definition is not defined in the workflow definition
+    ///
+    /// [event data filter]: OnEvents::event_data_filter
+    /// [action mode]: OnEvents::action_mode
+    /// [condition]: crate::workflow::definition::Action::condition
+    /// [action data filter]: crate::workflow::definition::Action::action_data_filter
+    /// [selected state data element]: crate::workflow::definition::EventDataFilter::to_state_data
+    /// [`InvalidExpressionResult`]: crate::Error::InvalidExpressionResult
+    /// [`StateDataMergeFailed`]: crate::Error::StateDataMergeFailed
+    /// [`UnsupportedAction`]: crate::Error::UnsupportedAction
+    /// [`UnknownFunction`]: crate::Error::UnknownFunction
+    /// [`UnknownAuth`]: crate::Error::UnknownAuth
+    pub fn run<E, X>(
+        &self,
+        definition: &WorkflowDefinition,
+        mut data: Map<String, Value>,
+        executor: &E,
+        evaluator: &X,
+    ) -> crate::Result<Map<String, Value>>
+    where
+        E: ActionExecutor + ?Sized,
+        X: ExpressionEvaluator + ?Sized,
+    {
+        for on_events in self.triggered() {
+            for event_ref in &on_events.event_refs {
+                if let Some(event) = self.events.get(event_ref.as_str()) {
+                    merge_event_data(
+                        &mut data,
+                        on_events.event_data_filter.as_ref(),
+                        event,
+                        evaluator,
+                    )?;
+                }
+            }
+
+            perform_actions(
+                definition,
+                on_events.actions.as_deref().unwrap_or_default(),
+                on_events.action_mode,
+                &mut data,
+                executor,
+                evaluator,
+            )?;
+        }
+
+        Ok(data)
+    }
+
+    fn satisfies(&self, on_events: &OnEvents) -> bool {
+        let mut consumed = on_events
+            .event_refs
+            .iter()
+            .map(|event_ref| self.events.contains_key(event_ref.as_str()));
+        if self.state.exclusive {
+            consumed.any(|consumed| consumed)
+        } else {
+            consumed.all(|consumed| consumed)
+        }
+    }
+
+    fn event_refs(&self) -> impl Iterator<Item = &'a str> + 'a {
+        self.state
+            .on_events
+            .iter()
+            .flat_map(|on_events| on_events.event_refs.iter().map(String::as_str))
+    }
+}
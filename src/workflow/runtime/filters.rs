@@ -0,0 +1,305 @@
+//! Data filters applied when executing workflow states.
+//!
+//! The specification defines three kinds of data filters, which are applied through an
+//! [`ExpressionEvaluator`]:
+//!
+//! * [State data filters] filter the data input of a state when entering it
+//!   (see [`filter_state_input`]) and its data output when leaving it (see
+//!   [`filter_state_output`]).
+//! * [Action data filters](ActionDataFilter) select the input of an action from the state data
+//!   (see [`filter_action_input`]), filter its results (see [`filter_action_results`]) and
+//!   select where they are merged into the state data (see [`merge_action_results`]).
+//! * [Event data filters](EventDataFilter) filter the payload of consumed events and select where
+//!   it is merged into the state data (see [`merge_event_data`]).
+//!
+//! Data is merged into the state data as follows: objects are merged recursively, elements of
+//! arrays that are not already in the existing array are appended to it, and other values replace
+//! the existing element (see [`merge_into_state_data`]).
+//!
+//! ```
+//! # use serde_json::{json, Value};
+//! # use travailleur::workflow::definition::ActionDataFilter;
+//! # use travailleur::workflow::runtime::filters::{filter_action_results, merge_action_results};
+//! // Evaluates simple paths only.
+//! let evaluate = |expression: &str, data: &Value| {
+//!     Ok(data.pointer(&expression.replace('.', "/")).cloned().unwrap_or_default())
+//! };
+//! let filter = ActionDataFilter {
+//!     from_state_data: None,
+//!     use_results: true,
+//!     results: Some("${ .greeting }".into()),
+//!     to_state_data: Some("${ .finalGreeting }".into()),
+//! };
+//!
+//! let results = json!({ "greeting": "Hello, Jane!", "language": "en" });
+//! let results = filter_action_results(Some(&filter), results, &evaluate).unwrap().unwrap();
+//!
+//! let mut data = json!({ "name": "Jane" }).as_object().unwrap().clone();
+//! merge_action_results(Some(&filter), &mut data, results).unwrap();
+//! assert_eq!(json!({ "name": "Jane", "finalGreeting": "Hello, Jane!" }), Value::Object(data));
+//! ```
+//!
+//! [State data filters]: crate::workflow::definition::StateDataFilter
+
+use serde_json::{Map, Value};
+
+use crate::detail::expression::simple_path;
+use crate::workflow::definition::{ActionDataFilter, EventDataFilter, State};
+use crate::workflow::runtime::{evaluate, ExpressionEvaluator};
+
+/// Applies the [input filter](crate::workflow::definition::StateDataFilter::input) of a state's data filter to the data
+/// passed to the state, and returns the state's data input.
+///
+/// Runtimes call this function when entering a state, before executing it. If the state has
+/// no input filter, `data` is returned unchanged.
+///
+/// # Errors
+///
+/// Any error returned by the evaluator, in addition to:
+///
+/// * [`InvalidExpressionResult`]: the filter did not evaluate to an object
+///
+/// [`InvalidExpressionResult`]: crate::Error::InvalidExpressionResult
+pub fn filter_state_input<X>(
+    state: &State,
+    data: Map<String, Value>,
+    evaluator: &X,
+) -> crate::Result<Map<String, Value>>
+where
+    X: ExpressionEvaluator + ?Sized,
+{
+    let filter = state.state_data_filter();
+    filter_state_data(filter.and_then(|filter| filter.input.as_deref()), data, evaluator)
+}
+
+/// Applies the [output filter](crate::workflow::definition::StateDataFilter::output) of a state's data filter to the state
+/// data, and returns the state's data output.
+///
+/// Runtimes call this function once a state has been executed, before transitioning to the next
+/// state or ending the workflow; the output is passed as data input to the next state. If the
+/// state has no output filter, `data` is returned unchanged.
+///
+/// # Errors
+///
+/// Any error returned by the evaluator, in addition to:
+///
+/// * [`InvalidExpressionResult`]: the filter did not evaluate to an object
+///
+/// [`InvalidExpressionResult`]: crate::Error::InvalidExpressionResult
+pub fn filter_state_output<X>(
+    state: &State,
+    data: Map<String, Value>,
+    evaluator: &X,
+) -> crate::Result<Map<String, Value>>
+where
+    X: ExpressionEvaluator + ?Sized,
+{
+    let filter = state.state_data_filter();
+    filter_state_data(filter.and_then(|filter| filter.output.as_deref()), data, evaluator)
+}
+
+/// Selects the input of an action from the state data, according to the action data filter's
+/// [`from_state_data`](ActionDataFilter::from_state_data) expression.
+///
+/// Function arguments that are workflow expressions are evaluated against this input. If there
+/// is no filter or it has no `from_state_data` expression, the whole state data is returned.
+///
+/// # Errors
+///
+/// Any error returned by the evaluator.
+pub fn filter_action_input<X>(
+    filter: Option<&ActionDataFilter>,
+    data: &Map<String, Value>,
+    evaluator: &X,
+) -> crate::Result<Value>
+where
+    X: ExpressionEvaluator + ?Sized,
+{
+    let data = Value::Object(data.clone());
+    match filter.and_then(|filter| filter.from_state_data.as_deref()) {
+        Some(from_state_data) => evaluate(evaluator, from_state_data, &data),
+        None => Ok(data),
+    }
+}
+
+/// Filters the results of an action according to the action data filter's
+/// [`results`](ActionDataFilter::results) expression.
+///
+/// Returns `None` if the filter's [`use_results`](ActionDataFilter::use_results) is `false`,
+/// in which case the results must not be merged into the state data. If there is no filter or
+/// it has no `results` expression, the results are returned unchanged.
+///
+/// # Errors
+///
+/// Any error returned by the evaluator.
+pub fn filter_action_results<X>(
+    filter: Option<&ActionDataFilter>,
+    results: Value,
+    evaluator: &X,
+) -> crate::Result<Option<Value>>
+where
+    X: ExpressionEvaluator + ?Sized,
+{
+    match filter {
+        Some(filter) if !filter.use_results => Ok(None),
+        Some(ActionDataFilter { results: Some(expression), .. }) => {
+            evaluate(evaluator, expression, &results).map(Some)
+        },
+        _ => Ok(Some(results)),
+    }
+}
+
+/// Merges [filtered](filter_action_results) action results into the state data element
+/// selected by the action data filter's [`to_state_data`](ActionDataFilter::to_state_data)
+/// expression, or into the top-level state data if there is no filter or it has no
+/// `to_state_data` expression.
+///
+/// # Errors
+///
+/// * [`StateDataMergeFailed`]: results could not be merged into the selected state data element
+///
+/// [`StateDataMergeFailed`]: crate::Error::StateDataMergeFailed
+pub fn merge_action_results(
+    filter: Option<&ActionDataFilter>,
+    data: &mut Map<String, Value>,
+    results: Value,
+) -> crate::Result<()> {
+    let to_state_data = filter.and_then(|filter| filter.to_state_data.as_deref());
+    merge_into_state_data(data, to_state_data, results)
+}
+
+/// Filters the payload of a consumed event and merges it into the state data, according to
+/// an event data filter.
+///
+/// * If the filter's [`use_data`](EventDataFilter::use_data) is `false`, the state data is left
+///   unchanged.
+/// * Otherwise, the payload is filtered by the filter's [`data`](EventDataFilter::data)
+///   expression, if any, then merged into the state data element selected by its
+///   [`to_state_data`](EventDataFilter::to_state_data) expression, or into the top-level state
+///   data if it has none.
+///
+/// Without a filter, the whole payload is merged into the top-level state data.
+///
+/// # Errors
+///
+/// Any error returned by the evaluator, in addition to:
+///
+/// * [`StateDataMergeFailed`]: the payload could not be merged into the selected state data
+///                             element
+///
+/// [`StateDataMergeFailed`]: crate::Error::StateDataMergeFailed
+pub fn merge_event_data<X>(
+    data: &mut Map<String, Value>,
+    filter: Option<&EventDataFilter>,
+    event: &Value,
+    evaluator: &X,
+) -> crate::Result<()>
+where
+    X: ExpressionEvaluator + ?Sized,
+{
+    let Some(filter) = filter else {
+        return merge_into_state_data(data, None, event.clone());
+    };
+    if !filter.use_data {
+        return Ok(());
+    }
+
+    let event = match &filter.data {
+        Some(expression) => evaluate(evaluator, expression, event)?,
+        None => event.clone(),
+    };
+    merge_into_state_data(data, filter.to_state_data.as_deref(), event)
+}
+
+/// Merges a value into the state data element selected by `to_state_data`, or into the
+/// top-level state data if `None`.
+///
+/// As described in the specification, objects are merged recursively and arrays keep the unique
+/// elements of both arrays: elements that are not already in the existing array are appended to
+/// it. Other values replace the existing element. Missing elements are created.
+///
+/// # Errors
+///
+/// * [`StateDataMergeFailed`]: `to_state_data` is not a simple path (e.g. `${ .a.b }`), one
+///                             of the selected element's parents is not an object, or a value
+///                             that is not an object is merged into the top-level state data
+///
+/// [`StateDataMergeFailed`]: crate::Error::StateDataMergeFailed
+pub fn merge_into_state_data(
+    data: &mut Map<String, Value>,
+    to_state_data: Option<&str>,
+    value: Value,
+) -> crate::Result<()> {
+    let element = to_state_data.unwrap_or("${ . }");
+    let error = |reason| crate::Error::StateDataMergeFailed { element: element.into(), reason };
+
+    let path = simple_path(element)
+        .ok_or_else(|| error("only simple paths (e.g. `${ .a.b }`) are supported"))?;
+    let Some((last, parents)) = path.split_last() else {
+        return match value {
+            Value::Object(value) => {
+                merge_object(data, value);
+                Ok(())
+            },
+            _ => Err(error("only objects can be merged into the top-level state data")),
+        };
+    };
+
+    let mut target = data;
+    for name in parents {
+        target = match target
+            .entry(*name)
+            .or_insert_with(|| Value::Object(Map::new()))
+        {
+            Value::Object(object) => object,
+            _ => return Err(error("a parent element is not an object")),
+        };
+    }
+    merge(target.entry(*last).or_insert(Value::Null), value);
+    Ok(())
+}
+
+// Merges the properties of an object into another.
+pub(crate) fn merge_object(target: &mut Map<String, Value>, value: Map<String, Value>) {
+    value
+        .into_iter()
+        .for_each(|(name, value)| merge(target.entry(name).or_insert(Value::Null), value));
+}
+
+// Merges a value into another: objects are merged recursively, elements of arrays are appended
+// unless already present and other values are replaced.
+fn merge(target: &mut Value, value: Value) {
+    match (target, value) {
+        (Value::Object(target), Value::Object(value)) => merge_object(target, value),
+        (Value::Array(target), Value::Array(value)) => {
+            for element in value {
+                if !target.contains(&element) {
+                    target.push(element);
+                }
+            }
+        },
+        (target, value) => *target = value,
+    }
+}
+
+// Applies a state data filter expression, which must evaluate to an object.
+fn filter_state_data<X>(
+    expression: Option<&str>,
+    data: Map<String, Value>,
+    evaluator: &X,
+) -> crate::Result<Map<String, Value>>
+where
+    X: ExpressionEvaluator + ?Sized,
+{
+    let Some(expression) = expression else {
+        return Ok(data);
+    };
+
+    match evaluate(evaluator, expression, &Value::Object(data))? {
+        Value::Object(data) => Ok(data),
+        _ => Err(crate::Error::InvalidExpressionResult {
+            expression: expression.into(),
+            expected: "an object",
+        }),
+    }
+}
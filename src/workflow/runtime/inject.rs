@@ -0,0 +1,46 @@
+//! Execution of [inject states](InjectState).
+
+use serde_json::{Map, Value};
+
+use crate::workflow::definition::InjectState;
+use crate::workflow::runtime::filters::merge_object;
+
+/// Execution of an [`InjectState`].
+///
+/// Inject states perform no action: [running](Self::run) them merges their static
+/// [data](InjectState::data) into the state data. Like for other states, runtimes apply the
+/// state's [data filter](InjectState::state_data_filter) around the execution (see
+/// [`filter_state_input`] and [`filter_state_output`]), which can be used to manipulate the
+/// injected data.
+///
+/// [`filter_state_input`]: crate::workflow::runtime::filters::filter_state_input
+/// [`filter_state_output`]: crate::workflow::runtime::filters::filter_state_output
+#[derive(Debug, Clone)]
+pub struct InjectStateExecution<'a> {
+    state: &'a InjectState,
+}
+
+impl<'a> InjectStateExecution<'a> {
+    /// Starts the execution of the given inject state.
+    pub fn new(state: &'a InjectState) -> Self {
+        Self { state }
+    }
+
+    /// Returns the executed inject state.
+    pub fn state(&self) -> &'a InjectState {
+        self.state
+    }
+
+    /// Merges the state's data into the state data and returns the resulting state data.
+    ///
+    /// Data is merged as described in [`merge_into_state_data`]: objects are merged
+    /// recursively, arrays keep the unique elements of both arrays and other values replace
+    /// existing ones.
+    ///
+    /// [`merge_into_state_data`]: crate::workflow::runtime::filters::merge_into_state_data
+    pub fn run(&self, mut data: Map<String, Value>) -> Map<String, Value> {
+        let injected = self.state.data.meta.clone().into_iter().collect();
+        merge_object(&mut data, injected);
+        data
+    }
+}
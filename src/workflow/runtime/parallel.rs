@@ -0,0 +1,320 @@
+//! Execution of [parallel states](ParallelState).
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::{Map, Value};
+
+use crate::clock::Clock;
+use crate::workflow::definition::timeouts::{ActionExecTimeout, BranchExecTimeout};
+use crate::workflow::definition::{Branch, CompletionType, ParallelState, WorkflowDefinition};
+use crate::workflow::executor::{ActionExecutor, InvocationContext};
+use crate::workflow::runtime::deadlines::{branch_action_exec_timeout, branch_exec_timeout};
+use crate::workflow::runtime::filters::{merge_action_results, merge_object};
+use crate::workflow::runtime::{perform_action, ExpressionEvaluator};
+
+/// Execution of a [`ParallelState`].
+///
+/// The state's branches are [run](Self::run) concurrently, each on its own thread. The state
+/// completes when all of its branches have completed, or, if its [completion type] is
+/// [`AtLeast`], as soon as [`num_completed`] branches have completed; the other branches are
+/// then cancelled.
+///
+/// [completion type]: ParallelState::completion_type
+/// [`AtLeast`]: CompletionType::AtLeast
+/// [`num_completed`]: ParallelState::num_completed
+#[derive(Debug, Clone)]
+pub struct ParallelStateExecution<'a> {
+    state: &'a ParallelState,
+}
+
+impl<'a> ParallelStateExecution<'a> {
+    /// Starts the execution of the given parallel state.
+    pub fn new(state: &'a ParallelState) -> Self {
+        Self { state }
+    }
+
+    /// Returns the executed parallel state.
+    pub fn state(&self) -> &'a ParallelState {
+        self.state
+    }
+
+    /// Returns the number of branches that must complete for the state to complete.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidCompletionCount`]: the state's completion type is [`AtLeast`], but
+    ///                               [`num_completed`] is missing, invalid or larger than
+    ///                               the number of branches
+    ///
+    /// [`InvalidCompletionCount`]: crate::Error::InvalidCompletionCount
+    /// [`AtLeast`]: CompletionType::AtLeast
+    /// [`num_completed`]: ParallelState::num_completed
+    pub fn required_branches(&self) -> crate::Result<usize> {
+        let branches = self.state.branches.len();
+        if self.state.completion_type == CompletionType::AllOf {
+            return Ok(branches);
+        }
+
+        let error = |reason: String| crate::Error::InvalidCompletionCount {
+            state: self.state.name.clone(),
+            reason,
+        };
+        let num_completed = self
+            .state
+            .num_completed
+            .as_ref()
+            .ok_or_else(|| error("numCompleted is required for completion type atLeast".into()))?
+            .value()
+            .map_err(|err| error(err.to_string()))?;
+        match usize::try_from(num_completed) {
+            Ok(required) if required <= branches => Ok(required),
+            _ => Err(error(format!(
+                "{} branches must complete, but the state only has {}",
+                num_completed, branches
+            ))),
+        }
+    }
+
+    /// Returns the execution timeout of the given branch, if any.
+    ///
+    /// The timeout is taken from the branch's timeouts, or else from the state's timeouts,
+    /// or else from the workflow's default timeouts.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidDuration`]: the timeout is not a valid ISO 8601 duration
+    /// * [`UnsupportedDuration`]: the timeout is calendar-dependent or too large
+    ///
+    /// [`InvalidDuration`]: crate::Error::InvalidDuration
+    /// [`UnsupportedDuration`]: crate::Error::UnsupportedDuration
+    pub fn branch_timeout(
+        &self,
+        definition: &WorkflowDefinition,
+        branch: &Branch,
+    ) -> crate::Result<Option<Duration>> {
+        branch_exec_timeout(definition, self.state, branch)
+            .map(BranchExecTimeout::as_duration)
+            .transpose()
+    }
+
+    /// Runs the state's branches concurrently and returns the resulting state data.
+    ///
+    /// Each branch performs its actions in sequence, on its own copy of `data`, as described in
+    /// [`EventStateExecution::run`]. Actions are called with a [deadline] derived from the
+    /// branch's [execution timeout](Self::branch_timeout) and the action execution timeout,
+    /// if any, as measured by the given [`Clock`]; a branch that does not complete in time
+    /// fails with a [`BranchTimedOut`] error.
+    ///
+    /// Once enough branches have completed (see [`required_branches`]), the other branches are
+    /// cancelled: they do not perform any more actions, and their results are discarded. Since
+    /// [`ActionExecutor`]s are blocking, this method only returns once the actions running when
+    /// the branches were cancelled have returned; executors should honor the deadline found in
+    /// the [call context](InvocationContext::deadline).
+    ///
+    /// The changes made to the state data by completed branches are then merged into it, in the
+    /// order the branches are defined: objects are merged recursively (so branches can update
+    /// different properties of the same object), elements added to arrays are appended and
+    /// other values are replaced, so if multiple branches change the same value, the last one
+    /// wins.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by a branch, if too many branches fail for the state to complete.
+    /// Errors are returned in the order they occur; in addition to the errors returned by
+    /// [`EventStateExecution::run`], they include:
+    ///
+    /// * [`BranchTimedOut`]: a branch did not complete before its execution timeout
+    /// * [`InvalidCompletionCount`]: the number of branches to complete is invalid (see
+    ///                               [`required_branches`])
+    /// * [`InvalidDuration`]: a timeout is not a valid ISO 8601 duration
+    /// * [`UnsupportedDuration`]: a timeout is calendar-dependent or too large
+    ///
+    /// [`EventStateExecution::run`]: crate::workflow::runtime::event::EventStateExecution::run
+    /// [deadline]: InvocationContext::deadline
+    /// [`required_branches`]: Self::required_branches
+    /// [`BranchTimedOut`]: crate::Error::BranchTimedOut
+    /// [`InvalidCompletionCount`]: crate::Error::InvalidCompletionCount
+    /// [`InvalidDuration`]: crate::Error::InvalidDuration
+    /// [`UnsupportedDuration`]: crate::Error::UnsupportedDuration
+    pub fn run<C, E, X>(
+        &self,
+        clock: &C,
+        definition: &WorkflowDefinition,
+        mut data: Map<String, Value>,
+        executor: &E,
+        evaluator: &X,
+    ) -> crate::Result<Map<String, Value>>
+    where
+        C: Clock + Sync + ?Sized,
+        E: ActionExecutor + Sync + ?Sized,
+        X: ExpressionEvaluator + Sync + ?Sized,
+    {
+        let required = self.required_branches()?;
+        let branches = &self.state.branches;
+        let timeouts = branches
+            .iter()
+            .map(|branch| {
+                Ok(BranchTimeouts {
+                    branch: self.branch_timeout(definition, branch)?,
+                    action: branch_action_exec_timeout(definition, branch)
+                        .map(ActionExecTimeout::as_duration)
+                        .transpose()?,
+                })
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let mut context = InvocationContext::new(definition);
+        context.state = Some(self.state.name.clone());
+
+        let cancelled = AtomicBool::new(false);
+        let mut outputs: Vec<Option<Map<String, Value>>> = vec![None; branches.len()];
+        let outcome = thread::scope(|scope| {
+            let (sender, receiver) = mpsc::channel();
+            for (index, (branch, timeouts)) in branches.iter().zip(timeouts).enumerate() {
+                let sender = sender.clone();
+                let (data, context, cancelled) = (&data, &context, &cancelled);
+                scope.spawn(move || {
+                    let run = BranchRun { clock, definition, state: self.state, branch, timeouts };
+                    let result = run.run(data.clone(), context, cancelled, executor, evaluator);
+                    // The receiver is gone if the state already completed or failed.
+                    let _ = sender.send((index, result));
+                });
+            }
+            drop(sender);
+
+            let (mut completed, mut failed) = (0, 0);
+            while completed < required {
+                let Ok((index, result)) = receiver.recv() else {
+                    break;
+                };
+                match result {
+                    Ok(Some(output)) => {
+                        outputs[index] = Some(output);
+                        completed += 1;
+                    },
+                    Ok(None) => (),
+                    Err(err) => {
+                        failed += 1;
+                        if branches.len() - failed < required {
+                            cancelled.store(true, Ordering::SeqCst);
+                            return Err(err);
+                        }
+                    },
+                }
+            }
+
+            cancelled.store(true, Ordering::SeqCst);
+            Ok(())
+        });
+        outcome?;
+
+        let original = data.clone();
+        for output in outputs.into_iter().flatten() {
+            merge_object(&mut data, branch_changes(&original, output));
+        }
+        Ok(data)
+    }
+}
+
+// Returns the properties of a branch's output that differ from the original state data,
+// recursing into objects so that unchanged nested properties are left out.
+fn branch_changes(original: &Map<String, Value>, output: Map<String, Value>) -> Map<String, Value> {
+    output
+        .into_iter()
+        .filter_map(|(name, value)| match (original.get(&name), value) {
+            (Some(original), value) if *original == value => None,
+            (Some(Value::Object(original)), Value::Object(value)) => {
+                Some((name, Value::Object(branch_changes(original, value))))
+            },
+            (_, value) => Some((name, value)),
+        })
+        .collect()
+}
+
+#[derive(Debug, Copy, Clone)]
+struct BranchTimeouts {
+    branch: Option<Duration>,
+    action: Option<Duration>,
+}
+
+// Execution of a single branch of a parallel state, on its own thread.
+struct BranchRun<'a, C: ?Sized> {
+    clock: &'a C,
+    definition: &'a WorkflowDefinition,
+    state: &'a ParallelState,
+    branch: &'a Branch,
+    timeouts: BranchTimeouts,
+}
+
+impl<C> BranchRun<'_, C>
+where
+    C: Clock + ?Sized,
+{
+    // Performs the branch's actions and returns the resulting data, or `None` if the branch
+    // was cancelled.
+    fn run<E, X>(
+        &self,
+        mut data: Map<String, Value>,
+        context: &InvocationContext,
+        cancelled: &AtomicBool,
+        executor: &E,
+        evaluator: &X,
+    ) -> crate::Result<Option<Map<String, Value>>>
+    where
+        E: ActionExecutor + ?Sized,
+        X: ExpressionEvaluator + ?Sized,
+    {
+        let deadline = self
+            .timeouts
+            .branch
+            .and_then(|timeout| self.clock.instant().checked_add(timeout));
+
+        for (index, action) in self.branch.actions.iter().enumerate() {
+            if cancelled.load(Ordering::SeqCst) {
+                return Ok(None);
+            }
+            self.check_deadline(deadline)?;
+
+            let action_deadline = self
+                .timeouts
+                .action
+                .and_then(|timeout| self.clock.instant().checked_add(timeout));
+            let mut context = context.clone();
+            context.deadline = match (deadline, action_deadline) {
+                (Some(deadline), Some(action_deadline)) => Some(deadline.min(action_deadline)),
+                (deadline, action_deadline) => deadline.or(action_deadline),
+            };
+
+            let results = perform_action(
+                self.definition,
+                action,
+                index,
+                &data,
+                &context,
+                executor,
+                evaluator,
+            )?;
+            self.check_deadline(deadline)?;
+            if let Some(results) = results {
+                merge_action_results(action.action_data_filter.as_ref(), &mut data, results)?;
+            }
+        }
+
+        Ok((!cancelled.load(Ordering::SeqCst)).then_some(data))
+    }
+
+    fn check_deadline(&self, deadline: Option<Instant>) -> crate::Result<()> {
+        match deadline {
+            Some(deadline) if self.clock.instant() >= deadline => {
+                Err(crate::Error::BranchTimedOut {
+                    state: self.state.name.clone(),
+                    branch: self.branch.name.clone(),
+                })
+            },
+            _ => Ok(()),
+        }
+    }
+}
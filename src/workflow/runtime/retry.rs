@@ -0,0 +1,199 @@
+//! Retries of failed actions.
+//!
+//! Whether an action is retried when it fails depends on the workflow's [`auto_retries`]
+//! property:
+//!
+//! * If `false` (the default), the action is only retried for the errors listed in its
+//!   [`retryable_errors`].
+//! * If `true`, the action is retried for all errors, except those listed in its
+//!   [`non_retryable_errors`].
+//!
+//! Errors are identified by the [workflow error] carried by the error returned by the
+//! [`ActionExecutor`], which is resolved to one of the workflow's error definitions by name or
+//! code (see [`WorkflowDefinition::error_def`]). Other errors are never retried.
+//!
+//! [`auto_retries`]: WorkflowDefinition::auto_retries
+//! [`retryable_errors`]: Action::retryable_errors
+//! [`non_retryable_errors`]: Action::non_retryable_errors
+//! [workflow error]: crate::Error::workflow_error
+//! [`ActionExecutor`]: crate::workflow::executor::ActionExecutor
+
+use std::time::Duration;
+
+use crate::workflow::definition::retries::RetryDef;
+use crate::workflow::definition::timeouts::parse_duration;
+use crate::workflow::definition::{Action, WorkflowDefinition};
+
+/// Retry policy of an [`Action`].
+///
+/// The policy determines whether the action should be retried when it fails, and how long to
+/// wait before retrying it, according to the action's retry strategy. The strategy is the
+/// [retry definition](RetryDef) referenced by the action's [`retry_ref`](Action::retry_ref),
+/// or else the runtime's [default strategy](Self::with_default_strategy), if any. Actions with
+/// no retry strategy are only attempted once.
+#[derive(Debug, Clone)]
+pub struct ActionRetries<'a> {
+    definition: &'a WorkflowDefinition,
+    action: &'a Action,
+    strategy: Option<&'a RetryDef>,
+}
+
+impl<'a> ActionRetries<'a> {
+    /// Returns the retry policy of the given action.
+    ///
+    /// # Errors
+    ///
+    /// * [`UnknownRetry`]: the action references a retry definition that is not defined in the
+    ///                     workflow definition
+    ///
+    /// [`UnknownRetry`]: crate::Error::UnknownRetry
+    pub fn new(definition: &'a WorkflowDefinition, action: &'a Action) -> crate::Result<Self> {
+        let strategy = action
+            .retry_ref
+            .as_deref()
+            .map(|retry_ref| {
+                definition
+                    .retry_def(retry_ref)
+                    .ok_or_else(|| crate::Error::UnknownRetry { retry: retry_ref.into() })
+            })
+            .transpose()?;
+
+        Ok(Self { definition, action, strategy })
+    }
+
+    /// Returns a copy of this policy using the given retry strategy if the action does not
+    /// reference one.
+    pub fn with_default_strategy(mut self, strategy: &'a RetryDef) -> Self {
+        if self.action.retry_ref.is_none() {
+            self.strategy = Some(strategy);
+        }
+        self
+    }
+
+    /// Returns the action's retry strategy, if any.
+    pub fn strategy(&self) -> Option<&'a RetryDef> {
+        self.strategy
+    }
+
+    /// Returns `true` if the action should be retried when it fails with the given
+    /// workflow error, provided it has attempts left.
+    ///
+    /// See the [module documentation](self) for details.
+    pub fn is_retryable(&self, error: &str) -> bool {
+        let name = self
+            .definition
+            .error_def(error)
+            .map_or(error, |def| def.name.as_str());
+        let listed = |errors: &Option<Vec<String>>| errors.iter().flatten().any(|e| e == name);
+
+        if self.definition.auto_retries {
+            !listed(&self.action.non_retryable_errors)
+        } else {
+            listed(&self.action.retryable_errors)
+        }
+    }
+
+    /// Returns the maximum number of times the action is attempted, including the first attempt.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidInt`]: the strategy's maximum number of attempts is not a valid number
+    ///
+    /// [`InvalidInt`]: crate::Error::InvalidInt
+    pub fn max_attempts(&self) -> crate::Result<u32> {
+        let Some(strategy) = self.strategy else {
+            return Ok(1);
+        };
+
+        let max_attempts = strategy.max_attempts.value()?;
+        Ok(u32::try_from(max_attempts).unwrap_or(u32::MAX).max(1))
+    }
+
+    /// Returns the delay to wait before the given retry (`1` for the first retry).
+    ///
+    /// The strategy's delay is multiplied by its multiplier and increased by its increment after
+    /// each retry, without exceeding its maximum delay. Jitter is not applied; runtimes can
+    /// randomize the delay according to the strategy's [jitter](RetryDef::jitter).
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidDuration`]: a duration of the strategy is not a valid ISO 8601 duration
+    /// * [`UnsupportedDuration`]: a duration of the strategy is calendar-dependent or too large
+    /// * [`InvalidFloat`]: the strategy's multiplier is not a valid number
+    ///
+    /// [`InvalidDuration`]: crate::Error::InvalidDuration
+    /// [`UnsupportedDuration`]: crate::Error::UnsupportedDuration
+    /// [`InvalidFloat`]: crate::Error::InvalidFloat
+    pub fn delay(&self, retry: u32) -> crate::Result<Duration> {
+        let Some(strategy) = self.strategy else {
+            return Ok(Duration::ZERO);
+        };
+
+        let duration = |duration: &Option<String>| duration.as_deref().map(parse_duration);
+        let max_delay = duration(&strategy.max_delay).transpose()?;
+        let increment = duration(&strategy.increment)
+            .transpose()?
+            .unwrap_or_default();
+        let multiplier = strategy
+            .multiplier
+            .as_ref()
+            .map(|multiplier| multiplier.value())
+            .transpose()?
+            .unwrap_or(1.0);
+
+        let mut delay = duration(&strategy.delay).transpose()?.unwrap_or_default();
+        for _ in 1..retry {
+            delay = Duration::try_from_secs_f64(delay.as_secs_f64() * multiplier)
+                .unwrap_or(Duration::MAX)
+                .saturating_add(increment);
+            if max_delay.is_some_and(|max_delay| delay >= max_delay) {
+                break;
+            }
+        }
+
+        Ok(max_delay.map_or(delay, |max_delay| delay.min(max_delay)))
+    }
+
+    /// Decides what to do after the given attempt at performing the action (starting at `1`,
+    /// like [`InvocationContext::attempt`]) failed with the given error.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by [`max_attempts`](Self::max_attempts) or [`delay`](Self::delay).
+    ///
+    /// [`InvocationContext::attempt`]: crate::workflow::executor::InvocationContext::attempt
+    pub fn decide(&self, error: &crate::Error, attempt: u32) -> crate::Result<RetryDecision> {
+        if !error
+            .workflow_error()
+            .is_some_and(|error| self.is_retryable(error))
+        {
+            return Ok(RetryDecision::NotRetryable);
+        }
+        if attempt >= self.max_attempts()? {
+            return Ok(RetryDecision::Exhausted);
+        }
+
+        Ok(RetryDecision::Retry { attempt: attempt + 1, delay: self.delay(attempt)? })
+    }
+}
+
+/// Decision taken by [`ActionRetries::decide`] after an action failed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RetryDecision {
+    /// The action should be attempted again after the given delay.
+    Retry {
+        /// Number of the next attempt
+        attempt: u32,
+
+        /// Delay to wait before the next attempt
+        delay: Duration,
+    },
+
+    /// The error is retryable, but the action has no attempts left.
+    Exhausted,
+
+    /// The error is not retryable; it should be handled by the state's [error handlers].
+    ///
+    /// [error handlers]: crate::workflow::definition::State::error_handler
+    NotRetryable,
+}
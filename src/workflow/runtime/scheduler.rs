@@ -0,0 +1,372 @@
+//! Scheduled creation of workflow instances.
+//!
+//! Workflow definitions can [schedule] the automatic creation of their
+//! instances, either at a [recurring interval](Schedule::recurring_interval) or according to a
+//! [cron definition], evaluated in the schedule's [timezone](Schedule::timezone).
+//! [`StartSchedule`] computes the upcoming start times of a schedule, while a [`Scheduler`] keeps
+//! track of the schedules of multiple workflow definitions and calls back the runtime each time
+//! an instance must be created, or when a schedule [expires](SchedulerEvent::Expired) so that
+//! operators can renew the workflow definition.
+//!
+//! The scheduler only tells when instances are due; creating them is up to the runtime. Note
+//! that for workflows starting with an event state, the specification states that the schedule
+//! defines when instances can be created upon receiving the start events.
+//!
+//! [schedule]: crate::workflow::definition::StartDef::schedule
+//! [cron definition]: crate::workflow::definition::CronDef
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
+use chrono_tz::Tz;
+
+use crate::clock::Clock;
+use crate::registry::WorkflowKey;
+use crate::workflow::definition::intervals::RecurringInterval;
+use crate::workflow::definition::{Schedule, WorkflowDefinition};
+
+/// Start times of a workflow definition's [schedule](Schedule).
+#[derive(Debug, Clone)]
+pub struct StartSchedule {
+    times: StartTimes,
+    timezone: Tz,
+}
+
+#[derive(Debug, Clone)]
+enum StartTimes {
+    Interval(RecurringInterval),
+    Cron { schedule: Box<cron::Schedule>, valid_until: Option<DateTime<FixedOffset>> },
+}
+
+impl StartSchedule {
+    /// Parses the given schedule.
+    ///
+    /// Returns `Ok(None)` if the schedule defines neither an interval nor a cron definition.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidRecurringInterval`]: the interval is not a valid ISO 8601 repeating interval
+    /// * [`InvalidCronExpression`]: the cron expression could not be parsed
+    /// * [`InvalidDateTime`]: the cron definition's end of validity is not a valid date and time
+    /// * [`InvalidTimezone`]: the timezone is not a known IANA time zone name
+    ///
+    /// [`InvalidRecurringInterval`]: crate::Error::InvalidRecurringInterval
+    /// [`InvalidCronExpression`]: crate::Error::InvalidCronExpression
+    /// [`InvalidDateTime`]: crate::Error::InvalidDateTime
+    /// [`InvalidTimezone`]: crate::Error::InvalidTimezone
+    pub fn new(schedule: &Schedule) -> crate::Result<Option<Self>> {
+        let timezone = schedule.timezone()?;
+        let times = match (schedule.recurring_interval()?, schedule) {
+            (Some(interval), _) => StartTimes::Interval(interval),
+            (None, Schedule::Complex { cron: Some(cron), .. }) => StartTimes::Cron {
+                schedule: Box::new(cron.schedule()?),
+                valid_until: cron.valid_until()?,
+            },
+            _ => return Ok(None),
+        };
+
+        Ok(Some(Self { times, timezone }))
+    }
+
+    /// Returns the start schedule of the given workflow definition, if it has one.
+    ///
+    /// # Errors
+    ///
+    /// See [`new`](Self::new).
+    pub fn for_definition(definition: &WorkflowDefinition) -> crate::Result<Option<Self>> {
+        match definition.start.as_ref().and_then(|start| start.schedule()) {
+            Some(schedule) => Self::new(schedule),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the timezone used to evaluate the schedule.
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    /// Returns the date and time when the schedule is no longer valid, if any.
+    ///
+    /// Only schedules using a [cron definition] with an [end of validity] expire.
+    ///
+    /// [cron definition]: crate::workflow::definition::CronDef
+    /// [end of validity]: crate::workflow::definition::CronDef::valid_until
+    pub fn valid_until(&self) -> Option<DateTime<Utc>> {
+        match &self.times {
+            StartTimes::Cron { valid_until, .. } => {
+                valid_until.map(|valid_until| valid_until.with_timezone(&Utc))
+            },
+            StartTimes::Interval(_) => None,
+        }
+    }
+
+    /// Returns the amount of time remaining before the schedule's [end of validity], as of `now`.
+    ///
+    /// * If the schedule does not have an end of validity, returns `None`.
+    /// * If the end of validity has been reached, returns a zero [`TimeDelta`].
+    ///
+    /// [end of validity]: Self::valid_until
+    pub fn remaining_validity(&self, now: &DateTime<Utc>) -> Option<TimeDelta> {
+        self.valid_until().map(|valid_until| {
+            valid_until
+                .signed_duration_since(now)
+                .max(TimeDelta::zero())
+        })
+    }
+
+    /// Returns the next (at most) `n` start times after the given date and time.
+    ///
+    /// See [`RecurringInterval::next_occurrences`] and [`CronDef::next_occurrences`] for details.
+    ///
+    /// [`CronDef::next_occurrences`]: crate::workflow::definition::CronDef::next_occurrences
+    pub fn next_start_times(&self, after: &DateTime<Utc>, n: usize) -> Vec<DateTime<Tz>> {
+        let after = after.with_timezone(&self.timezone);
+        match &self.times {
+            StartTimes::Interval(interval) => interval.next_occurrences(&after, n),
+            StartTimes::Cron { schedule, valid_until } => schedule
+                .after(&after)
+                .take_while(|start| match valid_until {
+                    Some(valid_until) => *start < *valid_until,
+                    None => true,
+                })
+                .take(n)
+                .collect(),
+        }
+    }
+
+    /// Returns the first start time after the given date and time, if any.
+    pub fn next_start_time(&self, after: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.next_start_times(after, 1)
+            .first()
+            .map(|start| start.with_timezone(&Utc))
+    }
+}
+
+/// Start of a workflow instance requested by a [`Scheduler`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledStart {
+    /// Key of the workflow definition to create an instance of
+    pub workflow: WorkflowKey,
+
+    /// Date and time at which the instance was scheduled to start
+    pub scheduled_at: DateTime<Utc>,
+}
+
+/// Expiry of a workflow definition's [start schedule](StartSchedule), reported by a [`Scheduler`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleExpiry {
+    /// Key of the workflow definition whose schedule expired
+    pub workflow: WorkflowKey,
+
+    /// Date and time at which the schedule stopped being valid
+    pub valid_until: DateTime<Utc>,
+}
+
+/// Event reported by a [`Scheduler`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchedulerEvent {
+    /// An instance of a workflow definition must be created.
+    Start(ScheduledStart),
+
+    /// The start schedule of a workflow definition reached its [end of validity]. The workflow
+    /// definition is unscheduled; it can be scheduled again once renewed.
+    ///
+    /// [end of validity]: StartSchedule::valid_until
+    Expired(ScheduleExpiry),
+}
+
+impl SchedulerEvent {
+    /// Returns the date and time at which the event occurs.
+    pub fn at(&self) -> DateTime<Utc> {
+        match self {
+            Self::Start(start) => start.scheduled_at,
+            Self::Expired(expiry) => expiry.valid_until,
+        }
+    }
+}
+
+/// Scheduler creating instances of workflow definitions according to their
+/// [start schedule](StartSchedule).
+///
+/// The current time is read from the scheduler's [`Clock`]; using a
+/// [`TestClock`](crate::clock::TestClock) allows testing scheduled workflows without waiting.
+#[derive(Debug)]
+pub struct Scheduler<C> {
+    clock: C,
+    workflows: BTreeMap<WorkflowKey, ScheduledWorkflow>,
+}
+
+#[derive(Debug, Clone)]
+struct ScheduledWorkflow {
+    schedule: StartSchedule,
+    next_start: Option<DateTime<Utc>>,
+}
+
+impl<C> Scheduler<C>
+where
+    C: Clock,
+{
+    /// Creates a scheduler using the given clock, without any scheduled workflow.
+    pub fn new(clock: C) -> Self {
+        Self { clock, workflows: BTreeMap::new() }
+    }
+
+    /// Returns the scheduler's clock.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Schedules the creation of instances of the given workflow definition, replacing any
+    /// previous schedule of the same workflow definition.
+    ///
+    /// The first instance is scheduled at the first start time after the current time. Returns
+    /// `false` if the workflow definition has no start schedule.
+    ///
+    /// # Errors
+    ///
+    /// * [`MissingIdentifier`]: the workflow definition has no identifier
+    ///
+    /// See [`StartSchedule::new`] for other errors.
+    ///
+    /// [`MissingIdentifier`]: crate::Error::MissingIdentifier
+    pub fn schedule(&mut self, definition: &WorkflowDefinition) -> crate::Result<bool> {
+        let key = WorkflowKey::for_definition(definition)?;
+        let Some(schedule) = StartSchedule::for_definition(definition)? else {
+            return Ok(false);
+        };
+
+        let next_start = schedule.next_start_time(&self.clock.now());
+        self.workflows
+            .insert(key, ScheduledWorkflow { schedule, next_start });
+        Ok(true)
+    }
+
+    /// Stops creating instances of the workflow definition with the given key.
+    ///
+    /// Returns `false` if the workflow definition was not scheduled.
+    pub fn unschedule(&mut self, workflow: &WorkflowKey) -> bool {
+        self.workflows.remove(workflow).is_some()
+    }
+
+    /// Returns the amount of time remaining before the [end of validity] of the start schedule
+    /// of the workflow definition with the given key, as of the current time.
+    ///
+    /// Returns `None` if the workflow definition is not scheduled or if its schedule does not
+    /// have an end of validity.
+    ///
+    /// [end of validity]: StartSchedule::valid_until
+    pub fn remaining_validity(&self, workflow: &WorkflowKey) -> Option<TimeDelta> {
+        self.workflows
+            .get(workflow)?
+            .schedule
+            .remaining_validity(&self.clock.now())
+    }
+
+    /// Returns the next (at most) `n` start times of the workflow definition with the given key.
+    pub fn upcoming(&self, workflow: &WorkflowKey, n: usize) -> Vec<DateTime<Utc>> {
+        let Some(ScheduledWorkflow { schedule, next_start: Some(next_start) }) =
+            self.workflows.get(workflow)
+        else {
+            return Vec::new();
+        };
+
+        let later = schedule
+            .next_start_times(next_start, n.saturating_sub(1))
+            .into_iter()
+            .map(|start| start.with_timezone(&Utc));
+        Some(*next_start).into_iter().chain(later).take(n).collect()
+    }
+
+    /// Returns the next instance start, across all scheduled workflow definitions, if any.
+    pub fn next_start(&self) -> Option<ScheduledStart> {
+        self.workflows
+            .iter()
+            .filter_map(|(workflow, scheduled)| {
+                scheduled
+                    .next_start
+                    .map(|scheduled_at| ScheduledStart { workflow: workflow.clone(), scheduled_at })
+            })
+            .min_by_key(|start| start.scheduled_at)
+    }
+
+    /// Calls `on_event` for each instance start or schedule expiry that is due at the current
+    /// time, in chronological order.
+    ///
+    /// Starts missed since the last call (e.g. because the runtime was stopped) are all
+    /// reported; their [scheduled time](ScheduledStart::scheduled_at) can be used to skip them.
+    /// A schedule's [expiry](SchedulerEvent::Expired) is reported once, after its last start;
+    /// the workflow definition is then unscheduled. Returns the number of events reported.
+    pub fn run_pending<F>(&mut self, mut on_event: F) -> usize
+    where
+        F: FnMut(SchedulerEvent),
+    {
+        let now = self.clock.now();
+        let mut count = 0;
+        while let Some(event) = self.next_event().filter(|event| event.at() <= now) {
+            match &event {
+                SchedulerEvent::Start(start) => {
+                    if let Some(scheduled) = self.workflows.get_mut(&start.workflow) {
+                        scheduled.next_start =
+                            scheduled.schedule.next_start_time(&start.scheduled_at);
+                    }
+                },
+                SchedulerEvent::Expired(expiry) => {
+                    self.workflows.remove(&expiry.workflow);
+                },
+            }
+            on_event(event);
+            count += 1;
+        }
+
+        count
+    }
+
+    /// Sleeps until each instance start or schedule expiry up to the given date and time,
+    /// calling `on_event` for each of them (see [`run_pending`](Self::run_pending)).
+    ///
+    /// Returns when the next event is after `until`, or when no event is left. Returns the
+    /// number of events reported.
+    pub fn run_until<F>(&mut self, until: &DateTime<Utc>, mut on_event: F) -> usize
+    where
+        F: FnMut(SchedulerEvent),
+    {
+        let mut count = 0;
+        while let Some(at) = self
+            .next_event()
+            .map(|event| event.at())
+            .filter(|at| at <= until)
+        {
+            let delay = at
+                .signed_duration_since(self.clock.now())
+                .to_std()
+                .unwrap_or(Duration::ZERO);
+            if !delay.is_zero() {
+                self.clock.sleep(delay);
+            }
+            count += self.run_pending(&mut on_event);
+        }
+
+        count
+    }
+
+    // Returns the next event across all scheduled workflow definitions. A schedule expires
+    // once it has no start left, since starts at or after its end of validity are not produced.
+    fn next_event(&self) -> Option<SchedulerEvent> {
+        self.workflows
+            .iter()
+            .filter_map(|(workflow, scheduled)| match scheduled.next_start {
+                Some(scheduled_at) => Some(SchedulerEvent::Start(ScheduledStart {
+                    workflow: workflow.clone(),
+                    scheduled_at,
+                })),
+                None => scheduled.schedule.valid_until().map(|valid_until| {
+                    SchedulerEvent::Expired(ScheduleExpiry {
+                        workflow: workflow.clone(),
+                        valid_until,
+                    })
+                }),
+            })
+            .min_by_key(SchedulerEvent::at)
+    }
+}
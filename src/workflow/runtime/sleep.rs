@@ -0,0 +1,120 @@
+//! Execution of sleep states and action sleeps.
+//!
+//! [Sleep states] suspend the workflow execution for a given duration, and actions can
+//! [sleep] before and/or after invoking their function or subflow. Waiting goes through a
+//! [`Clock`], so runtimes can sleep for real with the [`SystemClock`] and tests can sleep
+//! without waiting.
+//!
+//! Sleeps honor the execution path's [deadlines]: if a deadline passes before the end of the
+//! sleep, the execution only sleeps until that deadline, then fails with a [`TimedOut`] error.
+//!
+//! [Sleep states]: SleepState
+//! [sleep]: Action::sleep
+//! [`SystemClock`]: crate::clock::SystemClock
+//! [deadlines]: Deadlines
+//! [`TimedOut`]: crate::Error::TimedOut
+
+use std::time::Duration;
+
+use crate::clock::Clock;
+use crate::workflow::definition::{Action, Sleep, SleepState};
+use crate::workflow::runtime::deadlines::Deadlines;
+
+/// Sleeps for the duration of the given sleep state.
+///
+/// Returns the duration slept.
+///
+/// # Errors
+///
+/// * [`InvalidDuration`]: the duration is not a valid ISO 8601 duration
+/// * [`UnsupportedDuration`]: the duration is calendar-dependent or too large
+/// * [`TimedOut`]: a deadline passed before the end of the sleep
+///
+/// [`InvalidDuration`]: crate::Error::InvalidDuration
+/// [`UnsupportedDuration`]: crate::Error::UnsupportedDuration
+/// [`TimedOut`]: crate::Error::TimedOut
+pub fn sleep_state<C>(
+    clock: &C,
+    state: &SleepState,
+    deadlines: &Deadlines,
+) -> crate::Result<Duration>
+where
+    C: Clock + ?Sized,
+{
+    wait(clock, state.sleep_duration()?, deadlines)
+}
+
+/// Sleeps before invoking the function or subflow of the given action, if specified.
+///
+/// Sleeps do not apply to actions that [reference an event](Action::event_ref). Returns the
+/// duration slept, if any.
+///
+/// # Errors
+///
+/// See [`sleep_state`].
+pub fn sleep_before_action<C>(
+    clock: &C,
+    action: &Action,
+    deadlines: &Deadlines,
+) -> crate::Result<Option<Duration>>
+where
+    C: Clock + ?Sized,
+{
+    let Some(sleep) = action_sleep(action) else {
+        return Ok(None);
+    };
+
+    sleep
+        .before_as_duration()?
+        .map(|duration| wait(clock, duration, deadlines))
+        .transpose()
+}
+
+/// Sleeps after invoking the function or subflow of the given action, if specified.
+///
+/// Sleeps do not apply to actions that [reference an event](Action::event_ref). Returns the
+/// duration slept, if any.
+///
+/// # Errors
+///
+/// See [`sleep_state`].
+pub fn sleep_after_action<C>(
+    clock: &C,
+    action: &Action,
+    deadlines: &Deadlines,
+) -> crate::Result<Option<Duration>>
+where
+    C: Clock + ?Sized,
+{
+    let Some(sleep) = action_sleep(action) else {
+        return Ok(None);
+    };
+
+    sleep
+        .after_as_duration()?
+        .map(|duration| wait(clock, duration, deadlines))
+        .transpose()
+}
+
+fn action_sleep(action: &Action) -> Option<&Sleep> {
+    match action.event_ref {
+        Some(_) => None,
+        None => action.sleep.as_ref(),
+    }
+}
+
+// Sleeps for the given duration, or until the next deadline if it comes first.
+fn wait<C>(clock: &C, duration: Duration, deadlines: &Deadlines) -> crate::Result<Duration>
+where
+    C: Clock + ?Sized,
+{
+    let now = clock.instant();
+    let duration = match deadlines.next() {
+        Some(deadline) => duration.min(deadline.at.saturating_duration_since(now)),
+        None => duration,
+    };
+
+    clock.sleep(duration);
+    deadlines.check(clock.instant())?;
+    Ok(duration)
+}
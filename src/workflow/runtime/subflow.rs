@@ -0,0 +1,115 @@
+//! Invocation of [sub-workflows](SubflowRef).
+
+use std::rc::Rc;
+
+use serde_json::{Map, Value};
+
+use crate::registry::WorkflowRegistry;
+use crate::workflow::definition::{SubflowRef, WorkflowDefinition};
+use crate::workflow::instance::WorkflowInstance;
+use crate::workflow::runtime::DEFAULT_MAX_DEPTH;
+
+/// Invocation of a [`SubflowRef`], found in an [action](crate::workflow::definition::Action::sub_flow_ref).
+///
+/// When an action invokes a sub-workflow, runtimes [run](Self::run) this invocation to create
+/// the sub-workflow instance, then execute it like any other instance.
+///
+/// Workflows invoking themselves (directly or through other workflows, see
+/// [`WorkflowRegistry::recursive_workflows`]) could recurse indefinitely; the depth of nested
+/// invocations is therefore limited to a [maximum depth](Self::with_max_depth).
+#[derive(Debug, Clone)]
+pub struct SubflowInvocation<'a> {
+    subflow_ref: &'a SubflowRef,
+    max_depth: u32,
+}
+
+/// Sub-workflow instance, returned by [`SubflowInvocation::run`].
+#[derive(Debug, Clone)]
+pub struct InvokedSubflow {
+    /// Definition of the invoked sub-workflow
+    pub definition: Rc<WorkflowDefinition>,
+
+    /// New sub-workflow instance, starting at the sub-workflow's start state
+    pub instance: WorkflowInstance,
+}
+
+impl<'a> SubflowInvocation<'a> {
+    /// Starts the invocation of the given sub-workflow reference.
+    ///
+    /// The depth of nested invocations is limited to [`DEFAULT_MAX_DEPTH`].
+    pub fn new(subflow_ref: &'a SubflowRef) -> Self {
+        Self { subflow_ref, max_depth: DEFAULT_MAX_DEPTH }
+    }
+
+    /// Returns a copy of this invocation limiting the depth of sub-workflow instances to
+    /// `max_depth`.
+    ///
+    /// Each sub-workflow instance's [depth] is one more than the instance invoking it;
+    /// [`run`](Self::run) fails if it would exceed `max_depth`.
+    ///
+    /// [depth]: WorkflowInstance::depth
+    pub fn with_max_depth(self, max_depth: u32) -> Self {
+        Self { max_depth, ..self }
+    }
+
+    /// Returns the invoked sub-workflow reference.
+    pub fn subflow_ref(&self) -> &'a SubflowRef {
+        self.subflow_ref
+    }
+
+    /// Returns the maximum depth of sub-workflow instances.
+    pub fn max_depth(&self) -> u32 {
+        self.max_depth
+    }
+
+    /// Creates a new instance of the sub-workflow, invoked by the given instance.
+    ///
+    /// The sub-workflow is [resolved](WorkflowRegistry::resolve_reference) in the given registry.
+    /// The new instance starts at the sub-workflow's start state, with the given `input` (usually
+    /// the [filtered action input](crate::workflow::runtime::filters::filter_action_input)). It
+    /// keeps the instance's labels, and its [depth](WorkflowInstance::depth) is one more than
+    /// `instance`'s.
+    ///
+    /// # Errors
+    ///
+    /// * [`RecursionLimitExceeded`]: the new instance would exceed the
+    ///                               [maximum depth](Self::with_max_depth)
+    /// * [`MissingWorkflowDependency`]: the sub-workflow is not registered
+    /// * [`AmbiguousWorkflowReference`]: the reference does not specify a version and multiple
+    ///                                   versions of the sub-workflow are registered
+    ///
+    /// [`RecursionLimitExceeded`]: crate::Error::RecursionLimitExceeded
+    /// [`MissingWorkflowDependency`]: crate::Error::MissingWorkflowDependency
+    /// [`AmbiguousWorkflowReference`]: crate::Error::AmbiguousWorkflowReference
+    pub fn run(
+        &self,
+        definition: &WorkflowDefinition,
+        instance: &WorkflowInstance,
+        registry: &WorkflowRegistry,
+        input: Map<String, Value>,
+    ) -> crate::Result<InvokedSubflow> {
+        let depth = instance.depth.saturating_add(1);
+        if depth > self.max_depth {
+            return Err(crate::Error::RecursionLimitExceeded {
+                instance: instance.id.clone(),
+                workflow: self.subflow_ref.workflow_id().into(),
+                limit: self.max_depth,
+            });
+        }
+
+        let subflow = registry
+            .resolve_reference(
+                definition,
+                self.subflow_ref.workflow_id(),
+                self.subflow_ref.version(),
+            )?
+            .definition()
+            .clone();
+
+        let mut subflow_instance = WorkflowInstance::for_definition(&subflow, Some(input));
+        subflow_instance.labels = instance.labels.clone();
+        subflow_instance.depth = depth;
+
+        Ok(InvokedSubflow { definition: subflow, instance: subflow_instance })
+    }
+}
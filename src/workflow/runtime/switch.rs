@@ -0,0 +1,146 @@
+//! Execution of [event-based switch states](EventBasedSwitchState).
+
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+
+use crate::workflow::definition::{
+    EventBasedSwitchState, EventCondition, SwitchSelection, WorkflowDefinition,
+};
+use crate::workflow::runtime::filters::merge_event_data;
+use crate::workflow::runtime::ExpressionEvaluator;
+use crate::workflow::subscription::event_expiry;
+
+/// Execution of an [`EventBasedSwitchState`].
+///
+/// Runtimes [consume](Self::consume) the events referenced by the state's
+/// [event conditions](EventBasedSwitchState::event_conditions) as they arrive; the first one
+/// selects its condition. If no event arrives before the state's event timeout (or else the
+/// workflow's default event timeout), the execution [times out](Self::time_out) and selects the
+/// state's [default condition](EventBasedSwitchState::default_condition).
+///
+/// Once a condition is [selected](Self::selection), the execution can be [run](Self::run) to
+/// merge the consumed event's data into the state data before transitioning.
+#[derive(Debug, Clone)]
+pub struct EventSwitchExecution<'a> {
+    state: &'a EventBasedSwitchState,
+    expires_at: Option<DateTime<Utc>>,
+    selection: Option<SwitchSelection>,
+    event: Option<Value>,
+}
+
+impl<'a> EventSwitchExecution<'a> {
+    /// Starts the execution of the given event-based switch state at the given instant.
+    ///
+    /// # Errors
+    ///
+    /// * [`InvalidDuration`]: the event timeout is not a valid ISO 8601 duration
+    /// * [`UnsupportedDuration`]: the event timeout is calendar-dependent or too large
+    ///
+    /// [`InvalidDuration`]: crate::Error::InvalidDuration
+    /// [`UnsupportedDuration`]: crate::Error::UnsupportedDuration
+    pub fn new(
+        definition: &WorkflowDefinition,
+        state: &'a EventBasedSwitchState,
+        now: DateTime<Utc>,
+    ) -> crate::Result<Self> {
+        let event_timeout = state
+            .timeouts
+            .as_ref()
+            .and_then(|timeouts| timeouts.event_timeout.as_ref());
+        let expires_at = event_expiry(definition, event_timeout, now)?;
+
+        Ok(Self { state, expires_at, selection: None, event: None })
+    }
+
+    /// Returns the executed switch state.
+    pub fn state(&self) -> &'a EventBasedSwitchState {
+        self.state
+    }
+
+    /// Returns the instant at which the execution [times out](Self::time_out), or `None`
+    /// if it waits for events forever.
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    /// Consumes an event, identified by the name of its event definition.
+    ///
+    /// `data` is the part of the event accessible to the workflow (see
+    /// [`EventDef::consumed_event_data`]). If multiple conditions reference the event, the first
+    /// one defined is selected (see [`EventBasedSwitchState::select`]).
+    ///
+    /// Returns the selected condition, or `None` if no condition references the event or
+    /// a condition was already selected.
+    ///
+    /// [`EventDef::consumed_event_data`]: crate::workflow::definition::events::EventDef::consumed_event_data
+    pub fn consume(&mut self, event: &str, data: Value) -> Option<SwitchSelection> {
+        if self.selection.is_some() {
+            return None;
+        }
+
+        self.selection = self.state.select(event);
+        if self.selection.is_some() {
+            self.event = Some(data);
+        }
+        self.selection
+    }
+
+    /// Selects the default condition if the execution has expired at the given instant and
+    /// no condition was selected yet.
+    ///
+    /// Returns [`SwitchSelection::Default`] if the execution timed out, or `None` otherwise.
+    pub fn time_out(&mut self, now: &DateTime<Utc>) -> Option<SwitchSelection> {
+        let expired = self.expires_at.is_some_and(|expires_at| expires_at <= *now);
+        if self.selection.is_some() || !expired {
+            return None;
+        }
+
+        self.selection = Some(SwitchSelection::Default);
+        self.selection
+    }
+
+    /// Returns the selected condition, if any.
+    pub fn selection(&self) -> Option<SwitchSelection> {
+        self.selection
+    }
+
+    /// Returns the event condition selected by a consumed event, if any.
+    ///
+    /// Returns `None` if no condition was selected yet, or if the default condition was selected.
+    pub fn selected_condition(&self) -> Option<&'a EventCondition> {
+        match self.selection {
+            Some(SwitchSelection::Condition(index)) => self.state.event_conditions.get(index),
+            _ => None,
+        }
+    }
+
+    /// Merges the data of the consumed event into the state data according to the selected
+    /// condition's [event data filter], and returns the resulting state data.
+    ///
+    /// Data is merged as described in [`EventStateExecution::run`]. If the default condition
+    /// was selected or no condition was selected yet, `data` is returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by the evaluator, in addition to:
+    ///
+    /// * [`StateDataMergeFailed`]: data could not be merged into the selected state data element
+    ///
+    /// [event data filter]: EventCondition::event_data_filter
+    /// [`EventStateExecution::run`]: crate::workflow::runtime::event::EventStateExecution::run
+    /// [`StateDataMergeFailed`]: crate::Error::StateDataMergeFailed
+    pub fn run<X>(
+        &self,
+        mut data: Map<String, Value>,
+        evaluator: &X,
+    ) -> crate::Result<Map<String, Value>>
+    where
+        X: ExpressionEvaluator + ?Sized,
+    {
+        if let (Some(condition), Some(event)) = (self.selected_condition(), &self.event) {
+            merge_event_data(&mut data, condition.event_data_filter(), event, evaluator)?;
+        }
+
+        Ok(data)
+    }
+}
@@ -0,0 +1,553 @@
+//! Monte Carlo simulation of workflow executions.
+//!
+//! A [`Simulation`] walks the states of a workflow definition many times, using probabilities
+//! and durations provided by the caller to decide which branches are taken, how long events take
+//! to arrive and how often functions fail. The resulting [`SimulationReport`] can help with
+//! capacity planning before a workflow goes live.
+//!
+//! Simulations do not evaluate expressions nor invoke any function: they only rely on the
+//! parameters provided.
+
+use std::collections::HashMap;
+
+use chrono::TimeDelta;
+
+use crate::detail::rng::Rng;
+use crate::workflow::definition::common::ExecutionMode;
+use crate::workflow::definition::intervals::IsoDuration;
+use crate::workflow::definition::retries::RetryDef;
+use crate::workflow::definition::timeouts::EventTimeout;
+use crate::workflow::definition::{
+    Action, DataCondition, DefaultConditionDef, EventCondition, FunctionRef, State, SwitchState,
+    Transition, WorkflowDefinition,
+};
+use crate::workflow::graph::StateGraph;
+
+/// Monte Carlo simulation of the executions of a [`WorkflowDefinition`].
+///
+/// Executions are simulated as follows:
+///
+/// * Switch states pick a condition at random according to the [condition probabilities]
+///   of the state; the default condition is picked with the remaining probability.
+/// * Events take a random amount of time to arrive, following an exponential distribution with
+///   the [mean event delay] of the event. Events with no configured delay arrive immediately.
+/// * Function calls take their configured [function duration] and fail according to the
+///   function's [failure probability]. Failed actions are retried according to their
+///   `retryRef`; if all attempts fail, the state's first error handler is used, or the
+///   execution fails if the state has none.
+/// * `foreach` states are simulated as a single iteration.
+///
+/// Simulations are deterministic for a given [seed].
+///
+/// [condition probabilities]: Self::condition_probabilities
+/// [mean event delay]: Self::event_delay
+/// [function duration]: Self::function_duration
+/// [failure probability]: Self::failure_probability
+/// [seed]: Self::seed
+#[derive(Debug, Clone)]
+pub struct Simulation<'a> {
+    definition: &'a WorkflowDefinition,
+    graph: StateGraph<'a>,
+    condition_probabilities: HashMap<String, Vec<f64>>,
+    event_delays: HashMap<String, TimeDelta>,
+    function_durations: HashMap<String, TimeDelta>,
+    failure_probabilities: HashMap<String, f64>,
+    max_transitions: usize,
+    seed: u64,
+}
+
+impl<'a> Simulation<'a> {
+    /// Creates a simulation of the given workflow definition.
+    ///
+    /// By default, each execution is aborted after 1000 transitions and the seed is `0`.
+    pub fn new(definition: &'a WorkflowDefinition) -> Self {
+        Self {
+            definition,
+            graph: StateGraph::new(definition),
+            condition_probabilities: HashMap::new(),
+            event_delays: HashMap::new(),
+            function_durations: HashMap::new(),
+            failure_probabilities: HashMap::new(),
+            max_transitions: 1000,
+            seed: 0,
+        }
+    }
+
+    /// Sets the probabilities of the data or event conditions of the switch state with the given name.
+    ///
+    /// Probabilities are given in the order the conditions are defined in the state. The default
+    /// condition is picked with the remaining probability (if the probabilities add up to
+    /// more than `1`, the last conditions are picked less often than specified).
+    /// If no probabilities are set for a switch state, its default condition is always picked.
+    pub fn condition_probabilities<S, I>(mut self, state: S, probabilities: I) -> Self
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = f64>,
+    {
+        self.condition_probabilities
+            .insert(state.into(), probabilities.into_iter().collect());
+        self
+    }
+
+    /// Sets the mean delay before the event with the given name arrives.
+    pub fn event_delay<S>(mut self, event: S, mean: TimeDelta) -> Self
+    where
+        S: Into<String>,
+    {
+        self.event_delays.insert(event.into(), mean);
+        self
+    }
+
+    /// Sets the duration of each call to the function with the given name.
+    pub fn function_duration<S>(mut self, function: S, duration: TimeDelta) -> Self
+    where
+        S: Into<String>,
+    {
+        self.function_durations.insert(function.into(), duration);
+        self
+    }
+
+    /// Sets the probability that a call to the function with the given name fails.
+    pub fn failure_probability<S>(mut self, function: S, probability: f64) -> Self
+    where
+        S: Into<String>,
+    {
+        self.failure_probabilities
+            .insert(function.into(), probability);
+        self
+    }
+
+    /// Sets the maximum number of transitions in a simulated execution, after which
+    /// the execution is aborted.
+    ///
+    /// This prevents simulating workflows that loop forever.
+    pub fn max_transitions(self, max_transitions: usize) -> Self {
+        Self { max_transitions, ..self }
+    }
+
+    /// Sets the seed used to generate random numbers.
+    pub fn seed(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+
+    /// Simulates the given number of workflow executions.
+    pub fn run(&self, executions: usize) -> SimulationReport {
+        let mut rng = Rng(self.seed);
+        let mut report = SimulationReport::default();
+        for _ in 0..executions {
+            self.execute(&mut rng, &mut report);
+        }
+
+        report
+    }
+
+    fn execute(&self, rng: &mut Rng, report: &mut SimulationReport) {
+        let mut elapsed = TimeDelta::zero();
+        let mut current = self.graph.start().and_then(|name| self.graph.state(name));
+        let mut transitions = 0;
+
+        let outcome = loop {
+            let Some(state) = current else {
+                break Outcome::Aborted;
+            };
+            *report.state_visits.entry(state.name().into()).or_default() += 1;
+
+            match self.state(state, rng, &mut elapsed, report) {
+                Step::Transition(_) if transitions == self.max_transitions => {
+                    break Outcome::Aborted
+                },
+                Step::Transition(next_state) => {
+                    transitions += 1;
+                    current = self.graph.state(next_state);
+                },
+                Step::End => break Outcome::Completed,
+                Step::Failed => break Outcome::Failed,
+            }
+        };
+
+        report.executions += 1;
+        match outcome {
+            Outcome::Completed => report.completed += 1,
+            Outcome::Failed => report.failed += 1,
+            Outcome::Aborted => report.aborted += 1,
+        }
+        report.total_duration += elapsed;
+        report.max_duration = report.max_duration.max(elapsed);
+    }
+
+    fn state(
+        &self,
+        state: &'a State,
+        rng: &mut Rng,
+        elapsed: &mut TimeDelta,
+        report: &mut SimulationReport,
+    ) -> Step<'a> {
+        let (duration, transition, on_errors) = match state {
+            State::Sleep(state) => {
+                (Some(iso_duration(&state.duration)), &state.transition, &state.on_errors)
+            },
+            State::Event(state) => {
+                let durations: Vec<_> = state
+                    .on_events
+                    .iter()
+                    .map(|on_events| {
+                        let delay = on_events
+                            .event_refs
+                            .iter()
+                            .map(|event| self.event(event, rng))
+                            .max()
+                            .unwrap_or_default();
+                        let actions = on_events.actions.as_deref().unwrap_or_default();
+                        self.actions(actions, on_events.action_mode, rng, report)
+                            .map(|duration| delay + duration)
+                    })
+                    .collect();
+                let duration = if state.exclusive {
+                    durations
+                        .into_iter()
+                        .min_by_key(|duration| duration.unwrap_or(TimeDelta::MAX))
+                        .unwrap_or(Some(TimeDelta::zero()))
+                } else {
+                    longest(durations)
+                };
+
+                (duration, &state.transition, &state.on_errors)
+            },
+            State::Operation(state) => (
+                self.actions(&state.actions, state.action_mode, rng, report),
+                &state.transition,
+                &state.on_errors,
+            ),
+            State::Parallel(state) => {
+                let durations = state
+                    .branches
+                    .iter()
+                    .map(|branch| {
+                        self.actions(&branch.actions, ExecutionMode::Sequential, rng, report)
+                    })
+                    .collect();
+
+                (longest(durations), &state.transition, &state.on_errors)
+            },
+            State::Switch(SwitchState::DataBased(state)) => {
+                let condition =
+                    self.condition(&state.name, state.data_conditions.len(), rng, report);
+                return match condition.map(|index| &state.data_conditions[index]) {
+                    Some(DataCondition::Transition(condition)) => {
+                        Step::Transition(condition.transition.next_state())
+                    },
+                    Some(DataCondition::End(_)) => Step::End,
+                    None => default_condition(&state.default_condition),
+                };
+            },
+            State::Switch(SwitchState::EventBased(state)) => {
+                let condition =
+                    self.condition(&state.name, state.event_conditions.len(), rng, report);
+                return match condition.map(|index| &state.event_conditions[index]) {
+                    Some(EventCondition::Transition(condition)) => {
+                        *elapsed += self.event(&condition.event_ref, rng);
+                        Step::Transition(condition.transition.next_state())
+                    },
+                    Some(EventCondition::End(condition)) => {
+                        *elapsed += self.event(&condition.event_ref, rng);
+                        Step::End
+                    },
+                    None => {
+                        let event_timeout = state
+                            .timeouts
+                            .as_ref()
+                            .and_then(|timeouts| timeouts.event_timeout.as_ref());
+                        *elapsed += event_timeout
+                            .map(|EventTimeout(timeout)| iso_duration(timeout))
+                            .unwrap_or_default();
+                        default_condition(&state.default_condition)
+                    },
+                };
+            },
+            State::Inject(state) => (Some(TimeDelta::zero()), &state.transition, &None),
+            State::ForEach(state) => (
+                self.actions(&state.actions, state.mode, rng, report),
+                &state.transition,
+                &state.on_errors,
+            ),
+            State::Callback(state) => {
+                let duration = self
+                    .action(&state.action, rng, report)
+                    .map(|duration| duration + self.event(&state.event_ref, rng));
+
+                (duration, &state.transition, &state.on_errors)
+            },
+            State::Custom(state) => (Some(TimeDelta::zero()), &state.transition, &None),
+        };
+
+        match duration {
+            Some(duration) => {
+                *elapsed += duration;
+                outcome(transition)
+            },
+            None => on_errors
+                .iter()
+                .flatten()
+                .next()
+                .map_or(Step::Failed, |error| outcome(&error.transition)),
+        }
+    }
+
+    // Returns the index of the condition to pick, or `None` to pick the default condition.
+    fn condition(
+        &self,
+        state: &str,
+        conditions: usize,
+        rng: &mut Rng,
+        report: &mut SimulationReport,
+    ) -> Option<usize> {
+        let probabilities = self
+            .condition_probabilities
+            .get(state)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let draw = rng.next_f64();
+        let mut cumulative = 0.0;
+        let condition = probabilities
+            .iter()
+            .take(conditions)
+            .position(|probability| {
+                cumulative += probability;
+                draw < cumulative
+            });
+
+        let branches = report
+            .branches
+            .entry(state.into())
+            .or_insert_with(|| vec![0; conditions + 1]);
+        branches[condition.unwrap_or(conditions)] += 1;
+
+        condition
+    }
+
+    // Returns the duration of the actions, or `None` if one of them failed.
+    fn actions(
+        &self,
+        actions: &[Action],
+        mode: ExecutionMode,
+        rng: &mut Rng,
+        report: &mut SimulationReport,
+    ) -> Option<TimeDelta> {
+        match mode {
+            ExecutionMode::Sequential => {
+                actions.iter().try_fold(TimeDelta::zero(), |total, action| {
+                    Some(total + self.action(action, rng, report)?)
+                })
+            },
+            ExecutionMode::Parallel => longest(
+                actions
+                    .iter()
+                    .map(|action| self.action(action, rng, report))
+                    .collect(),
+            ),
+        }
+    }
+
+    // Returns the duration of the action, or `None` if it failed.
+    fn action(
+        &self,
+        action: &Action,
+        rng: &mut Rng,
+        report: &mut SimulationReport,
+    ) -> Option<TimeDelta> {
+        let sleep = action
+            .sleep
+            .iter()
+            .flat_map(|sleep| [sleep.before(), sleep.after()])
+            .flatten()
+            .map(iso_duration)
+            .sum::<TimeDelta>();
+
+        let Some(function) = action.function_ref.as_ref().map(FunctionRef::ref_name) else {
+            let result = action
+                .event_ref
+                .as_ref()
+                .map(|event_ref| self.event(&event_ref.result_event_ref, rng))
+                .unwrap_or_default();
+            return Some(sleep + result);
+        };
+
+        let duration = self
+            .function_durations
+            .get(function)
+            .copied()
+            .unwrap_or_default();
+        let failure_probability = self
+            .failure_probabilities
+            .get(function)
+            .copied()
+            .unwrap_or_default();
+        let retry = action
+            .retry_ref
+            .as_deref()
+            .and_then(|name| self.definition.retry_def(name));
+        let max_attempts = retry
+            .and_then(|retry| retry.max_attempts.value().ok())
+            .unwrap_or(1)
+            .max(1);
+
+        let mut total = sleep;
+        let mut delay = retry
+            .and_then(|retry| retry.delay.as_deref())
+            .map(iso_duration)
+            .unwrap_or_default();
+        for attempt in 1..=max_attempts {
+            total += duration;
+            if !rng.chance(failure_probability) {
+                return Some(total);
+            }
+
+            if attempt < max_attempts {
+                *report.retries.entry(function.into()).or_default() += 1;
+                total += delay;
+                delay = retry.map_or(delay, |retry| next_delay(retry, delay));
+            }
+        }
+
+        None
+    }
+
+    fn event(&self, event: &str, rng: &mut Rng) -> TimeDelta {
+        self.event_delays
+            .get(event)
+            .map(|mean| rng.exponential(*mean))
+            .unwrap_or_default()
+    }
+}
+
+/// Results of a [`Simulation`].
+#[derive(Debug, Clone, Default)]
+pub struct SimulationReport {
+    /// Number of simulated executions
+    pub executions: usize,
+
+    /// Number of executions that reached an end
+    pub completed: usize,
+
+    /// Number of executions that failed because of an unhandled function failure
+    pub failed: usize,
+
+    /// Number of executions that were aborted, either because they reached the maximum
+    /// number of transitions or because they transitioned to a state that does not exist
+    pub aborted: usize,
+
+    /// Number of times each state was visited, by state name
+    pub state_visits: HashMap<String, usize>,
+
+    /// Number of times each condition of the switch states was picked, by state name
+    ///
+    /// Conditions are in the order they are defined in the state; the last element
+    /// is the number of times the default condition was picked.
+    pub branches: HashMap<String, Vec<usize>>,
+
+    /// Number of retries of each function, by function name
+    pub retries: HashMap<String, usize>,
+
+    /// Total duration of all executions
+    pub total_duration: TimeDelta,
+
+    /// Duration of the longest execution
+    pub max_duration: TimeDelta,
+}
+
+impl SimulationReport {
+    /// Returns the frequencies at which the conditions of the switch state with the given name
+    /// were picked, with the default condition last.
+    ///
+    /// Returns `None` if the state was never visited.
+    pub fn branch_frequencies(&self, state: &str) -> Option<Vec<f64>> {
+        let branches = self.branches.get(state)?;
+        let total: usize = branches.iter().sum();
+
+        Some(
+            branches
+                .iter()
+                .map(|&count| count as f64 / total as f64)
+                .collect(),
+        )
+    }
+
+    /// Returns the average duration of an execution.
+    pub fn expected_duration(&self) -> TimeDelta {
+        i32::try_from(self.executions)
+            .ok()
+            .filter(|&executions| executions > 0)
+            .map(|executions| self.total_duration / executions)
+            .unwrap_or_default()
+    }
+
+    /// Returns the average number of retries of the function with the given name per execution.
+    pub fn expected_retries(&self, function: &str) -> f64 {
+        match self.executions {
+            0 => 0.0,
+            executions => {
+                self.retries.get(function).copied().unwrap_or_default() as f64 / executions as f64
+            },
+        }
+    }
+}
+
+enum Outcome {
+    Completed,
+    Failed,
+    Aborted,
+}
+
+enum Step<'a> {
+    Transition(&'a str),
+    End,
+    Failed,
+}
+
+// States (and conditions) with no transition end the workflow execution.
+fn outcome(transition: &Option<Transition>) -> Step<'_> {
+    match transition {
+        Some(transition) => Step::Transition(transition.next_state()),
+        None => Step::End,
+    }
+}
+
+fn default_condition(default_condition: &DefaultConditionDef) -> Step<'_> {
+    outcome(&default_condition.transition)
+}
+
+// Returns the duration of the longest of concurrent tasks, or `None` if one of them failed.
+fn longest(durations: Vec<Option<TimeDelta>>) -> Option<TimeDelta> {
+    durations
+        .into_iter()
+        .try_fold(TimeDelta::zero(), |longest, duration| Some(longest.max(duration?)))
+}
+
+fn iso_duration(duration: &str) -> TimeDelta {
+    duration
+        .parse::<IsoDuration>()
+        .ok()
+        .and_then(|duration| duration.to_time_delta())
+        .unwrap_or_default()
+}
+
+fn next_delay(retry: &RetryDef, delay: TimeDelta) -> TimeDelta {
+    let multiplier = retry
+        .multiplier
+        .as_ref()
+        .and_then(|multiplier| multiplier.value().ok())
+        .unwrap_or(1.0);
+    let increment = retry
+        .increment
+        .as_deref()
+        .map(iso_duration)
+        .unwrap_or_default();
+    let delay =
+        TimeDelta::milliseconds((delay.num_milliseconds() as f64 * multiplier) as i64) + increment;
+
+    match retry.max_delay.as_deref().map(iso_duration) {
+        Some(max_delay) => delay.min(max_delay),
+        None => delay,
+    }
+}
@@ -0,0 +1,340 @@
+//! Event subscriptions of workflow instances waiting for events.
+//!
+//! When a workflow instance enters an event state, event-based switch state or callback state,
+//! runtimes subscribe to the events it waits for. A [`SubscriptionRegistry`] keeps track of these
+//! subscriptions. Its [snapshot](SubscriptionRegistry::snapshot) can be persisted along with the
+//! workflow instances, so that the registry can be [restored](SubscriptionRegistry::restore) and
+//! broker subscriptions rebuilt when the runtime restarts, without missing events for instances
+//! that were waiting.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::workflow::definition::events::Events;
+use crate::workflow::definition::timeouts::{EventTimeout, Timeouts};
+use crate::workflow::definition::{EventCondition, State, SwitchState, WorkflowDefinition};
+use crate::workflow::format;
+use crate::workflow::instance::WorkflowInstance;
+
+/// Subscription of a workflow instance to an event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSubscription {
+    /// ID of the subscribed workflow instance
+    #[serde(with = "format::id")]
+    pub instance_id: String,
+
+    /// Name of the state waiting for the event
+    pub state: String,
+
+    /// Name of the event definition
+    pub event: String,
+
+    /// CloudEvent type of the event
+    #[serde(rename = "type")]
+    pub event_type: String,
+
+    /// CloudEvent source of the event, if specified
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+
+    /// Values of the CloudEvent context attributes used to correlate the event with the
+    /// workflow instance, by attribute name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub correlation: BTreeMap<String, String>,
+
+    /// Instant at which the state stops waiting for the event, if any
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "format::optional_timestamp")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl EventSubscription {
+    /// Returns the subscriptions of a workflow instance to the events its current state waits for.
+    ///
+    /// Correlation values are taken from the event definitions' [correlation rules]; rules that do
+    /// not specify a value can be completed with [`with_correlation`](Self::with_correlation) once
+    /// the value is known. Subscriptions expire after the state's event timeout, or the workflow's
+    /// default event timeout.
+    ///
+    /// Instances [kept active](WorkflowInstance::kept_active) wait for the events of the
+    /// workflow's start state, which can start new execution paths; runtimes resume them at
+    /// the subscriptions' [`state`](Self::state) when an event arrives.
+    ///
+    /// Returns no subscription if the instance has finished or its current state does not wait
+    /// for events.
+    ///
+    /// # Errors
+    ///
+    /// * [`UnknownState`]: the instance's current state does not exist in the workflow definition
+    /// * [`UnknownEvent`]: the state references an event that is not defined in the workflow
+    ///                     definition
+    /// * [`InvalidDuration`]: the event timeout is not a valid ISO 8601 duration
+    /// * [`UnsupportedDuration`]: the event timeout is calendar-dependent or too large
+    ///
+    /// [correlation rules]: crate::workflow::definition::events::EventDef::correlation
+    /// [`UnknownState`]: crate::Error::UnknownState
+    /// [`UnknownEvent`]: crate::Error::UnknownEvent
+    /// [`InvalidDuration`]: crate::Error::InvalidDuration
+    /// [`UnsupportedDuration`]: crate::Error::UnsupportedDuration
+    pub fn for_instance(
+        definition: &WorkflowDefinition,
+        instance: &WorkflowInstance,
+        now: DateTime<Utc>,
+    ) -> crate::Result<Vec<Self>> {
+        let state_name = match (&instance.state, instance.kept_active) {
+            (Some(state_name), _) => state_name.as_str(),
+            (None, true) => match definition.start_state_name() {
+                Some(start_state_name) => start_state_name,
+                None => return Ok(Vec::new()),
+            },
+            (None, false) => return Ok(Vec::new()),
+        };
+        let state = definition
+            .states
+            .iter()
+            .find(|state| state.name() == state_name)
+            .ok_or_else(|| crate::Error::UnknownState { state: state_name.into() })?;
+
+        let (event_refs, event_timeout): (Vec<&str>, _) = match state {
+            State::Event(state) => (
+                state
+                    .on_events
+                    .iter()
+                    .flat_map(|on_events| on_events.event_refs.iter().map(String::as_str))
+                    .collect(),
+                state
+                    .timeouts
+                    .as_ref()
+                    .and_then(|timeouts| timeouts.event_timeout.as_ref()),
+            ),
+            State::Switch(SwitchState::EventBased(state)) => (
+                state
+                    .event_conditions
+                    .iter()
+                    .map(EventCondition::event_ref)
+                    .collect(),
+                state
+                    .timeouts
+                    .as_ref()
+                    .and_then(|timeouts| timeouts.event_timeout.as_ref()),
+            ),
+            State::Callback(state) => (
+                vec![state.event_ref.as_str()],
+                state
+                    .timeouts
+                    .as_ref()
+                    .and_then(|timeouts| timeouts.event_timeout.as_ref()),
+            ),
+            _ => return Ok(Vec::new()),
+        };
+        let expires_at = event_expiry(definition, event_timeout, now)?;
+
+        let mut seen = BTreeSet::new();
+        event_refs
+            .into_iter()
+            .filter(|event_ref| seen.insert(*event_ref))
+            .map(|event_ref| {
+                let event = match &definition.events {
+                    Some(Events::Inline(events)) => {
+                        events.iter().find(|event| event.name == event_ref)
+                    },
+                    _ => None,
+                }
+                .ok_or_else(|| crate::Error::UnknownEvent { event: event_ref.into() })?;
+
+                let correlation = event
+                    .correlation
+                    .iter()
+                    .flatten()
+                    .filter_map(|correlation| {
+                        correlation.context_attribute_value.as_ref().map(|value| {
+                            (correlation.context_attribute_name.clone(), value.clone())
+                        })
+                    })
+                    .collect();
+
+                Ok(Self {
+                    instance_id: instance.id.clone(),
+                    state: state_name.into(),
+                    event: event.name.clone(),
+                    event_type: event.event_type.clone(),
+                    source: event.source.clone(),
+                    correlation,
+                    expires_at,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns a copy of this subscription where the given context attribute must have
+    /// the given value.
+    pub fn with_correlation<N, V>(mut self, attribute_name: N, value: V) -> Self
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        self.correlation.insert(attribute_name.into(), value.into());
+        self
+    }
+
+    /// Returns `true` if the subscription has expired at the given instant.
+    pub fn is_expired(&self, now: &DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= *now)
+    }
+
+    /// Returns `true` if an event with the given type, source and CloudEvent context attributes
+    /// (including extension attributes) matches this subscription.
+    ///
+    /// String attribute values are compared as-is; other values are compared to the expected value
+    /// parsed as JSON.
+    pub fn matches(
+        &self,
+        event_type: &str,
+        source: Option<&str>,
+        attributes: &Map<String, Value>,
+    ) -> bool {
+        self.event_type == event_type
+            && (self.source.is_none() || self.source.as_deref() == source)
+            && self
+                .correlation
+                .iter()
+                .all(|(name, expected)| match attributes.get(name) {
+                    Some(Value::String(value)) => value == expected,
+                    Some(value) => serde_json::from_str::<Value>(expected)
+                        .is_ok_and(|expected| expected == *value),
+                    None => false,
+                })
+    }
+}
+
+/// Serializable snapshot of the subscriptions registered in a [`SubscriptionRegistry`].
+///
+/// See [`SubscriptionRegistry::snapshot`] and [`SubscriptionRegistry::restore`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubscriptionSnapshot {
+    /// Registered subscriptions, in the order they were registered
+    pub subscriptions: Vec<EventSubscription>,
+}
+
+/// Registry of the [event subscriptions](EventSubscription) of workflow instances.
+#[derive(Debug, Default, Clone)]
+pub struct SubscriptionRegistry {
+    subscriptions: Vec<EventSubscription>,
+}
+
+impl SubscriptionRegistry {
+    /// Creates a new empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restores a registry from a [snapshot](Self::snapshot).
+    ///
+    /// All subscriptions are restored, including those that expired while the runtime was
+    /// stopped; use [`expire`](Self::expire) to find them so that their states can time out.
+    pub fn restore(snapshot: &SubscriptionSnapshot) -> Self {
+        Self { subscriptions: snapshot.subscriptions.clone() }
+    }
+
+    /// Returns a snapshot of the registered subscriptions.
+    ///
+    /// The snapshot can be serialized and later passed to [`restore`](Self::restore), for example
+    /// to restore the registry when a runtime restarts.
+    pub fn snapshot(&self) -> SubscriptionSnapshot {
+        SubscriptionSnapshot { subscriptions: self.subscriptions.clone() }
+    }
+
+    /// Registers a subscription.
+    ///
+    /// If the same state of the same instance was already subscribed to the same event,
+    /// the existing subscription is replaced.
+    pub fn subscribe(&mut self, subscription: EventSubscription) {
+        let existing = self.subscriptions.iter_mut().find(|existing| {
+            existing.instance_id == subscription.instance_id
+                && existing.state == subscription.state
+                && existing.event == subscription.event
+        });
+        match existing {
+            Some(existing) => *existing = subscription,
+            None => self.subscriptions.push(subscription),
+        }
+    }
+
+    /// Removes and returns the subscriptions of the workflow instance with the given ID,
+    /// for example when the instance leaves its waiting state.
+    pub fn unsubscribe(&mut self, instance_id: &str) -> Vec<EventSubscription> {
+        self.remove_if(|subscription| subscription.instance_id == instance_id)
+    }
+
+    /// Removes and returns the subscriptions that have expired at the given instant.
+    pub fn expire(&mut self, now: &DateTime<Utc>) -> Vec<EventSubscription> {
+        self.remove_if(|subscription| subscription.is_expired(now))
+    }
+
+    /// Returns the subscriptions matching an event (see [`EventSubscription::matches`]).
+    pub fn matching<'a>(
+        &'a self,
+        event_type: &'a str,
+        source: Option<&'a str>,
+        attributes: &'a Map<String, Value>,
+    ) -> impl Iterator<Item = &'a EventSubscription> + 'a {
+        self.subscriptions
+            .iter()
+            .filter(move |subscription| subscription.matches(event_type, source, attributes))
+    }
+
+    /// Returns the distinct event types (and sources, if specified) of the registered
+    /// subscriptions, which brokers need to subscribe to.
+    pub fn topics(&self) -> BTreeSet<(&str, Option<&str>)> {
+        self.subscriptions
+            .iter()
+            .map(|subscription| (subscription.event_type.as_str(), subscription.source.as_deref()))
+            .collect()
+    }
+
+    /// Returns an iterator over the registered subscriptions, in the order they were registered.
+    pub fn iter(&self) -> impl Iterator<Item = &EventSubscription> + '_ {
+        self.subscriptions.iter()
+    }
+
+    /// Returns the number of registered subscriptions.
+    pub fn len(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    /// Returns `true` if no subscription is registered.
+    pub fn is_empty(&self) -> bool {
+        self.subscriptions.is_empty()
+    }
+
+    fn remove_if<F>(&mut self, predicate: F) -> Vec<EventSubscription>
+    where
+        F: Fn(&EventSubscription) -> bool,
+    {
+        let (removed, kept) = self.subscriptions.drain(..).partition(predicate);
+        self.subscriptions = kept;
+        removed
+    }
+}
+
+// Returns the instant at which a state waiting for events stops waiting, given the state's
+// event timeout (or else the workflow's default event timeout), or `None` if it waits forever.
+pub(crate) fn event_expiry(
+    definition: &WorkflowDefinition,
+    event_timeout: Option<&EventTimeout>,
+    now: DateTime<Utc>,
+) -> crate::Result<Option<DateTime<Utc>>> {
+    let event_timeout = event_timeout.or(match &definition.timeouts {
+        Some(Timeouts::Complex { event_timeout, .. }) => event_timeout.as_ref(),
+        _ => None,
+    });
+    Ok(event_timeout
+        .map(EventTimeout::as_duration)
+        .transpose()?
+        // Timeouts too large to be represented never expire in practice.
+        .and_then(|timeout| TimeDelta::from_std(timeout).ok())
+        .and_then(|timeout| now.checked_add_signed(timeout)))
+}
@@ -0,0 +1,783 @@
+//! Serverless Workflow DSL 1.0 - workflow schema
+//!
+//! Parallel to the [v0.8 definition types](crate::workflow::definition), these types model
+//! workflow definitions written with version 1.0.x of the DSL. Constructs that are not yet
+//! used by the crate (for example, authentication policies or event filters) are kept as raw
+//! JSON values.
+//!
+//! Corresponding JSON schema: [workflow.yaml](https://github.com/serverlessworkflow/specification/blob/v1.0.0/schema/workflow.yaml).
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use indexmap::IndexMap;
+use serde::de::{self, IgnoredAny, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::detail::false_value;
+
+/// Workflow definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowDefinition {
+    /// Documents the workflow
+    #[cfg_attr(feature = "validate", garde(dive))]
+    pub document: Document,
+
+    /// Configures the workflow's input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub input: Option<Input>,
+
+    /// Defines the workflow's reusable components
+    #[serde(rename = "use", default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub uses: Option<Use>,
+
+    /// Tasks to perform
+    #[serde(rename = "do")]
+    #[cfg_attr(feature = "validate", garde(length(min = 1), dive))]
+    pub tasks: Vec<TaskItem>,
+
+    /// Workflow's timeout, or name of a reusable timeout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub timeout: Option<Value>,
+
+    /// Configures the workflow's output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub output: Option<Output>,
+
+    /// Schedule of the workflow
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub schedule: Option<Schedule>,
+}
+
+/// Workflow document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[serde(rename_all = "camelCase")]
+pub struct Document {
+    /// Version of the DSL used by the workflow (e.g. `1.0.0`)
+    #[cfg_attr(feature = "validate", garde(length(min = 1)))]
+    pub dsl: String,
+
+    /// Workflow namespace
+    #[cfg_attr(feature = "validate", garde(length(min = 1)))]
+    pub namespace: String,
+
+    /// Workflow name
+    #[cfg_attr(feature = "validate", garde(length(min = 1)))]
+    pub name: String,
+
+    /// Workflow semantic version
+    #[cfg_attr(feature = "validate", garde(length(min = 1)))]
+    pub version: String,
+
+    /// Workflow title
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub title: Option<String>,
+
+    /// Workflow summary, in Markdown format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub summary: Option<String>,
+
+    /// Key/value tags used to classify the workflow
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub tags: Option<IndexMap<String, String>>,
+
+    /// Additional information about the workflow
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub metadata: Option<Map<String, Value>>,
+}
+
+/// Reusable components of a workflow
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Use {
+    /// Reusable authentication policies, keyed by name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authentications: Option<Map<String, Value>>,
+
+    /// Reusable errors, keyed by name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Map<String, Value>>,
+
+    /// Workflow extensions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<Vec<Value>>,
+
+    /// Reusable functions (tasks), keyed by name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub functions: Option<IndexMap<String, Task>>,
+
+    /// Reusable retry policies, keyed by name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<Map<String, Value>>,
+
+    /// Names of the secrets used by the workflow
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<Vec<String>>,
+
+    /// Reusable timeouts, keyed by name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeouts: Option<Map<String, Value>>,
+}
+
+/// Input configuration of a workflow or task
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Input {
+    /// Schema used to validate the input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Value>,
+
+    /// Runtime expression (or object containing runtime expressions) used to transform the input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<Value>,
+}
+
+/// Output configuration of a workflow or task
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Output {
+    /// Schema used to validate the output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Value>,
+
+    /// Runtime expression (or object containing runtime expressions) used to transform the output
+    #[serde(rename = "as", default, skip_serializing_if = "Option::is_none")]
+    pub transform: Option<Value>,
+}
+
+/// Export configuration of a task
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Export {
+    /// Schema used to validate the workflow context
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Value>,
+
+    /// Runtime expression (or object containing runtime expressions) used to update the workflow context
+    #[serde(rename = "as", default, skip_serializing_if = "Option::is_none")]
+    pub transform: Option<Value>,
+}
+
+/// Schedule of a workflow
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schedule {
+    /// Interval at which to start workflow instances
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub every: Option<Value>,
+
+    /// Cron expression describing when to start workflow instances
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cron: Option<String>,
+
+    /// Delay after which to start a new workflow instance once the previous one completes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<Value>,
+
+    /// Events that start workflow instances
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on: Option<Value>,
+}
+
+/// Named task, as found in a task list
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+pub struct TaskItem {
+    /// Task name
+    #[cfg_attr(feature = "validate", garde(length(min = 1)))]
+    pub name: String,
+
+    /// Task definition
+    #[cfg_attr(feature = "validate", garde(dive))]
+    pub task: Task,
+}
+
+impl Serialize for TaskItem {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_single_entry(&self.name, &self.task, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TaskItem {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (name, task) = deserialize_single_entry(deserializer)?;
+        Ok(Self { name, task })
+    }
+}
+
+/// Possible workflow tasks
+///
+/// Tasks are identified by the property naming the kind of task (`call`, `do`, etc.).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[serde(untagged)]
+pub enum Task {
+    // Note: `for` tasks also have a `do` property, so they need to be matched before `do` tasks.
+    /// Task iterating over a collection
+    For(#[cfg_attr(feature = "validate", garde(dive))] ForTask),
+
+    /// Task calling a function
+    Call(#[cfg_attr(feature = "validate", garde(skip))] CallTask),
+
+    /// Task executing a list of subtasks
+    Do(#[cfg_attr(feature = "validate", garde(dive))] DoTask),
+
+    /// Task emitting an event
+    Emit(#[cfg_attr(feature = "validate", garde(skip))] EmitTask),
+
+    /// Task executing branches of subtasks concurrently
+    Fork(#[cfg_attr(feature = "validate", garde(dive))] ForkTask),
+
+    /// Task waiting for events
+    Listen(#[cfg_attr(feature = "validate", garde(skip))] ListenTask),
+
+    /// Task raising an error
+    Raise(#[cfg_attr(feature = "validate", garde(skip))] RaiseTask),
+
+    /// Task running a process (container, script, shell command or workflow)
+    Run(#[cfg_attr(feature = "validate", garde(skip))] RunTask),
+
+    /// Task setting data
+    Set(#[cfg_attr(feature = "validate", garde(skip))] SetTask),
+
+    /// Task selecting the next task to execute based on conditions
+    Switch(#[cfg_attr(feature = "validate", garde(skip))] SwitchTask),
+
+    /// Task executing subtasks and handling their errors
+    Try(#[cfg_attr(feature = "validate", garde(dive))] TryTask),
+
+    /// Task pausing execution for a given amount of time
+    Wait(#[cfg_attr(feature = "validate", garde(skip))] WaitTask),
+}
+
+impl Task {
+    /// Returns the properties common to all tasks.
+    pub fn base(&self) -> &TaskBase {
+        match self {
+            Self::For(task) => &task.base,
+            Self::Call(task) => &task.base,
+            Self::Do(task) => &task.base,
+            Self::Emit(task) => &task.base,
+            Self::Fork(task) => &task.base,
+            Self::Listen(task) => &task.base,
+            Self::Raise(task) => &task.base,
+            Self::Run(task) => &task.base,
+            Self::Set(task) => &task.base,
+            Self::Switch(task) => &task.base,
+            Self::Try(task) => &task.base,
+            Self::Wait(task) => &task.base,
+        }
+    }
+
+    /// Returns a mutable reference to the properties common to all tasks.
+    pub fn base_mut(&mut self) -> &mut TaskBase {
+        match self {
+            Self::For(task) => &mut task.base,
+            Self::Call(task) => &mut task.base,
+            Self::Do(task) => &mut task.base,
+            Self::Emit(task) => &mut task.base,
+            Self::Fork(task) => &mut task.base,
+            Self::Listen(task) => &mut task.base,
+            Self::Raise(task) => &mut task.base,
+            Self::Run(task) => &mut task.base,
+            Self::Set(task) => &mut task.base,
+            Self::Switch(task) => &mut task.base,
+            Self::Try(task) => &mut task.base,
+            Self::Wait(task) => &mut task.base,
+        }
+    }
+}
+
+/// Properties common to all tasks
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskBase {
+    /// Runtime expression determining whether the task should be executed
+    #[serde(rename = "if", default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+
+    /// Configures the task's input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<Input>,
+
+    /// Configures the task's output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<Output>,
+
+    /// Configures how the task's output updates the workflow context
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export: Option<Export>,
+
+    /// Task's timeout, or name of a reusable timeout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<Value>,
+
+    /// Flow directive (`continue`, `exit`, `end` or name of the next task)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub then: Option<String>,
+
+    /// Additional information about the task
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Map<String, Value>>,
+}
+
+/// Task calling a function
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallTask {
+    /// Name of the function to call (either a built-in function like `http` or a reusable function)
+    pub call: String,
+
+    /// Arguments of the function
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub with: Option<Map<String, Value>>,
+
+    /// Properties common to all tasks
+    #[serde(flatten)]
+    pub base: TaskBase,
+}
+
+/// Task executing a list of subtasks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[serde(rename_all = "camelCase")]
+pub struct DoTask {
+    /// Subtasks to execute sequentially
+    #[serde(rename = "do")]
+    #[cfg_attr(feature = "validate", garde(length(min = 1), dive))]
+    pub tasks: Vec<TaskItem>,
+
+    /// Properties common to all tasks
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub base: TaskBase,
+}
+
+/// Task emitting an event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmitTask {
+    /// Event to emit
+    pub emit: Emit,
+
+    /// Properties common to all tasks
+    #[serde(flatten)]
+    pub base: TaskBase,
+}
+
+/// Configuration of an [emit task](EmitTask)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Emit {
+    /// Event to emit
+    pub event: EventDefinition,
+}
+
+/// Definition of an event to emit
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventDefinition {
+    /// CloudEvent attributes (`source`, `type`, `data`, etc.)
+    pub with: Map<String, Value>,
+}
+
+/// Task iterating over a collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[serde(rename_all = "camelCase")]
+pub struct ForTask {
+    /// Configures the iteration
+    #[serde(rename = "for")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub iteration: ForLoop,
+
+    /// Runtime expression that must be true for iteration to continue
+    #[serde(rename = "while", default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub while_condition: Option<String>,
+
+    /// Subtasks to execute for each item
+    #[serde(rename = "do")]
+    #[cfg_attr(feature = "validate", garde(length(min = 1), dive))]
+    pub tasks: Vec<TaskItem>,
+
+    /// Properties common to all tasks
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub base: TaskBase,
+}
+
+/// Configuration of a [for task](ForTask)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForLoop {
+    /// Name of the variable containing the current item (default: `item`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub each: Option<String>,
+
+    /// Runtime expression returning the collection to iterate over
+    #[serde(rename = "in")]
+    pub collection: String,
+
+    /// Name of the variable containing the current index (default: `index`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at: Option<String>,
+}
+
+/// Task executing branches of subtasks concurrently
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[serde(rename_all = "camelCase")]
+pub struct ForkTask {
+    /// Configures the branches
+    #[cfg_attr(feature = "validate", garde(dive))]
+    pub fork: Fork,
+
+    /// Properties common to all tasks
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub base: TaskBase,
+}
+
+/// Configuration of a [fork task](ForkTask)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[serde(rename_all = "camelCase")]
+pub struct Fork {
+    /// Branches to execute concurrently
+    #[cfg_attr(feature = "validate", garde(length(min = 1), dive))]
+    pub branches: Vec<TaskItem>,
+
+    /// If `true`, the first branch to complete wins and other branches are cancelled
+    #[serde(default = "false_value")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub compete: bool,
+}
+
+/// Task waiting for events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListenTask {
+    /// Configures the events to listen to
+    pub listen: Listen,
+
+    /// Properties common to all tasks
+    #[serde(flatten)]
+    pub base: TaskBase,
+}
+
+/// Configuration of a [listen task](ListenTask)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Listen {
+    /// Event consumption strategy
+    pub to: EventConsumptionStrategy,
+}
+
+/// Strategy used to consume events
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventConsumptionStrategy {
+    /// All of the events must be consumed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub all: Option<Vec<EventFilter>>,
+
+    /// Any of the events must be consumed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub any: Option<Vec<EventFilter>>,
+
+    /// A single event must be consumed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub one: Option<EventFilter>,
+
+    /// Condition or events that stop event consumption
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<Value>,
+}
+
+/// Filter used to select events to consume
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventFilter {
+    /// CloudEvent attributes to match
+    pub with: Map<String, Value>,
+
+    /// Correlation keys, keyed by name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlate: Option<Map<String, Value>>,
+}
+
+/// Task raising an error
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RaiseTask {
+    /// Configures the error to raise
+    pub raise: Raise,
+
+    /// Properties common to all tasks
+    #[serde(flatten)]
+    pub base: TaskBase,
+}
+
+/// Configuration of a [raise task](RaiseTask)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Raise {
+    /// Error to raise, or name of a reusable error
+    pub error: Value,
+}
+
+/// Task running a process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunTask {
+    /// Configures the process to run
+    pub run: Run,
+
+    /// Properties common to all tasks
+    #[serde(flatten)]
+    pub base: TaskBase,
+}
+
+/// Configuration of a [run task](RunTask)
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Run {
+    /// Container to run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<Value>,
+
+    /// Script to run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<Value>,
+
+    /// Shell command to run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<Value>,
+
+    /// Workflow to run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<RunWorkflow>,
+
+    /// Whether to wait for the process to complete before continuing (default: `true`)
+    #[serde(rename = "await", default, skip_serializing_if = "Option::is_none")]
+    pub wait_for_completion: Option<bool>,
+}
+
+/// Workflow to run in a [run task](RunTask)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunWorkflow {
+    /// Namespace of the workflow to run
+    pub namespace: String,
+
+    /// Name of the workflow to run
+    pub name: String,
+
+    /// Version of the workflow to run (default: `latest`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// Input of the workflow to run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<Value>,
+}
+
+/// Task setting data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetTask {
+    /// Data to set (an object, or a runtime expression returning an object)
+    pub set: Value,
+
+    /// Properties common to all tasks
+    #[serde(flatten)]
+    pub base: TaskBase,
+}
+
+/// Task selecting the next task to execute based on conditions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchTask {
+    /// Cases of the switch, evaluated in order
+    pub switch: Vec<SwitchCaseItem>,
+
+    /// Properties common to all tasks
+    #[serde(flatten)]
+    pub base: TaskBase,
+}
+
+/// Named case of a [switch task](SwitchTask)
+#[derive(Debug, Clone)]
+pub struct SwitchCaseItem {
+    /// Case name
+    pub name: String,
+
+    /// Case definition
+    pub case: SwitchCase,
+}
+
+impl Serialize for SwitchCaseItem {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_single_entry(&self.name, &self.case, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SwitchCaseItem {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (name, case) = deserialize_single_entry(deserializer)?;
+        Ok(Self { name, case })
+    }
+}
+
+/// Case of a [switch task](SwitchTask)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchCase {
+    /// Runtime expression determining whether the case matches; cases without a condition always match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<String>,
+
+    /// Flow directive to follow if the case matches
+    pub then: String,
+}
+
+/// Task executing subtasks and handling their errors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[serde(rename_all = "camelCase")]
+pub struct TryTask {
+    /// Subtasks to execute
+    #[serde(rename = "try")]
+    #[cfg_attr(feature = "validate", garde(length(min = 1), dive))]
+    pub tasks: Vec<TaskItem>,
+
+    /// Configures how to handle errors
+    #[cfg_attr(feature = "validate", garde(dive))]
+    pub catch: Catch,
+
+    /// Properties common to all tasks
+    #[serde(flatten)]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub base: TaskBase,
+}
+
+/// Configuration of error handling in a [try task](TryTask)
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[serde(rename_all = "camelCase")]
+pub struct Catch {
+    /// Filters the errors to catch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub errors: Option<Value>,
+
+    /// Name of the variable containing the caught error (default: `error`)
+    #[serde(rename = "as", default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub error_variable: Option<String>,
+
+    /// Runtime expression determining whether to catch the error
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub when: Option<String>,
+
+    /// Runtime expression determining whether *not* to catch the error
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub except_when: Option<String>,
+
+    /// Retry policy, or name of a reusable retry policy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(skip))]
+    pub retry: Option<Value>,
+
+    /// Tasks to execute when an error is caught
+    #[serde(rename = "do", default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "validate", garde(dive))]
+    pub tasks: Option<Vec<TaskItem>>,
+}
+
+/// Task pausing execution for a given amount of time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaitTask {
+    /// Amount of time to wait (ISO 8601 duration or duration object)
+    pub wait: Value,
+
+    /// Properties common to all tasks
+    #[serde(flatten)]
+    pub base: TaskBase,
+}
+
+fn serialize_single_entry<K, V, S>(key: &K, value: &V, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: Serialize,
+    V: Serialize,
+    S: Serializer,
+{
+    let mut map = serializer.serialize_map(Some(1))?;
+    map.serialize_entry(key, value)?;
+    map.end()
+}
+
+fn deserialize_single_entry<'de, V, D>(deserializer: D) -> Result<(String, V), D::Error>
+where
+    V: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    struct SingleEntryVisitor<V>(PhantomData<V>);
+
+    impl<'de, V> Visitor<'de> for SingleEntryVisitor<V>
+    where
+        V: Deserialize<'de>,
+    {
+        type Value = (String, V);
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map with a single entry")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let entry = map
+                .next_entry()?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            if map.next_key::<IgnoredAny>()?.is_some() {
+                return Err(de::Error::invalid_length(2, &self));
+            }
+
+            Ok(entry)
+        }
+    }
+
+    deserializer.deserialize_map(SingleEntryVisitor(PhantomData))
+}
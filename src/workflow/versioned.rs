@@ -0,0 +1,111 @@
+//! Support for loading workflow definitions written for different versions of the specification.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use crate::workflow::{definition, v1};
+
+/// Version of the Serverless Workflow specification a workflow definition conforms to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum SpecVersion {
+    /// Version 0.8 (see [`definition`])
+    V0_8,
+
+    /// Version 1.0.x of the DSL (see [`v1`])
+    V1_0,
+}
+
+impl SpecVersion {
+    /// Detects the version of the specification used by a workflow definition document.
+    ///
+    /// * Documents with a `document.dsl` property of `1.0.x` are [`V1_0`](Self::V1_0).
+    /// * Documents with a `specVersion` property of `0.8` (or `0.8.x`) are [`V0_8`](Self::V0_8).
+    ///
+    /// # Errors
+    ///
+    /// * [`UnsupportedSpecVersion`]: document does not specify a version, or specifies
+    ///                               a version that is not supported
+    ///
+    /// [`UnsupportedSpecVersion`]: crate::Error::UnsupportedSpecVersion
+    pub fn detect(document: &Value) -> crate::Result<Self> {
+        let (version, expected, spec_version) = match document.get("document") {
+            Some(header) => (header.get("dsl"), "1.0", Self::V1_0),
+            None => (document.get("specVersion"), "0.8", Self::V0_8),
+        };
+
+        match version.and_then(Value::as_str) {
+            Some(version) if is_version(version, expected) => Ok(spec_version),
+            _ => Err(crate::Error::UnsupportedSpecVersion {
+                spec_version: version.map(|version| match version {
+                    Value::String(version) => version.clone(),
+                    version => version.to_string(),
+                }),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for SpecVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::V0_8 => write!(f, "0.8"),
+            Self::V1_0 => write!(f, "1.0"),
+        }
+    }
+}
+
+fn is_version(version: &str, expected: &str) -> bool {
+    version
+        .strip_prefix(expected)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.') || rest.starts_with('-'))
+}
+
+/// Workflow definition conforming to any supported version of the specification.
+///
+/// When deserialized, the version of the specification is [detected](SpecVersion::detect)
+/// and the matching definition type is used. This makes it possible to load either format
+/// through the same API, e.g. by passing this type to [`DefinitionLoader::load`] or
+/// [`DefinitionCache::get_or_insert`].
+///
+/// [`DefinitionLoader::load`]: crate::loader::DefinitionLoader::load
+/// [`DefinitionCache::get_or_insert`]: crate::cache::DefinitionCache::get_or_insert
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "validate", derive(garde::Validate))]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)] // Workflow definitions are not moved around much
+pub enum VersionedWorkflowDefinition {
+    /// Workflow definition conforming to version 0.8 of the specification
+    V0_8(#[cfg_attr(feature = "validate", garde(dive))] definition::WorkflowDefinition),
+
+    /// Workflow definition conforming to version 1.0.x of the DSL
+    V1_0(#[cfg_attr(feature = "validate", garde(dive))] v1::WorkflowDefinition),
+}
+
+impl VersionedWorkflowDefinition {
+    /// Returns the version of the specification the workflow definition conforms to.
+    pub fn spec_version(&self) -> SpecVersion {
+        match self {
+            Self::V0_8(_) => SpecVersion::V0_8,
+            Self::V1_0(_) => SpecVersion::V1_0,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for VersionedWorkflowDefinition {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let document = Value::deserialize(deserializer)?;
+        let spec_version = SpecVersion::detect(&document).map_err(serde::de::Error::custom)?;
+
+        match spec_version {
+            SpecVersion::V0_8 => serde_path_to_error::deserialize(document).map(Self::V0_8),
+            SpecVersion::V1_0 => serde_path_to_error::deserialize(document).map(Self::V1_0),
+        }
+        .map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,46 @@
+use arbitrary::{Arbitrary, Unstructured};
+use travailleur::workflow::definition::WorkflowDefinition;
+
+const ITERATIONS: u64 = 500;
+
+// Generates pseudo-random bytes (using SplitMix64) so that failures can be reproduced from the seed.
+fn random_bytes(seed: u64) -> Vec<u8> {
+    let mut state = seed;
+    (0..1024)
+        .flat_map(|_| {
+            state = state.wrapping_add(0x9e3779b97f4a7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            (z ^ (z >> 31)).to_le_bytes()
+        })
+        .collect()
+}
+
+fn arbitrary_definition(seed: u64) -> WorkflowDefinition {
+    WorkflowDefinition::arbitrary(&mut Unstructured::new(&random_bytes(seed)))
+        .expect("arbitrary workflow definition should be generated")
+}
+
+#[test]
+fn test_arbitrary_round_trip() {
+    for seed in 0..ITERATIONS {
+        let definition = arbitrary_definition(seed);
+        let serialized = serde_json::to_value(&definition).unwrap();
+        let round_trip: WorkflowDefinition = serde_json::from_value(serialized.clone())
+            .unwrap_or_else(|err| panic!("seed {}: {}\n{:#}", seed, err, serialized));
+
+        assert_eq!(serialized, serde_json::to_value(&round_trip).unwrap(), "seed {}", seed);
+    }
+}
+
+#[cfg(feature = "validate")]
+#[test]
+fn test_arbitrary_validation() {
+    use travailleur::validation::ValidateDefinition;
+
+    for seed in 0..ITERATIONS {
+        // Arbitrary definitions are mostly invalid; validating them must simply not panic.
+        let _ = arbitrary_definition(seed).validate_definition();
+    }
+}
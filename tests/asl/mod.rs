@@ -0,0 +1,527 @@
+use serde_json::{json, Value};
+use travailleur::convert::asl;
+use travailleur::workflow::definition::functions::Functions;
+use travailleur::workflow::definition::{DataCondition, State, SwitchState, WorkflowDefinition};
+
+fn order_state_machine() -> Value {
+    json!({
+        "Comment": "Processes an order",
+        "StartAt": "ValidateOrder",
+        "TimeoutSeconds": 3600,
+        "States": {
+            "ValidateOrder": {
+                "Type": "Task",
+                "Resource": "arn:aws:lambda:us-east-1:123456789012:function:ValidateOrder",
+                "Parameters": {
+                    "orderId.$": "$.order.id",
+                    "strict": true,
+                },
+                "ResultPath": "$.validation",
+                "TimeoutSeconds": 30,
+                "Next": "IsValid",
+            },
+            "IsValid": {
+                "Type": "Choice",
+                "Choices": [
+                    {
+                        "And": [
+                            { "Variable": "$.validation.valid", "BooleanEquals": true },
+                            { "Not": { "Variable": "$.order.total", "NumericGreaterThan": 1000 } },
+                        ],
+                        "Next": "ProcessItems",
+                    },
+                    {
+                        "Variable": "$.order.customer",
+                        "StringMatches": "vip-*",
+                        "Next": "ProcessItems",
+                    },
+                ],
+                "Default": "Reject",
+            },
+            "ProcessItems": {
+                "Type": "Map",
+                "ItemsPath": "$.order.items",
+                "MaxConcurrency": 5,
+                "ItemProcessor": {
+                    "StartAt": "ReserveItem",
+                    "States": {
+                        "ReserveItem": {
+                            "Type": "Task",
+                            "Resource": "arn:aws:lambda:us-east-1:123456789012:function:ReserveItem",
+                            "Parameters": { "sku.$": "$.sku" },
+                            "End": true,
+                        },
+                    },
+                },
+                "ResultPath": "$.reservations",
+                "Next": "Notify",
+            },
+            "Notify": {
+                "Type": "Parallel",
+                "Branches": [
+                    {
+                        "StartAt": "NotifyCustomer",
+                        "States": {
+                            "NotifyCustomer": {
+                                "Type": "Task",
+                                "Resource": "arn:aws:states:::sns:publish",
+                                "End": true,
+                            },
+                        },
+                    },
+                    {
+                        "StartAt": "NotifyWarehouse",
+                        "States": {
+                            "NotifyWarehouse": {
+                                "Type": "Task",
+                                "Resource": "arn:aws:states:::sns:publish",
+                                "End": true,
+                            },
+                        },
+                    },
+                ],
+                "Next": "Cooldown",
+            },
+            "Cooldown": {
+                "Type": "Wait",
+                "Seconds": 10,
+                "Next": "Done",
+            },
+            "Done": {
+                "Type": "Succeed",
+            },
+            "Reject": {
+                "Type": "Pass",
+                "Result": { "rejected": true },
+                "ResultPath": "$.status",
+                "End": true,
+            },
+        },
+    })
+}
+
+#[test]
+fn test_import() {
+    let import = asl::import("order", &order_state_machine()).unwrap();
+    assert!(import.is_complete(), "unexpected issues: {:?}", import.issues);
+
+    let definition = import.definition;
+    assert_eq!("order", definition.identifier.id().unwrap());
+    assert_eq!(Some("Processes an order"), definition.description.as_deref());
+    assert_eq!(Some("ValidateOrder"), definition.start_state_name());
+    assert_eq!("ValidateOrder", definition.states[0].name());
+
+    let Some(Functions::Inline(functions)) = &definition.functions else {
+        panic!("expected inline functions, got {:?}", definition.functions);
+    };
+    assert_eq!(
+        vec![
+            ("ValidateOrder", "arn:aws:lambda:us-east-1:123456789012:function:ValidateOrder"),
+            ("ReserveItem", "arn:aws:lambda:us-east-1:123456789012:function:ReserveItem"),
+            ("NotifyCustomer", "arn:aws:states:::sns:publish"),
+        ],
+        functions
+            .iter()
+            .map(|function| (function.name.as_str(), function.operation.as_str()))
+            .collect::<Vec<_>>()
+    );
+
+    let state = |name: &str| {
+        let state = definition
+            .states
+            .iter()
+            .find(|state| state.name() == name)
+            .unwrap_or_else(|| panic!("state '{}' not found", name));
+        serde_json::to_value(state).unwrap()
+    };
+    assert_eq!(
+        json!({
+            "name": "ValidateOrder",
+            "type": "operation",
+            "actionMode": "sequential",
+            "actions": [{
+                "name": "ValidateOrder",
+                "functionRef": {
+                    "refName": "ValidateOrder",
+                    "arguments": { "orderId": "${ .order.id }", "strict": true },
+                    "invoke": "sync",
+                },
+                "actionDataFilter": { "useResults": true, "toStateData": "${ .validation }" },
+            }],
+            "timeouts": { "actionExecTimeout": "PT30S" },
+            "transition": "IsValid",
+            "usedForCompensation": false,
+        }),
+        state("ValidateOrder")
+    );
+    assert_eq!(
+        json!({ "inputCollection": "${ .order.items }", "outputCollection": "${ .reservations }", "batchSize": 5 }),
+        json!({
+            "inputCollection": state("ProcessItems")["inputCollection"],
+            "outputCollection": state("ProcessItems")["outputCollection"],
+            "batchSize": state("ProcessItems")["batchSize"],
+        })
+    );
+    assert_eq!(
+        json!({ "sku": "${ .item.sku }" }),
+        state("ProcessItems")["actions"][0]["functionRef"]["arguments"]
+    );
+    assert_eq!(
+        json!(["NotifyCustomer", "NotifyCustomer"]),
+        json!([
+            state("Notify")["branches"][0]["actions"][0]["functionRef"]["refName"],
+            state("Notify")["branches"][1]["actions"][0]["functionRef"]["refName"],
+        ])
+    );
+    assert_eq!("PT10S", state("Cooldown")["duration"]);
+    assert_eq!(json!({ "status": { "rejected": true } }), state("Reject")["data"]);
+    assert_eq!(json!(true), state("Done")["end"]);
+
+    let Some(State::Switch(SwitchState::DataBased(switch))) = definition
+        .states
+        .iter()
+        .find(|state| state.name() == "IsValid")
+    else {
+        panic!("expected a data-based switch state");
+    };
+    let conditions: Vec<_> = switch
+        .data_conditions
+        .iter()
+        .map(|condition| match condition {
+            DataCondition::Transition(condition) => condition.condition.as_str(),
+            DataCondition::End(condition) => condition.condition.as_str(),
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            "${ (.validation.valid == true) and ((.order.total > 1000) | not) }",
+            "${ .order.customer | test(\"^vip-.*$\") }",
+        ],
+        conditions
+    );
+}
+
+#[test]
+fn test_import_reports_issues() {
+    let state_machine = json!({
+        "StartAt": "Call",
+        "States": {
+            "Call": {
+                "Type": "Task",
+                "Resource": "arn:aws:states:::lambda:invoke",
+                "Parameters": { "greeting.$": "States.Format('Hello, {}', $.name)" },
+                "Retry": [{ "ErrorEquals": ["States.ALL"], "MaxAttempts": 2 }],
+                "Next": "Check",
+            },
+            "Check": {
+                "Type": "Choice",
+                "Choices": [{ "Variable": "$.when", "TimestampEquals": "2024-01-01T00:00:00Z", "Next": "Fail" }],
+            },
+            "Fail": {
+                "Type": "Fail",
+                "Error": "Failed",
+            },
+        },
+    });
+
+    let import = asl::import("issues", &state_machine).unwrap();
+    let mut pointers: Vec<_> = import
+        .issues
+        .iter()
+        .map(|issue| issue.pointer.as_str())
+        .collect();
+    pointers.sort();
+    assert_eq!(
+        vec![
+            "/States/Call/Parameters/greeting.$",
+            "/States/Call/Retry",
+            "/States/Check",
+            "/States/Check/Choices/0/TimestampEquals",
+            "/States/Fail",
+        ],
+        pointers
+    );
+}
+
+#[test]
+fn test_import_invalid_state_machine() {
+    for (state_machine, expected_pointer) in [
+        (json!({ "States": {} }), "/StartAt"),
+        (
+            json!({ "StartAt": "A", "States": { "A": { "Type": "Unknown", "End": true } } }),
+            "/States/A/Type",
+        ),
+        (
+            json!({ "StartAt": "A", "States": { "A": { "Type": "Wait", "Seconds": 1 } } }),
+            "/States/A",
+        ),
+    ] {
+        match asl::import("invalid", &state_machine) {
+            Err(travailleur::Error::InvalidStateMachine { pointer, .. }) => {
+                assert_eq!(expected_pointer, pointer);
+            },
+            result => panic!("expected InvalidStateMachine error, got {:?}", result),
+        }
+    }
+}
+
+fn order_definition() -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "order",
+        "specVersion": "0.8",
+        "description": "Processes an order",
+        "start": "ValidateOrder",
+        "timeouts": { "workflowExecTimeout": "PT1H" },
+        "functions": [
+            { "name": "validateOrder", "operation": "arn:aws:lambda:us-east-1:123456789012:function:ValidateOrder", "type": "custom" },
+            { "name": "reserveItem", "operation": "arn:aws:lambda:us-east-1:123456789012:function:ReserveItem", "type": "custom" },
+            { "name": "notify", "operation": "arn:aws:states:::sns:publish", "type": "custom" },
+        ],
+        "retries": [
+            { "name": "default", "delay": "PT2S", "maxAttempts": 3, "multiplier": 2 },
+        ],
+        "states": [
+            {
+                "name": "ValidateOrder",
+                "type": "operation",
+                "actions": [{
+                    "functionRef": { "refName": "validateOrder", "arguments": { "orderId": "${ .order.id }", "strict": true } },
+                    "actionDataFilter": { "toStateData": "${ .validation }" },
+                    "retryRef": "default",
+                }],
+                "timeouts": { "actionExecTimeout": "PT30S" },
+                "transition": "IsValid",
+            },
+            {
+                "name": "IsValid",
+                "type": "switch",
+                "dataConditions": [
+                    { "condition": "${ .validation.valid == true }", "transition": "ProcessItems" },
+                    { "condition": "${ .order.customer != \"blocked\" }", "end": true },
+                ],
+                "defaultCondition": { "transition": "Reject" },
+            },
+            {
+                "name": "ProcessItems",
+                "type": "foreach",
+                "inputCollection": "${ .order.items }",
+                "outputCollection": "${ .reservations }",
+                "iterationParam": "item",
+                "batchSize": 5,
+                "actions": [{
+                    "functionRef": { "refName": "reserveItem", "arguments": { "sku": "${ .item.sku }" } },
+                }],
+                "transition": "Notify",
+            },
+            {
+                "name": "Notify",
+                "type": "operation",
+                "actionMode": "parallel",
+                "actions": [
+                    { "name": "customer", "functionRef": "notify", "actionDataFilter": { "useResults": false } },
+                    { "name": "warehouse", "functionRef": "notify", "actionDataFilter": { "useResults": false } },
+                ],
+                "transition": "Cooldown",
+            },
+            { "name": "Cooldown", "type": "sleep", "duration": "PT10S", "end": true },
+            {
+                "name": "Reject",
+                "type": "inject",
+                "data": { "status": "rejected", "code": 400 },
+                "stateDataFilter": { "output": "${ .status }" },
+                "end": true,
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_export() {
+    let state_machine = asl::export(&order_definition()).unwrap();
+
+    assert_eq!(
+        json!({
+            "Comment": "Processes an order",
+            "StartAt": "ValidateOrder",
+            "TimeoutSeconds": 3600,
+            "States": {
+                "ValidateOrder": {
+                    "Type": "Task",
+                    "Resource": "arn:aws:lambda:us-east-1:123456789012:function:ValidateOrder",
+                    "Parameters": { "orderId.$": "$.order.id", "strict": true },
+                    "ResultPath": "$.validation",
+                    "Retry": [{
+                        "ErrorEquals": ["States.ALL"],
+                        "MaxAttempts": 3,
+                        "IntervalSeconds": 2,
+                        "BackoffRate": 2.0,
+                    }],
+                    "TimeoutSeconds": 30,
+                    "Next": "IsValid",
+                },
+                "IsValid": {
+                    "Type": "Choice",
+                    "Choices": [
+                        { "Variable": "$.validation.valid", "BooleanEquals": true, "Next": "ProcessItems" },
+                        {
+                            "Not": { "Variable": "$.order.customer", "StringEquals": "blocked" },
+                            "Next": "IsValid (end)",
+                        },
+                    ],
+                    "Default": "Reject",
+                },
+                "IsValid (end)": { "Type": "Succeed" },
+                "ProcessItems": {
+                    "Type": "Map",
+                    "ItemsPath": "$.order.items",
+                    "ItemProcessor": {
+                        "StartAt": "ProcessItems (item)",
+                        "States": {
+                            "ProcessItems (item)": {
+                                "Type": "Task",
+                                "Resource": "arn:aws:lambda:us-east-1:123456789012:function:ReserveItem",
+                                "Parameters": { "sku.$": "$.sku" },
+                                "End": true,
+                            },
+                        },
+                    },
+                    "ResultPath": "$.reservations",
+                    "MaxConcurrency": 5,
+                    "Next": "Notify",
+                },
+                "Notify": {
+                    "Type": "Parallel",
+                    "Branches": [
+                        {
+                            "StartAt": "Notify (customer)",
+                            "States": {
+                                "Notify (customer)": {
+                                    "Type": "Task",
+                                    "Resource": "arn:aws:states:::sns:publish",
+                                    "ResultPath": null,
+                                    "End": true,
+                                },
+                            },
+                        },
+                        {
+                            "StartAt": "Notify (warehouse)",
+                            "States": {
+                                "Notify (warehouse)": {
+                                    "Type": "Task",
+                                    "Resource": "arn:aws:states:::sns:publish",
+                                    "ResultPath": null,
+                                    "End": true,
+                                },
+                            },
+                        },
+                    ],
+                    "Next": "Cooldown",
+                },
+                "Cooldown": { "Type": "Wait", "Seconds": 10, "End": true },
+                "Reject": {
+                    "Type": "Pass",
+                    "Result": 400,
+                    "ResultPath": "$.code",
+                    "Next": "Reject (status)",
+                },
+                "Reject (status)": {
+                    "Type": "Pass",
+                    "Result": "rejected",
+                    "ResultPath": "$.status",
+                    "OutputPath": "$.status",
+                    "End": true,
+                },
+            },
+        }),
+        state_machine
+    );
+
+    // Retriers are reported by the importer, but everything else survives the round trip.
+    let import = asl::import("order", &state_machine).unwrap();
+    assert_eq!(
+        vec!["/States/ValidateOrder/Retry"],
+        import
+            .issues
+            .iter()
+            .map(|issue| issue.pointer.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![
+            "ValidateOrder",
+            "IsValid (end)",
+            "IsValid",
+            "ProcessItems",
+            "Notify",
+            "Cooldown",
+            "Reject",
+            "Reject (status)",
+        ],
+        import
+            .definition
+            .states
+            .iter()
+            .map(State::name)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_export_unsupported() {
+    let unsupported = |update: &dyn Fn(&mut Value)| {
+        let mut definition = serde_json::to_value(order_definition()).unwrap();
+        update(&mut definition);
+        let definition: WorkflowDefinition = serde_json::from_value(definition).unwrap();
+
+        match asl::export(&definition) {
+            Err(travailleur::Error::UnsupportedConversion { pointer, .. }) => pointer,
+            result => panic!("expected UnsupportedConversion error, got {:?}", result),
+        }
+    };
+
+    assert_eq!(
+        "/states/0",
+        unsupported(&|definition| {
+            definition["events"] =
+                json!([{ "name": "OrderReceived", "type": "order", "source": "shop" }]);
+            definition["states"][0] = json!({
+                "name": "ValidateOrder",
+                "type": "event",
+                "onEvents": [{ "eventRefs": ["OrderReceived"] }],
+                "transition": "IsValid",
+            });
+        })
+    );
+    assert_eq!("/keepActive", unsupported(&|definition| definition["keepActive"] = json!(true)));
+    assert_eq!(
+        "/states/0/actions/0/functionRef",
+        unsupported(&|definition| definition["functions"][0]["type"] = json!("rest"))
+    );
+    assert_eq!(
+        "/states/0/timeouts/actionExecTimeout",
+        unsupported(&|definition| {
+            definition["states"][0]["timeouts"]["actionExecTimeout"] = json!("PT0.5S");
+        })
+    );
+    assert_eq!(
+        "/states/1/dataConditions/0/condition",
+        unsupported(&|definition| {
+            definition["states"][1]["dataConditions"][0]["condition"] =
+                json!("${ .a | length > 1 }");
+        })
+    );
+    assert_eq!(
+        "/states/2/actions/0/functionRef/arguments/sku",
+        unsupported(&|definition| {
+            definition["states"][2]["actions"][0]["functionRef"]["arguments"]["sku"] =
+                json!("${ .sku }");
+        })
+    );
+    assert_eq!(
+        "/states/4/onErrors",
+        unsupported(&|definition| {
+            definition["states"][4]["onErrors"] = json!([{ "errorRef": "Timeout", "end": true }]);
+        })
+    );
+}
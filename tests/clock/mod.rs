@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde_json::{json, Map};
+use travailleur::clock::{Clock, SystemClock, TestClock};
+use travailleur::workflow::definition::WorkflowDefinition;
+use travailleur::workflow::executor::InvocationContext;
+use travailleur::workflow::instance::WorkflowInstance;
+use travailleur::workflow::subscription::{EventSubscription, SubscriptionRegistry};
+
+fn start() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+#[test]
+fn test_test_clock() {
+    let clock = TestClock::new(start());
+    let instant = clock.instant();
+    assert_eq!(start(), clock.now());
+    assert_eq!(Duration::ZERO, clock.elapsed());
+
+    let shared = clock.clone();
+    shared.advance(Duration::from_secs(90));
+    assert_eq!(start() + TimeDelta::seconds(90), clock.now());
+    assert_eq!(instant + Duration::from_secs(90), clock.instant());
+    assert_eq!(Duration::from_secs(90), clock.elapsed());
+
+    assert_eq!(DateTime::UNIX_EPOCH, TestClock::default().now());
+}
+
+#[test]
+fn test_test_clock_sleep() {
+    let clock = TestClock::new(start());
+    let instant = clock.instant();
+
+    clock.sleep(Duration::from_secs(3600));
+    assert_eq!(start() + TimeDelta::hours(1), clock.now());
+    assert_eq!(instant + Duration::from_secs(3600), clock.instant());
+}
+
+#[test]
+fn test_system_clock() {
+    let before = Utc::now();
+    let now = SystemClock.now();
+    assert!(now >= before);
+}
+
+#[test]
+fn test_invocation_context_timeout() {
+    let definition: WorkflowDefinition = serde_json::from_value(json!({
+        "id": "sleepy",
+        "specVersion": "0.8",
+        "states": [{ "name": "Sleep", "type": "sleep", "duration": "PT1M", "end": true }]
+    }))
+    .unwrap();
+    let clock = TestClock::new(start());
+
+    let context =
+        InvocationContext::new(&definition).with_timeout_on(&clock, Duration::from_secs(30));
+    assert_eq!(Some(Duration::from_secs(30)), context.remaining_on(&clock));
+
+    clock.advance(Duration::from_secs(20));
+    assert_eq!(Some(Duration::from_secs(10)), context.remaining_on(&clock));
+
+    clock.advance(Duration::from_secs(20));
+    assert_eq!(Some(Duration::ZERO), context.remaining_on(&clock));
+}
+
+#[test]
+fn test_subscription_expiry() {
+    let definition: WorkflowDefinition = serde_json::from_value(json!({
+        "id": "approval",
+        "specVersion": "0.8",
+        "events": [{ "name": "Approved", "type": "approval.approved" }],
+        "states": [{
+            "name": "AwaitApproval",
+            "type": "event",
+            "timeouts": { "eventTimeout": "PT1H" },
+            "onEvents": [{ "eventRefs": ["Approved"] }],
+            "end": true
+        }]
+    }))
+    .unwrap();
+    let clock = TestClock::new(start());
+
+    let instance = WorkflowInstance::for_definition(&definition, Some(Map::new()));
+    let mut registry = SubscriptionRegistry::new();
+    for subscription in
+        EventSubscription::for_instance(&definition, &instance, clock.now()).unwrap()
+    {
+        registry.subscribe(subscription);
+    }
+
+    clock.advance(Duration::from_secs(59 * 60));
+    assert!(registry.expire(&clock.now()).is_empty());
+
+    clock.advance(Duration::from_secs(60));
+    assert_eq!(1, registry.expire(&clock.now()).len());
+    assert!(registry.is_empty());
+}
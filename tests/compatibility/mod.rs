@@ -0,0 +1,111 @@
+use serde_json::json;
+use travailleur::workflow::compatibility::Feature;
+use travailleur::workflow::definition::WorkflowDefinition;
+
+fn definition() -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "orders",
+        "specVersion": "0.8",
+        "functions": [{ "name": "ship", "operation": "ship", "type": "custom" }],
+        "events": [
+            { "name": "Paid", "source": "payments", "type": "paid" },
+            { "name": "Cancelled", "source": "orders", "type": "cancelled", "kind": "produced" },
+        ],
+        "states": [
+            {
+                "name": "Wait",
+                "type": "event",
+                "onEvents": [{ "eventRefs": ["Paid"], "actions": [{ "functionRef": "ship" }] }],
+                "transition": "Ship",
+            },
+            {
+                "name": "Ship",
+                "type": "operation",
+                "stateDataFilter": { "output": "${ .order }" },
+                "actions": [
+                    { "functionRef": "ship", "retryRef": "default" },
+                    { "subFlowRef": "invoice" },
+                ],
+                "compensatedBy": "Cancel",
+                "end": { "continueAs": "orders" },
+            },
+            {
+                "name": "Cancel",
+                "type": "inject",
+                "data": { "cancelled": true },
+                "usedForCompensation": true,
+                "end": { "terminate": true, "produceEvents": [{ "eventRef": "Cancelled" }] },
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_compatibility_report() {
+    let report = definition().compatibility_report();
+    let issues: Vec<_> = report
+        .issues
+        .iter()
+        .map(|issue| (issue.feature, issue.pointer.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            (Feature::OperationStates, "/states/1"),
+            (Feature::SubflowActions, "/states/1"),
+            (Feature::ActionRetries, "/states/1"),
+            (Feature::ProducedEvents, "/states/2"),
+        ],
+        issues
+    );
+    assert!(!report.is_fully_supported());
+    assert_eq!(
+        vec!["/states/1"],
+        report
+            .with_feature(Feature::SubflowActions)
+            .map(|issue| issue.pointer.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(4, report.features().len());
+    assert_eq!(
+        "/states/1: state 'Ship': actions calling sub-workflows are not supported",
+        report.issues[1].to_string()
+    );
+}
+
+#[test]
+fn test_fully_supported() {
+    let definition: WorkflowDefinition = serde_json::from_value(json!({
+        "id": "orders",
+        "specVersion": "0.8",
+        "functions": [{ "name": "ship", "operation": "ship", "type": "custom" }],
+        "events": [{ "name": "Paid", "source": "payments", "type": "paid" }],
+        "states": [
+            {
+                "name": "Wait",
+                "type": "event",
+                "onEvents": [{
+                    "eventRefs": ["Paid"],
+                    "actions": [{ "functionRef": "ship", "sleep": { "before": "PT1S" } }],
+                }],
+                "transition": "Pause",
+            },
+            { "name": "Pause", "type": "sleep", "duration": "PT5M", "end": true },
+        ],
+    }))
+    .unwrap();
+
+    let report = definition.compatibility_report();
+    assert!(report.is_fully_supported());
+    assert!(report.features().is_empty());
+}
+
+#[test]
+fn test_feature() {
+    assert_eq!("subflow-actions", Feature::SubflowActions.id());
+    assert_eq!("produced-events", Feature::ProducedEvents.to_string());
+    assert_eq!(
+        "https://github.com/clechasseur/travailleur/issues?q=is%3Aissue+%22action-retries%22",
+        Feature::ActionRetries.tracking_url()
+    );
+}
@@ -1 +1,30 @@
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+mod asl;
+mod clock;
+mod compatibility;
+mod errors;
+mod events;
 mod examples;
+mod executor;
+mod extensions;
+mod external;
+mod filters;
+mod instance;
+mod loader;
+mod metadata;
+mod migration;
+mod normalize;
+mod openapi;
+mod preflight;
+mod registry;
+mod retries;
+mod runtime;
+mod schedules;
+#[cfg(feature = "schemars")]
+mod schema;
+mod simulation;
+mod sleep;
+mod subscription;
+mod timeouts;
+mod versioned;
@@ -0,0 +1,98 @@
+use serde_json::json;
+use travailleur::workflow::definition::{End, WorkflowDefinition};
+use travailleur::workflow::runtime::errors::{error_handler, ErrorRoute};
+
+fn definition() -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "orders",
+        "specVersion": "0.8",
+        "functions": [{ "name": "charge", "operation": "charge", "type": "custom" }],
+        "errors": [
+            { "name": "ServiceUnavailable", "code": "503" },
+            { "name": "InvalidCard", "code": "402" },
+            { "name": "Fraud" },
+        ],
+        "states": [
+            {
+                "name": "Charge",
+                "type": "operation",
+                "actions": [{ "functionRef": "charge" }],
+                "onErrors": [
+                    { "errorRef": "*", "transition": "Fallback" },
+                    { "errorRefs": ["InvalidCard", "Fraud"], "end": { "terminate": true } },
+                    { "errorRef": "ServiceUnavailable", "transition": "Retry" },
+                ],
+                "end": true,
+            },
+            {
+                "name": "Fallback",
+                "type": "operation",
+                "actions": [{ "functionRef": "charge" }],
+                "onErrors": [{ "errorRef": "Fraud", "end": true }],
+                "end": true,
+            },
+            { "name": "Retry", "type": "inject", "data": {}, "end": true },
+        ],
+    }))
+    .unwrap()
+}
+
+fn failure(error: &str) -> travailleur::Error {
+    travailleur::Error::ActionFailed {
+        function: "charge".into(),
+        error: error.into(),
+        reason: "failed".into(),
+    }
+}
+
+#[test]
+fn test_error_route() {
+    let definition = definition();
+    let charge = &definition.states[0];
+
+    let route = ErrorRoute::resolve(&definition, charge, &failure("503"));
+    assert!(
+        matches!(route, ErrorRoute::Transition(transition) if transition.next_state() == "Retry")
+    );
+    assert!(route.is_handled());
+
+    let route = ErrorRoute::resolve(&definition, charge, &failure("InvalidCard"));
+    assert!(matches!(route, ErrorRoute::End(End::Complex { terminate: true, .. })));
+    let route = ErrorRoute::for_workflow_error(&definition, charge, "402");
+    assert!(matches!(route, ErrorRoute::End(_)));
+
+    let route = ErrorRoute::for_workflow_error(&definition, charge, "Timeout");
+    assert!(
+        matches!(route, ErrorRoute::Transition(transition) if transition.next_state() == "Fallback")
+    );
+}
+
+#[test]
+fn test_unhandled_errors() {
+    let definition = definition();
+
+    let route = ErrorRoute::for_workflow_error(&definition, &definition.states[1], "503");
+    assert_eq!(ErrorRoute::Unhandled, route);
+    assert!(!route.is_handled());
+
+    let route = ErrorRoute::for_workflow_error(&definition, &definition.states[2], "Fraud");
+    assert_eq!(ErrorRoute::Unhandled, route);
+
+    let error = travailleur::Error::UnknownFunction { function: "charge".into() };
+    assert_eq!(
+        ErrorRoute::Unhandled,
+        ErrorRoute::resolve(&definition, &definition.states[0], &error)
+    );
+}
+
+#[test]
+fn test_error_handler() {
+    let definition = definition();
+    let charge = &definition.states[0];
+
+    let handler = error_handler(&definition, charge, "503").unwrap();
+    assert_eq!(Some("ServiceUnavailable"), handler.error_ref.as_deref());
+    assert!(charge.error_handler("503").unwrap().is_wildcard());
+
+    assert!(error_handler(&definition, &definition.states[1], "503").is_none());
+}
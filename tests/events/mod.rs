@@ -0,0 +1,123 @@
+use serde_json::{json, Value};
+use travailleur::workflow::definition::events::EventDef;
+
+fn event_def(data_only: bool) -> EventDef {
+    serde_json::from_value(json!({
+        "name": "applicantInfo",
+        "type": "org.application.info",
+        "source": "applicationssource",
+        "dataOnly": data_only,
+    }))
+    .unwrap()
+}
+
+fn event() -> Value {
+    json!({
+        "specversion": "1.0",
+        "id": "A234-1234-1234",
+        "source": "applicationssource",
+        "type": "org.application.info",
+        "datacontenttype": "application/json",
+        "data": { "name": "John", "age": 42 },
+    })
+}
+
+#[test]
+fn test_consumed_event_data_data_only() {
+    assert_eq!(json!({ "name": "John", "age": 42 }), event_def(true).consumed_event_data(&event()));
+
+    let mut event = event();
+    event.as_object_mut().unwrap().remove("data");
+    assert_eq!(Value::Null, event_def(true).consumed_event_data(&event));
+}
+
+#[test]
+fn test_consumed_event_data_with_context_attributes() {
+    assert_eq!(event(), event_def(false).consumed_event_data(&event()));
+}
+
+#[test]
+fn test_data_only_defaults_to_true() {
+    let event_def: EventDef = serde_json::from_value(json!({
+        "name": "applicantInfo",
+        "type": "org.application.info",
+        "source": "applicationssource",
+    }))
+    .unwrap();
+
+    assert!(event_def.data_only);
+}
+
+#[cfg(feature = "cloudevents")]
+mod cloud_events {
+    use cloudevents::{AttributesReader, Event, EventBuilder, EventBuilderV10};
+    use serde_json::json;
+    use travailleur::workflow::definition::events::EventDef;
+    use travailleur::workflow::definition::{ProduceEventDef, WorkflowDefinition};
+
+    fn cloud_event(source: &str, region: Option<&str>) -> Event {
+        let mut builder = EventBuilderV10::new()
+            .id("A234-1234-1234")
+            .source(source)
+            .ty("org.application.info")
+            .data("application/json", json!({ "name": "John", "age": 42 }));
+        if let Some(region) = region {
+            builder = builder.extension("region", region);
+        }
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn test_matches_cloud_event() {
+        let event_def: EventDef = serde_json::from_value(json!({
+            "name": "applicantInfo",
+            "type": "org.application.info",
+            "source": "applicationssource",
+            "correlation": [{ "contextAttributeName": "region", "contextAttributeValue": "east" }],
+        }))
+        .unwrap();
+
+        assert!(event_def.matches_cloud_event(&cloud_event("applicationssource", Some("east"))));
+        assert!(!event_def.matches_cloud_event(&cloud_event("applicationssource", Some("west"))));
+        assert!(!event_def.matches_cloud_event(&cloud_event("applicationssource", None)));
+        assert!(!event_def.matches_cloud_event(&cloud_event("othersource", Some("east"))));
+
+        assert_eq!(
+            json!({ "name": "John", "age": 42 }),
+            event_def
+                .consumed_cloud_event_data(&cloud_event("applicationssource", None))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_to_cloud_event() {
+        let definition: WorkflowDefinition = serde_json::from_value(json!({
+            "id": "applicant",
+            "specVersion": "0.8",
+            "events": [
+                { "name": "applicantProcessed", "type": "org.application.processed", "kind": "produced" },
+            ],
+            "states": [{ "name": "Start", "type": "inject", "data": {}, "end": true }],
+        }))
+        .unwrap();
+        let produce: ProduceEventDef = serde_json::from_value(json!({
+            "eventRef": "applicantProcessed",
+            "data": { "approved": true },
+            "contextAttributes": { "region": "east" },
+        }))
+        .unwrap();
+
+        let event = produce.to_cloud_event(&definition, None).unwrap();
+        assert_eq!("org.application.processed", event.ty());
+        assert_eq!("applicant", event.source());
+        assert_eq!(Some("east".to_string()), event.extension("region").map(ToString::to_string));
+        assert_eq!(json!({ "approved": true }), serde_json::to_value(&event).unwrap()["data"]);
+
+        let produce = ProduceEventDef { event_ref: "unknown".into(), ..produce };
+        assert!(matches!(
+            produce.to_cloud_event(&definition, None),
+            Err(travailleur::Error::UnknownEvent { .. })
+        ));
+    }
+}
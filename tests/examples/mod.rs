@@ -1,4 +1,9 @@
+use std::fs;
 use std::path::PathBuf;
+use std::rc::Rc;
+
+use travailleur::cache::DefinitionCache;
+use travailleur::workflow::definition::WorkflowDefinition;
 
 fn examples_path() -> PathBuf {
     [env!("CARGO_MANIFEST_DIR"), "tests", "resources", "definitions", "examples"]
@@ -6,6 +11,27 @@ fn examples_path() -> PathBuf {
         .collect()
 }
 
+// Loads all example workflow definitions, along with the name of the file they were loaded from.
+fn examples() -> Vec<(String, Rc<WorkflowDefinition>)> {
+    let mut cache = DefinitionCache::new();
+    let mut paths: Vec<_> = fs::read_dir(examples_path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| cfg!(feature = "yaml") || path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let definition = cache
+                .get_or_insert(format!("file://{}", path.to_string_lossy()).as_str())
+                .unwrap();
+            (path.file_name().unwrap().to_string_lossy().into_owned(), definition)
+        })
+        .collect()
+}
+
 macro_rules! test_files {
     ( $id:ident[$format:ident] ) => {
         paste::paste! {
@@ -66,3 +92,43 @@ test_files! {
     notifycustomerworkflow,
     customerbankingtransactions,
 }
+
+#[test]
+fn test_examples_round_trip() {
+    for (file_name, definition) in examples() {
+        let round_trip: WorkflowDefinition =
+            serde_json::from_value(serde_json::to_value(&*definition).unwrap()).unwrap();
+        assert_eq!(*definition, round_trip, "{} did not survive a round trip", file_name);
+    }
+}
+
+#[test]
+fn test_examples_normalize() {
+    for (file_name, definition) in examples() {
+        let normalized = definition.normalize();
+        assert_eq!(
+            normalized,
+            normalized.normalize(),
+            "normalizing {} is not idempotent",
+            file_name
+        );
+
+        let round_trip: WorkflowDefinition =
+            serde_json::from_value(serde_json::to_value(&normalized).unwrap()).unwrap();
+        assert_eq!(normalized, round_trip, "normalized {} did not survive a round trip", file_name);
+    }
+}
+
+#[test]
+fn test_examples_minify() {
+    for (file_name, definition) in examples() {
+        let minified: WorkflowDefinition =
+            serde_json::from_value(definition.minify().unwrap()).unwrap();
+        assert_eq!(
+            definition.normalize(),
+            minified.normalize(),
+            "minified {} is not equivalent to the original",
+            file_name
+        );
+    }
+}
@@ -0,0 +1,802 @@
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Map, Value};
+use travailleur::workflow::definition::common::InvocationMode;
+use travailleur::workflow::definition::{FunctionRef, WorkflowDefinition};
+use travailleur::workflow::executor::graphql::GraphQlActionExecutor;
+use travailleur::workflow::executor::http::{EgressPolicy, HttpRequest, UrlPattern};
+use travailleur::workflow::executor::rest::RestActionExecutor;
+use travailleur::workflow::executor::script::{ScriptActionExecutor, ScriptSource};
+use travailleur::workflow::executor::wasm::WasmActionExecutor;
+use travailleur::workflow::executor::{
+    ActionExecutor, AsyncActionExecutor, AsyncAdapter, BlockingAdapter, ExecuteFuture,
+    FunctionCall, HandlerActionExecutor, InvocationContext, MockActionExecutor, NoopActionExecutor,
+    OversizedResult, PooledActionExecutor, SizeLimitActionExecutor, RESULT_TOO_LARGE_ERROR,
+};
+use travailleur::workflow::instance::WorkflowInstance;
+
+fn definition() -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "payment",
+        "specVersion": "0.8",
+        "functions": [
+            { "name": "charge", "operation": "file://api.json#charge", "authRef": "token" },
+            { "name": "refund", "operation": "file://api.json#refund", "authRef": "missing" },
+            { "name": "notify", "operation": "file://api.json#notify" },
+        ],
+        "auth": [
+            { "name": "token", "scheme": "bearer", "properties": { "token": "secret" } },
+        ],
+        "states": [
+            {
+                "name": "Charge",
+                "type": "operation",
+                "actions": [{ "functionRef": "charge" }],
+                "end": true,
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+fn arguments() -> Map<String, Value> {
+    json!({ "amount": 42 }).as_object().unwrap().clone()
+}
+
+#[test]
+fn test_function_call() {
+    let definition = definition();
+
+    let call =
+        FunctionCall::new(&definition, &FunctionRef::ByName("charge".into()), arguments()).unwrap();
+    assert_eq!("charge", call.function.name);
+    assert_eq!(Some("token"), call.auth.map(|auth| auth.name.as_str()));
+    assert_eq!(arguments(), call.arguments);
+
+    let call =
+        FunctionCall::new(&definition, &FunctionRef::ByName("notify".into()), Map::new()).unwrap();
+    assert!(call.auth.is_none());
+
+    assert!(matches!(
+        FunctionCall::new(&definition, &FunctionRef::ByName("unknown".into()), Map::new()),
+        Err(travailleur::Error::UnknownFunction { function }) if function == "unknown"
+    ));
+    assert!(matches!(
+        FunctionCall::new(&definition, &FunctionRef::ByName("refund".into()), Map::new()),
+        Err(travailleur::Error::UnknownAuth { auth }) if auth == "missing"
+    ));
+}
+
+#[test]
+fn test_invocation_context() {
+    let definition = definition();
+
+    let call =
+        FunctionCall::new(&definition, &FunctionRef::ByName("charge".into()), arguments()).unwrap();
+    assert_eq!(Some("payment"), call.context.workflow_id.as_deref());
+    assert_eq!(1, call.context.attempt);
+    assert!(call.context.instance_id.is_none());
+    assert!(call.context.remaining().is_none());
+
+    let instance = WorkflowInstance::for_definition(&definition, None);
+    let context = InvocationContext::new(&definition)
+        .with_instance(&instance)
+        .with_action("chargeCard")
+        .with_attempt(2)
+        .with_idempotency_key("charge-42");
+    assert_eq!(Some(instance.id.as_str()), context.instance_id.as_deref());
+    assert_eq!(Some("Charge"), context.state.as_deref());
+    assert_eq!(Some("chargeCard"), context.action.as_deref());
+    assert_eq!(2, context.attempt);
+    assert_eq!(Some("charge-42"), context.idempotency_key.as_deref());
+
+    let call = call.with_context(context.clone());
+    assert_eq!(context, call.context);
+
+    let context = context.with_timeout(Duration::from_secs(60));
+    assert!(context.remaining().unwrap() > Duration::from_secs(30));
+    let context = context.with_deadline(Instant::now());
+    assert_eq!(Some(Duration::ZERO), context.remaining());
+}
+
+#[test]
+fn test_executors() {
+    let definition = definition();
+    let call = |name: &str| {
+        FunctionCall::new(&definition, &FunctionRef::ByName(name.into()), arguments()).unwrap()
+    };
+
+    assert_eq!(Value::Null, NoopActionExecutor.execute(&call("charge")).unwrap());
+
+    let executor = MockActionExecutor::new()
+        .with_result("charge", json!({ "receipt": "r-1" }))
+        .with_failure("notify", "NotificationFailed");
+    assert_eq!(json!({ "receipt": "r-1" }), executor.execute(&call("charge")).unwrap());
+    assert!(matches!(
+        executor.execute(&call("notify")),
+        Err(travailleur::Error::ActionFailed { function, error, .. })
+            if function == "notify" && error == "NotificationFailed"
+    ));
+}
+
+#[test]
+fn test_handler_executor() {
+    let definition = definition();
+    let call = |name: &str| {
+        FunctionCall::new(&definition, &FunctionRef::ByName(name.into()), arguments()).unwrap()
+    };
+
+    let executor = HandlerActionExecutor::new()
+        .with_handler("charge", |call| Ok(json!({ "charged": call.arguments["amount"] })));
+    assert_eq!(json!({ "charged": 42 }), executor.execute(&call("charge")).unwrap());
+    assert!(matches!(
+        executor.execute(&call("notify")),
+        Err(travailleur::Error::MissingFunctionHandler { function }) if function == "notify"
+    ));
+
+    let executor = executor.with_fallback(NoopActionExecutor);
+    assert_eq!(Value::Null, executor.execute(&call("notify")).unwrap());
+}
+
+#[test]
+fn test_size_limit_executor() {
+    let definition = definition();
+    let call = |name: &str| {
+        FunctionCall::new(&definition, &FunctionRef::ByName(name.into()), arguments()).unwrap()
+    };
+
+    // {"receipt":"r-1"} is 17 bytes long.
+    let executor = SizeLimitActionExecutor::new(
+        MockActionExecutor::new()
+            .with_result("charge", json!({ "receipt": "r-1" }))
+            .with_result("notify", json!("ok")),
+        10,
+    );
+    assert_eq!(json!("ok"), executor.execute(&call("notify")).unwrap());
+    assert!(matches!(
+        executor.execute(&call("charge")),
+        Err(travailleur::Error::ActionResultTooLarge { function, size: 17, limit: 10 })
+            if function == "charge"
+    ));
+
+    let executor = executor.with_policy(OversizedResult::Fail);
+    assert!(matches!(
+        executor.execute(&call("charge")),
+        Err(travailleur::Error::ActionFailed { error, .. }) if error == RESULT_TOO_LARGE_ERROR
+    ));
+
+    let executor = executor.with_policy(OversizedResult::Discard);
+    assert_eq!(Value::Null, executor.execute(&call("charge")).unwrap());
+}
+
+// Executor recording the maximum number of functions called concurrently.
+#[derive(Default)]
+struct ConcurrencyExecutor {
+    active: AtomicUsize,
+    max: AtomicUsize,
+}
+
+impl ConcurrencyExecutor {
+    fn reset(&self) -> usize {
+        self.max.swap(0, Ordering::SeqCst)
+    }
+}
+
+impl ActionExecutor for &ConcurrencyExecutor {
+    fn execute(&self, _call: &FunctionCall<'_>) -> travailleur::Result<Value> {
+        let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max.fetch_max(active, Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(20));
+        self.active.fetch_sub(1, Ordering::SeqCst);
+        Ok(Value::Null)
+    }
+}
+
+#[test]
+fn test_pooled_executor() {
+    let annotated = |annotations: &[&str]| {
+        let mut definition = definition();
+        definition.annotations = Some(annotations.iter().map(|a| a.to_string()).collect());
+        definition
+    };
+    let gpu = annotated(&["ml", "gpu"]);
+    let io = annotated(&["io-heavy", "gpu"]);
+    let other = annotated(&["ml"]);
+    let none = definition();
+    fn call(definition: &WorkflowDefinition) -> FunctionCall<'_> {
+        FunctionCall::new(definition, &FunctionRef::ByName("notify".into()), Map::new()).unwrap()
+    }
+    assert_eq!(vec!["ml", "gpu"], call(&gpu).context.annotations);
+
+    let inner = ConcurrencyExecutor::default();
+    let executor = PooledActionExecutor::new(&inner)
+        .with_pool("gpu", NonZeroUsize::new(2).unwrap())
+        .with_pool("io-heavy", NonZeroUsize::new(4).unwrap())
+        .with_pool("gpu", NonZeroUsize::new(1).unwrap())
+        .with_default_concurrency(NonZeroUsize::new(2).unwrap());
+    assert_eq!(Some("gpu"), executor.pool(&call(&gpu)));
+    assert_eq!(Some("gpu"), executor.pool(&call(&io)));
+    assert_eq!(None, executor.pool(&call(&other)));
+    assert_eq!(None, executor.pool(&call(&none)));
+
+    let run = |definition: &WorkflowDefinition| {
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| executor.execute(&call(definition)).unwrap());
+            }
+        });
+        inner.reset()
+    };
+    assert_eq!(1, run(&gpu));
+    assert!(run(&other) <= 2);
+    assert_eq!(0, inner.active.load(Ordering::SeqCst));
+}
+
+// Executor yielding once before returning the function's operation, to make sure
+// blocking adapters handle pending futures.
+struct YieldingExecutor;
+
+impl AsyncActionExecutor for YieldingExecutor {
+    fn execute<'a>(&'a self, call: &'a FunctionCall<'_>) -> ExecuteFuture<'a> {
+        let mut yielded = false;
+        Box::pin(std::future::poll_fn(move |context| {
+            if yielded {
+                std::task::Poll::Ready(Ok(json!(call.function.operation)))
+            } else {
+                yielded = true;
+                context.waker().wake_by_ref();
+                std::task::Poll::Pending
+            }
+        }))
+    }
+}
+
+#[test]
+fn test_async_executors() {
+    let definition = definition();
+    let call = |name: &str| {
+        FunctionCall::new(&definition, &FunctionRef::ByName(name.into()), arguments()).unwrap()
+    };
+
+    let executor = BlockingAdapter(YieldingExecutor);
+    assert_eq!(json!("file://api.json#charge"), executor.execute(&call("charge")).unwrap());
+
+    let executor = BlockingAdapter(AsyncAdapter(
+        MockActionExecutor::new().with_result("charge", json!({ "receipt": "r-1" })),
+    ));
+    assert_eq!(json!({ "receipt": "r-1" }), executor.execute(&call("charge")).unwrap());
+}
+
+fn petstore_definition() -> WorkflowDefinition {
+    let path: PathBuf =
+        [env!("CARGO_MANIFEST_DIR"), "tests", "resources", "openapi", "petstore.json"]
+            .iter()
+            .collect();
+    let uri = format!("file://{}", path.to_string_lossy());
+
+    serde_json::from_value(json!({
+        "id": "petstore",
+        "specVersion": "0.8",
+        "functions": [
+            { "name": "listPets", "operation": format!("{}#listPets", uri), "authRef": "basic" },
+            { "name": "createPet", "operation": format!("{}#createPet", uri), "authRef": "bearer" },
+            { "name": "showPetById", "operation": format!("{}#showPetById", uri) },
+            { "name": "unknown", "operation": format!("{}#unknown", uri) },
+            { "name": "greet", "operation": "greet", "type": "expression" },
+        ],
+        "auth": [
+            {
+                "name": "basic",
+                "scheme": "basic",
+                "properties": { "username": "user", "password": "pass" },
+            },
+            { "name": "bearer", "scheme": "bearer", "properties": { "token": "secret" } },
+        ],
+        "states": [
+            {
+                "name": "List",
+                "type": "operation",
+                "actions": [{ "functionRef": "listPets" }],
+                "end": true,
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_rest_request() {
+    let definition = petstore_definition();
+    let executor = RestActionExecutor::new();
+    let request = |name: &str, arguments: Value| {
+        let call = FunctionCall::new(
+            &definition,
+            &FunctionRef::ByName(name.into()),
+            arguments.as_object().unwrap().clone(),
+        )
+        .unwrap();
+        executor.request(&call)
+    };
+
+    assert_eq!(
+        HttpRequest {
+            method: "GET",
+            url: "https://petstore.example.com/v1/pets?limit=10"
+                .parse()
+                .unwrap(),
+            headers: vec![
+                ("X-Request-Id".into(), "r-1".into()),
+                ("Authorization".into(), "Basic dXNlcjpwYXNz".into()),
+            ],
+            body: None,
+            timeout: None,
+        },
+        request("listPets", json!({ "limit": 10, "X-Request-Id": "r-1", "ignored": true }))
+            .unwrap()
+    );
+    assert_eq!(
+        HttpRequest {
+            method: "POST",
+            url: "https://petstore.example.com/v1/pets".parse().unwrap(),
+            headers: vec![("Authorization".into(), "Bearer secret".into())],
+            body: Some(json!({ "name": "Rex" })),
+            timeout: None,
+        },
+        request("createPet", json!({ "name": "Rex" })).unwrap()
+    );
+    assert_eq!(
+        "https://petstore.example.com/v1/pets/rex%201",
+        request("showPetById", json!({ "petId": "rex 1" }))
+            .unwrap()
+            .url
+            .as_str()
+    );
+
+    assert!(matches!(
+        request("listPets", json!({})),
+        Err(travailleur::Error::MissingFunctionArgument { function, argument })
+            if function == "listPets" && argument == "X-Request-Id"
+    ));
+    assert!(matches!(
+        request("unknown", json!({})),
+        Err(travailleur::Error::InvalidFunctionOperation { function, .. }) if function == "unknown"
+    ));
+    assert!(matches!(
+        request("greet", json!({})),
+        Err(travailleur::Error::InvalidFunctionOperation { function, .. }) if function == "greet"
+    ));
+
+    let call = FunctionCall::new(
+        &definition,
+        &FunctionRef::ByName("createPet".into()),
+        json!({ "name": "Rex" }).as_object().unwrap().clone(),
+    )
+    .unwrap();
+    let call = call.clone().with_context(
+        call.context
+            .with_idempotency_key("pet-rex")
+            .with_timeout(Duration::from_secs(60)),
+    );
+    let request = executor.request(&call).unwrap();
+    assert!(request
+        .headers
+        .contains(&("Idempotency-Key".into(), "pet-rex".into())));
+    assert!(request.timeout.is_some());
+}
+
+#[test]
+fn test_url_pattern() {
+    let matches = |pattern: &str, url: &str| {
+        pattern
+            .parse::<UrlPattern>()
+            .unwrap()
+            .matches(&url.parse().unwrap())
+    };
+
+    assert!(matches("api.example.com", "https://API.example.com/v1"));
+    assert!(!matches("api.example.com", "https://example.com/"));
+    assert!(matches("*.example.com", "http://a.b.example.com/"));
+    assert!(!matches("*.example.com", "https://example.com/"));
+    assert!(!matches("*.example.com", "https://badexample.com/"));
+    assert!(matches("*", "https://anything.test/"));
+    assert!(matches("https://api.example.com:443/v1/", "https://api.example.com/v1/pets"));
+    assert!(!matches("https://api.example.com/v1/", "http://api.example.com/v1/pets"));
+    assert!(!matches("api.example.com:8443", "https://api.example.com/"));
+    assert!(!matches("api.example.com/v1/", "https://api.example.com/v2/pets"));
+    assert!(matches("[::1]:8080", "http://[::1]:8080/"));
+
+    assert_eq!(
+        "https://api.example.com:8443/v1/",
+        "HTTPS://API.example.com:8443/v1/"
+            .parse::<UrlPattern>()
+            .unwrap()
+            .to_string()
+    );
+    for invalid in ["", "://example.com", "example.com:port", "api.*.com", "*.", "**.example.com"] {
+        assert!(
+            matches!(
+                invalid.parse::<UrlPattern>(),
+                Err(travailleur::Error::InvalidUrlPattern { .. })
+            ),
+            "'{}' should not be a valid URL pattern",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn test_egress_policy() {
+    let url = |url: &str| url.parse().unwrap();
+    let pattern = |pattern: &str| pattern.parse::<UrlPattern>().unwrap();
+
+    let policy = EgressPolicy::default().deny(pattern("*.internal"));
+    assert!(policy.allows(&url("https://api.example.com/")));
+    assert!(!policy.allows(&url("http://db.internal/")));
+
+    let policy = EgressPolicy::deny_by_default()
+        .allow(pattern("*.example.com"))
+        .deny(pattern("admin.example.com"));
+    assert!(policy.allows(&url("https://petstore.example.com/v1/pets")));
+    assert!(!policy.allows(&url("https://admin.example.com/")));
+    assert!(!policy.allows(&url("https://evil.test/")));
+
+    let definition = petstore_definition();
+    let call = |name: &str, arguments: Value| {
+        FunctionCall::new(
+            &definition,
+            &FunctionRef::ByName(name.into()),
+            arguments.as_object().unwrap().clone(),
+        )
+        .unwrap()
+    };
+    let executor = RestActionExecutor::new().with_egress_policy(
+        EgressPolicy::deny_by_default().allow(pattern("petstore.example.com/v1/pets/")),
+    );
+    assert!(executor
+        .request(&call("showPetById", json!({ "petId": "rex" })))
+        .is_ok());
+    assert!(matches!(
+        executor.request(&call("createPet", json!({ "name": "Rex" }))),
+        Err(travailleur::Error::EgressDenied { function, url })
+            if function == "createPet" && url.as_str() == "https://petstore.example.com/v1/pets"
+    ));
+    #[cfg(feature = "rest")]
+    {
+        assert!(executor.check(&call("showPetById", json!({}))).is_ok());
+        assert!(matches!(
+            executor.check(&call("createPet", json!({}))),
+            Err(travailleur::Error::EgressDenied { .. })
+        ));
+    }
+
+    let definition = graphql_definition("https://pets.example.com/graphql");
+    let executor = GraphQlActionExecutor::new().with_egress_policy(EgressPolicy::deny_by_default());
+    assert!(matches!(
+        executor.request(&graphql_call(&definition, "addPet", json!({}), None)),
+        Err(travailleur::Error::EgressDenied { .. })
+    ));
+}
+
+// Serves a single canned JSON response on a local port. Returns the server's URL and a handle
+// returning the request line and body that were received.
+#[cfg(any(feature = "graphql", feature = "rest"))]
+fn serve_once(response: Value) -> (String, std::thread::JoinHandle<(String, String)>) {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let server = format!("http://{}/api", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut content_length = 0;
+        let mut line = String::new();
+        while reader.read_line(&mut line).unwrap() > 2 {
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            line.clear();
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+
+        let response = response.to_string();
+        write!(
+            reader.get_mut(),
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            response.len(),
+            response
+        )
+        .unwrap();
+        (request_line, String::from_utf8(body).unwrap())
+    });
+
+    (server, handle)
+}
+
+#[cfg(feature = "rest")]
+#[test]
+fn test_rest_executor() {
+    let (server, handle) = serve_once(json!({ "id": "rex", "name": "Rex" }));
+
+    let definition = petstore_definition();
+    let call = FunctionCall::new(
+        &definition,
+        &FunctionRef::ByName("showPetById".into()),
+        json!({ "petId": "rex" }).as_object().unwrap().clone(),
+    )
+    .unwrap();
+    let executor = RestActionExecutor::new().with_server(server.parse().unwrap());
+    assert_eq!(json!({ "id": "rex", "name": "Rex" }), executor.execute(&call).unwrap());
+    assert_eq!("GET /api/pets/rex HTTP/1.1\r\n", handle.join().unwrap().0);
+}
+
+fn graphql_definition(endpoint: &str) -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "pets",
+        "specVersion": "0.8",
+        "functions": [
+            {
+                "name": "getPet",
+                "operation": format!("{}#query#pet", endpoint),
+                "type": "graphql",
+                "authRef": "bearer",
+            },
+            {
+                "name": "addPet",
+                "operation": format!("{}#mutation#addPet", endpoint),
+                "type": "graphql",
+            },
+            { "name": "invalid", "operation": format!("{}#pet", endpoint), "type": "graphql" },
+        ],
+        "auth": [
+            { "name": "bearer", "scheme": "bearer", "properties": { "token": "secret" } },
+        ],
+        "states": [],
+    }))
+    .unwrap()
+}
+
+fn graphql_call<'a>(
+    definition: &'a WorkflowDefinition,
+    name: &str,
+    arguments: Value,
+    selection_set: Option<&str>,
+) -> FunctionCall<'a> {
+    let function_ref = FunctionRef::Complex {
+        ref_name: name.into(),
+        arguments: None,
+        selection_set: selection_set.map(Into::into),
+        invoke: InvocationMode::Sync,
+    };
+    FunctionCall::new(definition, &function_ref, arguments.as_object().unwrap().clone()).unwrap()
+}
+
+#[test]
+fn test_graphql_request() {
+    let definition = graphql_definition("https://pets.example.com/graphql");
+    let executor = GraphQlActionExecutor::new();
+
+    let call = graphql_call(&definition, "getPet", json!({ "id": "rex" }), Some("{ id name }"));
+    assert_eq!(Some("{ id name }"), call.selection_set.as_deref());
+    assert_eq!(
+        HttpRequest {
+            method: "POST",
+            url: "https://pets.example.com/graphql".parse().unwrap(),
+            headers: vec![("Authorization".into(), "Bearer secret".into())],
+            body: Some(json!({ "query": r#"query { pet(id: "rex") { id name } }"# })),
+            timeout: None,
+        },
+        executor.request(&call).unwrap()
+    );
+
+    let call = graphql_call(
+        &definition,
+        "addPet",
+        json!({ "pet": { "name": "Rex", "tags": ["dog", 1] } }),
+        None,
+    );
+    assert_eq!(
+        Some(json!({ "query": r#"mutation { addPet(pet: {name: "Rex", tags: ["dog", 1]}) }"# })),
+        executor.request(&call).unwrap().body
+    );
+
+    let call = graphql_call(&definition, "invalid", json!({}), None);
+    assert!(matches!(
+        executor.request(&call),
+        Err(travailleur::Error::InvalidFunctionOperation { function, .. }) if function == "invalid"
+    ));
+}
+
+#[test]
+fn test_graphql_result() {
+    let definition = graphql_definition("https://pets.example.com/graphql");
+    let executor = GraphQlActionExecutor::new();
+    let call = graphql_call(&definition, "getPet", json!({ "id": "rex" }), None);
+
+    assert_eq!(
+        json!({ "id": "rex" }),
+        executor
+            .result(&call, json!({ "data": { "pet": { "id": "rex" } } }))
+            .unwrap()
+    );
+    assert!(matches!(
+        executor.result(&call, json!({
+            "data": null,
+            "errors": [{ "message": "not found", "extensions": { "code": "NOT_FOUND" } }],
+        })),
+        Err(travailleur::Error::ActionFailed { error, reason, .. })
+            if error == "NOT_FOUND" && reason == "not found"
+    ));
+    assert!(matches!(
+        executor.result(&call, json!({ "errors": [{ "message": "boom" }] })),
+        Err(travailleur::Error::ActionFailed { error, .. })
+            if error == GraphQlActionExecutor::GRAPHQL_ERROR
+    ));
+}
+
+#[cfg(feature = "graphql")]
+#[test]
+fn test_graphql_executor() {
+    let (server, handle) = serve_once(json!({ "data": { "pet": { "name": "Rex" } } }));
+
+    let definition = graphql_definition(&server);
+    let call = graphql_call(&definition, "getPet", json!({ "id": "rex" }), Some("{ name }"));
+    assert_eq!(json!({ "name": "Rex" }), GraphQlActionExecutor::new().execute(&call).unwrap());
+
+    let (request_line, body) = handle.join().unwrap();
+    assert_eq!("POST /api HTTP/1.1\r\n", request_line);
+    assert_eq!(
+        json!({ "query": r#"query { pet(id: "rex") { name } }"# }),
+        serde_json::from_str::<Value>(&body).unwrap()
+    );
+}
+
+fn wasm_definition() -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "wasm",
+        "specVersion": "0.8",
+        "functions": [
+            { "name": "echo", "operation": "wasm://echo#echo", "type": "custom" },
+            { "name": "invalid", "operation": "echo#echo", "type": "custom" },
+            { "name": "rest", "operation": "wasm://echo#echo" },
+        ],
+        "states": [],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_wasm_operation() {
+    let definition = wasm_definition();
+    let function = |name: &str| {
+        FunctionCall::new(&definition, &FunctionRef::ByName(name.into()), Map::new())
+            .unwrap()
+            .function
+    };
+
+    assert_eq!(("echo", "echo"), WasmActionExecutor::operation(function("echo")).unwrap());
+    for name in ["invalid", "rest"] {
+        assert!(matches!(
+            WasmActionExecutor::operation(function(name)),
+            Err(travailleur::Error::InvalidFunctionOperation { function, .. }) if function == name
+        ));
+    }
+}
+
+#[cfg(feature = "wasm")]
+#[test]
+fn test_wasm_executor() {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "resources", "wasm", "echo.wat"]
+        .iter()
+        .collect();
+    let executor = WasmActionExecutor::new().with_module("echo", path);
+
+    let definition = wasm_definition();
+    let call =
+        FunctionCall::new(&definition, &FunctionRef::ByName("echo".into()), arguments()).unwrap();
+    assert_eq!(json!({ "amount": 42 }), executor.execute(&call).unwrap());
+}
+
+fn script_definition() -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "scripts",
+        "specVersion": "0.8",
+        "functions": [
+            { "name": "double", "operation": "script:input.amount * 2", "type": "custom" },
+            { "name": "total", "operation": "script://total.rhai", "type": "custom" },
+            { "name": "greet", "operation": "script://greet", "type": "custom" },
+            { "name": "missing", "operation": "script://../total.rhai", "type": "custom" },
+            { "name": "rest", "operation": "script:1" },
+        ],
+        "states": [],
+    }))
+    .unwrap()
+}
+
+fn script_dir() -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "tests", "resources", "scripts"]
+        .iter()
+        .collect()
+}
+
+#[test]
+fn test_script_operation() {
+    let definition = script_definition();
+    let function = |name: &str| {
+        FunctionCall::new(&definition, &FunctionRef::ByName(name.into()), Map::new())
+            .unwrap()
+            .function
+    };
+
+    assert_eq!(
+        ScriptSource::Embedded("input.amount * 2"),
+        ScriptActionExecutor::operation(function("double")).unwrap()
+    );
+    assert_eq!(
+        ScriptSource::Referenced("total.rhai"),
+        ScriptActionExecutor::operation(function("total")).unwrap()
+    );
+    assert!(matches!(
+        ScriptActionExecutor::operation(function("rest")),
+        Err(travailleur::Error::InvalidFunctionOperation { .. })
+    ));
+
+    let executor = ScriptActionExecutor::new()
+        .with_script("greet", r#""Hello, " + input.name"#)
+        .with_script_dir(script_dir());
+    assert_eq!(r#""Hello, " + input.name"#, executor.script(function("greet"), "greet").unwrap());
+    assert!(executor
+        .script(function("total"), "total.rhai")
+        .unwrap()
+        .contains("InvalidQuantity"));
+    assert!(matches!(
+        executor.script(function("missing"), "../total.rhai"),
+        Err(travailleur::Error::InvalidFunctionOperation { .. })
+    ));
+}
+
+#[cfg(feature = "rhai")]
+#[test]
+fn test_script_executor() {
+    let definition = script_definition();
+    let call = |name: &str, arguments: Value| {
+        FunctionCall::new(
+            &definition,
+            &FunctionRef::ByName(name.into()),
+            arguments.as_object().unwrap().clone(),
+        )
+        .unwrap()
+    };
+    let executor = ScriptActionExecutor::new().with_script_dir(script_dir());
+
+    assert_eq!(
+        json!(84),
+        executor
+            .execute(&call("double", json!({ "amount": 42 })))
+            .unwrap()
+    );
+    assert_eq!(
+        json!({ "total": 30 }),
+        executor
+            .execute(&call("total", json!({ "price": 10, "quantity": 3 })))
+            .unwrap()
+    );
+    assert!(matches!(
+        executor.execute(&call("total", json!({ "price": 10, "quantity": 0 }))),
+        Err(travailleur::Error::ActionFailed { error, .. }) if error == "InvalidQuantity"
+    ));
+    assert!(matches!(
+        executor.execute(&call("double", json!({ "amount": "a" }))),
+        Err(travailleur::Error::ActionFailed { error, .. })
+            if error == ScriptActionExecutor::SCRIPT_ERROR
+    ));
+}
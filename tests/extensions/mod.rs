@@ -0,0 +1,59 @@
+use serde_json::json;
+use travailleur::workflow::definition::{State, WorkflowDefinition};
+
+fn definition_json() -> serde_json::Value {
+    json!({
+        "id": "greeting",
+        "name": "Greeting Workflow",
+        "specVersion": "0.8",
+        "x-owner": "team-a",
+        "functions": [
+            {
+                "name": "greetingFunction",
+                "operation": "file://myapis/greetingapis.json#greeting",
+                "x-timeout": 30,
+            },
+        ],
+        "states": [
+            {
+                "name": "Greet",
+                "type": "operation",
+                "actions": [
+                    { "functionRef": "greetingFunction", "x-ui": { "color": "blue" } },
+                ],
+                "end": true,
+                "x-ui": { "position": [10, 20] },
+            },
+        ],
+    })
+}
+
+#[test]
+fn test_extensions_round_trip() {
+    let definition: WorkflowDefinition = serde_json::from_value(definition_json()).unwrap();
+
+    assert_eq!(Some(&json!("team-a")), definition.extensions.get("x-owner"));
+    match &definition.states[0] {
+        State::Operation(state) => {
+            assert_eq!(Some(&json!({ "position": [10, 20] })), state.extensions.get("x-ui"));
+            assert_eq!(Some(&json!({ "color": "blue" })), state.actions[0].extensions.get("x-ui"));
+        },
+        state => panic!("expected operation state, got {:?}", state),
+    }
+
+    let serialized = serde_json::to_value(&definition).unwrap();
+    assert_eq!(Some(&json!("Greeting Workflow")), serialized.get("name"));
+    assert_eq!(Some(&json!("team-a")), serialized.get("x-owner"));
+    assert_eq!(Some(&json!(30)), serialized.pointer("/functions/0/x-timeout"));
+    assert_eq!(Some(&json!([10, 20])), serialized.pointer("/states/0/x-ui/position"));
+    assert_eq!(Some(&json!("blue")), serialized.pointer("/states/0/actions/0/x-ui/color"));
+}
+
+#[test]
+fn test_unknown_non_extension_properties_are_rejected() {
+    let mut value = definition_json();
+    value["states"][0]["actions"][0]["foo"] = json!("bar");
+
+    let error = serde_json::from_value::<WorkflowDefinition>(value).unwrap_err();
+    assert!(error.to_string().contains("unknown field `foo`"), "unexpected error: {}", error);
+}
@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+use serde_json::json;
+use travailleur::cache::DefinitionCache;
+use travailleur::workflow::definition::events::Events;
+use travailleur::workflow::definition::functions::Functions;
+use travailleur::workflow::definition::{Constants, WorkflowDefinition};
+
+fn external_uri(name: &str) -> String {
+    let path: PathBuf =
+        [env!("CARGO_MANIFEST_DIR"), "tests", "resources", "definitions", "external", name]
+            .iter()
+            .collect();
+
+    format!("file://{}", path.to_string_lossy())
+}
+
+#[test]
+fn test_resolve_external_refs() {
+    let definition: WorkflowDefinition = serde_json::from_value(json!({
+        "id": "greeting",
+        "specVersion": "0.8",
+        "functions": external_uri("functions.json"),
+        "events": external_uri("events.json"),
+        "constants": external_uri("constants.json"),
+        "states": [
+            {
+                "name": "Greet",
+                "type": "operation",
+                "actions": [
+                    { "functionRef": "greetingFunction" },
+                ],
+                "end": true,
+            },
+        ],
+    }))
+    .unwrap();
+
+    let mut cache = DefinitionCache::new();
+    let resolved = definition.resolve_external_refs(&mut cache).unwrap();
+
+    match resolved.functions {
+        Some(Functions::Inline(functions)) => {
+            assert_eq!(1, functions.len());
+            assert_eq!("greetingFunction", functions[0].name);
+        },
+        functions => panic!("expected inline functions, got {:?}", functions),
+    }
+    match resolved.events {
+        Some(Events::Inline(events)) => {
+            assert_eq!(1, events.len());
+            assert_eq!("GreetingEvent", events[0].name);
+        },
+        events => panic!("expected inline events, got {:?}", events),
+    }
+    match resolved.constants {
+        Some(Constants::Multiple { constants }) => {
+            assert_eq!(json!("chien"), constants["Translations"]["Dog"]["French"]);
+        },
+        constants => panic!("expected inline constants, got {:?}", constants),
+    }
+    assert!(resolved.errors.is_none());
+}
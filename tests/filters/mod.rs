@@ -0,0 +1,217 @@
+use serde_json::{json, Map, Value};
+use travailleur::workflow::definition::{ActionDataFilter, EventDataFilter};
+use travailleur::workflow::runtime::filters::{
+    filter_action_input, filter_action_results, merge_action_results, merge_event_data,
+    merge_into_state_data,
+};
+
+// Evaluates simple paths (e.g. `.a.b`).
+fn evaluate(expression: &str, data: &Value) -> travailleur::Result<Value> {
+    match expression {
+        "." => Ok(data.clone()),
+        _ => Ok(data
+            .pointer(&expression.replace('.', "/"))
+            .cloned()
+            .unwrap_or_default()),
+    }
+}
+
+fn data(value: Value) -> Map<String, Value> {
+    value.as_object().unwrap().clone()
+}
+
+fn action_filter(filter: Value) -> ActionDataFilter {
+    serde_json::from_value(filter).unwrap()
+}
+
+fn event_filter(filter: Value) -> EventDataFilter {
+    serde_json::from_value(filter).unwrap()
+}
+
+fn state_data() -> Map<String, Value> {
+    data(json!({
+        "fruits": ["apple", "orange", "pear"],
+        "vegetables": [
+            { "veggieName": "potato", "veggieLike": true },
+            { "veggieName": "broccoli", "veggieLike": false },
+        ],
+    }))
+}
+
+#[test]
+fn test_action_input() {
+    let filter = action_filter(json!({ "fromStateData": "${ .vegetables }" }));
+    assert_eq!(
+        json!([
+            { "veggieName": "potato", "veggieLike": true },
+            { "veggieName": "broccoli", "veggieLike": false },
+        ]),
+        filter_action_input(Some(&filter), &state_data(), &evaluate).unwrap()
+    );
+
+    // Without `fromStateData`, actions receive the whole state data.
+    let filter = action_filter(json!({ "results": "${ .greeting }" }));
+    for filter in [None, Some(&filter)] {
+        assert_eq!(
+            Value::Object(state_data()),
+            filter_action_input(filter, &state_data(), &evaluate).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_action_results() {
+    let results = json!({ "greeting": "Hello, Jane!", "language": "en" });
+
+    let filter = action_filter(json!({ "results": "${ .greeting }" }));
+    assert_eq!(
+        Some(json!("Hello, Jane!")),
+        filter_action_results(Some(&filter), results.clone(), &evaluate).unwrap()
+    );
+
+    let filter = action_filter(json!({ "toStateData": "${ .greeting }" }));
+    for filter in [None, Some(&filter)] {
+        assert_eq!(
+            Some(results.clone()),
+            filter_action_results(filter, results.clone(), &evaluate).unwrap()
+        );
+    }
+
+    // If `useResults` is `false`, `results` and `toStateData` are ignored.
+    let filter = action_filter(json!({
+        "useResults": false,
+        "results": "${ .greeting }",
+        "toStateData": "${ .greeting }",
+    }));
+    assert_eq!(None, filter_action_results(Some(&filter), results, &evaluate).unwrap());
+}
+
+#[test]
+fn test_merge_action_results() {
+    let filter = action_filter(json!({
+        "results": "${ .greeting }",
+        "toStateData": "${ .finalGreeting }",
+    }));
+    let results =
+        filter_action_results(Some(&filter), json!({ "greeting": "Hello, Jane!" }), &evaluate)
+            .unwrap()
+            .unwrap();
+    let mut merged = data(json!({ "name": "Jane" }));
+    merge_action_results(Some(&filter), &mut merged, results).unwrap();
+    assert_eq!(data(json!({ "name": "Jane", "finalGreeting": "Hello, Jane!" })), merged);
+
+    // Without `toStateData`, results are merged into the top-level state data.
+    let mut merged = state_data();
+    merge_action_results(None, &mut merged, json!({ "fruits": ["banana"], "dessert": "pie" }))
+        .unwrap();
+    assert_eq!(json!(["apple", "orange", "pear", "banana"]), merged["fruits"]);
+    assert_eq!(json!("pie"), merged["dessert"]);
+}
+
+#[test]
+fn test_event_data_filter() {
+    let event = json!({ "results": { "approved": true }, "reviewer": "john" });
+
+    let filter = event_filter(json!({ "data": "${ .results }", "toStateData": "${ .approval }" }));
+    let mut merged = data(json!({ "order": 42 }));
+    merge_event_data(&mut merged, Some(&filter), &event, &evaluate).unwrap();
+    assert_eq!(data(json!({ "order": 42, "approval": { "approved": true } })), merged);
+
+    // Without `data`, the whole payload is merged.
+    let filter = event_filter(json!({ "toStateData": "${ .review }" }));
+    let mut merged = data(json!({ "order": 42 }));
+    merge_event_data(&mut merged, Some(&filter), &event, &evaluate).unwrap();
+    assert_eq!(data(json!({ "order": 42, "review": event.clone() })), merged);
+
+    // Without a filter, the payload is merged into the top-level state data.
+    let mut merged = data(json!({ "order": 42, "results": { "score": 5 } }));
+    merge_event_data(&mut merged, None, &event, &evaluate).unwrap();
+    assert_eq!(
+        data(json!({
+            "order": 42,
+            "results": { "score": 5, "approved": true },
+            "reviewer": "john",
+        })),
+        merged
+    );
+
+    // If `useData` is `false`, `data` and `toStateData` are ignored.
+    let filter = event_filter(json!({
+        "useData": false,
+        "data": "${ .results }",
+        "toStateData": "${ .approval }",
+    }));
+    let mut merged = data(json!({ "order": 42 }));
+    merge_event_data(&mut merged, Some(&filter), &event, &evaluate).unwrap();
+    assert_eq!(data(json!({ "order": 42 })), merged);
+}
+
+#[test]
+fn test_merge_into_state_data() {
+    // Objects are merged recursively and other values are replaced.
+    let mut merged = data(json!({
+        "customer": { "name": "John", "address": { "city": "Montreal" } },
+        "status": "new",
+    }));
+    merge_into_state_data(
+        &mut merged,
+        None,
+        json!({
+            "customer": { "address": { "zip": "H0H 0H0" } },
+            "status": "paid",
+        }),
+    )
+    .unwrap();
+    assert_eq!(
+        data(json!({
+            "customer": { "name": "John", "address": { "city": "Montreal", "zip": "H0H 0H0" } },
+            "status": "paid",
+        })),
+        merged
+    );
+
+    // Arrays keep the unique elements of both arrays (spec example).
+    let mut merged = data(json!({
+        "customers": [
+            { "name": "John", "address": "1234 street", "zip": "12345" },
+            { "name": "Jane", "address": "4321 street", "zip": "54321" },
+        ],
+    }));
+    merge_into_state_data(
+        &mut merged,
+        None,
+        json!({
+            "customers": [
+                { "name": "Michael", "address": "6789 street", "zip": "6789" },
+                { "name": "Jane", "address": "4321 street", "zip": "54321" },
+            ],
+        }),
+    )
+    .unwrap();
+    assert_eq!(
+        data(json!({
+            "customers": [
+                { "name": "John", "address": "1234 street", "zip": "12345" },
+                { "name": "Jane", "address": "4321 street", "zip": "54321" },
+                { "name": "Michael", "address": "6789 street", "zip": "6789" },
+            ],
+        })),
+        merged
+    );
+
+    // Missing elements are created.
+    let mut merged = Map::new();
+    merge_into_state_data(&mut merged, Some("${ .a.b }"), json!([1])).unwrap();
+    assert_eq!(data(json!({ "a": { "b": [1] } })), merged);
+
+    let error = |to_state_data: Option<&str>, value: Value| {
+        let mut data = data(json!({ "a": 1 }));
+        match merge_into_state_data(&mut data, to_state_data, value) {
+            Err(travailleur::Error::StateDataMergeFailed { element, .. }) => element,
+            result => panic!("unexpected result: {:?}", result),
+        }
+    };
+    assert_eq!("${ . }", error(None, json!([1])));
+    assert_eq!("${ .a.b }", error(Some("${ .a.b }"), json!(1)));
+    assert_eq!("${ .a | length }", error(Some("${ .a | length }"), json!(1)));
+}
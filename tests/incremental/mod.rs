@@ -0,0 +1,75 @@
+use serde_json::json;
+use travailleur::validation::incremental::IncrementalValidator;
+use travailleur::workflow::definition::{State, WorkflowDefinition};
+
+fn definition() -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "greeting",
+        "specVersion": "0.8",
+        "description": "Greeting workflow",
+        "states": [
+            {
+                "name": "Start",
+                "type": "inject",
+                "data": { "greeting": "Hello" },
+                "transition": "Greet",
+            },
+            {
+                "name": "Greet",
+                "type": "inject",
+                "data": { "name": "World" },
+                "end": true,
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_only_changed_states_are_revalidated() {
+    let mut validator = IncrementalValidator::default();
+    let mut definition = definition();
+
+    let report = validator.validate(&definition).unwrap();
+    assert!(report.is_empty(), "unexpected findings: {:?}", report.findings);
+    assert_eq!(vec!["Start", "Greet"], validator.revalidated_states());
+
+    validator.validate(&definition).unwrap();
+    assert!(validator.revalidated_states().is_empty());
+
+    match &mut definition.states[1] {
+        State::Inject(state) => {
+            state.end = None;
+        },
+        state => panic!("expected inject state, got {:?}", state),
+    }
+    let report = validator.validate(&definition).unwrap();
+    assert_eq!(vec!["Greet"], validator.revalidated_states());
+    assert!(report.has_errors());
+    assert!(report
+        .findings
+        .iter()
+        .all(|finding| finding.pointer.starts_with("/states/1")));
+}
+
+#[test]
+#[cfg(feature = "validate")]
+fn test_top_level_is_revalidated_when_states_change() {
+    let mut validator = IncrementalValidator::default();
+    let mut definition = definition();
+
+    let report = validator.validate(&definition).unwrap();
+    assert!(report.is_empty(), "unexpected findings: {:?}", report.findings);
+
+    let states = std::mem::take(&mut definition.states);
+    let report = validator.validate(&definition).unwrap();
+    assert!(report.has_errors());
+    assert!(report
+        .findings
+        .iter()
+        .any(|finding| finding.pointer == "/states"));
+
+    definition.states = states;
+    let report = validator.validate(&definition).unwrap();
+    assert!(report.is_empty(), "unexpected findings: {:?}", report.findings);
+}
@@ -0,0 +1,120 @@
+use std::path::PathBuf;
+
+use serde_json::json;
+use travailleur::cache::DefinitionCache;
+use travailleur::validation::input::{example_input, validate_input, JsonSchema};
+use travailleur::workflow::definition::WorkflowDefinition;
+
+fn schema_uri(name: &str) -> String {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "resources", "schemas", name]
+        .iter()
+        .collect();
+
+    format!("file://{}", path.to_string_lossy())
+}
+
+fn definition(fail_on_validation_errors: bool) -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "greeting",
+        "specVersion": "0.8",
+        "dataInputSchema": {
+            "schema": schema_uri("person.json"),
+            "failOnValidationErrors": fail_on_validation_errors,
+        },
+        "states": [
+            {
+                "name": "Greet",
+                "type": "inject",
+                "data": { "greeting": "Hello" },
+                "end": true,
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_valid_input() {
+    let mut cache = DefinitionCache::new();
+    let input = json!({ "person": { "name": "John", "age": 42 } });
+
+    let errors = validate_input(&definition(true), &input, &mut cache).unwrap();
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn test_invalid_input() {
+    let mut cache = DefinitionCache::new();
+    let input = json!({ "person": { "age": -1 } });
+
+    match validate_input(&definition(true), &input, &mut cache) {
+        Err(travailleur::Error::InputValidationFailed { errors }) => {
+            assert_eq!(2, errors.len());
+            assert!(errors.iter().any(|err| err.instance_path == "/person/age"));
+        },
+        result => panic!("expected input validation to fail, got {:?}", result),
+    }
+}
+
+#[test]
+fn test_invalid_input_without_failing() {
+    let mut cache = DefinitionCache::new();
+    let input = json!({});
+
+    let errors = validate_input(&definition(false), &input, &mut cache).unwrap();
+    assert_eq!(1, errors.len());
+    assert_eq!("", errors[0].instance_path);
+}
+
+fn order_definition() -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "order",
+        "specVersion": "0.8",
+        "dataInputSchema": schema_uri("order.json"),
+        "states": [{ "name": "Start", "type": "inject", "data": {}, "end": true }],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_example_input() {
+    let mut cache = DefinitionCache::new();
+    let definition = order_definition();
+
+    let input = example_input(&definition, &mut cache).unwrap().unwrap();
+    assert_eq!(
+        json!({
+            "id": "00000000-0000-0000-0000-000000000000",
+            "customer": { "name": "stringxx", "email": "user@example.com", "country": "CA" },
+            "items": [{ "sku": "SKU-1", "quantity": 1, "price": 0.5 }],
+            "priority": "low",
+            "express": false,
+            "notes": "str",
+        }),
+        input
+    );
+    assert!(validate_input(&definition, &input, &mut cache)
+        .unwrap()
+        .is_empty());
+
+    let mut definition = definition;
+    definition.data_input_schema = None;
+    assert!(example_input(&definition, &mut cache).unwrap().is_none());
+}
+
+#[test]
+fn test_random_input() {
+    let mut cache = DefinitionCache::new();
+    let definition = order_definition();
+    let schema = cache
+        .get_or_insert::<JsonSchema, _>(schema_uri("order.json").as_str())
+        .unwrap();
+
+    for seed in 0..50 {
+        let input = schema.random_instance(seed);
+        let errors = validate_input(&definition, &input, &mut cache).unwrap();
+        assert!(errors.is_empty(), "seed {} generated invalid input {}: {:?}", seed, input, errors);
+    }
+    assert_eq!(schema.random_instance(42), schema.random_instance(42));
+    assert_ne!(schema.random_instance(1), schema.random_instance(2));
+}
@@ -0,0 +1,171 @@
+use serde::de::DeserializeSeed;
+use serde_json::{json, Map, Value};
+use travailleur::workflow::definition::WorkflowDefinition;
+use travailleur::workflow::format::{IdFormat, SerializationFormat};
+use travailleur::workflow::instance::{LabelSelector, WorkflowInstance};
+
+fn definition() -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "order",
+        "specVersion": "0.8",
+        "states": [
+            {
+                "name": "Charge",
+                "type": "operation",
+                "actions": [{ "functionRef": "charge" }],
+                "compensatedBy": "Refund",
+                "transition": "Ship"
+            },
+            {
+                "name": "Ship",
+                "type": "operation",
+                "actions": [{ "functionRef": "ship" }],
+                "end": true
+            },
+            {
+                "name": "Refund",
+                "type": "operation",
+                "actions": [{ "functionRef": "refund" }],
+                "usedForCompensation": true
+            }
+        ]
+    }))
+    .unwrap()
+}
+
+fn data() -> Map<String, Value> {
+    json!({ "orderId": 42 }).as_object().unwrap().clone()
+}
+
+#[test]
+fn test_resume_at() {
+    let instance = WorkflowInstance::resume_at(&definition(), "Ship", data()).unwrap();
+
+    assert_eq!(Some("order"), instance.workflow_identifier.id.as_deref());
+    assert_eq!(Some("Ship"), instance.state.as_deref());
+    assert_eq!(data(), instance.data);
+    assert!(!instance.terminated);
+}
+
+#[test]
+fn test_resume_at_unknown_state() {
+    let error = WorkflowInstance::resume_at(&definition(), "Deliver", data()).unwrap_err();
+
+    match error {
+        travailleur::Error::UnknownState { state } => assert_eq!("Deliver", state),
+        error => panic!("expected unknown state error, got {:?}", error),
+    }
+}
+
+#[test]
+fn test_resume_at_compensation_state() {
+    let error = WorkflowInstance::resume_at(&definition(), "Refund", data()).unwrap_err();
+
+    match error {
+        travailleur::Error::CompensationState { state } => assert_eq!("Refund", state),
+        error => panic!("expected compensation state error, got {:?}", error),
+    }
+}
+
+#[test]
+fn test_check_data_size() {
+    let instance = WorkflowInstance::resume_at(&definition(), "Ship", data()).unwrap();
+    assert_eq!(r#"{"orderId":42}"#.len(), instance.data_size());
+    assert!(instance.check_data_size(instance.data_size()).is_ok());
+
+    let error = instance.check_data_size(10).unwrap_err();
+    assert!(matches!(
+        error,
+        travailleur::Error::InstanceDataTooLarge { instance: id, size: 14, limit: 10 }
+            if id == instance.id
+    ));
+}
+
+#[test]
+fn test_labels() {
+    let definition = definition();
+    let instances = [
+        WorkflowInstance::for_definition(&definition, None)
+            .with_label("tenant", "acme")
+            .with_label("trigger", "api"),
+        WorkflowInstance::for_definition(&definition, None)
+            .with_label("tenant", "acme")
+            .with_label("trigger", "schedule"),
+        WorkflowInstance::for_definition(&definition, None).with_label("tenant", "globex"),
+        WorkflowInstance::for_definition(&definition, None),
+    ];
+    assert_eq!(Some("api"), instances[0].label("trigger"));
+    assert_eq!(None, instances[2].label("trigger"));
+
+    let selector = LabelSelector::new().with_label("tenant", "acme");
+    let selected: Vec<_> = selector
+        .filter(&instances)
+        .map(|instance| &instance.id)
+        .collect();
+    assert_eq!(vec![&instances[0].id, &instances[1].id], selected);
+
+    let selector = LabelSelector::new().with_key("tenant");
+    assert_eq!(3, selector.filter(&instances).count());
+    assert_eq!(4, LabelSelector::new().filter(&instances).count());
+
+    let counts = LabelSelector::new().count_by(&instances, "tenant");
+    assert_eq!(
+        vec![(None, 1), (Some("acme"), 2), (Some("globex"), 1)],
+        counts.into_iter().collect::<Vec<_>>()
+    );
+
+    let value = serde_json::to_value(&instances[2]).unwrap();
+    assert_eq!(json!({ "tenant": "globex" }), value["labels"]);
+    let instance: WorkflowInstance = serde_json::from_value(value).unwrap();
+    assert_eq!(instances[2].labels, instance.labels);
+    assert!(serde_json::to_value(&instances[3])
+        .unwrap()
+        .get("labels")
+        .is_none());
+}
+
+#[test]
+fn test_serialization_format() {
+    let definition = definition();
+    let instance = WorkflowInstance::resume_at(&definition, "Ship", data())
+        .unwrap()
+        .with_label("tenant", "acme");
+
+    let default = serde_json::to_value(&instance).unwrap();
+    assert_eq!(default, serde_json::to_value(SerializationFormat::new().apply(&instance)).unwrap());
+
+    let format = SerializationFormat::new().with_ids(IdFormat::Binary);
+    let binary = serde_json::to_value(format.apply(&instance)).unwrap();
+    let bytes = binary["id"].as_array().unwrap();
+    assert_eq!(16, bytes.len());
+    assert_eq!(default["labels"], binary["labels"]);
+
+    // Binary IDs are only read through a seed using the same format.
+    assert!(serde_json::from_value::<WorkflowInstance>(binary.clone()).is_err());
+    let deserialized: WorkflowInstance = format.seed().deserialize(binary).unwrap();
+    assert_eq!(instance.id, deserialized.id);
+    assert_eq!(instance.state, deserialized.state);
+    assert_eq!(instance.labels, deserialized.labels);
+
+    // The format only applies to the formatted value.
+    assert_eq!(default, serde_json::to_value(&instance).unwrap());
+    let deserialized: WorkflowInstance = SerializationFormat::new()
+        .seed()
+        .deserialize(default.clone())
+        .unwrap();
+    assert_eq!(instance.id, deserialized.id);
+
+    let mut custom = instance.clone();
+    custom.id = "order-42".into();
+    assert!(serde_json::to_value(format.apply(&custom)).is_err());
+    assert!(format
+        .seed::<WorkflowInstance>()
+        .deserialize(json!({
+            "id": [1, 2, 3],
+            "workflow_identifier": "test",
+            "state": null,
+            "data": {},
+            "terminated": false
+        }))
+        .is_err());
+}
@@ -0,0 +1,566 @@
+use std::path::PathBuf;
+
+use serde_json::json;
+use travailleur::registry::{WorkflowKey, WorkflowRegistry};
+use travailleur::validation::lint::rules::{RecursiveWorkflows, UndefinedOperations};
+use travailleur::validation::lint::{
+    lint, LintConfig, LintFinding, LintIssue, LintLevel, LintRule, Linter, Severity,
+};
+use travailleur::workflow::definition::{State, SwitchSelection, SwitchState, WorkflowDefinition};
+
+fn definition() -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "greeting",
+        "specVersion": "0.8",
+        "functions": [
+            { "name": "greetingFunction", "operation": "file://myapis/greetingapis.json#greeting" },
+            { "name": "unusedFunction", "operation": "file://myapis/greetingapis.json#unused" },
+        ],
+        "states": [
+            {
+                "name": "Greet",
+                "type": "operation",
+                "actions": [
+                    { "functionRef": { "refName": "greetingFunction" } },
+                ],
+                "end": true,
+            },
+            {
+                "name": "Unreachable",
+                "type": "inject",
+                "data": {},
+                "end": true,
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+fn finding(rule_id: &str, severity: Severity, pointer: &str, message: &str) -> LintFinding {
+    LintFinding {
+        rule_id: rule_id.into(),
+        severity,
+        pointer: pointer.into(),
+        message: message.into(),
+    }
+}
+
+#[test]
+fn test_default_config() {
+    let report = lint(&definition(), &LintConfig::default());
+
+    assert_eq!(
+        vec![
+            finding(
+                "unreachable-states",
+                Severity::Warning,
+                "/states/1",
+                "state 'Unreachable' is never transitioned to"
+            ),
+            finding(
+                "unused-functions",
+                Severity::Warning,
+                "/functions/1",
+                "function 'unusedFunction' is never used"
+            ),
+            finding(
+                "missing-description",
+                Severity::Info,
+                "",
+                "workflow definition has no description"
+            ),
+        ],
+        report.findings
+    );
+    assert!(!report.has_errors());
+    assert_eq!(2, report.with_severity(Severity::Warning).count());
+}
+
+#[test]
+fn test_custom_config() {
+    let config = LintConfig::default()
+        .with_level("unreachable-states", Severity::Error)
+        .with_level("unused-functions", LintLevel::Off)
+        .with_level("missing-description", LintLevel::Off);
+    let report = lint(&definition(), &config);
+
+    assert_eq!(1, report.findings.len());
+    assert_eq!("unreachable-states", report.findings[0].rule_id);
+    assert!(report.has_errors());
+}
+
+#[test]
+fn test_config_deserialization() {
+    let config: LintConfig = serde_json::from_value(json!({
+        "rules": { "missing-description": "off", "unreachable-states": "error" },
+    }))
+    .unwrap();
+
+    assert_eq!(
+        LintConfig::default()
+            .with_level("missing-description", LintLevel::Off)
+            .with_level("unreachable-states", LintLevel::Error),
+        config
+    );
+}
+
+struct NoInjectStates;
+
+impl LintRule for NoInjectStates {
+    fn id(&self) -> &'static str {
+        "no-inject-states"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, definition: &WorkflowDefinition) -> Vec<LintIssue> {
+        definition
+            .states
+            .iter()
+            .enumerate()
+            .filter(|(_, state)| {
+                matches!(state, travailleur::workflow::definition::State::Inject(_))
+            })
+            .map(|(i, _)| LintIssue::new(format!("/states/{}", i), "inject states are forbidden"))
+            .collect()
+    }
+}
+
+#[test]
+fn test_custom_rule() {
+    let linter = Linter::without_rules(LintConfig::default()).with_rule(NoInjectStates);
+    let report = linter.lint(&definition());
+
+    assert_eq!(vec!["no-inject-states"], linter.rule_ids().collect::<Vec<_>>());
+    assert_eq!(
+        vec![finding(
+            "no-inject-states",
+            Severity::Warning,
+            "/states/1",
+            "inject states are forbidden"
+        )],
+        report.findings
+    );
+}
+
+#[test]
+fn test_non_boolean_conditions() {
+    let definition: WorkflowDefinition = serde_json::from_value(json!({
+        "id": "conditions",
+        "specVersion": "0.8",
+        "description": "Workflow with conditions",
+        "functions": [
+            { "name": "approve", "operation": "file://myapis/approvals.json#approve" },
+        ],
+        "states": [
+            {
+                "name": "Check",
+                "type": "switch",
+                "dataConditions": [
+                    { "condition": "${ .applicant | .age >= 18 }", "transition": "Approve" },
+                    { "condition": "${ {approved: .approved} }", "transition": "Approve" },
+                    { "condition": "${ .tags | contains([\"vip\"]) }", "transition": "Approve" },
+                    { "condition": "${ .approved }", "transition": "Approve" },
+                    { "condition": "${ \"yes\" }", "end": true },
+                ],
+                "defaultCondition": { "end": true },
+            },
+            {
+                "name": "Approve",
+                "type": "operation",
+                "actions": [
+                    { "functionRef": "approve", "condition": "${ (.amount > 1000) }" },
+                    { "functionRef": "approve", "condition": "${ .amount | tostring | \"big\" }" },
+                ],
+                "end": true,
+            },
+        ],
+    }))
+    .unwrap();
+
+    let report = lint(&definition, &LintConfig::default());
+
+    assert_eq!(
+        vec![
+            finding(
+                "non-boolean-conditions",
+                Severity::Warning,
+                "/states/0/dataConditions/1/condition",
+                "condition '${ {approved: .approved} }' yields a value of type 'object' instead of a boolean"
+            ),
+            finding(
+                "non-boolean-conditions",
+                Severity::Warning,
+                "/states/0/dataConditions/4/condition",
+                "condition '${ \"yes\" }' yields a value of type 'string' instead of a boolean"
+            ),
+            finding(
+                "non-boolean-conditions",
+                Severity::Warning,
+                "/states/1/actions/1/condition",
+                "condition '${ .amount | tostring | \"big\" }' yields a value of type 'string' instead of a boolean"
+            ),
+        ],
+        report.findings
+    );
+}
+
+#[test]
+fn test_non_boolean_conditions_with_keyword_fields() {
+    let definition: WorkflowDefinition = serde_json::from_value(json!({
+        "id": "conditions",
+        "specVersion": "0.8",
+        "description": "Workflow with conditions accessing fields named like keywords",
+        "states": [
+            {
+                "name": "Check",
+                "type": "switch",
+                "dataConditions": [
+                    { "condition": "${ .flags.or }", "end": true },
+                    { "condition": "${ .flags.and == true }", "end": true },
+                    { "condition": "${ \"Hello \" + .user.and }", "end": true },
+                    { "condition": "${ 1 + .totals.or }", "end": true },
+                    { "condition": "${ .flags.or or .flags.and }", "end": true },
+                ],
+                "defaultCondition": { "end": true },
+            },
+        ],
+    }))
+    .unwrap();
+
+    let report = lint(&definition, &LintConfig::default());
+
+    assert_eq!(
+        vec![
+            finding(
+                "non-boolean-conditions",
+                Severity::Warning,
+                "/states/0/dataConditions/2/condition",
+                "condition '${ \"Hello \" + .user.and }' yields a value of type 'string' instead of a boolean"
+            ),
+            finding(
+                "non-boolean-conditions",
+                Severity::Warning,
+                "/states/0/dataConditions/3/condition",
+                "condition '${ 1 + .totals.or }' yields a value of type 'number' instead of a boolean"
+            ),
+        ],
+        report.findings
+    );
+}
+
+fn constants_definition(constants: serde_json::Value) -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "constants",
+        "specVersion": "0.8",
+        "description": "Workflow using constants",
+        "constants": constants,
+        "states": [
+            {
+                "name": "Translate",
+                "type": "inject",
+                "data": {
+                    "dog": "${ $CONST.Translations.Dog.French }",
+                    "cat": "${ $CONST[\"Cat\"] }",
+                    "literal": "${ \"$CONST.Bird\" }",
+                },
+                "stateDataFilter": { "output": "${ $CONST.Limits | .max }" },
+                "end": true,
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+fn undefined_constants(definition: &WorkflowDefinition) -> Vec<(String, String)> {
+    let config = LintConfig::default().with_level("missing-description", LintLevel::Off);
+    lint(definition, &config)
+        .findings
+        .into_iter()
+        .inspect(|finding| assert_eq!("undefined-constants", finding.rule_id))
+        .map(|finding| (finding.pointer, finding.message))
+        .collect()
+}
+
+#[test]
+fn test_undefined_constants() {
+    let definition = constants_definition(json!({ "Translations": {}, "Limits": { "max": 10 } }));
+
+    assert_eq!(
+        vec![("/states/0/data/cat".to_string(), "constant 'Cat' is not defined".to_string())],
+        undefined_constants(&definition)
+    );
+}
+
+#[test]
+fn test_undefined_constants_external() {
+    let uri = |name: &str| {
+        let path: PathBuf =
+            [env!("CARGO_MANIFEST_DIR"), "tests", "resources", "definitions", "external", name]
+                .iter()
+                .collect();
+        format!("file://{}", path.to_string_lossy())
+    };
+
+    let definition = constants_definition(json!(uri("constants.json")));
+    assert_eq!(
+        vec!["/states/0/data/cat", "/states/0/stateDataFilter/output"],
+        undefined_constants(&definition)
+            .iter()
+            .map(|(pointer, _)| pointer.as_str())
+            .collect::<Vec<_>>()
+    );
+
+    let definition = constants_definition(json!(uri("missing.json")));
+    let findings = undefined_constants(&definition);
+    assert_eq!(1, findings.len());
+    assert_eq!("/constants", findings[0].0);
+    assert!(findings[0]
+        .1
+        .starts_with("failed to load workflow constants"));
+}
+
+fn calling_definition(id: &str, sub_flow: &str) -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": id,
+        "specVersion": "0.8",
+        "states": [
+            {
+                "name": "Prepare",
+                "type": "inject",
+                "data": {},
+                "transition": "Call"
+            },
+            {
+                "name": "Call",
+                "type": "operation",
+                "actions": [{ "subFlowRef": sub_flow }],
+                "end": true
+            }
+        ]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_recursive_workflows() {
+    let parent = calling_definition("parent", "child");
+    let child = calling_definition("child", "grandchild");
+    let grandchild = calling_definition("grandchild", "child");
+
+    let mut registry = WorkflowRegistry::new();
+    for definition in [&parent, &child, &grandchild] {
+        registry.register(definition.clone()).unwrap();
+    }
+    let graph = registry.dependency_graph();
+    let key = |id: &str| WorkflowKey { id: id.into(), version: None };
+    assert_eq!(vec![&key("child")], graph.dependencies(&key("parent")));
+    assert_eq!(None, graph.cycle(&key("parent")));
+    assert_eq!(
+        Some(vec![&key("child"), &key("grandchild"), &key("child")]),
+        graph.cycle(&key("child"))
+    );
+
+    let linter =
+        Linter::without_rules(LintConfig::default()).with_rule(RecursiveWorkflows::new(&registry));
+    assert!(linter.lint(&parent).is_empty());
+    assert_eq!(
+        vec![finding(
+            "recursive-workflows",
+            Severity::Warning,
+            "/states/1",
+            "workflow 'grandchild' can invoke itself: grandchild -> child -> grandchild"
+        )],
+        linter.lint(&grandchild).findings
+    );
+
+    let config = LintConfig::default().with_level("recursive-workflows", LintLevel::Error);
+    let linter = Linter::without_rules(config).with_rule(RecursiveWorkflows::new(&registry));
+    assert!(linter.lint(&child).has_errors());
+}
+
+#[test]
+fn test_misplaced_wildcard_error_handlers() {
+    let definition: WorkflowDefinition = serde_json::from_value(json!({
+        "id": "payment",
+        "specVersion": "0.8",
+        "description": "Processes payments",
+        "errors": [
+            { "name": "*" },
+            { "name": "CardDeclined" },
+            { "name": "Timeout" },
+        ],
+        "states": [
+            {
+                "name": "Pay",
+                "type": "operation",
+                "actions": [],
+                "onErrors": [
+                    { "errorRef": "*", "end": true },
+                    { "errorRefs": ["CardDeclined", "Timeout"], "transition": "Retry" },
+                ],
+                "transition": "Retry",
+            },
+            {
+                "name": "Retry",
+                "type": "operation",
+                "actions": [],
+                "onErrors": [
+                    { "errorRef": "Timeout", "transition": "Pay" },
+                    { "errorRef": "*", "end": true },
+                ],
+                "end": true,
+            },
+        ],
+    }))
+    .unwrap();
+
+    let pay = &definition.states[0];
+    assert_eq!(Some(&pay.on_errors()[1]), pay.error_handler("Timeout"));
+    assert_eq!(Some(&pay.on_errors()[0]), pay.error_handler("Unknown"));
+
+    let report = lint(&definition, &LintConfig::default());
+    assert_eq!(
+        vec![finding(
+            "misplaced-wildcard-error-handlers",
+            Severity::Warning,
+            "/states/0/onErrors/0",
+            "wildcard error handler is defined before handler(s) for specific errors: CardDeclined, Timeout"
+        )],
+        report.findings
+    );
+}
+
+#[test]
+fn test_undefined_operations() {
+    let path: PathBuf =
+        [env!("CARGO_MANIFEST_DIR"), "tests", "resources", "openapi", "petstore.json"]
+            .iter()
+            .collect();
+    let uri = format!("file://{}", path.to_string_lossy());
+    let definition: WorkflowDefinition = serde_json::from_value(json!({
+        "id": "petstore",
+        "specVersion": "0.8",
+        "functions": [
+            { "name": "listPets", "operation": format!("{}#listPets", uri) },
+            { "name": "deletePet", "operation": format!("{}#deletePet", uri) },
+            { "name": "greet", "operation": "greet", "type": "expression" },
+            { "name": "missing", "operation": "file:///missing.json#listPets" },
+        ],
+        "states": [],
+    }))
+    .unwrap();
+
+    let linter =
+        Linter::without_rules(LintConfig::default()).with_rule(UndefinedOperations::default());
+    let findings = linter.lint(&definition).findings;
+    assert_eq!(2, findings.len());
+    assert_eq!(
+        finding(
+            "undefined-operations",
+            Severity::Error,
+            "/functions/1/operation",
+            &format!("operation '{}#deletePet' of function 'deletePet' is not defined", uri)
+        ),
+        findings[0]
+    );
+    assert_eq!("/functions/3/operation", findings[1].pointer);
+    assert!(findings[1]
+        .message
+        .starts_with("failed to resolve operation of function 'missing': "));
+}
+
+#[test]
+fn test_overlapping_conditions() {
+    let definition: WorkflowDefinition = serde_json::from_value(json!({
+        "id": "routing",
+        "specVersion": "0.8",
+        "description": "Routes orders",
+        "events": [
+            { "name": "Approved", "type": "order.approved" },
+            { "name": "Rejected", "type": "order.rejected" },
+        ],
+        "states": [
+            {
+                "name": "Route",
+                "type": "switch",
+                "dataConditions": [
+                    { "condition": "${ .amount > 1000 }", "transition": "Await" },
+                    { "condition": "${ false }", "transition": "Await" },
+                    { "condition": "${.amount > 1000}", "end": true },
+                    { "condition": "${ true }", "transition": "Await" },
+                    { "condition": "${ .vip }", "end": true },
+                ],
+                "defaultCondition": { "end": true },
+            },
+            {
+                "name": "Await",
+                "type": "switch",
+                "eventConditions": [
+                    { "eventRef": "Approved", "end": true },
+                    { "eventRef": "Rejected", "end": true },
+                    { "name": "late", "eventRef": "Approved", "end": true },
+                ],
+                "defaultCondition": { "end": true },
+            },
+        ],
+    }))
+    .unwrap();
+
+    let State::Switch(SwitchState::DataBased(route)) = &definition.states[0] else {
+        panic!("state should be a data-based switch");
+    };
+    let mut evaluated = Vec::new();
+    let selection = route
+        .select(|condition| {
+            evaluated.push(condition.to_string());
+            Ok(condition.contains(".amount"))
+        })
+        .unwrap();
+    assert_eq!(SwitchSelection::Condition(0), selection);
+    assert_eq!(vec!["${ .amount > 1000 }"], evaluated);
+    assert_eq!(SwitchSelection::Default, route.select(|_| Ok(false)).unwrap());
+    assert!(route
+        .select(|_| Err(travailleur::Error::UnknownState { state: "Route".into() }))
+        .is_err());
+
+    let State::Switch(SwitchState::EventBased(wait)) = &definition.states[1] else {
+        panic!("state should be an event-based switch");
+    };
+    assert_eq!(Some(SwitchSelection::Condition(0)), wait.select("Approved"));
+    assert_eq!(Some("late"), wait.event_conditions[2].name());
+    assert_eq!(None, wait.select("Unknown"));
+
+    let report = lint(&definition, &LintConfig::default());
+    assert_eq!(
+        vec![
+            finding(
+                "overlapping-conditions",
+                Severity::Warning,
+                "/states/0/dataConditions/1",
+                "condition never matches"
+            ),
+            finding(
+                "overlapping-conditions",
+                Severity::Warning,
+                "/states/0/dataConditions/2",
+                "condition is never selected: condition 0 has the same expression"
+            ),
+            finding(
+                "overlapping-conditions",
+                Severity::Warning,
+                "/states/0/dataConditions/4",
+                "condition is never selected: condition 3 always matches"
+            ),
+            finding(
+                "overlapping-conditions",
+                Severity::Warning,
+                "/states/1/eventConditions/2",
+                "condition is never selected: condition 0 references the same event 'Approved'"
+            ),
+        ],
+        report.findings
+    );
+}
@@ -0,0 +1,560 @@
+use std::path::PathBuf;
+
+use serde_json::json;
+use travailleur::cache::DefinitionCache;
+use travailleur::error::SourceLocation;
+#[cfg(feature = "yaml")]
+use travailleur::loader::CatalogOutcome;
+use travailleur::loader::{
+    CustomStateHandler, DefaultsPolicy, DefinitionLoader, LoadWarning, RoundTripDifference,
+};
+#[cfg(feature = "yaml")]
+use travailleur::workflow::definition::WorkflowHeader;
+use travailleur::workflow::definition::{CustomState, State, WorkflowDefinition};
+#[cfg(feature = "yaml")]
+use travailleur::workflow::versioned::SpecVersion;
+use travailleur::workflow::versioned::VersionedWorkflowDefinition;
+use url::Url;
+
+fn invalid_uri(name: &str) -> String {
+    let path: PathBuf =
+        [env!("CARGO_MANIFEST_DIR"), "tests", "resources", "definitions", "invalid", name]
+            .iter()
+            .collect();
+
+    format!("file://{}", path.to_string_lossy())
+}
+
+#[test]
+fn test_json_conversion_error_path() {
+    let mut cache = DefinitionCache::new();
+    let error = cache
+        .get_or_insert::<WorkflowDefinition, _>(invalid_uri("unknown-field.json").as_str())
+        .unwrap_err();
+
+    match error {
+        travailleur::Error::JsonConversionFailed { path, .. } => {
+            assert_eq!(Some("states[0]"), path.as_deref());
+        },
+        error => panic!("expected JSON conversion error, got {:?}", error),
+    }
+}
+
+#[test]
+#[cfg(feature = "yaml")]
+fn test_yaml_conversion_error_path() {
+    let mut cache = DefinitionCache::new();
+    let error = cache
+        .get_or_insert::<WorkflowDefinition, _>(invalid_uri("unknown-field.yaml").as_str())
+        .unwrap_err();
+
+    match error {
+        travailleur::Error::YamlConversionFailed { path, .. } => {
+            assert_eq!(Some("states[0]"), path.as_deref());
+        },
+        error => panic!("expected YAML conversion error, got {:?}", error),
+    }
+}
+
+#[test]
+fn test_json_syntax_error_location() {
+    let mut cache = DefinitionCache::new();
+    let error = cache
+        .get_or_insert::<WorkflowDefinition, _>(invalid_uri("syntax-error.json").as_str())
+        .unwrap_err();
+
+    assert_eq!(Some(SourceLocation { line: 8, column: 7 }), error.source_location());
+}
+
+#[test]
+fn test_json_error_location_inside_state() {
+    let mut cache = DefinitionCache::new();
+    let error = cache
+        .get_or_insert::<WorkflowDefinition, _>(invalid_uri("unknown-field.json").as_str())
+        .unwrap_err();
+
+    assert_eq!(Some(SourceLocation { line: 25, column: 11 }), error.source_location());
+}
+
+#[test]
+#[cfg(feature = "yaml")]
+fn test_yaml_error_location_inside_state() {
+    let mut cache = DefinitionCache::new();
+    let error = cache
+        .get_or_insert::<WorkflowDefinition, _>(invalid_uri("unknown-field.yaml").as_str())
+        .unwrap_err();
+
+    assert_eq!(Some(SourceLocation { line: 17, column: 5 }), error.source_location());
+}
+
+#[test]
+#[cfg(feature = "yaml")]
+fn test_yaml_syntax_error_location() {
+    let mut cache = DefinitionCache::new();
+    let error = cache
+        .get_or_insert::<WorkflowDefinition, _>(invalid_uri("syntax-error.yaml").as_str())
+        .unwrap_err();
+
+    assert_eq!(
+        8,
+        error
+            .source_location()
+            .map(|location| location.line)
+            .unwrap_or_default()
+    );
+}
+
+#[test]
+fn test_lenient_mode() {
+    let mut cache = DefinitionCache::with_loader(DefinitionLoader::new().lenient(true));
+    let uri = invalid_uri("unknown-field.json");
+    let definition = cache
+        .get_or_insert::<WorkflowDefinition, _>(uri.as_str())
+        .unwrap();
+
+    assert_eq!("greeting", definition.identifier.id().unwrap());
+    assert_eq!(
+        &[(
+            Url::parse(&uri).unwrap(),
+            LoadWarning::UnknownField {
+                pointer: "/states/0/actions/0/actionDataFiltr".into(),
+                value: json!({ "results": "${ {greeting: .greeting} }" }),
+            }
+        )],
+        cache.warnings()
+    );
+}
+
+#[test]
+#[cfg(feature = "yaml")]
+fn test_lenient_mode_yaml() {
+    let mut cache = DefinitionCache::with_loader(DefinitionLoader::new().lenient(true));
+    let definition = cache
+        .get_or_insert::<WorkflowDefinition, _>(invalid_uri("unknown-field.yaml").as_str())
+        .unwrap();
+
+    assert_eq!("greeting", definition.identifier.id().unwrap());
+    assert_eq!(1, cache.warnings().len());
+}
+
+#[test]
+fn test_strict_mode() {
+    let mut cache = DefinitionCache::with_loader(DefinitionLoader::new().lenient(false));
+    let result =
+        cache.get_or_insert::<WorkflowDefinition, _>(invalid_uri("unknown-field.json").as_str());
+
+    assert!(result.is_err());
+    assert!(cache.warnings().is_empty());
+}
+
+#[test]
+fn test_deprecation_warnings() {
+    let path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "tests",
+        "resources",
+        "definitions",
+        "examples",
+        "eventbasedswitchstate.json",
+    ]
+    .iter()
+    .collect();
+    let uri = Url::from_file_path(path).unwrap();
+
+    let loader = DefinitionLoader::new();
+    assert!(!loader.warns_deprecated());
+    let (_, warnings) = loader
+        .load_with_warnings::<WorkflowDefinition>(&uri)
+        .unwrap();
+    assert!(warnings.is_empty());
+
+    let loader = loader.warn_deprecated(true);
+    assert!(loader.warns_deprecated());
+    for warnings in [
+        loader
+            .load_with_warnings::<WorkflowDefinition>(&uri)
+            .unwrap()
+            .1,
+        loader
+            .load_with_warnings::<VersionedWorkflowDefinition>(&uri)
+            .unwrap()
+            .1,
+    ] {
+        assert_eq!(1, warnings.len());
+        match &warnings[0] {
+            LoadWarning::Deprecated { pointer, .. } => {
+                assert_eq!("/states/0/eventConditions", pointer);
+            },
+            warning => panic!("expected deprecation warning, got {:?}", warning),
+        }
+    }
+}
+
+#[test]
+fn test_misplaced_wildcard_warnings() {
+    let path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "tests",
+        "resources",
+        "definitions",
+        "warnings",
+        "misplaced-wildcard.json",
+    ]
+    .iter()
+    .collect();
+    let uri = Url::from_file_path(path).unwrap();
+
+    let (_, warnings) = DefinitionLoader::new()
+        .load_with_warnings::<WorkflowDefinition>(&uri)
+        .unwrap();
+    assert_eq!(
+        vec![LoadWarning::MisplacedWildcardErrorHandler {
+            pointer: "/states/0/onErrors/0".into(),
+            message: "wildcard error handler is defined before handler(s) for specific errors: \
+                      CardDeclined, Timeout"
+                .into(),
+        }],
+        warnings
+    );
+    assert_eq!(
+        "misplaced error handler at '/states/0/onErrors/0': wildcard error handler is defined \
+         before handler(s) for specific errors: CardDeclined, Timeout",
+        warnings[0].to_string()
+    );
+}
+
+#[derive(Debug)]
+struct HumanTaskHandler;
+
+impl CustomStateHandler for HumanTaskHandler {
+    fn validate(&self, state: &CustomState) -> Result<(), String> {
+        match state.properties.get("assignee") {
+            Some(_) => Ok(()),
+            None => Err("missing assignee".into()),
+        }
+    }
+}
+
+#[test]
+fn test_custom_state() {
+    let path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "tests",
+        "resources",
+        "definitions",
+        "custom",
+        "approval.json",
+    ]
+    .iter()
+    .collect();
+    let uri = Url::from_file_path(path).unwrap();
+
+    let error = DefinitionLoader::new()
+        .load::<WorkflowDefinition>(&uri)
+        .unwrap_err();
+    match error {
+        travailleur::Error::UnknownStateType { state, state_type } => {
+            assert_eq!("RequestApproval", state);
+            assert_eq!("x-human-task", state_type);
+        },
+        error => panic!("expected unknown state type error, got {:?}", error),
+    }
+
+    let loader = DefinitionLoader::new().custom_state("x-human-task", HumanTaskHandler);
+    let definition = loader.load::<WorkflowDefinition>(&uri).unwrap();
+    match &definition.states[0] {
+        State::Custom(state) => {
+            assert_eq!("x-human-task", state.state_type);
+            assert_eq!("RequestApproval", state.name);
+            assert_eq!(Some("Done"), state.transition.as_ref().map(|t| t.next_state()));
+            assert_eq!(Some(&json!("${ .manager }")), state.properties.get("assignee"));
+        },
+        state => panic!("expected custom state, got {:?}", state),
+    }
+    assert!(matches!(definition.states[1], State::Inject(_)));
+
+    let serialized = serde_json::to_value(&definition.states[0]).unwrap();
+    assert_eq!(
+        json!({
+            "name": "RequestApproval",
+            "type": "x-human-task",
+            "assignee": "${ .manager }",
+            "transition": "Done"
+        }),
+        serialized,
+    );
+
+    let mut invalid = serialized;
+    invalid.as_object_mut().unwrap().remove("assignee");
+    let state: State = serde_json::from_value(invalid).unwrap();
+    let State::Custom(state) = state else {
+        panic!("expected custom state, got {:?}", state);
+    };
+    assert_eq!(Err("missing assignee".into()), HumanTaskHandler.validate(&state));
+}
+
+#[test]
+fn test_custom_state_built_in_type_errors() {
+    let error = serde_json::from_value::<State>(json!({
+        "name": "Greet",
+        "type": "operation",
+        "actions": [],
+        "foo": "bar"
+    }))
+    .unwrap_err();
+
+    assert!(error.to_string().starts_with("unknown field `foo`"), "unexpected error: {}", error);
+}
+
+#[test]
+fn test_load_header() {
+    let loader = DefinitionLoader::new();
+    let uri = Url::parse(&invalid_uri("unknown-field.json")).unwrap();
+    let header = loader.load_header(&uri).unwrap();
+
+    assert_eq!(Some("greeting"), header.identifier.id.as_deref());
+    assert_eq!(Some("Greeting Workflow"), header.name.as_deref());
+    assert_eq!(Some("1.0.0"), header.version.as_deref());
+    assert_eq!("0.8", header.spec_version);
+    assert_eq!(None, header.annotations);
+}
+
+#[test]
+#[cfg(feature = "yaml")]
+fn test_load_header_yaml() {
+    let path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "tests",
+        "resources",
+        "definitions",
+        "examples",
+        "greeting.yaml",
+    ]
+    .iter()
+    .collect();
+    let uri = Url::from_file_path(path).unwrap();
+    let loader = DefinitionLoader::new();
+    let definition = loader.load::<WorkflowDefinition>(&uri).unwrap();
+
+    assert_eq!(WorkflowHeader::from(&*definition), loader.load_header(&uri).unwrap());
+}
+
+#[test]
+fn test_http_uri_unsupported() {
+    let error = DefinitionLoader::new()
+        .load::<WorkflowDefinition>(&Url::parse("https://example.com/workflow.json").unwrap())
+        .unwrap_err();
+
+    assert!(!travailleur::capabilities().http);
+    match error {
+        travailleur::Error::UnsupportedUriScheme { scheme } => assert_eq!("https", scheme),
+        error => panic!("expected unsupported URI scheme error, got {:?}", error),
+    }
+}
+
+#[test]
+fn test_capabilities() {
+    let capabilities = travailleur::capabilities();
+
+    assert_eq!(cfg!(feature = "yaml"), capabilities.yaml);
+    assert_eq!(cfg!(feature = "validate"), capabilities.validate);
+    assert_eq!(cfg!(feature = "json-schema"), capabilities.json_schema);
+    assert_eq!(
+        [
+            ("json-schema", capabilities.json_schema),
+            ("validate", capabilities.validate),
+            ("yaml", capabilities.yaml)
+        ]
+        .into_iter()
+        .filter(|(_, enabled)| !enabled)
+        .map(|(feature, _)| feature)
+        .collect::<Vec<_>>(),
+        capabilities.disabled_features().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+#[cfg(feature = "yaml")]
+fn test_load_catalog() {
+    let path: PathBuf =
+        [env!("CARGO_MANIFEST_DIR"), "tests", "resources", "definitions", "catalog"]
+            .iter()
+            .collect();
+    let report = DefinitionLoader::new().load_catalog(&path).unwrap();
+
+    let files: Vec<_> = report
+        .entries
+        .iter()
+        .map(|entry| entry.path.file_name().unwrap().to_str().unwrap())
+        .collect();
+    assert_eq!(
+        vec!["greeting.json", "legacy.json", "order-processing.yaml", "unknown-field.json"],
+        files
+    );
+    assert!(!report.is_complete());
+
+    let loaded: Vec<_> = report
+        .loaded()
+        .map(|entry| entry.spec_version().unwrap())
+        .collect();
+    assert_eq!(vec![SpecVersion::V0_8, SpecVersion::V1_0], loaded);
+
+    let unsupported: Vec<_> = report.unsupported().map(|entry| &entry.outcome).collect();
+    assert!(matches!(
+        unsupported[..],
+        [CatalogOutcome::Unsupported { spec_version: Some(ref spec_version) }]
+            if spec_version == "0.7"
+    ));
+
+    let failed: Vec<_> = report.failed().map(|entry| &entry.outcome).collect();
+    assert!(matches!(
+        failed[..],
+        [CatalogOutcome::Failed(travailleur::Error::JsonConversionFailed { .. })]
+    ));
+
+    let report = DefinitionLoader::new()
+        .lenient(true)
+        .load_catalog(&path)
+        .unwrap();
+    assert_eq!(3, report.loaded().count());
+}
+
+#[test]
+fn test_load_catalog_missing_directory() {
+    let result = DefinitionLoader::new().load_catalog("tests/resources/definitions/missing");
+
+    assert!(matches!(result, Err(travailleur::Error::FileIo(_))));
+}
+
+fn round_trip_document() -> serde_json::Value {
+    json!({
+        "id": "greeting",
+        "specVersion": "0.8",
+        "expressionLang": "jq",
+        "functions": [{ "name": "greet", "operation": "greet", "type": "custom" }],
+        "states": [{
+            "name": "Greet",
+            "type": "operation",
+            "actions": [{ "functionRef": "greet" }],
+            "end": true,
+        }],
+    })
+}
+
+#[test]
+fn test_round_trip() {
+    let bytes = serde_json::to_vec(&round_trip_document()).unwrap();
+    let loader = DefinitionLoader::new();
+
+    let differences = loader
+        .round_trip::<WorkflowDefinition>(&bytes, "json", DefaultsPolicy::Ignore)
+        .unwrap();
+    assert!(differences.is_empty(), "{:?}", differences);
+    loader
+        .assert_round_trip::<WorkflowDefinition>(&bytes, "json")
+        .unwrap();
+
+    let differences = loader
+        .round_trip::<WorkflowDefinition>(&bytes, "json", DefaultsPolicy::Strict)
+        .unwrap();
+    let mut pointers: Vec<_> = differences
+        .iter()
+        .map(RoundTripDifference::pointer)
+        .collect();
+    pointers.sort_unstable();
+    assert_eq!(
+        vec![
+            "/autoRetries",
+            "/keepActive",
+            "/states/0/actionMode",
+            "/states/0/usedForCompensation"
+        ],
+        pointers
+    );
+    assert!(differences.contains(&RoundTripDifference::Added {
+        pointer: "/states/0/actionMode".into(),
+        value: json!("sequential"),
+    }));
+}
+
+#[test]
+fn test_round_trip_lenient() {
+    let mut document = round_trip_document();
+    document["states"][0]["timeout"] = json!("PT1M");
+    let bytes = serde_json::to_vec(&document).unwrap();
+
+    let result = DefinitionLoader::new()
+        .lenient(true)
+        .assert_round_trip::<WorkflowDefinition>(&bytes, "json");
+    let Err(error @ travailleur::Error::RoundTripFailed { differences }) = &result else {
+        panic!("expected round trip to fail, got {:?}", result);
+    };
+    assert_eq!(
+        vec![RoundTripDifference::Lost {
+            pointer: "/states/0/timeout".into(),
+            original: json!("PT1M")
+        }],
+        *differences
+    );
+    assert_eq!("'/states/0/timeout' lost (was \"PT1M\")", differences[0].to_string());
+    assert_eq!("definition altered by round trip: 1 difference(s)", error.to_string());
+}
+
+#[test]
+fn test_round_trip_examples() {
+    let path: PathBuf =
+        [env!("CARGO_MANIFEST_DIR"), "tests", "resources", "definitions", "examples"]
+            .iter()
+            .collect();
+    let loader = DefinitionLoader::new();
+
+    let mut altered = Vec::new();
+    for entry in std::fs::read_dir(path).unwrap() {
+        let path = entry.unwrap().path();
+        let file_ext = path.extension().unwrap().to_str().unwrap();
+        if cfg!(not(feature = "yaml")) && file_ext == "yaml" {
+            continue;
+        }
+
+        let bytes = std::fs::read(&path).unwrap();
+        let differences = loader
+            .round_trip::<WorkflowDefinition>(&bytes, file_ext, DefaultsPolicy::Ignore)
+            .unwrap();
+        if !differences.is_empty() {
+            altered.push((path.file_stem().unwrap().to_owned(), differences));
+        }
+    }
+
+    // URIs of external resources are normalized by the `url` crate.
+    assert!(altered.iter().all(|(name, differences)| {
+        name == "paymentconfirmation"
+            && *differences
+                == vec![
+                    RoundTripDifference::Changed {
+                        pointer: "/functions".into(),
+                        original: json!("file://functiondefs.json"),
+                        value: json!("file://functiondefs.json/"),
+                    },
+                    RoundTripDifference::Changed {
+                        pointer: "/events".into(),
+                        original: json!("file://eventdefs.yml"),
+                        value: json!("file://eventdefs.yml/"),
+                    },
+                ]
+    }));
+    assert!(!altered.is_empty());
+}
+
+#[test]
+fn test_round_trip_unsupported_format() {
+    let result = DefinitionLoader::new().round_trip::<WorkflowDefinition>(
+        b"id = 'greeting'",
+        "toml",
+        DefaultsPolicy::Strict,
+    );
+
+    assert!(matches!(
+        result,
+        Err(travailleur::Error::UnsupportedFileFormat { file_ext }) if file_ext == "toml"
+    ));
+}
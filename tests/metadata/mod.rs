@@ -0,0 +1,49 @@
+use serde_json::json;
+use travailleur::workflow::definition::WorkflowDefinition;
+
+#[test]
+fn test_state_metadata() {
+    let definition: WorkflowDefinition = serde_json::from_value(json!({
+        "id": "onboarding",
+        "specVersion": "0.8",
+        "states": [
+            {
+                "name": "SendWelcomeEmail",
+                "type": "inject",
+                "data": {},
+                "metadata": {
+                    "displayName": "Send welcome email",
+                    "icon": "mail",
+                    "group": "Notifications",
+                    "documentationUrl": "https://example.com/docs/welcome"
+                },
+                "transition": "Broken"
+            },
+            {
+                "name": "Broken",
+                "type": "inject",
+                "data": {},
+                "metadata": { "documentationUrl": "not a url" },
+                "end": true
+            }
+        ]
+    }))
+    .unwrap();
+
+    let metadata = definition.states[0].metadata().unwrap();
+    assert_eq!(Some("Send welcome email"), metadata.display_name());
+    assert_eq!(Some("mail"), metadata.icon());
+    assert_eq!(Some("Notifications"), metadata.group());
+    assert_eq!(
+        Some("https://example.com/docs/welcome"),
+        metadata
+            .documentation_url()
+            .unwrap()
+            .as_ref()
+            .map(|url| url.as_str())
+    );
+
+    let metadata = definition.states[1].metadata().unwrap();
+    assert_eq!(None, metadata.display_name());
+    assert!(matches!(metadata.documentation_url(), Err(travailleur::Error::InvalidUrl(_))));
+}
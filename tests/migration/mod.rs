@@ -0,0 +1,166 @@
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use serde_json::json;
+use travailleur::cache::DefinitionCache;
+use travailleur::workflow::definition::WorkflowDefinition;
+use travailleur::workflow::migration::V1Migration;
+use travailleur::workflow::v1;
+use travailleur::workflow::versioned::VersionedWorkflowDefinition;
+
+fn migrate_example(name: &str) -> V1Migration {
+    let path: PathBuf =
+        [env!("CARGO_MANIFEST_DIR"), "tests", "resources", "definitions", "examples", name]
+            .iter()
+            .collect();
+
+    let mut cache = DefinitionCache::new();
+    let definition: Rc<WorkflowDefinition> = cache
+        .get_or_insert(format!("file://{}", path.to_string_lossy()).as_str())
+        .unwrap();
+
+    definition.migrate_to_v1()
+}
+
+#[test]
+fn test_migrate_operation_state() {
+    let migration = migrate_example("greeting.json");
+    assert!(migration.is_complete(), "unexpected issues: {:?}", migration.issues);
+
+    let document = &migration.definition.document;
+    assert_eq!("1.0.0", document.dsl);
+    assert_eq!("greeting", document.name);
+    assert_eq!("1.0", document.version);
+    assert_eq!(Some("Greeting Workflow"), document.title.as_deref());
+    assert_eq!(Some("Greet Someone"), document.summary.as_deref());
+
+    let migrated = serde_json::to_value(&migration.definition.tasks).unwrap();
+    assert_eq!(
+        json!([{
+            "Greet": {
+                "do": [{
+                    "action0": {
+                        "call": "openapi",
+                        "with": {
+                            "document": { "endpoint": "file://myapis/greetingapis.json" },
+                            "operationId": "greeting",
+                            "parameters": { "name": "${ .person.name }" },
+                        },
+                        "output": { "as": "${ .greeting }" },
+                    },
+                }],
+                "then": "end",
+            },
+        }]),
+        migrated
+    );
+}
+
+#[test]
+fn test_migrate_switch_state() {
+    let migration = migrate_example("applicantrequest.json");
+    assert!(migration.is_complete(), "unexpected issues: {:?}", migration.issues);
+
+    let tasks = &migration.definition.tasks;
+    assert_eq!(
+        vec!["CheckApplication", "StartApplication", "RejectApplication"],
+        tasks
+            .iter()
+            .map(|task| task.name.as_str())
+            .collect::<Vec<_>>()
+    );
+
+    let v1::Task::Switch(switch) = &tasks[0].task else {
+        panic!("expected a switch task, got {:?}", tasks[0].task);
+    };
+    let cases: Vec<_> = switch
+        .switch
+        .iter()
+        .map(|case| (case.name.as_str(), case.case.when.as_deref(), case.case.then.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            ("case0", Some("${ .applicants | .age >= 18 }"), "StartApplication"),
+            ("case1", Some("${ .applicants | .age < 18 }"), "RejectApplication"),
+            ("default", None, "RejectApplication"),
+        ],
+        cases
+    );
+
+    let v1::Task::Do(start_application) = &tasks[1].task else {
+        panic!("expected a do task, got {:?}", tasks[1].task);
+    };
+    let v1::Task::Run(run) = &start_application.tasks[0].task else {
+        panic!("expected a run task, got {:?}", start_application.tasks[0].task);
+    };
+    assert_eq!(
+        Some("startApplicationWorkflowId"),
+        run.run
+            .workflow
+            .as_ref()
+            .map(|workflow| workflow.name.as_str())
+    );
+}
+
+#[test]
+fn test_migrate_parallel_state() {
+    let migration = migrate_example("parallelexec.json");
+    assert!(migration.is_complete(), "unexpected issues: {:?}", migration.issues);
+
+    let v1::Task::Fork(fork) = &migration.definition.tasks[0].task else {
+        panic!("expected a fork task, got {:?}", migration.definition.tasks[0].task);
+    };
+    assert!(!fork.fork.compete);
+    assert_eq!(
+        vec!["ShortDelayBranch", "LongDelayBranch"],
+        fork.fork
+            .branches
+            .iter()
+            .map(|branch| branch.name.as_str())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_migrate_reports_issues() {
+    let migration = migrate_example("eventbasedswitchstate.json");
+    assert!(!migration.is_complete());
+    assert_eq!(
+        vec!["/states/0/eventConditions"],
+        migration
+            .issues
+            .iter()
+            .map(|issue| issue.pointer.as_str())
+            .collect::<Vec<_>>()
+    );
+
+    let v1::Task::Listen(listen) = &migration.definition.tasks[0].task else {
+        panic!("expected a listen task, got {:?}", migration.definition.tasks[0].task);
+    };
+    assert_eq!(Some(&json!({ "after": "PT1H" })), listen.base.timeout.as_ref());
+    assert_eq!(Some("HandleNoVisaDecision"), listen.base.then.as_deref());
+    assert_eq!(
+        json!({
+            "any": [
+                { "with": { "type": "VisaApproved", "source": "visaCheckSource" } },
+                { "with": { "type": "VisaRejected", "source": "visaCheckSource" } },
+            ],
+        }),
+        serde_json::to_value(&listen.listen.to).unwrap()
+    );
+}
+
+#[test]
+fn test_migrated_definition_round_trip() {
+    for name in ["greeting.json", "applicantrequest.json", "parallelexec.json"] {
+        let migration = migrate_example(name);
+        let serialized = serde_json::to_value(&migration.definition).unwrap();
+
+        let deserialized: VersionedWorkflowDefinition =
+            serde_json::from_value(serialized.clone()).unwrap();
+        let VersionedWorkflowDefinition::V1_0(deserialized) = deserialized else {
+            panic!("expected a v1 workflow definition for {}", name);
+        };
+        assert_eq!(serialized, serde_json::to_value(&deserialized).unwrap());
+    }
+}
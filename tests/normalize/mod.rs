@@ -0,0 +1,303 @@
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+use travailleur::cache::DefinitionCache;
+use travailleur::workflow::definition::WorkflowDefinition;
+
+fn normalize(definition: Value) -> Value {
+    let definition: WorkflowDefinition = serde_json::from_value(definition).unwrap();
+
+    serde_json::to_value(definition.normalize()).unwrap()
+}
+
+fn compact_definition() -> Value {
+    json!({
+        "id": "normalize",
+        "specVersion": "0.8",
+        "dataInputSchema": "file://schema.json",
+        "timeouts": {
+            "workflowExecTimeout": "PT1H",
+            "stateExecTimeout": "PT5M"
+        },
+        "states": [
+            {
+                "name": "Work",
+                "type": "operation",
+                "actions": [
+                    { "functionRef": "doWork" },
+                    { "subFlowRef": "child" }
+                ],
+                "timeouts": { "stateExecTimeout": "PT1M" },
+                "onErrors": [{ "errorRef": "failure", "end": true }],
+                "transition": "Choose"
+            },
+            {
+                "name": "Choose",
+                "type": "switch",
+                "dataConditions": [
+                    { "condition": "${ .done }", "end": { "continueAs": "next" } }
+                ],
+                "defaultCondition": { "transition": "Work" }
+            }
+        ]
+    })
+}
+
+#[test]
+fn test_normalize() {
+    let normalized = normalize(compact_definition());
+
+    let end = json!({ "terminate": false, "compensate": false });
+    assert_eq!(
+        json!({
+            "id": "normalize",
+            "specVersion": "0.8",
+            "expressionLang": "jq",
+            "keepActive": false,
+            "autoRetries": false,
+            "start": "Work",
+            "dataInputSchema": { "schema": "file://schema.json", "failOnValidationErrors": true },
+            "timeouts": {
+                "workflowExecTimeout": { "duration": "PT1H", "interrupt": true },
+                "stateExecTimeout": { "total": "PT5M" }
+            },
+            "states": [
+                {
+                    "name": "Work",
+                    "type": "operation",
+                    "actionMode": "sequential",
+                    "actions": [
+                        {
+                            "functionRef": { "refName": "doWork", "invoke": "sync" }
+                        },
+                        {
+                            "subFlowRef": {
+                                "workflowId": "child",
+                                "onParentComplete": "terminate",
+                                "invoke": "sync"
+                            }
+                        }
+                    ],
+                    "timeouts": { "stateExecTimeout": { "total": "PT1M" } },
+                    "onErrors": [{ "errorRef": "failure", "end": end }],
+                    "transition": { "nextState": "Choose", "compensate": false },
+                    "usedForCompensation": false
+                },
+                {
+                    "name": "Choose",
+                    "type": "switch",
+                    "dataConditions": [{
+                        "condition": "${ .done }",
+                        "end": {
+                            "terminate": false,
+                            "compensate": false,
+                            "continueAs": { "workflowId": "next" }
+                        }
+                    }],
+                    "defaultCondition": {
+                        "transition": { "nextState": "Work", "compensate": false }
+                    },
+                    "usedForCompensation": false
+                }
+            ]
+        }),
+        normalized,
+    );
+}
+
+#[test]
+fn test_normalize_explicit_start() {
+    let normalized = normalize(json!({
+        "id": "normalize",
+        "specVersion": "0.8",
+        "start": {
+            "stateName": "Wait",
+            "schedule": { "cron": "0 0 * * * ?" }
+        },
+        "states": [{ "name": "Wait", "type": "sleep", "duration": "PT1S", "end": true }]
+    }));
+
+    assert_eq!(
+        json!({ "stateName": "Wait", "schedule": { "cron": { "expression": "0 0 * * * ?" } } }),
+        normalized["start"],
+    );
+}
+
+#[test]
+fn test_normalize_empty_collections() {
+    let normalized = normalize(json!({
+        "id": "normalize",
+        "specVersion": "0.8",
+        "annotations": [],
+        "functions": [],
+        "events": [{ "name": "Ping", "type": "ping", "correlation": [] }],
+        "states": [{
+            "name": "Wait",
+            "type": "event",
+            "onEvents": [{ "eventRefs": ["Ping"], "actions": [] }],
+            "onErrors": [{ "errorRefs": [], "errorRef": "failure", "end": true }],
+            "end": { "produceEvents": [] }
+        }]
+    }));
+
+    assert!(normalized.get("annotations").is_none());
+    assert!(normalized.get("functions").is_none());
+    assert_eq!(
+        json!([{ "name": "Ping", "type": "ping", "kind": "consumed", "dataOnly": true }]),
+        normalized["events"]
+    );
+    let state = &normalized["states"][0];
+    assert!(state["onEvents"][0].get("actions").is_none());
+    assert!(state["onErrors"][0].get("errorRefs").is_none());
+    assert_eq!(json!({ "terminate": false, "compensate": false }), state["end"]);
+}
+
+fn empty_collections(file_ext: &str) -> Value {
+    let path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "tests",
+        "resources",
+        "definitions",
+        "normalize",
+        &format!("empty-collections.{}", file_ext),
+    ]
+    .iter()
+    .collect();
+
+    let mut cache = DefinitionCache::new();
+    let definition = cache
+        .get_or_insert::<WorkflowDefinition, _>(
+            format!("file://{}", path.to_string_lossy()).as_str(),
+        )
+        .unwrap();
+    serde_json::to_value(definition.normalize()).unwrap()
+}
+
+#[test]
+fn test_normalize_empty_collections_across_formats() {
+    let expected = normalize(json!({
+        "id": "emptyCollections",
+        "specVersion": "0.8",
+        "states": [
+            { "name": "Work", "type": "operation", "actions": [], "transition": "Done" },
+            { "name": "Done", "type": "inject", "data": {}, "end": { "terminate": true } }
+        ]
+    }));
+
+    assert_eq!(expected, empty_collections("json"));
+    #[cfg(feature = "yaml")]
+    assert_eq!(expected, empty_collections("yaml"));
+}
+
+#[test]
+fn test_minify() {
+    let definition: WorkflowDefinition = serde_json::from_value(compact_definition()).unwrap();
+
+    assert_eq!(compact_definition(), definition.minify().unwrap());
+    assert_eq!(compact_definition(), definition.normalize().minify().unwrap());
+}
+
+#[test]
+fn test_minify_keeps_non_defaults() {
+    let definition: WorkflowDefinition = serde_json::from_value(json!({
+        "id": "minify",
+        "specVersion": "0.8",
+        "start": "Second",
+        "keepActive": true,
+        "states": [
+            {
+                "name": "First",
+                "type": "operation",
+                "actionMode": "parallel",
+                "actions": [{ "functionRef": { "refName": "doWork", "invoke": "async" } }],
+                "end": { "terminate": true }
+            },
+            {
+                "name": "Second",
+                "type": "sleep",
+                "duration": "PT1S",
+                "transition": { "nextState": "First", "compensate": true }
+            }
+        ]
+    }))
+    .unwrap();
+
+    assert_eq!(
+        json!({
+            "id": "minify",
+            "specVersion": "0.8",
+            "start": "Second",
+            "keepActive": true,
+            "states": [
+                {
+                    "name": "First",
+                    "type": "operation",
+                    "actionMode": "parallel",
+                    "actions": [{ "functionRef": { "refName": "doWork", "invoke": "async" } }],
+                    "end": { "terminate": true }
+                },
+                {
+                    "name": "Second",
+                    "type": "sleep",
+                    "duration": "PT1S",
+                    "transition": { "nextState": "First", "compensate": true }
+                }
+            ]
+        }),
+        definition.minify().unwrap(),
+    );
+}
+
+#[test]
+fn test_fingerprint() {
+    let compact: WorkflowDefinition = serde_json::from_value(compact_definition()).unwrap();
+    let normalized = compact.normalize();
+    let mut changed = compact.clone();
+    changed.description = Some("Changed".into());
+
+    let fingerprint = compact.fingerprint().unwrap();
+    assert_eq!(fingerprint, normalized.fingerprint().unwrap());
+    assert_ne!(fingerprint, changed.fingerprint().unwrap());
+    // Fingerprints are persisted, so they must not change between releases.
+    assert_eq!(0x8438c78ff81f2b1a, fingerprint);
+}
+
+#[test]
+fn test_serialization_order() {
+    let source = r#"{
+        "id": "order",
+        "specVersion": "0.8",
+        "metadata": { "zeta": "1", "alpha": "2" },
+        "constants": { "zeta": 1, "alpha": 2 },
+        "functions": [{ "name": "doWork", "operation": "file://api.json#doWork" }],
+        "states": [
+            {
+                "name": "Inject",
+                "type": "inject",
+                "data": { "zeta": 1, "alpha": 2 },
+                "transition": "Work"
+            },
+            {
+                "name": "Work",
+                "type": "operation",
+                "actions": [
+                    {
+                        "functionRef": {
+                            "refName": "doWork",
+                            "arguments": { "zeta": 1, "alpha": 2 }
+                        }
+                    }
+                ],
+                "end": true
+            }
+        ]
+    }"#;
+
+    let definition: WorkflowDefinition = serde_json::from_str(source).unwrap();
+    let serialized = serde_json::to_string(&definition).unwrap();
+    assert_eq!(1, serialized.matches(r#"{"zeta":"1","alpha":"2"}"#).count());
+    assert_eq!(3, serialized.matches(r#"{"zeta":1,"alpha":2}"#).count());
+
+    let deserialized: WorkflowDefinition = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(serialized, serde_json::to_string(&deserialized).unwrap());
+}
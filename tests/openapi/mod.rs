@@ -0,0 +1,84 @@
+use std::path::PathBuf;
+
+use serde_json::json;
+use travailleur::cache::DefinitionCache;
+use travailleur::openapi::{operation_location, OpenApiDocument, ParameterLocation};
+use travailleur::workflow::definition::functions::{Function, FunctionType};
+
+fn petstore() -> OpenApiDocument {
+    let path: PathBuf =
+        [env!("CARGO_MANIFEST_DIR"), "tests", "resources", "openapi", "petstore.json"]
+            .iter()
+            .collect();
+
+    let mut cache = DefinitionCache::new();
+    let document = cache
+        .get_or_insert::<OpenApiDocument, _>(format!("file://{}", path.to_string_lossy()).as_str())
+        .unwrap();
+    OpenApiDocument::clone(&document)
+}
+
+#[test]
+fn test_operation() {
+    let document = petstore();
+
+    let operation = document.operation("listPets").unwrap();
+    assert_eq!("GET", operation.method);
+    assert_eq!("/pets", operation.path);
+    let parameters: Vec<_> = operation
+        .parameters()
+        .into_iter()
+        .map(|parameter| (parameter.name.as_str(), parameter.location, parameter.required))
+        .collect();
+    assert_eq!(
+        vec![
+            ("limit", ParameterLocation::Query, false),
+            ("X-Request-Id", ParameterLocation::Header, true)
+        ],
+        parameters
+    );
+
+    let operation = document.operation("createPet").unwrap();
+    assert_eq!("POST", operation.method);
+    assert!(operation.operation.request_body.is_some());
+
+    assert!(document.operation("deletePet").is_none());
+}
+
+#[test]
+fn test_parameter_references() {
+    let document = petstore();
+
+    let parameters = document.operation("showPetById").unwrap().parameters();
+    assert_eq!(1, parameters.len());
+    assert_eq!("petId", parameters[0].name);
+    assert_eq!(ParameterLocation::Path, parameters[0].location);
+    assert_eq!(Some(&json!({ "type": "string" })), parameters[0].schema.as_ref());
+}
+
+#[test]
+fn test_operation_location() {
+    let function = |operation: &str, function_type: &str| -> Function {
+        serde_json::from_value(json!({
+            "name": "listPets",
+            "operation": operation,
+            "type": function_type,
+        }))
+        .unwrap()
+    };
+
+    let rest = function("https://petstore.example.com/openapi.json#listPets", "rest");
+    assert_eq!(FunctionType::Rest, rest.function_type);
+    let (uri, operation_id) = operation_location(&rest).unwrap();
+    assert_eq!("https://petstore.example.com/openapi.json", uri.as_str());
+    assert_eq!("listPets", operation_id);
+
+    assert!(matches!(
+        operation_location(&function("listPets", "rest")),
+        Err(travailleur::Error::InvalidFunctionOperation { .. })
+    ));
+    assert!(matches!(
+        operation_location(&function("listPets", "expression")),
+        Err(travailleur::Error::InvalidFunctionOperation { .. })
+    ));
+}
@@ -0,0 +1,126 @@
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+use travailleur::workflow::definition::functions::FunctionType;
+use travailleur::workflow::definition::WorkflowDefinition;
+#[cfg(feature = "rest")]
+use travailleur::workflow::executor::rest::RestActionExecutor;
+use travailleur::workflow::executor::{HandlerActionExecutor, NoopActionExecutor};
+use travailleur::workflow::preflight::{Preflight, PreflightCheck};
+
+fn definition() -> WorkflowDefinition {
+    let path: PathBuf =
+        [env!("CARGO_MANIFEST_DIR"), "tests", "resources", "openapi", "petstore.json"]
+            .iter()
+            .collect();
+    let uri = format!("file://{}", path.to_string_lossy());
+
+    serde_json::from_value(json!({
+        "id": "petstore",
+        "specVersion": "0.8",
+        "functions": [
+            { "name": "listPets", "operation": format!("{}#listPets", uri), "authRef": "basic" },
+            { "name": "unknown", "operation": format!("{}#unknown", uri) },
+            { "name": "greet", "operation": "greet", "type": "expression" },
+            { "name": "audit", "operation": "audit", "type": "custom", "authRef": "missing" },
+            { "name": "notify", "operation": "script://notify", "type": "custom" },
+            { "name": "unused", "operation": "unused", "type": "expression" },
+        ],
+        "auth": [
+            {
+                "name": "basic",
+                "scheme": "basic",
+                "properties": { "username": "user", "password": "pass" },
+            },
+        ],
+        "events": [{ "name": "Adopted", "source": "petstore", "type": "adopted" }],
+        "states": [
+            {
+                "name": "List",
+                "type": "operation",
+                "actions": [
+                    { "functionRef": "listPets" },
+                    { "functionRef": "unknown" },
+                    { "functionRef": "greet" },
+                    { "functionRef": "audit" },
+                    { "functionRef": "ghost" },
+                    { "functionRef": "listPets" },
+                ],
+                "transition": "Wait",
+            },
+            {
+                "name": "Wait",
+                "type": "event",
+                "onEvents": [{ "eventRefs": ["Adopted"], "actions": [{ "functionRef": "notify" }] }],
+                "end": true,
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_preflight() {
+    let definition = definition();
+    let custom = HandlerActionExecutor::new().with_handler("audit", |_| Ok(Value::Null));
+
+    let report = Preflight::new(&definition)
+        .executor(FunctionType::Rest, &NoopActionExecutor)
+        .executor(FunctionType::Custom, &custom)
+        .run();
+    let issues: Vec<_> = report
+        .issues
+        .iter()
+        .map(|issue| (issue.check, issue.pointer.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            (PreflightCheck::Executor, "/functions/2"),
+            (PreflightCheck::Auth, "/functions/3"),
+            (PreflightCheck::Function, "/states/0"),
+            (PreflightCheck::Endpoint, "/functions/4"),
+            (PreflightCheck::Events, "/states/1"),
+        ],
+        issues
+    );
+    assert!(!report.is_ok());
+    assert_eq!(
+        "/states/0: workflow definition has no function named 'ghost'",
+        report
+            .with_check(PreflightCheck::Function)
+            .next()
+            .unwrap()
+            .to_string()
+    );
+
+    let report = Preflight::new(&definition)
+        .executor(FunctionType::Rest, &NoopActionExecutor)
+        .executor(FunctionType::Custom, &NoopActionExecutor)
+        .executor(FunctionType::Expression, &NoopActionExecutor)
+        .events(true)
+        .run();
+    assert_eq!(
+        vec!["/functions/3", "/states/0"],
+        report
+            .issues
+            .iter()
+            .map(|issue| issue.pointer.as_str())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[cfg(feature = "rest")]
+#[test]
+fn test_preflight_rest_executor() {
+    let definition = definition();
+    let executor = RestActionExecutor::new();
+
+    let report = Preflight::new(&definition)
+        .executor(FunctionType::Rest, &executor)
+        .run();
+    let endpoints: Vec<_> = report
+        .with_check(PreflightCheck::Endpoint)
+        .map(|issue| issue.pointer.as_str())
+        .collect();
+    assert_eq!(vec!["/functions/1"], endpoints);
+}
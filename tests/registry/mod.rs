@@ -0,0 +1,316 @@
+use std::path::PathBuf;
+
+use serde_json::json;
+use travailleur::cache::DefinitionCache;
+use travailleur::registry::{RegistrySnapshot, WorkflowKey, WorkflowRegistry};
+use travailleur::workflow::definition::WorkflowDefinition;
+
+fn example_uri(name: &str) -> String {
+    let path: PathBuf =
+        [env!("CARGO_MANIFEST_DIR"), "tests", "resources", "definitions", "examples", name]
+            .iter()
+            .collect();
+
+    format!("file://{}", path.to_string_lossy())
+}
+
+fn definition(version: &str, description: &str) -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "greeting",
+        "version": version,
+        "description": description,
+        "specVersion": "0.8",
+        "start": "Greet",
+        "states": [
+            {
+                "name": "Greet",
+                "type": "inject",
+                "data": { "greeting": "Hello" },
+                "end": true
+            }
+        ]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_register() {
+    let mut registry = WorkflowRegistry::new();
+    registry.register(definition("1.0", "Greets")).unwrap();
+    registry.register(definition("2.0", "Greets")).unwrap();
+
+    assert_eq!(2, registry.len());
+    assert!(registry.get("greeting", Some("1.0")).is_some());
+    assert!(registry.get("greeting", Some("3.0")).is_none());
+    assert!(registry.get("greeting", None).is_none());
+    assert_eq!(
+        vec!["greeting@1.0", "greeting@2.0"],
+        registry
+            .iter()
+            .map(|(key, _)| key.to_string())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_register_same_definition_twice() {
+    let mut registry = WorkflowRegistry::new();
+    let first_hash = registry
+        .register(definition("1.0", "Greets"))
+        .unwrap()
+        .content_hash();
+    let second_hash = registry
+        .register(definition("1.0", "Greets"))
+        .unwrap()
+        .content_hash();
+
+    assert_eq!(first_hash, second_hash);
+    assert_eq!(1, registry.len());
+}
+
+#[test]
+fn test_register_conflicting_definition() {
+    let mut registry = WorkflowRegistry::new();
+    registry.register(definition("1.0", "Greets")).unwrap();
+
+    match registry.register(definition("1.0", "Greets differently")) {
+        Err(travailleur::Error::DuplicateWorkflowDefinition { key, existing_uri, new_uri }) => {
+            assert_eq!(WorkflowKey { id: "greeting".into(), version: Some("1.0".into()) }, key);
+            assert!(existing_uri.is_none());
+            assert!(new_uri.is_none());
+        },
+        result => panic!("expected duplicate workflow definition error, got {:?}", result),
+    }
+
+    let definition = registry.get("greeting", Some("1.0")).unwrap().definition();
+    assert_eq!(Some("Greets"), definition.description.as_deref());
+}
+
+#[test]
+fn test_register_uri() {
+    let mut cache = DefinitionCache::new();
+    let mut registry = WorkflowRegistry::new();
+    let json_uri = example_uri("applicantrequest.json");
+
+    let registered = registry
+        .register_uri(json_uri.as_str(), &mut cache)
+        .unwrap();
+    assert_eq!(Some(json_uri.as_str()), registered.uri().map(|uri| uri.as_str()));
+
+    // Same workflow in a different format has the same content, so it is not a conflict.
+    #[cfg(feature = "yaml")]
+    registry
+        .register_uri(example_uri("applicantrequest.yaml").as_str(), &mut cache)
+        .unwrap();
+    assert_eq!(1, registry.len());
+}
+
+#[test]
+fn test_register_uri_conflicting_definition() {
+    let mut cache = DefinitionCache::new();
+    let mut registry = WorkflowRegistry::new();
+    let json_uri = example_uri("applicantrequest.json");
+
+    let mut definition = (*cache
+        .get_or_insert::<WorkflowDefinition, _>(json_uri.as_str())
+        .unwrap())
+    .clone();
+    definition.description = Some("Modified".into());
+    registry.register(definition).unwrap();
+
+    match registry.register_uri(json_uri.as_str(), &mut cache) {
+        Err(travailleur::Error::DuplicateWorkflowDefinition { existing_uri, new_uri, .. }) => {
+            assert!(existing_uri.is_none());
+            assert_eq!(Some(json_uri), new_uri);
+        },
+        result => panic!("expected duplicate workflow definition error, got {:?}", result),
+    }
+}
+
+#[test]
+fn test_snapshot_restore() {
+    let mut cache = DefinitionCache::new();
+    let mut registry = WorkflowRegistry::new();
+    registry
+        .register_uri(example_uri("applicantrequest.json").as_str(), &mut cache)
+        .unwrap();
+    registry
+        .register_uri(example_uri("greeting.json").as_str(), &mut cache)
+        .unwrap();
+
+    let snapshot = serde_json::to_string(&registry.snapshot()).unwrap();
+    let snapshot: RegistrySnapshot = serde_json::from_str(&snapshot).unwrap();
+    assert_eq!(registry.snapshot(), snapshot);
+
+    let restored = WorkflowRegistry::restore(&snapshot, &mut DefinitionCache::new()).unwrap();
+    assert_eq!(
+        registry
+            .iter()
+            .map(|(key, workflow)| (key.clone(), workflow.uri().cloned(), workflow.content_hash()))
+            .collect::<Vec<_>>(),
+        restored
+            .iter()
+            .map(|(key, workflow)| (key.clone(), workflow.uri().cloned(), workflow.content_hash()))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_restore_failed() {
+    let mut cache = DefinitionCache::new();
+    let mut registry = WorkflowRegistry::new();
+    registry
+        .register_uri(example_uri("greeting.json").as_str(), &mut cache)
+        .unwrap();
+
+    let mut snapshot = registry.snapshot();
+    snapshot.workflows[0].content_hash += 1;
+    match WorkflowRegistry::restore(&snapshot, &mut cache) {
+        Err(travailleur::Error::RegistryRestoreFailed { key, uri, .. }) => {
+            assert_eq!("greeting", key.id);
+            assert_eq!(Some(example_uri("greeting.json")), uri);
+        },
+        result => panic!("expected registry restore error, got {:?}", result),
+    }
+
+    let mut registry = WorkflowRegistry::new();
+    registry.register(definition("1.0", "Greets")).unwrap();
+    match WorkflowRegistry::restore(&registry.snapshot(), &mut cache) {
+        Err(travailleur::Error::RegistryRestoreFailed { uri: None, .. }) => (),
+        result => panic!("expected registry restore error, got {:?}", result),
+    }
+}
+
+fn calling_definition(id: &str, sub_flow: &str, continue_as: &str) -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": id,
+        "specVersion": "0.8",
+        "states": [
+            {
+                "name": "Call",
+                "type": "operation",
+                "actions": [{ "subFlowRef": sub_flow }],
+                "transition": "Check"
+            },
+            {
+                "name": "Check",
+                "type": "switch",
+                "dataConditions": [
+                    { "condition": "${ .again }", "end": { "continueAs": continue_as } }
+                ],
+                "defaultCondition": { "end": true }
+            }
+        ]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_recursive_workflows() {
+    let mut registry = WorkflowRegistry::new();
+    registry
+        .register(calling_definition("parent", "child", "other"))
+        .unwrap();
+    registry
+        .register(calling_definition("child", "grandchild", "other"))
+        .unwrap();
+    registry
+        .register(calling_definition("grandchild", "other", "child"))
+        .unwrap();
+    registry
+        .register(calling_definition("self", "other", "self"))
+        .unwrap();
+    registry.register(definition("1.0", "Greets")).unwrap();
+
+    assert_eq!(
+        vec!["child", "grandchild", "self"],
+        registry
+            .recursive_workflows()
+            .into_iter()
+            .map(WorkflowKey::to_string)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_dependencies() {
+    let mut registry = WorkflowRegistry::new();
+    registry
+        .register(calling_definition("child", "grandchild", "child"))
+        .unwrap();
+    registry
+        .register(calling_definition("grandchild", "greeting", "grandchild"))
+        .unwrap();
+    registry.register(definition("1.0", "Greets")).unwrap();
+    registry.register(definition("2.0", "Greets")).unwrap();
+
+    let parent = calling_definition("parent", "child", "parent");
+    assert!(matches!(
+        registry.dependencies(&parent),
+        Err(travailleur::Error::MissingWorkflowDependency { dependency, referenced_by })
+            if dependency == WorkflowKey { id: "parent".into(), version: None }
+                && referenced_by == Some(WorkflowKey { id: "parent".into(), version: None })
+    ));
+
+    registry.register(parent.clone()).unwrap();
+    assert_eq!(
+        vec!["child", "grandchild", "greeting@1.0", "greeting@2.0", "parent"],
+        registry
+            .dependencies(&parent)
+            .unwrap()
+            .into_iter()
+            .map(WorkflowKey::to_string)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_validate_references() {
+    let mut registry = WorkflowRegistry::new();
+    registry
+        .register(calling_definition("parent", "child", "parent"))
+        .unwrap();
+    registry
+        .register(calling_definition("child", "greeting", "child"))
+        .unwrap();
+    assert!(matches!(
+        registry.validate_references(),
+        Err(travailleur::Error::MissingWorkflowDependency { dependency, referenced_by })
+            if dependency == WorkflowKey { id: "greeting".into(), version: None }
+                && referenced_by == Some(WorkflowKey { id: "child".into(), version: None })
+    ));
+
+    registry.register(definition("1.0", "Greets")).unwrap();
+    registry.validate_references().unwrap();
+}
+
+#[test]
+fn test_resolve_reference() {
+    let parent = calling_definition("parent", "greeting", "parent");
+    let mut registry = WorkflowRegistry::new();
+    registry.register(definition("1.0", "Greets")).unwrap();
+
+    let version = |id: &str, version: Option<&str>| {
+        registry
+            .resolve_reference(&parent, id, version)
+            .map(|workflow| workflow.definition().version.clone())
+    };
+    assert_eq!(Some("1.0".to_string()), version("greeting", None).unwrap());
+    assert_eq!(Some("1.0".to_string()), version("greeting", Some("1.0")).unwrap());
+    assert!(matches!(
+        version("greeting", Some("2.0")),
+        Err(travailleur::Error::MissingWorkflowDependency { dependency, referenced_by })
+            if dependency == WorkflowKey { id: "greeting".into(), version: Some("2.0".into()) }
+                && referenced_by == Some(WorkflowKey { id: "parent".into(), version: None })
+    ));
+
+    registry.register(definition("2.0", "Greets")).unwrap();
+    assert!(matches!(
+        registry.resolve_reference(&parent, "greeting", None),
+        Err(travailleur::Error::AmbiguousWorkflowReference { id, versions })
+            if id == "greeting" && versions == ["1.0", "2.0"]
+    ));
+
+    registry.register(parent.clone()).unwrap();
+    assert!(registry.resolve_reference(&parent, "parent", None).is_ok());
+}
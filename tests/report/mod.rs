@@ -0,0 +1,110 @@
+use serde_json::json;
+use travailleur::validation::lint::{LintConfig, LintLevel, Linter, Severity};
+use travailleur::validation::report::{ValidationFinding, ValidationReport};
+use travailleur::workflow::definition::WorkflowDefinition;
+
+fn definition() -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "deadEnd",
+        "specVersion": "0.8",
+        "description": "Workflow with a dead-end state",
+        "states": [
+            {
+                "name": "Start",
+                "type": "inject",
+                "data": {},
+                "transition": "Stuck",
+            },
+            {
+                "name": "Stuck",
+                "type": "inject",
+                "data": {},
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_lint_findings() {
+    let linter = Linter::new(LintConfig::default());
+    let report = ValidationReport::for_definition(&definition(), &linter);
+
+    assert!(report.has_errors());
+    assert!(report.findings.contains(&ValidationFinding {
+        pointer: "/states/1".into(),
+        code: "dead-end-states".into(),
+        message: "state 'Stuck' has no transition and does not end the workflow".into(),
+        severity: Severity::Error,
+    }));
+}
+
+#[test]
+fn test_serialization() {
+    let config = LintConfig::default().with_level("dead-end-states", LintLevel::Warning);
+    let report = Linter::new(config).lint(&definition());
+    let report = ValidationReport::from(report);
+
+    assert_eq!(
+        json!({
+            "findings": [
+                {
+                    "pointer": "/states/1",
+                    "code": "dead-end-states",
+                    "message": "state 'Stuck' has no transition and does not end the workflow",
+                    "severity": "warning",
+                },
+            ],
+        }),
+        serde_json::to_value(&report).unwrap()
+    );
+}
+
+#[test]
+#[cfg(feature = "validate")]
+fn test_definition_validation_findings() {
+    let report = ValidationReport::for_definition(
+        &definition(),
+        &Linter::without_rules(LintConfig::default()),
+    );
+
+    assert!(!report.is_empty());
+    assert!(report
+        .findings
+        .iter()
+        .all(|finding| finding.code == "definition"
+            && finding.severity == Severity::Error
+            && finding.pointer.starts_with("/states/1")));
+}
+
+#[test]
+#[cfg(feature = "validate")]
+fn test_definition_validation_pointers() {
+    let definition: WorkflowDefinition = serde_json::from_value(json!({
+        "id": "invalidTransition",
+        "specVersion": "0.8",
+        "states": [
+            {
+                "name": "Start",
+                "type": "inject",
+                "data": {},
+                "transition": { "nextState": "" },
+            },
+        ],
+    }))
+    .unwrap();
+
+    let report = ValidationReport::for_definition(
+        &definition,
+        &Linter::without_rules(LintConfig::default()),
+    );
+
+    assert!(
+        report
+            .findings
+            .iter()
+            .any(|finding| finding.pointer == "/states/0/transition/nextState"),
+        "unexpected findings: {:?}",
+        report.findings
+    );
+}
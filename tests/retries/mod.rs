@@ -0,0 +1,199 @@
+use std::time::Duration;
+
+use serde_json::json;
+use travailleur::workflow::definition::retries::{Jitter, RetryDef};
+use travailleur::workflow::definition::WorkflowDefinition;
+use travailleur::workflow::runtime::retry::{ActionRetries, RetryDecision};
+
+fn definition(auto_retries: bool) -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "orders",
+        "specVersion": "0.8",
+        "autoRetries": auto_retries,
+        "functions": [{ "name": "charge", "operation": "charge", "type": "custom" }],
+        "errors": [
+            { "name": "ServiceUnavailable", "code": "503" },
+            { "name": "InvalidCard", "code": "402" },
+        ],
+        "retries": [{
+            "name": "backoff",
+            "delay": "PT1S",
+            "multiplier": 2,
+            "maxDelay": "PT3S",
+            "maxAttempts": 4,
+        }],
+        "states": [{
+            "name": "Charge",
+            "type": "operation",
+            "actions": [
+                {
+                    "functionRef": "charge",
+                    "retryRef": "backoff",
+                    "retryableErrors": ["ServiceUnavailable"],
+                    "nonRetryableErrors": ["InvalidCard"],
+                },
+                { "functionRef": "charge" },
+                { "functionRef": "charge", "retryRef": "missing" },
+            ],
+            "end": true,
+        }],
+    }))
+    .unwrap()
+}
+
+fn failure(error: &str) -> travailleur::Error {
+    travailleur::Error::ActionFailed {
+        function: "charge".into(),
+        error: error.into(),
+        reason: "failed".into(),
+    }
+}
+
+#[test]
+fn test_retryable_errors() {
+    let definition = definition(false);
+    let actions = definition.states[0].actions();
+    let retries = ActionRetries::new(&definition, actions[0]).unwrap();
+
+    assert!(retries.is_retryable("ServiceUnavailable"));
+    assert!(retries.is_retryable("503"));
+    assert!(!retries.is_retryable("InvalidCard"));
+    assert!(!retries.is_retryable("Unknown"));
+
+    let retries = ActionRetries::new(&definition, actions[1]).unwrap();
+    assert!(!retries.is_retryable("ServiceUnavailable"));
+}
+
+#[test]
+fn test_auto_retries() {
+    let definition = definition(true);
+    let actions = definition.states[0].actions();
+    let retries = ActionRetries::new(&definition, actions[0]).unwrap();
+
+    assert!(retries.is_retryable("ServiceUnavailable"));
+    assert!(!retries.is_retryable("InvalidCard"));
+    assert!(!retries.is_retryable("402"));
+    assert!(retries.is_retryable("Unknown"));
+
+    let retries = ActionRetries::new(&definition, actions[1]).unwrap();
+    assert!(retries.is_retryable("InvalidCard"));
+}
+
+#[test]
+fn test_decide() {
+    let definition = definition(false);
+    let actions = definition.states[0].actions();
+    let retries = ActionRetries::new(&definition, actions[0]).unwrap();
+    assert_eq!("backoff", retries.strategy().unwrap().name);
+    assert_eq!(4, retries.max_attempts().unwrap());
+
+    let error = failure("503");
+    assert_eq!(
+        RetryDecision::Retry { attempt: 2, delay: Duration::from_secs(1) },
+        retries.decide(&error, 1).unwrap()
+    );
+    assert_eq!(
+        RetryDecision::Retry { attempt: 3, delay: Duration::from_secs(2) },
+        retries.decide(&error, 2).unwrap()
+    );
+    assert_eq!(
+        RetryDecision::Retry { attempt: 4, delay: Duration::from_secs(3) },
+        retries.decide(&error, 3).unwrap()
+    );
+    assert_eq!(RetryDecision::Exhausted, retries.decide(&error, 4).unwrap());
+
+    assert_eq!(RetryDecision::NotRetryable, retries.decide(&failure("InvalidCard"), 1).unwrap());
+    let error = travailleur::Error::UnknownFunction { function: "charge".into() };
+    assert_eq!(RetryDecision::NotRetryable, retries.decide(&error, 1).unwrap());
+}
+
+#[test]
+fn test_default_strategy() {
+    let definition = definition(true);
+    let actions = definition.states[0].actions();
+
+    let retries = ActionRetries::new(&definition, actions[1]).unwrap();
+    assert!(retries.strategy().is_none());
+    assert_eq!(1, retries.max_attempts().unwrap());
+    assert_eq!(RetryDecision::Exhausted, retries.decide(&failure("503"), 1).unwrap());
+
+    let default: RetryDef = serde_json::from_value(json!({
+        "name": "default",
+        "delay": "PT0.5S",
+        "increment": "PT0.25S",
+        "maxAttempts": 3,
+    }))
+    .unwrap();
+    let retries = retries.with_default_strategy(&default);
+    assert_eq!(Duration::from_millis(500), retries.delay(1).unwrap());
+    assert_eq!(Duration::from_millis(750), retries.delay(2).unwrap());
+    assert_eq!(
+        RetryDecision::Retry { attempt: 3, delay: Duration::from_millis(750) },
+        retries.decide(&failure("503"), 2).unwrap()
+    );
+
+    let retries = ActionRetries::new(&definition, actions[0])
+        .unwrap()
+        .with_default_strategy(&default);
+    assert_eq!("backoff", retries.strategy().unwrap().name);
+}
+
+#[test]
+fn test_unknown_retry() {
+    let definition = definition(false);
+    let actions = definition.states[0].actions();
+
+    assert!(matches!(
+        ActionRetries::new(&definition, actions[2]),
+        Err(travailleur::Error::UnknownRetry { retry }) if retry == "missing"
+    ));
+}
+
+#[test]
+fn test_fixed() {
+    let retry = RetryDef::fixed("fixed", Duration::from_secs(90), 3);
+
+    assert_eq!(
+        json!({ "name": "fixed", "delay": "PT1M30S", "maxAttempts": 3, "jitter": null }),
+        serde_json::to_value(&retry).unwrap()
+    );
+}
+
+#[test]
+fn test_linear() {
+    let retry = RetryDef::linear("linear", Duration::from_secs(1), Duration::from_millis(500), 5);
+
+    assert_eq!(Some("PT1S"), retry.delay.as_deref());
+    assert_eq!(Some("PT0.5S"), retry.increment.as_deref());
+    assert!(retry.multiplier.is_none());
+}
+
+#[test]
+fn test_exponential() {
+    let retry = RetryDef::exponential("exponential", Duration::from_millis(200), 10)
+        .with_max_delay(Duration::from_secs(3600))
+        .with_jitter(Jitter::Float(0.1));
+
+    let value = serde_json::to_value(&retry).unwrap();
+    assert_eq!(
+        json!({
+            "name": "exponential",
+            "delay": "PT0.2S",
+            "maxDelay": "PT1H",
+            "multiplier": 2.0,
+            "maxAttempts": 10,
+            "jitter": 0.1
+        }),
+        value
+    );
+    assert_eq!(retry, serde_json::from_value(value).unwrap());
+}
+
+#[test]
+fn test_with_multiplier_rounds_to_hundredths() {
+    let retry = RetryDef::exponential("exponential", Duration::from_secs(1), 3);
+
+    let multiplier = |retry: RetryDef| retry.multiplier.unwrap().value().unwrap();
+    assert_eq!(1.57, multiplier(retry.clone().with_multiplier(1.5678)));
+    assert_eq!(0.0, multiplier(retry.with_multiplier(-2.0)));
+}
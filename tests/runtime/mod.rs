@@ -0,0 +1,1189 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use serde_json::{json, Map, Value};
+use travailleur::clock::{Clock, TestClock};
+use travailleur::registry::WorkflowRegistry;
+use travailleur::workflow::definition::{
+    EventBasedSwitchState, EventState, ParallelState, State, SubflowRef, SwitchSelection,
+    SwitchState, WorkflowDefinition,
+};
+use travailleur::workflow::executor::{
+    ActionExecutor, FunctionCall, HandlerActionExecutor, MockActionExecutor,
+};
+use travailleur::workflow::instance::WorkflowInstance;
+use travailleur::workflow::runtime::compensation::{CompensationLog, StateOutput};
+use travailleur::workflow::runtime::continue_as::ContinueAsExecution;
+use travailleur::workflow::runtime::end::{EndExecution, EndOutcome};
+use travailleur::workflow::runtime::event::EventStateExecution;
+use travailleur::workflow::runtime::filters::{filter_state_input, filter_state_output};
+use travailleur::workflow::runtime::inject::InjectStateExecution;
+use travailleur::workflow::runtime::parallel::ParallelStateExecution;
+use travailleur::workflow::runtime::subflow::SubflowInvocation;
+use travailleur::workflow::runtime::switch::EventSwitchExecution;
+use travailleur::workflow::subscription::EventSubscription;
+
+// Evaluates simple paths (e.g. `.a.b`) and boolean literals.
+fn evaluate(expression: &str, data: &Value) -> travailleur::Result<Value> {
+    match expression {
+        "true" | "false" => Ok(Value::Bool(expression == "true")),
+        "." => Ok(data.clone()),
+        _ => Ok(data
+            .pointer(&expression.replace('.', "/"))
+            .cloned()
+            .unwrap_or_default()),
+    }
+}
+
+fn definition(exclusive: bool, on_events: Value) -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "orders",
+        "specVersion": "0.8",
+        "events": [
+            { "name": "OrderPlaced", "type": "order.placed" },
+            { "name": "PaymentReceived", "type": "payment.received" },
+            { "name": "OrderShipped", "type": "order.shipped" },
+        ],
+        "functions": [
+            { "name": "reserve", "operation": "file://api.json#reserve" },
+            { "name": "charge", "operation": "file://api.json#charge" },
+            { "name": "ship", "operation": "file://api.json#ship" },
+        ],
+        "states": [
+            {
+                "name": "AwaitOrder",
+                "type": "event",
+                "exclusive": exclusive,
+                "onEvents": on_events,
+                "end": true,
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+fn event_state(definition: &WorkflowDefinition) -> &EventState {
+    match &definition.states[0] {
+        State::Event(state) => state,
+        _ => unreachable!(),
+    }
+}
+
+fn data(value: Value) -> Map<String, Value> {
+    value.as_object().unwrap().clone()
+}
+
+#[test]
+fn test_exclusive() {
+    let definition = definition(
+        true,
+        json!([
+            {
+                "eventRefs": ["OrderPlaced"],
+                "actions": [{ "functionRef": "reserve" }],
+            },
+            {
+                "eventRefs": ["PaymentReceived", "OrderShipped"],
+                "actions": [{ "functionRef": "charge" }],
+            },
+        ]),
+    );
+    let mut execution = EventStateExecution::new(event_state(&definition));
+    assert!(!execution.is_complete());
+    assert_eq!(vec!["OrderPlaced", "PaymentReceived", "OrderShipped"], execution.pending_events());
+    assert!(execution.triggered().is_empty());
+
+    assert!(!execution.consume("OrderCancelled", json!({})));
+    assert!(execution.consume("OrderShipped", json!({ "shipped": true })));
+    assert!(execution.is_complete());
+    assert!(!execution.is_satisfied(0));
+    assert!(execution.is_satisfied(1));
+    assert!(execution.pending_events().is_empty());
+    assert!(!execution.consume("OrderPlaced", json!({})));
+
+    let executor = MockActionExecutor::new()
+        .with_result("reserve", json!({ "reserved": true }))
+        .with_result("charge", json!({ "charged": true }));
+    let data = execution
+        .run(&definition, data(json!({ "id": 42 })), &executor, &evaluate)
+        .unwrap();
+    assert_eq!(json!({ "id": 42, "shipped": true, "charged": true }), Value::Object(data));
+}
+
+#[test]
+fn test_non_exclusive() {
+    let definition = definition(
+        false,
+        json!([
+            {
+                "eventRefs": ["OrderPlaced", "PaymentReceived"],
+                "eventDataFilter": { "data": "${ .items }", "toStateData": "${ .order.items }" },
+                "actions": [
+                    {
+                        "name": "reserve",
+                        "functionRef": {
+                            "refName": "reserve",
+                            "arguments": { "items": "${ .items }", "warehouse": "main" },
+                        },
+                        "actionDataFilter": {
+                            "fromStateData": "${ .order }",
+                            "results": "${ .reservation }",
+                            "toStateData": "${ .order.reservation }",
+                        },
+                    },
+                ],
+            },
+            {
+                "eventRefs": ["OrderShipped"],
+                "eventDataFilter": { "useData": false },
+            },
+        ]),
+    );
+    let mut execution = EventStateExecution::new(event_state(&definition));
+    assert!(execution.consume("OrderPlaced", json!({ "items": ["book"] })));
+    assert!(execution.consume("OrderShipped", json!({ "items": ["ignored"] })));
+    assert!(!execution.is_complete());
+    assert!(!execution.is_satisfied(0));
+    assert!(execution.is_satisfied(1));
+    assert_eq!(vec!["PaymentReceived"], execution.pending_events());
+    assert!(execution.triggered().is_empty());
+
+    assert!(execution.consume("PaymentReceived", json!({ "items": ["pen"] })));
+    assert!(execution.is_complete());
+    assert_eq!(2, execution.triggered().len());
+
+    let executor = HandlerActionExecutor::new().with_handler("reserve", |call| {
+        assert_eq!(Some("reserve"), call.context.action.as_deref());
+        Ok(json!({ "reservation": { "arguments": call.arguments } }))
+    });
+    let data = execution
+        .run(&definition, data(json!({ "order": { "items": ["cup"] } })), &executor, &evaluate)
+        .unwrap();
+    assert_eq!(
+        json!({
+            "order": {
+                "items": ["cup", "book", "pen"],
+                "reservation": {
+                    "arguments": { "items": ["cup", "book", "pen"], "warehouse": "main" },
+                },
+            },
+        }),
+        Value::Object(data)
+    );
+}
+
+#[test]
+fn test_action_modes() {
+    let on_events = |action_mode| {
+        json!([
+            {
+                "eventRefs": ["OrderPlaced"],
+                "actionMode": action_mode,
+                "actions": [
+                    {
+                        "functionRef": { "refName": "reserve", "arguments": { "step": "${ .step }" } },
+                    },
+                    {
+                        "functionRef": { "refName": "charge", "arguments": { "step": "${ .step }" } },
+                    },
+                    {
+                        "functionRef": "ship",
+                        "condition": "${ false }",
+                    },
+                ],
+            },
+        ])
+    };
+    let executor = HandlerActionExecutor::new()
+        .with_handler("reserve", |_| Ok(json!({ "step": 1 })))
+        .with_handler("charge", |call| Ok(json!({ "step": call.arguments["step"] })));
+    let run = |action_mode| {
+        let definition = definition(true, on_events(action_mode));
+        let mut execution = EventStateExecution::new(event_state(&definition));
+        execution.consume("OrderPlaced", json!({}));
+        execution
+            .run(&definition, data(json!({ "step": 0 })), &executor, &evaluate)
+            .map(Value::Object)
+    };
+
+    assert_eq!(json!({ "step": 1 }), run("sequential").unwrap());
+    assert_eq!(json!({ "step": 0 }), run("parallel").unwrap());
+}
+
+#[test]
+fn test_errors() {
+    let run = |action: Value| {
+        let definition =
+            definition(true, json!([{ "eventRefs": ["OrderPlaced"], "actions": [action] }]));
+        let mut execution = EventStateExecution::new(event_state(&definition));
+        execution.consume("OrderPlaced", json!({}));
+        execution.run(&definition, Map::new(), &MockActionExecutor::new(), &evaluate)
+    };
+
+    assert!(matches!(
+        run(json!({ "functionRef": "reserve", "condition": "${ .missing }" })),
+        Err(travailleur::Error::InvalidExpressionResult { expression, .. })
+            if expression == "${ .missing }"
+    ));
+    assert!(matches!(
+        run(json!({ "subFlowRef": "shipping" })),
+        Err(travailleur::Error::UnsupportedAction { action, .. }) if action == "#0"
+    ));
+    assert!(matches!(
+        run(json!({ "name": "notify", "functionRef": "notify" })),
+        Err(travailleur::Error::UnknownFunction { function }) if function == "notify"
+    ));
+    assert!(matches!(
+        run(json!({
+            "functionRef": "reserve",
+            "actionDataFilter": { "toStateData": "${ .a | .b }" },
+        })),
+        Err(travailleur::Error::StateDataMergeFailed { element, .. }) if element == "${ .a | .b }"
+    ));
+}
+
+fn switch_definition(timeouts: Value) -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "approval",
+        "specVersion": "0.8",
+        "timeouts": { "eventTimeout": "PT1H" },
+        "events": [
+            { "name": "Approved", "type": "approval.approved" },
+            { "name": "Rejected", "type": "approval.rejected" },
+        ],
+        "states": [
+            {
+                "name": "AwaitDecision",
+                "type": "switch",
+                "timeouts": timeouts,
+                "eventConditions": [
+                    {
+                        "eventRef": "Approved",
+                        "eventDataFilter": { "data": "${ .approver }", "toStateData": "${ .approvedBy }" },
+                        "transition": "Approve",
+                    },
+                    {
+                        "eventRef": "Rejected",
+                        "eventDataFilter": { "useData": false },
+                        "end": true,
+                    },
+                ],
+                "defaultCondition": { "transition": "Escalate" },
+            },
+            { "name": "Approve", "type": "inject", "data": {}, "end": true },
+            { "name": "Escalate", "type": "inject", "data": {}, "end": true },
+        ],
+    }))
+    .unwrap()
+}
+
+fn switch_state(definition: &WorkflowDefinition) -> &EventBasedSwitchState {
+    match &definition.states[0] {
+        State::Switch(SwitchState::EventBased(state)) => state,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_event_switch() {
+    let definition = switch_definition(json!({ "eventTimeout": "PT5M" }));
+    let clock = TestClock::default();
+    let mut execution =
+        EventSwitchExecution::new(&definition, switch_state(&definition), clock.now()).unwrap();
+    assert_eq!(Some(clock.now() + Duration::from_secs(300)), execution.expires_at());
+    assert_eq!(None, execution.selection());
+
+    clock.advance(Duration::from_secs(299));
+    assert_eq!(None, execution.time_out(&clock.now()));
+    assert_eq!(None, execution.consume("Unknown", json!({})));
+    assert_eq!(
+        Some(SwitchSelection::Condition(0)),
+        execution.consume("Approved", json!({ "approver": "alice" }))
+    );
+    assert_eq!(None, execution.consume("Rejected", json!({})));
+    assert_eq!(Some("Approved"), execution.selected_condition().map(|c| c.event_ref()));
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(None, execution.time_out(&clock.now()));
+    assert_eq!(Some(SwitchSelection::Condition(0)), execution.selection());
+    assert_eq!(
+        json!({ "request": 1, "approvedBy": "alice" }),
+        Value::Object(
+            execution
+                .run(data(json!({ "request": 1 })), &evaluate)
+                .unwrap()
+        )
+    );
+
+    let mut execution =
+        EventSwitchExecution::new(&definition, switch_state(&definition), clock.now()).unwrap();
+    execution.consume("Rejected", json!({ "approver": "bob" }));
+    assert_eq!(
+        json!({ "request": 1 }),
+        Value::Object(
+            execution
+                .run(data(json!({ "request": 1 })), &evaluate)
+                .unwrap()
+        )
+    );
+}
+
+#[test]
+fn test_event_switch_timeout() {
+    let definition = switch_definition(Value::Null);
+    let clock = TestClock::default();
+    let mut execution =
+        EventSwitchExecution::new(&definition, switch_state(&definition), clock.now()).unwrap();
+    assert_eq!(Some(clock.now() + Duration::from_secs(3600)), execution.expires_at());
+
+    clock.advance(Duration::from_secs(3600));
+    assert_eq!(Some(SwitchSelection::Default), execution.time_out(&clock.now()));
+    assert_eq!(None, execution.time_out(&clock.now()));
+    assert_eq!(None, execution.consume("Approved", json!({ "approver": "alice" })));
+    assert!(execution.selected_condition().is_none());
+    assert_eq!(
+        json!({ "request": 1 }),
+        Value::Object(
+            execution
+                .run(data(json!({ "request": 1 })), &evaluate)
+                .unwrap()
+        )
+    );
+}
+
+fn parallel_definition(parallel: Value) -> WorkflowDefinition {
+    let mut state = json!({
+        "name": "Prepare",
+        "type": "parallel",
+        "end": true,
+    });
+    state
+        .as_object_mut()
+        .unwrap()
+        .extend(parallel.as_object().unwrap().clone());
+
+    serde_json::from_value(json!({
+        "id": "orders",
+        "specVersion": "0.8",
+        "functions": [
+            { "name": "fast", "operation": "file://api.json#fast" },
+            { "name": "slow", "operation": "file://api.json#slow" },
+            { "name": "fail", "operation": "file://api.json#fail" },
+        ],
+        "states": [state],
+    }))
+    .unwrap()
+}
+
+fn parallel_state(definition: &WorkflowDefinition) -> &ParallelState {
+    match &definition.states[0] {
+        State::Parallel(state) => state,
+        _ => unreachable!(),
+    }
+}
+
+// Executor returning its arguments, after sleeping on its clock for the `slow` function.
+#[derive(Default)]
+struct BranchExecutor {
+    clock: TestClock,
+    calls: AtomicUsize,
+    deadlines: AtomicUsize,
+}
+
+impl ActionExecutor for BranchExecutor {
+    fn execute(&self, call: &FunctionCall<'_>) -> travailleur::Result<Value> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if call.context.deadline.is_some() {
+            self.deadlines.fetch_add(1, Ordering::SeqCst);
+        }
+
+        match call.function.name.as_str() {
+            "slow" => self.clock.sleep(Duration::from_millis(200)),
+            "fail" => {
+                return Err(travailleur::Error::ActionFailed {
+                    function: call.function.name.clone(),
+                    error: "Unavailable".into(),
+                    reason: "service unavailable".into(),
+                })
+            },
+            _ => (),
+        }
+        Ok(Value::Object(call.arguments.clone()))
+    }
+}
+
+fn call(function: &str, arguments: Value) -> Value {
+    json!({ "functionRef": { "refName": function, "arguments": arguments } })
+}
+
+#[test]
+fn test_parallel_all_of() {
+    let definition = parallel_definition(json!({
+        "branches": [
+            { "name": "reserve", "actions": [call("fast", json!({ "reserved": true }))] },
+            {
+                "name": "charge",
+                "actions": [
+                    call("slow", json!({ "charged": true })),
+                    call("fast", json!({ "receipt": "r-1" })),
+                ],
+            },
+        ],
+    }));
+    let execution = ParallelStateExecution::new(parallel_state(&definition));
+    assert_eq!(2, execution.required_branches().unwrap());
+
+    let executor = BranchExecutor::default();
+    let data = execution
+        .run(&executor.clock, &definition, data(json!({ "order": 42 })), &executor, &evaluate)
+        .unwrap();
+    assert_eq!(
+        json!({ "order": 42, "reserved": true, "charged": true, "receipt": "r-1" }),
+        Value::Object(data)
+    );
+    assert_eq!(3, executor.calls.load(Ordering::SeqCst));
+    assert_eq!(0, executor.deadlines.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_parallel_merge() {
+    let to_results = |function: &str, arguments: Value| {
+        let mut action = call(function, arguments);
+        action["actionDataFilter"] = json!({ "toStateData": ".results" });
+        action
+    };
+    let definition = parallel_definition(json!({
+        "branches": [
+            { "name": "reserve", "actions": [to_results("fast", json!({ "a": 1 }))] },
+            { "name": "charge", "actions": [to_results("fast", json!({ "b": 2 }))] },
+        ],
+    }));
+    let execution = ParallelStateExecution::new(parallel_state(&definition));
+
+    // Branches updating different properties of the same object do not override each other.
+    let executor = BranchExecutor::default();
+    let data = execution
+        .run(
+            &executor.clock,
+            &definition,
+            data(json!({ "order": 42, "results": { "c": 3 } })),
+            &executor,
+            &evaluate,
+        )
+        .unwrap();
+    assert_eq!(json!({ "order": 42, "results": { "a": 1, "b": 2, "c": 3 } }), Value::Object(data));
+}
+
+#[test]
+fn test_parallel_at_least() {
+    let definition = parallel_definition(json!({
+        "completionType": "atLeast",
+        "numCompleted": 1,
+        "branches": [
+            {
+                "name": "charge",
+                "timeouts": { "branchExecTimeout": "PT0.1S" },
+                "actions": [
+                    call("slow", json!({ "charged": true })),
+                    call("fast", json!({ "receipt": "r-1" })),
+                ],
+            },
+            { "name": "reserve", "actions": [call("fast", json!({ "reserved": true }))] },
+        ],
+    }));
+    let execution = ParallelStateExecution::new(parallel_state(&definition));
+    assert_eq!(1, execution.required_branches().unwrap());
+
+    // The slow branch times out (or is cancelled) before calling its second function.
+    let executor = BranchExecutor::default();
+    let data = execution
+        .run(&executor.clock, &definition, Map::new(), &executor, &evaluate)
+        .unwrap();
+    assert_eq!(json!({ "reserved": true }), Value::Object(data));
+    assert!(executor.calls.load(Ordering::SeqCst) <= 2);
+
+    // Failing branches are tolerated as long as enough branches complete.
+    let definition = parallel_definition(json!({
+        "completionType": "atLeast",
+        "numCompleted": "1",
+        "branches": [
+            { "name": "charge", "actions": [call("fail", json!({}))] },
+            { "name": "reserve", "actions": [call("slow", json!({ "reserved": true }))] },
+        ],
+    }));
+    let execution = ParallelStateExecution::new(parallel_state(&definition));
+    let executor = BranchExecutor::default();
+    let data = execution
+        .run(&executor.clock, &definition, Map::new(), &executor, &evaluate)
+        .unwrap();
+    assert_eq!(json!({ "reserved": true }), Value::Object(data));
+}
+
+#[test]
+fn test_parallel_timeouts() {
+    let definition = parallel_definition(json!({
+        "timeouts": { "branchExecTimeout": "PT0.05S" },
+        "completionType": "atLeast",
+        "numCompleted": 1,
+        "branches": [
+            { "name": "charge", "actions": [call("slow", json!({ "charged": true }))] },
+            {
+                "name": "reserve",
+                "timeouts": { "branchExecTimeout": "PT1M" },
+                "actions": [call("slow", json!({ "reserved": true }))],
+            },
+        ],
+    }));
+    let state = parallel_state(&definition);
+    let execution = ParallelStateExecution::new(state);
+    assert_eq!(
+        Some(Duration::from_millis(50)),
+        execution
+            .branch_timeout(&definition, &state.branches[0])
+            .unwrap()
+    );
+    assert_eq!(
+        Some(Duration::from_secs(60)),
+        execution
+            .branch_timeout(&definition, &state.branches[1])
+            .unwrap()
+    );
+
+    // The charge branch times out (or is cancelled), while the reserve branch, whose timeout
+    // is longer, completes.
+    let executor = BranchExecutor::default();
+    let data = execution
+        .run(&executor.clock, &definition, Map::new(), &executor, &evaluate)
+        .unwrap();
+    assert_eq!(json!({ "reserved": true }), Value::Object(data));
+    assert_eq!(executor.calls.load(Ordering::SeqCst), executor.deadlines.load(Ordering::SeqCst));
+
+    let definition = parallel_definition(json!({
+        "timeouts": { "branchExecTimeout": "PT0.05S" },
+        "branches": [
+            { "name": "charge", "actions": [call("slow", json!({ "charged": true }))] },
+            { "name": "reserve", "actions": [call("fast", json!({ "reserved": true }))] },
+        ],
+    }));
+    let execution = ParallelStateExecution::new(parallel_state(&definition));
+    let executor = BranchExecutor::default();
+    assert!(matches!(
+        execution.run(&executor.clock, &definition, Map::new(), &executor, &evaluate),
+        Err(travailleur::Error::BranchTimedOut { state, branch })
+            if state == "Prepare" && branch == "charge"
+    ));
+}
+
+#[test]
+fn test_parallel_errors() {
+    let definition = parallel_definition(json!({
+        "branches": [
+            { "name": "charge", "actions": [call("fail", json!({}))] },
+            { "name": "reserve", "actions": [call("fast", json!({ "reserved": true }))] },
+        ],
+    }));
+    let execution = ParallelStateExecution::new(parallel_state(&definition));
+    let executor = BranchExecutor::default();
+    assert!(matches!(
+        execution.run(&executor.clock, &definition, Map::new(), &executor, &evaluate),
+        Err(travailleur::Error::ActionFailed { function, .. }) if function == "fail"
+    ));
+
+    for num_completed in [json!(3), json!(null)] {
+        let definition = parallel_definition(json!({
+            "completionType": "atLeast",
+            "numCompleted": num_completed,
+            "branches": [
+                { "name": "charge", "actions": [call("fast", json!({}))] },
+                { "name": "reserve", "actions": [call("fast", json!({}))] },
+            ],
+        }));
+        let execution = ParallelStateExecution::new(parallel_state(&definition));
+        let executor = BranchExecutor::default();
+        assert!(matches!(
+            execution.run(&executor.clock, &definition, Map::new(), &executor, &evaluate),
+            Err(travailleur::Error::InvalidCompletionCount { state, .. }) if state == "Prepare"
+        ));
+    }
+}
+
+fn inject_definition(state_data_filter: Value) -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "orders",
+        "specVersion": "0.8",
+        "states": [
+            {
+                "name": "Defaults",
+                "type": "inject",
+                "data": {
+                    "order": { "priority": "normal", "items": ["gift-wrap"] },
+                    "status": "pending",
+                },
+                "stateDataFilter": state_data_filter,
+                "end": true,
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_inject_state() {
+    let definition = inject_definition(json!({}));
+    let State::Inject(state) = &definition.states[0] else {
+        unreachable!();
+    };
+
+    let execution = InjectStateExecution::new(state);
+    assert_eq!("Defaults", execution.state().name);
+    assert_eq!(
+        data(json!({
+            "order": { "id": 42, "priority": "normal", "items": ["book", "gift-wrap"] },
+            "status": "pending",
+            "customer": "jane",
+        })),
+        execution.run(data(json!({
+            "order": { "id": 42, "items": ["book"] },
+            "status": "new",
+            "customer": "jane",
+        })))
+    );
+}
+
+#[test]
+fn test_state_data_filters() {
+    let definition = inject_definition(json!({ "input": "${ .order }", "output": "${ .result }" }));
+    let state = &definition.states[0];
+    let State::Inject(inject) = state else {
+        unreachable!();
+    };
+
+    let input = filter_state_input(
+        state,
+        data(json!({ "order": { "id": 42 }, "customer": "jane" })),
+        &evaluate,
+    )
+    .unwrap();
+    assert_eq!(data(json!({ "id": 42 })), input);
+
+    let mut output = InjectStateExecution::new(inject).run(input);
+    output.insert("result".into(), json!({ "id": 42, "status": "pending" }));
+    assert_eq!(
+        data(json!({ "id": 42, "status": "pending" })),
+        filter_state_output(state, output, &evaluate).unwrap()
+    );
+
+    // States without filters leave the state data unchanged.
+    let definition = inject_definition(json!({}));
+    let unchanged = data(json!({ "order": { "id": 42 } }));
+    assert_eq!(
+        unchanged,
+        filter_state_input(&definition.states[0], unchanged.clone(), &evaluate).unwrap()
+    );
+    assert_eq!(
+        unchanged,
+        filter_state_output(&definition.states[0], unchanged.clone(), &evaluate).unwrap()
+    );
+
+    // Filters must evaluate to objects.
+    let definition = inject_definition(json!({ "input": "${ .order.id }" }));
+    assert!(matches!(
+        filter_state_input(&definition.states[0], unchanged, &evaluate),
+        Err(travailleur::Error::InvalidExpressionResult { expression, .. })
+            if expression == "${ .order.id }"
+    ));
+}
+
+fn compensation_definition(refund_used_for_compensation: bool) -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "orders",
+        "specVersion": "0.8",
+        "states": [
+            {
+                "name": "Reserve",
+                "type": "inject",
+                "data": { "reserved": true },
+                "compensatedBy": "CancelReservation",
+                "transition": "Charge",
+            },
+            {
+                "name": "Charge",
+                "type": "inject",
+                "data": { "charged": true },
+                "compensatedBy": "Refund",
+                "transition": "Check",
+            },
+            {
+                "name": "Check",
+                "type": "inject",
+                "data": {},
+                "transition": { "nextState": "Reserve", "compensate": true },
+            },
+            {
+                "name": "CancelReservation",
+                "type": "inject",
+                "data": { "reserved": false },
+                "usedForCompensation": true,
+                "end": true,
+            },
+            {
+                "name": "Refund",
+                "type": "inject",
+                "data": { "charged": false },
+                "usedForCompensation": refund_used_for_compensation,
+                "transition": "CancelReservation",
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_compensation() {
+    let definition = compensation_definition(true);
+    let mut log = CompensationLog::new();
+    assert!(log.record(&definition, "Reserve").unwrap());
+    assert!(log.record(&definition, "Charge").unwrap());
+    assert!(!log.record(&definition, "Check").unwrap());
+    assert!(log.record(&definition, "Reserve").unwrap());
+    assert_eq!(["Reserve", "Charge", "Reserve"], log.states());
+    assert!(matches!(
+        log.record(&definition, "Ghost"),
+        Err(travailleur::Error::UnknownState { state }) if state == "Ghost"
+    ));
+
+    let State::Inject(check) = &definition.states[2] else {
+        unreachable!();
+    };
+    assert!(check
+        .transition
+        .as_ref()
+        .is_some_and(|transition| transition.compensate()));
+
+    let steps: Vec<_> = log
+        .compensate(&definition)
+        .unwrap()
+        .into_iter()
+        .map(|step| (step.state.name(), step.compensated_by.name()))
+        .collect();
+    assert_eq!(
+        vec![
+            ("Reserve", "CancelReservation"),
+            ("Charge", "Refund"),
+            ("Reserve", "CancelReservation"),
+        ],
+        steps
+    );
+    assert!(log.is_empty());
+
+    // Logs are persisted with workflow instances.
+    let mut instance = WorkflowInstance::for_definition(&definition, None);
+    assert!(serde_json::to_value(&instance)
+        .unwrap()
+        .get("compensation")
+        .is_none());
+    instance.compensation.record(&definition, "Charge").unwrap();
+    let value = serde_json::to_value(&instance).unwrap();
+    assert_eq!(json!(["Charge"]), value["compensation"]);
+    let deserialized: WorkflowInstance = serde_json::from_value(value).unwrap();
+    assert_eq!(instance.compensation, deserialized.compensation);
+}
+
+#[test]
+fn test_compensation_run() {
+    let definition = compensation_definition(true);
+    let mut log = CompensationLog::new();
+    log.record(&definition, "Reserve").unwrap();
+    log.record(&definition, "Charge").unwrap();
+
+    // Runner executing inject states and recording their names.
+    let executed = RefCell::new(Vec::new());
+    let runner = |state: &State, data: Map<String, Value>| {
+        executed.borrow_mut().push(state.name().to_string());
+        let State::Inject(inject) = state else {
+            unreachable!();
+        };
+        Ok(StateOutput {
+            data: InjectStateExecution::new(inject).run(data),
+            next_state: inject
+                .transition
+                .as_ref()
+                .map(|transition| transition.next_state().into()),
+        })
+    };
+
+    let data = log
+        .run(&definition, data(json!({ "reserved": true, "charged": true })), &runner)
+        .unwrap();
+    assert_eq!(json!({ "reserved": false, "charged": false }), Value::Object(data));
+    assert_eq!(["Refund", "CancelReservation", "CancelReservation"], executed.take().as_slice());
+    assert!(log.is_empty());
+
+    // Compensation states can only transition to other states used for compensation.
+    log.record(&definition, "Charge").unwrap();
+    let runner = |state: &State, data: Map<String, Value>| {
+        executed.borrow_mut().push(state.name().to_string());
+        Ok(StateOutput { data, next_state: Some("Check".into()) })
+    };
+    assert!(matches!(
+        log.run(&definition, Map::new(), &runner),
+        Err(travailleur::Error::InvalidCompensation { state, compensated_by })
+            if state == "Charge" && compensated_by == "Check"
+    ));
+    assert_eq!(["Refund"], executed.take().as_slice());
+}
+
+#[test]
+fn test_compensation_errors() {
+    let definition = compensation_definition(false);
+    let mut log = CompensationLog::new();
+    log.record(&definition, "Reserve").unwrap();
+    log.record(&definition, "Charge").unwrap();
+
+    assert!(matches!(
+        log.compensate(&definition),
+        Err(travailleur::Error::InvalidCompensation { state, compensated_by })
+            if state == "Charge" && compensated_by == "Refund"
+    ));
+    assert_eq!(["Reserve", "Charge"], log.states());
+}
+
+fn continue_as_definition(id: &str, continue_as: Value) -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": id,
+        "version": "1.0",
+        "specVersion": "0.8",
+        "timeouts": { "workflowExecTimeout": "PT1H" },
+        "states": [
+            {
+                "name": "Process",
+                "type": "inject",
+                "data": {},
+                "end": { "continueAs": continue_as },
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_continue_as() {
+    let mut registry = WorkflowRegistry::new();
+    registry
+        .register(continue_as_definition("batch", json!("batch")))
+        .unwrap();
+
+    let definition = continue_as_definition(
+        "orders",
+        json!({
+            "workflowId": "batch",
+            "version": "1.0",
+            "data": { "orders": "${ .pending }", "source": "orders" },
+            "workflowExecTimeout": "PT10M",
+        }),
+    );
+    let continue_as = definition.states[0].ends()[0].continue_as().unwrap();
+    let mut instance = WorkflowInstance::for_definition(
+        &definition,
+        Some(data(json!({ "pending": [1, 2], "processed": 3 }))),
+    )
+    .with_label("tenant", "acme");
+
+    let continued = ContinueAsExecution::new(continue_as)
+        .run(&definition, &mut instance, &registry, &evaluate)
+        .unwrap();
+    assert_eq!(None, instance.state);
+    assert_eq!(data(json!({ "pending": [1, 2], "processed": 3 })), instance.data);
+
+    assert_eq!("batch", continued.definition.identifier.id.as_deref().unwrap());
+    let new_instance = &continued.instance;
+    assert_ne!(instance.id, new_instance.id);
+    assert_eq!(Some("Process"), new_instance.state.as_deref());
+    assert_eq!(data(json!({ "orders": [1, 2], "source": "orders" })), new_instance.data);
+    assert_eq!(Some("acme"), new_instance.label("tenant"));
+    assert_eq!(
+        Duration::from_secs(600),
+        new_instance
+            .exec_timeout(&continued.definition)
+            .unwrap()
+            .as_duration()
+            .unwrap()
+    );
+    assert_eq!(
+        Duration::from_secs(3600),
+        instance
+            .exec_timeout(&definition)
+            .unwrap()
+            .as_duration()
+            .unwrap()
+    );
+
+    // Without data, the whole state data is passed; without a timeout, the workflow's is used.
+    let definition = &continued.definition;
+    let continue_as = definition.states[0].ends()[0].continue_as().unwrap();
+    let mut instance = continued.instance.clone();
+    let continued = ContinueAsExecution::new(continue_as)
+        .run(definition, &mut instance, &registry, &evaluate)
+        .unwrap();
+    assert_eq!(instance.data, continued.instance.data);
+    assert_eq!(None, continued.instance.workflow_exec_timeout);
+    assert_eq!(
+        Duration::from_secs(3600),
+        continued
+            .instance
+            .exec_timeout(&continued.definition)
+            .unwrap()
+            .as_duration()
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_continue_as_errors() {
+    let registry = WorkflowRegistry::new();
+    let definition = continue_as_definition("orders", json!("batch"));
+    let continue_as = definition.states[0].ends()[0].continue_as().unwrap();
+    let mut instance = WorkflowInstance::for_definition(&definition, None);
+    assert!(matches!(
+        ContinueAsExecution::new(continue_as).run(&definition, &mut instance, &registry, &evaluate),
+        Err(travailleur::Error::MissingWorkflowDependency { dependency, .. })
+            if dependency.id == "batch"
+    ));
+    assert_eq!(Some("Process"), instance.state.as_deref());
+
+    let definition =
+        continue_as_definition("orders", json!({ "workflowId": "batch", "data": "${ .count }" }));
+    let continue_as = definition.states[0].ends()[0].continue_as().unwrap();
+    assert!(matches!(
+        ContinueAsExecution::new(continue_as).input(&data(json!({ "count": 3 })), &evaluate),
+        Err(travailleur::Error::InvalidExpressionResult { expression, .. })
+            if expression == "${ .count }"
+    ));
+}
+
+#[test]
+fn test_continue_as_max_depth() {
+    let mut registry = WorkflowRegistry::new();
+    registry
+        .register(continue_as_definition("loop", json!("loop")))
+        .unwrap();
+    let definition = continue_as_definition("loop", json!("loop"));
+    let continue_as = definition.states[0].ends()[0].continue_as().unwrap();
+
+    let mut instance = WorkflowInstance::for_definition(&definition, None);
+    assert_eq!(0, instance.depth);
+    let execution = ContinueAsExecution::new(continue_as).with_max_depth(3);
+    assert_eq!(3, execution.max_depth());
+    for depth in 1..=3 {
+        let continued = execution
+            .run(&definition, &mut instance, &registry, &evaluate)
+            .unwrap();
+        assert_eq!(depth, continued.instance.depth);
+        instance = continued.instance;
+    }
+
+    let error = execution
+        .run(&definition, &mut instance, &registry, &evaluate)
+        .unwrap_err();
+    assert!(matches!(
+        &error,
+        travailleur::Error::RecursionLimitExceeded { instance: id, workflow, limit: 3 }
+            if *id == instance.id && workflow == "loop"
+    ));
+    assert_eq!(Some("Process"), instance.state.as_deref());
+    assert_eq!(
+        format!(
+            "workflow instance '{}' cannot run workflow 'loop': maximum depth of 3 exceeded",
+            instance.id
+        ),
+        error.to_string()
+    );
+
+    assert_eq!(3, serde_json::to_value(&instance).unwrap()["depth"]);
+    assert_eq!(100, ContinueAsExecution::new(continue_as).max_depth());
+}
+
+fn subflow_definition(id: &str, subflow_ref: Value) -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": id,
+        "version": "1.0",
+        "specVersion": "0.8",
+        "states": [
+            {
+                "name": "Invoke",
+                "type": "operation",
+                "actions": [{ "subFlowRef": subflow_ref }],
+                "end": true,
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+fn subflow_ref(definition: &WorkflowDefinition) -> &SubflowRef {
+    match &definition.states[0] {
+        State::Operation(state) => state.actions[0].sub_flow_ref.as_ref().unwrap(),
+        state => panic!("expected operation state, got {:?}", state),
+    }
+}
+
+#[test]
+fn test_subflow_invocation() {
+    let mut registry = WorkflowRegistry::new();
+    registry
+        .register(continue_as_definition("batch", json!("batch")))
+        .unwrap();
+    let definition =
+        subflow_definition("orders", json!({ "workflowId": "batch", "version": "1.0" }));
+
+    let instance = WorkflowInstance::for_definition(&definition, None).with_label("tenant", "acme");
+    let invoked = SubflowInvocation::new(subflow_ref(&definition))
+        .run(&definition, &instance, &registry, data(json!({ "orders": [1, 2] })))
+        .unwrap();
+
+    assert_eq!("batch", invoked.definition.identifier.id().unwrap());
+    assert_eq!(Some("Process"), invoked.instance.state.as_deref());
+    assert_eq!(data(json!({ "orders": [1, 2] })), invoked.instance.data);
+    assert_eq!(instance.labels, invoked.instance.labels);
+    assert_eq!(1, invoked.instance.depth);
+    assert_ne!(instance.id, invoked.instance.id);
+
+    let definition = subflow_definition("orders", json!("missing"));
+    assert!(matches!(
+        SubflowInvocation::new(subflow_ref(&definition))
+            .run(&definition, &instance, &registry, Map::new())
+            .unwrap_err(),
+        travailleur::Error::MissingWorkflowDependency { .. }
+    ));
+}
+
+#[test]
+fn test_subflow_max_depth() {
+    let mut registry = WorkflowRegistry::new();
+    registry
+        .register(subflow_definition("recurse", json!("recurse")))
+        .unwrap();
+    let definition = subflow_definition("recurse", json!("recurse"));
+
+    let mut instance = WorkflowInstance::for_definition(&definition, None);
+    let invocation = SubflowInvocation::new(subflow_ref(&definition)).with_max_depth(3);
+    assert_eq!(3, invocation.max_depth());
+    for depth in 1..=3 {
+        let invoked = invocation
+            .run(&definition, &instance, &registry, Map::new())
+            .unwrap();
+        assert_eq!(depth, invoked.instance.depth);
+        instance = invoked.instance;
+    }
+
+    let error = invocation
+        .run(&definition, &instance, &registry, Map::new())
+        .unwrap_err();
+    assert!(matches!(
+        &error,
+        travailleur::Error::RecursionLimitExceeded { instance: id, workflow, limit: 3 }
+            if *id == instance.id && workflow == "recurse"
+    ));
+    assert_eq!(100, SubflowInvocation::new(subflow_ref(&definition)).max_depth());
+
+    // Continuations and sub-workflow invocations share the same depth.
+    let continue_as = continue_as_definition("recurse", json!("recurse"));
+    let mut continued = WorkflowInstance::for_definition(&continue_as, None);
+    continued.depth = 3;
+    assert!(matches!(
+        invocation
+            .run(&definition, &continued, &registry, Map::new())
+            .unwrap_err(),
+        travailleur::Error::RecursionLimitExceeded { limit: 3, .. }
+    ));
+}
+
+fn keep_active_definition(keep_active: bool) -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "orders",
+        "specVersion": "0.8",
+        "keepActive": keep_active,
+        "events": [
+            { "name": "OrderPlaced", "type": "order.placed" },
+            { "name": "OrderCancelled", "type": "order.cancelled" },
+        ],
+        "states": [
+            {
+                "name": "AwaitOrder",
+                "type": "event",
+                "onEvents": [{ "eventRefs": ["OrderPlaced"] }],
+                "end": true,
+            },
+            {
+                "name": "Close",
+                "type": "event",
+                "onEvents": [{ "eventRefs": ["OrderCancelled"] }],
+                "end": { "terminate": true },
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_end() {
+    let definition = keep_active_definition(false);
+    let ends: Vec<_> = definition.states.iter().flat_map(State::ends).collect();
+
+    let mut instance = WorkflowInstance::for_definition(&definition, None);
+    assert_eq!(EndOutcome::Completed, EndExecution::new(ends[0]).run(&definition, &mut instance));
+    assert!(instance.is_finished());
+    assert!(!instance.terminated);
+
+    let mut instance = WorkflowInstance::for_definition(&definition, None);
+    assert_eq!(EndOutcome::Terminated, EndExecution::new(ends[1]).run(&definition, &mut instance));
+    assert!(instance.is_finished());
+    assert!(instance.terminated);
+}
+
+#[test]
+fn test_keep_active() {
+    let definition = keep_active_definition(true);
+    let ends: Vec<_> = definition.states.iter().flat_map(State::ends).collect();
+    let now = TestClock::default().now();
+
+    let mut instance = WorkflowInstance::for_definition(&definition, None);
+    assert_eq!(EndOutcome::KeptActive, EndExecution::new(ends[0]).run(&definition, &mut instance));
+    assert_eq!(None, instance.state);
+    assert!(instance.kept_active);
+    assert!(!instance.is_finished());
+
+    // Kept-active instances await the events of the start state.
+    let subscriptions = EventSubscription::for_instance(&definition, &instance, now).unwrap();
+    assert_eq!(
+        vec![("AwaitOrder", "OrderPlaced")],
+        subscriptions
+            .iter()
+            .map(|subscription| (subscription.state.as_str(), subscription.event.as_str()))
+            .collect::<Vec<_>>()
+    );
+
+    let value = serde_json::to_value(&instance).unwrap();
+    assert_eq!(json!(true), value["kept_active"]);
+    let deserialized: WorkflowInstance = serde_json::from_value(value).unwrap();
+    assert!(deserialized.kept_active);
+
+    // Only terminating ends (or the workflow execution timeout) finish them.
+    instance.state = Some("Close".into());
+    assert_eq!(EndOutcome::Terminated, EndExecution::new(ends[1]).run(&definition, &mut instance));
+    assert!(instance.is_finished());
+    assert!(instance.terminated);
+    assert!(EventSubscription::for_instance(&definition, &instance, now)
+        .unwrap()
+        .is_empty());
+    assert!(serde_json::to_value(&instance)
+        .unwrap()
+        .get("kept_active")
+        .is_none());
+
+    let mut instance = WorkflowInstance::for_definition(&definition, None);
+    EndExecution::new(ends[0]).run(&definition, &mut instance);
+    instance.terminate();
+    assert!(instance.is_finished());
+}
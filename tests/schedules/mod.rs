@@ -0,0 +1,530 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, TimeDelta, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde_json::json;
+use travailleur::clock::{Clock, TestClock};
+use travailleur::registry::WorkflowKey;
+use travailleur::workflow::definition::{CronDef, Schedule, WorkflowDefinition};
+use travailleur::workflow::runtime::scheduler::{
+    ScheduleExpiry, ScheduledStart, Scheduler, SchedulerEvent, StartSchedule,
+};
+
+fn cron_def(json: &str) -> CronDef {
+    serde_json::from_str(json).expect("cron definition should deserialize")
+}
+
+#[test]
+fn test_cron_next_occurrences() {
+    let cron = cron_def(r#""0 0/15 * * * ?""#);
+    let after = Utc.with_ymd_and_hms(2024, 1, 1, 10, 5, 0).unwrap();
+
+    let occurrences = cron.next_occurrences(&after, 3).unwrap();
+    assert_eq!(
+        vec![
+            Utc.with_ymd_and_hms(2024, 1, 1, 10, 15, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 1, 10, 30, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 1, 10, 45, 0).unwrap(),
+        ],
+        occurrences
+    );
+}
+
+#[test]
+fn test_cron_next_occurrences_standard_expression() {
+    let cron = cron_def(r#""30 2 * * *""#);
+    let after = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+    let occurrences = cron.next_occurrences(&after, 2).unwrap();
+    assert_eq!(
+        vec![
+            Utc.with_ymd_and_hms(2024, 1, 1, 2, 30, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 2, 2, 30, 0).unwrap(),
+        ],
+        occurrences
+    );
+}
+
+#[test]
+fn test_cron_next_occurrences_standard_days_of_week() {
+    let weekdays = |expression: &str| {
+        let after = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        cron_def(&format!("{:?}", expression))
+            .next_occurrences(&after, 7)
+            .unwrap()
+            .iter()
+            .map(|occurrence| occurrence.weekday())
+            .collect::<Vec<_>>()
+    };
+
+    let monday_to_friday = vec![
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Mon,
+        Weekday::Tue,
+    ];
+    assert_eq!(monday_to_friday, weekdays("0 9 * * 1-5"));
+    assert_eq!(monday_to_friday, weekdays("0 9 * * MON-FRI"));
+    assert_eq!(vec![Weekday::Sun; 7], weekdays("0 0 * * 0"));
+    assert_eq!(vec![Weekday::Sun; 7], weekdays("0 0 * * 7"));
+    assert_eq!(
+        vec![
+            Weekday::Fri,
+            Weekday::Sat,
+            Weekday::Sun,
+            Weekday::Fri,
+            Weekday::Sat,
+            Weekday::Sun,
+            Weekday::Fri
+        ],
+        weekdays("0 0 * * 5-7")
+    );
+    assert_eq!(
+        vec![
+            Weekday::Wed,
+            Weekday::Sun,
+            Weekday::Mon,
+            Weekday::Wed,
+            Weekday::Sun,
+            Weekday::Mon,
+            Weekday::Wed
+        ],
+        weekdays("0 0 * * 0,1,3")
+    );
+
+    // 6-field expressions use the `cron` crate's numbering, where 1 is Sunday.
+    assert_eq!(vec![Weekday::Sun; 7], weekdays("0 0 0 * * 1"));
+}
+
+#[test]
+fn test_cron_next_occurrences_honors_valid_until() {
+    let cron = cron_def(r#"{ "expression": "0 0 * * * ?", "validUntil": "2024-01-01T03:00:00Z" }"#);
+    let after = Utc.with_ymd_and_hms(2024, 1, 1, 0, 30, 0).unwrap();
+
+    let occurrences = cron.next_occurrences(&after, 10).unwrap();
+    assert_eq!(
+        vec![
+            Utc.with_ymd_and_hms(2024, 1, 1, 1, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 1, 2, 0, 0).unwrap(),
+        ],
+        occurrences
+    );
+}
+
+#[test]
+fn test_cron_invalid_expression() {
+    let cron = cron_def(r#""not a cron expression""#);
+
+    assert!(matches!(cron.schedule(), Err(travailleur::Error::InvalidCronExpression(_))));
+}
+
+#[cfg(feature = "validate")]
+#[test]
+fn test_cron_validation() {
+    use travailleur::validation::ValidateDefinition;
+
+    assert!(cron_def(r#""0 0/15 * * * ?""#)
+        .validate_definition()
+        .is_ok());
+    assert!(cron_def(r#""61 * * * * ?""#).validate_definition().is_err());
+    assert!(cron_def(r#""0 0 * * 0""#).validate_definition().is_ok());
+    assert!(cron_def(r#""0 0 * * 8""#).validate_definition().is_err());
+    assert!(cron_def(r#"{ "expression": "0 0 * * * ?", "validUntil": "tomorrow" }"#)
+        .validate_definition()
+        .is_err());
+}
+
+fn schedule(json: &str) -> Schedule {
+    serde_json::from_str(json).expect("schedule should deserialize")
+}
+
+#[test]
+fn test_schedule_timezone() {
+    assert_eq!(Tz::UTC, schedule(r#""R/PT2H""#).timezone().unwrap());
+    assert_eq!(Tz::UTC, schedule(r#"{ "interval": "R/PT2H" }"#).timezone().unwrap());
+    assert_eq!(
+        Tz::America__Montreal,
+        schedule(r#"{ "cron": "0 0 * * * ?", "timezone": "America/Montreal" }"#)
+            .timezone()
+            .unwrap()
+    );
+    assert!(matches!(
+        schedule(r#"{ "cron": "0 0 * * * ?", "timezone": "Mars/Olympus_Mons" }"#).timezone(),
+        Err(travailleur::Error::InvalidTimezone { .. })
+    ));
+}
+
+#[cfg(feature = "validate")]
+#[test]
+fn test_schedule_timezone_validation() {
+    use travailleur::validation::ValidateDefinition;
+
+    assert!(schedule(r#"{ "cron": "0 0 * * * ?", "timezone": "Europe/Paris" }"#)
+        .validate_definition()
+        .is_ok());
+    assert!(schedule(r#"{ "cron": "0 0 * * * ?", "timezone": "Mars/Olympus_Mons" }"#)
+        .validate_definition()
+        .is_err());
+}
+
+#[test]
+fn test_cron_remaining_validity() {
+    let cron = cron_def(r#"{ "expression": "0 0 * * * ?", "validUntil": "2024-01-01T03:00:00Z" }"#);
+
+    let now = Utc.with_ymd_and_hms(2024, 1, 1, 1, 30, 0).unwrap();
+    assert_eq!(Some(TimeDelta::minutes(90)), cron.remaining_validity(&now).unwrap());
+    assert!(!cron.has_expired(&now).unwrap());
+
+    let now = Utc.with_ymd_and_hms(2024, 1, 1, 3, 0, 0).unwrap();
+    assert_eq!(Some(TimeDelta::zero()), cron.remaining_validity(&now).unwrap());
+    assert!(cron.has_expired(&now).unwrap());
+
+    let cron = cron_def(r#""0 0 * * * ?""#);
+    assert_eq!(None, cron.remaining_validity(&now).unwrap());
+    assert!(!cron.has_expired(&now).unwrap());
+}
+
+#[test]
+fn test_start_schedule() {
+    let after = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+
+    let interval = StartSchedule::new(&schedule(r#""R/PT2H""#))
+        .unwrap()
+        .unwrap();
+    assert_eq!(Tz::UTC, interval.timezone());
+    assert_eq!(Some(after + TimeDelta::hours(2)), interval.next_start_time(&after));
+
+    let cron = StartSchedule::new(&schedule(
+        r#"{ "cron": "0 0 9 * * ?", "timezone": "America/Montreal" }"#,
+    ))
+    .unwrap()
+    .unwrap();
+    let starts = cron.next_start_times(&after, 2);
+    assert_eq!(Tz::America__Montreal, starts[0].timezone());
+    assert_eq!(
+        vec![
+            Utc.with_ymd_and_hms(2024, 5, 1, 13, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 5, 2, 13, 0, 0).unwrap(),
+        ],
+        starts
+    );
+
+    let cron = StartSchedule::new(&schedule(
+        r#"{ "cron": { "expression": "0 0 * * * ?", "validUntil": "2024-05-01T14:00:00Z" } }"#,
+    ))
+    .unwrap()
+    .unwrap();
+    assert_eq!(1, cron.next_start_times(&after, 5).len());
+    assert_eq!(Some(Utc.with_ymd_and_hms(2024, 5, 1, 14, 0, 0).unwrap()), cron.valid_until());
+    assert_eq!(Some(TimeDelta::hours(2)), cron.remaining_validity(&after));
+    assert_eq!(None, interval.remaining_validity(&after));
+
+    assert!(StartSchedule::new(&schedule(r#"{ "timezone": "Europe/Paris" }"#))
+        .unwrap()
+        .is_none());
+}
+
+fn scheduled_definition(id: &str, schedule: serde_json::Value) -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": id,
+        "specVersion": "0.8",
+        "start": { "stateName": "Report", "schedule": schedule },
+        "states": [{ "name": "Report", "type": "inject", "data": {}, "end": true }],
+    }))
+    .unwrap()
+}
+
+fn key(id: &str) -> WorkflowKey {
+    WorkflowKey { id: id.into(), version: None }
+}
+
+#[test]
+fn test_scheduler() {
+    let start = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+    let at = |minutes: i64| start + TimeDelta::minutes(minutes);
+    let clock = TestClock::new(start);
+    let mut scheduler = Scheduler::new(clock.clone());
+
+    assert!(scheduler
+        .schedule(&scheduled_definition("reports", json!("R/PT30M")))
+        .unwrap());
+    assert!(scheduler
+        .schedule(&scheduled_definition("digest", json!({ "cron": "0 0 13 * * ?" })))
+        .unwrap());
+    let manual: WorkflowDefinition = serde_json::from_value(json!({
+        "id": "manual",
+        "specVersion": "0.8",
+        "states": [{ "name": "Report", "type": "inject", "data": {}, "end": true }],
+    }))
+    .unwrap();
+    assert!(!scheduler.schedule(&manual).unwrap());
+
+    assert_eq!(
+        Some(ScheduledStart { workflow: key("reports"), scheduled_at: at(30) }),
+        scheduler.next_start()
+    );
+    assert_eq!(vec![at(30), at(60), at(90)], scheduler.upcoming(&key("reports"), 3));
+    assert!(scheduler.upcoming(&key("manual"), 3).is_empty());
+
+    let mut starts: Vec<(String, DateTime<Utc>)> = Vec::new();
+    let count = scheduler.run_until(&at(60), |event| match event {
+        SchedulerEvent::Start(start) => starts.push((start.workflow.id, start.scheduled_at)),
+        event => panic!("expected a start, got {:?}", event),
+    });
+    assert_eq!(3, count);
+    assert_eq!(
+        vec![
+            ("reports".to_string(), at(30)),
+            ("digest".to_string(), at(60)),
+            ("reports".to_string(), at(60)),
+        ],
+        starts
+    );
+    assert_eq!(at(60), clock.now());
+    assert_eq!(0, scheduler.run_pending(|_| panic!("no start should be due")));
+
+    assert!(scheduler.unschedule(&key("digest")));
+    assert!(!scheduler.unschedule(&key("digest")));
+
+    clock.advance(Duration::from_secs(3600));
+    let mut missed = Vec::new();
+    scheduler.run_pending(|event| missed.push(event.at()));
+    assert_eq!(vec![at(90), at(120)], missed);
+    assert_eq!(Some(at(150)), scheduler.next_start().map(|start| start.scheduled_at));
+}
+
+#[test]
+fn test_scheduler_expiry() {
+    let start = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+    let at = |minutes: i64| start + TimeDelta::minutes(minutes);
+    let clock = TestClock::new(start);
+    let mut scheduler = Scheduler::new(clock.clone());
+
+    scheduler
+        .schedule(&scheduled_definition(
+            "reports",
+            json!({ "cron": { "expression": "0 0 * * * ?", "validUntil": "2024-05-01T14:30:00Z" } }),
+        ))
+        .unwrap();
+    scheduler
+        .schedule(&scheduled_definition("digest", json!("R/PT90M")))
+        .unwrap();
+    assert_eq!(Some(TimeDelta::minutes(150)), scheduler.remaining_validity(&key("reports")));
+    assert_eq!(None, scheduler.remaining_validity(&key("digest")));
+    assert_eq!(None, scheduler.remaining_validity(&key("manual")));
+
+    let mut events = Vec::new();
+    let count = scheduler.run_until(&at(180), |event| events.push(event));
+    assert_eq!(5, count);
+    assert_eq!(
+        vec![
+            SchedulerEvent::Start(ScheduledStart {
+                workflow: key("reports"),
+                scheduled_at: at(60)
+            }),
+            SchedulerEvent::Start(ScheduledStart { workflow: key("digest"), scheduled_at: at(90) }),
+            SchedulerEvent::Start(ScheduledStart {
+                workflow: key("reports"),
+                scheduled_at: at(120),
+            }),
+            SchedulerEvent::Expired(ScheduleExpiry {
+                workflow: key("reports"),
+                valid_until: at(150),
+            }),
+            SchedulerEvent::Start(ScheduledStart {
+                workflow: key("digest"),
+                scheduled_at: at(180)
+            }),
+        ],
+        events
+    );
+    assert_eq!(at(180), clock.now());
+
+    // Expired schedules are unscheduled, so their expiry is only reported once.
+    assert_eq!(None, scheduler.remaining_validity(&key("reports")));
+    assert!(scheduler.upcoming(&key("reports"), 1).is_empty());
+    assert!(!scheduler.unschedule(&key("reports")));
+    clock.advance(Duration::from_secs(90 * 60));
+    let mut events = Vec::new();
+    scheduler.run_pending(|event| events.push(event));
+    assert_eq!(
+        vec![SchedulerEvent::Start(ScheduledStart {
+            workflow: key("digest"),
+            scheduled_at: at(270)
+        })],
+        events
+    );
+}
+
+mod recurring_intervals {
+    use chrono::{FixedOffset, TimeDelta, TimeZone, Utc};
+    use travailleur::workflow::definition::intervals::{IsoDuration, RecurringInterval};
+    use travailleur::workflow::definition::Schedule;
+
+    fn interval(interval: &str) -> RecurringInterval {
+        interval.parse().expect("recurring interval should parse")
+    }
+
+    #[test]
+    fn test_duration_parsing() {
+        let duration: IsoDuration = "P1Y2M3W4DT5H6M7.5S".parse().unwrap();
+        assert_eq!(
+            IsoDuration {
+                years: 1,
+                months: 2,
+                weeks: 3,
+                days: 4,
+                hours: 5,
+                minutes: 6,
+                seconds: 7,
+                nanoseconds: 500_000_000,
+            },
+            duration
+        );
+        assert_eq!("P1Y2M3W4DT5H6M7.5S", duration.to_string());
+        assert!(duration.to_time_delta().is_none());
+
+        let duration: IsoDuration = "PT1H30M".parse().unwrap();
+        assert_eq!(Some(TimeDelta::minutes(90)), duration.to_time_delta());
+        assert_eq!(duration, IsoDuration::from(std::time::Duration::from_secs(90 * 60)));
+        assert_eq!(
+            "PT26H0.25S",
+            IsoDuration::from(std::time::Duration::from_millis(26 * 3600 * 1000 + 250)).to_string()
+        );
+        assert_eq!("PT0S", IsoDuration::from(std::time::Duration::ZERO).to_string());
+
+        for invalid in ["", "P", "PT", "1D", "P1H", "PT1D", "P1D1Y", "P1.5D", "PT1.S", "PT-1S"] {
+            assert!(
+                matches!(
+                    invalid.parse::<IsoDuration>(),
+                    Err(travailleur::Error::InvalidDuration { .. })
+                ),
+                "'{}' should not be a valid duration",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_interval_parsing() {
+        assert_eq!(
+            RecurringInterval {
+                repetitions: None,
+                start: None,
+                duration: IsoDuration { minutes: 30, ..IsoDuration::default() },
+            },
+            interval("R/PT30M")
+        );
+
+        let start = FixedOffset::east_opt(0)
+            .unwrap()
+            .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+            .unwrap();
+        assert_eq!(
+            RecurringInterval {
+                repetitions: Some(5),
+                start: Some(start),
+                duration: IsoDuration { days: 1, ..IsoDuration::default() },
+            },
+            interval("R5/2024-01-01T00:00:00Z/P1D")
+        );
+
+        let with_end = interval("R2/2024-01-01T00:00:00Z/2024-01-01T02:00:00Z");
+        assert_eq!(Some(start), with_end.start);
+        assert_eq!(Some(TimeDelta::hours(2)), with_end.duration.to_time_delta());
+    }
+
+    #[test]
+    fn test_invalid_intervals() {
+        for invalid in [
+            "PT30M",
+            "R0/PT30M",
+            "Rx/PT30M",
+            "R/PT0S",
+            "R/P",
+            "R/PT30M/2024-01-01T00:00:00Z",
+            "R/2024-01-01T02:00:00Z/2024-01-01T00:00:00Z",
+            "R/2024-01-01/PT30M",
+            "R/2024-01-01T00:00:00Z/PT30M/PT30M",
+        ] {
+            assert!(
+                matches!(
+                    invalid.parse::<RecurringInterval>(),
+                    Err(travailleur::Error::InvalidRecurringInterval { .. })
+                ),
+                "'{}' should not be a valid recurring interval",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_interval_next_occurrences() {
+        let interval = interval("R5/2024-01-01T00:00:00Z/P1D");
+
+        let after = Utc.with_ymd_and_hms(2024, 1, 2, 12, 0, 0).unwrap();
+        assert_eq!(
+            vec![
+                Utc.with_ymd_and_hms(2024, 1, 3, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 1, 4, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 1, 5, 0, 0, 0).unwrap(),
+            ],
+            interval.next_occurrences(&after, 10)
+        );
+
+        let before = Utc.with_ymd_and_hms(2023, 12, 31, 0, 0, 0).unwrap();
+        assert_eq!(
+            vec![
+                Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap(),
+            ],
+            interval.next_occurrences(&before, 2)
+        );
+    }
+
+    #[test]
+    fn test_interval_next_occurrences_without_start() {
+        let interval = interval("R/PT30M");
+        let after = Utc.with_ymd_and_hms(2024, 1, 1, 10, 5, 0).unwrap();
+
+        assert_eq!(
+            vec![
+                Utc.with_ymd_and_hms(2024, 1, 1, 10, 35, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 1, 1, 11, 5, 0).unwrap(),
+            ],
+            interval.next_occurrences(&after, 2)
+        );
+    }
+
+    #[test]
+    fn test_interval_next_occurrences_calendar_duration() {
+        let interval = interval("R/2024-01-31T00:00:00Z/P1M");
+        let after = Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap();
+
+        assert_eq!(
+            vec![
+                Utc.with_ymd_and_hms(2024, 2, 29, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 3, 31, 0, 0, 0).unwrap(),
+            ],
+            interval.next_occurrences(&after, 2)
+        );
+    }
+
+    #[test]
+    fn test_schedule_recurring_interval() {
+        let schedule: Schedule = serde_json::from_str(r#""R/PT1H""#).unwrap();
+        assert_eq!(Some(interval("R/PT1H")), schedule.recurring_interval().unwrap());
+
+        let schedule: Schedule = serde_json::from_str(r#"{ "cron": "0 0 * * * ?" }"#).unwrap();
+        assert!(schedule.recurring_interval().unwrap().is_none());
+
+        let schedule: Schedule = serde_json::from_str(r#"{ "interval": "PT1H" }"#).unwrap();
+        assert!(matches!(
+            schedule.recurring_interval(),
+            Err(travailleur::Error::InvalidRecurringInterval { .. })
+        ));
+    }
+}
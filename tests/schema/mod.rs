@@ -0,0 +1,62 @@
+#[cfg(feature = "json-schema")]
+use std::fs;
+#[cfg(feature = "json-schema")]
+use std::path::PathBuf;
+
+use schemars::schema_for;
+use serde_json::{json, Value};
+use travailleur::workflow::definition::WorkflowDefinition;
+
+fn definition_schema() -> Value {
+    serde_json::to_value(schema_for!(WorkflowDefinition)).unwrap()
+}
+
+#[test]
+fn test_definition_schema() {
+    let schema = definition_schema();
+
+    assert_eq!(Some("WorkflowDefinition"), schema["title"].as_str());
+    assert!(schema["properties"]["states"].is_object());
+    assert!(schema["definitions"]["State"]["oneOf"].is_array());
+    assert_eq!(json!({ "^x-": true }), schema["patternProperties"]);
+}
+
+#[cfg(feature = "json-schema")]
+#[test]
+fn test_definition_schema_accepts_examples() {
+    let validator = jsonschema::validator_for(&definition_schema()).unwrap();
+
+    let resources: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "resources", "definitions"]
+        .iter()
+        .collect();
+    let examples = fs::read_dir(resources.join("examples"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .chain([resources.join("custom").join("approval.json")])
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        });
+    for example in examples {
+        let definition: Value =
+            serde_json::from_str(&fs::read_to_string(&example).unwrap()).unwrap();
+        let errors: Vec<_> = validator
+            .iter_errors(&definition)
+            .map(|error| error.to_string())
+            .collect();
+        assert!(errors.is_empty(), "{} is invalid: {:?}", example.display(), errors);
+    }
+}
+
+#[cfg(feature = "json-schema")]
+#[test]
+fn test_definition_schema_rejects_incomplete_built_in_state() {
+    let validator = jsonschema::validator_for(&definition_schema()).unwrap();
+
+    let definition = json!({
+        "id": "sleepy",
+        "specVersion": "0.8",
+        "states": [{ "name": "Sleep", "type": "sleep", "end": true }]
+    });
+    assert!(!validator.is_valid(&definition));
+}
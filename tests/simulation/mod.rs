@@ -0,0 +1,123 @@
+use chrono::TimeDelta;
+use serde_json::json;
+use travailleur::workflow::definition::WorkflowDefinition;
+use travailleur::workflow::simulation::Simulation;
+
+fn approval_definition() -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "approval",
+        "specVersion": "0.8",
+        "retries": [{ "name": "ThreeAttempts", "maxAttempts": 3, "delay": "PT1S" }],
+        "states": [
+            {
+                "name": "Check",
+                "type": "switch",
+                "dataConditions": [
+                    { "condition": "${ .approve }", "transition": "Approve" },
+                    { "condition": "${ .reject }", "transition": "Reject" }
+                ],
+                "defaultCondition": { "end": true }
+            },
+            {
+                "name": "Approve",
+                "type": "operation",
+                "actions": [{ "functionRef": "approve", "retryRef": "ThreeAttempts" }],
+                "transition": "Wait"
+            },
+            { "name": "Wait", "type": "sleep", "duration": "PT10S", "end": true },
+            {
+                "name": "Reject",
+                "type": "operation",
+                "actions": [{ "functionRef": "reject" }],
+                "end": true
+            }
+        ]
+    }))
+    .unwrap()
+}
+
+fn assert_near(expected: f64, actual: f64) {
+    assert!((expected - actual).abs() < 0.02, "expected ~{expected}, got {actual}");
+}
+
+#[test]
+fn test_simulation() {
+    let definition = approval_definition();
+    let simulation = Simulation::new(&definition)
+        .condition_probabilities("Check", [0.6, 0.3])
+        .function_duration("approve", TimeDelta::seconds(2))
+        .failure_probability("approve", 0.5)
+        .seed(42);
+
+    let report = simulation.run(10_000);
+    assert_eq!(10_000, report.executions);
+    assert_eq!(10_000, report.completed + report.failed);
+    assert_eq!(0, report.aborted);
+    assert_eq!(Some(&10_000), report.state_visits.get("Check"));
+
+    let frequencies = report.branch_frequencies("Check").unwrap();
+    assert_eq!(3, frequencies.len());
+    assert_near(0.6, frequencies[0]);
+    assert_near(0.3, frequencies[1]);
+    assert_near(0.1, frequencies[2]);
+
+    // Approvals fail when all 3 attempts fail, and are retried after each failed attempt but the last.
+    assert_near(0.6 * 0.125, report.failed as f64 / report.executions as f64);
+    assert_near(0.6 * 0.75, report.expected_retries("approve"));
+    assert_eq!(0.0, report.expected_retries("reject"));
+
+    // Longest execution: 3 attempts of 2 seconds, 2 retry delays of 1 second and a 10 seconds sleep.
+    assert_eq!(TimeDelta::seconds(18), report.max_duration);
+    assert!(report.expected_duration() > TimeDelta::zero());
+    assert!(report.expected_duration() < report.max_duration);
+
+    let rerun = simulation.run(10_000);
+    assert_eq!(report.branches, rerun.branches);
+    assert_eq!(report.total_duration, rerun.total_duration);
+}
+
+#[test]
+fn test_simulation_event_delays() {
+    let definition: WorkflowDefinition = serde_json::from_value(json!({
+        "id": "events",
+        "specVersion": "0.8",
+        "events": [{ "name": "Approved", "source": "approvals", "type": "approved" }],
+        "states": [{
+            "name": "WaitForApproval",
+            "type": "switch",
+            "eventConditions": [{ "eventRef": "Approved", "end": true }],
+            "defaultCondition": { "end": true },
+            "timeouts": { "eventTimeout": "PT1H" }
+        }]
+    }))
+    .unwrap();
+
+    let report = Simulation::new(&definition)
+        .condition_probabilities("WaitForApproval", [1.0])
+        .event_delay("Approved", TimeDelta::minutes(10))
+        .run(10_000);
+    let expected_seconds = report.expected_duration().num_seconds() as f64;
+    assert!((expected_seconds - 600.0).abs() < 30.0, "unexpected duration: {expected_seconds}s");
+
+    let report = Simulation::new(&definition).run(10);
+    assert_eq!(Some(vec![0.0, 1.0]), report.branch_frequencies("WaitForApproval"));
+    assert_eq!(TimeDelta::hours(1), report.expected_duration());
+}
+
+#[test]
+fn test_simulation_max_transitions() {
+    let definition: WorkflowDefinition = serde_json::from_value(json!({
+        "id": "loop",
+        "specVersion": "0.8",
+        "states": [
+            { "name": "Ping", "type": "inject", "data": {}, "transition": "Pong" },
+            { "name": "Pong", "type": "inject", "data": {}, "transition": "Ping" }
+        ]
+    }))
+    .unwrap();
+
+    let report = Simulation::new(&definition).max_transitions(9).run(2);
+    assert_eq!(2, report.aborted);
+    assert_eq!(Some(&10), report.state_visits.get("Ping"));
+    assert_eq!(Some(&10), report.state_visits.get("Pong"));
+}
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use serde_json::json;
+use travailleur::clock::{Clock, TestClock};
+use travailleur::workflow::definition::{State, WorkflowDefinition};
+use travailleur::workflow::runtime::deadlines::{Deadlines, TimeoutKind};
+use travailleur::workflow::runtime::sleep::{sleep_after_action, sleep_before_action, sleep_state};
+
+fn definition() -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "sleepy",
+        "specVersion": "0.8",
+        "functions": [{ "name": "charge", "operation": "charge", "type": "custom" }],
+        "events": [
+            { "name": "Charge", "type": "charge", "kind": "produced" },
+            { "name": "Charged", "type": "charged", "source": "bank" },
+        ],
+        "states": [
+            {
+                "name": "Nap",
+                "type": "sleep",
+                "duration": "PT10M",
+                "timeouts": { "stateExecTimeout": "PT5M" },
+                "transition": "Charge",
+            },
+            {
+                "name": "Charge",
+                "type": "operation",
+                "actions": [
+                    { "functionRef": "charge", "sleep": { "before": "PT1S", "after": "PT2S" } },
+                    {
+                        "eventRef": { "triggerEventRef": "Charge", "resultEventRef": "Charged" },
+                        "sleep": { "before": "PT1S" },
+                    },
+                    { "functionRef": "charge", "sleep": { "before": "soon" } },
+                ],
+                "end": true,
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+fn sleep_state_of(
+    definition: &WorkflowDefinition,
+) -> &travailleur::workflow::definition::SleepState {
+    match &definition.states[0] {
+        State::Sleep(state) => state,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_sleep_state() {
+    let definition = definition();
+    let clock = TestClock::default();
+
+    let slept = sleep_state(&clock, sleep_state_of(&definition), &Deadlines::default()).unwrap();
+    assert_eq!(Duration::from_secs(600), slept);
+    assert_eq!(Duration::from_secs(600), clock.elapsed());
+}
+
+#[test]
+fn test_sleep_state_deadline() {
+    let definition = definition();
+    let clock = TestClock::default();
+    let mut deadlines = Deadlines::for_workflow(&definition, clock.instant()).unwrap();
+    deadlines
+        .enter_state(&definition, &definition.states[0], clock.instant())
+        .unwrap();
+
+    let result = sleep_state(&clock, sleep_state_of(&definition), &deadlines);
+    assert!(matches!(
+        result,
+        Err(travailleur::Error::TimedOut { timeout: TimeoutKind::StateExec, .. })
+    ));
+    assert_eq!(Duration::from_secs(300), clock.elapsed());
+}
+
+#[test]
+fn test_action_sleep() {
+    let definition = definition();
+    let actions = definition.states[1].actions();
+    let clock = TestClock::default();
+    let deadlines = Deadlines::default();
+
+    assert_eq!(
+        Some(Duration::from_secs(1)),
+        sleep_before_action(&clock, actions[0], &deadlines).unwrap()
+    );
+    assert_eq!(
+        Some(Duration::from_secs(2)),
+        sleep_after_action(&clock, actions[0], &deadlines).unwrap()
+    );
+    assert_eq!(None, sleep_before_action(&clock, actions[1], &deadlines).unwrap());
+    assert_eq!(None, sleep_after_action(&clock, actions[2], &deadlines).unwrap());
+    assert_eq!(Duration::from_secs(3), clock.elapsed());
+
+    assert!(matches!(
+        sleep_before_action(&clock, actions[2], &deadlines),
+        Err(travailleur::Error::InvalidDuration { duration }) if duration == "soon"
+    ));
+}
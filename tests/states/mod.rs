@@ -0,0 +1,147 @@
+use serde_json::json;
+use travailleur::validation::states::{
+    find_dead_end_states, find_unreachable_states, UnreachableState,
+};
+#[cfg(feature = "validate")]
+use travailleur::validation::ValidateDefinition;
+use travailleur::workflow::definition::WorkflowDefinition;
+use travailleur::workflow::graph::{EdgeKind, StateEdge, StateGraph};
+
+fn definition() -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "checkOrder",
+        "specVersion": "0.8",
+        "start": "CheckOrder",
+        "states": [
+            {
+                "name": "CheckOrder",
+                "type": "switch",
+                "dataConditions": [
+                    { "condition": "${ .valid }", "transition": "ProcessOrder" },
+                ],
+                "defaultCondition": { "transition": "RejectOrder" },
+            },
+            {
+                "name": "ProcessOrder",
+                "type": "operation",
+                "actions": [],
+                "onErrors": [
+                    { "errorRef": "failure", "transition": "RejectOrder" },
+                ],
+                "compensatedBy": "CancelOrder",
+                "end": true,
+            },
+            {
+                "name": "RejectOrder",
+                "type": "inject",
+                "data": {},
+                "end": true,
+            },
+            {
+                "name": "CancelOrder",
+                "type": "inject",
+                "data": {},
+                "usedForCompensation": true,
+            },
+            {
+                "name": "Orphan",
+                "type": "inject",
+                "data": {},
+                "transition": "OrphanChild",
+            },
+            {
+                "name": "OrphanChild",
+                "type": "inject",
+                "data": {},
+                "end": true,
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_state_graph() {
+    let definition = definition();
+    let graph = StateGraph::new(&definition);
+
+    assert_eq!(Some("CheckOrder"), graph.start());
+    assert_eq!(
+        vec![
+            StateEdge { from: "ProcessOrder", to: "RejectOrder", kind: EdgeKind::Error },
+            StateEdge { from: "ProcessOrder", to: "CancelOrder", kind: EdgeKind::Compensation },
+        ],
+        graph
+            .edges_from("ProcessOrder")
+            .copied()
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![
+            StateEdge { from: "CheckOrder", to: "RejectOrder", kind: EdgeKind::DefaultCondition },
+            StateEdge { from: "ProcessOrder", to: "RejectOrder", kind: EdgeKind::Error },
+        ],
+        graph.edges_to("RejectOrder").copied().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        Some(vec!["CheckOrder", "ProcessOrder", "CancelOrder"]),
+        graph.path_from_start("CancelOrder")
+    );
+    assert_eq!(None, graph.path_from_start("OrphanChild"));
+}
+
+#[test]
+fn test_unreachable_states() {
+    let definition = definition();
+
+    assert_eq!(
+        vec![
+            UnreachableState { name: "Orphan".into(), referenced_by: vec![] },
+            UnreachableState { name: "OrphanChild".into(), referenced_by: vec!["Orphan".into()] },
+        ],
+        find_unreachable_states(&definition)
+    );
+}
+
+fn dead_end_definition() -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "deadEnd",
+        "specVersion": "0.8",
+        "states": [
+            {
+                "name": "Start",
+                "type": "operation",
+                "actions": [],
+                "transition": "Stuck",
+            },
+            {
+                "name": "Stuck",
+                "type": "sleep",
+                "duration": "PT1S",
+            },
+            {
+                "name": "Compensate",
+                "type": "inject",
+                "data": {},
+                "usedForCompensation": true,
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_dead_end_states() {
+    assert_eq!(vec!["Stuck"], find_dead_end_states(&dead_end_definition()));
+    assert!(find_dead_end_states(&definition()).is_empty());
+}
+
+#[test]
+#[cfg(feature = "validate")]
+fn test_dead_end_states_validation() {
+    let error = dead_end_definition().validate_definition().unwrap_err();
+
+    assert!(error
+        .to_string()
+        .contains("must have a `transition` or an `end`"));
+}
@@ -0,0 +1,212 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::de::DeserializeSeed;
+use serde_json::{json, Map, Value};
+use travailleur::workflow::definition::WorkflowDefinition;
+use travailleur::workflow::format::{IdFormat, SerializationFormat, TimestampFormat};
+use travailleur::workflow::instance::WorkflowInstance;
+use travailleur::workflow::subscription::{
+    EventSubscription, SubscriptionRegistry, SubscriptionSnapshot,
+};
+
+fn definition() -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "payment",
+        "specVersion": "0.8",
+        "timeouts": { "eventTimeout": "PT1H" },
+        "events": [
+            {
+                "name": "PaymentReceived",
+                "type": "payment.received",
+                "source": "/payments",
+                "correlation": [
+                    { "contextAttributeName": "tenant", "contextAttributeValue": "acme" },
+                    { "contextAttributeName": "orderid" }
+                ]
+            },
+            { "name": "PaymentCancelled", "type": "payment.cancelled" }
+        ],
+        "states": [
+            {
+                "name": "Start",
+                "type": "inject",
+                "data": {},
+                "transition": "AwaitPayment"
+            },
+            {
+                "name": "AwaitPayment",
+                "type": "switch",
+                "timeouts": { "eventTimeout": "PT5M" },
+                "eventConditions": [
+                    { "eventRef": "PaymentReceived", "transition": "Confirm" },
+                    { "eventRef": "PaymentCancelled", "end": true }
+                ],
+                "defaultCondition": { "end": true }
+            },
+            {
+                "name": "Confirm",
+                "type": "event",
+                "onEvents": [{ "eventRefs": ["PaymentReceived"] }],
+                "end": true
+            },
+            {
+                "name": "Broken",
+                "type": "callback",
+                "action": { "name": "notify" },
+                "eventRef": "Missing",
+                "end": true
+            }
+        ]
+    }))
+    .unwrap()
+}
+
+fn now() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+fn attributes(value: Value) -> Map<String, Value> {
+    value.as_object().unwrap().clone()
+}
+
+#[test]
+fn test_for_instance() {
+    let definition = definition();
+
+    let instance = WorkflowInstance::resume_at(&definition, "AwaitPayment", Map::new()).unwrap();
+    let subscriptions = EventSubscription::for_instance(&definition, &instance, now()).unwrap();
+    assert_eq!(2, subscriptions.len());
+    assert_eq!(instance.id, subscriptions[0].instance_id);
+    assert_eq!("AwaitPayment", subscriptions[0].state);
+    assert_eq!("payment.received", subscriptions[0].event_type);
+    assert_eq!(Some("/payments"), subscriptions[0].source.as_deref());
+    assert_eq!(
+        [("tenant".to_string(), "acme".to_string())]
+            .into_iter()
+            .collect::<Vec<_>>(),
+        subscriptions[0]
+            .correlation
+            .clone()
+            .into_iter()
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(Some(now() + TimeDelta::minutes(5)), subscriptions[0].expires_at);
+    assert_eq!("payment.cancelled", subscriptions[1].event_type);
+
+    let instance = WorkflowInstance::resume_at(&definition, "Confirm", Map::new()).unwrap();
+    let subscriptions = EventSubscription::for_instance(&definition, &instance, now()).unwrap();
+    assert_eq!(1, subscriptions.len());
+    assert_eq!(Some(now() + TimeDelta::hours(1)), subscriptions[0].expires_at);
+
+    let instance = WorkflowInstance::resume_at(&definition, "Start", Map::new()).unwrap();
+    assert!(EventSubscription::for_instance(&definition, &instance, now())
+        .unwrap()
+        .is_empty());
+
+    let instance = WorkflowInstance::resume_at(&definition, "Broken", Map::new()).unwrap();
+    assert!(matches!(
+        EventSubscription::for_instance(&definition, &instance, now()),
+        Err(travailleur::Error::UnknownEvent { event }) if event == "Missing"
+    ));
+}
+
+#[test]
+fn test_matches() {
+    let definition = definition();
+    let instance = WorkflowInstance::resume_at(&definition, "Confirm", Map::new()).unwrap();
+    let subscription = EventSubscription::for_instance(&definition, &instance, now())
+        .unwrap()
+        .remove(0)
+        .with_correlation("orderid", "42");
+
+    let event = attributes(json!({ "tenant": "acme", "orderid": 42 }));
+    assert!(subscription.matches("payment.received", Some("/payments"), &event));
+    assert!(!subscription.matches("payment.received", Some("/refunds"), &event));
+    assert!(!subscription.matches("payment.cancelled", Some("/payments"), &event));
+
+    let event = attributes(json!({ "tenant": "acme", "orderid": "43" }));
+    assert!(!subscription.matches("payment.received", Some("/payments"), &event));
+
+    let event = attributes(json!({ "tenant": "acme" }));
+    assert!(!subscription.matches("payment.received", Some("/payments"), &event));
+}
+
+#[test]
+fn test_registry_snapshot_restore() {
+    let definition = definition();
+    let mut registry = SubscriptionRegistry::new();
+    for state in ["AwaitPayment", "Confirm"] {
+        let instance = WorkflowInstance::resume_at(&definition, state, Map::new()).unwrap();
+        EventSubscription::for_instance(&definition, &instance, now())
+            .unwrap()
+            .into_iter()
+            .for_each(|subscription| registry.subscribe(subscription));
+    }
+    assert_eq!(3, registry.len());
+    assert_eq!(
+        vec![("payment.cancelled", None), ("payment.received", Some("/payments"))],
+        registry.topics().into_iter().collect::<Vec<_>>()
+    );
+
+    let snapshot = serde_json::to_string(&registry.snapshot()).unwrap();
+    let snapshot: SubscriptionSnapshot = serde_json::from_str(&snapshot).unwrap();
+    let mut restored = SubscriptionRegistry::restore(&snapshot);
+    assert_eq!(registry.iter().collect::<Vec<_>>(), restored.iter().collect::<Vec<_>>());
+
+    let event = attributes(json!({ "tenant": "acme" }));
+    assert_eq!(
+        2,
+        restored
+            .matching("payment.received", Some("/payments"), &event)
+            .count()
+    );
+
+    let expired = restored.expire(&(now() + TimeDelta::minutes(10)));
+    assert_eq!(2, expired.len());
+    assert!(expired
+        .iter()
+        .all(|subscription| subscription.state == "AwaitPayment"));
+    assert_eq!(1, restored.len());
+
+    let instance_id = restored.iter().next().unwrap().instance_id.clone();
+    assert_eq!(1, restored.unsubscribe(&instance_id).len());
+    assert!(restored.is_empty());
+}
+
+#[test]
+fn test_serialization_format() {
+    let definition = definition();
+    let instance = WorkflowInstance::resume_at(&definition, "AwaitPayment", Map::new()).unwrap();
+    let snapshot = SubscriptionSnapshot {
+        subscriptions: EventSubscription::for_instance(&definition, &instance, now()).unwrap(),
+    };
+
+    let default = serde_json::to_value(&snapshot).unwrap();
+    assert_eq!(default, serde_json::to_value(SerializationFormat::new().apply(&snapshot)).unwrap());
+    assert_eq!("2024-05-01T12:05:00Z", default["subscriptions"][0]["expiresAt"]);
+
+    let format = SerializationFormat::new()
+        .with_ids(IdFormat::Binary)
+        .with_timestamps(TimestampFormat::EpochMillis);
+    let formatted = serde_json::to_value(format.apply(&snapshot)).unwrap();
+    let subscription = &formatted["subscriptions"][0];
+    assert_eq!(16, subscription["instanceId"].as_array().unwrap().len());
+    assert_eq!(
+        json!((now() + TimeDelta::minutes(5)).timestamp_millis()),
+        subscription["expiresAt"]
+    );
+    assert_eq!("payment.received", subscription["type"]);
+    assert_eq!("acme", subscription["correlation"]["tenant"]);
+
+    assert!(serde_json::from_value::<SubscriptionSnapshot>(formatted.clone()).is_err());
+    let deserialized: SubscriptionSnapshot = format.seed().deserialize(formatted).unwrap();
+    assert_eq!(snapshot, deserialized);
+
+    let subscription = EventSubscription::for_instance(&definition, &instance, now())
+        .unwrap()
+        .remove(1);
+    let formatted = serde_json::to_value(format.apply(&subscription)).unwrap();
+    let deserialized: EventSubscription = format.seed().deserialize(formatted).unwrap();
+    assert_eq!(subscription, deserialized);
+}
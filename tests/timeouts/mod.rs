@@ -0,0 +1,255 @@
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use travailleur::workflow::definition::timeouts::{
+    ActionExecTimeout, BranchExecTimeout, EventTimeout, StateExecTimeout, WorkflowExecTimeout,
+};
+use travailleur::workflow::definition::{State, WorkflowDefinition};
+use travailleur::workflow::executor::InvocationContext;
+use travailleur::workflow::runtime::deadlines::{Deadlines, TimeoutKind};
+
+#[test]
+fn test_as_duration() {
+    assert_eq!(Duration::from_secs(90), ActionExecTimeout("PT1M30S".into()).as_duration().unwrap());
+    assert_eq!(
+        Duration::from_millis(500),
+        BranchExecTimeout("PT0.5S".into()).as_duration().unwrap()
+    );
+    assert_eq!(Duration::from_secs(86_400), EventTimeout("P1D".into()).as_duration().unwrap());
+}
+
+#[test]
+fn test_workflow_exec_timeout_as_duration() {
+    let simple = WorkflowExecTimeout::Simple("PT1H".into());
+    assert_eq!(Duration::from_secs(3600), simple.as_duration().unwrap());
+
+    let complex =
+        WorkflowExecTimeout::Complex { duration: "PT2H".into(), interrupt: true, run_before: None };
+    assert_eq!(Duration::from_secs(7200), complex.as_duration().unwrap());
+}
+
+#[test]
+fn test_state_exec_timeout_as_duration() {
+    let simple = StateExecTimeout::Simple("PT10S".into());
+    assert_eq!(Duration::from_secs(10), simple.as_duration().unwrap());
+    assert_eq!(None, simple.single_as_duration().unwrap());
+
+    let complex = StateExecTimeout::Complex { single: Some("PT2S".into()), total: "PT10S".into() };
+    assert_eq!(Duration::from_secs(10), complex.as_duration().unwrap());
+    assert_eq!(Some(Duration::from_secs(2)), complex.single_as_duration().unwrap());
+}
+
+#[test]
+fn test_as_duration_errors() {
+    assert!(matches!(
+        ActionExecTimeout("10 seconds".into()).as_duration(),
+        Err(travailleur::Error::InvalidDuration { duration }) if duration == "10 seconds"
+    ));
+    assert!(matches!(
+        EventTimeout("P1M".into()).as_duration(),
+        Err(travailleur::Error::UnsupportedDuration { duration }) if duration == "P1M"
+    ));
+}
+
+fn definition(interrupt: bool) -> WorkflowDefinition {
+    serde_json::from_value(json!({
+        "id": "orders",
+        "specVersion": "0.8",
+        "timeouts": {
+            "workflowExecTimeout": { "duration": "PT1H", "interrupt": interrupt },
+            "stateExecTimeout": "PT10M",
+            "actionExecTimeout": "PT1M",
+            "eventTimeout": "PT30M",
+        },
+        "functions": [{ "name": "charge", "operation": "charge", "type": "custom" }],
+        "events": [{ "name": "Paid", "type": "paid", "source": "bank" }],
+        "states": [
+            {
+                "name": "Charge",
+                "type": "operation",
+                "actions": [{ "functionRef": "charge" }],
+                "timeouts": {
+                    "stateExecTimeout": { "single": "PT2M", "total": "PT5M" },
+                    "actionExecTimeout": "PT30S",
+                },
+                "transition": "Ship",
+            },
+            {
+                "name": "Ship",
+                "type": "parallel",
+                "branches": [
+                    {
+                        "name": "Label",
+                        "actions": [{ "functionRef": "charge" }],
+                        "timeouts": { "actionExecTimeout": "PT20S" },
+                    },
+                    {
+                        "name": "Pickup",
+                        "actions": [{ "functionRef": "charge" }],
+                        "timeouts": { "branchExecTimeout": "PT3M" },
+                    },
+                ],
+                "timeouts": { "branchExecTimeout": "PT4M" },
+                "transition": "WaitForPayment",
+            },
+            {
+                "name": "WaitForPayment",
+                "type": "event",
+                "onEvents": [{ "eventRefs": ["Paid"] }],
+                "end": true,
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+fn parallel_state(
+    definition: &WorkflowDefinition,
+) -> &travailleur::workflow::definition::ParallelState {
+    match &definition.states[1] {
+        State::Parallel(state) => state,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_deadlines() {
+    let definition = definition(true);
+    let start = Instant::now();
+    let at = |secs: u64| start + Duration::from_secs(secs);
+
+    let mut deadlines = Deadlines::for_workflow(&definition, start).unwrap();
+    assert_eq!(at(3600), deadlines.get(TimeoutKind::WorkflowExec).unwrap().at);
+    assert!(deadlines.state().is_none());
+
+    let charge = &definition.states[0];
+    deadlines.enter_state(&definition, charge, at(10)).unwrap();
+    assert_eq!(Some("Charge"), deadlines.state());
+    assert_eq!(at(310), deadlines.get(TimeoutKind::StateExec).unwrap().at);
+    assert_eq!(at(130), deadlines.get(TimeoutKind::SingleStateExec).unwrap().at);
+
+    deadlines
+        .start_action(&definition, charge, None, at(20))
+        .unwrap();
+    assert_eq!(TimeoutKind::ActionExec, deadlines.next().unwrap().timeout);
+    assert_eq!(at(50), deadlines.next().unwrap().at);
+    deadlines.finish_action();
+    assert!(deadlines.get(TimeoutKind::ActionExec).is_none());
+
+    deadlines.retry_state(&definition, charge, at(100)).unwrap();
+    assert_eq!(at(310), deadlines.get(TimeoutKind::StateExec).unwrap().at);
+    assert_eq!(at(220), deadlines.get(TimeoutKind::SingleStateExec).unwrap().at);
+
+    let wait = &definition.states[2];
+    deadlines.enter_state(&definition, wait, at(200)).unwrap();
+    assert_eq!(at(800), deadlines.get(TimeoutKind::StateExec).unwrap().at);
+    assert!(deadlines.get(TimeoutKind::SingleStateExec).is_none());
+    deadlines
+        .wait_for_events(&definition, wait, at(200))
+        .unwrap();
+    assert_eq!(at(2000), deadlines.get(TimeoutKind::Event).unwrap().at);
+    deadlines.events_received();
+    assert!(deadlines.get(TimeoutKind::Event).is_none());
+}
+
+#[test]
+fn test_branch_deadlines() {
+    let definition = definition(true);
+    let start = Instant::now();
+    let at = |secs: u64| start + Duration::from_secs(secs);
+
+    let ship = &definition.states[1];
+    let state = parallel_state(&definition);
+    let mut deadlines = Deadlines::for_workflow(&definition, start).unwrap();
+    deadlines.enter_state(&definition, ship, start).unwrap();
+
+    let mut label = deadlines.clone();
+    label
+        .enter_branch(&definition, state, &state.branches[0], start)
+        .unwrap();
+    assert_eq!(at(240), label.get(TimeoutKind::BranchExec).unwrap().at);
+    label
+        .start_action(&definition, ship, Some(&state.branches[0]), start)
+        .unwrap();
+    assert_eq!(at(20), label.get(TimeoutKind::ActionExec).unwrap().at);
+
+    let mut pickup = deadlines.clone();
+    pickup
+        .enter_branch(&definition, state, &state.branches[1], start)
+        .unwrap();
+    assert_eq!(at(180), pickup.get(TimeoutKind::BranchExec).unwrap().at);
+    pickup
+        .start_action(&definition, ship, Some(&state.branches[1]), start)
+        .unwrap();
+    assert_eq!(at(60), pickup.get(TimeoutKind::ActionExec).unwrap().at);
+
+    pickup
+        .enter_state(&definition, &definition.states[2], at(200))
+        .unwrap();
+    assert!(pickup.get(TimeoutKind::BranchExec).is_none());
+    assert!(pickup.get(TimeoutKind::ActionExec).is_none());
+}
+
+#[test]
+fn test_check() {
+    let definition = definition(true);
+    let start = Instant::now();
+    let at = |secs: u64| start + Duration::from_secs(secs);
+
+    let mut deadlines = Deadlines::for_workflow(&definition, start).unwrap();
+    deadlines
+        .enter_state(&definition, &definition.states[0], start)
+        .unwrap();
+    assert!(deadlines.check(at(119)).is_ok());
+
+    let error = deadlines.check(at(400)).unwrap_err();
+    assert!(matches!(
+        &error,
+        travailleur::Error::TimedOut { timeout: TimeoutKind::SingleStateExec, state: Some(state) }
+            if state == "Charge"
+    ));
+    assert_eq!("stateExecTimeout.single expired in state 'Charge'", error.to_string());
+
+    let deadlines = Deadlines::for_workflow(&definition, start).unwrap();
+    assert!(matches!(
+        deadlines.check(at(3600)),
+        Err(travailleur::Error::TimedOut { timeout: TimeoutKind::WorkflowExec, state: None })
+    ));
+    assert!(deadlines.workflow_expired(at(3600)));
+}
+
+#[test]
+fn test_non_interrupting_workflow_timeout() {
+    let definition = definition(false);
+    let start = Instant::now();
+
+    let deadlines = Deadlines::for_workflow(&definition, start).unwrap();
+    assert!(!deadlines.get(TimeoutKind::WorkflowExec).unwrap().interrupt);
+    assert!(deadlines.next().is_none());
+
+    let later = start + Duration::from_secs(7200);
+    assert!(deadlines.check(later).is_ok());
+    assert!(deadlines.workflow_expired(later));
+    assert!(!deadlines.workflow_expired(start));
+}
+
+#[test]
+fn test_apply() {
+    let definition = definition(true);
+    let start = Instant::now();
+
+    let context = InvocationContext::new(&definition);
+    assert!(Deadlines::default()
+        .apply(context.clone())
+        .deadline
+        .is_none());
+
+    let mut deadlines = Deadlines::for_workflow(&definition, start).unwrap();
+    deadlines
+        .enter_state(&definition, &definition.states[0], start)
+        .unwrap();
+    deadlines
+        .start_action(&definition, &definition.states[0], None, start)
+        .unwrap();
+    assert_eq!(Some(start + Duration::from_secs(30)), deadlines.apply(context).deadline);
+}
@@ -0,0 +1,6 @@
+mod incremental;
+#[cfg(feature = "json-schema")]
+mod input;
+mod lint;
+mod report;
+mod states;
@@ -0,0 +1,159 @@
+use std::path::PathBuf;
+
+use serde_json::json;
+use travailleur::cache::DefinitionCache;
+use travailleur::workflow::v1::Task;
+use travailleur::workflow::versioned::{SpecVersion, VersionedWorkflowDefinition};
+
+fn definition_uri(dir: &str, name: &str) -> String {
+    let path: PathBuf =
+        [env!("CARGO_MANIFEST_DIR"), "tests", "resources", "definitions", dir, name]
+            .iter()
+            .collect();
+
+    format!("file://{}", path.to_string_lossy())
+}
+
+#[test]
+fn test_detect_spec_version() {
+    assert_eq!(
+        SpecVersion::V0_8,
+        SpecVersion::detect(&json!({ "id": "test", "specVersion": "0.8" })).unwrap()
+    );
+    assert_eq!(
+        SpecVersion::V1_0,
+        SpecVersion::detect(&json!({ "document": { "dsl": "1.0.0" } })).unwrap()
+    );
+    assert_eq!(
+        SpecVersion::V1_0,
+        SpecVersion::detect(&json!({ "document": { "dsl": "1.0.0-alpha5" } })).unwrap()
+    );
+
+    for (document, expected) in [
+        (json!({ "id": "test", "specVersion": "0.7" }), Some("0.7")),
+        (json!({ "id": "test", "specVersion": "0.80" }), Some("0.80")),
+        (json!({ "document": { "dsl": "2.0.0" } }), Some("2.0.0")),
+        (json!({ "document": { "dsl": 1 } }), Some("1")),
+        (json!({ "id": "test" }), None),
+    ] {
+        match SpecVersion::detect(&document) {
+            Err(travailleur::Error::UnsupportedSpecVersion { spec_version }) => {
+                assert_eq!(expected, spec_version.as_deref());
+            },
+            result => panic!("expected unsupported spec version error, got {:?}", result),
+        }
+    }
+}
+
+#[test]
+fn test_load_v0_8() {
+    let mut cache = DefinitionCache::new();
+    let definition = cache
+        .get_or_insert::<VersionedWorkflowDefinition, _>(
+            definition_uri("examples", "applicantrequest.json").as_str(),
+        )
+        .unwrap();
+
+    assert_eq!(SpecVersion::V0_8, definition.spec_version());
+    match definition.as_ref() {
+        VersionedWorkflowDefinition::V0_8(definition) => {
+            assert_eq!("applicantrequest", definition.identifier.id().unwrap());
+        },
+        definition => panic!("expected v0.8 definition, got {:?}", definition),
+    }
+}
+
+#[test]
+fn test_load_v1_0() {
+    let mut cache = DefinitionCache::new();
+
+    for name in ["order-processing.json", "order-processing.yaml"]
+        .into_iter()
+        .filter(|name| cfg!(feature = "yaml") || name.ends_with(".json"))
+    {
+        let definition = cache
+            .get_or_insert::<VersionedWorkflowDefinition, _>(definition_uri("v1", name).as_str())
+            .unwrap();
+
+        assert_eq!(SpecVersion::V1_0, definition.spec_version());
+        let VersionedWorkflowDefinition::V1_0(definition) = definition.as_ref() else {
+            panic!("expected v1.0 definition, got {:?}", definition);
+        };
+
+        assert_eq!("order-processing", definition.document.name);
+        assert_eq!(
+            vec![
+                "validateOrder",
+                "processItems",
+                "notify",
+                "charge",
+                "pause",
+                "done",
+                "rejectOrder"
+            ],
+            definition
+                .tasks
+                .iter()
+                .map(|task| task.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert!(matches!(
+            definition
+                .tasks
+                .iter()
+                .map(|task| &task.task)
+                .collect::<Vec<_>>()
+                .as_slice(),
+            [
+                Task::Switch(_),
+                Task::For(_),
+                Task::Fork(_),
+                Task::Try(_),
+                Task::Wait(_),
+                Task::Set(_),
+                Task::Raise(_),
+            ]
+        ));
+        assert_eq!(Some("end"), definition.tasks[5].task.base().then.as_deref());
+
+        let Task::Fork(notify) = &definition.tasks[2].task else { unreachable!() };
+        assert!(matches!(
+            notify
+                .fork
+                .branches
+                .iter()
+                .map(|branch| &branch.task)
+                .collect::<Vec<_>>()
+                .as_slice(),
+            [Task::Emit(_), Task::Listen(_)]
+        ));
+    }
+}
+
+#[test]
+fn test_v1_0_round_trip() {
+    let document = json!({
+        "document": { "dsl": "1.0.0", "namespace": "test", "name": "greet", "version": "1.0.0" },
+        "do": [
+            { "greet": { "set": { "greeting": "Hello" }, "then": "end" } }
+        ]
+    });
+
+    let definition: VersionedWorkflowDefinition = serde_json::from_value(document.clone()).unwrap();
+    assert_eq!(document, serde_json::to_value(&definition).unwrap());
+}
+
+#[test]
+fn test_v1_0_task_list_entries_must_have_a_single_task() {
+    let document = json!({
+        "document": { "dsl": "1.0.0", "namespace": "test", "name": "greet", "version": "1.0.0" },
+        "do": [
+            {
+                "first": { "set": { "greeting": "Hello" } },
+                "second": { "set": { "greeting": "Bonjour" } }
+            }
+        ]
+    });
+
+    assert!(serde_json::from_value::<VersionedWorkflowDefinition>(document).is_err());
+}